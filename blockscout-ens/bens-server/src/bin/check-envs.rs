@@ -2,7 +2,7 @@ use bens_server::Settings;
 use env_collector::{run_env_collector_cli, PrefixFilter};
 
 fn main() {
-    run_env_collector_cli::<Settings>(
+    let code = run_env_collector_cli::<Settings>(
         "BENS",
         "README.md",
         "bens-server/config/example.json",
@@ -14,4 +14,5 @@ fn main() {
         ]),
         Some("envs_main"),
     );
+    std::process::exit(code);
 }
@@ -18,6 +18,8 @@ pub struct Model {
     pub commitment: Vec<u8>,
     #[sea_orm(column_type = "Binary(BlobSize::Blob(None))")]
     pub data: Vec<u8>,
+    pub offloaded: bool,
+    pub size: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
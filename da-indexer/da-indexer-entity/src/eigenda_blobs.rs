@@ -16,6 +16,9 @@ pub struct Model {
     pub blob_index: i32,
     #[sea_orm(column_type = "Binary(BlobSize::Blob(None))")]
     pub data: Vec<u8>,
+    pub offloaded: bool,
+    pub size: i64,
+    pub created_at: DateTime,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -2,6 +2,7 @@
 
 pub mod prelude;
 
+pub mod backfill_checkpoints;
 pub mod celestia_blobs;
 pub mod celestia_blocks;
 pub mod eigenda_batches;
@@ -1,6 +1,7 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.15
 
 pub use super::{
-    celestia_blobs::Entity as CelestiaBlobs, celestia_blocks::Entity as CelestiaBlocks,
-    eigenda_batches::Entity as EigendaBatches, eigenda_blobs::Entity as EigendaBlobs,
+    backfill_checkpoints::Entity as BackfillCheckpoints, celestia_blobs::Entity as CelestiaBlobs,
+    celestia_blocks::Entity as CelestiaBlocks, eigenda_batches::Entity as EigendaBatches,
+    eigenda_blobs::Entity as EigendaBlobs,
 };
@@ -0,0 +1,133 @@
+//! Backfilling of the historical range of heights.
+//!
+//! Runs independently of the live indexer. Progress is persisted
+//! after each batch of heights, so the backfill resumes from the
+//! last checkpoint after restart.
+
+pub mod repository;
+pub mod settings;
+
+#[cfg(test)]
+mod tests;
+
+use anyhow::Result;
+use futures::{stream, StreamExt, TryStreamExt};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tokio::time::sleep;
+use tracing::instrument;
+
+use crate::{
+    indexer::{new_da, DA},
+    metrics,
+    settings::DASettings,
+    status::{IndexerStatus, DEFAULT_NETWORK},
+};
+use settings::BackfillSettings;
+
+pub struct Backfiller {
+    db: Arc<DatabaseConnection>,
+    da: Box<dyn DA + Send + Sync>,
+    settings: BackfillSettings,
+}
+
+impl Backfiller {
+    pub async fn new(db: Arc<DatabaseConnection>, settings: BackfillSettings) -> Result<Self> {
+        settings.validate()?;
+        // The progress of the backfill is reported by its own metric
+        let status = Arc::new(IndexerStatus::without_metrics(
+            settings.da.name(),
+            DEFAULT_NETWORK,
+        ));
+        let da = new_da(db.clone(), da_settings(&settings), status).await?;
+        Ok(Self { db, da, settings })
+    }
+
+    /// Identifies the checkpoint, so that the backfill of another range
+    /// doesn't reuse the progress of the previous one
+    pub fn checkpoint_id(&self) -> String {
+        format!(
+            "{}:{}-{}",
+            self.settings.da.name(),
+            self.settings.start_height,
+            self.settings.end_height
+        )
+    }
+
+    #[instrument(name = "backfill", skip_all, level = "info")]
+    pub async fn run(&self) -> Result<()> {
+        let id = self.checkpoint_id();
+        let (start, end) = (self.settings.start_height, self.settings.end_height);
+
+        let mut from = match repository::find(self.db.as_ref(), &id).await? {
+            Some(checkpoint) => checkpoint
+                .processed_height
+                .map(|height| (height as u64 + 1).max(start))
+                .unwrap_or(start),
+            None => {
+                repository::upsert(self.db.as_ref(), &id, start, end, None).await?;
+                start
+            }
+        };
+        tracing::info!(checkpoint = id, from, to = end, "starting backfill");
+
+        while let Some((batch_start, batch_end)) = next_range(from, end, self.settings.batch_size) {
+            let mut retries = 0;
+            while let Err(err) = self.process_range(batch_start, batch_end).await {
+                if retries >= self.settings.max_retries {
+                    return Err(err.context(format!(
+                        "failed to backfill range {batch_start}-{batch_end} after {retries} retries"
+                    )));
+                }
+                retries += 1;
+                tracing::warn!(
+                    error = ?err,
+                    from = batch_start,
+                    to = batch_end,
+                    retries,
+                    delay = ?self.settings.retry_interval,
+                    "failed to backfill range, retrying"
+                );
+                sleep(self.settings.retry_interval).await;
+            }
+
+            repository::upsert(self.db.as_ref(), &id, start, end, Some(batch_end)).await?;
+            metrics::BACKFILL_PROCESSED_HEIGHT
+                .with_label_values(&[self.settings.da.name()])
+                .set(batch_end as i64);
+            tracing::info!(from = batch_start, to = batch_end, "backfilled range");
+            from = batch_end + 1;
+        }
+
+        tracing::info!(checkpoint = id, "backfill finished");
+        Ok(())
+    }
+
+    async fn process_range(&self, from: u64, to: u64) -> Result<()> {
+        let jobs = self.da.jobs_in_range(from, to).await?;
+        stream::iter(jobs)
+            .map(|job| self.da.process_job(job))
+            .buffer_unordered(self.settings.concurrency as usize)
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(())
+    }
+}
+
+/// The start height of the DA settings is replaced with the start of the range,
+/// so that the DA is initialized from the backfilled heights rather than the chain head.
+/// EigenDA start block is kept, as it is used to tell the recent batches from the pruned ones.
+fn da_settings(settings: &BackfillSettings) -> DASettings {
+    match settings.da.clone() {
+        DASettings::Celestia(mut da) => {
+            da.start_height = Some(settings.start_height);
+            DASettings::Celestia(da)
+        }
+        da @ DASettings::EigenDA(_) => da,
+    }
+}
+
+/// Returns the next inclusive range of at most `batch_size` heights
+fn next_range(from: u64, end: u64, batch_size: u64) -> Option<(u64, u64)> {
+    (from <= end).then(|| (from, end.min(from.saturating_add(batch_size - 1))))
+}
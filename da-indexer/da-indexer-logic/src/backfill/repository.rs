@@ -0,0 +1,38 @@
+use da_indexer_entity::backfill_checkpoints::{ActiveModel, Column, Entity, Model};
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ActiveValue::{NotSet, Set},
+    ConnectionTrait, EntityTrait,
+};
+
+pub async fn find<C: ConnectionTrait>(db: &C, id: &str) -> Result<Option<Model>, anyhow::Error> {
+    let checkpoint = Entity::find_by_id(id.to_string()).one(db).await?;
+    Ok(checkpoint)
+}
+
+pub async fn upsert<C: ConnectionTrait>(
+    db: &C,
+    id: &str,
+    start_height: u64,
+    end_height: u64,
+    processed_height: Option<u64>,
+) -> Result<(), anyhow::Error> {
+    let model = ActiveModel {
+        id: Set(id.to_string()),
+        start_height: Set(start_height as i64),
+        end_height: Set(end_height as i64),
+        processed_height: Set(processed_height.map(|height| height as i64)),
+        updated_at: NotSet,
+    };
+
+    Entity::insert(model)
+        .on_conflict(
+            OnConflict::column(Column::Id)
+                .update_column(Column::ProcessedHeight)
+                .value(Column::UpdatedAt, Expr::current_timestamp())
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use serde::Deserialize;
+use serde_with::serde_as;
+use std::time;
+
+use crate::settings::DASettings;
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BackfillSettings {
+    pub da: DASettings,
+    pub start_height: u64,
+    pub end_height: u64,
+    #[serde(default = "default_concurrency")]
+    pub concurrency: u32,
+    /// Number of heights processed between the checkpoints
+    #[serde(default = "default_batch_size")]
+    pub batch_size: u64,
+    #[serde(default = "default_retry_interval")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub retry_interval: time::Duration,
+    /// Number of retries of the failed batch before the backfill is stopped
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+}
+
+impl BackfillSettings {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.start_height > self.end_height {
+            anyhow::bail!(
+                "invalid backfill range: start height {} is greater than end height {}",
+                self.start_height,
+                self.end_height
+            );
+        }
+        if self.concurrency == 0 {
+            anyhow::bail!("backfill concurrency must be positive");
+        }
+        if self.batch_size == 0 {
+            anyhow::bail!("backfill batch size must be positive");
+        }
+        Ok(())
+    }
+}
+
+fn default_concurrency() -> u32 {
+    10
+}
+
+fn default_batch_size() -> u64 {
+    100
+}
+
+fn default_retry_interval() -> time::Duration {
+    time::Duration::from_secs(10)
+}
+
+fn default_max_retries() -> u32 {
+    10
+}
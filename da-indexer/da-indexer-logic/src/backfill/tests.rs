@@ -0,0 +1,83 @@
+use blockscout_service_launcher::test_database::TestDbGuard;
+
+use super::{repository, settings::BackfillSettings};
+use crate::settings::DASettings;
+
+async fn init_db(test_name: &str) -> TestDbGuard {
+    TestDbGuard::new::<migration::Migrator>(test_name).await
+}
+
+#[tokio::test]
+async fn checkpoint_upsert_test() {
+    let db = init_db("backfill_checkpoint_upsert_test").await;
+    let id = "celestia:1-1000";
+
+    assert!(repository::find(db.client().as_ref(), id)
+        .await
+        .unwrap()
+        .is_none());
+
+    repository::upsert(db.client().as_ref(), id, 1, 1000, None)
+        .await
+        .unwrap();
+    let checkpoint = repository::find(db.client().as_ref(), id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(checkpoint.start_height, 1);
+    assert_eq!(checkpoint.end_height, 1000);
+    assert_eq!(checkpoint.processed_height, None);
+
+    repository::upsert(db.client().as_ref(), id, 1, 1000, Some(100))
+        .await
+        .unwrap();
+    let checkpoint = repository::find(db.client().as_ref(), id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(checkpoint.processed_height, Some(100));
+}
+
+#[test]
+fn next_range_test() {
+    assert_eq!(super::next_range(1, 10, 4), Some((1, 4)));
+    assert_eq!(super::next_range(9, 10, 4), Some((9, 10)));
+    assert_eq!(super::next_range(11, 10, 4), None);
+}
+
+#[test]
+fn settings_validation_test() {
+    let settings = BackfillSettings {
+        da: DASettings::Celestia(Default::default()),
+        start_height: 1,
+        end_height: 10,
+        concurrency: 2,
+        batch_size: 5,
+        retry_interval: std::time::Duration::from_secs(1),
+        max_retries: 3,
+    };
+    assert!(settings.validate().is_ok());
+
+    for invalid in [
+        BackfillSettings {
+            start_height: 11,
+            ..settings.clone()
+        },
+        BackfillSettings {
+            concurrency: 0,
+            ..settings.clone()
+        },
+        BackfillSettings {
+            batch_size: 0,
+            ..settings.clone()
+        },
+    ] {
+        assert!(invalid.validate().is_err(), "{invalid:?}");
+    }
+
+    // the DA starts from the beginning of the range
+    match super::da_settings(&settings) {
+        DASettings::Celestia(da) => assert_eq!(da.start_height, Some(1)),
+        da => panic!("unexpected da settings: {da:?}"),
+    }
+}
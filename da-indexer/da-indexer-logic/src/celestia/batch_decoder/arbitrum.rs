@@ -0,0 +1,110 @@
+//! Arbitrum Nitro sequencer batches.
+//!
+//! The first byte of the batch is a header describing the payload,
+//! see <https://github.com/OffchainLabs/nitro/blob/master/arbstate/daprovider/util.go>
+
+use anyhow::{anyhow, Result};
+
+use super::Compression;
+
+pub const BROTLI_MESSAGE_HEADER_BYTE: u8 = 0x00;
+pub const DAS_MESSAGE_HEADER_FLAG: u8 = 0x80;
+pub const TREE_DAS_MESSAGE_HEADER_FLAG: u8 = 0x08;
+pub const L1_AUTHENTICATED_MESSAGE_HEADER_FLAG: u8 = 0x40;
+pub const ZEROHEAVY_MESSAGE_HEADER_FLAG: u8 = 0x20;
+pub const BLOB_HASHES_HEADER_FLAG: u8 = L1_AUTHENTICATED_MESSAGE_HEADER_FLAG | 0x10;
+pub const CELESTIA_MESSAGE_HEADER_FLAG: u8 = 0x63;
+
+/// Keyset hash, data hash, timeout, signers mask and BLS signature of the DAS certificate
+const DAS_CERTIFICATE_SIZE: usize = 32 + 32 + 8 + 8 + 96;
+/// Block height, start share, shares length, tx commitment and data root of the Celestia blob pointer
+const CELESTIA_BLOB_POINTER_SIZE: usize = 8 + 8 + 8 + 32 + 32;
+const BLOB_HASH_SIZE: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderKind {
+    Brotli,
+    DasCertificate,
+    TreeDasCertificate,
+    Zeroheavy,
+    BlobHashes,
+    Celestia,
+    Unknown,
+}
+
+impl HeaderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HeaderKind::Brotli => "brotli",
+            HeaderKind::DasCertificate => "das_certificate",
+            HeaderKind::TreeDasCertificate => "tree_das_certificate",
+            HeaderKind::Zeroheavy => "zeroheavy",
+            HeaderKind::BlobHashes => "blob_hashes",
+            HeaderKind::Celestia => "celestia",
+            HeaderKind::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchData {
+    pub header_byte: u8,
+    pub kind: HeaderKind,
+    pub compression: Option<Compression>,
+    /// Size of the batch without the header byte
+    pub payload_size: u64,
+}
+
+pub fn decode(data: &[u8]) -> Result<BatchData> {
+    let (&header_byte, payload) = data.split_first().ok_or(anyhow!("empty data"))?;
+    let kind = match header_byte {
+        BROTLI_MESSAGE_HEADER_BYTE => HeaderKind::Brotli,
+        CELESTIA_MESSAGE_HEADER_FLAG => HeaderKind::Celestia,
+        BLOB_HASHES_HEADER_FLAG => HeaderKind::BlobHashes,
+        b if b & DAS_MESSAGE_HEADER_FLAG != 0 && b & TREE_DAS_MESSAGE_HEADER_FLAG != 0 => {
+            HeaderKind::TreeDasCertificate
+        }
+        b if b & DAS_MESSAGE_HEADER_FLAG != 0 => HeaderKind::DasCertificate,
+        b if b & ZEROHEAVY_MESSAGE_HEADER_FLAG != 0 => HeaderKind::Zeroheavy,
+        _ => HeaderKind::Unknown,
+    };
+    validate_payload(kind, payload)?;
+    let compression = match kind {
+        HeaderKind::Brotli => Some(Compression::Brotli),
+        _ => None,
+    };
+    Ok(BatchData {
+        header_byte,
+        kind,
+        compression,
+        payload_size: payload.len() as u64,
+    })
+}
+
+/// Checks that the payload is consistent with the header, so that arbitrary
+/// data starting with a known header byte is not recognized as a batch.
+fn validate_payload(kind: HeaderKind, payload: &[u8]) -> Result<()> {
+    if payload.is_empty() {
+        return Err(anyhow!("empty {} payload", kind.as_str()));
+    }
+    match kind {
+        HeaderKind::DasCertificate if payload.len() < DAS_CERTIFICATE_SIZE => Err(anyhow!(
+            "das certificate is too short: {} bytes",
+            payload.len()
+        )),
+        // tree certificates are prefixed with the version byte
+        HeaderKind::TreeDasCertificate if payload.len() < DAS_CERTIFICATE_SIZE + 1 => Err(anyhow!(
+            "tree das certificate is too short: {} bytes",
+            payload.len()
+        )),
+        HeaderKind::BlobHashes if payload.len() % BLOB_HASH_SIZE != 0 => Err(anyhow!(
+            "blob hashes payload size is not a multiple of {BLOB_HASH_SIZE}: {} bytes",
+            payload.len()
+        )),
+        HeaderKind::Celestia if payload.len() < CELESTIA_BLOB_POINTER_SIZE => Err(anyhow!(
+            "celestia blob pointer is too short: {} bytes",
+            payload.len()
+        )),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,59 @@
+//! Decoding of the rollup batches stored inside the blobs.
+//!
+//! Only metadata is extracted (frames, headers, compression),
+//! the batches themselves are not decompressed.
+
+pub mod arbitrum;
+pub mod optimism;
+
+use super::l2_router::types::L2Type;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Zlib,
+    Brotli,
+    Unknown,
+}
+
+impl Compression {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::Zlib => "zlib",
+            Compression::Brotli => "brotli",
+            Compression::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodedBlob {
+    Optimism(optimism::BatcherData),
+    Arbitrum(arbitrum::BatchData),
+}
+
+/// Decodes the blob contents according to the `chain_type`.
+///
+/// If the chain type is unknown, OP Stack frames are tried first
+/// and then Arbitrum batch headers. Returns `None` if the contents
+/// do not match any supported format.
+pub fn decode(data: &[u8], chain_type: Option<&L2Type>) -> Option<DecodedBlob> {
+    match chain_type {
+        Some(L2Type::Optimism) => optimism::decode(data)
+            .inspect_err(|err| tracing::debug!(error = ?err, "failed to decode op stack frames"))
+            .ok()
+            .map(DecodedBlob::Optimism),
+        Some(L2Type::Arbitrum) => arbitrum::decode(data)
+            .inspect_err(|err| tracing::debug!(error = ?err, "failed to decode arbitrum batch"))
+            .ok()
+            .map(DecodedBlob::Arbitrum),
+        None => optimism::decode(data)
+            .map(DecodedBlob::Optimism)
+            .ok()
+            .or_else(|| {
+                arbitrum::decode(data)
+                    .ok()
+                    .filter(|batch| !matches!(batch.kind, arbitrum::HeaderKind::Unknown))
+                    .map(DecodedBlob::Arbitrum)
+            }),
+    }
+}
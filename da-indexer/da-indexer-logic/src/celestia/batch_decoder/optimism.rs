@@ -0,0 +1,109 @@
+//! OP Stack batcher transaction data.
+//!
+//! `batcher_data = derivation_version ++ frame ++ frame ++ ...`
+//!
+//! `frame = channel_id (16 bytes) ++ frame_number (u16) ++ frame_data_length (u32) ++ frame_data ++ is_last (u8)`
+//!
+//! See <https://specs.optimism.io/protocol/derivation.html#frame-format>
+
+use anyhow::{anyhow, bail, Result};
+
+use super::Compression;
+
+pub const DERIVATION_VERSION_0: u8 = 0;
+
+const CHANNEL_ID_LENGTH: usize = 16;
+// channel_id ++ frame_number ++ frame_data_length ++ is_last
+const FRAME_OVERHEAD: usize = CHANNEL_ID_LENGTH + 2 + 4 + 1;
+const MAX_FRAME_LENGTH: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub channel_id: [u8; CHANNEL_ID_LENGTH],
+    pub frame_number: u16,
+    pub data_length: u32,
+    pub is_last: bool,
+    /// Compression of the channel, known only for the first frame
+    /// of the channel
+    pub compression: Option<Compression>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatcherData {
+    pub derivation_version: u8,
+    pub frames: Vec<Frame>,
+}
+
+pub fn decode(data: &[u8]) -> Result<BatcherData> {
+    let (&derivation_version, mut rest) = data.split_first().ok_or(anyhow!("empty data"))?;
+    if derivation_version != DERIVATION_VERSION_0 {
+        bail!("unsupported derivation version: {derivation_version}");
+    }
+
+    let mut frames = vec![];
+    while !rest.is_empty() {
+        let (frame, remaining) = decode_frame(rest)?;
+        frames.push(frame);
+        rest = remaining;
+    }
+    if frames.is_empty() {
+        bail!("no frames found");
+    }
+
+    Ok(BatcherData {
+        derivation_version,
+        frames,
+    })
+}
+
+fn decode_frame(data: &[u8]) -> Result<(Frame, &[u8])> {
+    if data.len() < FRAME_OVERHEAD {
+        bail!("frame is too short: {} bytes", data.len());
+    }
+    let (channel_id, rest) = data.split_at(CHANNEL_ID_LENGTH);
+    let (frame_number, rest) = rest.split_at(2);
+    let (data_length, rest) = rest.split_at(4);
+
+    let frame_number = u16::from_be_bytes(frame_number.try_into()?);
+    let data_length = u32::from_be_bytes(data_length.try_into()?);
+    if data_length as usize > MAX_FRAME_LENGTH {
+        bail!("frame data is too large: {data_length} bytes");
+    }
+    // frame_data ++ is_last
+    if rest.len() < data_length as usize + 1 {
+        bail!("unexpected end of frame data");
+    }
+    let (frame_data, rest) = rest.split_at(data_length as usize);
+    let (&is_last, rest) = rest.split_first().ok_or(anyhow!("missing is_last"))?;
+    let is_last = match is_last {
+        0 => false,
+        1 => true,
+        _ => bail!("invalid is_last value: {is_last}"),
+    };
+
+    let compression = (frame_number == 0)
+        .then(|| frame_data.first().map(|&b| compression_from_first_byte(b)))
+        .flatten();
+
+    Ok((
+        Frame {
+            channel_id: channel_id.try_into()?,
+            frame_number,
+            data_length,
+            is_last,
+            compression,
+        },
+        rest,
+    ))
+}
+
+/// See <https://specs.optimism.io/protocol/fjord/derivation.html#brotli-channel-compression>
+fn compression_from_first_byte(b: u8) -> Compression {
+    if b & 0x0f == 0x08 || b & 0x0f == 0x0f {
+        Compression::Zlib
+    } else if b == 0x01 {
+        Compression::Brotli
+    } else {
+        Compression::Unknown
+    }
+}
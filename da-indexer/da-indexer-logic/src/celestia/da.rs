@@ -1,11 +1,12 @@
 use crate::{
     celestia::{client, repository::blobs},
     indexer::{Job, DA},
+    status::IndexerStatus,
 };
 use anyhow::Result;
 use async_trait::async_trait;
-use celestia_rpc::{Client, HeaderClient};
-use celestia_types::{Blob, ExtendedHeader};
+use celestia_rpc::{BlobClient, Client, HeaderClient};
+use celestia_types::{nmt::Namespace, Blob, ExtendedHeader};
 use sea_orm::{DatabaseConnection, TransactionTrait};
 use std::sync::{
     atomic::{AtomicBool, AtomicU64, Ordering},
@@ -20,13 +21,20 @@ use super::{
 pub struct CelestiaDA {
     client: Client,
     db: Arc<DatabaseConnection>,
+    /// Namespaces to be indexed (all namespaces if empty)
+    namespaces: Vec<Namespace>,
+    status: Arc<IndexerStatus>,
 
     last_known_height: AtomicU64,
     catch_up_completed: AtomicBool,
 }
 
 impl CelestiaDA {
-    pub async fn new(db: Arc<DatabaseConnection>, settings: IndexerSettings) -> Result<Self> {
+    pub async fn new(
+        db: Arc<DatabaseConnection>,
+        settings: IndexerSettings,
+        status: Arc<IndexerStatus>,
+    ) -> Result<Self> {
         let client = client::new_celestia_client(
             &settings.rpc.url,
             settings.rpc.auth_token.as_deref(),
@@ -44,11 +52,31 @@ impl CelestiaDA {
             start_from = 1;
         }
 
-        tracing::info!(start_from, "indexer initialized");
+        if let Some(max_height) = blocks::find_max_in_range(&db, 0, i64::MAX as u64).await? {
+            status.update_indexed_height(max_height as u64);
+        }
+
+        let namespaces = settings
+            .namespaces
+            .iter()
+            .map(|namespace| Namespace::from_raw(namespace))
+            .collect::<Result<Vec<_>, _>>()?;
+        let namespaces_hex: Vec<_> = namespaces
+            .iter()
+            .map(|namespace| hex::encode(namespace.as_bytes()))
+            .collect();
+
+        tracing::info!(
+            start_from,
+            namespaces = ?namespaces_hex,
+            "indexer initialized"
+        );
 
         Ok(Self {
             client,
             db,
+            namespaces,
+            status,
             last_known_height: AtomicU64::new(start_from.saturating_sub(1)),
             catch_up_completed: AtomicBool::new(false),
         })
@@ -57,14 +85,23 @@ impl CelestiaDA {
     async fn get_blobs_by_height(&self, height: u64) -> Result<(ExtendedHeader, Vec<Blob>)> {
         let header = self.client.header_get_by_height(height).await?;
 
-        let mut blobs = vec![];
-        if parser::maybe_contains_blobs(&header.dah) {
+        if !parser::maybe_contains_blobs(&header.dah) {
+            return Ok((header, vec![]));
+        }
+
+        let blobs = if self.namespaces.is_empty() {
             let eds = self
                 .client
                 .share_get_eds(height, header.header.version.app)
                 .await?;
-            blobs = parser::parse_eds(&eds, header.header.version.app)?;
-        }
+            parser::parse_eds(&eds, header.header.version.app)?
+        } else {
+            // only the shares of the indexed namespaces are requested from the node
+            self.client
+                .blob_get_all(height, &self.namespaces)
+                .await?
+                .unwrap_or_default()
+        };
 
         Ok((header, blobs))
     }
@@ -95,6 +132,7 @@ impl DA for CelestiaDA {
         }
 
         txn.commit().await?;
+        self.status.update_indexed_height(job.height);
 
         // this is not accurate, just to indicate progress
         if job.height % 1000 == 0 {
@@ -107,6 +145,7 @@ impl DA for CelestiaDA {
     async fn new_jobs(&self) -> anyhow::Result<Vec<Job>> {
         let height = self.client.header_local_head().await?.header.height.value();
         tracing::info!(height, "latest block");
+        self.status.update_chain_height(height);
 
         if height <= self.last_known_height.load(Ordering::Acquire) {
             tracing::info!("latest block is below last known height, skipping...");
@@ -119,6 +158,13 @@ impl DA for CelestiaDA {
             .collect())
     }
 
+    async fn jobs_in_range(&self, from: u64, to: u64) -> anyhow::Result<Vec<Job>> {
+        // genesis block can't be fetched by usual means
+        Ok((from.max(1)..=to)
+            .map(|height| Job::Celestia(CelestiaJob { height }))
+            .collect())
+    }
+
     async fn unprocessed_jobs(&self) -> anyhow::Result<Vec<Job>> {
         if self.catch_up_completed.load(Ordering::Acquire) {
             return Ok(vec![]);
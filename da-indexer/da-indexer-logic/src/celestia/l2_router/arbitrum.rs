@@ -1,6 +1,6 @@
 use super::{
     new_client,
-    types::{L2BatchMetadata, L2Config},
+    types::{CelestiaBlobId, L2BatchMetadata, L2Config},
 };
 use anyhow::Result;
 use blockscout_display_bytes::Bytes;
@@ -23,6 +23,51 @@ struct L2BatchArbitrum {
     transactions_count: u64,
 }
 
+#[derive(Deserialize, Debug)]
+struct DataAvailability {
+    batch_data_container: Option<String>,
+    height: Option<u64>,
+    tx_commitment: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct L2BatchArbitrumDA {
+    data_availability: Option<DataAvailability>,
+}
+
+pub async fn get_l2_batch_blobs(
+    config: &L2Config,
+    namespace: &str,
+    batch_number: u64,
+) -> Result<Option<Vec<CelestiaBlobId>>> {
+    let query = format!(
+        "{}/api/v2/arbitrum/batches/{}",
+        config.l2_api_url, batch_number,
+    );
+
+    let response = new_client(config)?.get(&query).send().await?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+        tracing::debug!(batch_number, "l2 batch not found");
+        return Ok(None);
+    }
+    let response: L2BatchArbitrumDA = response.json().await?;
+
+    let blobs = match response.data_availability {
+        Some(DataAvailability {
+            batch_data_container: Some(container),
+            height: Some(height),
+            tx_commitment: Some(commitment),
+        }) if container == "in_celestia" => vec![CelestiaBlobId {
+            namespace: namespace.to_string(),
+            height,
+            commitment,
+        }],
+        _ => vec![],
+    };
+    Ok(Some(blobs))
+}
+
 pub async fn get_l2_batch(
     config: &L2Config,
     height: u64,
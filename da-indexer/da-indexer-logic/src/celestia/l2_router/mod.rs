@@ -10,7 +10,7 @@ use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde::{Deserialize, Serialize};
 use settings::L2RouterSettings;
 use std::{collections::HashMap, fs};
-use types::{L2BatchMetadata, L2Config, L2Type};
+use types::{CelestiaBlobId, L2BatchMetadata, L2Config, L2Type};
 
 #[derive(Serialize, Deserialize)]
 pub struct L2Router {
@@ -37,6 +37,12 @@ impl L2Router {
         Ok(router)
     }
 
+    pub fn chain_type(&self, namespace: &[u8]) -> Option<&L2Type> {
+        self.routes
+            .get(&ToHex::to_hex(&namespace))
+            .map(|config| &config.l2_chain_type)
+    }
+
     pub async fn get_l2_batch_metadata(
         &self,
         height: u64,
@@ -57,6 +63,32 @@ impl L2Router {
             L2Type::Arbitrum => arbitrum::get_l2_batch(config, height, commitment).await,
         }
     }
+
+    /// Celestia blobs containing the batch `batch_number` of the L2 with `l2_chain_id`.
+    ///
+    /// Returns `None` if the L2 or the batch is unknown.
+    pub async fn get_l2_batch_blobs(
+        &self,
+        l2_chain_id: u32,
+        batch_number: u64,
+    ) -> Result<Option<Vec<CelestiaBlobId>>> {
+        let (namespace, config) = match self
+            .routes
+            .iter()
+            .find(|(_, config)| config.l2_chain_id == l2_chain_id)
+        {
+            Some(route) => route,
+            None => {
+                tracing::debug!("unknown l2 chain id: {}", l2_chain_id);
+                return Ok(None);
+            }
+        };
+
+        match config.l2_chain_type {
+            L2Type::Optimism => optimism::get_l2_batch_blobs(config, batch_number).await,
+            L2Type::Arbitrum => arbitrum::get_l2_batch_blobs(config, namespace, batch_number).await,
+        }
+    }
 }
 
 pub fn new_client(config: &L2Config) -> Result<ClientWithMiddleware> {
@@ -1,4 +1,8 @@
-use super::{new_client, types::L2BatchMetadata, L2Config};
+use super::{
+    new_client,
+    types::{CelestiaBlobId, L2BatchMetadata},
+    L2Config,
+};
 use anyhow::{anyhow, Result};
 use blockscout_display_bytes::Bytes;
 use chrono::DateTime;
@@ -26,6 +30,45 @@ pub struct L2BatchOptimism {
     transaction_count: u64,
 }
 
+#[derive(Deserialize, Debug)]
+struct L2BatchOptimismDA {
+    batch_data_container: Option<String>,
+    // format of the blobs depends on the container
+    #[serde(default)]
+    blobs: serde_json::Value,
+}
+
+pub async fn get_l2_batch_blobs(
+    config: &L2Config,
+    batch_number: u64,
+) -> Result<Option<Vec<CelestiaBlobId>>> {
+    let query = format!(
+        "{}/api/v2/optimism/batches/{}",
+        config.l2_api_url, batch_number,
+    );
+
+    let response = new_client(config)?.get(&query).send().await?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+        tracing::debug!(batch_number, "l2 batch not found");
+        return Ok(None);
+    }
+    let response: L2BatchOptimismDA = response.json().await?;
+
+    if response.batch_data_container.as_deref() != Some("in_celestia") {
+        return Ok(Some(vec![]));
+    }
+    let blobs = serde_json::from_value::<Vec<Blob>>(response.blobs)?
+        .into_iter()
+        .map(|blob| CelestiaBlobId {
+            height: blob.height,
+            namespace: blob.namespace,
+            commitment: blob.commitment,
+        })
+        .collect();
+    Ok(Some(blobs))
+}
+
 pub async fn get_l2_batch(
     config: &L2Config,
     height: u64,
@@ -9,6 +9,16 @@ pub enum L2Type {
     Arbitrum,
 }
 
+impl L2Type {
+    /// Same as the serialized value used in the router config
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            L2Type::Optimism => "Optimism",
+            L2Type::Arbitrum => "Arbitrum",
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(deny_unknown_fields)]
@@ -1,3 +1,4 @@
+pub mod batch_decoder;
 mod client;
 pub mod da;
 pub mod job;
@@ -3,19 +3,26 @@ use da_indexer_entity::{
     celestia_blocks,
 };
 use sea_orm::{
-    sea_query::OnConflict, ConnectionTrait, DatabaseConnection, EntityTrait, FromQueryResult,
-    JoinType, QuerySelect, QueryTrait, SelectColumns,
+    sea_query::{Expr, OnConflict},
+    ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait, FromQueryResult,
+    JoinType, Order, QueryFilter, QueryOrder, QuerySelect, QueryTrait, SelectColumns, Statement,
+    TransactionTrait,
 };
 use sha3::{Digest, Sha3_256};
 
 use celestia_types::Blob as CelestiaBlob;
 
+use crate::common::types::stored_blob::StoredBlob;
+
 #[derive(FromQueryResult)]
 pub struct Blob {
+    pub id: Vec<u8>,
     pub height: i64,
     pub namespace: Vec<u8>,
     pub commitment: Vec<u8>,
     pub data: Vec<u8>,
+    pub offloaded: bool,
+    pub size: i64,
     pub timestamp: i64,
 }
 
@@ -43,6 +50,210 @@ pub async fn find_by_height_and_commitment(
     Ok(blob)
 }
 
+/// Blobs whose commitment is within `[from, to)`, ordered by the commitment.
+/// `None` upper bound means the range is not limited from above.
+///
+/// Use [`commitment_prefix_range`] to search by the commitment prefix.
+pub async fn find_by_commitment_range(
+    db: &DatabaseConnection,
+    from: Vec<u8>,
+    to: Option<Vec<u8>>,
+    limit: u64,
+) -> Result<Vec<Blob>, anyhow::Error> {
+    let mut query = Entity::find().filter(Column::Commitment.gte(from));
+    if let Some(to) = to {
+        query = query.filter(Column::Commitment.lt(to));
+    }
+    let blobs = Blob::find_by_statement(
+        query
+            .join_rev(
+                JoinType::LeftJoin,
+                celestia_blocks::Entity::belongs_to(Entity)
+                    .from(celestia_blocks::Column::Height)
+                    .to(Column::Height)
+                    .into(),
+            )
+            .select_column(celestia_blocks::Column::Timestamp)
+            .order_by(Column::Commitment, Order::Asc)
+            .order_by(Column::Height, Order::Asc)
+            .limit(limit)
+            .build(db.get_database_backend()),
+    )
+    .all(db)
+    .await?;
+    Ok(blobs)
+}
+
+/// Converts hex-encoded commitment prefix (possibly with an odd number of digits)
+/// into the range of commitments starting with it.
+pub fn commitment_prefix_range(
+    prefix: &str,
+) -> Result<(Vec<u8>, Option<Vec<u8>>), hex::FromHexError> {
+    let prefix = prefix.strip_prefix("0x").unwrap_or(prefix);
+    if prefix.len() % 2 == 1 {
+        let from = hex::decode(format!("{prefix}0"))?;
+        let to = increment(hex::decode(format!("{prefix}f"))?);
+        Ok((from, to))
+    } else {
+        let from = hex::decode(prefix)?;
+        let to = increment(from.clone());
+        Ok((from, to))
+    }
+}
+
+/// The smallest byte string greater than any string starting with `bytes`
+fn increment(mut bytes: Vec<u8>) -> Option<Vec<u8>> {
+    while let Some(last) = bytes.pop() {
+        if last < u8::MAX {
+            bytes.push(last + 1);
+            return Some(bytes);
+        }
+    }
+    None
+}
+
+#[derive(FromQueryResult, Debug, PartialEq, Eq)]
+pub struct NamespaceStats {
+    pub blobs_count: i64,
+    pub total_size: i64,
+    pub first_height: Option<i64>,
+    pub last_height: Option<i64>,
+}
+
+/// Blobs of the `namespace` ordered from the newest to the oldest.
+///
+/// Returns the page and the token (`(height, id)`) of the next page, if any.
+pub async fn find_by_namespace(
+    db: &DatabaseConnection,
+    namespace: &[u8],
+    page_token: Option<(u64, Vec<u8>)>,
+    page_size: u64,
+) -> Result<(Vec<Blob>, Option<(u64, Vec<u8>)>), anyhow::Error> {
+    let mut query = Entity::find().filter(Column::Namespace.eq(namespace));
+    if let Some((height, id)) = page_token {
+        query = query.filter(
+            Condition::any().add(Column::Height.lt(height as i64)).add(
+                Condition::all()
+                    .add(Column::Height.eq(height as i64))
+                    .add(Column::Id.lte(id)),
+            ),
+        );
+    }
+    let mut blobs = Blob::find_by_statement(
+        query
+            .join_rev(
+                JoinType::LeftJoin,
+                celestia_blocks::Entity::belongs_to(Entity)
+                    .from(celestia_blocks::Column::Height)
+                    .to(Column::Height)
+                    .into(),
+            )
+            .select_column(celestia_blocks::Column::Timestamp)
+            .order_by(Column::Height, Order::Desc)
+            .order_by(Column::Id, Order::Desc)
+            .limit(page_size + 1)
+            .build(db.get_database_backend()),
+    )
+    .all(db)
+    .await?;
+
+    let next_page_token = if blobs.len() as u64 > page_size {
+        blobs.pop().map(|blob| (blob.height as u64, blob.id))
+    } else {
+        None
+    };
+    Ok((blobs, next_page_token))
+}
+
+pub async fn namespace_stats(
+    db: &DatabaseConnection,
+    namespace: &[u8],
+) -> Result<NamespaceStats, anyhow::Error> {
+    let stats = NamespaceStats::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        r#"
+            SELECT COUNT(*)::bigint as blobs_count,
+                   COALESCE(SUM(size), 0)::bigint as total_size,
+                   MIN(height) as first_height,
+                   MAX(height) as last_height
+            FROM celestia_blobs WHERE namespace = $1;"#,
+        [namespace.to_vec().into()],
+    ))
+    .one(db)
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("aggregate query returned no rows"))?;
+    Ok(stats)
+}
+
+/// Blobs from blocks older than `timestamp` whose data is still stored in the database.
+pub async fn find_stored_before(
+    db: &DatabaseConnection,
+    timestamp: i64,
+    limit: u64,
+) -> Result<Vec<StoredBlob>, anyhow::Error> {
+    let blobs = StoredBlob::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        r#"
+            SELECT b.id, b.data
+            FROM celestia_blobs b
+            JOIN celestia_blocks bl ON bl.height = b.height
+            WHERE NOT b.offloaded AND bl.timestamp < $1
+            ORDER BY b.height
+            LIMIT $2;"#,
+        [timestamp.into(), (limit as i64).into()],
+    ))
+    .all(db)
+    .await?;
+    Ok(blobs)
+}
+
+/// Removes the data of the blobs from the database, marking them as offloaded.
+pub async fn mark_offloaded<C: ConnectionTrait>(
+    db: &C,
+    ids: Vec<Vec<u8>>,
+) -> Result<u64, anyhow::Error> {
+    let result = Entity::update_many()
+        .col_expr(Column::Offloaded, Expr::value(true))
+        .col_expr(Column::Data, Expr::value(Vec::<u8>::new()))
+        .filter(Column::Id.is_in(ids))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// Removes the blobs from the database, decrementing `blobs_count` of their blocks.
+pub async fn delete_many<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    ids: Vec<Vec<u8>>,
+) -> Result<u64, anyhow::Error> {
+    let txn = db.begin().await?;
+    let counts: Vec<(i64, i64)> = Entity::find()
+        .select_only()
+        .column(Column::Height)
+        .column_as(Column::Id.count(), "count")
+        .filter(Column::Id.is_in(ids.clone()))
+        .group_by(Column::Height)
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    let result = Entity::delete_many()
+        .filter(Column::Id.is_in(ids))
+        .exec(&txn)
+        .await?;
+    for (height, count) in counts {
+        celestia_blocks::Entity::update_many()
+            .col_expr(
+                celestia_blocks::Column::BlobsCount,
+                Expr::col(celestia_blocks::Column::BlobsCount).sub(count as i32),
+            )
+            .filter(celestia_blocks::Column::Height.eq(height))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await?;
+    Ok(result.rows_affected)
+}
+
 pub async fn upsert_many<C: ConnectionTrait>(
     db: &C,
     height: u64,
@@ -54,7 +265,9 @@ pub async fn upsert_many<C: ConnectionTrait>(
             height: height as i64,
             namespace: blob.namespace.as_bytes().to_vec(),
             commitment: blob.commitment.0.to_vec(),
+            size: blob.data.len() as i64,
             data: blob.data,
+            offloaded: false,
         };
         let active: ActiveModel = model.into();
         active
@@ -1,5 +1,6 @@
+use blockscout_display_bytes::Bytes;
 use serde::Deserialize;
-use serde_with::serde_as;
+use serde_with::{formats::CommaSeparator, serde_as, StringWithSeparator};
 
 #[serde_as]
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -7,6 +8,11 @@ use serde_with::serde_as;
 pub struct IndexerSettings {
     pub rpc: RpcSettings,
     pub start_height: Option<u64>,
+    /// Hex-encoded namespaces (29 bytes, including version) to be indexed.
+    /// If empty, blobs from all namespaces are indexed.
+    #[serde(default)]
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, Bytes>")]
+    pub namespaces: Vec<Bytes>,
 }
 
 #[serde_as]
@@ -33,6 +39,7 @@ impl Default for IndexerSettings {
     fn default() -> Self {
         Self {
             start_height: None,
+            namespaces: vec![],
             rpc: RpcSettings {
                 url: "http://localhost:26658".to_string(),
                 auth_token: None,
@@ -0,0 +1,166 @@
+use crate::celestia::{
+    batch_decoder::{
+        arbitrum::{self, HeaderKind},
+        decode,
+        optimism::{self, Frame},
+        Compression, DecodedBlob,
+    },
+    l2_router::types::L2Type,
+};
+
+fn op_frame(channel_id: u8, frame_number: u16, data: &[u8], is_last: bool) -> Vec<u8> {
+    [
+        &[channel_id; 16][..],
+        &frame_number.to_be_bytes(),
+        &(data.len() as u32).to_be_bytes(),
+        data,
+        &[is_last as u8],
+    ]
+    .concat()
+}
+
+#[test]
+fn decode_op_stack_frames() {
+    let data = [
+        &[optimism::DERIVATION_VERSION_0][..],
+        &op_frame(1, 0, &[0x78, 0xda, 0x01], false),
+        &op_frame(1, 1, &[0x02, 0x03], true),
+        &op_frame(2, 0, &[0x01, 0xaa], true),
+    ]
+    .concat();
+
+    let decoded = optimism::decode(&data).unwrap();
+    assert_eq!(decoded.derivation_version, 0);
+    assert_eq!(
+        decoded.frames,
+        vec![
+            Frame {
+                channel_id: [1; 16],
+                frame_number: 0,
+                data_length: 3,
+                is_last: false,
+                compression: Some(Compression::Zlib),
+            },
+            Frame {
+                channel_id: [1; 16],
+                frame_number: 1,
+                data_length: 2,
+                is_last: true,
+                compression: None,
+            },
+            Frame {
+                channel_id: [2; 16],
+                frame_number: 0,
+                data_length: 2,
+                is_last: true,
+                compression: Some(Compression::Brotli),
+            },
+        ]
+    );
+}
+
+#[test]
+fn decode_op_stack_invalid_data() {
+    // unsupported derivation version
+    assert!(optimism::decode(&[&[1_u8][..], &op_frame(1, 0, &[0x78], true)].concat()).is_err());
+    // no frames
+    assert!(optimism::decode(&[optimism::DERIVATION_VERSION_0]).is_err());
+    // truncated frame
+    let frame = op_frame(1, 0, &[0x78, 0xda], true);
+    assert!(optimism::decode(
+        &[
+            &[optimism::DERIVATION_VERSION_0][..],
+            &frame[..frame.len() - 2]
+        ]
+        .concat()
+    )
+    .is_err());
+    // invalid is_last
+    let mut frame = op_frame(1, 0, &[0x78], true);
+    *frame.last_mut().unwrap() = 2;
+    assert!(optimism::decode(&[&[optimism::DERIVATION_VERSION_0][..], &frame].concat()).is_err());
+}
+
+#[test]
+fn decode_arbitrum_batch() {
+    let cases = [
+        (0x00, 2, HeaderKind::Brotli, Some(Compression::Brotli)),
+        (0x80, 176, HeaderKind::DasCertificate, None),
+        (0x88, 177, HeaderKind::TreeDasCertificate, None),
+        (0x20, 2, HeaderKind::Zeroheavy, None),
+        (0x50, 64, HeaderKind::BlobHashes, None),
+        (0x63, 88, HeaderKind::Celestia, None),
+        (0x01, 2, HeaderKind::Unknown, None),
+    ];
+    for (header_byte, payload_size, kind, compression) in cases {
+        let data = [&[header_byte][..], &vec![0xaa; payload_size]].concat();
+        let decoded = arbitrum::decode(&data).unwrap();
+        assert_eq!(decoded.header_byte, header_byte);
+        assert_eq!(decoded.kind, kind, "header byte: {header_byte:#x}");
+        assert_eq!(decoded.compression, compression);
+        assert_eq!(decoded.payload_size, payload_size as u64);
+    }
+    assert!(arbitrum::decode(&[]).is_err());
+}
+
+#[test]
+fn decode_arbitrum_invalid_payload() {
+    let cases = [
+        // header only
+        (0x00, 0),
+        (0x20, 0),
+        // truncated certificates
+        (0x80, 175),
+        (0x88, 176),
+        // not a list of hashes
+        (0x50, 33),
+        // truncated blob pointer
+        (0x63, 87),
+    ];
+    for (header_byte, payload_size) in cases {
+        let data = [&[header_byte][..], &vec![0xaa; payload_size]].concat();
+        assert!(
+            arbitrum::decode(&data).is_err(),
+            "header byte: {header_byte:#x}, payload size: {payload_size}"
+        );
+    }
+    // not recognized as an arbitrum batch if the chain type is unknown
+    assert!(decode(&[0x63, 0xaa, 0xbb], None).is_none());
+}
+
+#[test]
+fn decode_with_chain_type() {
+    let op_data = [
+        &[optimism::DERIVATION_VERSION_0][..],
+        &op_frame(1, 0, &[0x78], true),
+    ]
+    .concat();
+
+    assert!(matches!(
+        decode(&op_data, Some(&L2Type::Optimism)),
+        Some(DecodedBlob::Optimism(_))
+    ));
+    assert!(matches!(
+        decode(&op_data, None),
+        Some(DecodedBlob::Optimism(_))
+    ));
+    // forced arbitrum decoding treats the first byte as a header
+    assert!(matches!(
+        decode(&op_data, Some(&L2Type::Arbitrum)),
+        Some(DecodedBlob::Arbitrum(arbitrum::BatchData {
+            kind: HeaderKind::Brotli,
+            ..
+        }))
+    ));
+
+    // not a valid op stack batcher data, but a valid arbitrum brotli batch
+    let arbitrum_data = [0x00, 0x1b, 0x2c];
+    assert!(decode(&arbitrum_data, Some(&L2Type::Optimism)).is_none());
+    assert!(matches!(
+        decode(&arbitrum_data, None),
+        Some(DecodedBlob::Arbitrum(_))
+    ));
+
+    // unknown format
+    assert!(decode(&[0x01, 0x02, 0x03], None).is_none());
+}
@@ -7,6 +7,8 @@ use crate::celestia::{
     repository::{blobs, blocks},
     tests::init_db,
 };
+use da_indexer_entity::celestia_blocks;
+use sea_orm::{EntityTrait, QueryOrder};
 use sha3::{Digest, Sha3_256};
 
 #[tokio::test]
@@ -47,6 +49,219 @@ async fn smoke_test() {
     );
 }
 
+#[tokio::test]
+async fn find_by_namespace_test() {
+    let db = init_db("celestia_blobs_find_by_namespace_test").await;
+    for height in 1..=3 {
+        let blobs = (1..=3).map(celestia_blob).collect::<Vec<_>>();
+        blocks::upsert(db.client().as_ref(), height, &[], 3, height as i64)
+            .await
+            .unwrap();
+        blobs::upsert_many(db.client().as_ref(), height, blobs)
+            .await
+            .unwrap();
+    }
+    let namespace = celestia_blob(1).namespace.as_bytes().to_vec();
+
+    let (page, next_page_token) = blobs::find_by_namespace(&db.client(), &namespace, None, 2)
+        .await
+        .unwrap();
+    assert_eq!(
+        page.iter().map(|blob| blob.height).collect::<Vec<_>>(),
+        vec![3, 2]
+    );
+    assert!(page.iter().all(|blob| blob.namespace == namespace));
+    assert_eq!(page[0].timestamp, 3);
+    let next_page_token = next_page_token.unwrap();
+    assert_eq!(next_page_token.0, 1);
+
+    let (page, next_page_token) =
+        blobs::find_by_namespace(&db.client(), &namespace, Some(next_page_token), 2)
+            .await
+            .unwrap();
+    assert_eq!(
+        page.iter().map(|blob| blob.height).collect::<Vec<_>>(),
+        vec![1]
+    );
+    assert!(next_page_token.is_none());
+
+    let (page, next_page_token) = blobs::find_by_namespace(&db.client(), &[0_u8; 29], None, 2)
+        .await
+        .unwrap();
+    assert!(page.is_empty());
+    assert!(next_page_token.is_none());
+}
+
+#[tokio::test]
+async fn namespace_stats_test() {
+    let db = init_db("celestia_blobs_namespace_stats_test").await;
+    for height in 1..=3 {
+        let blobs = (1..=3).map(celestia_blob).collect::<Vec<_>>();
+        blocks::upsert(db.client().as_ref(), height, &[], 3, 0)
+            .await
+            .unwrap();
+        blobs::upsert_many(db.client().as_ref(), height, blobs)
+            .await
+            .unwrap();
+    }
+    let namespace = celestia_blob(2).namespace.as_bytes().to_vec();
+
+    let stats = blobs::namespace_stats(&db.client(), &namespace)
+        .await
+        .unwrap();
+    assert_eq!(
+        stats,
+        blobs::NamespaceStats {
+            blobs_count: 3,
+            total_size: 3 * 32,
+            first_height: Some(1),
+            last_height: Some(3),
+        }
+    );
+
+    let stats = blobs::namespace_stats(&db.client(), &[0_u8; 29])
+        .await
+        .unwrap();
+    assert_eq!(
+        stats,
+        blobs::NamespaceStats {
+            blobs_count: 0,
+            total_size: 0,
+            first_height: None,
+            last_height: None,
+        }
+    );
+}
+
+#[tokio::test]
+async fn retention_test() {
+    let db = init_db("celestia_blobs_retention_test").await;
+    for height in 1..=3 {
+        let blobs = (1..=2).map(celestia_blob).collect::<Vec<_>>();
+        blocks::upsert(db.client().as_ref(), height, &[], 2, height as i64 * 100)
+            .await
+            .unwrap();
+        blobs::upsert_many(db.client().as_ref(), height, blobs)
+            .await
+            .unwrap();
+    }
+
+    // blocks 1 and 2 are older than 300
+    let stored = blobs::find_stored_before(&db.client(), 300, 10)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 4);
+    assert_eq!(
+        blobs::find_stored_before(&db.client(), 300, 3)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+
+    let commitment = celestia_blob(1).commitment.0;
+    let blob = blobs::find_by_height_and_commitment(&db.client(), 1, &commitment)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        blobs::mark_offloaded(db.client().as_ref(), vec![blob.id.clone()])
+            .await
+            .unwrap(),
+        1
+    );
+    let offloaded = blobs::find_by_height_and_commitment(&db.client(), 1, &commitment)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(offloaded.offloaded);
+    assert!(offloaded.data.is_empty());
+    assert_eq!(offloaded.size, 32);
+    assert_eq!(
+        blobs::find_stored_before(&db.client(), 300, 10)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+
+    let ids = stored.into_iter().map(|blob| blob.id).collect();
+    assert_eq!(
+        blobs::delete_many(db.client().as_ref(), ids).await.unwrap(),
+        4
+    );
+    assert!(blobs::find_stored_before(&db.client(), 300, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        blobs::find_stored_before(&db.client(), 400, 10)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    let blobs_counts = celestia_blocks::Entity::find()
+        .order_by_asc(celestia_blocks::Column::Height)
+        .all(db.client().as_ref())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|block| block.blobs_count)
+        .collect::<Vec<_>>();
+    assert_eq!(blobs_counts, vec![0, 0, 2]);
+}
+
+#[tokio::test]
+async fn find_by_commitment_range_test() {
+    let db = init_db("celestia_blobs_find_by_commitment_range_test").await;
+    let blobs = (1..=10).map(celestia_blob).collect::<Vec<_>>();
+    blocks::upsert(db.client().as_ref(), 1, &[], 10, 1)
+        .await
+        .unwrap();
+    blobs::upsert_many(db.client().as_ref(), 1, blobs)
+        .await
+        .unwrap();
+
+    let commitment = celestia_blob(1).commitment.0.to_vec();
+    let prefix = hex::encode(&commitment[..3]);
+    let (from, to) = blobs::commitment_prefix_range(&prefix).unwrap();
+    let found = blobs::find_by_commitment_range(&db.client(), from, to, 10)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].commitment, commitment);
+
+    let (from, to) = blobs::commitment_prefix_range("").unwrap();
+    let found = blobs::find_by_commitment_range(&db.client(), from, to, 5)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 5);
+    assert!(found.windows(2).all(|w| w[0].commitment <= w[1].commitment));
+}
+
+#[test]
+fn commitment_prefix_range_test() {
+    assert_eq!(
+        blobs::commitment_prefix_range("0x12ab").unwrap(),
+        (vec![0x12, 0xab], Some(vec![0x12, 0xac]))
+    );
+    assert_eq!(
+        blobs::commitment_prefix_range("12a").unwrap(),
+        (vec![0x12, 0xa0], Some(vec![0x12, 0xb0]))
+    );
+    assert_eq!(
+        blobs::commitment_prefix_range("12ff").unwrap(),
+        (vec![0x12, 0xff], Some(vec![0x13]))
+    );
+    assert_eq!(
+        blobs::commitment_prefix_range("fff").unwrap(),
+        (vec![0xff, 0xf0], None)
+    );
+    assert!(blobs::commitment_prefix_range("xyz").is_err());
+}
+
 fn celestia_blob(seed: u32) -> CelestiaBlob {
     let namespace =
         Namespace::new(0, &[&[0_u8; 18], &sha3("namespace", seed)[..10]].concat()).unwrap();
@@ -82,6 +82,37 @@ async fn test_arbitrum_router() {
     assert_eq!(batch_metadata.related_blobs.len(), 0);
 }
 
+#[tokio::test]
+async fn test_l2_batch_blobs() {
+    let l2_router = create_test_router().await;
+
+    let blobs = l2_router
+        .get_l2_batch_blobs(123420111, 5)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(blobs.len(), 2);
+    assert_eq!(blobs[0].height, 760960);
+    assert_eq!(
+        blobs[0].commitment,
+        "0xf1a51990b5a358a2376e85648b489138ca38533e2b86e0283d41ceeebcf058ea"
+    );
+
+    let blobs = l2_router
+        .get_l2_batch_blobs(123, 610699)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(blobs.len(), 1);
+    assert_eq!(blobs[0].height, 2282948);
+    assert_eq!(
+        blobs[0].namespace,
+        "0x00000000000000000000000000000000000000ca1de12a1f4dbe943b6b"
+    );
+
+    assert!(l2_router.get_l2_batch_blobs(1, 1).await.unwrap().is_none());
+}
+
 async fn create_test_router() -> L2Router {
     let mock_server = create_blockscout_mock().await;
     let mut routes: HashMap<String, L2Config> = HashMap::new();
@@ -176,5 +207,46 @@ async fn create_blockscout_mock() -> MockServer {
         )))
         .mount(&mock_server)
         .await;
+    Mock::given(method("GET"))
+        .and(path("api/v2/optimism/batches/5"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!(
+            {
+                "batch_data_container": "in_celestia",
+                "blobs": [
+                  {
+                    "commitment": "0xf1a51990b5a358a2376e85648b489138ca38533e2b86e0283d41ceeebcf058ea",
+                    "height": 760960,
+                    "l1_timestamp": "2023-12-20T10:17:12.000000Z",
+                    "l1_transaction_hash": "0xf41211e966ec23032dde713d1f775ae5cb07dc5e15951281e6844d74cc02a930",
+                    "namespace": "0x00000000000000000000000000000000000000000008e5f679bf7116cb"
+                  },
+                  {
+                    "commitment": "0x3834d3a92ede97db07defc291c848b6085389c236f88c52b67a933271f316fee",
+                    "height": 760961,
+                    "l1_timestamp": "2023-12-20T10:17:24.000000Z",
+                    "l1_transaction_hash": "0x9abc0df13890e8c0818b448b15056ecd96368dc2b4f625c1232285e05e5b3826",
+                    "namespace": "0x00000000000000000000000000000000000000000008e5f679bf7116cb"
+                  }
+                ],
+                "internal_id": 5
+            }
+        )))
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("GET"))
+        .and(path("api/v2/arbitrum/batches/610699"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!(
+            {
+                "data_availability": {
+                    "batch_data_container": "in_celestia",
+                    "tx_commitment": "0x5f4dece44a8b054de4fd1837c2fc0aef0e68b2f39d55ec0658bfb659ba7bb8e9",
+                    "height": 2282948
+                },
+                "number": 610699
+            }
+        )))
+        .mount(&mock_server)
+        .await;
     mock_server
 }
@@ -1,3 +1,4 @@
+pub mod batch_decoder;
 pub mod blobs;
 pub mod blocks;
 pub mod l2_router;
@@ -1 +1,2 @@
 pub mod gap;
+pub mod stored_blob;
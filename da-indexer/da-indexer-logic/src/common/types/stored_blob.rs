@@ -0,0 +1,8 @@
+use sea_orm::FromQueryResult;
+
+/// Blob data stored in the database, used to apply retention policies.
+#[derive(FromQueryResult, Clone, Debug, PartialEq, Eq)]
+pub struct StoredBlob {
+    pub id: Vec<u8>,
+    pub data: Vec<u8>,
+}
@@ -12,6 +12,7 @@ use crate::{
     common::{eth_provider::EthProvider, types::gap::Gap},
     eigenda::repository::{batches, blobs},
     indexer::{Job, DA},
+    status::IndexerStatus,
 };
 
 use super::{client::Client, job::EigenDAJob, settings::IndexerSettings};
@@ -22,13 +23,18 @@ pub struct EigenDA {
     db: Arc<DatabaseConnection>,
     client: Client,
     provider: EthProvider,
+    status: Arc<IndexerStatus>,
 
     last_known_block: AtomicU64,
     unprocessed_gaps: Mutex<Vec<Gap>>,
 }
 
 impl EigenDA {
-    pub async fn new(db: Arc<DatabaseConnection>, settings: IndexerSettings) -> Result<Self> {
+    pub async fn new(
+        db: Arc<DatabaseConnection>,
+        settings: IndexerSettings,
+        status: Arc<IndexerStatus>,
+    ) -> Result<Self> {
         let provider = EthProvider::new(&settings.rpc.url).await?;
         let client = Client::new(&settings.disperser_url, vec![5, 15, 30]).await?;
         let start_from = settings
@@ -40,11 +46,15 @@ impl EigenDA {
             start_from as i64,
         )
         .await?;
+        if let Some(max_block) = batches::find_max_l1_block_in_range(&db, 0, i64::MAX).await? {
+            status.update_indexed_height(max_block as u64);
+        }
         Ok(Self {
             settings,
             db,
             client,
             provider,
+            status,
             last_known_block: AtomicU64::new(start_from.saturating_sub(1)),
             unprocessed_gaps: Mutex::new(gaps),
         })
@@ -134,6 +144,7 @@ impl DA for EigenDA {
             job.block_number as i64,
         )
         .await?;
+        self.status.update_indexed_height(job.block_number);
 
         Ok(())
     }
@@ -141,12 +152,17 @@ impl DA for EigenDA {
     async fn new_jobs(&self) -> Result<Vec<Job>> {
         let from = self.last_known_block.load(Ordering::Acquire) + 1;
         let to = self.provider.get_block_number().await?;
+        self.status.update_chain_height(to);
 
         let jobs = self.jobs_from_block_range(from, to, None).await?;
         self.last_known_block.store(to, Ordering::Release);
         Ok(jobs)
     }
 
+    async fn jobs_in_range(&self, from: u64, to: u64) -> Result<Vec<Job>> {
+        self.jobs_from_block_range(from, to, None).await
+    }
+
     /// Returns the earliest unprocessed batch or multiple batches
     /// if there are many in the same block
     async fn unprocessed_jobs(&self) -> Result<Vec<Job>> {
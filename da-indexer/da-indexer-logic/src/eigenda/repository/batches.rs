@@ -77,6 +77,16 @@ pub async fn find_max_l1_block_in_range(
     Ok(max_block.flatten())
 }
 
+pub async fn find_max_l1_block(db: &DatabaseConnection) -> Result<Option<i64>, anyhow::Error> {
+    let max_block: Option<Option<i64>> = Entity::find()
+        .select_only()
+        .column_as(Expr::col(Column::L1Block).max(), "l1_block")
+        .into_tuple()
+        .one(db)
+        .await?;
+    Ok(max_block.flatten())
+}
+
 pub async fn find_min_batch_id(
     db: &DatabaseConnection,
 ) -> Result<Option<(i64, i64)>, anyhow::Error> {
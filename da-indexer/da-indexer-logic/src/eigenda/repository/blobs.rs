@@ -1,21 +1,28 @@
 use da_indexer_entity::{
     eigenda_batches,
-    eigenda_blobs::{ActiveModel, Column, Entity, Model},
+    eigenda_blobs::{ActiveModel, Column, Entity},
 };
 use sea_orm::{
-    sea_query::OnConflict, ConnectionTrait, DatabaseConnection, EntityTrait, FromQueryResult,
-    JoinType, QueryOrder, QuerySelect, QueryTrait, SelectColumns,
+    sea_query::{Expr, OnConflict},
+    ActiveValue::{NotSet, Set},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, FromQueryResult, JoinType,
+    QueryFilter, QueryOrder, QuerySelect, QueryTrait, SelectColumns, Statement, TransactionTrait,
 };
 use sha3::{Digest, Sha3_256};
 
+use crate::common::types::stored_blob::StoredBlob;
+
 #[derive(FromQueryResult)]
 pub struct Blob {
+    pub id: Vec<u8>,
     pub batch_header_hash: Vec<u8>,
     pub batch_id: i64,
     pub blob_index: i32,
     pub l1_tx_hash: Vec<u8>,
     pub l1_block: i64,
     pub data: Vec<u8>,
+    pub offloaded: bool,
+    pub size: i64,
 }
 
 pub async fn find(
@@ -47,6 +54,99 @@ pub async fn find(
     Ok(blob)
 }
 
+/// Blobs of the batches confirmed in the L1 transaction `l1_tx_hash`.
+pub async fn find_by_l1_tx_hash(
+    db: &DatabaseConnection,
+    l1_tx_hash: &[u8],
+    limit: u64,
+) -> Result<Vec<Blob>, anyhow::Error> {
+    let blobs = Blob::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        r#"
+            SELECT b.id, b.batch_header_hash, bt.batch_id, b.blob_index,
+                   bt.l1_tx_hash, bt.l1_block, b.data, b.offloaded, b.size
+            FROM eigenda_batches bt
+            JOIN eigenda_blobs b ON b.batch_header_hash = bt.batch_header_hash
+            WHERE bt.l1_tx_hash = $1
+            ORDER BY bt.batch_id, b.blob_index
+            LIMIT $2;"#,
+        [l1_tx_hash.to_vec().into(), (limit as i64).into()],
+    ))
+    .all(db)
+    .await?;
+    Ok(blobs)
+}
+
+/// Blobs of the batches confirmed before `l1_block` whose data is still stored in the database.
+pub async fn find_stored_before(
+    db: &DatabaseConnection,
+    l1_block: i64,
+    limit: u64,
+) -> Result<Vec<StoredBlob>, anyhow::Error> {
+    let blobs = StoredBlob::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        r#"
+            SELECT b.id, b.data
+            FROM eigenda_blobs b
+            WHERE NOT b.offloaded AND EXISTS (
+                SELECT 1 FROM eigenda_batches bt
+                WHERE bt.batch_header_hash = b.batch_header_hash AND bt.l1_block < $1
+            )
+            LIMIT $2;"#,
+        [l1_block.into(), (limit as i64).into()],
+    ))
+    .all(db)
+    .await?;
+    Ok(blobs)
+}
+
+/// Removes the data of the blobs from the database, marking them as offloaded.
+pub async fn mark_offloaded<C: ConnectionTrait>(
+    db: &C,
+    ids: Vec<Vec<u8>>,
+) -> Result<u64, anyhow::Error> {
+    let result = Entity::update_many()
+        .col_expr(Column::Offloaded, Expr::value(true))
+        .col_expr(Column::Data, Expr::value(Vec::<u8>::new()))
+        .filter(Column::Id.is_in(ids))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// Removes the blobs from the database, decrementing `blobs_count` of their batches.
+pub async fn delete_many<C: ConnectionTrait + TransactionTrait>(
+    db: &C,
+    ids: Vec<Vec<u8>>,
+) -> Result<u64, anyhow::Error> {
+    let txn = db.begin().await?;
+    let counts: Vec<(Vec<u8>, i64)> = Entity::find()
+        .select_only()
+        .column(Column::BatchHeaderHash)
+        .column_as(Column::Id.count(), "count")
+        .filter(Column::Id.is_in(ids.clone()))
+        .group_by(Column::BatchHeaderHash)
+        .into_tuple()
+        .all(&txn)
+        .await?;
+    let result = Entity::delete_many()
+        .filter(Column::Id.is_in(ids))
+        .exec(&txn)
+        .await?;
+    for (batch_header_hash, count) in counts {
+        eigenda_batches::Entity::update_many()
+            .col_expr(
+                eigenda_batches::Column::BlobsCount,
+                Expr::col(eigenda_batches::Column::BlobsCount).sub(count as i32),
+            )
+            .filter(eigenda_batches::Column::BatchHeaderHash.eq(batch_header_hash))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await?;
+    Ok(result.rows_affected)
+}
+
 pub async fn upsert_many<C: ConnectionTrait>(
     db: &C,
     start_index: i32,
@@ -55,14 +155,16 @@ pub async fn upsert_many<C: ConnectionTrait>(
 ) -> Result<(), anyhow::Error> {
     let blobs = blobs.into_iter().enumerate().map(|(i, data)| {
         let blob_index = start_index + i as i32;
-        let model = Model {
-            id: compute_id(batch_header_hash, blob_index),
-            batch_header_hash: batch_header_hash.to_vec(),
-            blob_index,
-            data,
-        };
-        let active: ActiveModel = model.into();
-        active
+        // `created_at` is filled by the database
+        ActiveModel {
+            id: Set(compute_id(batch_header_hash, blob_index)),
+            batch_header_hash: Set(batch_header_hash.to_vec()),
+            blob_index: Set(blob_index),
+            size: Set(data.len() as i64),
+            data: Set(data),
+            offloaded: Set(false),
+            created_at: NotSet,
+        }
     });
 
     Entity::insert_many(blobs)
@@ -2,6 +2,8 @@ use crate::eigenda::{
     repository::{batches, blobs},
     tests::init_db,
 };
+use da_indexer_entity::eigenda_batches;
+use sea_orm::{EntityTrait, QueryOrder};
 
 #[tokio::test]
 async fn smoke_test() {
@@ -43,3 +45,133 @@ async fn smoke_test() {
     assert_eq!(blob.data, vec![2_u8; 32]);
     assert_eq!(blob.batch_header_hash, batch_header_hash);
 }
+
+#[tokio::test]
+async fn find_by_l1_tx_hash_test() {
+    let db = init_db("eigenda_blobs_find_by_l1_tx_hash_test").await;
+
+    let tx_hash = [1_u8; 32];
+    for (batch_id, batch_header_hash) in [(1, [10_u8; 32]), (2, [20_u8; 32])] {
+        batches::upsert(
+            db.client().as_ref(),
+            &batch_header_hash,
+            batch_id,
+            2,
+            &tx_hash,
+            100,
+        )
+        .await
+        .unwrap();
+        blobs::upsert_many(
+            db.client().as_ref(),
+            0,
+            &batch_header_hash,
+            vec![vec![0_u8; 32], vec![1_u8; 32]],
+        )
+        .await
+        .unwrap();
+    }
+
+    let found = blobs::find_by_l1_tx_hash(db.client().as_ref(), &tx_hash, 10)
+        .await
+        .unwrap();
+    assert_eq!(
+        found
+            .iter()
+            .map(|blob| (blob.batch_id, blob.blob_index))
+            .collect::<Vec<_>>(),
+        vec![(1, 0), (1, 1), (2, 0), (2, 1)]
+    );
+
+    let found = blobs::find_by_l1_tx_hash(db.client().as_ref(), &tx_hash, 3)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 3);
+
+    assert!(
+        blobs::find_by_l1_tx_hash(db.client().as_ref(), &[2_u8; 32], 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn retention_test() {
+    let db = init_db("eigenda_blobs_retention_test").await;
+    for (batch_id, l1_block) in [(1, 100), (2, 200), (3, 300)] {
+        let batch_header_hash = [batch_id as u8; 32];
+        batches::upsert(
+            db.client().as_ref(),
+            &batch_header_hash,
+            batch_id,
+            2,
+            &[batch_id as u8; 32],
+            l1_block,
+        )
+        .await
+        .unwrap();
+        blobs::upsert_many(
+            db.client().as_ref(),
+            0,
+            &batch_header_hash,
+            vec![vec![0_u8; 32], vec![1_u8; 16]],
+        )
+        .await
+        .unwrap();
+    }
+    assert_eq!(
+        batches::find_max_l1_block(&db.client()).await.unwrap(),
+        Some(300)
+    );
+
+    // batches 1 and 2 are confirmed before block 300
+    let stored = blobs::find_stored_before(&db.client(), 300, 10)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 4);
+
+    let blob = blobs::find(db.client().as_ref(), &[1_u8; 32], 1)
+        .await
+        .unwrap()
+        .unwrap();
+    blobs::mark_offloaded(db.client().as_ref(), vec![blob.id])
+        .await
+        .unwrap();
+    let offloaded = blobs::find(db.client().as_ref(), &[1_u8; 32], 1)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(offloaded.offloaded);
+    assert!(offloaded.data.is_empty());
+    assert_eq!(offloaded.size, 16);
+    assert_eq!(
+        blobs::find_stored_before(&db.client(), 300, 10)
+            .await
+            .unwrap()
+            .len(),
+        3
+    );
+
+    let ids = blobs::find_stored_before(&db.client(), 300, 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|blob| blob.id)
+        .collect();
+    assert_eq!(
+        blobs::delete_many(db.client().as_ref(), ids).await.unwrap(),
+        3
+    );
+
+    let blobs_counts = eigenda_batches::Entity::find()
+        .order_by_asc(eigenda_batches::Column::BatchId)
+        .all(db.client().as_ref())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|batch| batch.blobs_count)
+        .collect::<Vec<_>>();
+    // the offloaded blob is kept
+    assert_eq!(blobs_counts, vec![1, 0, 2]);
+}
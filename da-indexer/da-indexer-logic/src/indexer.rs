@@ -13,6 +13,7 @@ use tracing::instrument;
 use crate::{
     celestia, eigenda,
     settings::{DASettings, IndexerSettings},
+    status::IndexerStatus,
 };
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone)]
@@ -26,6 +27,24 @@ pub trait DA {
     async fn process_job(&self, job: Job) -> Result<()>;
     async fn unprocessed_jobs(&self) -> Result<Vec<Job>>;
     async fn new_jobs(&self) -> Result<Vec<Job>>;
+    /// Jobs within the inclusive range of heights, used for backfilling
+    async fn jobs_in_range(&self, from: u64, to: u64) -> Result<Vec<Job>>;
+}
+
+pub async fn new_da(
+    db: Arc<DatabaseConnection>,
+    settings: DASettings,
+    status: Arc<IndexerStatus>,
+) -> Result<Box<dyn DA + Send + Sync>> {
+    let da: Box<dyn DA + Send + Sync> = match settings {
+        DASettings::Celestia(settings) => {
+            Box::new(celestia::da::CelestiaDA::new(db, settings, status).await?)
+        }
+        DASettings::EigenDA(settings) => {
+            Box::new(eigenda::da::EigenDA::new(db, settings, status).await?)
+        }
+    };
+    Ok(da)
 }
 
 pub struct Indexer {
@@ -36,15 +55,12 @@ pub struct Indexer {
 }
 
 impl Indexer {
-    pub async fn new(db: Arc<DatabaseConnection>, settings: IndexerSettings) -> Result<Self> {
-        let da: Box<dyn DA + Send + Sync> = match settings.da.clone() {
-            DASettings::Celestia(settings) => {
-                Box::new(celestia::da::CelestiaDA::new(db.clone(), settings).await?)
-            }
-            DASettings::EigenDA(settings) => {
-                Box::new(eigenda::da::EigenDA::new(db.clone(), settings).await?)
-            }
-        };
+    pub async fn new(
+        db: Arc<DatabaseConnection>,
+        settings: IndexerSettings,
+        status: Arc<IndexerStatus>,
+    ) -> Result<Self> {
+        let da = new_da(db, settings.da.clone(), status).await?;
         Ok(Self {
             da,
             settings,
@@ -1,8 +1,13 @@
 #[macro_use]
 extern crate lazy_static;
 
+pub mod backfill;
 pub mod celestia;
 pub mod common;
 pub mod eigenda;
 pub mod indexer;
+pub mod metrics;
+pub mod retention;
+pub mod s3_storage;
 pub mod settings;
+pub mod status;
@@ -0,0 +1,46 @@
+use prometheus::{register_int_counter_vec, register_int_gauge_vec, IntCounterVec, IntGaugeVec};
+
+lazy_static! {
+    pub static ref INDEXER_CHAIN_HEIGHT: IntGaugeVec = register_int_gauge_vec!(
+        "da_indexer_chain_height",
+        "latest known height of the DA layer",
+        &["da", "network"],
+    )
+    .unwrap();
+    pub static ref INDEXER_INDEXED_HEIGHT: IntGaugeVec = register_int_gauge_vec!(
+        "da_indexer_indexed_height",
+        "latest indexed height of the DA layer",
+        &["da", "network"],
+    )
+    .unwrap();
+    pub static ref INDEXER_LAG: IntGaugeVec = register_int_gauge_vec!(
+        "da_indexer_lag",
+        "difference between the latest known and the latest indexed heights",
+        &["da", "network"],
+    )
+    .unwrap();
+    pub static ref BACKFILL_PROCESSED_HEIGHT: IntGaugeVec = register_int_gauge_vec!(
+        "da_indexer_backfill_processed_height",
+        "latest height processed by the backfill",
+        &["da"],
+    )
+    .unwrap();
+    pub static ref RETENTION_OFFLOADED_BYTES: IntCounterVec = register_int_counter_vec!(
+        "da_indexer_retention_offloaded_bytes",
+        "total size of blobs data moved to the external storage",
+        &["da"],
+    )
+    .unwrap();
+    pub static ref RETENTION_OFFLOADED_BLOBS: IntCounterVec = register_int_counter_vec!(
+        "da_indexer_retention_offloaded_blobs",
+        "total number of blobs moved to the external storage",
+        &["da"],
+    )
+    .unwrap();
+    pub static ref RETENTION_DELETED_BLOBS: IntCounterVec = register_int_counter_vec!(
+        "da_indexer_retention_deleted_blobs",
+        "total number of blobs deleted by retention policy",
+        &["da"],
+    )
+    .unwrap();
+}
@@ -0,0 +1,189 @@
+//! Retention of the old blobs.
+//!
+//! Periodically moves blobs older than configured age to the external
+//! storage or deletes them, depending on the policy of each DA layer.
+
+pub mod settings;
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tokio::time::sleep;
+
+use crate::{
+    celestia, eigenda, metrics,
+    s3_storage::{self, S3Storage},
+};
+use settings::{RetentionAction, RetentionPolicySettings, RetentionSettings};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Celestia,
+    EigenDA,
+}
+
+impl Target {
+    fn label(&self) -> &'static str {
+        match self {
+            Target::Celestia => "celestia",
+            Target::EigenDA => "eigenda",
+        }
+    }
+
+    fn storage_key(&self, network: &str, id: &[u8]) -> String {
+        match self {
+            Target::Celestia => s3_storage::celestia_blob_key(network, id),
+            Target::EigenDA => s3_storage::eigenda_blob_key(id),
+        }
+    }
+}
+
+pub struct RetentionWorker {
+    db: Arc<DatabaseConnection>,
+    storage: Option<Arc<S3Storage>>,
+    settings: RetentionSettings,
+    /// Celestia network whose database is processed
+    network: String,
+}
+
+impl RetentionWorker {
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        storage: Option<Arc<S3Storage>>,
+        settings: RetentionSettings,
+        network: impl Into<String>,
+    ) -> Result<Self> {
+        let requires_storage = [&settings.celestia, &settings.eigenda]
+            .into_iter()
+            .flatten()
+            .any(|policy| policy.action == RetentionAction::Offload);
+        if requires_storage && storage.is_none() {
+            anyhow::bail!("s3 storage must be configured to offload blobs");
+        }
+        Ok(Self {
+            db,
+            storage,
+            settings,
+            network: network.into(),
+        })
+    }
+
+    pub async fn run(self) {
+        loop {
+            if let Err(err) = self.apply_policies().await {
+                tracing::error!(error = ?err, "failed to apply retention policies");
+            }
+            sleep(self.settings.interval).await;
+        }
+    }
+
+    pub async fn apply_policies(&self) -> Result<()> {
+        if let Some(policy) = &self.settings.celestia {
+            self.apply_policy(Target::Celestia, policy).await?;
+        }
+        if let Some(policy) = &self.settings.eigenda {
+            self.apply_policy(Target::EigenDA, policy).await?;
+        }
+        Ok(())
+    }
+
+    async fn apply_policy(&self, target: Target, policy: &RetentionPolicySettings) -> Result<()> {
+        // Celestia blocks are compared by the timestamp, while EigenDA batches have
+        // no timestamps, so their age is measured in L1 blocks from the latest indexed batch
+        let threshold = match target {
+            Target::Celestia => {
+                (Utc::now() - Duration::days(policy.max_age_days as i64)).timestamp()
+            }
+            Target::EigenDA => {
+                match eigenda::repository::batches::find_max_l1_block(&self.db).await? {
+                    Some(l1_block) => {
+                        l1_block
+                            - (policy.max_age_days * self.settings.eigenda_l1_blocks_per_day) as i64
+                    }
+                    None => return Ok(()),
+                }
+            }
+        };
+        let batch_size = self.settings.batch_size;
+        let mut processed = 0;
+        loop {
+            let blobs = match target {
+                Target::Celestia => {
+                    celestia::repository::blobs::find_stored_before(&self.db, threshold, batch_size)
+                        .await?
+                }
+                Target::EigenDA => {
+                    eigenda::repository::blobs::find_stored_before(&self.db, threshold, batch_size)
+                        .await?
+                }
+            };
+            if blobs.is_empty() {
+                break;
+            }
+            let count = blobs.len() as u64;
+
+            match policy.action {
+                RetentionAction::Offload => {
+                    let storage = self
+                        .storage
+                        .as_ref()
+                        .ok_or_else(|| anyhow::anyhow!("s3 storage is not configured"))?;
+                    let mut bytes = 0;
+                    for blob in &blobs {
+                        storage
+                            .put(&target.storage_key(&self.network, &blob.id), &blob.data)
+                            .await?;
+                        bytes += blob.data.len() as u64;
+                    }
+                    let ids = blobs.into_iter().map(|blob| blob.id).collect();
+                    match target {
+                        Target::Celestia => {
+                            celestia::repository::blobs::mark_offloaded(self.db.as_ref(), ids)
+                                .await?
+                        }
+                        Target::EigenDA => {
+                            eigenda::repository::blobs::mark_offloaded(self.db.as_ref(), ids)
+                                .await?
+                        }
+                    };
+                    metrics::RETENTION_OFFLOADED_BYTES
+                        .with_label_values(&[target.label()])
+                        .inc_by(bytes);
+                    metrics::RETENTION_OFFLOADED_BLOBS
+                        .with_label_values(&[target.label()])
+                        .inc_by(count);
+                }
+                RetentionAction::Delete => {
+                    let ids = blobs.into_iter().map(|blob| blob.id).collect();
+                    match target {
+                        Target::Celestia => {
+                            celestia::repository::blobs::delete_many(self.db.as_ref(), ids).await?
+                        }
+                        Target::EigenDA => {
+                            eigenda::repository::blobs::delete_many(self.db.as_ref(), ids).await?
+                        }
+                    };
+                    metrics::RETENTION_DELETED_BLOBS
+                        .with_label_values(&[target.label()])
+                        .inc_by(count);
+                }
+            }
+
+            processed += count;
+            if count < batch_size {
+                break;
+            }
+        }
+        if processed > 0 {
+            tracing::info!(
+                da = target.label(),
+                network = self.network,
+                action = ?policy.action,
+                processed,
+                "applied retention policy"
+            );
+        }
+        Ok(())
+    }
+}
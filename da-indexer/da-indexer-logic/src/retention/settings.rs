@@ -0,0 +1,64 @@
+use serde::Deserialize;
+use serde_with::serde_as;
+use std::time;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+pub enum RetentionAction {
+    /// Move blob data to the external storage, keeping the metadata in the database
+    Offload,
+    /// Remove blobs from the database
+    Delete,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionPolicySettings {
+    pub action: RetentionAction,
+    /// Blobs older than this number of days are subject to the policy
+    pub max_age_days: u64,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RetentionSettings {
+    pub celestia: Option<RetentionPolicySettings>,
+    pub eigenda: Option<RetentionPolicySettings>,
+    #[serde(default = "default_interval")]
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub interval: time::Duration,
+    #[serde(default = "default_batch_size")]
+    pub batch_size: u64,
+    /// EigenDA batches have no timestamps, so their age is measured in the L1 blocks
+    /// of the confirmation chain, assuming this number of blocks per day
+    #[serde(default = "default_eigenda_l1_blocks_per_day")]
+    pub eigenda_l1_blocks_per_day: u64,
+}
+
+impl RetentionSettings {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.interval.is_zero() {
+            anyhow::bail!("retention interval must be positive");
+        }
+        if self.batch_size == 0 {
+            anyhow::bail!("retention batch size must be positive");
+        }
+        if self.eigenda_l1_blocks_per_day == 0 {
+            anyhow::bail!("retention eigenda l1 blocks per day must be positive");
+        }
+        Ok(())
+    }
+}
+
+fn default_interval() -> time::Duration {
+    time::Duration::from_secs(3600)
+}
+
+fn default_batch_size() -> u64 {
+    100
+}
+
+/// Ethereum produces a block every 12 seconds
+fn default_eigenda_l1_blocks_per_day() -> u64 {
+    24 * 60 * 60 / 12
+}
@@ -0,0 +1,99 @@
+use crate::status::DEFAULT_NETWORK;
+use anyhow::Result;
+use s3::{creds::Credentials, Bucket, Region};
+use serde::Deserialize;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct S3StorageSettings {
+    pub access_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub region: Option<String>,
+    pub endpoint: Option<String>,
+    pub bucket: String,
+}
+
+/// External storage for blobs removed from the database.
+pub struct S3Storage {
+    bucket: Bucket,
+}
+
+impl S3Storage {
+    pub fn new(settings: &S3StorageSettings) -> Result<Self> {
+        let region = new_region(settings.region.clone(), settings.endpoint.clone())
+            .ok_or_else(|| anyhow::anyhow!("got invalid region/endpoint settings"))?;
+        let is_custom = matches!(region, Region::Custom { .. });
+        let bucket = Bucket::new(
+            &settings.bucket,
+            region,
+            Credentials::new(
+                settings.access_key.as_deref(),
+                settings.secret_key.as_deref(),
+                None,
+                None,
+                None,
+            )?,
+        )?;
+        // custom (e.g. minio) endpoints usually do not support virtual-hosted-style
+        let bucket = if is_custom {
+            bucket.with_path_style()
+        } else {
+            bucket
+        };
+        Ok(Self { bucket })
+    }
+
+    pub async fn put(&self, key: &str, data: &[u8]) -> Result<()> {
+        let response = self.bucket.put_object(key, data).await?;
+        if response.status_code() != 200 {
+            anyhow::bail!(
+                "failed to put object {key}: status code {}",
+                response.status_code()
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let response = self.bucket.get_object(key).await?;
+        if response.status_code() != 200 {
+            anyhow::bail!(
+                "failed to get object {key}: status code {}",
+                response.status_code()
+            );
+        }
+        Ok(response.bytes().to_vec())
+    }
+}
+
+/// Blobs of the additional networks are prefixed with the network name,
+/// as the same blob ids may appear in different networks
+pub fn celestia_blob_key(network: &str, id: &[u8]) -> String {
+    if network == DEFAULT_NETWORK {
+        format!("celestia/{}", hex::encode(id))
+    } else {
+        format!("celestia/{network}/{}", hex::encode(id))
+    }
+}
+
+pub fn eigenda_blob_key(id: &[u8]) -> String {
+    format!("eigenda/{}", hex::encode(id))
+}
+
+fn new_region(region: Option<String>, endpoint: Option<String>) -> Option<Region> {
+    let region = region.unwrap_or_default();
+    if let Some(endpoint) = endpoint {
+        return Some(Region::Custom { region, endpoint });
+    }
+
+    // try to match with AWS regions, fail otherwise
+    let region = Region::from_str(&region).ok()?;
+    match region {
+        Region::Custom {
+            region: _,
+            endpoint: _,
+        } => None,
+        region => Some(region),
+    }
+}
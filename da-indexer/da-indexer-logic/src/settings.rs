@@ -14,6 +14,15 @@ pub enum DASettings {
     EigenDA(EigendaSettings),
 }
 
+impl DASettings {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DASettings::Celestia(_) => "celestia",
+            DASettings::EigenDA(_) => "eigenda",
+        }
+    }
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
@@ -0,0 +1,122 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::metrics;
+
+pub const DEFAULT_NETWORK: &str = "default";
+
+/// Progress of the indexer, shared between the indexer and the API.
+///
+/// Heights are the heights of the DA layer blocks
+/// (L1 blocks for EigenDA).
+#[derive(Debug)]
+pub struct IndexerStatus {
+    da: &'static str,
+    network: String,
+    report_metrics: bool,
+    chain_height: AtomicU64,
+    indexed_height: AtomicU64,
+}
+
+impl IndexerStatus {
+    pub fn new(da: &'static str, network: impl Into<String>) -> Self {
+        Self {
+            da,
+            network: network.into(),
+            report_metrics: true,
+            chain_height: AtomicU64::new(0),
+            indexed_height: AtomicU64::new(0),
+        }
+    }
+
+    /// Status which is not reported in the indexer metrics,
+    /// so that it does not interfere with the live indexer of the same network
+    pub fn without_metrics(da: &'static str, network: impl Into<String>) -> Self {
+        Self {
+            report_metrics: false,
+            ..Self::new(da, network)
+        }
+    }
+
+    pub fn da(&self) -> &'static str {
+        self.da
+    }
+
+    pub fn network(&self) -> &str {
+        &self.network
+    }
+
+    pub fn chain_height(&self) -> u64 {
+        self.chain_height.load(Ordering::Acquire)
+    }
+
+    pub fn indexed_height(&self) -> u64 {
+        self.indexed_height.load(Ordering::Acquire)
+    }
+
+    /// Returns `None` if the chain height is not known yet
+    pub fn lag(&self) -> Option<u64> {
+        let chain_height = self.chain_height();
+        (chain_height > 0).then(|| chain_height.saturating_sub(self.indexed_height()))
+    }
+
+    /// Stores the latest observed chain height, even if it is lower than the previous one
+    /// (e.g., the node has been restarted or switched), so that the node falling behind is visible
+    pub fn update_chain_height(&self, height: u64) {
+        self.chain_height.store(height, Ordering::Release);
+        if !self.report_metrics {
+            return;
+        }
+        metrics::INDEXER_CHAIN_HEIGHT
+            .with_label_values(&[self.da, &self.network])
+            .set(self.chain_height() as i64);
+        self.update_lag_metric();
+    }
+
+    /// Jobs are processed concurrently and the gaps are caught up in the background,
+    /// so the indexed height is the highest processed one
+    pub fn update_indexed_height(&self, height: u64) {
+        self.indexed_height.fetch_max(height, Ordering::AcqRel);
+        if !self.report_metrics {
+            return;
+        }
+        metrics::INDEXER_INDEXED_HEIGHT
+            .with_label_values(&[self.da, &self.network])
+            .set(self.indexed_height() as i64);
+        self.update_lag_metric();
+    }
+
+    fn update_lag_metric(&self) {
+        if let Some(lag) = self.lag() {
+            metrics::INDEXER_LAG
+                .with_label_values(&[self.da, &self.network])
+                .set(lag as i64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lag_works() {
+        let status = IndexerStatus::new("test", DEFAULT_NETWORK);
+        assert_eq!(status.lag(), None);
+
+        status.update_indexed_height(10);
+        assert_eq!(status.lag(), None);
+
+        status.update_chain_height(15);
+        assert_eq!(status.lag(), Some(5));
+
+        // the latest chain height is reported, while the indexed height is the highest one
+        status.update_chain_height(12);
+        status.update_indexed_height(8);
+        assert_eq!(status.chain_height(), 12);
+        assert_eq!(status.indexed_height(), 10);
+        assert_eq!(status.lag(), Some(2));
+
+        status.update_indexed_height(20);
+        assert_eq!(status.lag(), Some(0));
+    }
+}
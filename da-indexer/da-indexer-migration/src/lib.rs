@@ -3,6 +3,11 @@ use sea_orm_migration::sea_orm::{Statement, TransactionTrait};
 
 mod m20220101_000001_create_table;
 mod m20240523_095338_eigenda_tables;
+mod m20241015_000000_celestia_blobs_namespace_index;
+mod m20241016_000000_blobs_retention;
+mod m20241017_000000_backfill_checkpoints;
+mod m20241018_000000_blob_search_indexes;
+mod m20241019_000000_blobs_size;
 
 pub struct Migrator;
 
@@ -12,6 +17,11 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20220101_000001_create_table::Migration),
             Box::new(m20240523_095338_eigenda_tables::Migration),
+            Box::new(m20241015_000000_celestia_blobs_namespace_index::Migration),
+            Box::new(m20241016_000000_blobs_retention::Migration),
+            Box::new(m20241017_000000_backfill_checkpoints::Migration),
+            Box::new(m20241018_000000_blob_search_indexes::Migration),
+            Box::new(m20241019_000000_blobs_size::Migration),
         ]
     }
 }
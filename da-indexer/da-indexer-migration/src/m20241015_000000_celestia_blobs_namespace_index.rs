@@ -0,0 +1,23 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE INDEX "celestia_blobs_namespace_height_id_index"
+                ON "celestia_blobs" ("namespace", "height" DESC, "id" DESC);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX "celestia_blobs_namespace_height_id_index";
+        "#;
+
+        crate::from_sql(manager, sql).await
+    }
+}
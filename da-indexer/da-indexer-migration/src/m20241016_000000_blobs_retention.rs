@@ -0,0 +1,37 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "celestia_blobs" ADD COLUMN "offloaded" boolean NOT NULL DEFAULT false;
+
+            ALTER TABLE "eigenda_blobs" ADD COLUMN "offloaded" boolean NOT NULL DEFAULT false;
+            ALTER TABLE "eigenda_blobs" ADD COLUMN "created_at" timestamp NOT NULL DEFAULT now();
+
+            CREATE INDEX "celestia_blobs_height_not_offloaded_index"
+                ON "celestia_blobs" ("height") WHERE NOT "offloaded";
+            CREATE INDEX "eigenda_blobs_created_at_not_offloaded_index"
+                ON "eigenda_blobs" ("created_at") WHERE NOT "offloaded";
+
+            COMMENT ON COLUMN "celestia_blobs"."offloaded" IS 'Blob data was moved to the external storage';
+            COMMENT ON COLUMN "eigenda_blobs"."offloaded" IS 'Blob data was moved to the external storage';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX "eigenda_blobs_created_at_not_offloaded_index";
+            DROP INDEX "celestia_blobs_height_not_offloaded_index";
+            ALTER TABLE "eigenda_blobs" DROP COLUMN "created_at";
+            ALTER TABLE "eigenda_blobs" DROP COLUMN "offloaded";
+            ALTER TABLE "celestia_blobs" DROP COLUMN "offloaded";
+        "#;
+
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,30 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "backfill_checkpoints" (
+                "id" text PRIMARY KEY,
+                "start_height" bigint NOT NULL,
+                "end_height" bigint NOT NULL,
+                "processed_height" bigint,
+                "updated_at" timestamp NOT NULL DEFAULT now()
+            );
+
+            COMMENT ON TABLE "backfill_checkpoints" IS 'Table contains progress of the backfill runs';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "backfill_checkpoints";
+        "#;
+
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE INDEX "celestia_blobs_commitment_index"
+                ON "celestia_blobs" ("commitment");
+            CREATE INDEX "eigenda_batches_l1_tx_hash_index"
+                ON "eigenda_batches" ("l1_tx_hash");
+            CREATE INDEX "eigenda_blobs_batch_header_hash_blob_index_index"
+                ON "eigenda_blobs" ("batch_header_hash", "blob_index");
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX "eigenda_blobs_batch_header_hash_blob_index_index";
+            DROP INDEX "eigenda_batches_l1_tx_hash_index";
+            DROP INDEX "celestia_blobs_commitment_index";
+        "#;
+
+        crate::from_sql(manager, sql).await
+    }
+}
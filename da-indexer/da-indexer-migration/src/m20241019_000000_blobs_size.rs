@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Blobs offloaded before this migration have no data to compute the size from,
+        // so their size stays unknown (zero).
+        let sql = r#"
+            ALTER TABLE "celestia_blobs" ADD COLUMN "size" bigint NOT NULL DEFAULT 0;
+            ALTER TABLE "eigenda_blobs" ADD COLUMN "size" bigint NOT NULL DEFAULT 0;
+
+            UPDATE "celestia_blobs" SET "size" = octet_length("data") WHERE NOT "offloaded";
+            UPDATE "eigenda_blobs" SET "size" = octet_length("data") WHERE NOT "offloaded";
+
+            DROP INDEX "eigenda_blobs_created_at_not_offloaded_index";
+            CREATE INDEX "eigenda_batches_l1_block_index" ON "eigenda_batches" ("l1_block");
+
+            COMMENT ON COLUMN "celestia_blobs"."size" IS 'Size of the blob data in bytes, kept after the data is offloaded';
+            COMMENT ON COLUMN "eigenda_blobs"."size" IS 'Size of the blob data in bytes, kept after the data is offloaded';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX "eigenda_batches_l1_block_index";
+            CREATE INDEX "eigenda_blobs_created_at_not_offloaded_index"
+                ON "eigenda_blobs" ("created_at") WHERE NOT "offloaded";
+            ALTER TABLE "eigenda_blobs" DROP COLUMN "size";
+            ALTER TABLE "celestia_blobs" DROP COLUMN "size";
+        "#;
+
+        crate::from_sql(manager, sql).await
+    }
+}
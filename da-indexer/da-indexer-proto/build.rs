@@ -26,6 +26,22 @@ fn compile(
             ".blockscout.daIndexer.v1.CelestiaL2BatchMetadata",
             "#[serde_with::skip_serializing_none]"
         )
+        .message_attribute(
+            ".blockscout.daIndexer.v1.CelestiaDecodedBlob",
+            "#[serde_with::skip_serializing_none]"
+        )
+        .message_attribute(
+            ".blockscout.daIndexer.v1.OpStackFrame",
+            "#[serde_with::skip_serializing_none]"
+        )
+        .message_attribute(
+            ".blockscout.daIndexer.v1.ArbitrumBatch",
+            "#[serde_with::skip_serializing_none]"
+        )
+        .message_attribute(
+            ".blockscout.daIndexer.v1.CelestiaNamespaceStats",
+            "#[serde_with::skip_serializing_none]"
+        )
         ;
     config.compile_protos(protos, includes)?;
     Ok(())
@@ -43,7 +59,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     ]));
     compile(
         &["proto/v1/da-indexer.proto", "proto/v1/health.proto"],
-        &["proto"],
+        &["proto", "../../proto"],
         gens,
     )?;
     Ok(())
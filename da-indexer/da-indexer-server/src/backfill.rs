@@ -0,0 +1,11 @@
+use da_indexer_logic::backfill::{settings::BackfillSettings, Backfiller};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+pub async fn run(
+    settings: BackfillSettings,
+    db_connection: DatabaseConnection,
+) -> Result<(), anyhow::Error> {
+    let backfiller = Backfiller::new(Arc::new(db_connection), settings).await?;
+    backfiller.run().await
+}
@@ -1,4 +1,4 @@
-use da_indexer_logic::{indexer::Indexer, settings::IndexerSettings};
+use da_indexer_logic::{indexer::Indexer, settings::IndexerSettings, status::IndexerStatus};
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
 use tokio::time::sleep;
@@ -6,12 +6,14 @@ use tokio::time::sleep;
 pub async fn run(
     settings: IndexerSettings,
     db_connection: DatabaseConnection,
+    status: Arc<IndexerStatus>,
 ) -> Result<(), anyhow::Error> {
     let db_connection = Arc::new(db_connection);
 
     // If the first connect fails, the function will return an error immediately.
     // All subsequent reconnects are done inside tokio task and will not propagate to above.
-    let mut indexer = Indexer::new(db_connection.clone(), settings.clone()).await?;
+    let mut indexer =
+        Indexer::new(db_connection.clone(), settings.clone(), status.clone()).await?;
     let delay = settings.restart_delay;
 
     tokio::spawn(async move {
@@ -30,7 +32,7 @@ pub async fn run(
 
                 tracing::info!("re-connecting to rpc");
 
-                match Indexer::new(db_connection.clone(), settings.clone()).await {
+                match Indexer::new(db_connection.clone(), settings.clone(), status.clone()).await {
                     Ok(new_indexer) => {
                         indexer = new_indexer;
                         break;
@@ -1,9 +1,13 @@
+mod backfill;
 mod indexer;
 mod proto;
+mod retention;
 mod server;
 mod services;
 mod settings;
 
+pub use backfill::run as run_backfill;
 pub use indexer::run as run_indexer;
+pub use retention::run as run_retention;
 pub use server::run as run_server;
 pub use settings::Settings;
@@ -1,9 +1,60 @@
-use blockscout_service_launcher::{database, launcher::ConfigSettings};
-use da_indexer_logic::celestia::l2_router::L2Router;
-use da_indexer_server::{run_indexer, run_server, Settings};
+use anyhow::Context;
+use blockscout_service_launcher::{
+    database::{self, DatabaseSettings},
+    launcher::ConfigSettings,
+};
+use da_indexer_logic::{
+    celestia::l2_router::L2Router,
+    s3_storage::S3Storage,
+    status::{IndexerStatus, DEFAULT_NETWORK},
+};
+use da_indexer_server::{run_backfill, run_indexer, run_retention, run_server, Settings};
 use migration::Migrator;
+use sea_orm::DatabaseConnection;
+use std::{collections::BTreeMap, sync::Arc};
 
 const SERVICE_NAME: &str = "da_indexer";
+const BACKFILL_COMMAND: &str = "backfill";
+
+/// Connections to the default database and to the databases of the additional networks
+type DatabaseConnections = (
+    Option<DatabaseConnection>,
+    BTreeMap<String, DatabaseConnection>,
+);
+
+/// Connects to the database, creating and migrating it as configured if `setup` is set
+async fn connect_database(
+    database_settings: &DatabaseSettings,
+    setup: bool,
+) -> Result<DatabaseConnection, anyhow::Error> {
+    let database_url = &database_settings.connect.url();
+    let mut connect_options = sea_orm::ConnectOptions::new(database_url);
+    connect_options.sqlx_logging_level(tracing::log::LevelFilter::Debug);
+    database::initialize_postgres::<Migrator>(
+        connect_options,
+        setup && database_settings.create_database,
+        setup && database_settings.run_migrations,
+    )
+    .await
+}
+
+async fn connect_databases(
+    settings: &Settings,
+    setup: bool,
+) -> Result<DatabaseConnections, anyhow::Error> {
+    let db_connection = match settings.database.as_ref() {
+        Some(database_settings) => Some(connect_database(database_settings, setup).await?),
+        None => None,
+    };
+    let mut networks_db_connections = BTreeMap::new();
+    for (name, network) in &settings.celestia_networks {
+        networks_db_connections.insert(
+            name.clone(),
+            connect_database(&network.database, setup).await?,
+        );
+    }
+    Ok((db_connection, networks_db_connections))
+}
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
@@ -15,44 +66,90 @@ async fn main() -> Result<(), anyhow::Error> {
         &settings.jaeger,
     )?;
 
-    let db_connection = match settings.database.clone() {
-        Some(database_settings) => {
-            let database_url = &database_settings.connect.url();
-            let mut connect_options = sea_orm::ConnectOptions::new(database_url);
-            connect_options.sqlx_logging_level(tracing::log::LevelFilter::Debug);
-            Some(
-                database::initialize_postgres::<Migrator>(
-                    connect_options,
-                    database_settings.create_database,
-                    database_settings.run_migrations,
-                )
-                .await?,
-            )
+    // The backfill is run as a separate command, which exits once the range is indexed
+    if std::env::args().nth(1).as_deref() == Some(BACKFILL_COMMAND) {
+        let backfill_settings = settings
+            .backfill
+            .clone()
+            .context("backfill settings are required for the backfill command")?;
+        let database_settings = settings
+            .database
+            .as_ref()
+            .context("database is required for the backfill")?;
+        let db_connection = connect_database(database_settings, true).await?;
+        return run_backfill(backfill_settings, db_connection).await;
+    }
+
+    let (db_connection, networks_db_connections) = connect_databases(&settings, true).await?;
+
+    let mut l2_routers = BTreeMap::new();
+    if let Some(settings) = settings.l2_router.clone() {
+        l2_routers.insert(
+            DEFAULT_NETWORK.to_string(),
+            L2Router::from_settings(settings)?,
+        );
+    }
+    for (name, network) in &settings.celestia_networks {
+        if let Some(settings) = network.l2_router.clone() {
+            l2_routers.insert(name.clone(), L2Router::from_settings(settings)?);
         }
+    }
+
+    let s3_storage = match settings.s3_storage.as_ref() {
+        Some(s3_settings) => Some(Arc::new(S3Storage::new(s3_settings)?)),
         None => None,
     };
 
-    let mut l2_router = None;
-    if let Some(settings) = settings.l2_router.clone() {
-        l2_router = Some(L2Router::from_settings(settings)?);
+    if let Some(retention_settings) = settings.retention.clone() {
+        let db_connection = db_connection
+            .clone()
+            .context("database is required for the retention")?;
+        run_retention(
+            retention_settings.clone(),
+            db_connection,
+            s3_storage.clone(),
+            DEFAULT_NETWORK,
+        )
+        .await?;
+        for (name, db_connection) in &networks_db_connections {
+            run_retention(
+                retention_settings.clone(),
+                db_connection.clone(),
+                s3_storage.clone(),
+                name,
+            )
+            .await?;
+        }
     }
 
+    let mut indexer_statuses = vec![];
     if let Some(indexer_settings) = settings.indexer.clone() {
-        let db_connection = db_connection.expect("database is required for the indexer");
-        run_indexer(indexer_settings, db_connection).await?;
+        let db_connection = db_connection.context("database is required for the indexer")?;
+        let status = Arc::new(IndexerStatus::new(
+            indexer_settings.da.name(),
+            DEFAULT_NETWORK,
+        ));
+        run_indexer(indexer_settings, db_connection, status.clone()).await?;
+        indexer_statuses.push(status);
+    }
+    for (name, network) in &settings.celestia_networks {
+        if let Some(indexer_settings) = network.indexer.clone() {
+            let db_connection = networks_db_connections[name].clone();
+            let status = Arc::new(IndexerStatus::new(indexer_settings.da.name(), name));
+            run_indexer(indexer_settings, db_connection, status.clone()).await?;
+            indexer_statuses.push(status);
+        }
     }
 
-    let db_connection = match settings.database.clone() {
-        Some(database_settings) => Some(
-            database::initialize_postgres::<Migrator>(
-                &database_settings.connect.url(),
-                false,
-                false,
-            )
-            .await?,
-        ),
-        None => None,
-    };
+    let (db_connection, networks_db_connections) = connect_databases(&settings, false).await?;
 
-    run_server(settings, db_connection, l2_router).await
+    run_server(
+        settings,
+        db_connection,
+        networks_db_connections,
+        l2_routers,
+        s3_storage,
+        indexer_statuses,
+    )
+    .await
 }
@@ -0,0 +1,17 @@
+use da_indexer_logic::{
+    retention::{settings::RetentionSettings, RetentionWorker},
+    s3_storage::S3Storage,
+};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+
+pub async fn run(
+    settings: RetentionSettings,
+    db_connection: DatabaseConnection,
+    storage: Option<Arc<S3Storage>>,
+    network: &str,
+) -> Result<(), anyhow::Error> {
+    let worker = RetentionWorker::new(Arc::new(db_connection), storage, settings, network)?;
+    tokio::spawn(worker.run());
+    Ok(())
+}
@@ -1,18 +1,22 @@
 use crate::{
     proto::{health_actix::route_health, health_server::HealthServer},
-    services::{CelestiaService, EigenDaService, HealthService},
+    services::{CelestiaNetwork, CelestiaService, EigenDaService, HealthService},
     settings::Settings,
 };
 use blockscout_service_launcher::{launcher, launcher::LaunchSettings};
 
-use da_indexer_logic::celestia::l2_router::L2Router;
+use da_indexer_logic::{
+    celestia::l2_router::L2Router,
+    s3_storage::S3Storage,
+    status::{IndexerStatus, DEFAULT_NETWORK},
+};
 use da_indexer_proto::blockscout::da_indexer::v1::{
     celestia_service_actix::route_celestia_service, celestia_service_server::CelestiaServiceServer,
     eigen_da_service_actix::route_eigen_da_service, eigen_da_service_server::EigenDaServiceServer,
 };
 use sea_orm::DatabaseConnection;
 
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 const SERVICE_NAME: &str = "da_indexer";
 
@@ -43,11 +47,37 @@ impl launcher::HttpRouter for Router {
 pub async fn run(
     settings: Settings,
     database_connection: Option<DatabaseConnection>,
-    l2_router: Option<L2Router>,
+    networks_database_connections: BTreeMap<String, DatabaseConnection>,
+    mut l2_routers: BTreeMap<String, L2Router>,
+    s3_storage: Option<Arc<S3Storage>>,
+    indexer_statuses: Vec<Arc<IndexerStatus>>,
 ) -> Result<(), anyhow::Error> {
-    let health = Arc::new(HealthService::default());
-    let celestia = Arc::new(CelestiaService::new(database_connection.clone(), l2_router));
-    let eigenda = Arc::new(EigenDaService::new(database_connection.clone()));
+    let health = Arc::new(HealthService::new(
+        indexer_statuses,
+        settings.health.max_indexing_lag,
+    ));
+    let mut celestia_networks = BTreeMap::from([(
+        DEFAULT_NETWORK.to_string(),
+        CelestiaNetwork {
+            db: database_connection.clone(),
+            l2_router: l2_routers.remove(DEFAULT_NETWORK),
+        },
+    )]);
+    for (name, db) in networks_database_connections {
+        let l2_router = l2_routers.remove(&name);
+        celestia_networks.insert(
+            name,
+            CelestiaNetwork {
+                db: Some(db),
+                l2_router,
+            },
+        );
+    }
+    let celestia = Arc::new(CelestiaService::new(celestia_networks, s3_storage.clone()));
+    let eigenda = Arc::new(EigenDaService::new(
+        database_connection.clone(),
+        s3_storage,
+    ));
 
     let router = Router {
         health,
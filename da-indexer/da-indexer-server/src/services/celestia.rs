@@ -1,71 +1,238 @@
 use crate::proto::celestia_service_server::CelestiaService as Celestia;
 use base64::prelude::*;
-use da_indexer_logic::celestia::{l2_router::L2Router, repository::blobs};
+use da_indexer_logic::celestia::{
+    batch_decoder::{self, DecodedBlob},
+    l2_router::{types::L2Type, L2Router},
+    repository::blobs,
+};
+use da_indexer_logic::{
+    s3_storage::{self, S3Storage},
+    status::DEFAULT_NETWORK,
+};
 use da_indexer_proto::blockscout::da_indexer::v1::{
-    CelestiaBlob, CelestiaBlobId, CelestiaL2BatchMetadata, GetCelestiaBlobRequest,
+    ArbitrumBatch, CelestiaBlob, CelestiaBlobId, CelestiaDecodedBlob, CelestiaL2BatchMetadata,
+    CelestiaNamespaceStats, GetCelestiaBlobRequest, GetCelestiaDecodedBlobRequest,
+    GetCelestiaL2BatchMetadataRequest, GetCelestiaNamespaceStatsRequest,
+    ListCelestiaNamespaceBlobsRequest, ListCelestiaNamespaceBlobsResponse, OpStackBatcherData,
+    OpStackFrame, Pagination, SearchCelestiaBlobsRequest, SearchCelestiaBlobsResponse,
 };
 use sea_orm::DatabaseConnection;
+use std::{collections::BTreeMap, sync::Arc};
 use tonic::{Request, Response, Status};
 
-use super::bytes_from_hex_or_base64;
+use super::{blob_data, bytes_from_hex_or_base64};
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Database and l2 router of a Celestia network
+#[derive(Default)]
+pub struct CelestiaNetwork {
+    pub db: Option<DatabaseConnection>,
+    pub l2_router: Option<L2Router>,
+}
+
+impl CelestiaNetwork {
+    fn db(&self) -> Result<&DatabaseConnection, Status> {
+        self.db
+            .as_ref()
+            .ok_or(Status::unimplemented("database is not configured"))
+    }
+
+    fn l2_router(&self) -> Result<&L2Router, Status> {
+        self.l2_router
+            .as_ref()
+            .ok_or(Status::unimplemented("l2 router is not configured"))
+    }
+}
 
 #[derive(Default)]
 pub struct CelestiaService {
-    db: Option<DatabaseConnection>,
-    l2_router: Option<L2Router>,
+    /// Networks by their names, the default network is stored under [`DEFAULT_NETWORK`]
+    networks: BTreeMap<String, CelestiaNetwork>,
+    s3_storage: Option<Arc<S3Storage>>,
 }
 
 impl CelestiaService {
-    pub fn new(db: Option<DatabaseConnection>, l2_router: Option<L2Router>) -> Self {
-        Self { db, l2_router }
+    pub fn new(
+        networks: BTreeMap<String, CelestiaNetwork>,
+        s3_storage: Option<Arc<S3Storage>>,
+    ) -> Self {
+        Self {
+            networks,
+            s3_storage,
+        }
+    }
+
+    async fn load_offloaded_data(
+        &self,
+        network: &str,
+        blob: &mut blobs::Blob,
+    ) -> Result<(), Status> {
+        let id = blob.id.clone();
+        blob.data = blob_data(
+            self.s3_storage.as_deref(),
+            blob.offloaded,
+            std::mem::take(&mut blob.data),
+            || s3_storage::celestia_blob_key(network, &id),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Network from the request path; the default one if not specified
+    fn network<'a>(&'a self, name: &'a str) -> Result<(&'a str, &'a CelestiaNetwork), Status> {
+        let name = if name.is_empty() {
+            DEFAULT_NETWORK
+        } else {
+            name
+        };
+        self.networks
+            .get(name)
+            .map(|network| (name, network))
+            .ok_or_else(|| Status::not_found(format!("unknown network: {name}")))
+    }
+}
+
+fn decoded_blob_to_proto(
+    decoded: Option<DecodedBlob>,
+) -> (
+    Option<String>,
+    Option<OpStackBatcherData>,
+    Option<ArbitrumBatch>,
+) {
+    match decoded {
+        Some(DecodedBlob::Optimism(data)) => (
+            Some(L2Type::Optimism.as_str().to_string()),
+            Some(OpStackBatcherData {
+                derivation_version: data.derivation_version as u32,
+                frames: data
+                    .frames
+                    .into_iter()
+                    .map(|frame| OpStackFrame {
+                        channel_id: hex::encode(frame.channel_id),
+                        frame_number: frame.frame_number as u32,
+                        data_length: frame.data_length,
+                        is_last: frame.is_last,
+                        compression: frame.compression.map(|c| c.as_str().to_string()),
+                    })
+                    .collect(),
+            }),
+            None,
+        ),
+        Some(DecodedBlob::Arbitrum(batch)) => (
+            Some(L2Type::Arbitrum.as_str().to_string()),
+            None,
+            Some(ArbitrumBatch {
+                header_byte: batch.header_byte as u32,
+                header_kind: batch.kind.as_str().to_string(),
+                compression: batch.compression.map(|c| c.as_str().to_string()),
+                payload_size: batch.payload_size,
+            }),
+        ),
+        None => (None, None, None),
     }
 }
 
+fn blob_to_proto(blob: blobs::Blob, skip_data: bool) -> CelestiaBlob {
+    CelestiaBlob {
+        height: blob.height as u64,
+        namespace: hex::encode(&blob.namespace),
+        commitment: BASE64_STANDARD.encode(&blob.commitment),
+        timestamp: blob.timestamp as u64,
+        size: blob.size as u64,
+        data: (!skip_data).then(|| BASE64_STANDARD.encode(&blob.data)),
+    }
+}
+
+fn parse_page_token(page_token: &str) -> Result<(u64, Vec<u8>), Status> {
+    let invalid = || Status::invalid_argument("invalid page_token format");
+    let (height, id) = page_token.split_once(',').ok_or_else(invalid)?;
+    let height = height.parse::<u64>().map_err(|_| invalid())?;
+    let id = hex::decode(id).map_err(|_| invalid())?;
+    Ok((height, id))
+}
+
 #[async_trait::async_trait]
 impl Celestia for CelestiaService {
     async fn get_blob(
         &self,
         request: Request<GetCelestiaBlobRequest>,
     ) -> Result<Response<CelestiaBlob>, Status> {
-        let db = self
-            .db
-            .as_ref()
-            .ok_or(Status::unimplemented("database is not configured"))?;
         let inner = request.into_inner();
+        let (network_name, network) = self.network(&inner.network)?;
+        let db = network.db()?;
 
         let height = inner.height;
         let commitment = bytes_from_hex_or_base64(&inner.commitment, "commitment")?;
 
-        let blob = blobs::find_by_height_and_commitment(db, height, &commitment)
+        let mut blob = blobs::find_by_height_and_commitment(db, height, &commitment)
             .await
             .map_err(|err| {
                 tracing::error!(error = ?err, "failed to query blob");
                 Status::internal("failed to query blob")
             })?
             .ok_or(Status::not_found("blob not found"))?;
+        let skip_data = inner.skip_data.unwrap_or_default();
+        if !skip_data {
+            self.load_offloaded_data(network_name, &mut blob).await?;
+        }
 
-        let data =
-            (!inner.skip_data.unwrap_or_default()).then_some(BASE64_STANDARD.encode(&blob.data));
+        let data = (!skip_data).then_some(BASE64_STANDARD.encode(&blob.data));
 
         Ok(Response::new(CelestiaBlob {
             height: blob.height as u64,
             namespace: hex::encode(blob.namespace),
             commitment: inner.commitment,
             timestamp: blob.timestamp as u64,
-            size: blob.data.len() as u64,
+            size: blob.size as u64,
             data,
         }))
     }
 
-    async fn get_l2_batch_metadata(
+    async fn get_decoded_blob(
         &self,
-        request: Request<CelestiaBlobId>,
-    ) -> Result<Response<CelestiaL2BatchMetadata>, Status> {
-        let l2_router = self
+        request: Request<GetCelestiaDecodedBlobRequest>,
+    ) -> Result<Response<CelestiaDecodedBlob>, Status> {
+        let inner = request.into_inner();
+        let (network_name, network) = self.network(&inner.network)?;
+        let db = network.db()?;
+
+        let height = inner.height;
+        let commitment = bytes_from_hex_or_base64(&inner.commitment, "commitment")?;
+
+        let mut blob = blobs::find_by_height_and_commitment(db, height, &commitment)
+            .await
+            .map_err(|err| {
+                tracing::error!(error = ?err, "failed to query blob");
+                Status::internal("failed to query blob")
+            })?
+            .ok_or(Status::not_found("blob not found"))?;
+        self.load_offloaded_data(network_name, &mut blob).await?;
+
+        let chain_type = network
             .l2_router
             .as_ref()
-            .ok_or(Status::unimplemented("l2 router is not configured"))?;
+            .and_then(|router| router.chain_type(&blob.namespace));
+        let (l2_chain_type, op_stack, arbitrum) =
+            decoded_blob_to_proto(batch_decoder::decode(&blob.data, chain_type));
+
+        Ok(Response::new(CelestiaDecodedBlob {
+            height: blob.height as u64,
+            namespace: hex::encode(blob.namespace),
+            commitment: inner.commitment,
+            l2_chain_type,
+            op_stack,
+            arbitrum,
+        }))
+    }
+
+    async fn get_l2_batch_metadata(
+        &self,
+        request: Request<GetCelestiaL2BatchMetadataRequest>,
+    ) -> Result<Response<CelestiaL2BatchMetadata>, Status> {
         let inner = request.into_inner();
+        let l2_router = self.network(&inner.network)?.1.l2_router()?;
 
         let height = inner.height;
         let commitment = bytes_from_hex_or_base64(&inner.commitment, "commitment")?;
@@ -103,4 +270,147 @@ impl Celestia for CelestiaService {
             related_blobs,
         }))
     }
+
+    async fn list_namespace_blobs(
+        &self,
+        request: Request<ListCelestiaNamespaceBlobsRequest>,
+    ) -> Result<Response<ListCelestiaNamespaceBlobsResponse>, Status> {
+        let inner = request.into_inner();
+        let (network_name, network) = self.network(&inner.network)?;
+        let db = network.db()?;
+
+        let namespace = bytes_from_hex_or_base64(&inner.namespace, "namespace")?;
+        let page_size = inner
+            .page_size
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE);
+        let page_token = inner
+            .page_token
+            .as_deref()
+            .map(parse_page_token)
+            .transpose()?;
+        let skip_data = inner.skip_data.unwrap_or_default();
+
+        let (mut blobs, next_page_token) =
+            blobs::find_by_namespace(db, &namespace, page_token, page_size as u64)
+                .await
+                .map_err(|err| {
+                    tracing::error!(error = ?err, "failed to query blobs");
+                    Status::internal("failed to query blobs")
+                })?;
+        if !skip_data {
+            for blob in blobs.iter_mut() {
+                self.load_offloaded_data(network_name, blob).await?;
+            }
+        }
+
+        let items = blobs
+            .into_iter()
+            .map(|blob| blob_to_proto(blob, skip_data))
+            .collect();
+
+        Ok(Response::new(ListCelestiaNamespaceBlobsResponse {
+            items,
+            next_page_params: next_page_token.map(|(height, id)| Pagination {
+                page_token: format!("{},{}", height, hex::encode(id)),
+                page_size,
+            }),
+        }))
+    }
+
+    async fn get_namespace_stats(
+        &self,
+        request: Request<GetCelestiaNamespaceStatsRequest>,
+    ) -> Result<Response<CelestiaNamespaceStats>, Status> {
+        let inner = request.into_inner();
+        let (_, network) = self.network(&inner.network)?;
+        let db = network.db()?;
+
+        let namespace = bytes_from_hex_or_base64(&inner.namespace, "namespace")?;
+        let stats = blobs::namespace_stats(db, &namespace)
+            .await
+            .map_err(|err| {
+                tracing::error!(error = ?err, "failed to query namespace stats");
+                Status::internal("failed to query namespace stats")
+            })?;
+
+        Ok(Response::new(CelestiaNamespaceStats {
+            namespace: hex::encode(&namespace),
+            blobs_count: stats.blobs_count as u64,
+            total_size: stats.total_size as u64,
+            first_height: stats.first_height.map(|h| h as u64),
+            last_height: stats.last_height.map(|h| h as u64),
+        }))
+    }
+
+    async fn search_blobs(
+        &self,
+        request: Request<SearchCelestiaBlobsRequest>,
+    ) -> Result<Response<SearchCelestiaBlobsResponse>, Status> {
+        let inner = request.into_inner();
+        let (_, network) = self.network(&inner.network)?;
+        let db = network.db()?;
+        let limit = inner
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE) as u64;
+
+        let blobs = match (
+            inner.commitment_prefix,
+            inner.l2_chain_id,
+            inner.l2_batch_number,
+        ) {
+            (Some(prefix), None, None) => {
+                if prefix.trim_start_matches("0x").is_empty() {
+                    return Err(Status::invalid_argument("commitment prefix is empty"));
+                }
+                let (from, to) = blobs::commitment_prefix_range(&prefix)
+                    .map_err(|_| Status::invalid_argument("invalid commitment prefix"))?;
+                blobs::find_by_commitment_range(db, from, to, limit)
+                    .await
+                    .map_err(|err| {
+                        tracing::error!(error = ?err, "failed to query blobs");
+                        Status::internal("failed to query blobs")
+                    })?
+            }
+            (None, Some(l2_chain_id), Some(l2_batch_number)) => {
+                let l2_router = network.l2_router()?;
+                let blob_ids = l2_router
+                    .get_l2_batch_blobs(l2_chain_id, l2_batch_number)
+                    .await
+                    .map_err(|err| {
+                        tracing::error!(l2_chain_id, l2_batch_number, error = ?err, "failed to query l2 batch");
+                        Status::internal("failed to query l2 batch")
+                    })?
+                    .ok_or(Status::not_found("l2 batch not found"))?;
+
+                let mut blobs = vec![];
+                for blob_id in blob_ids.into_iter().take(limit as usize) {
+                    let commitment = bytes_from_hex_or_base64(&blob_id.commitment, "commitment")?;
+                    let blob =
+                        blobs::find_by_height_and_commitment(db, blob_id.height, &commitment)
+                            .await
+                            .map_err(|err| {
+                                tracing::error!(error = ?err, "failed to query blob");
+                                Status::internal("failed to query blob")
+                            })?;
+                    // blobs that are not indexed yet are skipped
+                    blobs.extend(blob);
+                }
+                blobs
+            }
+            _ => {
+                return Err(Status::invalid_argument(
+                    "either commitment_prefix or both l2_chain_id and l2_batch_number must be set",
+                ))
+            }
+        };
+
+        Ok(Response::new(SearchCelestiaBlobsResponse {
+            items: blobs
+                .into_iter()
+                .map(|blob| blob_to_proto(blob, true))
+                .collect(),
+        }))
+    }
 }
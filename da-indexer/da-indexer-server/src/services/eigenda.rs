@@ -1,20 +1,30 @@
 use crate::proto::eigen_da_service_server::EigenDaService as EigenDa;
 use base64::prelude::*;
-use da_indexer_logic::eigenda::repository::blobs;
-use da_indexer_proto::blockscout::da_indexer::v1::{EigenDaBlob, GetEigenDaBlobRequest};
+use da_indexer_logic::{
+    eigenda::repository::blobs,
+    s3_storage::{self, S3Storage},
+};
+use da_indexer_proto::blockscout::da_indexer::v1::{
+    EigenDaBlob, GetEigenDaBlobRequest, SearchEigenDaBlobsRequest, SearchEigenDaBlobsResponse,
+};
 use sea_orm::DatabaseConnection;
+use std::sync::Arc;
 use tonic::{Request, Response, Status};
 
-use super::bytes_from_hex_or_base64;
+use super::{blob_data, bytes_from_hex_or_base64};
+
+const DEFAULT_SEARCH_LIMIT: u32 = 50;
+const MAX_SEARCH_LIMIT: u32 = 100;
 
 #[derive(Default)]
 pub struct EigenDaService {
     db: Option<DatabaseConnection>,
+    s3_storage: Option<Arc<S3Storage>>,
 }
 
 impl EigenDaService {
-    pub fn new(db: Option<DatabaseConnection>) -> Self {
-        Self { db }
+    pub fn new(db: Option<DatabaseConnection>, s3_storage: Option<Arc<S3Storage>>) -> Self {
+        Self { db, s3_storage }
     }
 }
 
@@ -41,9 +51,18 @@ impl EigenDa for EigenDaService {
                 Status::internal("failed to query blob")
             })?
             .ok_or(Status::not_found("blob not found"))?;
-
-        let data =
-            (!inner.skip_data.unwrap_or_default()).then_some(BASE64_STANDARD.encode(&blob.data));
+        let data = if inner.skip_data.unwrap_or_default() {
+            None
+        } else {
+            let blob_bytes = blob_data(
+                self.s3_storage.as_deref(),
+                blob.offloaded,
+                blob.data,
+                || s3_storage::eigenda_blob_key(&blob.id),
+            )
+            .await?;
+            Some(BASE64_STANDARD.encode(&blob_bytes))
+        };
 
         Ok(Response::new(EigenDaBlob {
             batch_header_hash: inner.batch_header_hash,
@@ -51,8 +70,46 @@ impl EigenDa for EigenDaService {
             blob_index: blob.blob_index as u32,
             l1_confirmation_block: blob.l1_block as u64,
             l1_confirmation_tx_hash: format!("0x{}", hex::encode(blob.l1_tx_hash)),
-            size: blob.data.len() as u64,
+            size: blob.size as u64,
             data,
         }))
     }
+    async fn search_blobs(
+        &self,
+        request: Request<SearchEigenDaBlobsRequest>,
+    ) -> Result<Response<SearchEigenDaBlobsResponse>, Status> {
+        let db = self
+            .db
+            .as_ref()
+            .ok_or(Status::unimplemented("database is not configured"))?;
+        let inner = request.into_inner();
+
+        let l1_tx_hash = bytes_from_hex_or_base64(&inner.l1_tx_hash, "l1 tx hash")?;
+        let limit = inner
+            .limit
+            .unwrap_or(DEFAULT_SEARCH_LIMIT)
+            .clamp(1, MAX_SEARCH_LIMIT);
+
+        let blobs = blobs::find_by_l1_tx_hash(db, &l1_tx_hash, limit as u64)
+            .await
+            .map_err(|err| {
+                tracing::error!(error = ?err, "failed to query blobs");
+                Status::internal("failed to query blobs")
+            })?;
+
+        let items = blobs
+            .into_iter()
+            .map(|blob| EigenDaBlob {
+                batch_header_hash: format!("0x{}", hex::encode(blob.batch_header_hash)),
+                batch_id: blob.batch_id as u64,
+                blob_index: blob.blob_index as u32,
+                l1_confirmation_block: blob.l1_block as u64,
+                l1_confirmation_tx_hash: format!("0x{}", hex::encode(blob.l1_tx_hash)),
+                size: blob.size as u64,
+                data: None,
+            })
+            .collect();
+
+        Ok(Response::new(SearchEigenDaBlobsResponse { items }))
+    }
 }
@@ -1,9 +1,54 @@
+use da_indexer_logic::status::IndexerStatus;
+use std::sync::Arc;
+
 use crate::proto::{
     health_check_response, health_server::Health, HealthCheckRequest, HealthCheckResponse,
 };
 
 #[derive(Default)]
-pub struct HealthService {}
+pub struct HealthService {
+    indexer_statuses: Vec<Arc<IndexerStatus>>,
+    max_indexing_lag: Option<u64>,
+}
+
+impl HealthService {
+    pub fn new(indexer_statuses: Vec<Arc<IndexerStatus>>, max_indexing_lag: Option<u64>) -> Self {
+        Self {
+            indexer_statuses,
+            max_indexing_lag,
+        }
+    }
+
+    /// Not serving if any of the indexers lags behind more than allowed
+    fn serving_status(&self) -> health_check_response::ServingStatus {
+        let Some(max_lag) = self.max_indexing_lag else {
+            return health_check_response::ServingStatus::Serving;
+        };
+        let mut serving = true;
+        for status in &self.indexer_statuses {
+            match status.lag() {
+                Some(lag) if lag > max_lag => {
+                    tracing::warn!(
+                        da = status.da(),
+                        network = status.network(),
+                        lag,
+                        max_lag,
+                        chain_height = status.chain_height(),
+                        indexed_height = status.indexed_height(),
+                        "indexing lag exceeds the threshold"
+                    );
+                    serving = false;
+                }
+                _ => {}
+            }
+        }
+        if serving {
+            health_check_response::ServingStatus::Serving
+        } else {
+            health_check_response::ServingStatus::NotServing
+        }
+    }
+}
 
 #[async_trait::async_trait]
 impl Health for HealthService {
@@ -12,7 +57,46 @@ impl Health for HealthService {
         _request: tonic::Request<HealthCheckRequest>,
     ) -> Result<tonic::Response<HealthCheckResponse>, tonic::Status> {
         Ok(tonic::Response::new(HealthCheckResponse {
-            status: health_check_response::ServingStatus::Serving.into(),
+            status: self.serving_status().into(),
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serving_status_depends_on_lag() {
+        let status = Arc::new(IndexerStatus::new("celestia", "mainnet"));
+        let other_status = Arc::new(IndexerStatus::new("celestia", "mocha"));
+        other_status.update_chain_height(10);
+        other_status.update_indexed_height(10);
+        let service = HealthService::new(vec![status.clone(), other_status], Some(10));
+        // chain height is unknown yet
+        assert_eq!(
+            service.serving_status(),
+            health_check_response::ServingStatus::Serving
+        );
+
+        status.update_chain_height(100);
+        status.update_indexed_height(95);
+        assert_eq!(
+            service.serving_status(),
+            health_check_response::ServingStatus::Serving
+        );
+
+        status.update_chain_height(106);
+        assert_eq!(
+            service.serving_status(),
+            health_check_response::ServingStatus::NotServing
+        );
+
+        // threshold is not configured
+        let service = HealthService::new(vec![status], None);
+        assert_eq!(
+            service.serving_status(),
+            health_check_response::ServingStatus::Serving
+        );
+    }
+}
@@ -2,12 +2,13 @@ mod celestia;
 mod eigenda;
 mod health;
 
-pub use celestia::CelestiaService;
+pub use celestia::{CelestiaNetwork, CelestiaService};
 pub use eigenda::EigenDaService;
 pub use health::HealthService;
 
 use base64::prelude::*;
 use blockscout_display_bytes::Bytes;
+use da_indexer_logic::s3_storage::S3Storage;
 use std::str::FromStr;
 use tonic::Status;
 
@@ -20,3 +21,24 @@ pub fn bytes_from_hex_or_base64(s: &str, name: &str) -> Result<Vec<u8>, Status>
             Status::invalid_argument(format!("failed to decode {}", name))
         })
 }
+
+/// Returns the blob data, loading it from the external storage
+/// if the blob was offloaded.
+pub async fn blob_data(
+    storage: Option<&S3Storage>,
+    offloaded: bool,
+    data: Vec<u8>,
+    key: impl FnOnce() -> String,
+) -> Result<Vec<u8>, Status> {
+    if !offloaded {
+        return Ok(data);
+    }
+    let storage = storage.ok_or(Status::unavailable(
+        "blob data was offloaded, but storage is not configured",
+    ))?;
+    let key = key();
+    storage.get(&key).await.map_err(|err| {
+        tracing::error!(error = ?err, key, "failed to load offloaded blob");
+        Status::internal("failed to load offloaded blob")
+    })
+}
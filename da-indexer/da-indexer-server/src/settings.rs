@@ -4,9 +4,15 @@ use blockscout_service_launcher::{
     tracing::{JaegerSettings, TracingSettings},
 };
 use da_indexer_logic::{
-    celestia::l2_router::settings::L2RouterSettings, settings::IndexerSettings,
+    backfill::settings::BackfillSettings,
+    celestia::l2_router::settings::L2RouterSettings,
+    retention::settings::RetentionSettings,
+    s3_storage::S3StorageSettings,
+    settings::{DASettings, IndexerSettings},
+    status::DEFAULT_NETWORK,
 };
 use serde::Deserialize;
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
@@ -22,11 +28,73 @@ pub struct Settings {
 
     pub database: Option<DatabaseSettings>,
     pub indexer: Option<IndexerSettings>,
+    /// Indexes the fixed range of heights when started with the `backfill` command
+    pub backfill: Option<BackfillSettings>,
+    pub l2_router: Option<L2RouterSettings>,
+    #[serde(default)]
+    pub health: HealthSettings,
+    pub retention: Option<RetentionSettings>,
+    /// External storage for the offloaded blobs
+    pub s3_storage: Option<S3StorageSettings>,
+    /// Additional Celestia networks (e.g. `mocha`) indexed by the same instance.
+    /// Each network uses its own database and is available in the API
+    /// under `/api/v1/celestia/networks/{network}/`.
+    #[serde(default)]
+    pub celestia_networks: BTreeMap<String, CelestiaNetworkSettings>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct CelestiaNetworkSettings {
+    pub database: DatabaseSettings,
+    /// If not set, the network is only served by the API
+    pub indexer: Option<IndexerSettings>,
     pub l2_router: Option<L2RouterSettings>,
 }
 
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthSettings {
+    /// Health check fails if the indexer is behind the chain
+    /// by more than this number of blocks
+    pub max_indexing_lag: Option<u64>,
+}
+
 impl ConfigSettings for Settings {
     const SERVICE_NAME: &'static str = "DA_INDEXER";
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(backfill) = &self.backfill {
+            backfill.validate()?;
+        }
+        if let Some(retention) = &self.retention {
+            retention.validate()?;
+        }
+        if self.database.is_none() {
+            if self.indexer.is_some() {
+                anyhow::bail!("database is required for the indexer");
+            }
+            if self.retention.is_some() {
+                anyhow::bail!("database is required for the retention");
+            }
+            if self.backfill.is_some() {
+                anyhow::bail!("database is required for the backfill");
+            }
+        }
+        for (name, network) in &self.celestia_networks {
+            if name == DEFAULT_NETWORK {
+                anyhow::bail!(
+                    "network name '{DEFAULT_NETWORK}' is reserved for the default network"
+                );
+            }
+            if let Some(indexer) = &network.indexer {
+                if !matches!(indexer.da, DASettings::Celestia(_)) {
+                    anyhow::bail!("network '{name}' must use celestia indexer");
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Settings {
@@ -42,7 +110,12 @@ impl Settings {
                 run_migrations: Default::default(),
             }),
             indexer: Some(Default::default()),
+            backfill: None,
             l2_router: None,
+            health: Default::default(),
+            retention: None,
+            s3_storage: None,
+            celestia_networks: Default::default(),
         }
     }
 }
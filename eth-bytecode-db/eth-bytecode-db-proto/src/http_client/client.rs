@@ -161,6 +161,13 @@ pub mod database_client {
         let path = "/api/v2/bytecodes/sources:search-all";
         client.post_request(client.build_url(path), &request).await
     }
+    pub async fn search_alliance_deployments(
+        client: &Client,
+        request: proto::SearchAllianceDeploymentsRequest,
+    ) -> Result<proto::SearchAllianceDeploymentsResponse> {
+        let path = "/api/v2/bytecodes/deployments:search-alliance";
+        client.post_request(client.build_url(path), &request).await
+    }
     pub async fn search_event_descriptions(
         client: &Client,
         request: proto::SearchEventDescriptionsRequest,
@@ -5,7 +5,8 @@ use crate::blockscout::eth_bytecode_db::v2::{
     vyper_verifier_actix::route_vyper_verifier, vyper_verifier_server::VyperVerifier,
     AllianceStats, BatchSearchEventDescriptionsRequest, BatchSearchEventDescriptionsResponse,
     GetAllianceStatsRequest, ListCompilerVersionsRequest, ListCompilerVersionsResponse,
-    SearchAllSourcesRequest, SearchAllSourcesResponse, SearchAllianceSourcesRequest,
+    SearchAllSourcesRequest, SearchAllSourcesResponse, SearchAllianceDeploymentsRequest,
+    SearchAllianceDeploymentsResponse, SearchAllianceSourcesRequest,
     SearchEventDescriptionsRequest, SearchEventDescriptionsResponse, SearchSourcesRequest,
     SearchSourcesResponse, SearchSourcifySourcesRequest, VerifyFromEtherscanSourcifyRequest,
     VerifyResponse, VerifySolidityMultiPartRequest, VerifySolidityStandardJsonRequest,
@@ -27,6 +28,8 @@ mock! {
 
         async fn search_all_sources(&self, request: tonic::Request<SearchAllSourcesRequest>) -> Result<tonic::Response<SearchAllSourcesResponse>, tonic::Status>;
 
+        async fn search_alliance_deployments(&self, request: tonic::Request<SearchAllianceDeploymentsRequest>) -> Result<tonic::Response<SearchAllianceDeploymentsResponse>, tonic::Status>;
+
         async fn search_event_descriptions(&self, request: tonic::Request<SearchEventDescriptionsRequest>) -> Result<tonic::Response<SearchEventDescriptionsResponse>, tonic::Status>;
 
         async fn batch_search_event_descriptions(&self, request: tonic::Request<BatchSearchEventDescriptionsRequest>) -> Result<tonic::Response<BatchSearchEventDescriptionsResponse>, tonic::Status>;
@@ -1,10 +1,12 @@
 use crate::{
     proto::{
-        database_server::Database, AllianceStats, BatchSearchEventDescriptionsRequest,
-        BatchSearchEventDescriptionsResponse, BytecodeType, GetAllianceStatsRequest,
-        SearchAllSourcesRequest, SearchAllSourcesResponse, SearchAllianceSourcesRequest,
-        SearchEventDescriptionsRequest, SearchEventDescriptionsResponse, SearchSourcesRequest,
-        SearchSourcesResponse, SearchSourcifySourcesRequest, Source, VerifyResponse,
+        database_server::Database, search_alliance_deployments_response, AllianceStats,
+        BatchSearchEventDescriptionsRequest, BatchSearchEventDescriptionsResponse, BytecodeType,
+        GetAllianceStatsRequest, SearchAllSourcesRequest, SearchAllSourcesResponse,
+        SearchAllianceDeploymentsRequest, SearchAllianceDeploymentsResponse,
+        SearchAllianceSourcesRequest, SearchEventDescriptionsRequest,
+        SearchEventDescriptionsResponse, SearchSourcesRequest, SearchSourcesResponse,
+        SearchSourcifySourcesRequest, Source, VerifyResponse,
     },
     types::{BytecodeTypeWrapper, EventDescriptionWrapper, SourceWrapper, VerifyResponseWrapper},
 };
@@ -108,6 +110,46 @@ impl Database for DatabaseService {
         }
     }
 
+    #[instrument(skip_all)]
+    async fn search_alliance_deployments(
+        &self,
+        request: tonic::Request<SearchAllianceDeploymentsRequest>,
+    ) -> Result<tonic::Response<SearchAllianceDeploymentsResponse>, tonic::Status> {
+        let request = request.into_inner();
+
+        let alliance_db_client = match self.client.alliance_db_client.clone() {
+            None => {
+                tracing::trace!("Unavailable: verifier alliance is not enabled");
+                return Err(tonic::Status::unavailable(
+                    "Verifier alliance is not enabled",
+                ));
+            }
+            Some(alliance_db_client) => alliance_db_client,
+        };
+
+        let code_type = BytecodeTypeWrapper::from_inner(request.bytecode_type()).try_into()?;
+        let code = DisplayBytes::from_str(&request.bytecode)
+            .map_err(|err| tonic::Status::invalid_argument(format!("Invalid bytecode: {err}")))?
+            .0;
+
+        let deployments =
+            search::alliance_db_find_deployments(alliance_db_client.as_ref(), code_type, &code)
+                .await
+                .map_err(|err| tonic::Status::internal(err.to_string()))?
+                .into_iter()
+                .map(
+                    |deployment| search_alliance_deployments_response::Deployment {
+                        chain_id: deployment.chain_id,
+                        address: DisplayBytes::from(deployment.address).to_string(),
+                    },
+                )
+                .collect();
+
+        Ok(tonic::Response::new(SearchAllianceDeploymentsResponse {
+            deployments,
+        }))
+    }
+
     #[instrument(skip_all)]
     async fn search_all_sources(
         &self,
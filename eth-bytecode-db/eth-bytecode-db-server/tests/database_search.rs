@@ -7,11 +7,12 @@ use eth_bytecode_db::{verification, verification::MatchType};
 use eth_bytecode_db_proto::blockscout::eth_bytecode_db::{
     v2 as eth_bytecode_db_v2,
     v2::{
-        BatchSearchEventDescriptionsRequest, BatchSearchEventDescriptionsResponse,
-        EventDescription, SearchAllSourcesRequest, SearchAllSourcesResponse,
-        SearchAllianceSourcesRequest, SearchEventDescriptionsRequest,
-        SearchEventDescriptionsResponse, SearchSourcesRequest, SearchSourcesResponse,
-        SearchSourcifySourcesRequest, Source,
+        search_alliance_deployments_response, BatchSearchEventDescriptionsRequest,
+        BatchSearchEventDescriptionsResponse, EventDescription, SearchAllSourcesRequest,
+        SearchAllSourcesResponse, SearchAllianceDeploymentsRequest,
+        SearchAllianceDeploymentsResponse, SearchAllianceSourcesRequest,
+        SearchEventDescriptionsRequest, SearchEventDescriptionsResponse, SearchSourcesRequest,
+        SearchSourcesResponse, SearchSourcifySourcesRequest, Source,
     },
 };
 use pretty_assertions::assert_eq;
@@ -272,6 +273,52 @@ async fn search_alliance_sources(
     );
 }
 
+#[rstest]
+#[tokio::test]
+#[timeout(std::time::Duration::from_secs(60))]
+#[ignore = "Needs database to run"]
+async fn search_alliance_deployments(
+    #[files("tests/alliance_test_cases/*.json")] test_case_path: PathBuf,
+) {
+    const TEST_NAME: &str = "search_alliance_deployments";
+    const ROUTE: &str = "/api/v2/bytecodes/deployments:search-alliance";
+
+    let setup_data = verifier_alliance_setup::Setup::new(TEST_NAME)
+        .authorized()
+        .setup(TEST_SUITE_NAME, test_case_path)
+        .await;
+
+    let request = SearchAllianceDeploymentsRequest {
+        bytecode: setup_data.test_case.deployed_runtime_code.to_string(),
+        bytecode_type: eth_bytecode_db_v2::BytecodeType::DeployedBytecode.into(),
+    };
+    let search_response: SearchAllianceDeploymentsResponse =
+        test_server::send_post_request(&setup_data.eth_bytecode_db_base, ROUTE, &request).await;
+
+    let expected_response = SearchAllianceDeploymentsResponse {
+        deployments: vec![search_alliance_deployments_response::Deployment {
+            chain_id: setup_data.test_case.chain_id.to_string(),
+            address: setup_data.test_case.address.to_string(),
+        }],
+    };
+    assert_eq!(
+        expected_response, search_response,
+        "Invalid response returned"
+    );
+
+    let request = SearchAllianceDeploymentsRequest {
+        bytecode: "0x6080".to_string(),
+        bytecode_type: eth_bytecode_db_v2::BytecodeType::DeployedBytecode.into(),
+    };
+    let search_response: SearchAllianceDeploymentsResponse =
+        test_server::send_post_request(&setup_data.eth_bytecode_db_base, ROUTE, &request).await;
+    assert_eq!(
+        SearchAllianceDeploymentsResponse::default(),
+        search_response,
+        "Unknown code must have no deployments"
+    );
+}
+
 #[rstest]
 #[tokio::test]
 #[timeout(std::time::Duration::from_secs(60))]
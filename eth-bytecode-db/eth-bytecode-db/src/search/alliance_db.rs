@@ -1,8 +1,22 @@
-use super::MatchContract;
+use super::{BytecodeType, MatchContract};
 use crate::verification::{MatchType, SourceType};
 use anyhow::Context;
-use sea_orm::{entity::prelude::Decimal, ColumnTrait, ConnectionTrait, EntityTrait, QueryFilter};
-use verifier_alliance_entity::{compiled_contracts, contract_deployments, verified_contracts};
+use sea_orm::{
+    entity::prelude::Decimal, ColumnTrait, ConnectionTrait, EntityTrait, JoinType, QueryFilter,
+    QueryOrder, QuerySelect, RelationTrait,
+};
+use verifier_alliance_entity::{
+    compiled_contracts, contract_deployments, contracts, verified_contracts,
+};
+
+/// Max number of deployments returned by a single search
+const MAX_DEPLOYMENTS: u64 = 100;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deployment {
+    pub chain_id: String,
+    pub address: Vec<u8>,
+}
 
 pub async fn find_contract<C: ConnectionTrait>(
     db: &C,
@@ -33,6 +47,40 @@ pub async fn find_contract<C: ConnectionTrait>(
     }
 }
 
+/// Looks for the deployments of the contracts which code is identical to the given one
+pub async fn find_deployments<C: ConnectionTrait>(
+    db: &C,
+    bytecode_type: BytecodeType,
+    code: &[u8],
+) -> Result<Vec<Deployment>, anyhow::Error> {
+    let code_hash = keccak_hash::keccak(code).0.to_vec();
+    let code_hash_column = match bytecode_type {
+        BytecodeType::CreationInput => contracts::Column::CreationCodeHash,
+        BytecodeType::DeployedBytecode => contracts::Column::RuntimeCodeHash,
+    };
+
+    let deployments = contract_deployments::Entity::find()
+        .join(
+            JoinType::InnerJoin,
+            contract_deployments::Relation::Contracts.def(),
+        )
+        .filter(code_hash_column.eq(code_hash))
+        .order_by_asc(contract_deployments::Column::ChainId)
+        .order_by_asc(contract_deployments::Column::Address)
+        .limit(MAX_DEPLOYMENTS)
+        .all(db)
+        .await
+        .context("retrieve contract deployments")?
+        .into_iter()
+        .map(|deployment| Deployment {
+            chain_id: deployment.chain_id.to_string(),
+            address: deployment.address,
+        })
+        .collect();
+
+    Ok(deployments)
+}
+
 async fn retrieve_compiled_verified_pairs<C: ConnectionTrait>(
     db: &C,
     chain_id: i64,
@@ -1,4 +1,6 @@
-// TODO: try move to common crate since code is copipasted from smart-contract-verifier
+// TODO: try move to common crate since code is copipasted from smart-contract-verifier.
+//  Metadata hash segments may be located via `solidity_metadata::find_metadata_hashes`
+//  once the dependency is bumped to solidity-metadata 2.0.
 
 use crate::verification::MatchType;
 use blockscout_display_bytes::Bytes as DisplayBytes;
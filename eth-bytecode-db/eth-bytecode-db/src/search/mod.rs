@@ -7,7 +7,10 @@ mod match_contract;
 mod matches;
 mod types;
 
-pub use alliance_db::find_contract as alliance_db_find_contract;
+pub use alliance_db::{
+    find_contract as alliance_db_find_contract, find_deployments as alliance_db_find_deployments,
+    Deployment as AllianceDeployment,
+};
 pub use any_match::find_contract as eth_bytecode_db_find_contract;
 pub use entity::sea_orm_active_enums::BytecodeType;
 pub use events::{find_event_descriptions, EventDescription};
@@ -1,10 +1,14 @@
 use super::endpoint::Endpoint;
-use crate::Error;
+use crate::{
+    observability::{EndpointName, ObservabilityMiddleware},
+    retry::is_transient_failure,
+    Error, ResponseCache,
+};
 use reqwest::{header::HeaderMap, Response, StatusCode};
-use reqwest_middleware::ClientBuilder;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde::Deserialize;
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 #[derive(Clone)]
 pub struct HttpApiClientConfig {
@@ -12,9 +16,14 @@ pub struct HttpApiClientConfig {
     /// cancelled.
     pub http_timeout: Duration,
     /// Maximum number of allowed retries attempts. Defaults to 1.
+    /// Applies only to the endpoints whose [`Endpoint::retry_policy`] allows retries.
     pub max_retries: u32,
     /// A default set of HTTP headers which will be sent with each API request.
     pub default_headers: HeaderMap,
+    /// Mirrors of the base url tried in order when the requests to the previous host
+    /// fail with a transient error after all retries. Used only for the endpoints
+    /// whose [`Endpoint::retry_policy`] allows retries. Defaults to none.
+    pub fallback_base_urls: Vec<url::Url>,
 }
 
 impl Default for HttpApiClientConfig {
@@ -23,6 +32,7 @@ impl Default for HttpApiClientConfig {
             http_timeout: Duration::from_secs(30),
             max_retries: 1,
             default_headers: HeaderMap::default(),
+            fallback_base_urls: vec![],
         }
     }
 }
@@ -30,7 +40,10 @@ impl Default for HttpApiClientConfig {
 #[derive(Clone)]
 pub struct HttpApiClient {
     base_url: url::Url,
-    http_client: reqwest_middleware::ClientWithMiddleware,
+    fallback_base_urls: Vec<url::Url>,
+    http_client: ClientWithMiddleware,
+    http_client_without_retries: ClientWithMiddleware,
+    cache: Option<Arc<dyn ResponseCache>>,
 }
 
 impl HttpApiClient {
@@ -40,39 +53,95 @@ impl HttpApiClient {
             .default_headers(config.default_headers)
             .timeout(config.http_timeout)
             .build()?;
-        let client = ClientBuilder::new(reqwest_client)
+        let client = ClientBuilder::new(reqwest_client.clone())
+            .with(ObservabilityMiddleware)
             .with(RetryTransientMiddleware::new_with_policy(retry_policy))
             .build();
+        let client_without_retries = ClientBuilder::new(reqwest_client)
+            .with(ObservabilityMiddleware)
+            .build();
         Ok(Self {
             base_url,
+            fallback_base_urls: config.fallback_base_urls,
             http_client: client,
+            http_client_without_retries: client_without_retries,
+            cache: None,
         })
     }
 
+    /// Enables caching of the successful responses of the endpoints
+    /// returning [`Endpoint::cache_ttl`]
+    pub fn with_cache(mut self, cache: Arc<dyn ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
     /// Issue an API request of the given type.
     pub async fn request<EndpointType: Endpoint>(
         &self,
         endpoint: &EndpointType,
     ) -> Result<<EndpointType as Endpoint>::Response, Error> {
-        // Build the request
-        let mut request = self
-            .http_client
-            .request(endpoint.method(), endpoint.url(&self.base_url));
+        let url = endpoint.url(&self.base_url);
+        let cache = self.cache.as_ref().zip(endpoint.cache_ttl());
+        let cache_key = format!("{} {url}", endpoint.method());
+        if let Some((cache, _)) = cache {
+            if let Ok(Some(raw_value)) = cache.get(&cache_key).await {
+                // Responses cached before the response type has changed are ignored
+                if let Ok(value) = deserialize_response(&raw_value) {
+                    return Ok(value);
+                }
+            }
+        }
 
-        if let Some(body) = endpoint.body() {
-            request = request.body(body);
-            request = request.header(
-                reqwest::header::CONTENT_TYPE,
-                endpoint.content_type().as_ref(),
-            );
+        let allows_retries = endpoint.retry_policy().allows_retries(&endpoint.method());
+        let (http_client, fallback_base_urls) = if allows_retries {
+            (&self.http_client, self.fallback_base_urls.as_slice())
+        } else {
+            (&self.http_client_without_retries, [].as_slice())
+        };
+        let mut base_urls = std::iter::once(&self.base_url)
+            .chain(fallback_base_urls)
+            .peekable();
+        let response = loop {
+            let base_url = base_urls
+                .next()
+                .expect("the primary base url is always present");
+            let result = send_request(http_client, endpoint, base_url).await;
+            if base_urls.peek().is_none() || !is_transient_failure(&result) {
+                break result?;
+            }
+        };
+        let raw_value = process_api_response(response).await?;
+        let value = deserialize_response(&raw_value)?;
+        if let Some((cache, ttl)) = cache {
+            // Failing cache should not fail the request
+            let _ = cache.set(&cache_key, raw_value, ttl).await;
         }
+        Ok(value)
+    }
+}
+
+async fn send_request<EndpointType: Endpoint>(
+    http_client: &ClientWithMiddleware,
+    endpoint: &EndpointType,
+    base_url: &url::Url,
+) -> reqwest_middleware::Result<Response> {
+    let mut request = http_client
+        .request(endpoint.method(), endpoint.url(base_url))
+        .with_extension(EndpointName(endpoint.name()));
 
-        let response = request.send().await?;
-        process_api_response(response).await
+    if let Some(body) = endpoint.body() {
+        request = request.body(body);
+        request = request.header(
+            reqwest::header::CONTENT_TYPE,
+            endpoint.content_type().as_ref(),
+        );
     }
+
+    request.send().await
 }
 
-async fn process_api_response<T: for<'a> Deserialize<'a>>(response: Response) -> Result<T, Error> {
+async fn process_api_response(response: Response) -> Result<Vec<u8>, Error> {
     let status = response.status();
     match status {
         status if status.is_success() => (),
@@ -85,8 +154,11 @@ async fn process_api_response<T: for<'a> Deserialize<'a>>(response: Response) ->
         }
     }
 
-    let raw_value = response.bytes().await?;
-    let deserializer = &mut serde_json::Deserializer::from_slice(raw_value.as_ref());
+    Ok(response.bytes().await?.to_vec())
+}
+
+fn deserialize_response<T: for<'a> Deserialize<'a>>(raw_value: &[u8]) -> Result<T, Error> {
+    let deserializer = &mut serde_json::Deserializer::from_slice(raw_value);
     let value: T = serde_path_to_error::deserialize(deserializer)?;
     Ok(value)
 }
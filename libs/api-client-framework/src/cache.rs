@@ -0,0 +1,108 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Storage of the raw responses used by [`crate::HttpApiClient::with_cache`].
+///
+/// Cache failures never fail the requests: if the cache is unavailable,
+/// the request is sent to the api as if there were no cache.
+#[async_trait::async_trait]
+pub trait ResponseCache: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, anyhow::Error>;
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), anyhow::Error>;
+}
+
+/// Cache local to the process. When `max_entries` is reached,
+/// the expired entries are evicted, and new responses are not cached until there is space.
+pub struct InMemoryCache {
+    max_entries: usize,
+    entries: Mutex<HashMap<String, (Instant, Vec<u8>)>>,
+}
+
+impl InMemoryCache {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ResponseCache for InMemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, anyhow::Error> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((expires_at, value)) if *expires_at > Instant::now() => Ok(Some(value.clone())),
+            Some(_) => {
+                entries.remove(key);
+                Ok(None)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), anyhow::Error> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.max_entries && !entries.contains_key(key) {
+            let now = Instant::now();
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+            if entries.len() >= self.max_entries {
+                return Ok(());
+            }
+        }
+        entries.insert(key.to_string(), (Instant::now() + ttl, value));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "redis")]
+pub use redis_cache::RedisCache;
+
+#[cfg(feature = "redis")]
+mod redis_cache {
+    use super::ResponseCache;
+    use redis::AsyncCommands;
+    use std::time::Duration;
+
+    /// Cache shared between the replicas of the service
+    #[derive(Clone)]
+    pub struct RedisCache {
+        connection: redis::aio::ConnectionManager,
+        key_prefix: String,
+    }
+
+    impl RedisCache {
+        pub async fn new(redis_url: &str, key_prefix: String) -> Result<Self, redis::RedisError> {
+            let client = redis::Client::open(redis_url)?;
+            let connection = redis::aio::ConnectionManager::new(client).await?;
+            Ok(Self {
+                connection,
+                key_prefix,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl ResponseCache for RedisCache {
+        async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, anyhow::Error> {
+            let mut connection = self.connection.clone();
+            let value: Option<Vec<u8>> =
+                connection.get(format!("{}{key}", self.key_prefix)).await?;
+            Ok(value)
+        }
+
+        async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), anyhow::Error> {
+            let mut connection = self.connection.clone();
+            // Redis expirations have seconds precision, and zero is not allowed
+            let ttl = ttl.as_secs().max(1);
+            connection
+                .set_ex::<_, _, ()>(format!("{}{key}", self.key_prefix), value, ttl)
+                .await?;
+            Ok(())
+        }
+    }
+}
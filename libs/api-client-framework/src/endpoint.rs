@@ -1,5 +1,6 @@
+use crate::RetryPolicy;
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, fmt::Debug};
+use std::{borrow::Cow, fmt::Debug, time::Duration};
 use url::Url;
 
 /// Represents a specification for an API call that can be built into an HTTP request and sent.
@@ -9,6 +10,14 @@ use url::Url;
 pub trait Endpoint {
     type Response: for<'a> Deserialize<'a> + Debug;
 
+    /// The name used in the metrics and tracing spans of the requests.
+    /// Defaults to the name of the implementing type.
+    fn name(&self) -> Cow<'static, str> {
+        let type_name = std::any::type_name::<Self>();
+        let type_name = type_name.split('<').next().unwrap_or(type_name);
+        Cow::Borrowed(type_name.rsplit("::").next().unwrap_or(type_name))
+    }
+
     /// The HTTP Method used for this endpoint (e.g. GET, PATCH, DELETE)
     fn method(&self) -> reqwest::Method;
 
@@ -31,6 +40,25 @@ pub trait Endpoint {
         None
     }
 
+    /// How long the successful response may be served from the cache of the client
+    /// (see [`crate::HttpApiClient::with_cache`]). Defaults to `None`, i.e., not cached.
+    ///
+    /// Implementors should inline this.
+    #[inline]
+    fn cache_ttl(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Whether the failed requests may be retried or sent to the fallback hosts.
+    /// Defaults to retrying any request, so non-idempotent endpoints
+    /// which must not be repeated should override it.
+    ///
+    /// Implementors should inline this.
+    #[inline]
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
     /// Builds and returns a formatted full URL, including query, for the endpoint.
     ///
     /// Implementors should generally not override this.
@@ -1,10 +1,17 @@
 //! Adapted from https://github.com/cloudflare/cloudflare-rs
 
 mod async_client;
+mod cache;
 mod endpoint;
+mod observability;
+mod retry;
 
 pub use async_client::{HttpApiClient, HttpApiClientConfig};
+#[cfg(feature = "redis")]
+pub use cache::RedisCache;
+pub use cache::{InMemoryCache, ResponseCache};
 pub use endpoint::{serialize_query, Endpoint};
+pub use retry::RetryPolicy;
 
 /******************** Config definition ********************/
 
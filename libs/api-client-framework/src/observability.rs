@@ -0,0 +1,99 @@
+use reqwest::{header::HeaderValue, Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use std::{borrow::Cow, time::Instant};
+use tracing::Instrument;
+
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+#[cfg(feature = "metrics")]
+mod metrics {
+    use once_cell::sync::Lazy;
+    use prometheus::{
+        register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec,
+    };
+
+    pub static REQUEST_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+        register_histogram_vec!(
+            "api_client_request_duration_seconds",
+            "duration of the api client requests including retries",
+            &["client", "endpoint"],
+        )
+        .unwrap()
+    });
+
+    pub static RESPONSES: Lazy<IntCounterVec> = Lazy::new(|| {
+        register_int_counter_vec!(
+            "api_client_responses_total",
+            "number of the api client responses by status code",
+            &["client", "endpoint", "status"],
+        )
+        .unwrap()
+    });
+}
+
+/// Name of the endpoint the request is made to, attached to the request extensions
+#[derive(Debug, Clone)]
+pub(crate) struct EndpointName(pub Cow<'static, str>);
+
+/// Wraps each request into a tracing span with a request id, which is also sent
+/// in the `x-request-id` header, and records the request metrics (with `metrics` feature).
+/// The middleware is applied before the retries, so the retried attempts share the request id.
+/// The `client` label is the host the request is sent to, so the requests
+/// to the fallback hosts are recorded separately.
+pub(crate) struct ObservabilityMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for ObservabilityMiddleware {
+    async fn handle(
+        &self,
+        mut req: Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let endpoint = extensions
+            .get::<EndpointName>()
+            .map(|name| name.0.clone())
+            .unwrap_or(Cow::Borrowed("unknown"));
+        let client = req.url().host_str().unwrap_or_default().to_string();
+        let request_id = match req.headers().get(REQUEST_ID_HEADER) {
+            Some(request_id) => request_id.to_str().unwrap_or_default().to_string(),
+            None => {
+                let request_id = uuid::Uuid::new_v4().to_string();
+                req.headers_mut().insert(
+                    REQUEST_ID_HEADER,
+                    HeaderValue::from_str(&request_id).expect("uuid is a valid header value"),
+                );
+                request_id
+            }
+        };
+        let span = tracing::info_span!(
+            "api_client_request",
+            client = %client,
+            endpoint = %endpoint,
+            method = %req.method(),
+            request_id = %request_id,
+            status = tracing::field::Empty,
+        );
+
+        let start = Instant::now();
+        let result = next.run(req, extensions).instrument(span.clone()).await;
+        let status = match &result {
+            Ok(response) => response.status().as_u16().to_string(),
+            Err(_) => "error".to_string(),
+        };
+        span.record("status", status.as_str());
+        #[cfg(feature = "metrics")]
+        {
+            metrics::REQUEST_DURATION
+                .with_label_values(&[&client, &endpoint])
+                .observe(start.elapsed().as_secs_f64());
+            metrics::RESPONSES
+                .with_label_values(&[&client, &endpoint, &status])
+                .inc();
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = start;
+
+        result
+    }
+}
@@ -0,0 +1,36 @@
+use reqwest::{Method, Response};
+use reqwest_retry::Retryable;
+
+/// Defines whether the failed requests to the endpoint may be repeated,
+/// both as retries to the same host and as requests to the fallback hosts
+/// (see [`crate::HttpApiClientConfig::fallback_base_urls`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RetryPolicy {
+    /// Repeats the requests with idempotent methods only (GET, HEAD, PUT, DELETE, OPTIONS, TRACE).
+    /// Should be used by the non-idempotent endpoints which are not safe to be sent several times.
+    IdempotentOnly,
+    /// Repeats any request, which is how all the requests were handled before the policies were added.
+    #[default]
+    Always,
+    /// Never repeats the requests.
+    Never,
+}
+
+impl RetryPolicy {
+    pub fn allows_retries(&self, method: &Method) -> bool {
+        match self {
+            RetryPolicy::IdempotentOnly => method.is_idempotent(),
+            RetryPolicy::Always => true,
+            RetryPolicy::Never => false,
+        }
+    }
+}
+
+/// Whether the request failed in a way that may be resolved by sending it to another host.
+pub(crate) fn is_transient_failure(result: &reqwest_middleware::Result<Response>) -> bool {
+    let retryable = match result {
+        Ok(response) => reqwest_retry::default_on_request_success(response),
+        Err(error) => reqwest_retry::default_on_request_failure(error),
+    };
+    matches!(retryable, Some(Retryable::Transient))
+}
@@ -0,0 +1,85 @@
+use crate::BlockscoutChains;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+const CACHE_FILE_NAME: &str = "blockscout-chains.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CachedChains {
+    pub chains: BlockscoutChains,
+    pub etag: Option<String>,
+    pub fetched_at: SystemTime,
+}
+
+/// Latest fetched catalog kept in memory and optionally on disk,
+/// so that it survives the service restarts
+#[derive(Debug)]
+pub(crate) struct ChainsCache {
+    ttl: Duration,
+    dir: Option<PathBuf>,
+    entry: Mutex<Option<CachedChains>>,
+}
+
+impl ChainsCache {
+    pub fn new(ttl: Duration, dir: Option<PathBuf>) -> Self {
+        let entry = dir.as_ref().and_then(|dir| {
+            let content = std::fs::read(dir.join(CACHE_FILE_NAME)).ok()?;
+            match serde_json::from_slice(&content) {
+                Ok(entry) => Some(entry),
+                Err(err) => {
+                    tracing::warn!("failed to parse cached chains: {err}");
+                    None
+                }
+            }
+        });
+        Self {
+            ttl,
+            dir,
+            entry: Mutex::new(entry),
+        }
+    }
+
+    pub fn get(&self) -> Option<CachedChains> {
+        self.entry.lock().unwrap().clone()
+    }
+
+    pub fn is_fresh(&self, entry: &CachedChains) -> bool {
+        SystemTime::now()
+            .duration_since(entry.fetched_at)
+            .map(|age| age < self.ttl)
+            .unwrap_or(true)
+    }
+
+    pub async fn insert(&self, entry: CachedChains) {
+        if let Some(dir) = &self.dir {
+            if let Err(err) = Self::persist(dir, &entry).await {
+                tracing::warn!("failed to save chains cache: {err}");
+            }
+        }
+        *self.entry.lock().unwrap() = Some(entry);
+    }
+
+    /// Makes the cached catalog stale, so that it is revalidated on the next fetch
+    pub async fn invalidate(&self) {
+        if let Some(entry) = self.get() {
+            self.insert(CachedChains {
+                fetched_at: SystemTime::UNIX_EPOCH,
+                ..entry
+            })
+            .await;
+        }
+    }
+
+    async fn persist(dir: &Path, entry: &CachedChains) -> Result<(), std::io::Error> {
+        tokio::fs::create_dir_all(dir).await?;
+        let content = serde_json::to_vec(entry)?;
+        // Write to a temporary file first, so that the cache is never left partially written
+        let temp_path = dir.join(format!("{CACHE_FILE_NAME}.{}.tmp", std::process::id()));
+        tokio::fs::write(&temp_path, content).await?;
+        tokio::fs::rename(temp_path, dir.join(CACHE_FILE_NAME)).await
+    }
+}
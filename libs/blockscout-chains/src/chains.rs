@@ -0,0 +1,124 @@
+use crate::BlockscoutChainData;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt, num::ParseIntError, str::FromStr};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(transparent)]
+pub struct ChainId(pub u64);
+
+impl fmt::Display for ChainId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for ChainId {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse().map(Self)
+    }
+}
+
+impl From<u64> for ChainId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+impl From<ChainId> for u64 {
+    fn from(id: ChainId) -> Self {
+        id.0
+    }
+}
+
+/// Chains of the catalog by their ids.
+/// Filtering methods return a new catalog, so they can be chained:
+/// `chains.mainnets_only().by_ecosystem("Optimism")`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct BlockscoutChains(HashMap<ChainId, BlockscoutChainData>);
+
+impl BlockscoutChains {
+    pub fn by_chain_id(&self, id: impl Into<ChainId>) -> Option<&BlockscoutChainData> {
+        self.0.get(&id.into())
+    }
+
+    /// Chains not marked as testnets
+    pub fn mainnets_only(&self) -> Self {
+        self.filter(|chain| !chain.is_testnet.unwrap_or_default())
+    }
+
+    /// Chains belonging to the ecosystem, the name is compared case-insensitively
+    pub fn by_ecosystem(&self, ecosystem: &str) -> Self {
+        self.filter(|chain| chain.ecosystem.contains(ecosystem))
+    }
+
+    /// Chains with the rollup type (e.g. `optimistic` or `zk`), compared case-insensitively
+    pub fn rollups_of_type(&self, rollup_type: &str) -> Self {
+        self.filter(|chain| {
+            chain
+                .rollup_type
+                .as_deref()
+                .is_some_and(|value| value.eq_ignore_ascii_case(rollup_type))
+        })
+    }
+
+    pub fn filter(&self, predicate: impl Fn(&BlockscoutChainData) -> bool) -> Self {
+        self.iter()
+            .filter(|(_, chain)| predicate(chain))
+            .map(|(id, chain)| (*id, chain.clone()))
+            .collect()
+    }
+
+    pub fn filter_by_id(&self, predicate: impl Fn(ChainId) -> bool) -> Self {
+        self.iter()
+            .filter(|(id, _)| predicate(**id))
+            .map(|(id, chain)| (*id, chain.clone()))
+            .collect()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = ChainId> + '_ {
+        self.0.keys().copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ChainId, &BlockscoutChainData)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn into_inner(self) -> HashMap<ChainId, BlockscoutChainData> {
+        self.0
+    }
+}
+
+impl FromIterator<(ChainId, BlockscoutChainData)> for BlockscoutChains {
+    fn from_iter<T: IntoIterator<Item = (ChainId, BlockscoutChainData)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for BlockscoutChains {
+    type Item = (ChainId, BlockscoutChainData);
+    type IntoIter = std::collections::hash_map::IntoIter<ChainId, BlockscoutChainData>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a BlockscoutChains {
+    type Item = (&'a ChainId, &'a BlockscoutChainData);
+    type IntoIter = std::collections::hash_map::Iter<'a, ChainId, BlockscoutChainData>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
@@ -1,13 +1,34 @@
+mod cache;
+mod chains;
+mod watcher;
+mod write;
+
+pub use chains::{BlockscoutChains, ChainId};
+pub use watcher::{ChainsDiff, ChainsWatcher, ChangedChain, ZeroIntervalError};
+
+use cache::{CachedChains, ChainsCache};
+use reqwest::{header, StatusCode};
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
-use serde::Deserialize;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
 const CHAINS_URL: &str = "https://chains.blockscout.com/api/chains";
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+/// Snapshot of the catalog bundled into the crate, used as the default fallback
+const BUNDLED_SNAPSHOT: &str = include_str!("../snapshot/chains.json");
 
 pub struct BlockscoutChainsClient {
     client: ClientWithMiddleware,
+    /// Used for the non-idempotent write requests, which must not be sent twice
+    client_without_retries: ClientWithMiddleware,
     url: String,
+    cache: Option<ChainsCache>,
+    fallback: Option<BlockscoutChains>,
+    api_key: Option<String>,
 }
 
 impl BlockscoutChainsClient {
@@ -15,9 +36,81 @@ impl BlockscoutChainsClient {
         Default::default()
     }
 
+    /// Returns the cached catalog while it is fresh, otherwise revalidates it
+    /// using the ETag of the cached response. If the remote catalog is unreachable,
+    /// the stale cached catalog or the fallback snapshot is returned instead.
     pub async fn fetch_all(&self) -> Result<BlockscoutChains, reqwest_middleware::Error> {
-        let res = self.client.get(&self.url).send().await?;
+        self.fetch_all_with_source()
+            .await
+            .map(|(chains, _is_fallback)| chains)
+    }
+
+    /// Same as [`Self::fetch_all`], but also tells whether the fallback snapshot has been returned
+    pub(crate) async fn fetch_all_with_source(
+        &self,
+    ) -> Result<(BlockscoutChains, bool), reqwest_middleware::Error> {
+        let cached = self.cache.as_ref().and_then(|cache| cache.get());
+        if let (Some(cache), Some(cached)) = (&self.cache, &cached) {
+            if cache.is_fresh(cached) {
+                return Ok((cached.chains.clone(), false));
+            }
+        }
+
+        match self.fetch_remote(cached.as_ref()).await {
+            Ok(chains) => Ok((chains, false)),
+            Err(err) => {
+                if let Some(cached) = cached {
+                    tracing::warn!("failed to fetch chains, using stale cached catalog: {err}");
+                    Ok((cached.chains, false))
+                } else if let Some(fallback) = &self.fallback {
+                    tracing::warn!("failed to fetch chains, using fallback snapshot: {err}");
+                    Ok((fallback.clone(), true))
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    async fn fetch_remote(
+        &self,
+        cached: Option<&CachedChains>,
+    ) -> Result<BlockscoutChains, reqwest_middleware::Error> {
+        let mut request = self.client.get(&self.url);
+        if let Some(etag) = cached.and_then(|cached| cached.etag.as_ref()) {
+            request = request.header(header::IF_NONE_MATCH, etag);
+        }
+        let res = request.send().await?;
+
+        if let (Some(cache), Some(cached)) = (&self.cache, cached) {
+            if res.status() == StatusCode::NOT_MODIFIED {
+                let chains = cached.chains.clone();
+                cache
+                    .insert(CachedChains {
+                        fetched_at: SystemTime::now(),
+                        ..cached.clone()
+                    })
+                    .await;
+                return Ok(chains);
+            }
+        }
+
+        let res = res.error_for_status()?;
+        let etag = res
+            .headers()
+            .get(header::ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string);
         let chains: BlockscoutChains = res.json().await?;
+        if let Some(cache) = &self.cache {
+            cache
+                .insert(CachedChains {
+                    chains: chains.clone(),
+                    etag,
+                    fetched_at: SystemTime::now(),
+                })
+                .await;
+        }
         Ok(chains)
     }
 }
@@ -31,6 +124,11 @@ impl Default for BlockscoutChainsClient {
 pub struct BlockscoutChainsClientBuilder {
     max_retries: u32,
     url: String,
+    cache_enabled: bool,
+    cache_ttl: Duration,
+    cache_dir: Option<PathBuf>,
+    fallback: Option<BlockscoutChains>,
+    api_key: Option<String>,
 }
 
 impl BlockscoutChainsClientBuilder {
@@ -44,14 +142,61 @@ impl BlockscoutChainsClientBuilder {
         self
     }
 
+    /// Keeps the fetched catalog in memory and serves it without any requests
+    /// until `ttl` expires. After that the catalog is revalidated with `If-None-Match`.
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_enabled = true;
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Additionally persists the cached catalog in `dir`, so that it is reused after restarts
+    pub fn with_cache_dir(mut self, dir: PathBuf) -> Self {
+        self.cache_enabled = true;
+        self.cache_dir = Some(dir);
+        self
+    }
+
+    /// Snapshot returned when the remote catalog is unreachable and nothing is cached yet.
+    /// By default, the snapshot bundled into the crate is used.
+    pub fn with_fallback(mut self, chains: BlockscoutChains) -> Self {
+        self.fallback = Some(chains);
+        self
+    }
+
+    pub fn with_fallback_json(self, json: &str) -> Result<Self, serde_json::Error> {
+        Ok(self.with_fallback(serde_json::from_str(json)?))
+    }
+
+    /// Returns an error instead of the fallback snapshot when the remote catalog is unreachable
+    pub fn without_fallback(mut self) -> Self {
+        self.fallback = None;
+        self
+    }
+
+    /// Key sent in the `x-api-key` header of the write requests to a self-hosted catalog
+    pub fn with_api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
     pub fn build(self) -> BlockscoutChainsClient {
         let retry_policy = ExponentialBackoff::builder().build_with_max_retries(self.max_retries);
-        let client = ClientBuilder::new(reqwest::Client::new())
+        let reqwest_client = reqwest::Client::new();
+        let client = ClientBuilder::new(reqwest_client.clone())
             .with(RetryTransientMiddleware::new_with_policy(retry_policy))
             .build();
+        let client_without_retries = ClientBuilder::new(reqwest_client).build();
+        let cache = self
+            .cache_enabled
+            .then(|| ChainsCache::new(self.cache_ttl, self.cache_dir));
         BlockscoutChainsClient {
             client,
+            client_without_retries,
             url: self.url,
+            cache,
+            fallback: self.fallback,
+            api_key: self.api_key,
         }
     }
 }
@@ -61,13 +206,20 @@ impl Default for BlockscoutChainsClientBuilder {
         Self {
             url: CHAINS_URL.to_string(),
             max_retries: 3,
+            cache_enabled: false,
+            cache_ttl: DEFAULT_CACHE_TTL,
+            cache_dir: None,
+            fallback: Some(bundled_snapshot()),
+            api_key: None,
         }
     }
 }
 
-pub type BlockscoutChains = HashMap<String, BlockscoutChainData>;
+fn bundled_snapshot() -> BlockscoutChains {
+    serde_json::from_str(BUNDLED_SNAPSHOT).expect("bundled chains snapshot is valid")
+}
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockscoutChainData {
     pub name: String,
@@ -81,14 +233,29 @@ pub struct BlockscoutChainData {
     pub logo: String,
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum Ecosystem {
     Single(String),
     Multiple(Vec<String>),
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+impl Ecosystem {
+    pub fn names(&self) -> &[String] {
+        match self {
+            Ecosystem::Single(name) => std::slice::from_ref(name),
+            Ecosystem::Multiple(names) => names,
+        }
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names()
+            .iter()
+            .any(|value| value.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ExplorerConfig {
     pub url: String,
@@ -103,10 +270,174 @@ mod tests {
     async fn test_get_blockscout_chains() {
         let chains = BlockscoutChainsClient::builder()
             .with_max_retries(0)
+            .without_fallback()
             .build()
             .fetch_all()
             .await
             .unwrap();
         assert!(!chains.is_empty());
     }
+
+    fn chains() -> BlockscoutChains {
+        serde_json::from_value(serde_json::json!({
+            "1": {
+                "name": "Ethereum",
+                "description": "Ethereum mainnet",
+                "ecosystem": "Ethereum",
+                "isTestnet": false,
+                "layer": 1,
+                "rollupType": null,
+                "website": "https://ethereum.org",
+                "explorers": [{"url": "https://eth.blockscout.com", "hostedBy": "blockscout"}],
+                "logo": "https://example.com/ethereum.svg"
+            }
+        }))
+        .unwrap()
+    }
+
+    async fn mock_catalog(expected_requests: u64) -> wiremock::MockServer {
+        use wiremock::{matchers, Mock, ResponseTemplate};
+
+        let server = wiremock::MockServer::start().await;
+        Mock::given(matchers::method("GET"))
+            .and(matchers::header("If-None-Match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .mount(&server)
+            .await;
+        Mock::given(matchers::method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .set_body_json(chains()),
+            )
+            .expect(expected_requests)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn cache_revalidates_with_etag() {
+        let server = mock_catalog(1).await;
+        let client = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url(server.uri())
+            .with_cache_ttl(Duration::ZERO)
+            .build();
+
+        assert_eq!(client.fetch_all().await.unwrap(), chains());
+        // The cache is already stale, so the catalog is revalidated and `304` is returned
+        assert_eq!(client.fetch_all().await.unwrap(), chains());
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].headers.get("If-None-Match").unwrap(), "\"v1\"");
+    }
+
+    #[tokio::test]
+    async fn cache_is_persisted_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = mock_catalog(1).await;
+        let client = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url(server.uri())
+            .with_cache_dir(dir.path().to_path_buf())
+            .build();
+        assert_eq!(client.fetch_all().await.unwrap(), chains());
+        assert_eq!(client.fetch_all().await.unwrap(), chains());
+
+        // The fresh catalog is loaded from disk without any requests
+        let unreachable = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url("http://127.0.0.1:1".to_string())
+            .with_cache_dir(dir.path().to_path_buf())
+            .build();
+        assert_eq!(unreachable.fetch_all().await.unwrap(), chains());
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    /// Regenerates the bundled snapshot from the remote catalog:
+    /// `cargo test -p blockscout-chains update_bundled_snapshot -- --ignored`
+    #[tokio::test]
+    #[ignore = "requires network access, overwrites the bundled snapshot"]
+    async fn update_bundled_snapshot() {
+        let chains = BlockscoutChainsClient::builder()
+            .without_fallback()
+            .build()
+            .fetch_all()
+            .await
+            .unwrap();
+        let json = serde_json::to_string_pretty(&serde_json::to_value(chains).unwrap()).unwrap();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/snapshot/chains.json");
+        std::fs::write(path, json + "\n").unwrap();
+    }
+
+    #[tokio::test]
+    async fn fallback_is_used_when_unreachable() {
+        let client = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url("http://127.0.0.1:1".to_string())
+            .without_fallback()
+            .build();
+        assert!(client.fetch_all().await.is_err());
+
+        // The bundled snapshot is used by default
+        let client = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url("http://127.0.0.1:1".to_string())
+            .build();
+        let chains = client.fetch_all().await.unwrap();
+        assert_eq!(chains, bundled_snapshot());
+        assert_eq!(chains.by_chain_id(1).unwrap().name, "Ethereum");
+
+        let client = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url("http://127.0.0.1:1".to_string())
+            .with_fallback_json(&serde_json::to_string(&chains()).unwrap())
+            .unwrap()
+            .build();
+        assert_eq!(client.fetch_all().await.unwrap(), chains());
+    }
+
+    #[test]
+    fn lookup_helpers_work() {
+        let chains: BlockscoutChains = serde_json::from_value(serde_json::json!({
+            "1": {
+                "name": "Ethereum", "description": "", "ecosystem": "Ethereum",
+                "isTestnet": false, "layer": 1, "website": "", "explorers": [], "logo": ""
+            },
+            "10": {
+                "name": "OP Mainnet", "description": "", "ecosystem": ["Optimism", "Superchain"],
+                "layer": 2, "rollupType": "optimistic", "website": "", "explorers": [], "logo": ""
+            },
+            "11155420": {
+                "name": "OP Sepolia", "description": "", "ecosystem": "Optimism",
+                "isTestnet": true, "layer": 2, "rollupType": "optimistic",
+                "website": "", "explorers": [], "logo": ""
+            },
+            "324": {
+                "name": "zkSync Era", "description": "", "ecosystem": "zkSync",
+                "layer": 2, "rollupType": "zk", "website": "", "explorers": [], "logo": ""
+            }
+        }))
+        .unwrap();
+        let sorted_ids = |chains: BlockscoutChains| {
+            let mut ids = chains.ids().map(u64::from).collect::<Vec<_>>();
+            ids.sort();
+            ids
+        };
+
+        assert_eq!(chains.by_chain_id(10).unwrap().name, "OP Mainnet");
+        assert!(chains.by_chain_id(ChainId(5)).is_none());
+        assert_eq!(sorted_ids(chains.mainnets_only()), vec![1, 10, 324]);
+        assert_eq!(
+            sorted_ids(chains.by_ecosystem("optimism")),
+            vec![10, 11155420]
+        );
+        assert_eq!(sorted_ids(chains.by_ecosystem("Superchain")), vec![10]);
+        assert_eq!(sorted_ids(chains.rollups_of_type("zk")), vec![324]);
+        assert_eq!(
+            sorted_ids(chains.mainnets_only().rollups_of_type("optimistic")),
+            vec![10]
+        );
+    }
 }
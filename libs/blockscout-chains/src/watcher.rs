@@ -0,0 +1,234 @@
+use crate::{BlockscoutChainData, BlockscoutChains, BlockscoutChainsClient, ChainId};
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::broadcast, task::JoinHandle};
+
+const DEFAULT_CHANNEL_CAPACITY: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedChain {
+    pub id: ChainId,
+    pub old: BlockscoutChainData,
+    pub new: BlockscoutChainData,
+}
+
+/// Difference between two versions of the catalog
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainsDiff {
+    pub added: BlockscoutChains,
+    pub removed: BlockscoutChains,
+    pub changed: Vec<ChangedChain>,
+}
+
+impl ChainsDiff {
+    pub fn compute(old: &BlockscoutChains, new: &BlockscoutChains) -> Self {
+        let added = new.filter_by_id(|id| old.by_chain_id(id).is_none());
+        let removed = old.filter_by_id(|id| new.by_chain_id(id).is_none());
+        let mut changed = new
+            .iter()
+            .filter_map(|(id, chain)| {
+                let old_chain = old.by_chain_id(*id)?;
+                (old_chain != chain).then(|| ChangedChain {
+                    id: *id,
+                    old: old_chain.clone(),
+                    new: chain.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        changed.sort_by_key(|chain| chain.id);
+        Self {
+            added,
+            removed,
+            changed,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZeroIntervalError;
+
+impl std::fmt::Display for ZeroIntervalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "chains watcher interval must be non-zero")
+    }
+}
+
+impl std::error::Error for ZeroIntervalError {}
+
+/// Periodically refetches the catalog and notifies the subscribers about the changes.
+/// The first successful fetch is reported with all the chains being added.
+/// The fallback snapshot returned while the remote catalog is unreachable is not compared
+/// with the current catalog, as it is usually outdated and would produce spurious changes.
+pub struct ChainsWatcher {
+    client: BlockscoutChainsClient,
+    interval: Duration,
+    sender: broadcast::Sender<Arc<ChainsDiff>>,
+}
+
+impl ChainsWatcher {
+    pub fn new(
+        client: BlockscoutChainsClient,
+        interval: Duration,
+    ) -> Result<Self, ZeroIntervalError> {
+        if interval.is_zero() {
+            return Err(ZeroIntervalError);
+        }
+        let (sender, _) = broadcast::channel(DEFAULT_CHANNEL_CAPACITY);
+        Ok(Self {
+            client,
+            interval,
+            sender,
+        })
+    }
+
+    /// Receivers lagging behind by more than the channel capacity skip the oldest diffs
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<ChainsDiff>> {
+        self.sender.subscribe()
+    }
+
+    pub fn spawn(self) -> JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+
+    pub async fn run(self) {
+        let mut current = BlockscoutChains::default();
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            interval.tick().await;
+            let chains = match self.client.fetch_all_with_source().await {
+                Ok((_, true)) => continue,
+                Ok((chains, false)) => chains,
+                Err(err) => {
+                    tracing::warn!(err = ?err, "failed to fetch blockscout chains");
+                    continue;
+                }
+            };
+            let diff = ChainsDiff::compute(&current, &chains);
+            current = chains;
+            if !diff.is_empty() {
+                tracing::info!(
+                    added = diff.added.len(),
+                    removed = diff.removed.len(),
+                    changed = diff.changed.len(),
+                    "blockscout chains changed"
+                );
+                // Sending fails only if there are no subscribers at the moment
+                let _ = self.sender.send(Arc::new(diff));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+    fn chains(value: serde_json::Value) -> BlockscoutChains {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn chain(name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "name": name, "description": "", "ecosystem": "Ethereum",
+            "website": "", "explorers": [], "logo": ""
+        })
+    }
+
+    #[test]
+    fn diff_works() {
+        let old = chains(serde_json::json!({"1": chain("Ethereum"), "5": chain("Goerli")}));
+        let new = chains(serde_json::json!({"1": chain("Ethereum Mainnet"), "10": chain("OP")}));
+
+        let diff = ChainsDiff::compute(&old, &new);
+        assert_eq!(diff.added.ids().collect::<Vec<_>>(), vec![ChainId(10)]);
+        assert_eq!(diff.removed.ids().collect::<Vec<_>>(), vec![ChainId(5)]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].id, ChainId(1));
+        assert_eq!(diff.changed[0].old.name, "Ethereum");
+        assert_eq!(diff.changed[0].new.name, "Ethereum Mainnet");
+        assert!(ChainsDiff::compute(&new, &new).is_empty());
+    }
+
+    #[tokio::test]
+    async fn watcher_notifies_subscribers() {
+        let server = MockServer::start().await;
+        let respond_with = |value| ResponseTemplate::new(200).set_body_json(value);
+        Mock::given(matchers::method("GET"))
+            .respond_with(respond_with(serde_json::json!({"1": chain("Ethereum")})))
+            .mount(&server)
+            .await;
+
+        let client = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url(server.uri())
+            .build();
+        let watcher = ChainsWatcher::new(client, Duration::from_millis(50)).unwrap();
+        let mut receiver = watcher.subscribe();
+        let handle = watcher.spawn();
+
+        let diff = receiver.recv().await.unwrap();
+        assert_eq!(diff.added.ids().collect::<Vec<_>>(), vec![ChainId(1)]);
+
+        server.reset().await;
+        Mock::given(matchers::method("GET"))
+            .respond_with(respond_with(
+                serde_json::json!({"1": chain("Ethereum"), "10": chain("OP")}),
+            ))
+            .mount(&server)
+            .await;
+        // Unchanged catalog is not reported, so the next diff contains only the new chain
+        let diff = receiver.recv().await.unwrap();
+        assert_eq!(diff.added.ids().collect::<Vec<_>>(), vec![ChainId(10)]);
+        assert!(diff.removed.is_empty() && diff.changed.is_empty());
+
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn fallback_snapshot_is_not_diffed() {
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let client = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url(server.uri())
+            .with_fallback(chains(serde_json::json!({"5": chain("Goerli")})))
+            .build();
+        let watcher = ChainsWatcher::new(client, Duration::from_millis(50)).unwrap();
+        let mut receiver = watcher.subscribe();
+        let handle = watcher.spawn();
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(receiver.try_recv().is_err());
+
+        server.reset().await;
+        Mock::given(matchers::method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"1": chain("Ethereum")})),
+            )
+            .mount(&server)
+            .await;
+        let diff = receiver.recv().await.unwrap();
+        assert_eq!(diff.added.ids().collect::<Vec<_>>(), vec![ChainId(1)]);
+        assert!(diff.removed.is_empty());
+
+        handle.abort();
+    }
+
+    #[test]
+    fn zero_interval_is_rejected() {
+        let client = BlockscoutChainsClient::builder().build();
+        assert_eq!(
+            ChainsWatcher::new(client, Duration::ZERO).err(),
+            Some(ZeroIntervalError)
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{BlockscoutChainData, BlockscoutChainsClient, ChainId, ExplorerConfig};
+use reqwest::Method;
+use serde::Serialize;
+
+const API_KEY_NAME: &str = "x-api-key";
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RegisterChainRequest<'a> {
+    chain_id: ChainId,
+    #[serde(flatten)]
+    chain: &'a BlockscoutChainData,
+}
+
+/// Write operations supported by self-hosted catalogs. The requests are authenticated
+/// with the key set via [`crate::BlockscoutChainsClientBuilder::with_api_key`].
+impl BlockscoutChainsClient {
+    /// `POST {url}` - adds a new chain to the catalog
+    pub async fn register_chain(
+        &self,
+        id: ChainId,
+        chain: &BlockscoutChainData,
+    ) -> Result<(), reqwest_middleware::Error> {
+        let body = RegisterChainRequest {
+            chain_id: id,
+            chain,
+        };
+        self.send_write(Method::POST, self.url.clone(), &body).await
+    }
+
+    /// `PUT {url}/{id}` - replaces the data of an existing chain
+    pub async fn update_chain(
+        &self,
+        id: ChainId,
+        chain: &BlockscoutChainData,
+    ) -> Result<(), reqwest_middleware::Error> {
+        let url = format!("{}/{id}", self.url);
+        self.send_write(Method::PUT, url, chain).await
+    }
+
+    /// `PUT {url}/{id}/explorers` - replaces the explorers of an existing chain
+    pub async fn update_explorers(
+        &self,
+        id: ChainId,
+        explorers: &[ExplorerConfig],
+    ) -> Result<(), reqwest_middleware::Error> {
+        let url = format!("{}/{id}/explorers", self.url);
+        self.send_write(Method::PUT, url, explorers).await
+    }
+
+    async fn send_write<B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        url: String,
+        body: &B,
+    ) -> Result<(), reqwest_middleware::Error> {
+        let client = if method.is_idempotent() {
+            &self.client
+        } else {
+            &self.client_without_retries
+        };
+        let mut request = client.request(method, url).json(body);
+        if let Some(api_key) = &self.api_key {
+            request = request.header(API_KEY_NAME, api_key);
+        }
+        request.send().await?.error_for_status()?;
+        // The catalog has been changed, so the cached one should not be served anymore
+        if let Some(cache) = &self.cache {
+            cache.invalidate().await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+    fn chain() -> BlockscoutChainData {
+        serde_json::from_value(serde_json::json!({
+            "name": "Local", "description": "", "ecosystem": "Ethereum",
+            "website": "", "explorers": [], "logo": ""
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn write_requests_are_authenticated() {
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("POST"))
+            .and(matchers::path("/api/chains"))
+            .and(matchers::header(API_KEY_NAME, "secret"))
+            .and(matchers::body_partial_json(
+                serde_json::json!({"chainId": 31337, "name": "Local"}),
+            ))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(matchers::method("PUT"))
+            .and(matchers::path("/api/chains/31337/explorers"))
+            .and(matchers::header(API_KEY_NAME, "secret"))
+            .and(matchers::body_json(serde_json::json!([
+                {"url": "http://localhost", "hostedBy": "self"}
+            ])))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(matchers::any())
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/api/chains", server.uri());
+        let client = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url(url.clone())
+            .with_api_key("secret".to_string())
+            .build();
+        client
+            .register_chain(ChainId(31337), &chain())
+            .await
+            .unwrap();
+        let explorers = [ExplorerConfig {
+            url: "http://localhost".to_string(),
+            hosted_by: "self".to_string(),
+        }];
+        client
+            .update_explorers(ChainId(31337), &explorers)
+            .await
+            .unwrap();
+
+        let unauthenticated = BlockscoutChainsClient::builder()
+            .with_max_retries(0)
+            .with_url(url)
+            .build();
+        assert!(unauthenticated
+            .update_chain(ChainId(31337), &chain())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn only_idempotent_writes_are_retried() {
+        let server = MockServer::start().await;
+        Mock::given(matchers::method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(matchers::method("PUT"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = BlockscoutChainsClient::builder()
+            .with_max_retries(1)
+            .with_url(format!("{}/api/chains", server.uri()))
+            .build();
+        assert!(client
+            .register_chain(ChainId(31337), &chain())
+            .await
+            .is_err());
+        assert!(client.update_chain(ChainId(31337), &chain()).await.is_err());
+    }
+}
@@ -7,7 +7,7 @@
  * Contact: you@your-company.com
  * Generated by: https://openapi-generator.tech
  */
-use reqwest_middleware::ClientBuilder;
+use crate::client::ClientBuilder;
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use url::Url;
 
@@ -36,6 +36,13 @@ impl Configuration {
         Configuration::default().with_base_path(base_path)
     }
 
+    /// Opt-in alternative to [`Configuration::new`], which uses the client built by `builder`
+    /// (e.g., `ClientBuilder::default()` with retries, a timeout and instrumentation)
+    /// instead of the plain client without any middlewares.
+    pub fn new_with_client_builder(base_path: Url, builder: ClientBuilder) -> Configuration {
+        Configuration::new(base_path).with_client_builder(builder)
+    }
+
     pub fn with_base_path(mut self, base_path: Url) -> Configuration {
         base_path
             .as_str()
@@ -55,7 +62,7 @@ impl Configuration {
     pub fn with_client_max_retry(self, max_retry: u32) -> Configuration {
         let retry_policy = ExponentialBackoff::builder().build_with_max_retries(max_retry);
         self.with_client(
-            ClientBuilder::new(reqwest::Client::new())
+            reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
                 .with(RetryTransientMiddleware::new_with_policy(retry_policy))
                 .build(),
         )
@@ -104,10 +104,12 @@ pub mod addresses_api;
 pub mod blocks_api;
 pub mod config_api;
 pub mod health_api;
+pub mod internal_transactions_api;
 pub mod main_page_api;
 pub mod search_api;
 pub mod smart_contracts_api;
 pub mod stats_api;
+pub mod token_transfers_api;
 pub mod tokens_api;
 pub mod transactions_api;
 pub mod withdrawals_api;
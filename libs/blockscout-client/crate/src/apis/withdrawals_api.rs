@@ -20,6 +20,13 @@ pub enum GetWithdrawalsError {
     UnknownValue(serde_json::Value),
 }
 
+/// struct for typed errors of method [`get_withdrawals_counters`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GetWithdrawalsCountersError {
+    UnknownValue(serde_json::Value),
+}
+
 pub async fn get_withdrawals(
     configuration: &configuration::Configuration,
 ) -> Result<models::GetBlockWithdrawals200Response, Error<GetWithdrawalsError>> {
@@ -55,3 +62,42 @@ pub async fn get_withdrawals(
         Err(Error::ResponseError(local_var_error))
     }
 }
+
+pub async fn get_withdrawals_counters(
+    configuration: &configuration::Configuration,
+) -> Result<models::GetWithdrawalsCounters200Response, Error<GetWithdrawalsCountersError>> {
+    let local_var_configuration = configuration;
+
+    let local_var_client = &local_var_configuration.client;
+
+    let local_var_uri_str = format!(
+        "{}/api/v2/withdrawals/counters",
+        local_var_configuration.base_path
+    );
+    let mut local_var_req_builder =
+        local_var_client.request(reqwest::Method::GET, local_var_uri_str.as_str());
+
+    if let Some(ref local_var_user_agent) = local_var_configuration.user_agent {
+        local_var_req_builder =
+            local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = local_var_client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        serde_json::from_str(&local_var_content).map_err(Error::from)
+    } else {
+        let local_var_entity: Option<GetWithdrawalsCountersError> =
+            serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent {
+            status: local_var_status,
+            content: local_var_content,
+            entity: local_var_entity,
+        };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
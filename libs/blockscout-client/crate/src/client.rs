@@ -0,0 +1,129 @@
+//! HTTP client used by [`Configuration`] with retries, rate limiting
+//! and instrumentation set up, so that the consuming services do not wire the middlewares themselves.
+
+use crate::{
+    apis::configuration::Configuration,
+    instrumentation::InstrumentationMiddleware,
+    schema_validation::{SchemaValidationMiddleware, SchemaViolation, ViolationCallback},
+};
+use reqwest_middleware::{ClientWithMiddleware, Middleware};
+use reqwest_rate_limiter::DefaultRateLimiterMiddleware;
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use std::{num::NonZeroU32, sync::Arc, time::Duration};
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Builds the client used by the generated methods. The builder is cheap to clone and
+/// the rate limiter is shared between the clones, so per-call overrides can be made with
+/// `configuration.clone().with_client_builder(builder.clone().max_retries(0))`
+/// without bypassing the rate limit.
+#[derive(Clone)]
+pub struct ClientBuilder {
+    max_retries: u32,
+    timeout: Duration,
+    rate_limiter: Option<Arc<DefaultRateLimiterMiddleware>>,
+    instrumentation: bool,
+    on_schema_violation: Option<ViolationCallback>,
+    middleware_stack: Vec<Arc<dyn Middleware>>,
+}
+
+impl Default for ClientBuilder {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            timeout: DEFAULT_TIMEOUT,
+            rate_limiter: None,
+            instrumentation: true,
+            on_schema_violation: None,
+            middleware_stack: vec![],
+        }
+    }
+}
+
+impl ClientBuilder {
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Timeout of a single attempt
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn rate_limit_per_second(self, requests: NonZeroU32) -> Self {
+        self.rate_limiter(DefaultRateLimiterMiddleware::per_second(requests))
+    }
+
+    pub fn rate_limit_per_minute(self, requests: NonZeroU32) -> Self {
+        self.rate_limiter(DefaultRateLimiterMiddleware::per_minute(requests))
+    }
+
+    fn rate_limiter(mut self, rate_limiter: DefaultRateLimiterMiddleware) -> Self {
+        self.rate_limiter = Some(Arc::new(rate_limiter));
+        self
+    }
+
+    pub fn without_rate_limit(mut self) -> Self {
+        self.rate_limiter = None;
+        self
+    }
+
+    /// Disables tracing spans and metrics of the requests
+    pub fn without_instrumentation(mut self) -> Self {
+        self.instrumentation = false;
+        self
+    }
+
+    /// Enables the strict mode: successful responses are compared with the bundled
+    /// OpenAPI schema, and unknown or missing fields are reported to `on_violation`
+    pub fn with_schema_validation(
+        mut self,
+        on_violation: impl Fn(SchemaViolation) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_schema_violation = Some(Arc::new(on_violation));
+        self
+    }
+
+    pub fn with_middleware<M: Middleware>(self, middleware: M) -> Self {
+        self.with_arc_middleware(Arc::new(middleware))
+    }
+
+    pub fn with_arc_middleware<M: Middleware>(mut self, middleware: Arc<M>) -> Self {
+        self.middleware_stack.push(middleware);
+        self
+    }
+
+    /// The middlewares are applied to every retry attempt,
+    /// so each attempt is rate limited and measured separately
+    pub fn build(self) -> ClientWithMiddleware {
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .expect("client configuration is valid");
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(self.max_retries);
+        let mut client_builder = reqwest_middleware::ClientBuilder::new(client)
+            .with(RetryTransientMiddleware::new_with_policy(retry_policy));
+        if let Some(rate_limiter) = self.rate_limiter {
+            client_builder = client_builder.with_arc(rate_limiter);
+        }
+        if self.instrumentation {
+            client_builder = client_builder.with(InstrumentationMiddleware);
+        }
+        if let Some(on_violation) = self.on_schema_violation {
+            client_builder = client_builder.with(SchemaValidationMiddleware::new(on_violation));
+        }
+        for middleware in self.middleware_stack {
+            client_builder = client_builder.with_arc(middleware);
+        }
+        client_builder.build()
+    }
+}
+
+impl Configuration {
+    pub fn with_client_builder(self, builder: ClientBuilder) -> Configuration {
+        self.with_client(builder.build())
+    }
+}
@@ -0,0 +1,90 @@
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use std::time::Instant;
+use tracing::Instrument;
+
+#[cfg(feature = "metrics")]
+mod metrics {
+    use once_cell::sync::Lazy;
+    use prometheus::{register_histogram_vec, HistogramVec};
+
+    pub static REQUEST_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+        register_histogram_vec!(
+            "blockscout_client_request_duration_seconds",
+            "duration of the requests to blockscout api",
+            &["method", "endpoint", "status"],
+        )
+        .unwrap()
+    });
+}
+
+/// Wraps each request into a tracing span and records its duration
+/// in the `blockscout_client_request_duration_seconds` histogram (with `metrics` feature)
+pub struct InstrumentationMiddleware;
+
+#[async_trait::async_trait]
+impl Middleware for InstrumentationMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let method = req.method().to_string();
+        let endpoint = endpoint_label(req.url().path());
+        let span = tracing::debug_span!(
+            "blockscout_client_request",
+            method = %method,
+            endpoint = %endpoint,
+            status = tracing::field::Empty,
+        );
+
+        let start = Instant::now();
+        let result = next.run(req, extensions).instrument(span.clone()).await;
+        let status = match &result {
+            Ok(response) => response.status().as_u16().to_string(),
+            Err(_) => "error".to_string(),
+        };
+        span.record("status", status.as_str());
+        #[cfg(feature = "metrics")]
+        metrics::REQUEST_DURATION
+            .with_label_values(&[&method, &endpoint, &status])
+            .observe(start.elapsed().as_secs_f64());
+        #[cfg(not(feature = "metrics"))]
+        let _ = start;
+
+        result
+    }
+}
+
+/// Replaces hashes and numbers in the path with placeholders
+/// to keep the cardinality of the metric labels low
+fn endpoint_label(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if segment.starts_with("0x") {
+                "{hash}"
+            } else if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                "{number}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn endpoint_label_works() {
+        assert_eq!(endpoint_label("/api/v2/blocks"), "/api/v2/blocks");
+        assert_eq!(
+            endpoint_label("/api/v2/tokens/0xB87b96868644d99Cc70a8565BA7311482eDEBF6e/instances/1"),
+            "/api/v2/tokens/{hash}/instances/{number}"
+        );
+    }
+}
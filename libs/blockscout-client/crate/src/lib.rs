@@ -7,6 +7,16 @@ extern crate serde_json;
 extern crate url;
 
 pub mod apis;
+pub mod client;
+mod instrumentation;
 pub mod models;
+pub mod multi_client;
+pub mod pagination;
+pub mod schema_validation;
+#[cfg(feature = "websocket")]
+pub mod websocket;
 
 pub use apis::{configuration::Configuration, Error, ResponseContent};
+pub use client::ClientBuilder;
+pub use instrumentation::InstrumentationMiddleware;
+pub use multi_client::MultiClient;
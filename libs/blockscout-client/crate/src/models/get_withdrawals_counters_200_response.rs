@@ -0,0 +1,32 @@
+/*
+ * BlockScout API
+ *
+ * API for BlockScout web app
+ *
+ * The version of the OpenAPI document: 1.0.0
+ * Contact: you@your-company.com
+ * Generated by: https://openapi-generator.tech
+ */
+
+use crate::models;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GetWithdrawalsCounters200Response {
+    #[serde(rename = "withdrawals_count")]
+    pub withdrawals_count: String,
+    #[serde(rename = "withdrawals_sum")]
+    pub withdrawals_sum: String,
+}
+
+impl GetWithdrawalsCounters200Response {
+    pub fn new(
+        withdrawals_count: String,
+        withdrawals_sum: String,
+    ) -> GetWithdrawalsCounters200Response {
+        GetWithdrawalsCounters200Response {
+            withdrawals_count,
+            withdrawals_sum,
+        }
+    }
+}
@@ -92,6 +92,8 @@ pub mod get_txs_200_response;
 pub use self::get_txs_200_response::GetTxs200Response;
 pub mod get_txs_chart_200_response;
 pub use self::get_txs_chart_200_response::GetTxsChart200Response;
+pub mod get_withdrawals_counters_200_response;
+pub use self::get_withdrawals_counters_200_response::GetWithdrawalsCounters200Response;
 pub mod get_write_methods_200_response_inner;
 pub use self::get_write_methods_200_response_inner::GetWriteMethods200ResponseInner;
 pub mod holder;
@@ -0,0 +1,68 @@
+//! Executes the same request against several Blockscout instances.
+
+use crate::apis::configuration::Configuration;
+use futures::{stream, Future, StreamExt};
+use std::collections::BTreeMap;
+
+const DEFAULT_MAX_CONCURRENCY: usize = 10;
+
+/// Instances are identified by the names they are added with (e.g. chain ids)
+#[derive(Debug, Clone)]
+pub struct MultiClient {
+    instances: BTreeMap<String, Configuration>,
+    max_concurrency: usize,
+}
+
+impl Default for MultiClient {
+    fn default() -> Self {
+        Self {
+            instances: BTreeMap::new(),
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+        }
+    }
+}
+
+impl MultiClient {
+    pub fn new(instances: impl IntoIterator<Item = (String, Configuration)>) -> Self {
+        Self {
+            instances: instances.into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_instance(mut self, name: String, configuration: Configuration) -> Self {
+        self.instances.insert(name, configuration);
+        self
+    }
+
+    /// Maximum number of the requests executed at the same time
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    pub fn instances(&self) -> &BTreeMap<String, Configuration> {
+        &self.instances
+    }
+
+    /// Executes `request` against every instance and returns the results by the instance names.
+    /// A failure of one instance does not affect the others:
+    ///
+    /// ```ignore
+    /// let blocks = multi_client.execute(|config| blocks_api::get_blocks(config, None)).await;
+    /// ```
+    pub async fn execute<'a, F, Fut, R>(&'a self, request: F) -> BTreeMap<String, R>
+    where
+        F: Fn(&'a Configuration) -> Fut,
+        Fut: Future<Output = R> + 'a,
+    {
+        stream::iter(&self.instances)
+            .map(|(name, configuration)| {
+                let response = request(configuration);
+                async move { (name.clone(), response.await) }
+            })
+            .buffer_unordered(self.max_concurrency)
+            .collect()
+            .await
+    }
+}
@@ -0,0 +1,281 @@
+//! Streams over the paginated v2 list endpoints.
+//!
+//! The generated methods return only the first page. The streams below request
+//! the following pages lazily, passing `next_page_params` of the previous response
+//! as query parameters, until the last page (`next_page_params: null`) is reached.
+
+use crate::{
+    apis::{
+        addresses_api::{GetAddressLogsError, GetAddressTokenTransfersError, GetAddressTxsError},
+        configuration::Configuration,
+        internal_transactions_api::GetInternalTxsListError,
+        token_transfers_api::GetTokenTransfersListError,
+        tokens_api::GetTokenTokenTransfersError,
+        transactions_api::{GetLogsError, GetTokenTransfersError, GetTxsError},
+        urlencode,
+        withdrawals_api::GetWithdrawalsError,
+        Error, ResponseContent,
+    },
+    models,
+};
+use futures::{stream, Stream, TryStreamExt};
+use serde::{de::DeserializeOwned, Deserialize};
+
+#[derive(Debug, Deserialize)]
+struct Page<T> {
+    items: Vec<T>,
+    next_page_params: Option<serde_json::Value>,
+}
+
+/// Stream of the items of all pages of the endpoint at `path`
+/// (e.g. `/api/v2/transactions`). `query` is sent with every page request.
+pub fn paginate<'a, T, E>(
+    configuration: &'a Configuration,
+    path: String,
+    query: Vec<(String, String)>,
+) -> impl Stream<Item = Result<T, Error<E>>> + 'a
+where
+    T: DeserializeOwned + 'a,
+    E: DeserializeOwned + 'a,
+{
+    // `None` means the last page has been fetched, `Some(None)` - the first page is next
+    let initial: Option<Option<Vec<(String, String)>>> = Some(None);
+    stream::try_unfold(initial, move |state| {
+        let path = path.clone();
+        let query = query.clone();
+        async move {
+            let Some(page_params) = state else {
+                return Ok::<_, Error<E>>(None);
+            };
+            let mut query = query;
+            query.extend(page_params.unwrap_or_default());
+            let page: Page<T> = fetch_page(configuration, &path, &query).await?;
+            let next_state = match page.next_page_params {
+                Some(params) if !page.items.is_empty() => Some(Some(to_query(&params))),
+                _ => None,
+            };
+            Ok(Some((page.items, next_state)))
+        }
+    })
+    .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+async fn fetch_page<T, E>(
+    configuration: &Configuration,
+    path: &str,
+    query: &[(String, String)],
+) -> Result<Page<T>, Error<E>>
+where
+    T: DeserializeOwned,
+    E: DeserializeOwned,
+{
+    let local_var_uri_str = format!("{}{path}", configuration.base_path);
+    let mut local_var_req_builder = configuration
+        .client
+        .request(reqwest::Method::GET, local_var_uri_str.as_str())
+        .query(query);
+    if let Some(ref local_var_user_agent) = configuration.user_agent {
+        local_var_req_builder =
+            local_var_req_builder.header(reqwest::header::USER_AGENT, local_var_user_agent.clone());
+    }
+
+    let local_var_req = local_var_req_builder.build()?;
+    let local_var_resp = configuration.client.execute(local_var_req).await?;
+
+    let local_var_status = local_var_resp.status();
+    let local_var_content = local_var_resp.text().await?;
+
+    if !local_var_status.is_client_error() && !local_var_status.is_server_error() {
+        serde_json::from_str(&local_var_content).map_err(Error::from)
+    } else {
+        let local_var_entity: Option<E> = serde_json::from_str(&local_var_content).ok();
+        let local_var_error = ResponseContent {
+            status: local_var_status,
+            content: local_var_content,
+            entity: local_var_entity,
+        };
+        Err(Error::ResponseError(local_var_error))
+    }
+}
+
+/// `next_page_params` is a flat object, e.g. `{"block_number": 123, "index": 5}`
+fn to_query(params: &serde_json::Value) -> Vec<(String, String)> {
+    let serde_json::Value::Object(params) = params else {
+        return vec![];
+    };
+    params
+        .iter()
+        .filter_map(|(key, value)| {
+            let value = match value {
+                serde_json::Value::Null => return None,
+                serde_json::Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            Some((key.clone(), value))
+        })
+        .collect()
+}
+
+fn optional_query(params: &[(&str, Option<&str>)]) -> Vec<(String, String)> {
+    params
+        .iter()
+        .filter_map(|(key, value)| value.map(|value| (key.to_string(), value.to_string())))
+        .collect()
+}
+
+/// All pages of [`crate::apis::transactions_api::get_txs`]
+pub fn transactions<'a>(
+    configuration: &'a Configuration,
+    filter: Option<&str>,
+    r#type: Option<&str>,
+    method: Option<&str>,
+) -> impl Stream<Item = Result<models::Transaction, Error<GetTxsError>>> + 'a {
+    paginate(
+        configuration,
+        "/api/v2/transactions".to_string(),
+        optional_query(&[("filter", filter), ("type", r#type), ("method", method)]),
+    )
+}
+
+/// All pages of [`crate::apis::transactions_api::get_token_transfers`]
+pub fn transaction_token_transfers<'a>(
+    configuration: &'a Configuration,
+    transaction_hash: &str,
+    r#type: Option<&str>,
+) -> impl Stream<Item = Result<models::TokenTransfer, Error<GetTokenTransfersError>>> + 'a {
+    paginate(
+        configuration,
+        format!(
+            "/api/v2/transactions/{}/token-transfers",
+            urlencode(transaction_hash)
+        ),
+        optional_query(&[("type", r#type)]),
+    )
+}
+
+/// All pages of [`crate::apis::transactions_api::get_logs`]
+pub fn transaction_logs<'a>(
+    configuration: &'a Configuration,
+    transaction_hash: &str,
+) -> impl Stream<Item = Result<models::Log, Error<GetLogsError>>> + 'a {
+    paginate(
+        configuration,
+        format!("/api/v2/transactions/{}/logs", urlencode(transaction_hash)),
+        vec![],
+    )
+}
+
+/// All pages of [`crate::apis::addresses_api::get_address_txs`]
+pub fn address_transactions<'a>(
+    configuration: &'a Configuration,
+    address_hash: &str,
+    filter: Option<&str>,
+) -> impl Stream<Item = Result<models::Transaction, Error<GetAddressTxsError>>> + 'a {
+    paginate(
+        configuration,
+        format!("/api/v2/addresses/{}/transactions", urlencode(address_hash)),
+        optional_query(&[("filter", filter)]),
+    )
+}
+
+/// All pages of [`crate::apis::addresses_api::get_address_token_transfers`]
+pub fn address_token_transfers<'a>(
+    configuration: &'a Configuration,
+    address_hash: &str,
+    r#type: Option<&str>,
+    filter: Option<&str>,
+    token: Option<&str>,
+) -> impl Stream<Item = Result<models::TokenTransfer, Error<GetAddressTokenTransfersError>>> + 'a {
+    paginate(
+        configuration,
+        format!(
+            "/api/v2/addresses/{}/token-transfers",
+            urlencode(address_hash)
+        ),
+        optional_query(&[("type", r#type), ("filter", filter), ("token", token)]),
+    )
+}
+
+/// All pages of [`crate::apis::addresses_api::get_address_logs`]
+pub fn address_logs<'a>(
+    configuration: &'a Configuration,
+    address_hash: &str,
+) -> impl Stream<Item = Result<models::Log, Error<GetAddressLogsError>>> + 'a {
+    paginate(
+        configuration,
+        format!("/api/v2/addresses/{}/logs", urlencode(address_hash)),
+        vec![],
+    )
+}
+
+/// All pages of [`crate::apis::tokens_api::get_token_token_transfers`]
+pub fn token_transfers<'a>(
+    configuration: &'a Configuration,
+    address_hash: &str,
+) -> impl Stream<Item = Result<models::TokenTransfer, Error<GetTokenTokenTransfersError>>> + 'a {
+    paginate(
+        configuration,
+        format!("/api/v2/tokens/{}/transfers", urlencode(address_hash)),
+        vec![],
+    )
+}
+
+/// All pages of [`crate::apis::token_transfers_api::get_token_transfers_list`]
+pub fn all_token_transfers<'a>(
+    configuration: &'a Configuration,
+    r#type: Option<&str>,
+) -> impl Stream<Item = Result<models::TokenTransfer, Error<GetTokenTransfersListError>>> + 'a {
+    paginate(
+        configuration,
+        "/api/v2/token-transfers".to_string(),
+        optional_query(&[("type", r#type)]),
+    )
+}
+
+/// All pages of [`crate::apis::internal_transactions_api::get_internal_txs_list`]
+pub fn internal_transactions(
+    configuration: &Configuration,
+) -> impl Stream<Item = Result<models::InternalTransaction, Error<GetInternalTxsListError>>> + '_ {
+    paginate(
+        configuration,
+        "/api/v2/internal-transactions".to_string(),
+        vec![],
+    )
+}
+
+/// All pages of [`crate::apis::withdrawals_api::get_withdrawals`]
+pub fn withdrawals(
+    configuration: &Configuration,
+) -> impl Stream<Item = Result<models::Withdrawal, Error<GetWithdrawalsError>>> + '_ {
+    paginate(configuration, "/api/v2/withdrawals".to_string(), vec![])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn next_page_params_to_query() {
+        let params = serde_json::json!({
+            "block_number": 20078262,
+            "index": 129,
+            "hash": "0x01",
+            "fee": null,
+            "is_pending": false
+        });
+        let mut query = to_query(&params);
+        query.sort();
+        assert_eq!(
+            query,
+            vec![
+                ("block_number".to_string(), "20078262".to_string()),
+                ("hash".to_string(), "0x01".to_string()),
+                ("index".to_string(), "129".to_string()),
+                ("is_pending".to_string(), "false".to_string()),
+            ]
+        );
+        assert!(to_query(&serde_json::Value::Null).is_empty());
+    }
+}
@@ -0,0 +1,346 @@
+//! Strict mode comparing the responses with the bundled OpenAPI schema.
+//!
+//! The generated models ignore unknown fields and may silently get default values,
+//! so the differences between the schema and the actual Blockscout responses
+//! are reported via a callback instead of failing the requests.
+
+use reqwest::{Request, Response};
+use reqwest_middleware::{Middleware, Next};
+use serde_json::Value;
+use std::{
+    fmt,
+    sync::{Arc, OnceLock},
+};
+
+const BUNDLED_SCHEMA: &str = include_str!("../../swaggers/blockscout-api.yaml");
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaViolationKind {
+    /// The field is returned, but is not described in the schema
+    UnknownField,
+    /// The field is required by the schema, but is not returned
+    MissingField,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaViolation {
+    /// Path template of the endpoint, e.g. `/api/v2/blocks/{block_number_or_hash}`
+    pub endpoint: String,
+    /// JSON pointer to the field in the response, e.g. `/items/0/miner/hash`
+    pub pointer: String,
+    pub kind: SchemaViolationKind,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            SchemaViolationKind::UnknownField => "unknown field",
+            SchemaViolationKind::MissingField => "missing field",
+        };
+        write!(f, "{}: {kind} {}", self.endpoint, self.pointer)
+    }
+}
+
+pub type ViolationCallback = Arc<dyn Fn(SchemaViolation) + Send + Sync>;
+
+/// Validates successful JSON responses of the endpoints described in the schema.
+/// Responses of unknown endpoints are passed as is.
+pub struct SchemaValidationMiddleware {
+    schema: &'static OpenApiSchema,
+    on_violation: ViolationCallback,
+}
+
+impl SchemaValidationMiddleware {
+    pub fn new(on_violation: ViolationCallback) -> Self {
+        static SCHEMA: OnceLock<OpenApiSchema> = OnceLock::new();
+        let schema = SCHEMA.get_or_init(|| {
+            OpenApiSchema::new(
+                serde_yaml::from_str(BUNDLED_SCHEMA).expect("bundled schema is valid yaml"),
+            )
+        });
+        Self {
+            schema,
+            on_violation,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for SchemaValidationMiddleware {
+    async fn handle(
+        &self,
+        req: Request,
+        extensions: &mut http::Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<Response> {
+        let method = req.method().as_str().to_lowercase();
+        let path = req.url().path().to_string();
+        let response = next.run(req, extensions).await?;
+        let Some((endpoint, schema)) = self.schema.response_schema(&method, &path) else {
+            return Ok(response);
+        };
+        if !response.status().is_success() {
+            return Ok(response);
+        }
+
+        // The body can be read only once, so the response is rebuilt from the read bytes
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        if let Ok(value) = serde_json::from_slice::<Value>(&body) {
+            let mut violations = vec![];
+            self.schema
+                .validate(schema, &value, String::new(), &mut violations);
+            for (pointer, kind) in violations {
+                (self.on_violation)(SchemaViolation {
+                    endpoint: endpoint.to_string(),
+                    pointer,
+                    kind,
+                });
+            }
+        }
+
+        let mut rebuilt = http::Response::new(body);
+        *rebuilt.status_mut() = status;
+        *rebuilt.headers_mut() = headers;
+        Ok(Response::from(rebuilt))
+    }
+}
+
+struct OpenApiSchema {
+    document: Value,
+}
+
+impl OpenApiSchema {
+    fn new(document: Value) -> Self {
+        Self { document }
+    }
+
+    /// Finds the path template matching the end of the request path,
+    /// as the base path of the instance may contain a prefix
+    fn response_schema<'a>(&'a self, method: &str, path: &str) -> Option<(&'a str, &'a Value)> {
+        let path_segments = path.trim_end_matches('/').split('/').collect::<Vec<_>>();
+        let paths = self.document.get("paths")?.as_object()?;
+        paths
+            .iter()
+            .filter(|(template, _)| {
+                let template_segments = template.split('/').collect::<Vec<_>>();
+                template_segments.len() <= path_segments.len()
+                    && template_segments
+                        .iter()
+                        .rev()
+                        .zip(path_segments.iter().rev())
+                        .all(|(expected, actual)| expected == actual || expected.starts_with('{'))
+            })
+            // Prefer the templates with the most literal segments, e.g. `/tokens/{hash}/counters`
+            // over `/tokens/{hash}/{id}`
+            .max_by_key(|(template, _)| {
+                template
+                    .split('/')
+                    .filter(|segment| !segment.starts_with('{'))
+                    .count()
+            })
+            .and_then(|(template, item)| {
+                let schema = item
+                    .get(method)?
+                    .get("responses")?
+                    .get("200")?
+                    .get("content")?
+                    .get("application/json")?
+                    .get("schema")?;
+                Some((template.as_str(), schema))
+            })
+    }
+
+    fn resolve<'a>(&'a self, schema: &'a Value) -> &'a Value {
+        let mut schema = schema;
+        while let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let resolved = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.document.pointer(pointer));
+            match resolved {
+                Some(resolved) => schema = resolved,
+                None => break,
+            }
+        }
+        schema
+    }
+
+    fn validate(
+        &self,
+        schema: &Value,
+        value: &Value,
+        pointer: String,
+        violations: &mut Vec<(String, SchemaViolationKind)>,
+    ) {
+        let schema = self.resolve(schema);
+        // Variants are not distinguished, so only the common parts would be reliable
+        if schema.get("oneOf").is_some() || schema.get("anyOf").is_some() {
+            return;
+        }
+        match value {
+            Value::Object(object) => {
+                let mut properties = serde_json::Map::new();
+                let mut required = vec![];
+                let mut open = false;
+                self.collect_object_schema(schema, &mut properties, &mut required, &mut open);
+                if properties.is_empty() && required.is_empty() {
+                    // Free-form objects like `next_page_params`
+                    return;
+                }
+                for field in required {
+                    if !object.contains_key(&field) {
+                        violations.push((
+                            format!("{pointer}/{field}"),
+                            SchemaViolationKind::MissingField,
+                        ));
+                    }
+                }
+                for (field, field_value) in object {
+                    let field_pointer = format!("{pointer}/{field}");
+                    match properties.get(field) {
+                        Some(field_schema) => {
+                            self.validate(field_schema, field_value, field_pointer, violations)
+                        }
+                        None if !open => {
+                            violations.push((field_pointer, SchemaViolationKind::UnknownField))
+                        }
+                        None => {}
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(items_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.validate(items_schema, item, format!("{pointer}/{index}"), violations);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_object_schema(
+        &self,
+        schema: &Value,
+        properties: &mut serde_json::Map<String, Value>,
+        required: &mut Vec<String>,
+        open: &mut bool,
+    ) {
+        let schema = self.resolve(schema);
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            for part in parts {
+                self.collect_object_schema(part, properties, required, open);
+            }
+        }
+        if let Some(schema_properties) = schema.get("properties").and_then(Value::as_object) {
+            properties.extend(
+                schema_properties
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone())),
+            );
+        }
+        if let Some(schema_required) = schema.get("required").and_then(Value::as_array) {
+            required.extend(
+                schema_required
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string),
+            );
+        }
+        if matches!(schema.get("additionalProperties"), Some(value) if value != &Value::Bool(false))
+        {
+            *open = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn schema() -> OpenApiSchema {
+        let document = serde_yaml::from_str(
+            r#"
+paths:
+  /api/v2/blocks/{block_number_or_hash}:
+    get:
+      responses:
+        '200':
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Block'
+components:
+  schemas:
+    Block:
+      type: object
+      required:
+        - hash
+        - height
+      properties:
+        hash:
+          type: string
+        height:
+          type: integer
+        rewards:
+          type: array
+          items:
+            $ref: '#/components/schemas/Reward'
+    Reward:
+      type: object
+      required:
+        - type
+      properties:
+        type:
+          type: string
+"#,
+        )
+        .unwrap();
+        OpenApiSchema::new(document)
+    }
+
+    #[test]
+    fn validation_works() {
+        let schema = schema();
+        assert!(schema.response_schema("get", "/api/v2/blocks").is_none());
+        assert!(schema.response_schema("post", "/api/v2/blocks/1").is_none());
+        let (endpoint, response_schema) = schema
+            .response_schema("get", "/prefix/api/v2/blocks/1")
+            .unwrap();
+        assert_eq!(endpoint, "/api/v2/blocks/{block_number_or_hash}");
+
+        let response = serde_json::json!({
+            "hash": "0x01",
+            "size": 100,
+            "rewards": [{"type": "Miner"}, {"reward": "1"}]
+        });
+        let mut violations = vec![];
+        schema.validate(response_schema, &response, String::new(), &mut violations);
+        assert_eq!(
+            violations,
+            vec![
+                ("/height".to_string(), SchemaViolationKind::MissingField),
+                (
+                    "/rewards/1/type".to_string(),
+                    SchemaViolationKind::MissingField
+                ),
+                (
+                    "/rewards/1/reward".to_string(),
+                    SchemaViolationKind::UnknownField
+                ),
+                ("/size".to_string(), SchemaViolationKind::UnknownField),
+            ]
+        );
+    }
+
+    #[test]
+    fn bundled_schema_is_valid() {
+        let document: Value = serde_yaml::from_str(BUNDLED_SCHEMA).unwrap();
+        let schema = OpenApiSchema::new(document);
+        assert!(schema
+            .response_schema("get", "/api/v2/transactions")
+            .is_some());
+    }
+}
@@ -0,0 +1,287 @@
+//! Live updates from the Blockscout WebSocket API.
+//!
+//! Blockscout exposes Phoenix channels at `/socket/v2/websocket`. The client joins the channels
+//! of the requested subscriptions, keeps the connection alive with heartbeats, and reconnects
+//! with exponential backoff when the connection is lost. Events are delivered through a channel.
+
+use crate::{apis::configuration::Configuration, models};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+const SOCKET_PATH: &str = "/socket/v2/websocket?vsn=2.0.0";
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+const DEFAULT_MIN_RECONNECT_DELAY: Duration = Duration::from_secs(1);
+const DEFAULT_MAX_RECONNECT_DELAY: Duration = Duration::from_secs(60);
+const DEFAULT_CHANNEL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Subscription {
+    NewBlocks,
+    AddressTransactions { address_hash: String },
+}
+
+impl Subscription {
+    fn topic(&self) -> String {
+        match self {
+            Subscription::NewBlocks => "blocks:new_block".to_string(),
+            Subscription::AddressTransactions { address_hash } => {
+                format!("addresses:{}", address_hash.to_lowercase())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// The connection has been (re)established and the channels are joined.
+    /// Events emitted while disconnected are lost, so the consumers may need to catch up via REST.
+    Connected,
+    Disconnected {
+        reason: String,
+    },
+    NewBlock {
+        block: Box<models::Block>,
+    },
+    AddressTransactions {
+        address_hash: String,
+        transactions: Vec<models::Transaction>,
+    },
+    /// Message of the joined channels that is not recognized or could not be parsed
+    Other {
+        topic: String,
+        event: String,
+        payload: serde_json::Value,
+    },
+}
+
+pub struct WebSocketClient {
+    url: String,
+    subscriptions: Vec<Subscription>,
+    min_reconnect_delay: Duration,
+    max_reconnect_delay: Duration,
+    channel_capacity: usize,
+}
+
+impl WebSocketClient {
+    /// Connects to the same instance the REST `configuration` points to
+    pub fn new(configuration: &Configuration) -> Self {
+        let base_path = &configuration.base_path;
+        let base_path = if let Some(host) = base_path.strip_prefix("https://") {
+            format!("wss://{host}")
+        } else if let Some(host) = base_path.strip_prefix("http://") {
+            format!("ws://{host}")
+        } else {
+            base_path.clone()
+        };
+        Self {
+            url: format!("{base_path}{SOCKET_PATH}"),
+            subscriptions: vec![],
+            min_reconnect_delay: DEFAULT_MIN_RECONNECT_DELAY,
+            max_reconnect_delay: DEFAULT_MAX_RECONNECT_DELAY,
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+        }
+    }
+
+    pub fn subscribe(mut self, subscription: Subscription) -> Self {
+        self.subscriptions.push(subscription);
+        self
+    }
+
+    /// The delay starts with `min` and doubles after each failed attempt up to `max`
+    pub fn with_reconnect_delays(mut self, min: Duration, max: Duration) -> Self {
+        self.min_reconnect_delay = min;
+        self.max_reconnect_delay = max;
+        self
+    }
+
+    pub fn with_channel_capacity(mut self, capacity: usize) -> Self {
+        self.channel_capacity = capacity;
+        self
+    }
+
+    /// Spawns the connection task. The task stops once the receiver is dropped.
+    pub fn spawn(self) -> mpsc::Receiver<Event> {
+        let (sender, receiver) = mpsc::channel(self.channel_capacity);
+        tokio::spawn(self.run(sender));
+        receiver
+    }
+
+    async fn run(self, sender: mpsc::Sender<Event>) {
+        let mut delay = self.min_reconnect_delay;
+        loop {
+            let reason = match self.connect_and_listen(&sender, &mut delay).await {
+                Ok(()) => "connection closed".to_string(),
+                Err(ListenError::ReceiverDropped) => return,
+                Err(ListenError::WebSocket(err)) => err.to_string(),
+            };
+            if sender.send(Event::Disconnected { reason }).await.is_err() {
+                return;
+            }
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(self.max_reconnect_delay);
+        }
+    }
+
+    async fn connect_and_listen(
+        &self,
+        sender: &mpsc::Sender<Event>,
+        delay: &mut Duration,
+    ) -> Result<(), ListenError> {
+        let (socket, _) = tokio_tungstenite::connect_async(self.url.as_str()).await?;
+        let (mut write, mut read) = socket.split();
+
+        let mut message_ref = 0u64;
+        let mut next_ref = || {
+            message_ref += 1;
+            message_ref.to_string()
+        };
+        for subscription in &self.subscriptions {
+            let join_ref = next_ref();
+            let message =
+                serde_json::json!([join_ref, join_ref, subscription.topic(), "phx_join", {}]);
+            write.send(Message::Text(message.to_string())).await?;
+        }
+        *delay = self.min_reconnect_delay;
+        send(sender, Event::Connected).await?;
+
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = heartbeat.tick() => {
+                    let message = serde_json::json!([null, next_ref(), "phoenix", "heartbeat", {}]);
+                    write.send(Message::Text(message.to_string())).await?;
+                }
+                message = read.next() => {
+                    let text = match message.transpose()? {
+                        None | Some(Message::Close(_)) => return Ok(()),
+                        Some(Message::Text(text)) => text,
+                        Some(_) => continue,
+                    };
+                    if let Some(event) = parse_message(&text) {
+                        send(sender, event).await?;
+                    }
+                }
+            }
+        }
+    }
+}
+
+enum ListenError {
+    ReceiverDropped,
+    WebSocket(tokio_tungstenite::tungstenite::Error),
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for ListenError {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        ListenError::WebSocket(err)
+    }
+}
+
+async fn send(sender: &mpsc::Sender<Event>, event: Event) -> Result<(), ListenError> {
+    sender
+        .send(event)
+        .await
+        .map_err(|_| ListenError::ReceiverDropped)
+}
+
+/// `[join_ref, ref, topic, event, payload]` message of the Phoenix v2 serializer
+#[derive(Debug, Deserialize)]
+struct PhoenixMessage(
+    Option<String>,
+    Option<String>,
+    String,
+    String,
+    serde_json::Value,
+);
+
+#[derive(Debug, Deserialize)]
+struct NewBlockPayload {
+    block: Box<models::Block>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransactionsPayload {
+    transactions: Vec<models::Transaction>,
+}
+
+/// Returns `None` for the service messages (join and heartbeat replies)
+fn parse_message(text: &str) -> Option<Event> {
+    let PhoenixMessage(_, _, topic, event, payload) = serde_json::from_str(text).ok()?;
+    if topic == "phoenix" || event.starts_with("phx_") {
+        return None;
+    }
+
+    let typed = match (topic.split_once(':'), event.as_str()) {
+        (Some(("blocks", _)), "new_block") => {
+            serde_json::from_value(payload.clone())
+                .ok()
+                .map(|payload: NewBlockPayload| Event::NewBlock {
+                    block: payload.block,
+                })
+        }
+        (Some(("addresses", address_hash)), "transaction") => {
+            serde_json::from_value(payload.clone())
+                .ok()
+                .map(|payload: TransactionsPayload| Event::AddressTransactions {
+                    address_hash: address_hash.to_string(),
+                    transactions: payload.transactions,
+                })
+        }
+        _ => None,
+    };
+    Some(typed.unwrap_or(Event::Other {
+        topic,
+        event,
+        payload,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn websocket_url_works() {
+        let client = WebSocketClient::new(&Configuration::new(
+            "https://eth.blockscout.com/".parse().unwrap(),
+        ));
+        assert_eq!(
+            client.url,
+            "wss://eth.blockscout.com/socket/v2/websocket?vsn=2.0.0"
+        );
+        let client = WebSocketClient::new(&Configuration::new(
+            "http://localhost:4000".parse().unwrap(),
+        ));
+        assert_eq!(
+            client.url,
+            "ws://localhost:4000/socket/v2/websocket?vsn=2.0.0"
+        );
+    }
+
+    #[test]
+    fn parse_message_works() {
+        assert!(
+            parse_message(r#"["1","1","blocks:new_block","phx_reply",{"status":"ok"}]"#).is_none()
+        );
+        assert!(parse_message(r#"[null,"2","phoenix","phx_reply",{"status":"ok"}]"#).is_none());
+        assert!(parse_message("not a message").is_none());
+
+        let event =
+            parse_message(r#"["1",null,"addresses:0xabc","transaction",{"transactions":[]}]"#)
+                .unwrap();
+        assert!(matches!(
+            event,
+            Event::AddressTransactions { address_hash, transactions }
+                if address_hash == "0xabc" && transactions.is_empty()
+        ));
+
+        // Payloads not matching the models are still delivered
+        let event =
+            parse_message(r#"["1",null,"blocks:new_block","new_block",{"block":{}}]"#).unwrap();
+        assert!(matches!(event, Event::Other { event, .. } if event == "new_block"));
+    }
+}
@@ -84,6 +84,64 @@ async fn tokens(blockscout: Stubr) {
             .expect("Failed to get transactions");
 }
 
+#[rstest]
+#[tokio::test]
+async fn transactions_pagination(blockscout: Stubr) {
+    use futures::{StreamExt, TryStreamExt};
+
+    let config = get_config_from_stubr(&blockscout);
+    // The first page contains 50 items, so the second page should be requested
+    let transactions = blockscout_client::pagination::transactions(&config, None, None, None)
+        .take(60)
+        .try_collect::<Vec<_>>()
+        .await
+        .expect("Failed to get transactions");
+    assert_eq!(transactions.len(), 60);
+}
+
+#[rstest]
+#[tokio::test]
+async fn multi_client(blockscout: Stubr) {
+    let unavailable =
+        Configuration::new("http://127.0.0.1:1".parse().unwrap()).with_client_max_retry(0);
+    let multi_client = blockscout_client::MultiClient::new([
+        ("first".to_string(), get_config_from_stubr(&blockscout)),
+        ("second".to_string(), get_config_from_stubr(&blockscout)),
+        ("unavailable".to_string(), unavailable),
+    ])
+    .with_max_concurrency(2);
+
+    let results = multi_client.execute(health_api::health).await;
+    assert_eq!(
+        results.keys().collect::<Vec<_>>(),
+        vec!["first", "second", "unavailable"]
+    );
+    assert_eq!(results["first"].as_ref().unwrap().healthy, Some(true));
+    assert_eq!(results["second"].as_ref().unwrap().healthy, Some(true));
+    assert!(results["unavailable"].is_err());
+}
+
+#[rstest]
+#[tokio::test]
+async fn schema_validation(blockscout: Stubr) {
+    use std::sync::{Arc, Mutex};
+
+    let violations = Arc::new(Mutex::new(vec![]));
+    let builder = blockscout_client::ClientBuilder::default().with_schema_validation({
+        let violations = violations.clone();
+        move |violation| violations.lock().unwrap().push(violation)
+    });
+    let config = get_config_from_stubr(&blockscout).with_client_builder(builder);
+    // The response is read by the validation, but should still be returned as is
+    let blocks = blocks_api::get_blocks(&config, None)
+        .await
+        .expect("Failed to get blocks");
+    assert!(!blocks.items.is_empty());
+    for violation in violations.lock().unwrap().iter() {
+        assert_eq!(violation.endpoint, "/api/v2/blocks");
+    }
+}
+
 #[fixture]
 fn blockscout() -> Stubr {
     Stubr::start_blocking("tests/recorded/eth_blockscout_com")
@@ -82,6 +82,33 @@ pub async fn initialize_postgres<Migrator: MigratorTrait>(
     Ok(db)
 }
 
+/// Readiness check verifying that the database is reachable
+pub async fn check_connection(db: &DatabaseConnection) -> anyhow::Result<()> {
+    db.execute(Statement::from_string(
+        db.get_database_backend(),
+        "SELECT 1".to_string(),
+    ))
+    .await
+    .context("database is unreachable")?;
+    Ok(())
+}
+
+/// Readiness check verifying that all migrations have been applied
+pub async fn check_migrations<Migrator: MigratorTrait>(
+    db: &DatabaseConnection,
+) -> anyhow::Result<()> {
+    let pending = Migrator::get_pending_migrations(db)
+        .await
+        .context("cannot get pending migrations")?;
+    if !pending.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{} migrations are not applied",
+            pending.len()
+        ));
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
 pub struct DatabaseSettings {
@@ -0,0 +1,368 @@
+use super::{
+    probes::{LIVENESS_PATH, READINESS_PATH},
+    settings::ServerSettings,
+};
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{header, StatusCode},
+    middleware::Next,
+    web, HttpMessage, HttpResponse,
+};
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiKeyAuthSettings {
+    /// If enabled, all http requests except the ones to `public_paths`
+    /// must contain a valid api key.
+    /// The keys are not checked by the grpc server, so it must be disabled.
+    pub enabled: bool,
+    pub header: String,
+    /// Static keys by their names
+    pub keys: BTreeMap<String, StaticApiKeySettings>,
+    /// Limit applied to the keys without their own one (unlimited if not set)
+    pub default_rate_limit: Option<RateLimitSettings>,
+    pub public_paths: Vec<String>,
+}
+
+impl Default for ApiKeyAuthSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            header: "x-api-key".to_string(),
+            keys: Default::default(),
+            default_rate_limit: None,
+            public_paths: vec![
+                "/health".to_string(),
+                READINESS_PATH.to_string(),
+                LIVENESS_PATH.to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StaticApiKeySettings {
+    pub key: String,
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitSettings>,
+}
+
+/// Allows `requests` per `period`, replenished gradually (token bucket)
+#[serde_as]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitSettings {
+    pub requests: u32,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub period: Duration,
+}
+
+/// Authenticated api key; available to the handlers via `web::ReqData<ApiKey>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiKey {
+    pub name: String,
+    pub rate_limit: Option<RateLimitSettings>,
+}
+
+/// Source of the api keys not listed in the settings (e.g., stored in the database)
+pub trait ApiKeyStore: Send + Sync {
+    fn find<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<ApiKey>>>;
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorResponse {
+    message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AuthError {
+    MissingKey,
+    InvalidKey,
+    RateLimited { retry_after: Duration },
+    Internal,
+}
+
+impl AuthError {
+    fn into_response(self, header_name: &str) -> HttpResponse {
+        let (status, message) = match self {
+            AuthError::MissingKey => (
+                StatusCode::UNAUTHORIZED,
+                format!("{header_name} header is missing"),
+            ),
+            AuthError::InvalidKey => (StatusCode::UNAUTHORIZED, "invalid api key".to_string()),
+            AuthError::RateLimited { retry_after } => {
+                let retry_after = retry_after.as_secs_f64().ceil() as u64;
+                return HttpResponse::TooManyRequests()
+                    .insert_header((header::RETRY_AFTER, retry_after.max(1).to_string()))
+                    .json(ErrorResponse {
+                        message: "rate limit exceeded".to_string(),
+                    });
+            }
+            AuthError::Internal => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to check api key".to_string(),
+            ),
+        };
+        HttpResponse::build(status).json(ErrorResponse { message })
+    }
+}
+
+/// Validates the api keys of http requests and applies per-key rate limits.
+///
+/// Keys from the settings are checked first, then the optional [`ApiKeyStore`].
+#[derive(Clone)]
+pub struct ApiKeyAuth {
+    settings: Arc<ApiKeyAuthSettings>,
+    static_keys: Arc<HashMap<String, ApiKey>>,
+    store: Option<Arc<dyn ApiKeyStore>>,
+    limiter: Arc<RateLimiter>,
+}
+
+impl ApiKeyAuth {
+    pub fn new(settings: ApiKeyAuthSettings, store: Option<Arc<dyn ApiKeyStore>>) -> Self {
+        let static_keys = settings
+            .keys
+            .iter()
+            .map(|(name, key)| {
+                let api_key = ApiKey {
+                    name: name.clone(),
+                    rate_limit: key.rate_limit,
+                };
+                (key.key.clone(), api_key)
+            })
+            .collect();
+        Self {
+            settings: Arc::new(settings),
+            static_keys: Arc::new(static_keys),
+            store,
+            limiter: Default::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    /// Returns `None` for public paths accessible without the key
+    async fn authorize(&self, path: &str, key: Option<&str>) -> Result<Option<ApiKey>, AuthError> {
+        if self
+            .settings
+            .public_paths
+            .iter()
+            .any(|public| public == path)
+        {
+            return Ok(None);
+        }
+        let key = key.ok_or(AuthError::MissingKey)?;
+        let api_key = match self.static_keys.get(key) {
+            Some(api_key) => api_key.clone(),
+            None => match &self.store {
+                Some(store) => store
+                    .find(key)
+                    .await
+                    .map_err(|err| {
+                        tracing::error!(err = ?err, "failed to find api key");
+                        AuthError::Internal
+                    })?
+                    .ok_or(AuthError::InvalidKey)?,
+                None => return Err(AuthError::InvalidKey),
+            },
+        };
+
+        if let Some(rate_limit) = api_key.rate_limit.or(self.settings.default_rate_limit) {
+            self.limiter
+                .check(&api_key.name, &rate_limit, Instant::now())
+                .map_err(|retry_after| AuthError::RateLimited { retry_after })?;
+        }
+        Ok(Some(api_key))
+    }
+}
+
+/// The grpc server has no api key checks, so leaving it enabled would bypass the http auth
+pub(crate) fn validate_server_settings(settings: &ServerSettings) -> anyhow::Result<()> {
+    if settings.http.api_key_auth.enabled && settings.grpc.enabled {
+        anyhow::bail!(
+            "api key auth is supported only by the http server, grpc server must be disabled"
+        );
+    }
+    Ok(())
+}
+
+/// Http middleware using [`ApiKeyAuth`] stored in the app data
+pub(crate) async fn middleware(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    // The middleware is wrapped only if the auth is enabled, and the auth is stored along with it
+    if let Some(auth) = req.app_data::<web::Data<ApiKeyAuth>>().cloned() {
+        let key = req
+            .headers()
+            .get(&auth.settings.header)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        match auth.authorize(req.path(), key.as_deref()).await {
+            Ok(Some(api_key)) => {
+                req.extensions_mut().insert(api_key);
+            }
+            Ok(None) => {}
+            Err(err) => {
+                let response = err.into_response(&auth.settings.header);
+                return Ok(req.into_response(response).map_into_right_body());
+            }
+        }
+    }
+    next.call(req)
+        .await
+        .map(ServiceResponse::map_into_left_body::<BoxBody>)
+}
+
+#[derive(Default)]
+struct RateLimiter {
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    /// Consumes one request of the key; returns the time to wait for the next one otherwise
+    fn check(&self, name: &str, limit: &RateLimitSettings, now: Instant) -> Result<(), Duration> {
+        let capacity = f64::from(limit.requests);
+        let refill_rate = capacity / limit.period.as_secs_f64().max(f64::EPSILON);
+
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.entry(name.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated_at: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * refill_rate).min(capacity);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else if refill_rate > 0.0 {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / refill_rate))
+        } else {
+            Err(limit.period)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct TestStore;
+
+    impl ApiKeyStore for TestStore {
+        fn find<'a>(&'a self, key: &'a str) -> BoxFuture<'a, anyhow::Result<Option<ApiKey>>> {
+            Box::pin(async move {
+                Ok((key == "db-key").then(|| ApiKey {
+                    name: "db".to_string(),
+                    rate_limit: None,
+                }))
+            })
+        }
+    }
+
+    fn auth() -> ApiKeyAuth {
+        let settings = ApiKeyAuthSettings {
+            enabled: true,
+            keys: BTreeMap::from([(
+                "static".to_string(),
+                StaticApiKeySettings {
+                    key: "static-key".to_string(),
+                    rate_limit: None,
+                },
+            )]),
+            default_rate_limit: Some(RateLimitSettings {
+                requests: 1,
+                period: Duration::from_secs(60),
+            }),
+            ..Default::default()
+        };
+        ApiKeyAuth::new(settings, Some(Arc::new(TestStore)))
+    }
+
+    #[tokio::test]
+    async fn keys_are_validated() {
+        let auth = auth();
+        assert_eq!(Ok(None), auth.authorize("/health", None).await);
+        assert_eq!(
+            Err(AuthError::MissingKey),
+            auth.authorize("/api/v1/items", None).await
+        );
+        assert_eq!(
+            Err(AuthError::InvalidKey),
+            auth.authorize("/api/v1/items", Some("unknown")).await
+        );
+
+        let api_key = auth
+            .authorize("/api/v1/items", Some("static-key"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!("static", api_key.name);
+        let api_key = auth
+            .authorize("/api/v1/items", Some("db-key"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!("db", api_key.name);
+
+        // Default limit of one request per minute has been reached
+        assert!(matches!(
+            auth.authorize("/api/v1/items", Some("db-key")).await,
+            Err(AuthError::RateLimited { .. })
+        ));
+    }
+
+    #[test]
+    fn grpc_server_is_rejected_with_auth() {
+        let mut settings = ServerSettings::default();
+        settings.http.api_key_auth.enabled = true;
+        settings.grpc.enabled = true;
+        assert!(validate_server_settings(&settings).is_err());
+
+        settings.grpc.enabled = false;
+        assert!(validate_server_settings(&settings).is_ok());
+    }
+
+    #[test]
+    fn rate_limit_is_replenished() {
+        let limiter = RateLimiter::default();
+        let limit = RateLimitSettings {
+            requests: 2,
+            period: Duration::from_secs(10),
+        };
+        let now = Instant::now();
+
+        assert_eq!(Ok(()), limiter.check("key", &limit, now));
+        assert_eq!(Ok(()), limiter.check("key", &limit, now));
+        assert_eq!(
+            Err(Duration::from_secs(5)),
+            limiter.check("key", &limit, now)
+        );
+        // Limits are tracked per key
+        assert_eq!(Ok(()), limiter.check("other", &limit, now));
+
+        let now = now + Duration::from_secs(5);
+        assert_eq!(Ok(()), limiter.check("key", &limit, now));
+        assert!(limiter.check("key", &limit, now).is_err());
+    }
+}
@@ -1,19 +1,30 @@
 use super::{
+    api_key_auth::{self, ApiKeyAuth, ApiKeyStore},
     metrics::Metrics,
+    probes::{Probes, LIVENESS_PATH, READINESS_PATH},
     router::{configure_router, HttpRouter},
     settings::{MetricsSettings, ServerSettings},
+    shutdown::GracefulShutdownHandler,
     span_builder::CompactRootSpanBuilder,
     HttpServerSettings,
 };
-use actix_web::{middleware::Condition, App, HttpServer};
+use actix_web::{
+    middleware::{from_fn, Condition},
+    web, App, HttpServer,
+};
 use actix_web_prom::PrometheusMetrics;
-use std::net::SocketAddr;
+use std::{net::SocketAddr, sync::Arc};
 use tracing_actix_web::TracingLogger;
 
 pub struct LaunchSettings {
     pub service_name: String,
     pub server: ServerSettings,
     pub metrics: MetricsSettings,
+    pub graceful_shutdown: GracefulShutdownHandler,
+    pub probes: Probes,
+    /// Source of the api keys in addition to the ones from the settings
+    /// (used only if api key auth is enabled for the http server)
+    pub api_key_store: Option<Arc<dyn ApiKeyStore>>,
 }
 
 pub async fn launch<R>(
@@ -24,11 +35,17 @@ pub async fn launch<R>(
 where
     R: HttpRouter + Send + Sync + Clone + 'static,
 {
+    api_key_auth::validate_server_settings(&settings.server)?;
+
     let metrics = settings
         .metrics
         .enabled
         .then(|| Metrics::new(&settings.service_name, &settings.metrics.route));
 
+    let shutdown = settings.graceful_shutdown.clone();
+    let drain_timeout = settings.server.shutdown.drain_timeout;
+    let signal_listener = tokio::spawn(shutdown.clone().cancel_on_signal());
+
     let mut futures = vec![];
 
     if settings.server.http.enabled {
@@ -39,7 +56,11 @@ where
                     .as_ref()
                     .map(|metrics| metrics.http_middleware().clone()),
                 &settings.server.http,
+                drain_timeout,
+                settings.probes.clone(),
+                settings.api_key_store.clone(),
             );
+            stop_on_shutdown(http_server_future.handle(), &shutdown);
             tokio::spawn(async move { http_server_future.await.map_err(anyhow::Error::msg) })
         };
         futures.push(http_server)
@@ -47,7 +68,7 @@ where
 
     if settings.server.grpc.enabled {
         let grpc_server = {
-            let grpc_server_future = grpc_serve(grpc, settings.server.grpc.addr);
+            let grpc_server_future = grpc_serve(grpc, settings.server.grpc.addr, &shutdown);
             tokio::spawn(async move { grpc_server_future.await.map_err(anyhow::Error::msg) })
         };
         futures.push(grpc_server)
@@ -55,45 +76,82 @@ where
 
     if let Some(metrics) = metrics {
         let addr = settings.metrics.addr;
+        let metrics_server_future = metrics.run_server(addr);
+        stop_on_shutdown(metrics_server_future.handle(), &shutdown);
         futures.push(tokio::spawn(async move {
-            metrics.run_server(addr).await?;
+            metrics_server_future.await?;
             Ok(())
         }));
     }
 
+    // Either the shutdown has been initiated, or one of the servers has failed.
+    // In both cases the rest of the servers are given time to process in-flight requests.
     let (res, _, others) = futures::future::select_all(futures).await;
-    for future in others.into_iter() {
-        future.abort()
+    shutdown.shutdown();
+    let abort_handles: Vec<_> = others.iter().map(|future| future.abort_handle()).collect();
+    if tokio::time::timeout(drain_timeout, futures::future::join_all(others))
+        .await
+        .is_err()
+    {
+        tracing::warn!("servers did not stop in time, aborting");
+        for handle in abort_handles {
+            handle.abort()
+        }
     }
+    shutdown.complete(&settings.server.shutdown).await;
+    signal_listener.abort();
+
     res?
 }
 
+fn stop_on_shutdown(handle: actix_web::dev::ServerHandle, shutdown: &GracefulShutdownHandler) {
+    let shutdown_token = shutdown.shutdown_token.clone();
+    tokio::spawn(async move {
+        shutdown_token.cancelled().await;
+        handle.stop(true).await
+    });
+}
+
 fn http_serve<R>(
     http: R,
     metrics: Option<PrometheusMetrics>,
     settings: &HttpServerSettings,
+    drain_timeout: std::time::Duration,
+    probes: Probes,
+    api_key_store: Option<Arc<dyn ApiKeyStore>>,
 ) -> actix_web::dev::Server
 where
     R: HttpRouter + Send + Sync + Clone + 'static,
 {
     tracing::info!("starting http server on addr {}", settings.addr);
 
-    // Initialize the tracing logger not to print http request and response messages on health endpoint
-    CompactRootSpanBuilder::init_skip_http_trace_paths(["/health"]);
+    // Initialize the tracing logger not to print http request and response messages on health endpoints
+    CompactRootSpanBuilder::init_skip_http_trace_paths(["/health", READINESS_PATH, LIVENESS_PATH]);
 
     let json_cfg = actix_web::web::JsonConfig::default().limit(settings.max_body_size);
     let cors_settings = settings.cors.clone();
     let cors_enabled = cors_settings.enabled;
+    let api_key_auth = ApiKeyAuth::new(settings.api_key_auth.clone(), api_key_store);
+    let api_key_auth_enabled = api_key_auth.is_enabled();
     if let Some(metrics) = metrics {
         HttpServer::new(move || {
             let cors = cors_settings.clone().build();
             App::new()
+                .wrap(Condition::new(
+                    api_key_auth_enabled,
+                    from_fn(api_key_auth::middleware),
+                ))
                 .wrap(TracingLogger::<CompactRootSpanBuilder>::new())
                 .wrap(metrics.clone())
                 .wrap(Condition::new(cors_enabled, cors))
                 .app_data(json_cfg.clone())
+                .app_data(web::Data::new(api_key_auth.clone()))
+                .configure(|service_config| probes.register_routes(service_config))
                 .configure(configure_router(&http))
         })
+        // Signals are handled by the launcher to stop all the servers together
+        .disable_signals()
+        .shutdown_timeout(drain_timeout.as_secs())
         .bind(settings.addr)
         .expect("failed to bind server")
         .run()
@@ -101,11 +159,20 @@ where
         HttpServer::new(move || {
             let cors = cors_settings.clone().build();
             App::new()
+                .wrap(Condition::new(
+                    api_key_auth_enabled,
+                    from_fn(api_key_auth::middleware),
+                ))
                 .wrap(TracingLogger::<CompactRootSpanBuilder>::new())
                 .wrap(Condition::new(cors_enabled, cors))
                 .app_data(json_cfg.clone())
+                .app_data(web::Data::new(api_key_auth.clone()))
+                .configure(|service_config| probes.register_routes(service_config))
                 .configure(configure_router(&http))
         })
+        // Signals are handled by the launcher to stop all the servers together
+        .disable_signals()
+        .shutdown_timeout(drain_timeout.as_secs())
         .bind(settings.addr)
         .expect("failed to bind server")
         .run()
@@ -115,7 +182,9 @@ where
 fn grpc_serve(
     grpc: tonic::transport::server::Router,
     addr: SocketAddr,
+    shutdown: &GracefulShutdownHandler,
 ) -> impl futures::Future<Output = Result<(), tonic::transport::Error>> {
     tracing::info!("starting grpc server on addr {}", addr);
-    grpc.serve(addr)
+    let shutdown_token = shutdown.shutdown_token.clone();
+    grpc.serve_with_shutdown(addr, async move { shutdown_token.cancelled().await })
 }
@@ -36,6 +36,7 @@ impl Metrics {
     pub fn run_server(self, addr: SocketAddr) -> actix_web::dev::Server {
         tracing::info!(addr = ?addr, "starting metrics server");
         HttpServer::new(move || App::new().wrap(self.metrics_middleware.clone()))
+            .disable_signals()
             .bind(addr)
             .unwrap()
             .run()
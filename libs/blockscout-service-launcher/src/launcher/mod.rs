@@ -1,9 +1,21 @@
+mod api_key_auth;
 mod launch;
 mod metrics;
+mod probes;
+mod reload;
 mod router;
 mod settings;
+mod shutdown;
 mod span_builder;
+mod workers;
 
+pub use api_key_auth::{
+    ApiKey, ApiKeyAuth, ApiKeyAuthSettings, ApiKeyStore, RateLimitSettings, StaticApiKeySettings,
+};
 pub use launch::{launch, LaunchSettings};
+pub use probes::{Probes, ReadinessFlag, LIVENESS_PATH, READINESS_PATH};
+pub use reload::ConfigReloader;
 pub use router::HttpRouter;
 pub use settings::*;
+pub use shutdown::{GracefulShutdownHandler, ShutdownSettings};
+pub use workers::{RestartPolicy, Workers};
@@ -0,0 +1,237 @@
+use actix_web::{http::StatusCode, web, HttpResponse};
+use futures::future::BoxFuture;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+pub const READINESS_PATH: &str = "/ready";
+pub const LIVENESS_PATH: &str = "/live";
+
+type Check = Arc<dyn Fn() -> BoxFuture<'static, Result<(), anyhow::Error>> + Send + Sync>;
+
+/// Readiness and liveness checks exposed by the http server
+/// as [`READINESS_PATH`] and [`LIVENESS_PATH`] endpoints.
+///
+/// Liveness shows whether the service is operational and should not be restarted;
+/// readiness - whether it is ready to receive the traffic (e.g., database is reachable,
+/// migrations are applied, caches are warmed up). Without checks, the endpoints
+/// respond successfully as long as the http server is running.
+#[derive(Clone)]
+pub struct Probes {
+    readiness_checks: Vec<(String, Check)>,
+    liveness_checks: Vec<(String, Check)>,
+    check_timeout: Duration,
+}
+
+impl Default for Probes {
+    fn default() -> Self {
+        Self {
+            readiness_checks: vec![],
+            liveness_checks: vec![],
+            check_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Probes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_readiness_check<F, Fut>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        self.readiness_checks
+            .push((name.into(), Arc::new(move || Box::pin(check()))));
+        self
+    }
+
+    pub fn with_liveness_check<F, Fut>(mut self, name: impl Into<String>, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        self.liveness_checks
+            .push((name.into(), Arc::new(move || Box::pin(check()))));
+        self
+    }
+
+    /// Readiness check that passes once the flag is set (e.g., after the caches have been warmed up)
+    pub fn with_readiness_flag(self, name: impl Into<String>, flag: ReadinessFlag) -> Self {
+        self.with_readiness_check(name, move || {
+            let is_ready = flag.is_ready();
+            async move {
+                if is_ready {
+                    Ok(())
+                } else {
+                    Err(anyhow::anyhow!("not ready yet"))
+                }
+            }
+        })
+    }
+
+    /// Checks not finished in the given time are considered failed (defaults to 5 seconds)
+    pub fn with_check_timeout(mut self, check_timeout: Duration) -> Self {
+        self.check_timeout = check_timeout;
+        self
+    }
+
+    pub(crate) fn register_routes(&self, service_config: &mut web::ServiceConfig) {
+        let readiness = self.clone();
+        let liveness = self.clone();
+        service_config
+            .route(
+                READINESS_PATH,
+                web::get().to(move || {
+                    let probes = readiness.clone();
+                    async move { probes.respond(&probes.readiness_checks).await }
+                }),
+            )
+            .route(
+                LIVENESS_PATH,
+                web::get().to(move || {
+                    let probes = liveness.clone();
+                    async move { probes.respond(&probes.liveness_checks).await }
+                }),
+            );
+    }
+
+    async fn respond(&self, checks: &[(String, Check)]) -> HttpResponse {
+        let response = run_checks(checks, self.check_timeout).await;
+        let status_code = match response.status {
+            Status::Ok => StatusCode::OK,
+            Status::Failed => StatusCode::SERVICE_UNAVAILABLE,
+        };
+        HttpResponse::build(status_code).json(response)
+    }
+}
+
+/// Flag to be set by the service when some part of it becomes ready
+#[derive(Clone, Debug, Default)]
+pub struct ReadinessFlag(Arc<AtomicBool>);
+
+impl ReadinessFlag {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_ready(&self, is_ready: bool) {
+        self.0.store(is_ready, Ordering::Release)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Status {
+    Ok,
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct CheckResult {
+    status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+struct ProbeResponse {
+    status: Status,
+    checks: BTreeMap<String, CheckResult>,
+}
+
+async fn run_checks(checks: &[(String, Check)], check_timeout: Duration) -> ProbeResponse {
+    let results = futures::future::join_all(checks.iter().map(|(name, check)| async move {
+        let result = match tokio::time::timeout(check_timeout, check()).await {
+            Ok(Ok(())) => CheckResult {
+                status: Status::Ok,
+                error: None,
+            },
+            Ok(Err(err)) => CheckResult {
+                status: Status::Failed,
+                error: Some(format!("{err:#}")),
+            },
+            Err(_) => CheckResult {
+                status: Status::Failed,
+                error: Some("check timed out".to_string()),
+            },
+        };
+        (name.clone(), result)
+    }))
+    .await;
+
+    let status = if results
+        .iter()
+        .all(|(_, result)| result.status == Status::Ok)
+    {
+        Status::Ok
+    } else {
+        Status::Failed
+    };
+    ProbeResponse {
+        status,
+        checks: results.into_iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn checks_are_reported_separately() {
+        let flag = ReadinessFlag::new();
+        let probes = Probes::new()
+            .with_readiness_check("database", || async { Ok(()) })
+            .with_readiness_flag("cache", flag.clone())
+            .with_check_timeout(Duration::from_millis(100));
+
+        let response = run_checks(&probes.readiness_checks, probes.check_timeout).await;
+        let expected = ProbeResponse {
+            status: Status::Failed,
+            checks: BTreeMap::from([
+                (
+                    "cache".to_string(),
+                    CheckResult {
+                        status: Status::Failed,
+                        error: Some("not ready yet".to_string()),
+                    },
+                ),
+                (
+                    "database".to_string(),
+                    CheckResult {
+                        status: Status::Ok,
+                        error: None,
+                    },
+                ),
+            ]),
+        };
+        assert_eq!(expected, response);
+
+        flag.set_ready(true);
+        let response = run_checks(&probes.readiness_checks, probes.check_timeout).await;
+        assert_eq!(Status::Ok, response.status);
+    }
+
+    #[tokio::test]
+    async fn no_checks_is_ok() {
+        let probes = Probes::new();
+        let response = run_checks(&probes.liveness_checks, probes.check_timeout).await;
+        assert_eq!(Status::Ok, response.status);
+        assert!(response.checks.is_empty());
+    }
+}
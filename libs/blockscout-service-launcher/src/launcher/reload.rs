@@ -0,0 +1,235 @@
+use super::{
+    settings::{take_config_path, ConfigSettings},
+    shutdown::GracefulShutdownHandler,
+};
+use serde::de::DeserializeOwned;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::watch;
+
+type ReloadablePart<S> = Box<dyn Fn(&S) -> bool + Send + Sync>;
+
+/// Re-reads the settings from the config file and the environment
+/// and applies the changes of the registered reloadable parts at runtime.
+///
+/// Each registered part (e.g., rate limits, feature flags) is delivered
+/// to the subscribers via [`watch::Receiver`], which is notified only when that part
+/// actually changes. The log level is applied to the logs directly
+/// (see [`ConfigReloader::register_log_level`]). Invalid configs are rejected,
+/// keeping the current settings; changes of not registered parts are ignored
+/// until the service is restarted.
+///
+/// The config file is not watched for changes: the settings are re-read either on demand
+/// via [`ConfigReloader::reload`], or periodically via [`ConfigReloader::spawn`].
+pub struct ConfigReloader<S> {
+    config_path: Option<String>,
+    current: Mutex<S>,
+    parts: Vec<(String, ReloadablePart<S>)>,
+}
+
+impl<S> ConfigReloader<S>
+where
+    S: ConfigSettings + DeserializeOwned + PartialEq + Send + 'static,
+{
+    /// Builds the settings as [`ConfigSettings::build`] does, remembering
+    /// the config file to be re-read on reloads.
+    pub fn build() -> anyhow::Result<(S, Self)>
+    where
+        S: Clone,
+    {
+        let config_path = take_config_path(S::SERVICE_NAME);
+        let settings = S::build_with_config_path(config_path.as_deref())?;
+        Ok((settings.clone(), Self::new(settings, config_path)))
+    }
+
+    pub fn new(settings: S, config_path: Option<String>) -> Self {
+        Self {
+            config_path,
+            current: Mutex::new(settings),
+            parts: vec![],
+        }
+    }
+
+    /// Registers the part of the settings to be applied on reloads.
+    /// Returns the receiver initialized with the current value of the part.
+    pub fn register<T, F>(&mut self, name: impl Into<String>, extract: F) -> watch::Receiver<T>
+    where
+        T: PartialEq + Send + Sync + 'static,
+        F: Fn(&S) -> T + Send + Sync + 'static,
+    {
+        let (sender, receiver) = watch::channel(extract(&self.current.lock().unwrap()));
+        let part: ReloadablePart<S> = Box::new(move |settings| {
+            let value = extract(settings);
+            sender.send_if_modified(|current| {
+                if *current != value {
+                    *current = value;
+                    true
+                } else {
+                    false
+                }
+            })
+        });
+        self.parts.push((name.into(), part));
+        receiver
+    }
+
+    /// Registers the log level directives (e.g., `debug` or `my_service=debug,info`)
+    /// to be applied to the logs on reloads. If `extract` returns `None`,
+    /// the level is not changed by the reload.
+    #[cfg(feature = "tracing")]
+    pub fn register_log_level<F>(
+        &mut self,
+        handle: crate::tracing::LogLevelHandle,
+        extract: F,
+    ) -> anyhow::Result<()>
+    where
+        F: Fn(&S) -> Option<String> + Send + Sync + 'static,
+    {
+        let initial = extract(&self.current.lock().unwrap());
+        if let Some(directives) = &initial {
+            handle.set_directives(directives)?;
+        }
+        let current = Mutex::new(initial);
+        let part: ReloadablePart<S> = Box::new(move |settings| {
+            let value = extract(settings);
+            let mut current = current.lock().unwrap();
+            if *current == value {
+                return false;
+            }
+            if let Some(directives) = &value {
+                if let Err(err) = handle.set_directives(directives) {
+                    tracing::error!(err = ?err, "invalid log level, keeping the current one");
+                    return false;
+                }
+            }
+            *current = value;
+            true
+        });
+        self.parts.push(("log_level".to_string(), part));
+        Ok(())
+    }
+
+    /// Re-reads the settings and notifies the subscribers of the changed parts.
+    /// Returns the names of the parts that have been changed.
+    pub fn reload(&self) -> anyhow::Result<Vec<String>> {
+        let settings = S::build_with_config_path(self.config_path.as_deref())?;
+        let mut current = self.current.lock().unwrap();
+        if *current == settings {
+            return Ok(vec![]);
+        }
+
+        let changed: Vec<_> = self
+            .parts
+            .iter()
+            .filter(|(_, apply)| apply(&settings))
+            .map(|(name, _)| name.clone())
+            .collect();
+        if changed.is_empty() {
+            tracing::warn!("config has been changed, but the changes require the service restart");
+        } else {
+            tracing::info!(parts = ?changed, "config has been reloaded");
+        }
+        *current = settings;
+        Ok(changed)
+    }
+
+    /// Reloads the settings every `interval` until the shutdown.
+    /// The `interval` must be non-zero.
+    pub fn spawn(
+        self,
+        interval: Duration,
+        shutdown: &GracefulShutdownHandler,
+    ) -> anyhow::Result<()> {
+        // `tokio::time::interval` panics on zero
+        if interval.is_zero() {
+            anyhow::bail!("config reload interval must be non-zero");
+        }
+        let reloader = Arc::new(self);
+        let token = shutdown.shutdown_token.clone();
+        shutdown.task_tracker.spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately, while the settings are already up to date
+            interval.tick().await;
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = interval.tick() => {}
+                }
+                if let Err(err) = reloader.reload() {
+                    tracing::error!(err = ?err, "failed to reload the config, keeping the current settings");
+                }
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+    use std::path::Path;
+
+    #[derive(Clone, Debug, PartialEq, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct TestSettings {
+        log_level: String,
+        rate_limit: u32,
+        addr: String,
+    }
+
+    impl ConfigSettings for TestSettings {
+        const SERVICE_NAME: &'static str = "CONFIG_RELOADER_TEST";
+    }
+
+    fn write_config(path: &Path, log_level: &str, addr: &str) {
+        let config =
+            format!(r#"{{"log_level": "{log_level}", "rate_limit": 10, "addr": "{addr}"}}"#);
+        std::fs::write(path, config).unwrap();
+    }
+
+    #[test]
+    fn reload_notifies_changed_parts_only() {
+        let file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        write_config(file.path(), "info", "0.0.0.0:8050");
+        let config_path = file.path().to_str().unwrap().to_string();
+
+        let settings = TestSettings::build_with_config_path(Some(&config_path)).unwrap();
+        let mut reloader = ConfigReloader::new(settings, Some(config_path));
+        let mut log_level = reloader.register("log_level", |s| s.log_level.clone());
+        let mut rate_limit = reloader.register("rate_limit", |s| s.rate_limit);
+
+        write_config(file.path(), "debug", "0.0.0.0:8050");
+        assert_eq!(vec!["log_level".to_string()], reloader.reload().unwrap());
+        assert!(log_level.has_changed().unwrap());
+        assert_eq!("debug", *log_level.borrow_and_update());
+        assert!(!rate_limit.has_changed().unwrap());
+        assert_eq!(10, *rate_limit.borrow_and_update());
+
+        // Not registered parts are not applied
+        write_config(file.path(), "debug", "0.0.0.0:8051");
+        assert!(reloader.reload().unwrap().is_empty());
+        assert!(!log_level.has_changed().unwrap());
+
+        // Invalid config keeps the current settings
+        std::fs::write(file.path(), r#"{"log_level": "warn"}"#).unwrap();
+        assert!(reloader.reload().is_err());
+        assert_eq!("debug", *log_level.borrow());
+    }
+
+    #[tokio::test]
+    async fn spawn_rejects_zero_interval() {
+        let settings = TestSettings {
+            log_level: "info".to_string(),
+            rate_limit: 10,
+            addr: "0.0.0.0:8050".to_string(),
+        };
+        let reloader = ConfigReloader::new(settings, None);
+        let shutdown = GracefulShutdownHandler::new();
+        assert!(reloader.spawn(Duration::ZERO, &shutdown).is_err());
+    }
+}
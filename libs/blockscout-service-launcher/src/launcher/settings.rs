@@ -1,3 +1,4 @@
+use super::{api_key_auth::ApiKeyAuthSettings, shutdown::ShutdownSettings};
 use actix_cors::Cors;
 use config::{Config, File};
 use serde::{Deserialize, Serialize};
@@ -10,13 +11,18 @@ pub trait ConfigSettings {
     where
         Self: Deserialize<'static>,
     {
-        let config_path_name: &String = &format!("{}__CONFIG", Self::SERVICE_NAME);
-        let config_path = std::env::var(config_path_name);
+        let config_path = take_config_path(Self::SERVICE_NAME);
+        Self::build_with_config_path(config_path.as_deref())
+    }
 
+    /// Builds the settings from the given config file (if any) and the environment
+    fn build_with_config_path(config_path: Option<&str>) -> anyhow::Result<Self>
+    where
+        Self: Deserialize<'static>,
+    {
         let mut builder = Config::builder();
-        if let Ok(config_path) = config_path {
-            builder = builder.add_source(File::with_name(&config_path));
-            std::env::remove_var(config_path_name);
+        if let Some(config_path) = config_path {
+            builder = builder.add_source(File::with_name(config_path));
         };
         // Use `__` so that it would be possible to address keys with underscores in names (e.g. `access_key`)
         builder = builder
@@ -41,6 +47,7 @@ pub trait ConfigSettings {
 pub struct ServerSettings {
     pub http: HttpServerSettings,
     pub grpc: GrpcServerSettings,
+    pub shutdown: ShutdownSettings,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -50,6 +57,7 @@ pub struct HttpServerSettings {
     pub addr: SocketAddr,
     pub max_body_size: usize,
     pub cors: CorsSettings,
+    pub api_key_auth: ApiKeyAuthSettings,
 }
 
 impl Default for HttpServerSettings {
@@ -59,6 +67,7 @@ impl Default for HttpServerSettings {
             addr: SocketAddr::from_str("0.0.0.0:8050").unwrap(),
             max_body_size: 2 * 1024 * 1024, // 2 Mb - default Actix value
             cors: Default::default(),
+            api_key_auth: Default::default(),
         }
     }
 }
@@ -151,6 +160,17 @@ impl Default for MetricsSettings {
     }
 }
 
+/// Returns the config file path specified via `<SERVICE_NAME>__CONFIG`, removing the variable
+/// so that it would not be treated as the unknown `config` field of the settings
+pub(crate) fn take_config_path(service_name: &str) -> Option<String> {
+    let config_path_name = format!("{service_name}__CONFIG");
+    let config_path = std::env::var(&config_path_name).ok();
+    if config_path.is_some() {
+        std::env::remove_var(&config_path_name);
+    }
+    config_path
+}
+
 fn split_string(s: &str) -> Vec<&str> {
     s.split(',').map(|s| s.trim()).collect()
 }
@@ -0,0 +1,183 @@
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::{
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShutdownSettings {
+    /// Time given to the servers to process in-flight requests,
+    /// and then to the tracked background tasks to finish
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub drain_timeout: Duration,
+    /// Time given to each of the registered shutdown hooks
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub hook_timeout: Duration,
+}
+
+impl Default for ShutdownSettings {
+    fn default() -> Self {
+        Self {
+            drain_timeout: Duration::from_secs(30),
+            hook_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+type ShutdownHook = Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>;
+
+/// Coordinates the graceful shutdown of the service.
+///
+/// On SIGTERM or SIGINT the `shutdown_token` is cancelled, servers stop accepting
+/// new connections and finish in-flight requests. After that, the launcher waits for
+/// the tasks spawned via `task_tracker` (background workers should stop on the token
+/// cancellation), and runs the registered hooks (e.g., closing database pools).
+#[derive(Clone, Default)]
+pub struct GracefulShutdownHandler {
+    pub shutdown_token: CancellationToken,
+    pub task_tracker: TaskTracker,
+    hooks: Arc<Mutex<Vec<(i32, String, ShutdownHook)>>>,
+}
+
+impl GracefulShutdownHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the hook to be run after the servers and the tracked tasks are stopped.
+    /// Hooks are run sequentially in ascending `order` (hooks with the same order -
+    /// in the order of registration), e.g. flushing caches before closing the database pool.
+    pub fn register_hook<F, Fut>(&self, name: impl Into<String>, order: i32, hook: F)
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let hook: ShutdownHook = Box::new(move || Box::pin(hook()));
+        self.hooks.lock().unwrap().push((order, name.into(), hook));
+    }
+
+    /// Initiates the shutdown programmatically
+    pub fn shutdown(&self) {
+        self.shutdown_token.cancel()
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown_token.is_cancelled()
+    }
+
+    /// Cancels the `shutdown_token` on SIGTERM or SIGINT
+    pub(crate) async fn cancel_on_signal(self) {
+        tokio::select! {
+            _ = wait_for_signal() => {
+                tracing::info!("shutdown signal received, stopping the service");
+                self.shutdown_token.cancel();
+            }
+            _ = self.shutdown_token.cancelled() => {}
+        }
+    }
+
+    /// Waits for the tracked tasks and runs the hooks.
+    /// Should be called after the servers have been stopped.
+    pub(crate) async fn complete(&self, settings: &ShutdownSettings) {
+        self.task_tracker.close();
+        if tokio::time::timeout(settings.drain_timeout, self.task_tracker.wait())
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                tasks = self.task_tracker.len(),
+                "background tasks did not finish in time"
+            );
+        }
+
+        let mut hooks = std::mem::take(&mut *self.hooks.lock().unwrap());
+        // `sort_by_key` is stable, so the registration order is preserved for equal orders
+        hooks.sort_by_key(|(order, _, _)| *order);
+        for (_, name, hook) in hooks {
+            tracing::info!(hook = %name, "running shutdown hook");
+            if tokio::time::timeout(settings.hook_timeout, hook())
+                .await
+                .is_err()
+            {
+                tracing::warn!(hook = %name, "shutdown hook did not finish in time");
+            }
+        }
+    }
+}
+
+async fn wait_for_signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!(err = ?err, "failed to listen for SIGINT");
+            futures::future::pending::<()>().await
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(err) => {
+                tracing::error!(err = ?err, "failed to listen for SIGTERM");
+                futures::future::pending::<()>().await
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = futures::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn hooks_run_in_order() {
+        let handler = GracefulShutdownHandler::new();
+        let calls = Arc::new(Mutex::new(vec![]));
+        for (name, order) in [("db", 10), ("cache", 0), ("queue", 0)] {
+            let calls = calls.clone();
+            handler.register_hook(name, order, move || async move {
+                calls.lock().unwrap().push(name);
+            });
+        }
+
+        handler.shutdown();
+        handler.complete(&ShutdownSettings::default()).await;
+
+        assert_eq!(vec!["cache", "queue", "db"], *calls.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn waits_for_tracked_tasks() {
+        let handler = GracefulShutdownHandler::new();
+        let finished = Arc::new(Mutex::new(false));
+        {
+            let token = handler.shutdown_token.clone();
+            let finished = finished.clone();
+            handler.task_tracker.spawn(async move {
+                token.cancelled().await;
+                *finished.lock().unwrap() = true;
+            });
+        }
+
+        handler.shutdown();
+        handler.complete(&ShutdownSettings::default()).await;
+
+        assert!(*finished.lock().unwrap());
+    }
+}
@@ -0,0 +1,322 @@
+use super::shutdown::GracefulShutdownHandler;
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::{mpsc, Mutex};
+use tokio_util::sync::CancellationToken;
+
+mod metrics {
+    use once_cell::sync::Lazy;
+    use prometheus::{
+        register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec,
+    };
+
+    pub static WORKER_RUNS: Lazy<IntCounterVec> = Lazy::new(|| {
+        register_int_counter_vec!(
+            "worker_runs_total",
+            "number of the background worker runs by their result",
+            &["worker", "status"],
+        )
+        .unwrap()
+    });
+
+    pub static WORKER_RUN_DURATION: Lazy<HistogramVec> = Lazy::new(|| {
+        register_histogram_vec!(
+            "worker_run_duration_seconds",
+            "duration of the background worker runs",
+            &["worker"],
+        )
+        .unwrap()
+    });
+}
+
+/// What to do with the worker which has panicked
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// The worker is stopped after the first panic
+    Never,
+    /// The worker is restarted after the `delay`, at most `max_restarts` times
+    /// (unlimited if `None`)
+    OnPanic {
+        max_restarts: Option<u32>,
+        delay: Duration,
+    },
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy::OnPanic {
+            max_restarts: None,
+            delay: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RestartPolicy {
+    fn restart_delay(&self, restarts: u32) -> Option<Duration> {
+        match self {
+            RestartPolicy::Never => None,
+            RestartPolicy::OnPanic {
+                max_restarts,
+                delay,
+            } => max_restarts
+                .map_or(true, |max_restarts| restarts < max_restarts)
+                .then_some(*delay),
+        }
+    }
+}
+
+/// Spawns the background workers supervised by the launcher.
+///
+/// Workers are tracked by the [`GracefulShutdownHandler`]: on shutdown they finish
+/// the current run (or the item being processed) and stop, and the launcher waits for them
+/// before running the shutdown hooks. Each run is recorded in the `worker_runs_total`
+/// and `worker_run_duration_seconds` metrics.
+#[derive(Clone)]
+pub struct Workers {
+    shutdown: GracefulShutdownHandler,
+}
+
+impl Workers {
+    pub fn new(shutdown: GracefulShutdownHandler) -> Self {
+        Self { shutdown }
+    }
+
+    /// Runs the `task` every `interval` until the shutdown.
+    /// Errors are logged and do not stop the worker. The `interval` must be non-zero.
+    pub fn spawn_periodic<F, Fut>(
+        &self,
+        name: impl Into<String>,
+        interval: Duration,
+        restart_policy: RestartPolicy,
+        task: F,
+    ) -> Result<(), anyhow::Error>
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        let name = name.into();
+        // `tokio::time::interval` panics on zero, which would restart the worker endlessly
+        if interval.is_zero() {
+            anyhow::bail!("interval of the periodic worker '{name}' must be non-zero");
+        }
+        let task = Arc::new(task);
+        let token = self.shutdown.shutdown_token.clone();
+        let worker_name = name.clone();
+        self.supervise(name, restart_policy, move || {
+            let name = worker_name.clone();
+            let task = task.clone();
+            let token = token.clone();
+            async move {
+                let mut interval = tokio::time::interval(interval);
+                interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                loop {
+                    tokio::select! {
+                        _ = token.cancelled() => break,
+                        _ = interval.tick() => {}
+                    }
+                    run_once(&name, task()).await;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Processes the items received from the `queue` one by one until the shutdown,
+    /// or until all senders are dropped. Errors are logged and do not stop the worker.
+    pub fn spawn_queue<T, F, Fut>(
+        &self,
+        name: impl Into<String>,
+        queue: mpsc::Receiver<T>,
+        restart_policy: RestartPolicy,
+        handler: F,
+    ) where
+        T: Send + 'static,
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        let name = name.into();
+        let handler = Arc::new(handler);
+        // Shared between the restarts of the worker
+        let queue = Arc::new(Mutex::new(queue));
+        let token = self.shutdown.shutdown_token.clone();
+        let worker_name = name.clone();
+        self.supervise(name, restart_policy, move || {
+            let name = worker_name.clone();
+            let handler = handler.clone();
+            let queue = queue.clone();
+            let token = token.clone();
+            async move {
+                let mut queue = queue.lock().await;
+                loop {
+                    let item = tokio::select! {
+                        _ = token.cancelled() => None,
+                        item = queue.recv() => item,
+                    };
+                    let Some(item) = item else {
+                        break;
+                    };
+                    run_once(&name, handler(item)).await;
+                }
+            }
+        })
+    }
+
+    /// Spawns the long-running `task`, which is expected to stop on the token cancellation
+    pub fn spawn<F, Fut>(&self, name: impl Into<String>, restart_policy: RestartPolicy, task: F)
+    where
+        F: Fn(CancellationToken) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        let name = name.into();
+        let token = self.shutdown.shutdown_token.clone();
+        let worker_name = name.clone();
+        self.supervise(name, restart_policy, move || {
+            run_once(&worker_name, task(token.clone()))
+        })
+    }
+
+    /// Runs the worker in a separate task and restarts it on panics according to the policy
+    fn supervise<F, Fut>(&self, name: String, restart_policy: RestartPolicy, worker: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let token = self.shutdown.shutdown_token.clone();
+        self.shutdown.task_tracker.spawn(async move {
+            tracing::info!(worker = %name, "starting worker");
+            let mut restarts = 0;
+            loop {
+                match tokio::spawn(worker()).await {
+                    Ok(()) => break,
+                    Err(err) if err.is_panic() => {
+                        metrics::WORKER_RUNS
+                            .with_label_values(&[&name, "panic"])
+                            .inc();
+                        let delay = restart_policy.restart_delay(restarts);
+                        match delay {
+                            Some(delay) if !token.is_cancelled() => {
+                                tracing::error!(worker = %name, err = ?err, "worker panicked, restarting in {delay:?}");
+                                restarts += 1;
+                                tokio::select! {
+                                    _ = token.cancelled() => break,
+                                    _ = tokio::time::sleep(delay) => {}
+                                }
+                            }
+                            _ => {
+                                tracing::error!(worker = %name, err = ?err, "worker panicked, stopping");
+                                break;
+                            }
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+            tracing::info!(worker = %name, "worker stopped");
+        });
+    }
+}
+
+async fn run_once(name: &str, run: impl Future<Output = Result<(), anyhow::Error>>) {
+    let start = Instant::now();
+    let status = match run.await {
+        Ok(()) => "success",
+        Err(err) => {
+            tracing::error!(worker = %name, err = ?err, "worker run failed");
+            "error"
+        }
+    };
+    metrics::WORKER_RUN_DURATION
+        .with_label_values(&[name])
+        .observe(start.elapsed().as_secs_f64());
+    metrics::WORKER_RUNS
+        .with_label_values(&[name, status])
+        .inc();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launcher::ShutdownSettings;
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn panicking_run() -> Result<(), anyhow::Error> {
+        panic!("test panic")
+    }
+
+    #[tokio::test]
+    async fn periodic_worker_restarts_after_panic() {
+        let shutdown = GracefulShutdownHandler::new();
+        let workers = Workers::new(shutdown.clone());
+        let runs = Arc::new(AtomicU32::new(0));
+        {
+            let runs = runs.clone();
+            let restart_policy = RestartPolicy::OnPanic {
+                max_restarts: Some(1),
+                delay: Duration::from_millis(1),
+            };
+            workers
+                .spawn_periodic(
+                    "test",
+                    Duration::from_millis(1),
+                    restart_policy,
+                    move || {
+                        let runs = runs.clone();
+                        async move {
+                            runs.fetch_add(1, Ordering::SeqCst);
+                            panicking_run()
+                        }
+                    },
+                )
+                .unwrap();
+        }
+
+        // The worker is expected to stop after the second panic by itself
+        shutdown.task_tracker.close();
+        tokio::time::timeout(Duration::from_secs(5), shutdown.task_tracker.wait())
+            .await
+            .expect("worker did not stop");
+        assert_eq!(2, runs.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn periodic_worker_rejects_zero_interval() {
+        let shutdown = GracefulShutdownHandler::new();
+        let workers = Workers::new(shutdown.clone());
+        let result =
+            workers.spawn_periodic("test", Duration::ZERO, RestartPolicy::Never, || async {
+                Ok(())
+            });
+        assert!(result.is_err());
+        assert!(shutdown.task_tracker.is_empty());
+    }
+
+    #[tokio::test]
+    async fn queue_worker_processes_items() {
+        let shutdown = GracefulShutdownHandler::new();
+        let workers = Workers::new(shutdown.clone());
+        let processed = Arc::new(std::sync::Mutex::new(vec![]));
+        let (sender, receiver) = mpsc::channel(10);
+        {
+            let processed = processed.clone();
+            workers.spawn_queue("test", receiver, RestartPolicy::Never, move |item: u32| {
+                let processed = processed.clone();
+                async move {
+                    processed.lock().unwrap().push(item);
+                    Ok(())
+                }
+            });
+        }
+
+        for item in [1, 2, 3] {
+            sender.send(item).await.unwrap();
+        }
+        drop(sender);
+        shutdown.complete(&ShutdownSettings::default()).await;
+
+        assert_eq!(vec![1, 2, 3], *processed.lock().unwrap());
+    }
+}
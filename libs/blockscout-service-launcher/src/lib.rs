@@ -1,6 +1,9 @@
 #[cfg(feature = "database")]
 pub mod database;
 
+#[cfg(feature = "redis")]
+pub mod redis;
+
 #[cfg(feature = "launcher")]
 pub mod launcher;
 
@@ -1,4 +1,6 @@
 use crate::launcher::ServerSettings;
+#[cfg(feature = "test-database")]
+use crate::{database::MigratorTrait, test_database::TestDbGuard};
 use reqwest::Url;
 use std::{
     future::Future,
@@ -6,7 +8,7 @@ use std::{
     str::FromStr,
     time::Duration,
 };
-use tokio::{task::JoinHandle, time::timeout};
+use tokio::{net::TcpStream, task::JoinHandle, time::timeout};
 
 fn get_free_port() -> u16 {
     let listener = TcpListener::bind("127.0.0.1:0").unwrap();
@@ -22,6 +24,158 @@ pub fn get_test_server_settings() -> (ServerSettings, Url) {
     (server, base)
 }
 
+/// Boots the full service for integration tests.
+///
+/// The http server (and optionally the grpc one) listens on a random free port,
+/// and the test database (if any) lives as long as the returned [`TestServer`].
+///
+/// # Example
+///
+/// ```text
+/// let server = TestServerBuilder::new(Settings::default(db_url), |settings| &mut settings.server)
+///     .with_grpc()
+///     .with_overrides(|settings| settings.metrics.enabled = false)
+///     .start(my_service_server::run)
+///     .await;
+/// let response: HealthCheckResponse = server.get("/health").await;
+/// let mut client = server.grpc_client(MyServiceClient::new).await;
+/// ```
+pub struct TestServerBuilder<S> {
+    settings: S,
+    server_settings: fn(&mut S) -> &mut ServerSettings,
+    grpc_enabled: bool,
+    overrides: Vec<Box<dyn FnOnce(&mut S) + Send>>,
+    #[cfg(feature = "test-database")]
+    database: Option<TestDbGuard>,
+}
+
+impl<S: Send + 'static> TestServerBuilder<S> {
+    /// `server_settings` returns the server settings of the service,
+    /// which are replaced by the ones listening on random ports
+    pub fn new(settings: S, server_settings: fn(&mut S) -> &mut ServerSettings) -> Self {
+        Self {
+            settings,
+            server_settings,
+            grpc_enabled: false,
+            overrides: vec![],
+            #[cfg(feature = "test-database")]
+            database: None,
+        }
+    }
+
+    pub fn with_grpc(mut self) -> Self {
+        self.grpc_enabled = true;
+        self
+    }
+
+    /// Overrides are applied in the order of registration, after the server settings are set
+    pub fn with_overrides(mut self, overrides: impl FnOnce(&mut S) + Send + 'static) -> Self {
+        self.overrides.push(Box::new(overrides));
+        self
+    }
+
+    /// Creates the test database with migrations applied;
+    /// `set_db_url` should point the service settings to the database
+    #[cfg(feature = "test-database")]
+    pub async fn with_database<Migrator: MigratorTrait>(
+        mut self,
+        db_name: &str,
+        set_db_url: impl FnOnce(&mut S, String),
+    ) -> Self {
+        let database = TestDbGuard::new::<Migrator>(db_name).await;
+        set_db_url(&mut self.settings, database.db_url());
+        self.database = Some(database);
+        self
+    }
+
+    /// Runs the service and waits for its http server (and the grpc one, if enabled) to start
+    pub async fn start<F, R>(mut self, run: F) -> TestServer
+    where
+        F: FnOnce(S) -> R + Send + 'static,
+        R: Future<Output = Result<(), anyhow::Error>> + Send,
+    {
+        let (mut server_settings, http_base) = get_test_server_settings();
+        let grpc_base = self.grpc_enabled.then(|| {
+            let port = get_free_port();
+            server_settings.grpc.enabled = true;
+            server_settings.grpc.addr = SocketAddr::from_str(&format!("127.0.0.1:{port}")).unwrap();
+            Url::parse(&format!("http://{}", server_settings.grpc.addr)).unwrap()
+        });
+        let grpc_addr = server_settings.grpc.addr;
+        *(self.server_settings)(&mut self.settings) = server_settings;
+        for overrides in self.overrides {
+            overrides(&mut self.settings)
+        }
+
+        let settings = self.settings;
+        let mut handle = init_server(move || run(settings), &http_base).await;
+        if grpc_base.is_some() {
+            // the servers are started concurrently, so the grpc one may still be starting
+            let wait_grpc = async {
+                while TcpStream::connect(grpc_addr).await.is_err() {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            };
+            handle = wait_server_start(wait_grpc, handle).await;
+        }
+        TestServer {
+            http_base,
+            grpc_base,
+            handle,
+            #[cfg(feature = "test-database")]
+            database: self.database,
+        }
+    }
+}
+
+/// Running service; stopped when dropped
+pub struct TestServer {
+    pub http_base: Url,
+    pub grpc_base: Option<Url>,
+    handle: JoinHandle<Result<(), anyhow::Error>>,
+    #[cfg(feature = "test-database")]
+    database: Option<TestDbGuard>,
+}
+
+impl TestServer {
+    pub async fn get<Response: for<'a> serde::Deserialize<'a>>(&self, route: &str) -> Response {
+        send_get_request(&self.http_base, route).await
+    }
+
+    pub async fn post<Response: for<'a> serde::Deserialize<'a>>(
+        &self,
+        route: &str,
+        payload: &impl serde::Serialize,
+    ) -> Response {
+        send_post_request(&self.http_base, route, payload).await
+    }
+
+    /// Connects the generated grpc client (e.g., `server.grpc_client(MyServiceClient::new)`)
+    pub async fn grpc_client<C>(&self, new: impl FnOnce(tonic::transport::Channel) -> C) -> C {
+        let grpc_base = self
+            .grpc_base
+            .as_ref()
+            .expect("grpc server is not enabled; use `TestServerBuilder::with_grpc`");
+        let channel = tonic::transport::Channel::from_shared(grpc_base.to_string())
+            .expect("invalid grpc url")
+            .connect()
+            .await
+            .expect("failed to connect to the grpc server");
+        new(channel)
+    }
+
+    #[cfg(feature = "test-database")]
+    pub fn database(&self) -> Option<&TestDbGuard> {
+        self.database.as_ref()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.handle.abort()
+    }
+}
+
 pub async fn init_server<F, R>(run: F, base: &Url) -> JoinHandle<Result<(), anyhow::Error>>
 where
     F: FnOnce() -> R + Send + 'static,
@@ -45,7 +199,15 @@ where
         }
     };
     // Wait for the server to start
-    if (timeout(Duration::from_secs(10), wait_health_check).await).is_err() {
+    wait_server_start(wait_health_check, server_handle).await
+}
+
+/// Panics if `wait_start` does not complete in time
+async fn wait_server_start(
+    wait_start: impl Future<Output = ()>,
+    server_handle: JoinHandle<Result<(), anyhow::Error>>,
+) -> JoinHandle<Result<(), anyhow::Error>> {
+    if (timeout(Duration::from_secs(10), wait_start).await).is_err() {
         match timeout(Duration::from_secs(1), server_handle).await {
             Ok(Ok(result)) => {
                 panic!("Server terminated with: {result:?}")
@@ -138,3 +300,48 @@ pub async fn send_get_request<Response: for<'a> serde::Deserialize<'a>>(
 ) -> Response {
     send_annotated_request(url, route, reqwest::Method::GET, None::<&()>, None).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{web, App, HttpResponse, HttpServer};
+    use tokio::net::TcpListener;
+
+    /// Imitates the service, which grpc server starts listening later than the http one
+    async fn run(settings: ServerSettings) -> Result<(), anyhow::Error> {
+        let http = HttpServer::new(|| App::new().route("/health", web::get().to(HttpResponse::Ok)))
+            .bind(settings.http.addr)?
+            .run();
+        tokio::try_join!(
+            async { http.await.map_err(anyhow::Error::from) },
+            serve_grpc(settings.grpc.addr)
+        )?;
+        Ok(())
+    }
+
+    async fn serve_grpc(addr: SocketAddr) -> Result<(), anyhow::Error> {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let listener = TcpListener::bind(addr).await?;
+        loop {
+            listener.accept().await?;
+        }
+    }
+
+    #[tokio::test]
+    async fn start_waits_for_grpc_server() {
+        let server = TestServerBuilder::new(ServerSettings::default(), |settings| settings)
+            .with_grpc()
+            .start(run)
+            .await;
+
+        let grpc_base = server.grpc_base.as_ref().expect("grpc is enabled");
+        let grpc_addr = format!(
+            "{}:{}",
+            grpc_base.host_str().unwrap(),
+            grpc_base.port().unwrap()
+        );
+        TcpStream::connect(grpc_addr)
+            .await
+            .expect("grpc server should be started");
+    }
+}
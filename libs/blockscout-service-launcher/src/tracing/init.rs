@@ -1,13 +1,17 @@
-use super::{JaegerSettings, TracingFormat, TracingSettings};
+use super::{JaegerSettings, OtlpSettings, TracingFormat, TracingSettings};
 use opentelemetry::{
     global::{self},
-    sdk::{self, propagation::TraceContextPropagator},
+    metrics::{MeterProvider as _, MetricsError},
+    sdk::{self, propagation::TraceContextPropagator, trace::Sampler, Resource},
     trace::TraceError,
+    KeyValue,
 };
+use opentelemetry_otlp::{HttpExporterBuilder, WithExportConfig};
 use std::marker::Send;
 use tracing::Metadata;
 use tracing_subscriber::{
-    filter::LevelFilter, fmt::format::FmtSpan, layer::SubscriberExt, prelude::*, Layer,
+    filter::LevelFilter, fmt::format::FmtSpan, layer::SubscriberExt, prelude::*, reload, EnvFilter,
+    Layer, Registry,
 };
 
 pub fn init_logs(
@@ -29,9 +33,121 @@ pub fn init_logs_with_filter<F: Fn(&Metadata) -> bool + Send + Sync + 'static>(
     jaeger_settings: &JaegerSettings,
     filter: tracing_subscriber::filter::FilterFn<F>,
 ) -> Result<(), anyhow::Error> {
+    // OTLP export is disabled by default, so there are no providers to be shut down
+    init_telemetry_with_filter(
+        service_name,
+        tracing_settings,
+        jaeger_settings,
+        &OtlpSettings::default(),
+        filter,
+    )
+    .map(|_providers| ())
+}
+
+/// Providers installed by [`init_telemetry`]. The exporters send the data in batches,
+/// so the providers should be shut down before the service exits to flush the pending data
+/// (e.g., via [`TelemetryProviders::register_shutdown_hook`]).
+#[must_use]
+#[derive(Default)]
+pub struct TelemetryProviders {
+    tracer_provider: bool,
+    meter_provider: Option<sdk::metrics::MeterProvider>,
+    prometheus_bridge: Option<tokio::task::JoinHandle<()>>,
+    log_level: Option<LogLevelHandle>,
+}
+
+impl TelemetryProviders {
+    /// Handle to change the level of the stdout logs at runtime,
+    /// `None` if tracing is disabled
+    pub fn log_level_handle(&self) -> Option<LogLevelHandle> {
+        self.log_level.clone()
+    }
+
+    /// Flushes and shuts down the providers. Blocks until the pending data is exported.
+    pub fn shutdown(self) {
+        if let Some(prometheus_bridge) = self.prometheus_bridge {
+            prometheus_bridge.abort();
+        }
+        if let Some(meter_provider) = self.meter_provider {
+            if let Err(err) = meter_provider.shutdown() {
+                tracing::warn!(err = ?err, "failed to shut down the meter provider");
+            }
+        }
+        if self.tracer_provider {
+            global::shutdown_tracer_provider();
+        }
+    }
+
+    /// Shuts down the providers after all the other shutdown hooks,
+    /// so that the data recorded by them is exported as well.
+    #[cfg(feature = "launcher")]
+    pub fn register_shutdown_hook(self, shutdown: &crate::launcher::GracefulShutdownHandler) {
+        shutdown.register_hook("telemetry", i32::MAX, move || async move {
+            if let Err(err) = tokio::task::spawn_blocking(move || self.shutdown()).await {
+                tracing::warn!(err = ?err, "failed to shut down telemetry");
+            }
+        });
+    }
+}
+
+/// Changes the filter of the stdout logs at runtime (e.g., on the config reload).
+/// By default the filter is taken from the `RUST_LOG` environment variable.
+#[derive(Clone)]
+pub struct LogLevelHandle(reload::Handle<EnvFilter, Registry>);
+
+impl LogLevelHandle {
+    /// Replaces the filter with the given directives (e.g., `debug` or `my_service=debug,info`)
+    pub fn set_directives(&self, directives: &str) -> Result<(), anyhow::Error> {
+        let filter = EnvFilter::builder()
+            .with_default_directive(LevelFilter::INFO.into())
+            .parse(directives)?;
+        self.0.reload(filter)?;
+        Ok(())
+    }
+}
+
+/// Initializes the logs as [`init_logs`] does, and additionally
+/// the export of traces and metrics to the OpenTelemetry collector.
+/// The returned providers should be shut down on the service exit.
+pub fn init_telemetry(
+    service_name: &str,
+    tracing_settings: &TracingSettings,
+    jaeger_settings: &JaegerSettings,
+    otlp_settings: &OtlpSettings,
+) -> Result<TelemetryProviders, anyhow::Error> {
+    init_telemetry_with_filter(
+        service_name,
+        tracing_settings,
+        jaeger_settings,
+        otlp_settings,
+        tracing_subscriber::filter::filter_fn(move |_| true),
+    )
+}
+
+pub fn init_telemetry_with_filter<F: Fn(&Metadata) -> bool + Send + Sync + 'static>(
+    service_name: &str,
+    tracing_settings: &TracingSettings,
+    jaeger_settings: &JaegerSettings,
+    otlp_settings: &OtlpSettings,
+    filter: tracing_subscriber::filter::FilterFn<F>,
+) -> Result<TelemetryProviders, anyhow::Error> {
+    otlp_settings.validate()?;
+    let mut providers = TelemetryProviders::default();
+
+    // Metrics export does not depend on whether tracing is enabled
+    if otlp_settings.metrics.enabled {
+        let meter_provider = init_otlp_meter_provider(service_name, otlp_settings)?;
+        global::set_meter_provider(meter_provider.clone());
+        providers.prometheus_bridge = Some(super::prometheus_bridge::spawn(
+            meter_provider.meter("prometheus"),
+            otlp_settings.metrics.export_interval,
+        ));
+        providers.meter_provider = Some(meter_provider);
+    }
+
     // If tracing is disabled, there is nothing to initialize
     if !tracing_settings.enabled {
-        return Ok(());
+        return Ok(providers);
     }
 
     let mut layers: Vec<_> = vec![];
@@ -43,14 +159,16 @@ pub fn init_logs_with_filter<F: Fn(&Metadata) -> bool + Send + Sync + 'static>(
         }
     }
 
+    let (env_filter, log_level) = reload::Layer::new(
+        EnvFilter::builder()
+            .with_default_directive(LevelFilter::INFO.into())
+            .from_env_lossy(),
+    );
+    providers.log_level = Some(LogLevelHandle(log_level));
     let stdout_layer: Box<dyn Layer<_> + Sync + Send + 'static> = match tracing_settings.format {
         TracingFormat::Default => tracing_subscriber::fmt::layer()
             .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
-            .with_filter(
-                tracing_subscriber::EnvFilter::builder()
-                    .with_default_directive(LevelFilter::INFO.into())
-                    .from_env_lossy(),
-            )
+            .with_filter(env_filter)
             .with_filter(filter)
             .boxed(),
         TracingFormat::Json => tracing_subscriber::fmt::layer()
@@ -59,11 +177,7 @@ pub fn init_logs_with_filter<F: Fn(&Metadata) -> bool + Send + Sync + 'static>(
             .flatten_event(true)
             .with_current_span(true)
             .with_span_list(false)
-            .with_filter(
-                tracing_subscriber::EnvFilter::builder()
-                    .with_default_directive(LevelFilter::INFO.into())
-                    .from_env_lossy(),
-            )
+            .with_filter(env_filter)
             .with_filter(filter)
             .boxed(),
     };
@@ -71,6 +185,7 @@ pub fn init_logs_with_filter<F: Fn(&Metadata) -> bool + Send + Sync + 'static>(
 
     if jaeger_settings.enabled {
         let tracer = init_jaeger_tracer(service_name, &jaeger_settings.agent_endpoint)?;
+        providers.tracer_provider = true;
         // output traces to jaeger with default log level (default is DEBUG)
         let jaeger_layer = tracing_opentelemetry::layer()
             .with_tracer(tracer)
@@ -79,10 +194,20 @@ pub fn init_logs_with_filter<F: Fn(&Metadata) -> bool + Send + Sync + 'static>(
         layers.push(jaeger_layer)
     }
 
+    if otlp_settings.traces.enabled {
+        let tracer = init_otlp_tracer(service_name, otlp_settings)?;
+        providers.tracer_provider = true;
+        let otlp_layer = tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(LevelFilter::DEBUG)
+            .boxed();
+        layers.push(otlp_layer)
+    }
+
     let registry = tracing_subscriber::registry().with(layers);
     registry.try_init()?;
 
-    Ok(())
+    Ok(providers)
 }
 
 pub fn init_jaeger_tracer(
@@ -97,3 +222,51 @@ pub fn init_jaeger_tracer(
         .with_auto_split_batch(true)
         .install_batch(opentelemetry::runtime::Tokio)
 }
+
+pub fn init_otlp_tracer(
+    service_name: &str,
+    settings: &OtlpSettings,
+) -> Result<sdk::trace::Tracer, TraceError> {
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+        settings.traces.sampling_ratio,
+    )));
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(otlp_exporter(settings))
+        .with_trace_config(
+            sdk::trace::config()
+                .with_sampler(sampler)
+                .with_resource(service_resource(service_name)),
+        )
+        .install_batch(opentelemetry::runtime::Tokio)
+}
+
+/// The provider exports the metrics recorded via the `opentelemetry` API only.
+/// [`init_telemetry`] additionally bridges the default Prometheus registry
+/// (`actix-web-prom` and the ones registered with `prometheus::register_*!`) into it.
+pub fn init_otlp_meter_provider(
+    service_name: &str,
+    settings: &OtlpSettings,
+) -> Result<sdk::metrics::MeterProvider, MetricsError> {
+    opentelemetry_otlp::new_pipeline()
+        .metrics(opentelemetry::runtime::Tokio)
+        .with_exporter(otlp_exporter(settings))
+        .with_period(settings.metrics.export_interval)
+        .with_timeout(settings.timeout)
+        .with_resource(service_resource(service_name))
+        .build()
+}
+
+fn otlp_exporter(settings: &OtlpSettings) -> HttpExporterBuilder {
+    opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(&settings.endpoint)
+        .with_timeout(settings.timeout)
+        .with_headers(settings.headers.clone().into_iter().collect())
+}
+
+fn service_resource(service_name: &str) -> Resource {
+    Resource::new([KeyValue::new("service.name", service_name.to_string())])
+}
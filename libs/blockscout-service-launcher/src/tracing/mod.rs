@@ -1,4 +1,5 @@
 mod init;
+mod prometheus_bridge;
 mod settings;
 
 #[cfg(feature = "actix-request-id")]
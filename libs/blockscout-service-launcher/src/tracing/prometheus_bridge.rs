@@ -0,0 +1,264 @@
+use opentelemetry::{
+    metrics::{CallbackRegistration, Meter, ObservableCounter, ObservableGauge, Observer},
+    KeyValue,
+};
+use prometheus::proto::{MetricFamily, MetricType};
+use std::{any::Any, collections::BTreeMap, sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+/// Exports the metrics of the default Prometheus registry through the given meter.
+///
+/// Prometheus metrics are usually registered lazily, so the registry is checked
+/// for the new time series every `interval`. The values themselves are read
+/// from the registry on each collection of the meter.
+pub(crate) fn spawn(meter: Meter, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut instruments = BTreeMap::<String, Instrument>::new();
+        let mut registration: Option<Box<dyn CallbackRegistration>> = None;
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+
+            let mut discovered = false;
+            for family in prometheus::gather() {
+                for sample in samples(&family) {
+                    if !instruments.contains_key(&sample.name) {
+                        let instrument = Instrument::new(
+                            &meter,
+                            sample.name.clone(),
+                            family.get_help().to_string(),
+                            sample.kind,
+                        );
+                        instruments.insert(sample.name, instrument);
+                        discovered = true;
+                    }
+                }
+            }
+            if !discovered {
+                continue;
+            }
+
+            // the callback has to be registered for all instruments it observes,
+            // so it is replaced each time new time series appear
+            if let Some(mut registration) = registration.take() {
+                if let Err(err) = registration.unregister() {
+                    tracing::warn!(err = ?err, "failed to unregister prometheus metrics callback");
+                }
+            }
+            let any_instruments: Vec<_> = instruments.values().map(Instrument::as_any).collect();
+            let callback_instruments = instruments.clone();
+            let callback = move |observer: &dyn Observer| {
+                for family in prometheus::gather() {
+                    for sample in samples(&family) {
+                        if let Some(instrument) = callback_instruments.get(&sample.name) {
+                            instrument.observe(observer, sample.value, &sample.attributes);
+                        }
+                    }
+                }
+            };
+            match meter.register_callback(&any_instruments, callback) {
+                Ok(new_registration) => registration = Some(new_registration),
+                Err(err) => {
+                    tracing::warn!(err = ?err, "failed to register prometheus metrics callback")
+                }
+            }
+        }
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Counter,
+    Gauge,
+}
+
+#[derive(Clone)]
+enum Instrument {
+    Counter(ObservableCounter<f64>),
+    Gauge(ObservableGauge<f64>),
+}
+
+impl Instrument {
+    fn new(meter: &Meter, name: String, description: String, kind: Kind) -> Self {
+        match kind {
+            Kind::Counter => Self::Counter(
+                meter
+                    .f64_observable_counter(name)
+                    .with_description(description)
+                    .init(),
+            ),
+            Kind::Gauge => Self::Gauge(
+                meter
+                    .f64_observable_gauge(name)
+                    .with_description(description)
+                    .init(),
+            ),
+        }
+    }
+
+    fn as_any(&self) -> Arc<dyn Any> {
+        match self {
+            Self::Counter(counter) => counter.as_any(),
+            Self::Gauge(gauge) => gauge.as_any(),
+        }
+    }
+
+    fn observe(&self, observer: &dyn Observer, value: f64, attributes: &[KeyValue]) {
+        match self {
+            Self::Counter(counter) => observer.observe_f64(counter, value, attributes),
+            Self::Gauge(gauge) => observer.observe_f64(gauge, value, attributes),
+        }
+    }
+}
+
+/// Single time series value, named the same way as in the Prometheus text format
+/// (e.g., histograms are split into `_bucket`, `_sum` and `_count` series)
+#[derive(Debug, PartialEq)]
+struct Sample {
+    name: String,
+    kind: Kind,
+    value: f64,
+    attributes: Vec<KeyValue>,
+}
+
+fn samples(family: &MetricFamily) -> Vec<Sample> {
+    let name = family.get_name();
+    let mut samples = Vec::new();
+    let mut push = |suffix: &str, kind: Kind, value: f64, attributes: Vec<KeyValue>| {
+        samples.push(Sample {
+            name: format!("{name}{suffix}"),
+            kind,
+            value,
+            attributes,
+        })
+    };
+
+    for metric in family.get_metric() {
+        let labels: Vec<_> = metric
+            .get_label()
+            .iter()
+            .map(|label| KeyValue::new(label.get_name().to_string(), label.get_value().to_string()))
+            .collect();
+        let with_label = |key: &'static str, value: String| {
+            let mut attributes = labels.clone();
+            attributes.push(KeyValue::new(key, value));
+            attributes
+        };
+
+        match family.get_field_type() {
+            MetricType::COUNTER => push(
+                "",
+                Kind::Counter,
+                metric.get_counter().get_value(),
+                labels.clone(),
+            ),
+            MetricType::GAUGE => push(
+                "",
+                Kind::Gauge,
+                metric.get_gauge().get_value(),
+                labels.clone(),
+            ),
+            MetricType::UNTYPED => push(
+                "",
+                Kind::Gauge,
+                metric.get_untyped().get_value(),
+                labels.clone(),
+            ),
+            MetricType::HISTOGRAM => {
+                let histogram = metric.get_histogram();
+                let count = histogram.get_sample_count() as f64;
+                for bucket in histogram.get_bucket() {
+                    let le = bucket.get_upper_bound().to_string();
+                    let value = bucket.get_cumulative_count() as f64;
+                    push("_bucket", Kind::Counter, value, with_label("le", le));
+                }
+                push(
+                    "_bucket",
+                    Kind::Counter,
+                    count,
+                    with_label("le", "+Inf".to_string()),
+                );
+                push(
+                    "_sum",
+                    Kind::Counter,
+                    histogram.get_sample_sum(),
+                    labels.clone(),
+                );
+                push("_count", Kind::Counter, count, labels.clone());
+            }
+            MetricType::SUMMARY => {
+                let summary = metric.get_summary();
+                for quantile in summary.get_quantile() {
+                    let label = quantile.get_quantile().to_string();
+                    let value = quantile.get_value();
+                    push("", Kind::Gauge, value, with_label("quantile", label));
+                }
+                push(
+                    "_sum",
+                    Kind::Counter,
+                    summary.get_sample_sum(),
+                    labels.clone(),
+                );
+                let count = summary.get_sample_count() as f64;
+                push("_count", Kind::Counter, count, labels.clone());
+            }
+        }
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::{register_counter_vec_with_registry, HistogramOpts, HistogramVec, Registry};
+
+    fn sample(name: &str, kind: Kind, value: f64, attributes: &[(&'static str, &str)]) -> Sample {
+        Sample {
+            name: name.to_string(),
+            kind,
+            value,
+            attributes: attributes
+                .iter()
+                .map(|(key, value)| KeyValue::new(*key, value.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn counters_are_bridged_with_labels() {
+        let registry = Registry::new();
+        let counter =
+            register_counter_vec_with_registry!("requests", "help", &["method"], registry).unwrap();
+        counter.with_label_values(&["get"]).inc_by(3.0);
+
+        let families = registry.gather();
+        assert_eq!(
+            samples(&families[0]),
+            vec![sample("requests", Kind::Counter, 3.0, &[("method", "get")])]
+        );
+    }
+
+    #[test]
+    fn histograms_are_split_into_series() {
+        let registry = Registry::new();
+        let histogram = HistogramVec::new(
+            HistogramOpts::new("latency", "help").buckets(vec![1.0]),
+            &[],
+        )
+        .unwrap();
+        registry.register(Box::new(histogram.clone())).unwrap();
+        histogram.with_label_values(&[]).observe(0.5);
+        histogram.with_label_values(&[]).observe(2.0);
+
+        let families = registry.gather();
+        assert_eq!(
+            samples(&families[0]),
+            vec![
+                sample("latency_bucket", Kind::Counter, 1.0, &[("le", "1")]),
+                sample("latency_bucket", Kind::Counter, 2.0, &[("le", "+Inf")]),
+                sample("latency_sum", Kind::Counter, 2.5, &[]),
+                sample("latency_count", Kind::Counter, 2.0, &[]),
+            ]
+        );
+    }
+}
@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use std::{collections::BTreeMap, time::Duration};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -40,3 +42,103 @@ impl Default for JaegerSettings {
         }
     }
 }
+
+/// Export of traces and metrics to the OpenTelemetry collector
+/// via OTLP over http (complementary to the jaeger agent export).
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OtlpSettings {
+    /// Base url of the collector; traces and metrics are sent
+    /// to `/v1/traces` and `/v1/metrics` correspondingly
+    pub endpoint: String,
+    /// Additional headers sent with each export request (e.g., authorization)
+    pub headers: BTreeMap<String, String>,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub timeout: Duration,
+    pub traces: OtlpTracesSettings,
+    pub metrics: OtlpMetricsSettings,
+}
+
+impl OtlpSettings {
+    pub fn validate(&self) -> Result<(), anyhow::Error> {
+        let sampling_ratio = self.traces.sampling_ratio;
+        if !(0.0..=1.0).contains(&sampling_ratio) {
+            anyhow::bail!("otlp traces sampling ratio must be within [0, 1], got {sampling_ratio}");
+        }
+        Ok(())
+    }
+}
+
+impl Default for OtlpSettings {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://127.0.0.1:4318".to_string(),
+            headers: Default::default(),
+            timeout: Duration::from_secs(10),
+            traces: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OtlpTracesSettings {
+    pub enabled: bool,
+    /// Fraction of the root traces to be sampled (from 0 to 1).
+    /// Child spans follow the sampling decision of their parents.
+    pub sampling_ratio: f64,
+}
+
+impl Default for OtlpTracesSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sampling_ratio: 1.0,
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OtlpMetricsSettings {
+    /// If enabled, the meter provider exporting to the collector
+    /// is installed as the global `opentelemetry` one.
+    /// Metrics of the default Prometheus registry are exported as well.
+    pub enabled: bool,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub export_interval: Duration,
+}
+
+impl Default for OtlpMetricsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            export_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampling_ratio_is_validated() {
+        let settings = |sampling_ratio| OtlpSettings {
+            traces: OtlpTracesSettings {
+                enabled: true,
+                sampling_ratio,
+            },
+            ..Default::default()
+        };
+        for ratio in [0.0, 0.5, 1.0] {
+            assert!(settings(ratio).validate().is_ok(), "{ratio}");
+        }
+        for ratio in [-0.1, 1.5, f64::NAN] {
+            assert!(settings(ratio).validate().is_err(), "{ratio}");
+        }
+    }
+}
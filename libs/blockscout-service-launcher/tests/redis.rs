@@ -0,0 +1,55 @@
+use blockscout_service_launcher::redis::{initialize_redis, redis, RedisSettings};
+
+fn settings(namespace: &str) -> RedisSettings {
+    RedisSettings {
+        url: std::env::var("REDIS_URL").expect("Redis url must be set to run the test"),
+        pool: Default::default(),
+        namespace: Some(namespace.to_string()),
+    }
+}
+
+#[tokio::test]
+#[ignore = "needs redis to run"]
+async fn namespaced_keys_are_stored() {
+    let pool = initialize_redis(&settings("launcher_test"))
+        .await
+        .expect("failed to connect to redis");
+    pool.check_connection().await.unwrap();
+
+    let key = pool.key("namespaced_keys_are_stored");
+    assert_eq!("launcher_test:namespaced_keys_are_stored", key);
+
+    let mut connection = pool.get().await.unwrap();
+    let _: () = redis::cmd("SET")
+        .arg(&key)
+        .arg("value")
+        .arg("EX")
+        .arg(60)
+        .query_async(&mut *connection)
+        .await
+        .unwrap();
+    let value: Option<String> = redis::cmd("GET")
+        .arg(&key)
+        .query_async(&mut *connection)
+        .await
+        .unwrap();
+    assert_eq!(Some("value".to_string()), value);
+
+    let value: Option<String> = redis::cmd("GET")
+        .arg("namespaced_keys_are_stored")
+        .query_async(&mut *connection)
+        .await
+        .unwrap();
+    assert_eq!(None, value);
+}
+
+#[tokio::test]
+async fn unreachable_redis_is_reported() {
+    let settings = RedisSettings {
+        // nothing is expected to listen on the discard port
+        url: "redis://127.0.0.1:9".to_string(),
+        pool: Default::default(),
+        namespace: None,
+    };
+    assert!(initialize_redis(&settings).await.is_err());
+}
@@ -0,0 +1,210 @@
+use crate::{ReportedVariable, VALIDATE_ONLY_ENV};
+use anyhow::Context;
+use serde::Serialize;
+use std::{collections::BTreeMap, path::PathBuf, sync::Mutex};
+
+/// All variables are documented, or the markdown has been updated
+pub const EXIT_CODE_SUCCESS: i32 = 0;
+/// Some variables are not documented or documented incorrectly (only if `VALIDATE_ONLY` is set),
+/// or some documented variables are not used (only if `--fail-on-unused` is passed)
+pub const EXIT_CODE_INVALID: i32 = 1;
+/// Variables could not be collected, or the markdown could not be updated
+pub const EXIT_CODE_ERROR: i32 = 2;
+
+const DEFAULT_REPORT_PATH: &str = "env-collector-report.json";
+
+/// Unknown arguments are rejected, so that a typo in the CI configuration does not
+/// silently skip the report
+#[derive(Debug, Default)]
+struct CliArgs {
+    /// `--report json [--report-path <PATH>]`
+    report_path: Option<PathBuf>,
+    /// `--fail-on-unused`
+    fail_on_unused: bool,
+}
+
+impl CliArgs {
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, anyhow::Error> {
+        let mut args = args.into_iter();
+        let mut report_format = None;
+        let mut report_path = None;
+        let mut fail_on_unused = false;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--report" => report_format = Some(args.next().context("missing report format")?),
+                "--report-path" => report_path = Some(args.next().context("missing report path")?),
+                "--fail-on-unused" => fail_on_unused = true,
+                _ => anyhow::bail!("unknown argument: {arg}"),
+            }
+        }
+        let report_path = match report_format.as_deref() {
+            Some("json") => Some(
+                report_path
+                    .unwrap_or_else(|| DEFAULT_REPORT_PATH.to_string())
+                    .into(),
+            ),
+            Some(format) => anyhow::bail!("unsupported report format: {format}"),
+            None if report_path.is_some() => anyhow::bail!("--report-path requires --report"),
+            None => None,
+        };
+        Ok(Self {
+            report_path,
+            fail_on_unused,
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct Report {
+    /// No variables are missing or documented incorrectly
+    valid: bool,
+    /// The markdown has been updated with the missing variables
+    updated: bool,
+    /// Reported variables by the service name
+    services: BTreeMap<String, Vec<ReportedVariable>>,
+}
+
+impl Report {
+    fn merge(&mut self, other: Report) {
+        self.valid &= other.valid;
+        self.updated |= other.updated;
+        self.services.extend(other.services);
+    }
+}
+
+/// Reports written by the current process, so that the services checked by the same binary
+/// (e.g., several `run_env_collector_cli` calls) are written to the same file without
+/// overriding each other
+static WRITTEN_REPORTS: Mutex<BTreeMap<PathBuf, Report>> = Mutex::new(BTreeMap::new());
+
+/// Reports the variables of the services and updates the markdown if some of them are
+/// not documented properly, unless `VALIDATE_ONLY` is set. Returns the process exit code.
+pub(crate) fn check_services(
+    report: impl FnOnce() -> Result<BTreeMap<String, Vec<ReportedVariable>>, anyhow::Error>,
+    update_markdown: impl FnOnce() -> Result<(), anyhow::Error>,
+) -> i32 {
+    match try_check_services(report, update_markdown) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            EXIT_CODE_ERROR
+        }
+    }
+}
+
+fn try_check_services(
+    report: impl FnOnce() -> Result<BTreeMap<String, Vec<ReportedVariable>>, anyhow::Error>,
+    update_markdown: impl FnOnce() -> Result<(), anyhow::Error>,
+) -> Result<i32, anyhow::Error> {
+    let args = CliArgs::parse(std::env::args().skip(1))?;
+    let validate_only = std::env::var(VALIDATE_ONLY_ENV)
+        .unwrap_or_default()
+        .to_lowercase()
+        .eq("true");
+
+    let services = report()?;
+    for (service_name, vars) in services.iter().filter(|(_, vars)| !vars.is_empty()) {
+        println!("{service_name}:");
+        for var in vars {
+            println!("  {var}");
+        }
+    }
+    let valid = !services
+        .values()
+        .flatten()
+        .any(ReportedVariable::is_invalid);
+    // Unused variables may be documented intentionally, so they are never removed automatically
+    let has_unused = services
+        .values()
+        .flatten()
+        .any(|var| matches!(var, ReportedVariable::Unused(_)));
+    let updated = !valid && !validate_only;
+    if valid {
+        println!("All variables are documented");
+    } else if updated {
+        update_markdown().context("failed to update markdown")?;
+        println!("Markdown updated with the missing variables");
+    } else {
+        println!("Markdown is not updated, as {VALIDATE_ONLY_ENV} is set");
+    }
+    if has_unused {
+        println!("Warning: unused variables should be removed from the markdown manually");
+    }
+
+    if let Some(report_path) = args.report_path {
+        let report = Report {
+            valid,
+            updated,
+            services,
+        };
+        let mut written = WRITTEN_REPORTS.lock().unwrap();
+        let merged = match written.remove(&report_path) {
+            Some(mut merged) => {
+                merged.merge(report);
+                merged
+            }
+            None => report,
+        };
+        let content = serde_json::to_string_pretty(&merged)?;
+        written.insert(report_path.clone(), merged);
+        std::fs::write(&report_path, content).context("failed to write report")?;
+        println!("Report written to: {}", report_path.display());
+    }
+
+    if (!valid && !updated) || (args.fail_on_unused && has_unused) {
+        Ok(EXIT_CODE_INVALID)
+    } else {
+        Ok(EXIT_CODE_SUCCESS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse(args: &[&str]) -> Result<Option<PathBuf>, anyhow::Error> {
+        CliArgs::parse(args.iter().map(|arg| arg.to_string())).map(|args| args.report_path)
+    }
+
+    fn report(service: &str, valid: bool, updated: bool) -> Report {
+        Report {
+            valid,
+            updated,
+            services: BTreeMap::from([(service.to_string(), vec![])]),
+        }
+    }
+
+    #[test]
+    fn args_parsing_works() {
+        assert_eq!(parse(&[]).unwrap(), None);
+        assert_eq!(
+            parse(&["--report", "json"]).unwrap(),
+            Some(PathBuf::from(DEFAULT_REPORT_PATH))
+        );
+        assert_eq!(
+            parse(&["--report-path", "report.json", "--report", "json"]).unwrap(),
+            Some(PathBuf::from("report.json"))
+        );
+        assert!(parse(&["--report", "yaml"]).is_err());
+        assert!(parse(&["--report"]).is_err());
+        assert!(parse(&["--report-path", "report.json"]).is_err());
+        assert!(parse(&["--unknown"]).is_err());
+
+        let args = CliArgs::parse(["--fail-on-unused".to_string()]).unwrap();
+        assert!(args.fail_on_unused);
+        assert_eq!(args.report_path, None);
+    }
+
+    #[test]
+    fn reports_are_merged() {
+        let mut merged = report("first", true, false);
+        merged.merge(report("second", false, true));
+        assert!(!merged.valid);
+        assert!(merged.updated);
+        assert_eq!(
+            merged.services.keys().collect::<Vec<_>>(),
+            vec!["first", "second"]
+        );
+    }
+}
@@ -1,3 +1,6 @@
+mod cli;
+mod multi_service;
+
 use anyhow::Context;
 use config::{Config, File};
 use itertools::{Either, Itertools};
@@ -8,19 +11,33 @@ use std::{
     collections::BTreeMap,
     marker::PhantomData,
     path::{Path, PathBuf},
+    sync::Mutex,
 };
 
+pub use cli::{EXIT_CODE_ERROR, EXIT_CODE_INVALID, EXIT_CODE_SUCCESS};
+pub use multi_service::{run_multi_env_collector_cli, MultiEnvCollector};
+
 const ANCHOR_START: &str = "anchors.envs.start";
 const ANCHOR_END: &str = "anchors.envs.end";
 const VALIDATE_ONLY_ENV: &str = "VALIDATE_ONLY";
-
+const SCHEMA_OUTPUT_ENV: &str = "SCHEMA_OUTPUT";
+const ENV_EXAMPLE_OUTPUT_ENV: &str = "ENV_EXAMPLE_OUTPUT";
+const ANCHOR_DEPRECATED_START: &str = "anchors.envs.deprecated.start";
+const ANCHOR_DEPRECATED_END: &str = "anchors.envs.deprecated.end";
+const UNKNOWN_VARIANT: &str = "__env_collector_unknown_variant__";
+const UNKNOWN_FIELD: &str = "__env_collector_unknown_field__";
+
+/// Checks the variables of the service (see the README for the supported environment variables
+/// and arguments) and returns the process exit code, so that several services could be checked
+/// by the same binary before it exits.
+#[must_use]
 pub fn run_env_collector_cli<S: Serialize + DeserializeOwned>(
     service_name: &str,
     markdown_path: &str,
     config_path: &str,
     vars_filter: PrefixFilter,
     anchor_postfix: Option<&str>,
-) {
+) -> i32 {
     let collector = EnvCollector::<S>::new(
         service_name.to_string(),
         markdown_path.into(),
@@ -28,32 +45,39 @@ pub fn run_env_collector_cli<S: Serialize + DeserializeOwned>(
         vars_filter,
         anchor_postfix.map(|s| s.to_string()),
     );
-    let validate_only = std::env::var(VALIDATE_ONLY_ENV)
-        .unwrap_or_default()
-        .to_lowercase()
-        .eq("true");
-    let missing = collector
-        .find_missing()
-        .expect("Failed to find missing variables");
-    if missing.is_empty() {
-        println!("All variables are documented");
-    } else {
-        println!("Found missing variables:");
-        for env in missing {
-            println!("  {}", env.key);
-        }
-
-        if validate_only {
-            std::process::exit(1);
-        } else {
-            println!("Ready to update markdown file: {}", markdown_path);
-            println!("Press any key to continue...");
-            std::io::stdin().read_line(&mut String::new()).unwrap();
-            collector
-                .update_markdown()
-                .expect("Failed to update markdown");
-        }
+    if let Ok(schema_path) = std::env::var(SCHEMA_OUTPUT_ENV) {
+        return match collector.write_schema(Path::new(&schema_path)) {
+            Ok(()) => {
+                println!("Schema written to: {}", schema_path);
+                EXIT_CODE_SUCCESS
+            }
+            Err(err) => {
+                eprintln!("Error: failed to write schema: {err:#}");
+                EXIT_CODE_ERROR
+            }
+        };
+    }
+    if let Ok(example_path) = std::env::var(ENV_EXAMPLE_OUTPUT_ENV) {
+        return match collector.write_env_example(Path::new(&example_path)) {
+            Ok(()) => {
+                println!("Env example written to: {}", example_path);
+                EXIT_CODE_SUCCESS
+            }
+            Err(err) => {
+                eprintln!("Error: failed to write env example: {err:#}");
+                EXIT_CODE_ERROR
+            }
+        };
     }
+    cli::check_services(
+        || {
+            Ok(BTreeMap::from([(
+                service_name.to_string(),
+                collector.report()?,
+            )]))
+        },
+        || collector.update_markdown(),
+    )
 }
 
 #[derive(Debug, Clone)]
@@ -107,6 +131,231 @@ where
             self.anchor_postfix.clone(),
         )
     }
+
+    /// Aliases of the settings fields (see `#[serde(alias = "...")]`).
+    /// Detected only in the structures with `#[serde(deny_unknown_fields)]`.
+    pub fn find_deprecated(&self) -> Result<Vec<DeprecatedVariable>, anyhow::Error> {
+        find_deprecated_variables::<S>(
+            &self.service_name,
+            self.config_path
+                .to_str()
+                .expect("config path is not valid utf-8"),
+            self.vars_filter.clone(),
+        )
+    }
+
+    /// Verification result: missing and incorrectly documented variables fail the validation,
+    /// while the unused and the deprecated ones are only informational
+    pub fn report(&self) -> Result<Vec<ReportedVariable>, anyhow::Error> {
+        let example = Envs::from_example::<S>(
+            &self.service_name,
+            self.config_path
+                .to_str()
+                .expect("config path is not valid utf-8"),
+            self.vars_filter.clone(),
+        )?;
+        let markdown = Envs::from_markdown(
+            std::fs::read_to_string(&self.markdown_path)
+                .context("failed to read markdown file")?
+                .as_str(),
+            self.anchor_postfix.clone(),
+        )?;
+        let deprecated = self.find_deprecated()?;
+
+        let mut report = vec![];
+        for (id, var) in &example.vars {
+            match markdown.vars.get(id) {
+                None => report.push(ReportedVariable::Missing(var.clone())),
+                Some(documented) if !documented.eq_with_ignores(var) => {
+                    report.push(ReportedVariable::Incorrect(var.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+        for (id, var) in &markdown.vars {
+            let is_deprecated = deprecated.iter().any(|deprecated| deprecated.key == *id);
+            if !example.vars.contains_key(id) && !is_deprecated && self.vars_filter.filter(id) {
+                report.push(ReportedVariable::Unused(var.clone()));
+            }
+        }
+        report.extend(deprecated.into_iter().map(ReportedVariable::Deprecated));
+        Ok(report)
+    }
+
+    /// Collects the variables with the descriptions from the markdown table if documented
+    pub fn schema(&self) -> Result<Vec<EnvSchemaVariable>, anyhow::Error> {
+        let markdown = std::fs::read_to_string(&self.markdown_path)
+            .ok()
+            .and_then(|content| Envs::from_markdown(&content, self.anchor_postfix.clone()).ok());
+        let schema = collect_variables::<S>(
+            &self.service_name,
+            self.config_path
+                .to_str()
+                .expect("config path is not valid utf-8"),
+            self.vars_filter.clone(),
+        )?
+        .into_iter()
+        .map(|var| {
+            let documented = markdown
+                .as_ref()
+                .and_then(|markdown| markdown.vars.get(&var.key));
+            let description = match documented {
+                Some(documented) => documented.description.clone(),
+                None => var.description(),
+            };
+            let value_type = EnvValueType::of(&var.example)
+                .or_else(|| var.default_value.as_ref().and_then(EnvValueType::of));
+            EnvSchemaVariable {
+                required: var.default_value.is_none(),
+                key: var.key,
+                description,
+                default_value: var.default_value,
+                value_type,
+                allowed_values: var.allowed_values,
+            }
+        })
+        .collect();
+        Ok(schema)
+    }
+
+    /// Writes the schema as JSON or YAML depending on the file extension.
+    /// Schemas written to the same file by the current process are merged.
+    pub fn write_schema(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        if !matches!(extension, Some("json" | "yaml" | "yml")) {
+            anyhow::bail!("schema file extension should be one of: json, yaml, yml");
+        }
+        let schema = self.schema()?;
+
+        let mut written = WRITTEN_SCHEMAS.lock().unwrap();
+        let merged = written.entry(path.to_path_buf()).or_default();
+        merged.retain(|var| !schema.iter().any(|new| new.key == var.key));
+        merged.extend(schema);
+        let content = match extension {
+            Some("json") => serde_json::to_string_pretty(&merged)?,
+            _ => serde_yaml::to_string(&merged)?,
+        };
+        std::fs::write(path, content).context("failed to write schema file")
+    }
+
+    /// Required variables are set to the values of the example config,
+    /// the others are commented out with their default values
+    pub fn env_example(&self, format: EnvExampleFormat) -> Result<String, anyhow::Error> {
+        let variables = collect_variables::<S>(
+            &self.service_name,
+            self.config_path
+                .to_str()
+                .expect("config path is not valid utf-8"),
+            self.vars_filter.clone(),
+        )?;
+        let (required, optional): (Vec<_>, Vec<_>) = variables
+            .into_iter()
+            .partition(|var| var.default_value.is_none());
+
+        let mut lines = vec![];
+        for var in required {
+            lines.push((false, var.key, var.example));
+        }
+        for var in optional {
+            let default_value = var.default_value.unwrap_or_default();
+            lines.push((true, var.key, default_value));
+        }
+        let mut result = env_example_header(format).to_string();
+        for (commented, key, value) in lines {
+            let value = match value {
+                Value::Null => String::new(),
+                value => json_value_to_env_value(&value),
+            };
+            let comment = if commented { "# " } else { "" };
+            let line = match format {
+                EnvExampleFormat::Dotenv => {
+                    format!("{comment}{key}={}\n", quote_env_value(&value))
+                }
+                // List items are always strings, while the mapping values
+                // may be parsed by docker-compose as booleans or numbers
+                EnvExampleFormat::DockerCompose => {
+                    let item = serde_yaml::to_string(&format!("{key}={value}"))?;
+                    format!("  {comment}- {}\n", item.trim_end())
+                }
+            };
+            result.push_str(&line);
+        }
+        Ok(result)
+    }
+
+    /// Writes docker-compose `environment:` block for `.yaml` and `.yml` files
+    /// and `.env` file otherwise. Examples written to the same file by the current process
+    /// are merged.
+    pub fn write_env_example(&self, path: &Path) -> Result<(), anyhow::Error> {
+        let format = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("yaml" | "yml") => EnvExampleFormat::DockerCompose,
+            _ => EnvExampleFormat::Dotenv,
+        };
+        let header = env_example_header(format);
+        let example = self.env_example(format)?;
+
+        let mut written = WRITTEN_ENV_EXAMPLES.lock().unwrap();
+        let merged = written.entry(path.to_path_buf()).or_default();
+        merged.push_str(&example[header.len()..]);
+        std::fs::write(path, format!("{header}{merged}"))
+            .context("failed to write env example file")
+    }
+}
+
+/// Outputs written by the current process, so that the collectors of several settings
+/// (e.g., run by the same `check-envs` binary) write to the same file without overriding
+/// each other
+static WRITTEN_SCHEMAS: Mutex<BTreeMap<PathBuf, Vec<EnvSchemaVariable>>> =
+    Mutex::new(BTreeMap::new());
+static WRITTEN_ENV_EXAMPLES: Mutex<BTreeMap<PathBuf, String>> = Mutex::new(BTreeMap::new());
+
+fn env_example_header(format: EnvExampleFormat) -> &'static str {
+    match format {
+        EnvExampleFormat::Dotenv => "",
+        EnvExampleFormat::DockerCompose => "environment:\n",
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvExampleFormat {
+    /// `.env` file
+    Dotenv,
+    /// `environment:` block of the docker-compose service
+    DockerCompose,
+}
+
+/// Machine-readable description of the variable for the deployment tooling
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EnvSchemaVariable {
+    pub key: String,
+    pub required: bool,
+    pub default_value: Option<Value>,
+    pub description: String,
+    /// `None` if neither the example nor the default value is set
+    #[serde(rename = "type")]
+    pub value_type: Option<EnvValueType>,
+    /// Variants of the enum fields
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvValueType {
+    String,
+    Number,
+    Boolean,
+}
+
+impl EnvValueType {
+    fn of(value: &Value) -> Option<Self> {
+        match value {
+            Value::String(_) => Some(Self::String),
+            Value::Number(_) => Some(Self::Number),
+            Value::Bool(_) => Some(Self::Boolean),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -141,12 +390,55 @@ impl PrefixFilter {
     }
 }
 
-#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
+/// Variable accepted for compatibility after the corresponding field was renamed
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq, Serialize)]
+pub struct DeprecatedVariable {
+    pub key: String,
+    pub replaced_by: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReportedVariable {
+    /// Variable is not documented
+    Missing(EnvVariable),
+    /// Variable is documented with a different name or `required` flag
+    Incorrect(EnvVariable),
+    /// Variable is documented, but is not a part of the settings
+    Unused(EnvVariable),
+    Deprecated(DeprecatedVariable),
+}
+
+impl ReportedVariable {
+    pub fn is_invalid(&self) -> bool {
+        matches!(self, Self::Missing(_) | Self::Incorrect(_))
+    }
+}
+
+impl std::fmt::Display for ReportedVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(var) => write!(f, "missing: {}", var.key),
+            Self::Incorrect(var) => write!(f, "documented incorrectly: {}", var.key),
+            Self::Unused(var) => write!(f, "unused: {}", var.key),
+            Self::Deprecated(var) => {
+                write!(
+                    f,
+                    "deprecated: {} (replaced by {})",
+                    var.key, var.replaced_by
+                )
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq, Serialize)]
 pub struct EnvVariable {
     pub key: String,
     pub description: String,
     pub required: bool,
     pub default_value: Option<String>,
+    #[serde(skip)]
     pub table_index: Option<usize>,
 }
 
@@ -184,36 +476,26 @@ impl Envs {
     where
         S: Serialize + DeserializeOwned,
     {
-        let settings: S = Config::builder()
-            .add_source(File::with_name(example_config_path))
-            .build()
-            .context("failed to build config")?
-            .try_deserialize()
-            .context("failed to deserialize config")?;
-        let json = serde_json::to_value(&settings).context("failed to convert config to json")?;
-        let from_config: Envs = flatten_json(&json, service_prefix)
-            .into_iter()
-            .filter(|(key, _)| vars_filter.filter(key))
-            .map(|(key, value)| {
-                let default_value =
-                    default_of_var(&settings, &from_key_to_json_path(&key, service_prefix));
-                let required = default_value.is_none();
-                let description = try_get_description(&key, &value, &default_value);
-                let default_value =
-                    default_value.map(|v| format!("`{}`", json_value_to_env_value(&v)));
-                let var = EnvVariable {
-                    key: key.clone(),
-                    required,
-                    default_value,
-                    description,
-                    // No order in json
-                    table_index: None,
-                };
+        let from_config: Envs =
+            collect_variables::<S>(service_prefix, example_config_path, vars_filter)?
+                .into_iter()
+                .map(|var| {
+                    let description = var.description();
+                    let var = EnvVariable {
+                        key: var.key,
+                        required: var.default_value.is_none(),
+                        default_value: var
+                            .default_value
+                            .map(|v| format!("`{}`", json_value_to_env_value(&v))),
+                        description,
+                        // No order in json
+                        table_index: None,
+                    };
 
-                (key, var)
-            })
-            .collect::<BTreeMap<_, _>>()
-            .into();
+                    (var.key.clone(), var)
+                })
+                .collect::<BTreeMap<_, _>>()
+                .into();
 
         Ok(from_config)
     }
@@ -265,9 +547,23 @@ impl Envs {
         Ok(result)
     }
 
+    /// Adds the missing variables and fixes the incorrectly documented ones keeping their
+    /// descriptions. Variables missing in `other` are kept as is.
     pub fn update_no_override(&mut self, other: Envs) {
-        for (id, value) in other.vars {
-            self.vars.entry(id).or_insert(value);
+        for (id, var) in other.vars {
+            match self.vars.get_mut(&id) {
+                Some(documented) if !documented.eq_with_ignores(&var) => {
+                    *documented = EnvVariable {
+                        description: std::mem::take(&mut documented.description),
+                        table_index: documented.table_index,
+                        ..var
+                    };
+                }
+                Some(_) => {}
+                None => {
+                    self.vars.insert(id, var);
+                }
+            }
         }
     }
 
@@ -299,6 +595,72 @@ impl Envs {
     }
 }
 
+/// Variable of the example config with the default value it would have if not set
+struct CollectedVariable {
+    key: String,
+    example: Value,
+    default_value: Option<Value>,
+    allowed_values: Vec<String>,
+}
+
+impl CollectedVariable {
+    fn description(&self) -> String {
+        let example = try_get_description(&self.key, &self.example, &self.default_value);
+        if self.allowed_values.is_empty() {
+            return example;
+        }
+        let allowed_values = format!(
+            "one of {}",
+            self.allowed_values
+                .iter()
+                .map(|value| format!("`{value}`"))
+                .join(", ")
+        );
+        if example.is_empty() {
+            allowed_values
+        } else {
+            format!("{example}; {allowed_values}")
+        }
+    }
+}
+
+fn load_settings<S: DeserializeOwned>(config_path: &str) -> Result<S, anyhow::Error> {
+    Config::builder()
+        .add_source(File::with_name(config_path))
+        .build()
+        .context("failed to build config")?
+        .try_deserialize()
+        .context("failed to deserialize config")
+}
+
+fn collect_variables<S>(
+    service_prefix: &str,
+    example_config_path: &str,
+    vars_filter: PrefixFilter,
+) -> Result<Vec<CollectedVariable>, anyhow::Error>
+where
+    S: Serialize + DeserializeOwned,
+{
+    let settings: S = load_settings(example_config_path)?;
+    let json = serde_json::to_value(&settings).context("failed to convert config to json")?;
+    let variables = flatten_json(&json, service_prefix)
+        .into_iter()
+        .filter(|(key, _)| vars_filter.filter(key))
+        .map(|(key, example)| {
+            let path = from_key_to_json_path(&key, service_prefix);
+            let default_value = default_of_var(&settings, &path);
+            let allowed_values = allowed_values_of_var(&settings, &path, &example);
+            CollectedVariable {
+                key,
+                example,
+                default_value,
+                allowed_values,
+            }
+        })
+        .collect();
+    Ok(variables)
+}
+
 fn find_missing_variables_in_markdown<S>(
     service_name: &str,
     markdown_path: &Path,
@@ -348,13 +710,11 @@ fn update_markdown_file<S>(
 where
     S: Serialize + DeserializeOwned,
 {
-    let from_config = Envs::from_example::<S>(
-        service_name,
-        config_path
-            .to_str()
-            .expect("config path is not valid utf-8"),
-        vars_filter,
-    )?;
+    let config_path = config_path
+        .to_str()
+        .expect("config path is not valid utf-8");
+    let from_config = Envs::from_example::<S>(service_name, config_path, vars_filter.clone())?;
+    let deprecated = find_deprecated_variables::<S>(service_name, config_path, vars_filter)?;
     let mut markdown_config = Envs::from_markdown(
         std::fs::read_to_string(markdown_path)
             .context("failed to read markdown file")?
@@ -365,24 +725,150 @@ where
     let table = serialize_env_vars_to_md_table(markdown_config);
 
     let content = std::fs::read_to_string(markdown_path).context("failed to read markdown file")?;
+    let mut content = replace_anchored_lines(
+        &content,
+        &push_postfix_to_anchor(ANCHOR_START, anchor_postfix.clone()),
+        &push_postfix_to_anchor(ANCHOR_END, anchor_postfix.clone()),
+        &table,
+    )
+    .context("anchors.envs.start or anchors.envs.end not found in markdown")?;
+    // The table of deprecated variables is optional
+    let deprecated_table = serialize_deprecated_vars_to_md_table(&deprecated);
+    if let Some(updated) = replace_anchored_lines(
+        &content,
+        &push_postfix_to_anchor(ANCHOR_DEPRECATED_START, anchor_postfix.clone()),
+        &push_postfix_to_anchor(ANCHOR_DEPRECATED_END, anchor_postfix),
+        &deprecated_table,
+    ) {
+        content = updated;
+    }
+    std::fs::write(markdown_path, content).context("failed to write file")?;
+    Ok(())
+}
+
+/// Replaces the lines between the anchors, returns `None` if any anchor is not found
+fn replace_anchored_lines(
+    content: &str,
+    start_anchor: &str,
+    end_anchor: &str,
+    replacement: &str,
+) -> Option<String> {
     let lines = content.lines().collect::<Vec<&str>>();
-    let line_start = lines
-        .iter()
-        .position(|line| {
-            line.contains(&push_postfix_to_anchor(
-                ANCHOR_START,
-                anchor_postfix.clone(),
-            ))
-        })
-        .context("anchors.envs.start not found in markdown")?;
-    let line_end = lines
-        .iter()
-        .position(|line| line.contains(&push_postfix_to_anchor(ANCHOR_END, anchor_postfix.clone())))
-        .context("anchors.envs.end not found in markdown")?;
+    let line_start = lines.iter().position(|line| line.contains(start_anchor))?;
+    let line_end = lines.iter().position(|line| line.contains(end_anchor))?;
+    let new_content = [&lines[..=line_start], &[replacement], &lines[line_end..]].concat();
+    Some(new_content.join("\n"))
+}
 
-    let new_content = [&lines[..=line_start], &[&table], &lines[line_end..]].concat();
-    std::fs::write(markdown_path, new_content.join("\n")).context("failed to write file")?;
-    Ok(())
+/// Fields aliases, so that the variables renamed in the settings are still accepted.
+/// Aliases can be detected only in the structures with `#[serde(deny_unknown_fields)]`,
+/// as otherwise the names of the accepted fields are not reported by serde.
+fn find_deprecated_variables<S>(
+    service_prefix: &str,
+    example_config_path: &str,
+    vars_filter: PrefixFilter,
+) -> Result<Vec<DeprecatedVariable>, anyhow::Error>
+where
+    S: Serialize + DeserializeOwned,
+{
+    let settings: S = load_settings(example_config_path)?;
+    let json = serde_json::to_value(&settings).context("failed to convert config to json")?;
+    let mut object_paths = vec![];
+    collect_object_paths(&json, &mut vec![], &mut object_paths);
+
+    let mut deprecated = vec![];
+    for path in object_paths {
+        for (alias, field) in aliases_of_object::<S>(&json, &path) {
+            let key = |name: &str| {
+                [service_prefix]
+                    .into_iter()
+                    .chain(path.iter().map(String::as_str))
+                    .chain([name])
+                    .join("__")
+                    .to_uppercase()
+            };
+            let var = DeprecatedVariable {
+                key: key(&alias),
+                replaced_by: key(&field),
+            };
+            if vars_filter.filter(&var.key) {
+                deprecated.push(var);
+            }
+        }
+    }
+    deprecated.sort();
+    Ok(deprecated)
+}
+
+fn collect_object_paths(json: &Value, path: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+    if let Value::Object(map) = json {
+        paths.push(path.clone());
+        for (key, value) in map {
+            path.push(key.clone());
+            collect_object_paths(value, path, paths);
+            path.pop();
+        }
+    }
+}
+
+fn object_at_mut<'a>(
+    json: &'a mut Value,
+    path: &[String],
+) -> Option<&'a mut serde_json::Map<String, Value>> {
+    path.iter()
+        .try_fold(json, |json, key| json.get_mut(key))?
+        .as_object_mut()
+}
+
+/// Returns (alias, field) pairs of the object at the given path
+fn aliases_of_object<S>(json: &Value, path: &[String]) -> Vec<(String, String)>
+where
+    S: Serialize + DeserializeOwned,
+{
+    let mut probe = json.clone();
+    let Some(object) = object_at_mut(&mut probe, path) else {
+        return vec![];
+    };
+    let fields: Vec<String> = object.keys().cloned().collect();
+    object.insert(UNKNOWN_FIELD.to_string(), Value::Null);
+    let accepted = match serde_json::from_value::<S>(probe) {
+        Ok(_) => return vec![],
+        Err(error) => expected_names(&error.to_string(), "field", UNKNOWN_FIELD),
+    };
+
+    // Accepted names which are not serialized are either aliases or skipped fields,
+    // so the alias is confirmed only if it is deserialized into the same settings
+    let mut aliases = vec![];
+    for name in accepted.into_iter().filter(|name| !fields.contains(name)) {
+        let field = fields.iter().find(|field| {
+            let mut candidate = json.clone();
+            let object = object_at_mut(&mut candidate, path).expect("object path exists");
+            let value = object.remove(*field).expect("field exists");
+            object.insert(name.clone(), value);
+            serde_json::from_value::<S>(candidate)
+                .ok()
+                .and_then(|settings| serde_json::to_value(settings).ok())
+                .is_some_and(|deserialized| &deserialized == json)
+        });
+        if let Some(field) = field {
+            aliases.push((name, field.clone()));
+        }
+    }
+    aliases
+}
+
+/// Parses names from serde errors like "unknown field `x`, expected one of `a`, `b`"
+fn expected_names(error: &str, kind: &str, unknown: &str) -> Vec<String> {
+    let Some((_, expected)) = error
+        .strip_prefix(&format!("unknown {kind} `{unknown}`"))
+        .and_then(|rest| rest.split_once("expected"))
+    else {
+        return vec![];
+    };
+    let re = regex::Regex::new(r"`([^`]*)`").expect("regex should be valid");
+    re.captures_iter(expected)
+        .map(|c| c[1].to_string())
+        .collect()
 }
 
 fn default_of_var<S>(settings: &S, path: &str) -> Option<serde_json::Value>
@@ -402,7 +888,27 @@ where
     Some(default_value)
 }
 
-fn try_get_description(_key: &str, value: &str, default: &Option<serde_json::Value>) -> String {
+/// Variants are extracted from the error of deserializing an unknown variant,
+/// as they are not available via `Serialize` and `Deserialize` traits otherwise
+fn allowed_values_of_var<S>(settings: &S, path: &str, value: &Value) -> Vec<String>
+where
+    S: Serialize + DeserializeOwned,
+{
+    // Only unit variants are represented as strings (`null` for optional fields)
+    if !matches!(value, Value::String(_) | Value::Null) {
+        return vec![];
+    }
+    let mut json = serde_json::to_value(settings).expect("structure should be serializable");
+    json.dot_set(path, UNKNOWN_VARIANT)
+        .expect("value path not found");
+    match serde_json::from_value::<S>(json) {
+        Ok(_) => vec![],
+        Err(error) => expected_names(&error.to_string(), "variant", UNKNOWN_VARIANT),
+    }
+}
+
+fn try_get_description(_key: &str, value: &Value, default: &Option<serde_json::Value>) -> String {
+    let value = json_value_to_env_value(value);
     if value.is_empty() {
         return Default::default();
     }
@@ -456,6 +962,29 @@ fn serialize_env_vars_to_md_table(vars: Envs) -> String {
     result
 }
 
+fn serialize_deprecated_vars_to_md_table(vars: &[DeprecatedVariable]) -> String {
+    let mut result = r#"
+| Variable | Replaced by |
+| --- | --- |
+"#
+    .to_string();
+    for var in vars {
+        result.push_str(&format!("| `{}` | `{}` |\n", var.key, var.replaced_by));
+    }
+    result
+}
+
+fn quote_env_value(value: &str) -> String {
+    let needs_quotes = value
+        .chars()
+        .any(|c| c.is_whitespace() || matches!(c, '#' | '"' | '\'' | '\\' | '$'));
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
 fn push_postfix_to_anchor(anchor: &str, postfix: Option<String>) -> String {
     if let Some(postfix) = postfix {
         format!("{anchor}.{postfix}")
@@ -474,7 +1003,7 @@ fn regex_md_table_row() -> &'static str {
     )
 }
 
-fn flatten_json(json: &Value, initial_prefix: &str) -> BTreeMap<String, String> {
+fn flatten_json(json: &Value, initial_prefix: &str) -> BTreeMap<String, Value> {
     let mut env_vars = BTreeMap::new();
     _flat_json(json, initial_prefix, &mut env_vars);
     env_vars
@@ -490,7 +1019,7 @@ fn json_value_to_env_value(json: &Value) -> String {
     }
 }
 
-fn _flat_json(json: &Value, prefix: &str, env_vars: &mut BTreeMap<String, String>) {
+fn _flat_json(json: &Value, prefix: &str, env_vars: &mut BTreeMap<String, Value>) {
     match json {
         Value::Object(map) => {
             for (key, value) in map {
@@ -504,8 +1033,7 @@ fn _flat_json(json: &Value, prefix: &str, env_vars: &mut BTreeMap<String, String
         }
         _ => {
             let env_var_name = prefix.to_uppercase();
-            let env_var_value = json_value_to_env_value(json);
-            env_vars.insert(env_var_name, env_var_value);
+            env_vars.insert(env_var_name, json.clone());
         }
     }
 }
@@ -860,4 +1388,339 @@ mod tests {
 "#
         );
     }
+
+    #[test]
+    fn schema_works() {
+        let mut markdown = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            markdown,
+            r#"
+[anchor]: <> (anchors.envs.start)
+| `TEST_SERVICE__TEST` | true | documented description | |
+[anchor]: <> (anchors.envs.end)
+"#
+        )
+        .unwrap();
+        let config = default_config_example_file_toml();
+        let collector = EnvCollector::<TestSettings>::new(
+            "TEST_SERVICE".to_string(),
+            markdown.path().to_path_buf(),
+            config.path().to_path_buf(),
+            PrefixFilter::whitelist(&["TEST_SERVICE__TEST"]),
+            None,
+        );
+
+        let schema_file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        collector.write_schema(schema_file.path()).unwrap();
+        let schema: Value =
+            serde_json::from_str(&std::fs::read_to_string(schema_file.path()).unwrap()).unwrap();
+        assert_eq!(
+            schema,
+            serde_json::json!([
+                {
+                    "key": "TEST_SERVICE__TEST",
+                    "required": true,
+                    "default_value": null,
+                    "description": "documented description",
+                    "type": "string",
+                },
+                {
+                    "key": "TEST_SERVICE__TEST2",
+                    "required": false,
+                    "default_value": 1000,
+                    "description": "e.g. `123`",
+                    "type": "number",
+                },
+                {
+                    "key": "TEST_SERVICE__TEST3_SET",
+                    "required": false,
+                    "default_value": null,
+                    "description": "e.g. `false`",
+                    "type": "boolean",
+                },
+                {
+                    "key": "TEST_SERVICE__TEST4_NOT_SET",
+                    "required": false,
+                    "default_value": null,
+                    "description": "",
+                    "type": null,
+                },
+                {
+                    "key": "TEST_SERVICE__TEST5_WITH_UNICODE",
+                    "required": false,
+                    "default_value": false,
+                    "description": "",
+                    "type": "boolean",
+                },
+            ])
+        );
+
+        let invalid_file = tempfile::Builder::new().suffix(".txt").tempfile().unwrap();
+        assert!(collector.write_schema(invalid_file.path()).is_err());
+    }
+
+    #[test]
+    fn outputs_of_several_collectors_are_merged() {
+        let config = default_config_example_file_toml();
+        let collector = |prefix: &str| {
+            EnvCollector::<TestSettings>::new(
+                "TEST_SERVICE".to_string(),
+                PathBuf::from("README.md"),
+                config.path().to_path_buf(),
+                PrefixFilter::whitelist(&[prefix]),
+                None,
+            )
+        };
+        let first = collector("TEST_SERVICE__TEST2");
+        let second = collector("TEST_SERVICE__STRING_WITH_DEFAULT");
+
+        let schema_file = tempfile::Builder::new().suffix(".json").tempfile().unwrap();
+        first.write_schema(schema_file.path()).unwrap();
+        second.write_schema(schema_file.path()).unwrap();
+        let schema: Vec<Value> =
+            serde_json::from_str(&std::fs::read_to_string(schema_file.path()).unwrap()).unwrap();
+        assert_eq!(
+            schema.iter().map(|var| &var["key"]).collect::<Vec<_>>(),
+            vec!["TEST_SERVICE__TEST2", "TEST_SERVICE__STRING_WITH_DEFAULT"]
+        );
+
+        let example_file = tempfile::Builder::new().suffix(".yaml").tempfile().unwrap();
+        first.write_env_example(example_file.path()).unwrap();
+        second.write_env_example(example_file.path()).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(example_file.path()).unwrap(),
+            "environment:\n  # - TEST_SERVICE__TEST2=1000\n  # - TEST_SERVICE__STRING_WITH_DEFAULT=kekek\n"
+        );
+    }
+
+    #[test]
+    fn env_example_works() {
+        let config = default_config_example_file_toml();
+        let collector = EnvCollector::<TestSettings>::new(
+            "TEST_SERVICE".to_string(),
+            PathBuf::from("README.md"),
+            config.path().to_path_buf(),
+            PrefixFilter::whitelist(&["TEST_SERVICE__TEST"]),
+            None,
+        );
+
+        assert_eq!(
+            collector.env_example(EnvExampleFormat::Dotenv).unwrap(),
+            r#"TEST_SERVICE__TEST=value
+# TEST_SERVICE__TEST2=1000
+# TEST_SERVICE__TEST3_SET=
+# TEST_SERVICE__TEST4_NOT_SET=
+# TEST_SERVICE__TEST5_WITH_UNICODE=false
+"#
+        );
+        assert_eq!(
+            collector
+                .env_example(EnvExampleFormat::DockerCompose)
+                .unwrap(),
+            r#"environment:
+  - TEST_SERVICE__TEST=value
+  # - TEST_SERVICE__TEST2=1000
+  # - TEST_SERVICE__TEST3_SET=
+  # - TEST_SERVICE__TEST4_NOT_SET=
+  # - TEST_SERVICE__TEST5_WITH_UNICODE=false
+"#
+        );
+        assert_eq!(quote_env_value("with space"), r#""with space""#);
+        assert_eq!(quote_env_value(r#"a"b"#), r#""a\"b""#);
+    }
+
+    #[test]
+    fn allowed_values_works() {
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+        #[serde(rename_all = "snake_case")]
+        enum Mode {
+            Fast,
+            SlowAndSteady,
+        }
+
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+        struct EnumSettings {
+            mode: Mode,
+            #[serde(default)]
+            optional_mode: Option<Mode>,
+            name: String,
+        }
+
+        let config = tempfile_with_content(
+            r#"mode = "fast"
+            name = "value"
+            "#,
+            ".toml",
+        );
+        let descriptions: Vec<_> = Envs::from_example::<EnumSettings>(
+            "TEST_SERVICE",
+            config.path().to_str().unwrap(),
+            PrefixFilter::Empty,
+        )
+        .unwrap()
+        .vars
+        .into_values()
+        .map(|var| (var.key, var.description))
+        .collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                (
+                    "TEST_SERVICE__MODE".to_string(),
+                    "e.g. `fast`; one of `fast`, `slow_and_steady`".to_string()
+                ),
+                ("TEST_SERVICE__NAME".to_string(), "e.g. `value`".to_string()),
+                (
+                    "TEST_SERVICE__OPTIONAL_MODE".to_string(),
+                    "one of `fast`, `slow_and_steady`".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn deprecated_variables_work() {
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+        #[serde(deny_unknown_fields)]
+        struct ServerSettings {
+            #[serde(alias = "address")]
+            addr: String,
+            #[serde(default)]
+            enabled: bool,
+        }
+
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+        #[serde(deny_unknown_fields)]
+        struct RenamedSettings {
+            #[serde(alias = "http")]
+            server: ServerSettings,
+            #[serde(alias = "chain_id_old", alias = "network_id")]
+            chain_id: u64,
+        }
+
+        let mut markdown = tempfile::NamedTempFile::new().unwrap();
+        writeln!(
+            markdown,
+            r#"[anchor]: <> (anchors.envs.start)
+[anchor]: <> (anchors.envs.end)
+[anchor]: <> (anchors.envs.deprecated.start)
+[anchor]: <> (anchors.envs.deprecated.end)"#
+        )
+        .unwrap();
+        let config = tempfile_with_content(
+            r#"chain_id = 1
+            [server]
+            addr = "0.0.0.0:8050"
+            "#,
+            ".toml",
+        );
+        let collector = EnvCollector::<RenamedSettings>::new(
+            "TEST_SERVICE".to_string(),
+            markdown.path().to_path_buf(),
+            config.path().to_path_buf(),
+            PrefixFilter::Empty,
+            None,
+        );
+
+        let deprecated = |key: &str, replaced_by: &str| DeprecatedVariable {
+            key: key.to_string(),
+            replaced_by: replaced_by.to_string(),
+        };
+        assert_eq!(
+            collector.find_deprecated().unwrap(),
+            vec![
+                deprecated("TEST_SERVICE__CHAIN_ID_OLD", "TEST_SERVICE__CHAIN_ID"),
+                deprecated("TEST_SERVICE__HTTP", "TEST_SERVICE__SERVER"),
+                deprecated("TEST_SERVICE__NETWORK_ID", "TEST_SERVICE__CHAIN_ID"),
+                deprecated(
+                    "TEST_SERVICE__SERVER__ADDRESS",
+                    "TEST_SERVICE__SERVER__ADDR"
+                ),
+            ]
+        );
+
+        collector.update_markdown().unwrap();
+        let markdown_content = std::fs::read_to_string(markdown.path()).unwrap();
+        assert_eq!(
+            markdown_content,
+            r#"[anchor]: <> (anchors.envs.start)
+
+| Variable | Req&#x200B;uir&#x200B;ed | Description | Default value |
+| --- | --- | --- | --- |
+| `TEST_SERVICE__CHAIN_ID` | true | e.g. `1` | |
+| `TEST_SERVICE__SERVER__ADDR` | true | e.g. `0.0.0.0:8050` | |
+| `TEST_SERVICE__SERVER__ENABLED` | | | `false` |
+
+[anchor]: <> (anchors.envs.end)
+[anchor]: <> (anchors.envs.deprecated.start)
+
+| Variable | Replaced by |
+| --- | --- |
+| `TEST_SERVICE__CHAIN_ID_OLD` | `TEST_SERVICE__CHAIN_ID` |
+| `TEST_SERVICE__HTTP` | `TEST_SERVICE__SERVER` |
+| `TEST_SERVICE__NETWORK_ID` | `TEST_SERVICE__CHAIN_ID` |
+| `TEST_SERVICE__SERVER__ADDRESS` | `TEST_SERVICE__SERVER__ADDR` |
+
+[anchor]: <> (anchors.envs.deprecated.end)"#
+        );
+    }
+
+    #[test]
+    fn report_works() {
+        #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+        #[serde(deny_unknown_fields)]
+        struct ReportSettings {
+            documented: String,
+            #[serde(default)]
+            incorrect: bool,
+            missing: String,
+            #[serde(alias = "old_name")]
+            new_name: String,
+        }
+
+        let markdown = tempfile_with_content(
+            r#"[anchor]: <> (anchors.envs.start)
+| `TEST_SERVICE__DOCUMENTED` | true | | |
+| `TEST_SERVICE__INCORRECT` | true | | |
+| `TEST_SERVICE__NEW_NAME` | true | | |
+| `TEST_SERVICE__OLD_NAME` | | | |
+| `TEST_SERVICE__REMOVED` | | | |
+[anchor]: <> (anchors.envs.end)"#,
+            ".md",
+        );
+        let config = tempfile_with_content(
+            r#"documented = "value"
+            missing = "value"
+            new_name = "value"
+            "#,
+            ".toml",
+        );
+        let collector = EnvCollector::<ReportSettings>::new(
+            "TEST_SERVICE".to_string(),
+            markdown.path().to_path_buf(),
+            config.path().to_path_buf(),
+            PrefixFilter::Empty,
+            None,
+        );
+
+        let report = collector.report().unwrap();
+        assert_eq!(
+            report.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "documented incorrectly: TEST_SERVICE__INCORRECT",
+                "missing: TEST_SERVICE__MISSING",
+                "unused: TEST_SERVICE__REMOVED",
+                "deprecated: TEST_SERVICE__OLD_NAME (replaced by TEST_SERVICE__NEW_NAME)",
+            ]
+        );
+        assert_eq!(report.iter().filter(|var| var.is_invalid()).count(), 2);
+        assert_eq!(
+            serde_json::to_value(&report[3]).unwrap(),
+            serde_json::json!({
+                "kind": "deprecated",
+                "key": "TEST_SERVICE__OLD_NAME",
+                "replaced_by": "TEST_SERVICE__NEW_NAME",
+            })
+        );
+    }
 }
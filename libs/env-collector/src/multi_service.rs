@@ -0,0 +1,264 @@
+use crate::{cli, collect_variables, EnvCollector, EnvVariable, PrefixFilter, ReportedVariable};
+use itertools::Itertools;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// Checks the variables of all the services, returns the process exit code
+#[must_use]
+pub fn run_multi_env_collector_cli(collector: MultiEnvCollector) -> i32 {
+    cli::check_services(
+        || {
+            collector.check_conflicts()?;
+            collector.report()
+        },
+        || collector.update_markdown(),
+    )
+}
+
+/// Documents several services in a single markdown file,
+/// each service has its own table marked by the anchors with the service postfix
+pub struct MultiEnvCollector {
+    markdown_path: PathBuf,
+    services: Vec<Box<dyn ServiceEnvCollector>>,
+}
+
+impl MultiEnvCollector {
+    pub fn new(markdown_path: PathBuf) -> Self {
+        Self {
+            markdown_path,
+            services: vec![],
+        }
+    }
+
+    pub fn add_service<S>(
+        mut self,
+        service_name: String,
+        config_path: PathBuf,
+        vars_filter: PrefixFilter,
+        anchor_postfix: String,
+    ) -> Self
+    where
+        S: Serialize + DeserializeOwned + 'static,
+    {
+        self.services.push(Box::new(EnvCollector::<S>::new(
+            service_name,
+            self.markdown_path.clone(),
+            config_path,
+            vars_filter,
+            Some(anchor_postfix),
+        )));
+        self
+    }
+
+    /// Services should have distinct anchors and should not share any variables
+    pub fn check_conflicts(&self) -> Result<(), anyhow::Error> {
+        let mut errors = vec![];
+
+        let duplicated_anchors = self
+            .services
+            .iter()
+            .map(|service| service.anchor_postfix())
+            .duplicates()
+            .collect::<Vec<_>>();
+        for anchor in duplicated_anchors {
+            errors.push(format!(
+                "anchor postfix \"{anchor}\" is used by several services"
+            ));
+        }
+
+        let mut owners: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for service in &self.services {
+            for key in service.keys()? {
+                owners.entry(key).or_default().push(service.service_name());
+            }
+        }
+        for (key, services) in owners.into_iter().filter(|(_, owners)| owners.len() > 1) {
+            errors.push(format!(
+                "variable {key} is defined by several services: {}",
+                services.into_iter().join(", ")
+            ));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(errors.join("; ")))
+        }
+    }
+
+    /// Missing variables of each service by the service name
+    pub fn find_missing(&self) -> Result<BTreeMap<String, Vec<EnvVariable>>, anyhow::Error> {
+        self.services
+            .iter()
+            .map(|service| Ok((service.service_name().to_string(), service.find_missing()?)))
+            .collect()
+    }
+
+    /// Reported variables of each service by the service name
+    pub fn report(&self) -> Result<BTreeMap<String, Vec<ReportedVariable>>, anyhow::Error> {
+        self.services
+            .iter()
+            .map(|service| Ok((service.service_name().to_string(), service.report()?)))
+            .collect()
+    }
+
+    pub fn update_markdown(&self) -> Result<(), anyhow::Error> {
+        for service in &self.services {
+            service.update_markdown()?;
+        }
+        Ok(())
+    }
+}
+
+/// Allows to document the services with different settings types together
+trait ServiceEnvCollector {
+    fn service_name(&self) -> &str;
+    fn anchor_postfix(&self) -> &str;
+    fn keys(&self) -> Result<Vec<String>, anyhow::Error>;
+    fn find_missing(&self) -> Result<Vec<EnvVariable>, anyhow::Error>;
+    fn report(&self) -> Result<Vec<ReportedVariable>, anyhow::Error>;
+    fn update_markdown(&self) -> Result<(), anyhow::Error>;
+}
+
+impl<S> ServiceEnvCollector for EnvCollector<S>
+where
+    S: Serialize + DeserializeOwned,
+{
+    fn service_name(&self) -> &str {
+        &self.service_name
+    }
+
+    fn anchor_postfix(&self) -> &str {
+        self.anchor_postfix.as_deref().unwrap_or_default()
+    }
+
+    fn keys(&self) -> Result<Vec<String>, anyhow::Error> {
+        let vars = collect_variables::<S>(
+            &self.service_name,
+            self.config_path
+                .to_str()
+                .expect("config path is not valid utf-8"),
+            self.vars_filter.clone(),
+        )?;
+        Ok(vars.into_iter().map(|var| var.key).collect())
+    }
+
+    fn find_missing(&self) -> Result<Vec<EnvVariable>, anyhow::Error> {
+        EnvCollector::find_missing(self)
+    }
+
+    fn report(&self) -> Result<Vec<ReportedVariable>, anyhow::Error> {
+        EnvCollector::report(self)
+    }
+
+    fn update_markdown(&self) -> Result<(), anyhow::Error> {
+        EnvCollector::update_markdown(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+    use std::io::Write;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    struct ApiSettings {
+        pub url: String,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    struct IndexerSettings {
+        #[serde(default)]
+        pub enabled: bool,
+    }
+
+    fn tempfile_with_content(content: &str, format: &str) -> tempfile::NamedTempFile {
+        let mut file = tempfile::Builder::new().suffix(format).tempfile().unwrap();
+        writeln!(file, "{}", content).unwrap();
+        file
+    }
+
+    #[test]
+    fn multiple_services_work() {
+        let markdown = tempfile_with_content(
+            r#"
+[anchor]: <> (anchors.envs.start.api)
+[anchor]: <> (anchors.envs.end.api)
+
+[anchor]: <> (anchors.envs.start.indexer)
+[anchor]: <> (anchors.envs.end.indexer)
+"#,
+            ".md",
+        );
+        let api_config = tempfile_with_content(r#"url = "http://localhost""#, ".toml");
+        let indexer_config = tempfile_with_content("enabled = true", ".toml");
+        let collector = MultiEnvCollector::new(markdown.path().to_path_buf())
+            .add_service::<ApiSettings>(
+                "API".to_string(),
+                api_config.path().to_path_buf(),
+                PrefixFilter::Empty,
+                "api".to_string(),
+            )
+            .add_service::<IndexerSettings>(
+                "INDEXER".to_string(),
+                indexer_config.path().to_path_buf(),
+                PrefixFilter::Empty,
+                "indexer".to_string(),
+            );
+
+        collector.check_conflicts().unwrap();
+        let missing = collector.find_missing().unwrap();
+        assert_eq!(
+            missing
+                .iter()
+                .map(|(service, vars)| (service.as_str(), vars.len()))
+                .collect::<Vec<_>>(),
+            vec![("API", 1), ("INDEXER", 1)]
+        );
+        collector.update_markdown().unwrap();
+        assert!(collector
+            .find_missing()
+            .unwrap()
+            .values()
+            .all(Vec::is_empty));
+        // Trailing new lines are not preserved
+        assert_eq!(
+            std::fs::read_to_string(markdown.path()).unwrap().trim_end(),
+            r#"
+[anchor]: <> (anchors.envs.start.api)
+
+| Variable | Req&#x200B;uir&#x200B;ed | Description | Default value |
+| --- | --- | --- | --- |
+| `API__URL` | true | e.g. `http://localhost` | |
+
+[anchor]: <> (anchors.envs.end.api)
+
+[anchor]: <> (anchors.envs.start.indexer)
+
+| Variable | Req&#x200B;uir&#x200B;ed | Description | Default value |
+| --- | --- | --- | --- |
+| `INDEXER__ENABLED` | | e.g. `true` | `false` |
+
+[anchor]: <> (anchors.envs.end.indexer)"#
+        );
+
+        let conflicting = MultiEnvCollector::new(markdown.path().to_path_buf())
+            .add_service::<ApiSettings>(
+                "API".to_string(),
+                api_config.path().to_path_buf(),
+                PrefixFilter::Empty,
+                "api".to_string(),
+            )
+            .add_service::<ApiSettings>(
+                "API".to_string(),
+                api_config.path().to_path_buf(),
+                PrefixFilter::Empty,
+                "api".to_string(),
+            );
+        let error = conflicting.check_conflicts().unwrap_err().to_string();
+        assert!(error.contains("anchor postfix \"api\""), "{error}");
+        assert!(error.contains("variable API__URL"), "{error}");
+    }
+}
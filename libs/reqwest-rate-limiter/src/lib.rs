@@ -53,6 +53,23 @@ where
     }
 }
 
+impl<S, C, MW, PO> RateLimiterMiddleware<state::direct::NotKeyed, S, C, MW>
+where
+    S: state::StateStore<Key = state::direct::NotKeyed>,
+    C: clock::Clock + clock::ReasonablyRealtime,
+    MW: middleware::RateLimitingMiddleware<
+        C::Instant,
+        NegativeOutcome = NotUntil<C::Instant>,
+        PositiveOutcome = PO,
+    >,
+{
+    /// Waits until the next request is allowed, so that the same limits
+    /// could be applied to the requests not sent via `reqwest` (e.g., rpc transports)
+    pub async fn until_ready(&self) -> PO {
+        self.rate_limiter.until_ready().await
+    }
+}
+
 #[async_trait::async_trait]
 impl<S, C, MW, PO> Middleware for RateLimiterMiddleware<state::direct::NotKeyed, S, C, MW>
 where
@@ -73,7 +90,7 @@ where
         extensions: &mut http::Extensions,
         next: Next<'_>,
     ) -> reqwest_middleware::Result<Response> {
-        self.rate_limiter.until_ready().await;
+        self.until_ready().await;
         next.run(req, extensions).await
     }
 }
@@ -3,13 +3,25 @@ use semver::Version;
 use std::str::FromStr;
 use thiserror::Error;
 
+mod scan;
+
+pub use scan::{find_metadata_hashes, MetadataHashSegment};
+
 /// Parsed metadata hash
 /// (https://docs.soliditylang.org/en/v0.8.14/metadata.html#encoding-of-the-metadata-hash-in-the-bytecode).
 ///
-/// Currently we are interested only in `solc` value.
+/// Unknown keys are skipped.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct MetadataHash {
     pub solc: Option<Version>,
+    /// IPFS multihash of the metadata file (appeared in 0.6.0)
+    pub ipfs: Option<Vec<u8>>,
+    /// Swarm hash of the metadata file (used from 0.4.7 until 0.5.12)
+    pub bzzr0: Option<Vec<u8>>,
+    /// Swarm hash of the metadata file (used from 0.5.12 until 0.6.0)
+    pub bzzr1: Option<Vec<u8>>,
+    /// Whether the contract has been compiled with experimental features enabled
+    pub experimental: bool,
 }
 
 impl MetadataHash {
@@ -19,6 +31,13 @@ impl MetadataHash {
 
         Ok((result, context.used_size))
     }
+
+    /// IPFS CID (v0) of the metadata file, which may be used to retrieve it from IPFS gateways
+    pub fn ipfs_cid(&self) -> Option<String> {
+        self.ipfs
+            .as_ref()
+            .map(|ipfs| bs58::encode(ipfs).into_string())
+    }
 }
 
 #[derive(Clone, Debug, Error, PartialEq, Eq, Hash)]
@@ -27,8 +46,12 @@ enum ParseMetadataHashError {
     InvalidSolcType(Type),
     #[error("solc is not a valid version: {0}")]
     InvalidSolcVersion(String),
-    #[error("\"solc\" key met more than once")]
-    DuplicateKeys,
+    #[error("invalid \"{key}\" type. Expected \"bytes\", found \"{type_}\"")]
+    InvalidHashType { key: String, type_: Type },
+    #[error("invalid experimental type. Expected \"bool\", found \"{0}\"")]
+    InvalidExperimentalType(Type),
+    #[error("\"{0}\" key met more than once")]
+    DuplicateKeys(String),
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq)]
@@ -41,60 +64,35 @@ impl<'b> Decode<'b, DecodeContext> for MetadataHash {
         d: &mut Decoder<'b>,
         ctx: &mut DecodeContext,
     ) -> Result<Self, minicbor::decode::Error> {
-        use minicbor::decode::Error;
-
         let number_of_elements = d.map()?.unwrap_or(u64::MAX);
 
         let mut solc = None;
+        let mut ipfs = None;
+        let mut bzzr0 = None;
+        let mut bzzr1 = None;
+        let mut experimental = None;
         for _ in 0..number_of_elements {
             // try to parse the key
-            match d.str() {
-                Ok("solc") => {
-                    if solc.is_some() {
-                        // duplicate keys are not allowed in CBOR (RFC 8949)
-                        return Err(Error::custom(ParseMetadataHashError::DuplicateKeys));
-                    }
-                    solc = match d.datatype()? {
-                        // Appeared in 0.5.9.
-                        // https://docs.soliditylang.org/en/v0.8.17/metadata.html#encoding-of-the-metadata-hash-in-the-bytecode
-                        Type::Bytes => {
-                            // Release builds of solc use a 3 byte encoding of the version
-                            // (one byte each for major, minor and patch version number)
-                            let bytes = d.bytes()?;
-                            if bytes.len() != 3 {
-                                // Something went wrong
-                                return Err(Error::custom(
-                                    ParseMetadataHashError::InvalidSolcVersion(
-                                        "release build should be encoded as exactly 3 bytes".into(),
-                                    ),
-                                ));
-                            }
-                            let (major, minor, patch) = (bytes[0], bytes[1], bytes[2]);
-                            Some(Version::new(major as u64, minor as u64, patch as u64))
-                        }
-                        Type::String => {
-                            // Prerelease builds use a complete version string including commit hash and build date
-                            let s = d.str()?;
-                            let version = Version::from_str(s).map_err(|err| {
-                                Error::custom(ParseMetadataHashError::InvalidSolcVersion(
-                                    err.to_string(),
-                                ))
-                            })?;
-                            Some(version)
-                        }
+            match d.str()? {
+                "solc" => set_once(&mut solc, "solc", decode_solc(d)?)?,
+                "ipfs" => set_once(&mut ipfs, "ipfs", decode_hash(d, "ipfs")?)?,
+                "bzzr0" => set_once(&mut bzzr0, "bzzr0", decode_hash(d, "bzzr0")?)?,
+                "bzzr1" => set_once(&mut bzzr1, "bzzr1", decode_hash(d, "bzzr1")?)?,
+                "experimental" => {
+                    let value = match d.datatype()? {
+                        Type::Bool => d.bool()?,
                         type_ => {
-                            // value of "solc" key must be either String or Bytes
-                            return Err(Error::custom(ParseMetadataHashError::InvalidSolcType(
-                                type_,
-                            )));
+                            return Err(minicbor::decode::Error::custom(
+                                ParseMetadataHashError::InvalidExperimentalType(type_),
+                            ))
                         }
-                    }
+                    };
+                    set_once(&mut experimental, "experimental", value)?
                 }
-                Ok(_) => {
-                    // if key is not "solc" str we may skip the corresponding value
+                _ => {
+                    // the values of unknown keys may be skipped
                     d.skip()?;
                 }
-                Err(err) => return Err(err),
             }
         }
 
@@ -103,11 +101,75 @@ impl<'b> Decode<'b, DecodeContext> for MetadataHash {
         // function.
         ctx.used_size = d.position();
 
-        Ok(MetadataHash { solc })
+        Ok(MetadataHash {
+            solc,
+            ipfs,
+            bzzr0,
+            bzzr1,
+            experimental: experimental.unwrap_or_default(),
+        })
     }
 
     fn nil() -> Option<Self> {
-        Some(Self { solc: None })
+        Some(Self::default())
+    }
+}
+
+fn set_once<T>(field: &mut Option<T>, key: &str, value: T) -> Result<(), minicbor::decode::Error> {
+    if field.is_some() {
+        // duplicate keys are not allowed in CBOR (RFC 8949)
+        return Err(minicbor::decode::Error::custom(
+            ParseMetadataHashError::DuplicateKeys(key.to_string()),
+        ));
+    }
+    *field = Some(value);
+    Ok(())
+}
+
+fn decode_solc(d: &mut Decoder) -> Result<Version, minicbor::decode::Error> {
+    use minicbor::decode::Error;
+
+    match d.datatype()? {
+        // Appeared in 0.5.9.
+        // https://docs.soliditylang.org/en/v0.8.17/metadata.html#encoding-of-the-metadata-hash-in-the-bytecode
+        Type::Bytes => {
+            // Release builds of solc use a 3 byte encoding of the version
+            // (one byte each for major, minor and patch version number)
+            let bytes = d.bytes()?;
+            if bytes.len() != 3 {
+                // Something went wrong
+                return Err(Error::custom(ParseMetadataHashError::InvalidSolcVersion(
+                    "release build should be encoded as exactly 3 bytes".into(),
+                )));
+            }
+            let (major, minor, patch) = (bytes[0], bytes[1], bytes[2]);
+            Ok(Version::new(major as u64, minor as u64, patch as u64))
+        }
+        Type::String => {
+            // Prerelease builds use a complete version string including commit hash and build date
+            let s = d.str()?;
+            Version::from_str(s).map_err(|err| {
+                Error::custom(ParseMetadataHashError::InvalidSolcVersion(err.to_string()))
+            })
+        }
+        type_ => {
+            // value of "solc" key must be either String or Bytes
+            Err(Error::custom(ParseMetadataHashError::InvalidSolcType(
+                type_,
+            )))
+        }
+    }
+}
+
+fn decode_hash(d: &mut Decoder, key: &str) -> Result<Vec<u8>, minicbor::decode::Error> {
+    match d.datatype()? {
+        Type::Bytes => Ok(d.bytes()?.to_vec()),
+        type_ => Err(minicbor::decode::Error::custom(
+            ParseMetadataHashError::InvalidHashType {
+                key: key.to_string(),
+                type_,
+            },
+        )),
     }
 }
 
@@ -131,7 +193,9 @@ mod metadata_hash_deserialization_tests {
         let parse_metadata_hash_error_to_string = |err: ParseMetadataHashError| match err {
             ParseMetadataHashError::InvalidSolcType(_) => "InvalidSolcType",
             ParseMetadataHashError::InvalidSolcVersion(_) => "InvalidSolcVersion",
-            ParseMetadataHashError::DuplicateKeys => "DuplicateKeys",
+            ParseMetadataHashError::InvalidHashType { .. } => "InvalidHashType",
+            ParseMetadataHashError::InvalidExperimentalType(_) => "InvalidExperimentalType",
+            ParseMetadataHashError::DuplicateKeys(_) => "DuplicateKeys",
         };
         format!("{error:?}").contains(parse_metadata_hash_error_to_string(expected))
     }
@@ -143,7 +207,13 @@ mod metadata_hash_deserialization_tests {
         let hex =
             "a165627a7a72305820d4fba422541feba2d648f6657d9354ec14ea9f5919b520abe0feb60981d7b17c";
         let encoded = decode_hex(hex).unwrap();
-        let expected = MetadataHash { solc: None };
+        let expected = MetadataHash {
+            bzzr0: Some(
+                decode_hex("d4fba422541feba2d648f6657d9354ec14ea9f5919b520abe0feb60981d7b17c")
+                    .unwrap(),
+            ),
+            ..Default::default()
+        };
         let expected_size = encoded.len();
 
         // when
@@ -163,6 +233,11 @@ mod metadata_hash_deserialization_tests {
         let encoded = decode_hex(hex).unwrap();
         let expected = MetadataHash {
             solc: Some(Version::new(0, 8, 14)),
+            ipfs: Some(
+                decode_hex("1220bcc988b1311237f2c00ccd0bfbd8b01d24dc18f720603b0de93fe6327df53625")
+                    .unwrap(),
+            ),
+            ..Default::default()
         };
         let expected_size = encoded.len();
 
@@ -186,6 +261,11 @@ mod metadata_hash_deserialization_tests {
                 Version::from_str("0.8.15-ci.2022.5.23+commit.21591531")
                     .expect("solc version parsing"),
             ),
+            ipfs: Some(
+                decode_hex("1220ba5af27fe13bc83e671bd6981216d35df49ab3ac923741b8948b277f93fbf732")
+                    .unwrap(),
+            ),
+            ..Default::default()
         };
         let expected_size = encoded.len();
 
@@ -210,6 +290,11 @@ mod metadata_hash_deserialization_tests {
         let encoded = decode_hex(&hex).unwrap();
         let expected = MetadataHash {
             solc: Some(Version::new(0, 8, 14)),
+            ipfs: Some(
+                decode_hex("1220bcc988b1311237f2c00ccd0bfbd8b01d24dc18f720603b0de93fe6327df53625")
+                    .unwrap(),
+            ),
+            ..Default::default()
         };
         let expected_size = decode_hex(first).unwrap().len();
 
@@ -270,7 +355,10 @@ mod metadata_hash_deserialization_tests {
         // then
         assert!(decoded.is_err(), "Deserialization should fail");
         assert!(
-            is_valid_custom_error(decoded.unwrap_err(), ParseMetadataHashError::DuplicateKeys),
+            is_valid_custom_error(
+                decoded.unwrap_err(),
+                ParseMetadataHashError::DuplicateKeys("solc".into())
+            ),
             "Should fail with custom (DuplicateKey) error"
         );
     }
@@ -314,4 +402,75 @@ mod metadata_hash_deserialization_tests {
             "Should fail with custom (InvalidSolcType) error"
         );
     }
+
+    #[test]
+    fn deserialization_metadata_hash_with_experimental() {
+        // given
+        // { "bzzr1": b"7ed1b4e6e1b6e6a4b2f1b1d84a3b1d5f3e7b1a9d4f1c0e2b5a7c9d1e3f5a7b9c", "experimental": true, "solc": b'00050c' }
+        let hex = "a365627a7a72315820\
+            7ed1b4e6e1b6e6a4b2f1b1d84a3b1d5f3e7b1a9d4f1c0e2b5a7c9d1e3f5a7b9c\
+            6c6578706572696d656e74616cf5\
+            64736f6c634300050c";
+        let encoded = decode_hex(hex).unwrap();
+        let expected = MetadataHash {
+            solc: Some(Version::new(0, 5, 12)),
+            bzzr1: Some(
+                decode_hex("7ed1b4e6e1b6e6a4b2f1b1d84a3b1d5f3e7b1a9d4f1c0e2b5a7c9d1e3f5a7b9c")
+                    .unwrap(),
+            ),
+            experimental: true,
+            ..Default::default()
+        };
+        let expected_size = encoded.len();
+
+        // when
+        let (decoded, decoded_size) = MetadataHash::from_cbor(encoded.as_ref())
+            .expect("Error when decoding valid metadata hash");
+
+        // then
+        assert_eq!(expected, decoded, "Incorrectly decoded");
+        assert_eq!(expected_size, decoded_size, "Incorrect decoded size")
+    }
+
+    #[test]
+    fn ipfs_cid_of_metadata_hash() {
+        // given
+        // { "ipfs": b"1220BCC988B1311237F2C00CCD0BFBD8B01D24DC18F720603B0DE93FE6327DF53625", "solc": b'00080e' }
+        let hex = "a2646970667358221220bcc988b1311237f2c00ccd0bfbd8b01d24dc18f720603b0de93fe6327df5362564736f6c634300080e";
+        let encoded = decode_hex(hex).unwrap();
+
+        // when
+        let (decoded, _) = MetadataHash::from_cbor(encoded.as_ref())
+            .expect("Error when decoding valid metadata hash");
+
+        // then
+        assert_eq!(
+            Some("Qmb3bbRhqQGFeTxDDMmDPcr2sZrKJTB9L3qKGhkcWaBxZi".to_string()),
+            decoded.ipfs_cid()
+        );
+    }
+
+    #[test]
+    fn deserialization_with_ipfs_neither_bytes_should_fail() {
+        // given
+        // { "ipfs": "hash" }
+        let hex = "a164697066736468617368";
+        let encoded = decode_hex(hex).unwrap();
+
+        // when
+        let decoded = MetadataHash::from_cbor(encoded.as_ref());
+
+        // then
+        assert!(decoded.is_err(), "Deserialization should fail");
+        assert!(
+            is_valid_custom_error(
+                decoded.unwrap_err(),
+                ParseMetadataHashError::InvalidHashType {
+                    key: "ipfs".into(),
+                    type_: minicbor::data::Type::String
+                }
+            ),
+            "Should fail with custom (InvalidHashType) error"
+        );
+    }
 }
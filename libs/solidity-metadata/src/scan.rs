@@ -0,0 +1,122 @@
+use crate::MetadataHash;
+use std::ops::Range;
+
+/// Metadata hash (auxdata) segment located in the bytecode
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MetadataHashSegment {
+    /// Offset of the CBOR encoded metadata hash in the bytecode
+    pub offset: usize,
+    /// Length of the segment, including the 2-byte length of the metadata hash following it
+    pub length: usize,
+    pub metadata: MetadataHash,
+}
+
+impl MetadataHashSegment {
+    pub fn range(&self) -> Range<usize> {
+        self.offset..self.offset + self.length
+    }
+}
+
+/// Scans the bytecode and returns all metadata hash segments in the order they appear.
+///
+/// The bytecode may contain several segments (e.g., the bytecodes of the contracts
+/// deployed by a factory are embedded into the factory bytecode). A segment is considered
+/// valid if it is a CBOR map containing at least one of the known keys, followed by
+/// its length encoded as 2 big-endian bytes.
+pub fn find_metadata_hashes(bytecode: &[u8]) -> Vec<MetadataHashSegment> {
+    let mut segments = vec![];
+    let mut i = 0;
+    while i < bytecode.len() {
+        match parse_segment(bytecode, i) {
+            Some(segment) => {
+                i += segment.length;
+                segments.push(segment);
+            }
+            None => i += 1,
+        }
+    }
+    segments
+}
+
+fn parse_segment(bytecode: &[u8], offset: usize) -> Option<MetadataHashSegment> {
+    // Only maps (major type 5) may be valid metadata hashes
+    if bytecode[offset] >> 5 != 5 {
+        return None;
+    }
+    let (metadata, metadata_length) = MetadataHash::from_cbor(&bytecode[offset..]).ok()?;
+    let length_start = offset + metadata_length;
+    let encoded_length = bytecode.get(length_start..length_start + 2)?;
+    if usize::from(u16::from_be_bytes([encoded_length[0], encoded_length[1]])) != metadata_length {
+        return None;
+    }
+    let has_known_keys = metadata.solc.is_some()
+        || metadata.ipfs.is_some()
+        || metadata.bzzr0.is_some()
+        || metadata.bzzr1.is_some();
+    if !has_known_keys {
+        return None;
+    }
+
+    Some(MetadataHashSegment {
+        offset,
+        length: metadata_length + 2,
+        metadata,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockscout_display_bytes::decode_hex;
+    use semver::Version;
+
+    // { "ipfs": b"1220BCC988B1311237F2C00CCD0BFBD8B01D24DC18F720603B0DE93FE6327DF53625", "solc": b'00080e' }
+    const IPFS_METADATA: &str = "a2646970667358221220bcc988b1311237f2c00ccd0bfbd8b01d24dc18f720603b0de93fe6327df5362564736f6c634300080e";
+    // { "bzzr0": b"d4fba422541feba2d648f6657d9354ec14ea9f5919b520abe0feb60981d7b17c" }
+    const BZZR0_METADATA: &str =
+        "a165627a7a72305820d4fba422541feba2d648f6657d9354ec14ea9f5919b520abe0feb60981d7b17c";
+
+    #[test]
+    fn finds_all_segments() {
+        // given
+        let arguments = "0000000000000000000000000000000000000000000000000000000000000001";
+        let hex = format!("6080604052{IPFS_METADATA}003360806040{BZZR0_METADATA}0029{arguments}");
+        let bytecode = decode_hex(&hex).unwrap();
+
+        // when
+        let segments = find_metadata_hashes(&bytecode);
+
+        // then
+        assert_eq!(2, segments.len(), "Invalid number of segments");
+        assert_eq!(5..58, segments[0].range());
+        assert_eq!(Some(Version::new(0, 8, 14)), segments[0].metadata.solc);
+        assert_eq!(62..105, segments[1].range());
+        assert!(segments[1].metadata.bzzr0.is_some());
+    }
+
+    #[test]
+    fn segments_with_invalid_length_are_skipped() {
+        // given
+        let hex = format!("6080604052{IPFS_METADATA}0032");
+        let bytecode = decode_hex(&hex).unwrap();
+
+        // when
+        let segments = find_metadata_hashes(&bytecode);
+
+        // then
+        assert!(segments.is_empty(), "No segments expected: {segments:?}");
+    }
+
+    #[test]
+    fn segments_without_length_are_skipped() {
+        // given
+        let hex = format!("6080604052{BZZR0_METADATA}");
+        let bytecode = decode_hex(&hex).unwrap();
+
+        // when
+        let segments = find_metadata_hashes(&bytecode);
+
+        // then
+        assert!(segments.is_empty(), "No segments expected: {segments:?}");
+    }
+}
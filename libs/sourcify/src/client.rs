@@ -1,17 +1,24 @@
 use crate::{
+    ipfs,
     types::{
-        CustomError, EmptyCustomError, ErrorResponse, GetSourceFilesResponse,
-        VerifyFromEtherscanResponse,
+        AnyErrorResponse, ContractMatch, CustomError, EmptyCustomError, GetSourceFilesResponse,
+        ListContractsResponse, LookupContractResponse, SubmitVerificationJobError,
+        SubmitVerificationJobResponse, VerificationJob, VerifyFromEtherscanResponse,
+        VerifyFromSolcJsonResponse, VerifyResponse,
     },
     Error, SourcifyError, VerifyFromEtherscanError,
 };
 use blockscout_display_bytes::ToHex;
 use bytes::Bytes;
-use reqwest::{Response, StatusCode};
+use futures::{stream, Stream, TryStreamExt};
+use reqwest::{
+    multipart::{Form, Part},
+    Response, StatusCode,
+};
 use reqwest_middleware::{ClientWithMiddleware, Middleware};
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
 use serde::{Deserialize, Serialize};
-use std::{str::FromStr, sync::Arc};
+use std::{collections::BTreeMap, str::FromStr, sync::Arc, time::Duration};
 use url::Url;
 
 mod retryable_strategy {
@@ -53,6 +60,8 @@ pub struct ClientBuilder {
     base_url: Url,
     max_retries: u32,
     middleware_stack: Vec<Arc<dyn Middleware>>,
+    ipfs_gateways: Vec<Url>,
+    ipfs_request_timeout: Duration,
 }
 
 impl Default for ClientBuilder {
@@ -61,6 +70,8 @@ impl Default for ClientBuilder {
             base_url: Url::from_str("https://sourcify.dev/server/").unwrap(),
             max_retries: 3,
             middleware_stack: vec![],
+            ipfs_gateways: vec![],
+            ipfs_request_timeout: Duration::from_secs(10),
         }
     }
 }
@@ -73,6 +84,24 @@ impl ClientBuilder {
         Ok(self)
     }
 
+    /// Gateways used to retrieve the files by their IPFS CIDs when the Sourcify server
+    /// is unavailable (see [`Client::get_source_files_any_with_ipfs_fallback`]).
+    /// Gateways are tried in the given order.
+    pub fn try_ipfs_gateways(mut self, gateways: &[&str]) -> Result<Self, String> {
+        self.ipfs_gateways = gateways
+            .iter()
+            .map(|gateway| Url::from_str(gateway).map_err(|err| err.to_string()))
+            .collect::<Result<_, _>>()?;
+
+        Ok(self)
+    }
+
+    /// Timeout of a single request to the IPFS gateway, after which the next gateway is tried
+    pub fn ipfs_request_timeout(mut self, timeout: Duration) -> Self {
+        self.ipfs_request_timeout = timeout;
+        self
+    }
+
     pub fn max_retries(mut self, max_retries: u32) -> Self {
         self.max_retries = max_retries;
         self
@@ -89,19 +118,32 @@ impl ClientBuilder {
 
     pub fn build(self) -> Client {
         let retry_policy = ExponentialBackoff::builder().build_with_max_retries(self.max_retries);
-        let mut client_builder = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        let reqwest_client = reqwest::Client::new();
+        let mut client_builder = reqwest_middleware::ClientBuilder::new(reqwest_client.clone())
             .with(RetryTransientMiddleware::new_with_policy_and_strategy(
                 retry_policy,
                 retryable_strategy::SourcifyRetryableStrategy,
             ));
+        // Multipart requests have streaming bodies which cannot be cloned,
+        // thus are sent without the retry middleware
+        let mut client_without_retries_builder =
+            reqwest_middleware::ClientBuilder::new(reqwest_client);
         for middleware in self.middleware_stack {
-            client_builder = client_builder.with_arc(middleware);
+            client_builder = client_builder.with_arc(middleware.clone());
+            client_without_retries_builder = client_without_retries_builder.with_arc(middleware);
         }
         let client = client_builder.build();
+        let client_without_retries = client_without_retries_builder.build();
 
         Client {
             base_url: self.base_url,
             reqwest_client: client,
+            reqwest_client_without_retries: client_without_retries,
+            ipfs_gateways: self.ipfs_gateways,
+            ipfs_client: reqwest::Client::builder()
+                .timeout(self.ipfs_request_timeout)
+                .build()
+                .expect("ipfs client configuration is valid"),
         }
     }
 }
@@ -110,6 +152,9 @@ impl ClientBuilder {
 pub struct Client {
     base_url: Url,
     reqwest_client: ClientWithMiddleware,
+    reqwest_client_without_retries: ClientWithMiddleware,
+    ipfs_gateways: Vec<Url>,
+    ipfs_client: reqwest::Client,
 }
 
 impl Default for Client {
@@ -148,6 +193,29 @@ impl Client {
         Self::process_sourcify_response(response).await
     }
 
+    /// Same as [`Client::get_source_files_any`], but if the Sourcify server is unavailable
+    /// and the IPFS gateways are configured, retrieves the metadata by `metadata_cid`
+    /// (e.g., extracted from the contract bytecode) and the source files referenced in it.
+    /// Contracts retrieved from IPFS have not been recompiled, so are reported as partial matches.
+    pub async fn get_source_files_any_with_ipfs_fallback(
+        &self,
+        chain_id: &str,
+        contract_address: Bytes,
+        metadata_cid: Option<&str>,
+    ) -> Result<GetSourceFilesResponse, Error<EmptyCustomError>> {
+        let result = self.get_source_files_any(chain_id, contract_address).await;
+        match (result, metadata_cid) {
+            (Err(err), Some(metadata_cid))
+                if err.is_server_unavailable() && !self.ipfs_gateways.is_empty() =>
+            {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(target: "sourcify", "sourcify is unavailable, retrieving files from ipfs; err: {err}");
+                ipfs::get_source_files(&self.ipfs_client, &self.ipfs_gateways, metadata_cid).await
+            }
+            (result, _) => result,
+        }
+    }
+
     pub async fn verify_from_etherscan(
         &self,
         chain_id: &str,
@@ -180,6 +248,262 @@ impl Client {
 
         Self::process_sourcify_response(response).await
     }
+
+    /// Submits the contract for verification using the metadata and source files
+    /// uploaded as a multipart form. If the metadata describes several contracts,
+    /// `chosen_contract` is the index of the contract to be verified.
+    pub async fn verify(
+        &self,
+        chain_id: &str,
+        contract_address: Bytes,
+        files: BTreeMap<String, String>,
+        chosen_contract: Option<usize>,
+    ) -> Result<VerifyResponse, Error<EmptyCustomError>> {
+        let url = self.generate_url("verify");
+
+        let mut form = Form::new()
+            .text("address", ToHex::to_hex(&contract_address))
+            .text("chain", chain_id.to_string());
+        if let Some(chosen_contract) = chosen_contract {
+            form = form.text("chosenContract", chosen_contract.to_string());
+        }
+        for (name, content) in files {
+            form = form.part("files", Part::text(content).file_name(name));
+        }
+
+        let response = self
+            .reqwest_client_without_retries
+            .post(url)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|error| match error {
+                reqwest_middleware::Error::Middleware(err) => Error::ReqwestMiddleware(err),
+                reqwest_middleware::Error::Reqwest(err) => Error::Reqwest(err),
+            })?;
+
+        Self::process_sourcify_response(response).await
+    }
+
+    /// Submits the contract for verification using the Standard JSON input of the compiler
+    pub async fn verify_from_solc_json(
+        &self,
+        chain_id: &str,
+        contract_address: Bytes,
+        compiler_version: &str,
+        contract_name: &str,
+        solc_json_input: &str,
+    ) -> Result<VerifyFromSolcJsonResponse, Error<EmptyCustomError>> {
+        let url = self.generate_url("verify/solc-json");
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            address: String,
+            chain: &'a str,
+            files: BTreeMap<&'a str, &'a str>,
+            compiler_version: &'a str,
+            contract_name: &'a str,
+        }
+
+        let request = Request {
+            address: ToHex::to_hex(&contract_address),
+            chain: chain_id,
+            files: BTreeMap::from([("SolcJsonInput.json", solc_json_input)]),
+            compiler_version,
+            contract_name,
+        };
+
+        let response = self
+            .reqwest_client
+            .post(url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|error| match error {
+                reqwest_middleware::Error::Middleware(err) => Error::ReqwestMiddleware(err),
+                reqwest_middleware::Error::Reqwest(err) => Error::Reqwest(err),
+            })?;
+
+        Self::process_sourcify_response(response).await
+    }
+
+    /// Looks up the verified contract using the v2 API.
+    /// `fields` are the additional contract fields to be returned
+    /// (e.g., "metadata", "sources", or "all" for every available field).
+    pub async fn lookup_contract(
+        &self,
+        chain_id: &str,
+        contract_address: Bytes,
+        fields: &[&str],
+    ) -> Result<LookupContractResponse, Error<EmptyCustomError>> {
+        let mut url = self.generate_url(
+            format!(
+                "v2/contract/{}/{}",
+                chain_id,
+                ToHex::to_hex(&contract_address)
+            )
+            .as_str(),
+        );
+        if !fields.is_empty() {
+            url.query_pairs_mut()
+                .append_pair("fields", &fields.join(","));
+        }
+
+        let response = self
+            .reqwest_client
+            .get(url)
+            .send()
+            .await
+            .map_err(|error| match error {
+                reqwest_middleware::Error::Middleware(err) => Error::ReqwestMiddleware(err),
+                reqwest_middleware::Error::Reqwest(err) => Error::Reqwest(err),
+            })?;
+
+        Self::process_sourcify_response(response).await
+    }
+
+    /// Lists the verified contracts of the chain in the order of their verification
+    /// using the v2 API. If `after_match_id` is specified, only the contracts
+    /// verified after the contract with that match id are returned.
+    pub async fn list_contracts(
+        &self,
+        chain_id: &str,
+        after_match_id: Option<&str>,
+        limit: u32,
+    ) -> Result<ListContractsResponse, Error<EmptyCustomError>> {
+        let mut url = self.generate_url(format!("v2/contracts/{chain_id}").as_str());
+        url.query_pairs_mut()
+            .append_pair("sort", "asc")
+            .append_pair("limit", &limit.to_string());
+        if let Some(after_match_id) = after_match_id {
+            url.query_pairs_mut()
+                .append_pair("afterMatchId", after_match_id);
+        }
+
+        let response = self
+            .reqwest_client
+            .get(url)
+            .send()
+            .await
+            .map_err(|error| match error {
+                reqwest_middleware::Error::Middleware(err) => Error::ReqwestMiddleware(err),
+                reqwest_middleware::Error::Reqwest(err) => Error::Reqwest(err),
+            })?;
+
+        Self::process_sourcify_response(response).await
+    }
+
+    /// Stream of all verified contracts of the chain in the order of their verification.
+    /// Pages are requested lazily via [`Client::list_contracts`].
+    ///
+    /// To resume the stream, pass the `match_id` of the last processed contract
+    /// as `after_match_id`; `None` starts from the first verified contract.
+    /// Fails if the last contract of a full page has no `match_id` to continue from.
+    pub fn contracts_stream<'a>(
+        &'a self,
+        chain_id: &'a str,
+        after_match_id: Option<String>,
+    ) -> impl Stream<Item = Result<ContractMatch, Error<EmptyCustomError>>> + 'a {
+        const PAGE_SIZE: u32 = 200;
+
+        // `None` means the last page has been fetched,
+        // `Some(cursor)` - the page after the cursor is next
+        let initial: Option<Option<String>> = Some(after_match_id);
+        stream::try_unfold(initial, move |state| async move {
+            let Some(after_match_id) = state else {
+                return Ok::<_, Error<EmptyCustomError>>(None);
+            };
+            let page = self
+                .list_contracts(chain_id, after_match_id.as_deref(), PAGE_SIZE)
+                .await?;
+            let next_state = match page.results.last() {
+                Some(last) if page.results.len() as u32 >= PAGE_SIZE => {
+                    // the next page cannot be requested without the cursor
+                    let match_id = last.match_id.clone().ok_or_else(|| {
+                        Error::InvalidResponse(
+                            "contract of the full page has no match id".to_string(),
+                        )
+                    })?;
+                    Some(Some(match_id))
+                }
+                _ => None,
+            };
+            Ok(Some((page.results, next_state)))
+        })
+        .map_ok(|results| stream::iter(results.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Submits the Standard JSON input verification job using the v2 API.
+    /// The result should be polled via [`Client::get_verification_job`].
+    pub async fn submit_verification_job(
+        &self,
+        chain_id: &str,
+        contract_address: Bytes,
+        std_json_input: &serde_json::Value,
+        compiler_version: &str,
+        contract_identifier: &str,
+        creation_transaction_hash: Option<Bytes>,
+    ) -> Result<SubmitVerificationJobResponse, Error<SubmitVerificationJobError>> {
+        let url = self.generate_url(
+            format!(
+                "v2/verify/{}/{}",
+                chain_id,
+                ToHex::to_hex(&contract_address)
+            )
+            .as_str(),
+        );
+
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Request<'a> {
+            std_json_input: &'a serde_json::Value,
+            compiler_version: &'a str,
+            contract_identifier: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            creation_transaction_hash: Option<String>,
+        }
+
+        let request = Request {
+            std_json_input,
+            compiler_version,
+            contract_identifier,
+            creation_transaction_hash: creation_transaction_hash.map(|hash| ToHex::to_hex(&hash)),
+        };
+
+        let response = self
+            .reqwest_client
+            .post(url)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|error| match error {
+                reqwest_middleware::Error::Middleware(err) => Error::ReqwestMiddleware(err),
+                reqwest_middleware::Error::Reqwest(err) => Error::Reqwest(err),
+            })?;
+
+        Self::process_sourcify_response(response).await
+    }
+
+    pub async fn get_verification_job(
+        &self,
+        verification_id: &str,
+    ) -> Result<VerificationJob, Error<EmptyCustomError>> {
+        let url = self.generate_url(format!("v2/verify/{verification_id}").as_str());
+
+        let response = self
+            .reqwest_client
+            .get(url)
+            .send()
+            .await
+            .map_err(|error| match error {
+                reqwest_middleware::Error::Middleware(err) => Error::ReqwestMiddleware(err),
+                reqwest_middleware::Error::Reqwest(err) => Error::Reqwest(err),
+            })?;
+
+        Self::process_sourcify_response(response).await
+    }
 }
 
 impl Client {
@@ -192,13 +516,13 @@ impl Client {
     ) -> Result<T, Error<E>> {
         let error_message = |response: Response| async {
             response
-                .json::<ErrorResponse>()
+                .json::<AnyErrorResponse>()
                 .await
-                .map(|value| value.error)
+                .map(AnyErrorResponse::into_message)
         };
 
         match response.status() {
-            StatusCode::OK => Ok(response.json::<T>().await?),
+            StatusCode::OK | StatusCode::ACCEPTED => Ok(response.json::<T>().await?),
             StatusCode::NOT_FOUND => {
                 let message = error_message(response).await?;
                 if let Some(err) = E::handle_not_found(&message) {
@@ -524,4 +848,114 @@ mod tests {
         * async fn verify_from_etherscan_verified_with_errors() {}
         */
     }
+
+    mod contracts_stream {
+        use super::*;
+        use pretty_assertions::assert_eq;
+        use wiremock::{matchers, Mock, MockServer, Request, ResponseTemplate};
+
+        fn contract(match_id: Option<u64>) -> serde_json::Value {
+            json!({
+                "match": "exact_match",
+                "creationMatch": "exact_match",
+                "runtimeMatch": "exact_match",
+                "chainId": "1",
+                "address": format!("0x{:040x}", match_id.unwrap_or_default()),
+                "verifiedAt": "2024-07-24T12:00:00Z",
+                "matchId": match_id.map(|id| id.to_string()),
+            })
+        }
+
+        async fn mock_page(
+            server: &MockServer,
+            after_match_id: Option<&str>,
+            results: Vec<serde_json::Value>,
+        ) {
+            let after_match_id = after_match_id.map(str::to_string);
+            Mock::given(matchers::method("GET"))
+                .and(matchers::path("/v2/contracts/1"))
+                .and(matchers::query_param("limit", "200"))
+                .and(move |request: &Request| {
+                    let cursor = request
+                        .url
+                        .query_pairs()
+                        .find(|(key, _)| key == "afterMatchId")
+                        .map(|(_, value)| value.into_owned());
+                    cursor == after_match_id
+                })
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(json!({ "results": results })),
+                )
+                .expect(1)
+                .mount(server)
+                .await;
+        }
+
+        fn mock_client(server: &MockServer) -> Client {
+            ClientBuilder::default()
+                .try_base_url(&format!("{}/", server.uri()))
+                .unwrap()
+                .build()
+        }
+
+        #[tokio::test]
+        async fn fetches_all_pages() {
+            let server = MockServer::start().await;
+            mock_page(
+                &server,
+                None,
+                (1..=200).map(|id| contract(Some(id))).collect(),
+            )
+            .await;
+            mock_page(
+                &server,
+                Some("200"),
+                (201..=203).map(|id| contract(Some(id))).collect(),
+            )
+            .await;
+
+            let client = mock_client(&server);
+            let contracts: Vec<_> = client
+                .contracts_stream("1", None)
+                .try_collect()
+                .await
+                .expect("stream failed");
+            let match_ids: Vec<_> = contracts
+                .into_iter()
+                .map(|contract| contract.match_id.unwrap())
+                .collect();
+            let expected: Vec<_> = (1..=203).map(|id| id.to_string()).collect();
+            assert_eq!(expected, match_ids);
+        }
+
+        #[tokio::test]
+        async fn resumes_after_match_id() {
+            let server = MockServer::start().await;
+            mock_page(&server, Some("200"), vec![contract(Some(201))]).await;
+
+            let client = mock_client(&server);
+            let contracts: Vec<_> = client
+                .contracts_stream("1", Some("200".to_string()))
+                .try_collect()
+                .await
+                .expect("stream failed");
+            assert_eq!(1, contracts.len());
+        }
+
+        #[tokio::test]
+        async fn fails_on_full_page_without_match_id() {
+            let server = MockServer::start().await;
+            let mut results: Vec<_> = (1..200).map(|id| contract(Some(id))).collect();
+            results.push(contract(None));
+            mock_page(&server, None, results).await;
+
+            let client = mock_client(&server);
+            let result: Result<Vec<_>, _> = client.contracts_stream("1", None).try_collect().await;
+            let err = result.expect_err("error expected");
+            assert!(
+                matches!(err, Error::InvalidResponse(_)),
+                "expected: 'Error::InvalidResponse', got: {err:?}"
+            );
+        }
+    }
 }
@@ -0,0 +1,358 @@
+use crate::{types::FileOrigin, Error, GetSourceFilesResponse, MatchType};
+use blockscout_display_bytes::decode_hex;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use std::collections::BTreeMap;
+use url::Url;
+
+/// Chunk size used by the IPFS nodes (and the solidity compiler) when a file is added
+const MAX_CHUNK_SIZE: usize = 256 * 1024;
+/// Max number of links in one node of the balanced file layout
+const MAX_CHILDREN: usize = 174;
+
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    sources: BTreeMap<String, MetadataSource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MetadataSource {
+    keccak256: String,
+    content: Option<String>,
+    #[serde(default)]
+    urls: Vec<String>,
+}
+
+impl MetadataSource {
+    /// Urls are expected in the "dweb:/ipfs/<cid>" format
+    fn ipfs_cid(&self) -> Option<&str> {
+        self.urls
+            .iter()
+            .find_map(|url| url.strip_prefix("dweb:/ipfs/"))
+    }
+
+    fn verify(&self, content: &[u8]) -> Result<(), String> {
+        let expected = decode_hex(&self.keccak256)
+            .map_err(|err| format!("invalid keccak256 hash: '{err}'"))?;
+        let actual = Keccak256::digest(content);
+        if expected != actual.as_slice() {
+            return Err(format!("keccak256 mismatch; expected={}", self.keccak256));
+        }
+        Ok(())
+    }
+}
+
+/// Retrieves the metadata file by its CID and the source files referenced in it.
+/// Contracts retrieved this way have not been recompiled, so are reported as partial matches.
+pub(crate) async fn get_source_files<E: std::error::Error>(
+    client: &reqwest::Client,
+    gateways: &[Url],
+    metadata_cid: &str,
+) -> Result<GetSourceFilesResponse, Error<E>> {
+    let (metadata_content, metadata_gateway) = fetch(client, gateways, metadata_cid, |content| {
+        verify_cid(metadata_cid, content)
+    })
+    .await?;
+    let metadata_json: serde_json::Value = serde_json::from_str(&metadata_content)
+        .map_err(|err| Error::Ipfs(format!("metadata file is not a valid json: '{err}'")))?;
+    let metadata: Metadata = serde_json::from_value(metadata_json.clone())
+        .map_err(|err| Error::Ipfs(format!("metadata file is invalid: '{err}'")))?;
+
+    let mut sources = BTreeMap::new();
+    let mut source_origins = BTreeMap::new();
+    for (path, source) in metadata.sources {
+        let (content, origin) = match (source.content.clone(), source.ipfs_cid()) {
+            (Some(content), _) => {
+                source.verify(content.as_bytes()).map_err(|err| {
+                    Error::Ipfs(format!(
+                        "source file content is invalid: {path}; err: {err}"
+                    ))
+                })?;
+                (content, FileOrigin::Metadata)
+            }
+            (None, Some(cid)) => {
+                let (content, gateway) =
+                    fetch(client, gateways, cid, |content| source.verify(content)).await?;
+                (content, FileOrigin::Ipfs { gateway })
+            }
+            (None, None) => {
+                return Err(Error::Ipfs(format!(
+                    "source file has neither content nor ipfs url: {path}"
+                )))
+            }
+        };
+        sources.insert(path.clone(), content);
+        source_origins.insert(path, origin);
+    }
+
+    Ok(GetSourceFilesResponse {
+        status: MatchType::Partial,
+        sources,
+        metadata: metadata_json,
+        library_map: BTreeMap::new(),
+        constructor_arguments: None,
+        immutable_references: None,
+        raw_json: serde_json::Value::Null,
+        metadata_origin: FileOrigin::Ipfs {
+            gateway: metadata_gateway,
+        },
+        source_origins,
+    })
+}
+
+/// Tries the gateways in order and returns the content with the gateway it was retrieved from.
+/// Content which does not pass the verification is treated as a gateway failure.
+async fn fetch<E: std::error::Error>(
+    client: &reqwest::Client,
+    gateways: &[Url],
+    cid: &str,
+    verify: impl Fn(&[u8]) -> Result<(), String>,
+) -> Result<(String, Url), Error<E>> {
+    let mut errors = vec![];
+    for gateway in gateways {
+        let url = file_url(gateway, cid)
+            .map_err(|err| Error::Ipfs(format!("invalid cid: {cid}; err: '{err}'")))?;
+        let result = async {
+            let content = client
+                .get(url)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|err| err.to_string())?
+                .bytes()
+                .await
+                .map_err(|err| err.to_string())?;
+            verify(&content)?;
+            String::from_utf8(content.to_vec()).map_err(|err| err.to_string())
+        }
+        .await;
+        match result {
+            Ok(content) => return Ok((content, gateway.clone())),
+            Err(err) => errors.push(format!("{gateway}: {err}")),
+        }
+    }
+
+    Err(Error::Ipfs(format!(
+        "cid {cid} cannot be retrieved from the gateways: [{}]",
+        errors.join(", ")
+    )))
+}
+
+/// Gateways may be configured with a path and without a trailing slash,
+/// in which case joining would replace the last path segment.
+fn file_url(gateway: &Url, cid: &str) -> Result<Url, url::ParseError> {
+    let mut gateway = gateway.clone();
+    if !gateway.path().ends_with('/') {
+        gateway.set_path(&format!("{}/", gateway.path()));
+    }
+    gateway.join(&format!("ipfs/{cid}"))
+}
+
+/// Checks that the content is the file identified by the CIDv0 (the version used by the compilers).
+fn verify_cid(cid: &str, content: &[u8]) -> Result<(), String> {
+    if !cid.starts_with("Qm") {
+        return Err(format!("only CIDv0 can be verified; cid={cid}"));
+    }
+    let actual = cid_v0(content);
+    if actual != cid {
+        return Err(format!("cid mismatch; actual={actual}"));
+    }
+    Ok(())
+}
+
+struct Chunk {
+    multihash: Vec<u8>,
+    size: usize,
+    block_size: usize,
+}
+
+/// Calculates the CIDv0 the file would get with the default `ipfs add` settings:
+/// dag-pb nodes with UnixFS data, 256 KiB chunks, and the balanced layout.
+/// Follows the implementation of the solidity compiler (libsolutil/IpfsHash.cpp).
+fn cid_v0(content: &[u8]) -> String {
+    let mut level: Vec<_> = if content.is_empty() {
+        vec![leaf_chunk(content)]
+    } else {
+        content.chunks(MAX_CHUNK_SIZE).map(leaf_chunk).collect()
+    };
+    while level.len() > 1 {
+        level = level.chunks(MAX_CHILDREN).map(parent_chunk).collect();
+    }
+    bs58::encode(&level[0].multihash).into_string()
+}
+
+fn leaf_chunk(data: &[u8]) -> Chunk {
+    // UnixFS: Type=File, Data, filesize
+    let mut unixfs = vec![0x08, 0x02];
+    if !data.is_empty() {
+        unixfs.push(0x12);
+        unixfs.extend(varint(data.len()));
+        unixfs.extend(data);
+    }
+    unixfs.push(0x18);
+    unixfs.extend(varint(data.len()));
+
+    let block = length_delimited(0x0a, &unixfs);
+    Chunk {
+        multihash: multihash(&block),
+        size: data.len(),
+        block_size: block.len(),
+    }
+}
+
+fn parent_chunk(children: &[Chunk]) -> Chunk {
+    let mut links = vec![];
+    let mut block_sizes = vec![];
+    for child in children {
+        // PBLink: Hash, Name="", Tsize
+        let mut link = length_delimited(0x0a, &child.multihash);
+        link.extend([0x12, 0x00, 0x18]);
+        link.extend(varint(child.block_size));
+        links.extend(length_delimited(0x12, &link));
+
+        block_sizes.push(0x20);
+        block_sizes.extend(varint(child.size));
+    }
+    let size = children.iter().map(|child| child.size).sum();
+
+    // UnixFS: Type=File, filesize, blocksizes
+    let mut unixfs = vec![0x08, 0x02, 0x18];
+    unixfs.extend(varint(size));
+    unixfs.extend(block_sizes);
+
+    let mut block = links;
+    block.extend(length_delimited(0x0a, &unixfs));
+    Chunk {
+        multihash: multihash(&block),
+        size,
+        block_size: block.len() + children.iter().map(|child| child.block_size).sum::<usize>(),
+    }
+}
+
+/// sha2-256 multihash
+fn multihash(data: &[u8]) -> Vec<u8> {
+    let mut multihash = vec![0x12, 0x20];
+    multihash.extend(Sha256::digest(data));
+    multihash
+}
+
+fn length_delimited(tag: u8, data: &[u8]) -> Vec<u8> {
+    let mut result = vec![tag];
+    result.extend(varint(data.len()));
+    result.extend(data);
+    result
+}
+
+fn varint(mut value: usize) -> Vec<u8> {
+    let mut result = vec![];
+    while value >= 0x80 {
+        result.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    result.push(value as u8);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_metadata_sources() {
+        let metadata: Metadata = serde_json::from_value(json!({
+            "language": "Solidity",
+            "sources": {
+                "contracts/A.sol": {
+                    "keccak256": "0xc40380283b7d4a97da5e247fbb7b795f6241cfe3d86e34493d87528dfcb4d56b",
+                    "urls": [
+                        "bzz-raw://86ec578963cb912c4b912f066390e564c54ea1bc5fb1a55aa4e4c77bb92b07ba",
+                        "dweb:/ipfs/QmeqihJa8kUjbNHNCpFRHkq1scCbjjFvaUN2gWEJCNEx1Q"
+                    ]
+                },
+                "contracts/B.sol": {
+                    "keccak256": "0xff9e0ddd21b0579491371fe8d4f7e09254ffc7af9382ba287ef8d2a2fd1ce8e2",
+                    "content": "contract B {}"
+                }
+            }
+        }))
+        .unwrap();
+
+        let a = &metadata.sources["contracts/A.sol"];
+        assert_eq!(
+            Some("QmeqihJa8kUjbNHNCpFRHkq1scCbjjFvaUN2gWEJCNEx1Q"),
+            a.ipfs_cid()
+        );
+        assert_eq!(None, a.content);
+
+        let b = &metadata.sources["contracts/B.sol"];
+        assert_eq!(None, b.ipfs_cid());
+        assert_eq!(Some("contract B {}".to_string()), b.content);
+    }
+
+    #[test]
+    fn verify_source_keccak256() {
+        let source: MetadataSource = serde_json::from_value(json!({
+            "keccak256": "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            "content": ""
+        }))
+        .unwrap();
+        assert_eq!(Ok(()), source.verify(b""));
+        assert!(source.verify(b"contract A {}").is_err());
+    }
+
+    #[test]
+    fn calculate_cid_v0() {
+        assert_eq!(
+            "QmbFMke1KXqnYyBBWxB74N4c5SBnJMVAiMNRcGu6x1AwQH",
+            cid_v0(b"")
+        );
+        assert_eq!(
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o",
+            cid_v0(b"hello world\n")
+        );
+        // Does not fit into a single chunk
+        assert_eq!(
+            "QmTaxvXcxpzzaatSEEAYr7t3knkJ6DmTVbr8MjJJWLRWpV",
+            cid_v0(&vec![b'a'; MAX_CHUNK_SIZE + 1])
+        );
+
+        assert_eq!(
+            Ok(()),
+            verify_cid(
+                "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o",
+                b"hello world\n"
+            )
+        );
+        assert!(verify_cid(
+            "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o",
+            b"hello world"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn file_url_for_gateways() {
+        let cid = "QmT78zSuBmuS4z925WZfrqQ1qHaJ56DQaTfyMUF7F8ff5o";
+        for (gateway, expected) in [
+            ("https://ipfs.io", "https://ipfs.io/ipfs/{cid}"),
+            ("https://ipfs.io/", "https://ipfs.io/ipfs/{cid}"),
+            (
+                "https://gateway.io/prefix",
+                "https://gateway.io/prefix/ipfs/{cid}",
+            ),
+            (
+                "https://gateway.io/prefix/",
+                "https://gateway.io/prefix/ipfs/{cid}",
+            ),
+        ] {
+            let gateway = Url::parse(gateway).unwrap();
+            assert_eq!(
+                expected.replace("{cid}", cid),
+                file_url(&gateway, cid).unwrap().as_str(),
+                "gateway={gateway}"
+            );
+        }
+    }
+}
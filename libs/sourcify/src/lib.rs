@@ -1,10 +1,13 @@
 mod client;
+mod ipfs;
 mod types;
 
 pub use client::{Client, ClientBuilder};
 pub use types::{
-    EmptyCustomError, GetSourceFilesResponse, MatchType, VerifyFromEtherscanError,
-    VerifyFromEtherscanResponse,
+    ContractMatch, EmptyCustomError, FileOrigin, GetSourceFilesResponse, ListContractsResponse,
+    LookupContractResponse, MatchType, SubmitVerificationJobError, SubmitVerificationJobResponse,
+    VerificationJob, VerificationJobError, VerifyFromEtherscanError, VerifyFromEtherscanResponse,
+    VerifyFromSolcJsonResponse, VerifyResponse,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
@@ -36,4 +39,23 @@ pub enum Error<E: std::error::Error> {
     ReqwestMiddleware(anyhow::Error),
     #[error("error got from the Sourcify: {0}")]
     Sourcify(#[from] SourcifyError<E>),
+    #[error("error occurred while retrieving files from ipfs: {0}")]
+    Ipfs(String),
+    #[error("invalid response from the Sourcify: {0}")]
+    InvalidResponse(String),
+}
+
+impl<E: std::error::Error> Error<E> {
+    /// Whether the error indicates that the Sourcify server is unavailable,
+    /// rather than that it has processed the request unsuccessfully
+    pub fn is_server_unavailable(&self) -> bool {
+        match self {
+            Error::Reqwest(_) | Error::ReqwestMiddleware(_) => true,
+            Error::Sourcify(SourcifyError::BadGateway(_)) => true,
+            Error::Sourcify(SourcifyError::UnexpectedStatusCode { status_code, .. }) => {
+                status_code.is_server_error()
+            }
+            Error::Sourcify(_) | Error::Ipfs(_) | Error::InvalidResponse(_) => false,
+        }
+    }
 }
@@ -5,6 +5,28 @@ pub(crate) struct ErrorResponse {
     pub error: String,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub(crate) struct ErrorResponseV2 {
+    pub message: String,
+}
+
+/// Error responses of v1 and v2 APIs have different formats
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum AnyErrorResponse {
+    V1(ErrorResponse),
+    V2(ErrorResponseV2),
+}
+
+impl AnyErrorResponse {
+    pub fn into_message(self) -> String {
+        match self {
+            AnyErrorResponse::V1(response) => response.error,
+            AnyErrorResponse::V2(response) => response.message,
+        }
+    }
+}
+
 pub(crate) use custom_error::CustomError;
 mod custom_error {
     pub(crate) trait CustomError: std::error::Error + Sized {
@@ -38,11 +60,26 @@ pub enum EmptyCustomError {}
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum MatchType {
-    #[serde(alias = "perfect")]
+    /// "exact_match" in v2 API
+    #[serde(alias = "perfect", alias = "exact_match")]
     Full,
+    /// "match" in v2 API
+    #[serde(alias = "match")]
     Partial,
 }
 
+/// Where the contract file has been retrieved from
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileOrigin {
+    Sourcify,
+    /// Retrieved by its CID from the IPFS gateway
+    Ipfs {
+        gateway: url::Url,
+    },
+    /// Embedded into the metadata file as `content`
+    Metadata,
+}
+
 pub use get_source_files_response::GetSourceFilesResponse;
 mod get_source_files_response {
     use super::*;
@@ -81,6 +118,10 @@ mod get_source_files_response {
         pub immutable_references: Option<serde_json::Value>,
 
         pub raw_json: serde_json::Value,
+
+        pub metadata_origin: FileOrigin,
+        /// Origins of the `sources` by their paths
+        pub source_origins: BTreeMap<String, FileOrigin>,
     }
 
     impl<'de> Deserialize<'de> for GetSourceFilesResponse {
@@ -164,6 +205,11 @@ mod get_source_files_response {
                 return Err("sources are missing".to_string());
             }
 
+            let source_origins = sources
+                .keys()
+                .map(|path| (path.clone(), FileOrigin::Sourcify))
+                .collect();
+
             Ok(Self {
                 status,
                 sources,
@@ -172,6 +218,8 @@ mod get_source_files_response {
                 constructor_arguments,
                 immutable_references,
                 raw_json: raw,
+                metadata_origin: FileOrigin::Sourcify,
+                source_origins,
             })
         }
     }
@@ -411,6 +459,259 @@ mod verify_from_etherscan {
     }
 }
 
+pub use verify::VerifyResponse;
+mod verify {
+    use super::*;
+    use blockscout_display_bytes::decode_hex;
+    use bytes::Bytes;
+    use serde::{de, Deserializer};
+    use std::collections::BTreeMap;
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct VerifyResponse {
+        pub address: Bytes,
+        pub chain_id: String,
+        pub status: MatchType,
+        pub library_map: BTreeMap<String, Bytes>,
+        pub immutable_references: Option<serde_json::Value>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ResultWrapper {
+        pub address: String,
+        pub chain_id: String,
+        pub status: MatchType,
+        #[serde(default)]
+        pub library_map: BTreeMap<String, String>,
+        pub immutable_references: Option<serde_json::Value>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct VerifyResponseRaw {
+        result: Vec<ResultWrapper>,
+    }
+
+    impl<'de> Deserialize<'de> for VerifyResponse {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let raw = VerifyResponseRaw::deserialize(deserializer)?;
+
+            VerifyResponse::try_from(raw).map_err(de::Error::custom)
+        }
+    }
+
+    impl TryFrom<VerifyResponseRaw> for VerifyResponse {
+        type Error = String;
+
+        fn try_from(value: VerifyResponseRaw) -> Result<Self, Self::Error> {
+            let value = value
+                .result
+                .into_iter()
+                .next()
+                .ok_or_else(|| "response does not contain any result".to_string())?;
+
+            let address = decode_hex(&value.address).map_err(|err| {
+                format!(
+                    "address is not a valid byte sequence; address: {}, err: '{err}'",
+                    value.address
+                )
+            })?;
+
+            let library_map = value
+                .library_map
+                .into_iter()
+                .map(|(placeholder, value)| {
+                    let address = decode_hex(&value).map_err(|err| {
+                        format!(
+                            "library map has a placeholder value that is not a valid byte sequence; \
+                            placeholder: {placeholder}, value: {value}, err: '{err}'"
+                        )
+                    })?;
+
+                    Ok((placeholder, address.into()))
+                })
+                .collect::<Result<BTreeMap<_, _>, String>>()?;
+
+            Ok(Self {
+                address: address.into(),
+                chain_id: value.chain_id,
+                status: value.status,
+                library_map,
+                immutable_references: value.immutable_references,
+            })
+        }
+    }
+}
+
+pub use verify_from_solc_json::VerifyFromSolcJsonResponse;
+mod verify_from_solc_json {
+    use super::*;
+    use blockscout_display_bytes::decode_hex;
+    use bytes::Bytes;
+    use serde::{de, Deserializer};
+
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct VerifyFromSolcJsonResponse {
+        pub address: Bytes,
+        pub chain_id: String,
+        pub status: MatchType,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ResultWrapper {
+        pub address: String,
+        pub chain_id: String,
+        pub status: MatchType,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct VerifyFromSolcJsonResponseRaw {
+        result: Vec<ResultWrapper>,
+    }
+
+    impl<'de> Deserialize<'de> for VerifyFromSolcJsonResponse {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let raw = VerifyFromSolcJsonResponseRaw::deserialize(deserializer)?;
+
+            VerifyFromSolcJsonResponse::try_from(raw).map_err(de::Error::custom)
+        }
+    }
+
+    impl TryFrom<VerifyFromSolcJsonResponseRaw> for VerifyFromSolcJsonResponse {
+        type Error = String;
+
+        fn try_from(value: VerifyFromSolcJsonResponseRaw) -> Result<Self, Self::Error> {
+            let value = value
+                .result
+                .into_iter()
+                .next()
+                .ok_or_else(|| "response does not contain any result".to_string())?;
+
+            let address = decode_hex(&value.address).map_err(|err| {
+                format!(
+                    "address is not a valid byte sequence; address: {}, err: '{err}'",
+                    value.address
+                )
+            })?;
+
+            Ok(Self {
+                address: address.into(),
+                chain_id: value.chain_id,
+                status: value.status,
+            })
+        }
+    }
+}
+
+pub use v2::{
+    ContractMatch, ListContractsResponse, LookupContractResponse, SubmitVerificationJobError,
+    SubmitVerificationJobResponse, VerificationJob, VerificationJobError,
+};
+mod v2 {
+    use super::*;
+    use bytes::Bytes;
+    use serde::Deserializer;
+    use std::collections::BTreeMap;
+
+    fn deserialize_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Bytes, D::Error> {
+        blockscout_display_bytes::Bytes::deserialize(deserializer).map(|value| value.0)
+    }
+
+    /// Match classification of the contract returned by the v2 API.
+    /// Matches are `None` if the contract is not verified (yet).
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ContractMatch {
+        #[serde(rename = "match")]
+        pub match_type: Option<MatchType>,
+        pub creation_match: Option<MatchType>,
+        pub runtime_match: Option<MatchType>,
+        pub chain_id: String,
+        #[serde(deserialize_with = "deserialize_bytes")]
+        pub address: Bytes,
+        pub verified_at: Option<String>,
+        /// Sourcify internal id of the match; increases with each new verification.
+        pub match_id: Option<String>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+    pub struct LookupContractResponse {
+        #[serde(flatten)]
+        pub contract: ContractMatch,
+        /// Additional fields requested in the lookup (e.g., "metadata", "sources", "abi")
+        #[serde(flatten)]
+        pub fields: BTreeMap<String, serde_json::Value>,
+    }
+
+    impl LookupContractResponse {
+        pub fn metadata(&self) -> Option<&serde_json::Value> {
+            self.fields.get("metadata")
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+    pub struct ListContractsResponse {
+        pub results: Vec<ContractMatch>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SubmitVerificationJobResponse {
+        pub verification_id: String,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+    pub enum SubmitVerificationJobError {
+        #[error("{0}")]
+        AlreadyVerified(String),
+        #[error("{0}")]
+        TooManyRequests(String),
+    }
+
+    impl CustomError for SubmitVerificationJobError {
+        fn handle_status_code(status_code: reqwest::StatusCode, text: &str) -> Option<Self> {
+            match status_code {
+                reqwest::StatusCode::CONFLICT => Some(SubmitVerificationJobError::AlreadyVerified(
+                    text.to_string(),
+                )),
+                reqwest::StatusCode::TOO_MANY_REQUESTS => Some(
+                    SubmitVerificationJobError::TooManyRequests(text.to_string()),
+                ),
+                _ => None,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct VerificationJob {
+        pub is_job_completed: bool,
+        pub verification_id: String,
+        pub job_start_time: String,
+        pub job_finish_time: Option<String>,
+        pub contract: ContractMatch,
+        /// Set if the job has been completed unsuccessfully
+        pub error: Option<VerificationJobError>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct VerificationJobError {
+        pub custom_code: String,
+        pub message: String,
+        pub error_id: String,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{get_source_files_response::*, *};
@@ -431,6 +732,13 @@ mod tests {
         assert_eq!(expected, result, "{msg_prefix}check failed");
     }
 
+    fn sourcify_origins<const N: usize>(paths: [&str; N]) -> BTreeMap<String, FileOrigin> {
+        paths
+            .into_iter()
+            .map(|path| (path.to_string(), FileOrigin::Sourcify))
+            .collect()
+    }
+
     #[test]
     fn parse_error_response() {
         let value = json!({
@@ -479,6 +787,8 @@ mod tests {
             constructor_arguments: None,
             immutable_references: None,
             raw_json: value.clone(),
+            metadata_origin: FileOrigin::Sourcify,
+            source_origins: sourcify_origins(["contracts/project_/ExternalTestMultiple.sol"]),
         };
 
         check(value, expected, Some("full match"));
@@ -510,6 +820,8 @@ mod tests {
             constructor_arguments: None,
             immutable_references: None,
             raw_json: value.clone(),
+            metadata_origin: FileOrigin::Sourcify,
+            source_origins: sourcify_origins(["Reinit_Poc.sol"]),
         };
 
         check(value, expected, Some("partial match"));
@@ -553,6 +865,8 @@ mod tests {
             constructor_arguments: Some(Bytes::new()),
             immutable_references: None,
             raw_json: value.clone(),
+            metadata_origin: FileOrigin::Sourcify,
+            source_origins: sourcify_origins(["verse.sol"]),
         };
 
         check(value, expected, Some("contract with constructor-arguments"));
@@ -615,6 +929,8 @@ mod tests {
             constructor_arguments: None,
             immutable_references: Some(serde_json::Value::from_str("{\"76\":[{\"length\":32,\"start\":242}],\"90\":[{\"length\":32,\"start\":636}],\"92\":[{\"length\":32,\"start\":708}]}").unwrap()),
             raw_json: value.clone(),
+            metadata_origin: FileOrigin::Sourcify,
+            source_origins: sourcify_origins(["contracts/Array.sol", "contracts/Array2.sol", "contracts/C1.sol", "contracts/C2.sol"]),
         };
 
         check(
@@ -704,4 +1020,170 @@ mod tests {
             Some("partial match with library-map, immutable-references"),
         );
     }
+
+    #[test]
+    fn parse_verify_from_solc_json_response() {
+        let value = json!({
+          "result": [
+            {
+              "address": "0x123f681646d4a755815f9cb19e1acc8565a0c2ac",
+              "chainId": "5",
+              "status": "partial",
+              "storageTimestamp": "2023-06-20T10:05:02.000Z"
+            }
+          ]
+        });
+        let expected = VerifyFromSolcJsonResponse {
+            address: decode_hex("0x123f681646d4a755815f9cb19e1acc8565a0c2ac")
+                .unwrap()
+                .into(),
+            chain_id: "5".to_string(),
+            status: MatchType::Partial,
+        };
+
+        check(value, expected, Some("partial match"));
+    }
+
+    #[test]
+    fn parse_error_response_v2() {
+        let value = json!({
+            "customCode": "not_found",
+            "message": "Contract 0x027f1fe8BbC2a7E9fE97868E82c6Ec6939086c51 on chain 1 not found",
+            "errorId": "3bd8a4d6-5e2c-4b28-8ad4-b2e3f6d2f5a2"
+        });
+        let expected = AnyErrorResponse::V2(ErrorResponseV2 {
+            message: "Contract 0x027f1fe8BbC2a7E9fE97868E82c6Ec6939086c51 on chain 1 not found"
+                .to_string(),
+        });
+        check(value, expected, None);
+    }
+
+    #[test]
+    fn parse_lookup_contract_response() {
+        let value = json!({
+            "match": "exact_match",
+            "creationMatch": "match",
+            "runtimeMatch": "exact_match",
+            "chainId": "11155111",
+            "address": "0x4E7095a3519A33dF3D25774c2F9D7a89eB99745D",
+            "verifiedAt": "2024-07-24T12:00:00Z",
+            "matchId": "3266227",
+            "metadata": { "language": "Solidity" }
+        });
+        let expected = LookupContractResponse {
+            contract: ContractMatch {
+                match_type: Some(MatchType::Full),
+                creation_match: Some(MatchType::Partial),
+                runtime_match: Some(MatchType::Full),
+                chain_id: "11155111".to_string(),
+                address: decode_hex("0x4E7095a3519A33dF3D25774c2F9D7a89eB99745D")
+                    .unwrap()
+                    .into(),
+                verified_at: Some("2024-07-24T12:00:00Z".to_string()),
+                match_id: Some("3266227".to_string()),
+            },
+            fields: BTreeMap::from([("metadata".to_string(), json!({ "language": "Solidity" }))]),
+        };
+
+        check(value, expected, None);
+    }
+
+    #[test]
+    fn parse_verification_job() {
+        let value = json!({
+            "isJobCompleted": true,
+            "verificationId": "72d5b6fc-1a96-4c5d-8a8a-0a6c4d6b0f3e",
+            "jobStartTime": "2024-07-24T12:00:00Z",
+            "jobFinishTime": "2024-07-24T12:00:10Z",
+            "contract": {
+                "match": null,
+                "creationMatch": null,
+                "runtimeMatch": null,
+                "chainId": "11155111",
+                "address": "0x4E7095a3519A33dF3D25774c2F9D7a89eB99745D"
+            },
+            "error": {
+                "customCode": "no_match",
+                "message": "The onchain and recompiled bytecodes don't match.",
+                "errorId": "0b8c2cbe-51e8-4b5c-b3a7-7a1a2a44b1a1"
+            }
+        });
+        let expected = VerificationJob {
+            is_job_completed: true,
+            verification_id: "72d5b6fc-1a96-4c5d-8a8a-0a6c4d6b0f3e".to_string(),
+            job_start_time: "2024-07-24T12:00:00Z".to_string(),
+            job_finish_time: Some("2024-07-24T12:00:10Z".to_string()),
+            contract: ContractMatch {
+                match_type: None,
+                creation_match: None,
+                runtime_match: None,
+                chain_id: "11155111".to_string(),
+                address: decode_hex("0x4E7095a3519A33dF3D25774c2F9D7a89eB99745D")
+                    .unwrap()
+                    .into(),
+                verified_at: None,
+                match_id: None,
+            },
+            error: Some(VerificationJobError {
+                custom_code: "no_match".to_string(),
+                message: "The onchain and recompiled bytecodes don't match.".to_string(),
+                error_id: "0b8c2cbe-51e8-4b5c-b3a7-7a1a2a44b1a1".to_string(),
+            }),
+        };
+
+        check(value, expected, None);
+    }
+
+    #[test]
+    fn parse_verify_response() {
+        let value = json!({
+          "result": [
+            {
+              "address": "0x831b003398106153eD89a758bEC9734667D18AeC",
+              "chainId": "10",
+              "status": "perfect",
+              "libraryMap": {
+                "__$5762d9689e001ee319dd424b89cc702f5c$__": "9224ee604e9b62f8e0a0e5824fee2e0df2ca902f"
+              },
+              "immutableReferences": {"2155":[{"length":32,"start":4157}]},
+              "storageTimestamp": "2023-06-20T10:05:02.000Z"
+            }
+          ]
+        });
+        let expected = VerifyResponse {
+            address: decode_hex("0x831b003398106153eD89a758bEC9734667D18AeC")
+                .unwrap()
+                .into(),
+            chain_id: "10".to_string(),
+            status: MatchType::Full,
+            library_map: BTreeMap::from([(
+                "__$5762d9689e001ee319dd424b89cc702f5c$__".to_string(),
+                decode_hex("0x9224ee604e9b62f8e0a0e5824fee2e0df2ca902f")
+                    .unwrap()
+                    .into(),
+            )]),
+            immutable_references: Some(json!({"2155":[{"length":32,"start":4157}]})),
+        };
+        check(value, expected, Some("full match"));
+
+        let value = json!({
+          "result": [
+            {
+              "address": "0x123f681646d4a755815f9cb19e1acc8565a0c2ac",
+              "chainId": "5",
+              "status": "partial"
+            }
+          ]
+        });
+        let expected = VerifyResponse {
+            address: decode_hex("0x123f681646d4a755815f9cb19e1acc8565a0c2ac")
+                .unwrap()
+                .into(),
+            chain_id: "5".to_string(),
+            status: MatchType::Partial,
+            library_map: BTreeMap::new(),
+            immutable_references: None,
+        };
+        check(value, expected, Some("partial match"));
+    }
 }
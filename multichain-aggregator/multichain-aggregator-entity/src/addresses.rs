@@ -26,6 +26,9 @@ pub struct Model {
     pub is_token: bool,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub token_symbol: Option<String>,
+    pub holders_count: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
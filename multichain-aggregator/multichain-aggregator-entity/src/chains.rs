@@ -11,6 +11,7 @@ pub struct Model {
     pub icon_url: Option<String>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
+    pub is_active: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -21,10 +22,14 @@ pub enum Relation {
     ApiKeys,
     #[sea_orm(has_one = "super::block_ranges::Entity")]
     BlockRanges,
-    #[sea_orm(has_many = "super::dapps::Entity")]
-    Dapps,
+    #[sea_orm(has_many = "super::dapp_chains::Entity")]
+    DappChains,
     #[sea_orm(has_many = "super::hashes::Entity")]
     Hashes,
+    #[sea_orm(has_many = "super::import_dead_letters::Entity")]
+    ImportDeadLetters,
+    #[sea_orm(has_many = "super::nft_holdings::Entity")]
+    NftHoldings,
 }
 
 impl Related<super::addresses::Entity> for Entity {
@@ -45,9 +50,9 @@ impl Related<super::block_ranges::Entity> for Entity {
     }
 }
 
-impl Related<super::dapps::Entity> for Entity {
+impl Related<super::dapp_chains::Entity> for Entity {
     fn to() -> RelationDef {
-        Relation::Dapps.def()
+        Relation::DappChains.def()
     }
 }
 
@@ -57,4 +62,16 @@ impl Related<super::hashes::Entity> for Entity {
     }
 }
 
+impl Related<super::import_dead_letters::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ImportDeadLetters.def()
+    }
+}
+
+impl Related<super::nft_holdings::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::NftHoldings.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
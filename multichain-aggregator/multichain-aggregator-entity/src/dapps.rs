@@ -6,30 +6,26 @@ use sea_orm::entity::prelude::*;
 #[sea_orm(table_name = "dapps")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
-    pub chain_id: i64,
-    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
     pub name: String,
     pub description: String,
     pub link: String,
+    pub logo: Option<String>,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub categories: Json,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
-    #[sea_orm(
-        belongs_to = "super::chains::Entity",
-        from = "Column::ChainId",
-        to = "super::chains::Column::Id",
-        on_update = "NoAction",
-        on_delete = "NoAction"
-    )]
-    Chains,
+    #[sea_orm(has_many = "super::dapp_chains::Entity")]
+    DappChains,
 }
 
-impl Related<super::chains::Entity> for Entity {
+impl Related<super::dapp_chains::Entity> for Entity {
     fn to() -> RelationDef {
-        Relation::Chains.def()
+        Relation::DappChains.def()
     }
 }
 
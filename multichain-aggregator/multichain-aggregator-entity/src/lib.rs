@@ -6,6 +6,10 @@ pub mod addresses;
 pub mod api_keys;
 pub mod block_ranges;
 pub mod chains;
+pub mod dapp_chains;
 pub mod dapps;
 pub mod hashes;
+pub mod import_dead_letters;
+pub mod nft_holdings;
 pub mod sea_orm_active_enums;
+pub mod token_mappings;
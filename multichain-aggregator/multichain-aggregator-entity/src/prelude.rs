@@ -2,6 +2,8 @@
 
 pub use super::{
     addresses::Entity as Addresses, api_keys::Entity as ApiKeys,
-    block_ranges::Entity as BlockRanges, chains::Entity as Chains, dapps::Entity as Dapps,
-    hashes::Entity as Hashes,
+    block_ranges::Entity as BlockRanges, chains::Entity as Chains,
+    dapp_chains::Entity as DappChains, dapps::Entity as Dapps, hashes::Entity as Hashes,
+    import_dead_letters::Entity as ImportDeadLetters, nft_holdings::Entity as NftHoldings,
+    token_mappings::Entity as TokenMappings,
 };
@@ -0,0 +1,111 @@
+use crate::{
+    error::ServiceError,
+    metrics, repository,
+    types::{chain_freshness::ChainFreshness, ChainId},
+};
+use sea_orm::DatabaseConnection;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+/// Periodically exports the freshness of the active chains as metrics
+pub async fn run_chain_freshness_metrics(
+    db: DatabaseConnection,
+    stale_threshold: Duration,
+    interval: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut reported_chain_ids = HashSet::new();
+    loop {
+        interval.tick().await;
+        if let Err(err) =
+            update_chain_freshness_metrics(&db, stale_threshold, &mut reported_chain_ids).await
+        {
+            tracing::error!(error = ?err, "failed to update chain freshness metrics");
+        }
+    }
+}
+
+/// Series are updated in place rather than reset, so that scrapes never observe
+/// a partially exported state. Only the series of the removed chains are deleted.
+async fn update_chain_freshness_metrics(
+    db: &DatabaseConnection,
+    stale_threshold: Duration,
+    reported_chain_ids: &mut HashSet<String>,
+) -> Result<(), ServiceError> {
+    let chain_ids = repository::chains::list_active(db)
+        .await?
+        .into_iter()
+        .map(|c| c.id)
+        .collect::<Vec<_>>();
+    let freshness = chains_freshness(db, &chain_ids).await?;
+
+    let mut chain_ids = HashSet::with_capacity(freshness.len());
+    for f in freshness {
+        let chain_id = f.chain_id.to_string();
+        if let Some(block_number) = f.last_block_number {
+            metrics::CHAIN_LAST_BLOCK_NUMBER
+                .with_label_values(&[&chain_id])
+                .set(block_number as i64);
+        }
+        if let Some(last_imported_at) = f.last_imported_at {
+            metrics::CHAIN_LAST_IMPORT_TIMESTAMP
+                .with_label_values(&[&chain_id])
+                .set(last_imported_at.and_utc().timestamp());
+        }
+        metrics::CHAIN_STALE
+            .with_label_values(&[&chain_id])
+            .set(f.is_stale(stale_threshold) as i64);
+        chain_ids.insert(chain_id);
+    }
+
+    // Removed chains should not be reported anymore
+    for chain_id in reported_chain_ids.difference(&chain_ids) {
+        let _ = metrics::CHAIN_LAST_BLOCK_NUMBER.remove_label_values(&[chain_id]);
+        let _ = metrics::CHAIN_LAST_IMPORT_TIMESTAMP.remove_label_values(&[chain_id]);
+        let _ = metrics::CHAIN_STALE.remove_label_values(&[chain_id]);
+    }
+    *reported_chain_ids = chain_ids;
+
+    Ok(())
+}
+
+/// Returns the freshness of each of the given chains, in the same order.
+/// Chains which have never been imported have no freshness data.
+pub async fn chains_freshness(
+    db: &DatabaseConnection,
+    chain_ids: &[ChainId],
+) -> Result<Vec<ChainFreshness>, ServiceError> {
+    let mut imported = repository::block_ranges::list_freshness(db, chain_ids)
+        .await?
+        .into_iter()
+        .map(|f| (f.chain_id, f))
+        .collect::<HashMap<_, _>>();
+
+    Ok(chain_ids
+        .iter()
+        .map(|chain_id| {
+            imported.remove(chain_id).unwrap_or(ChainFreshness {
+                chain_id: *chain_id,
+                last_block_number: None,
+                last_imported_at: None,
+            })
+        })
+        .collect())
+}
+
+/// Returns the chains among the given ones with no data imported within the threshold
+pub async fn stale_chain_ids(
+    db: &DatabaseConnection,
+    chain_ids: &[ChainId],
+    stale_threshold: Duration,
+) -> Result<HashSet<ChainId>, ServiceError> {
+    Ok(chains_freshness(db, chain_ids)
+        .await?
+        .into_iter()
+        .filter(|f| f.is_stale(stale_threshold))
+        .map(|f| f.chain_id)
+        .collect())
+}
@@ -0,0 +1,117 @@
+use crate::ChainId;
+use api_client_framework::{
+    serialize_query, Endpoint, Error, HttpApiClient as Client, HttpApiClientConfig,
+};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+
+/// Name resolution is an optional part of the search,
+/// so requests are not retried and fail fast after the `timeout`
+pub fn new_client(url: Url, timeout: Duration) -> Result<Client, Error> {
+    let config = HttpApiClientConfig {
+        http_timeout: timeout,
+        max_retries: 0,
+        ..Default::default()
+    };
+    Client::new(url, config)
+}
+
+/// Characters which are not allowed in the url path segment, as defined by the WHATWG URL standard,
+/// together with `/` and `%`, so that the domain name is always a single segment
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}')
+    .add(b'/')
+    .add(b'%')
+    .add(b'\\');
+
+/// BENS client bound to the chain where the names are resolved
+#[derive(Clone)]
+pub struct BensClient {
+    pub client: Client,
+    pub chain_id: ChainId,
+}
+
+pub struct GetDomain {
+    pub chain_id: ChainId,
+    pub name: String,
+    pub params: GetDomainParams,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq)]
+pub struct GetDomainParams {
+    pub only_active: bool,
+}
+
+impl Endpoint for GetDomain {
+    type Response = Domain;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self) -> String {
+        format!(
+            "/api/v1/{}/domains/{}",
+            self.chain_id,
+            utf8_percent_encode(&self.name, PATH_SEGMENT)
+        )
+    }
+
+    fn query(&self) -> Option<String> {
+        serialize_query(&self.params)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Domain {
+    pub id: String,
+    pub name: String,
+    pub resolved_address: Option<DomainAddress>,
+    pub protocol: Option<ProtocolInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DomainAddress {
+    pub hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProtocolInfo {
+    pub id: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn domain_name_is_a_single_path_segment() {
+        let path = |name: &str| {
+            GetDomain {
+                chain_id: 1,
+                name: name.to_string(),
+                params: Default::default(),
+            }
+            .path()
+        };
+        assert_eq!(path("vitalik.eth"), "/api/v1/1/domains/vitalik.eth");
+        assert_eq!(path("💎.eth"), "/api/v1/1/domains/%F0%9F%92%8E.eth");
+        assert_eq!(
+            path("../../admin?x=1#.eth"),
+            "/api/v1/1/domains/..%2F..%2Fadmin%3Fx=1%23.eth"
+        );
+        assert_eq!(path("a%2F.eth"), "/api/v1/1/domains/a%252F.eth");
+    }
+}
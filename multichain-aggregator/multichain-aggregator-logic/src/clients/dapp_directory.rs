@@ -0,0 +1,51 @@
+use api_client_framework::{Endpoint, Error, HttpApiClient as Client, HttpApiClientConfig};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_with::{serde_as, DisplayFromStr, PickFirst};
+use url::Url;
+
+/// Creates a client for the dapp catalog.
+/// `url` should point directly to the JSON document with the catalog.
+pub fn new_client(url: Url) -> Result<Client, Error> {
+    let config = HttpApiClientConfig::default();
+    Client::new(url, config)
+}
+
+pub struct GetCatalog;
+
+impl Endpoint for GetCatalog {
+    type Response = Vec<CatalogDapp>;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self) -> String {
+        "".to_string()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogDapp {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub link: String,
+    pub logo: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub chains: Vec<CatalogDappChain>,
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogDappChain {
+    #[serde_as(as = "PickFirst<(_, DisplayFromStr)>")]
+    pub chain_id: i64,
+    #[serde(default)]
+    pub contract_addresses: Vec<String>,
+}
@@ -1,2 +1,6 @@
+pub mod bens;
 pub mod dapp;
+pub mod dapp_directory;
+pub mod opensearch;
 pub mod token_info;
+pub mod token_list;
@@ -0,0 +1,203 @@
+use api_client_framework::{Endpoint, Error, HttpApiClient as Client, HttpApiClientConfig};
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{borrow::Cow, fmt::Debug, marker::PhantomData, time::Duration};
+use url::Url;
+
+pub fn new_client(url: Url, timeout: Duration) -> Result<Client, Error> {
+    let config = HttpApiClientConfig {
+        http_timeout: timeout,
+        ..Default::default()
+    };
+    Client::new(url, config)
+}
+
+pub struct GetIndex {
+    pub index: String,
+}
+
+impl Endpoint for GetIndex {
+    type Response = serde_json::Value;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self) -> String {
+        format!("/{}", self.index)
+    }
+}
+
+pub struct CreateIndex {
+    pub index: String,
+    pub body: serde_json::Value,
+}
+
+impl Endpoint for CreateIndex {
+    type Response = serde_json::Value;
+
+    fn method(&self) -> Method {
+        Method::PUT
+    }
+
+    fn path(&self) -> String {
+        format!("/{}", self.index)
+    }
+
+    fn body(&self) -> Option<String> {
+        Some(self.body.to_string())
+    }
+}
+
+/// Bulk request, `body` is a newline-delimited JSON of the actions and the documents
+pub struct Bulk {
+    pub body: String,
+}
+
+impl Endpoint for Bulk {
+    type Response = BulkResponse;
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn path(&self) -> String {
+        "/_bulk".to_string()
+    }
+
+    fn body(&self) -> Option<String> {
+        Some(self.body.clone())
+    }
+
+    fn content_type(&self) -> Cow<'static, str> {
+        Cow::Borrowed("application/x-ndjson")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkResponse {
+    pub errors: bool,
+    pub items: Vec<serde_json::Value>,
+}
+
+pub struct Search<T> {
+    pub index: String,
+    pub body: serde_json::Value,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Search<T> {
+    pub fn new(index: String, body: serde_json::Value) -> Self {
+        Self {
+            index,
+            body,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Debug> Endpoint for Search<T> {
+    type Response = SearchResponse<T>;
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn path(&self) -> String {
+        format!("/{}/_search", self.index)
+    }
+
+    fn body(&self) -> Option<String> {
+        Some(self.body.to_string())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct SearchResponse<T> {
+    pub hits: SearchHits<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct SearchHits<T> {
+    pub hits: Vec<SearchHit<T>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(bound = "T: DeserializeOwned")]
+pub struct SearchHit<T> {
+    #[serde(rename = "_source")]
+    pub source: T,
+}
+
+pub struct DeleteByQuery {
+    pub index: String,
+    pub body: serde_json::Value,
+}
+
+impl Endpoint for DeleteByQuery {
+    type Response = serde_json::Value;
+
+    fn method(&self) -> Method {
+        Method::POST
+    }
+
+    fn path(&self) -> String {
+        format!("/{}/_delete_by_query", self.index)
+    }
+
+    fn body(&self) -> Option<String> {
+        Some(self.body.to_string())
+    }
+}
+
+pub struct GetDocument<T> {
+    pub index: String,
+    pub id: String,
+    _marker: PhantomData<T>,
+}
+
+impl<T> GetDocument<T> {
+    pub fn new(index: String, id: String) -> Self {
+        Self {
+            index,
+            id,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned + Debug> Endpoint for GetDocument<T> {
+    type Response = SearchHit<T>;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self) -> String {
+        format!("/{}/_doc/{}", self.index, self.id)
+    }
+}
+
+pub struct IndexDocument {
+    pub index: String,
+    pub id: String,
+    pub body: serde_json::Value,
+}
+
+impl Endpoint for IndexDocument {
+    type Response = serde_json::Value;
+
+    fn method(&self) -> Method {
+        Method::PUT
+    }
+
+    fn path(&self) -> String {
+        format!("/{}/_doc/{}", self.index, self.id)
+    }
+
+    fn body(&self) -> Option<String> {
+        Some(self.body.to_string())
+    }
+}
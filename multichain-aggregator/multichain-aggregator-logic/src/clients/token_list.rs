@@ -0,0 +1,119 @@
+use api_client_framework::{Endpoint, Error, HttpApiClient as Client, HttpApiClientConfig};
+use reqwest::Method;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use url::Url;
+
+/// Creates a client for the token list in the [Uniswap format](https://tokenlists.org).
+/// `url` should point directly to the JSON document with the list.
+pub fn new_client(url: Url) -> Result<Client, Error> {
+    let config = HttpApiClientConfig::default();
+    Client::new(url, config)
+}
+
+pub struct GetTokenList;
+
+impl Endpoint for GetTokenList {
+    type Response = TokenList;
+
+    fn method(&self) -> Method {
+        Method::GET
+    }
+
+    fn path(&self) -> String {
+        "".to_string()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenList {
+    /// Invalid tokens are skipped, so that a single malformed entry does not discard the whole list
+    #[serde(deserialize_with = "deserialize_valid_tokens")]
+    pub tokens: Vec<TokenListToken>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListToken {
+    pub chain_id: i64,
+    pub address: String,
+    #[serde(default)]
+    pub extensions: Option<TokenListExtensions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListExtensions {
+    /// Representations of the token on other chains, keyed by the chain id
+    #[serde(default)]
+    pub bridge_info: BTreeMap<String, BridgeInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeInfo {
+    pub token_address: String,
+}
+
+fn deserialize_valid_tokens<'de, D>(deserializer: D) -> Result<Vec<TokenListToken>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tokens = Vec::<serde_json::Value>::deserialize(deserializer)?
+        .into_iter()
+        .filter_map(|token| {
+            serde_json::from_value(token)
+                .inspect_err(|err| {
+                    tracing::warn!(error = ?err, "skipping invalid token list entry");
+                })
+                .ok()
+        })
+        .collect();
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn invalid_tokens_are_skipped() {
+        let list: TokenList = serde_json::from_value(serde_json::json!({
+            "name": "list",
+            "tokens": [
+                {
+                    "chainId": 1,
+                    "address": "0x0000000000000000000000000000000000000001",
+                    "extensions": {
+                        "bridgeInfo": {
+                            "10": { "tokenAddress": "0x0000000000000000000000000000000000000002" }
+                        }
+                    }
+                },
+                { "chainId": "one", "address": "0x0000000000000000000000000000000000000003" },
+                { "chainId": 1 },
+                {
+                    "chainId": 1,
+                    "address": "0x0000000000000000000000000000000000000004",
+                    "extensions": { "bridgeInfo": { "10": {} } }
+                },
+                { "chainId": 10, "address": "0x0000000000000000000000000000000000000005" }
+            ]
+        }))
+        .unwrap();
+
+        let addresses = list
+            .tokens
+            .iter()
+            .map(|token| token.address.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            addresses,
+            vec![
+                "0x0000000000000000000000000000000000000001",
+                "0x0000000000000000000000000000000000000005",
+            ]
+        );
+    }
+}
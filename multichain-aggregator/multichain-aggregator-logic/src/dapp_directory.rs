@@ -0,0 +1,86 @@
+use crate::{
+    clients::dapp_directory::GetCatalog, error::ServiceError, repository,
+    search_backend::SearchBackend, types::dapp_directory::DirectoryDapp,
+};
+use anyhow::Context;
+use api_client_framework::HttpApiClient;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::Duration,
+};
+
+/// Periodically imports the dapp catalog into the database.
+/// The `interval` must be non-zero, which is checked on the settings validation.
+pub async fn run_dapp_directory_import(
+    db: DatabaseConnection,
+    client: HttpApiClient,
+    search_backend: SearchBackend,
+    interval: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        match import_dapp_directory(&db, &client, &search_backend).await {
+            Ok(count) => tracing::info!(count, "dapp directory imported"),
+            Err(err) => tracing::error!(error = ?err, "failed to import dapp directory"),
+        }
+    }
+}
+
+/// Fetches the dapp catalog and replaces the stored directory with it.
+///
+/// Invalid catalog entries are skipped, as well as chains which are not active in the cluster.
+/// Returns the number of imported dapps.
+pub async fn import_dapp_directory(
+    db: &DatabaseConnection,
+    client: &HttpApiClient,
+    search_backend: &SearchBackend,
+) -> Result<usize, ServiceError> {
+    let catalog = client
+        .request(&GetCatalog)
+        .await
+        .context("failed to fetch dapp catalog")?;
+
+    let chain_ids = repository::chains::list_active(db)
+        .await?
+        .into_iter()
+        .map(|c| c.id)
+        .collect::<HashSet<_>>();
+    let dapps = catalog
+        .into_iter()
+        .filter_map(|d| {
+            let id = d.id.clone();
+            DirectoryDapp::try_from(d)
+                .inspect_err(|err| {
+                    tracing::warn!(id, error = ?err, "skipping invalid catalog dapp");
+                })
+                .ok()
+        })
+        .map(|mut d| {
+            d.chains.retain(|c| chain_ids.contains(&c.chain_id));
+            (d.id.clone(), d)
+        })
+        // the last entry wins if the catalog contains duplicated ids
+        .collect::<BTreeMap<_, _>>()
+        .into_values()
+        .collect::<Vec<_>>();
+
+    // an empty catalog is most likely a broken one, keep the previous directory
+    if dapps.is_empty() {
+        tracing::warn!("dapp catalog is empty, skipping import");
+        return Ok(0);
+    }
+
+    let count = dapps.len();
+    let tx = db.begin().await?;
+    repository::dapps::replace_all(&tx, dapps.clone()).await?;
+    tx.commit().await?;
+
+    if let Err(err) = search_backend.index_dapps(&dapps).await {
+        tracing::error!(error = ?err, "failed to index dapp directory");
+    }
+
+    Ok(count)
+}
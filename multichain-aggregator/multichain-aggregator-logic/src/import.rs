@@ -1,10 +1,21 @@
-use crate::{error::ServiceError, repository, types::batch_import_request::BatchImportRequest};
+use crate::{
+    error::ServiceError,
+    proto, repository,
+    search_backend::SearchBackend,
+    types::{
+        batch_import_request::BatchImportRequest, import_dead_letters::ImportDeadLetter, ChainId,
+    },
+};
 use sea_orm::{DatabaseConnection, TransactionTrait};
 
 pub async fn batch_import(
     db: &DatabaseConnection,
+    search_backend: &SearchBackend,
     request: BatchImportRequest,
 ) -> Result<(), ServiceError> {
+    // the database is the source of truth, so the search index is updated after the commit
+    let addresses = request.addresses.clone();
+
     let tx = db.begin().await?;
     repository::addresses::upsert_many(&tx, request.addresses)
         .await
@@ -21,6 +32,69 @@ pub async fn batch_import(
         .inspect_err(|e| {
             tracing::error!(error = ?e, "failed to upsert hashes");
         })?;
+    repository::nft_holdings::upsert_many(&tx, request.nft_holdings)
+        .await
+        .inspect_err(|e| {
+            tracing::error!(error = ?e, "failed to upsert nft holdings");
+        })?;
     tx.commit().await?;
+
+    if let Err(err) = search_backend.index_addresses(&addresses).await {
+        tracing::error!(error = ?err, "failed to index addresses");
+    }
     Ok(())
 }
+
+/// Validates the import request of the chain.
+/// Rejected requests are stored in the dead-letter queue, so that they can be replayed later.
+pub async fn validate_batch_import(
+    db: &DatabaseConnection,
+    chain_id: ChainId,
+    mut request: proto::BatchImportRequest,
+) -> Result<BatchImportRequest, ServiceError> {
+    // the payload is stored as is, so the key should not leak into the database
+    request.api_key = String::new();
+
+    match BatchImportRequest::try_from(request.clone()) {
+        Ok(request) => Ok(request),
+        Err(err) => {
+            match repository::import_dead_letters::insert(db, chain_id, &request, err.to_string())
+                .await
+            {
+                Ok(id) => tracing::warn!(id, chain_id, error = ?err, "batch import rejected"),
+                Err(e) => tracing::error!(error = ?e, "failed to store rejected batch import"),
+            }
+            Err(err)
+        }
+    }
+}
+
+/// Imports the rejected request again, e.g. after the validation has been fixed.
+/// On failure, the error of the dead letter is updated. Already replayed dead letters are not imported twice.
+pub async fn replay_dead_letter(
+    db: &DatabaseConnection,
+    search_backend: &SearchBackend,
+    id: i64,
+) -> Result<ImportDeadLetter, ServiceError> {
+    let dead_letter = repository::import_dead_letters::get(db, id)
+        .await?
+        .ok_or_else(|| ServiceError::NotFound(format!("dead letter {id}")))?;
+
+    if dead_letter.replayed_at.is_none() {
+        let res = match BatchImportRequest::try_from(dead_letter.payload) {
+            Ok(request) => batch_import(db, search_backend, request).await,
+            Err(err) => Err(err),
+        };
+        match res {
+            Ok(()) => repository::import_dead_letters::mark_replayed(db, id).await?,
+            Err(err) => {
+                tracing::warn!(id, error = ?err, "dead letter replay failed");
+                repository::import_dead_letters::mark_failed(db, id, err.to_string()).await?
+            }
+        }
+    }
+
+    repository::import_dead_letters::get(db, id)
+        .await?
+        .ok_or_else(|| ServiceError::NotFound(format!("dead letter {id}")))
+}
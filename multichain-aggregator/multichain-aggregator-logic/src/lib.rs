@@ -1,14 +1,26 @@
 pub mod api_key_manager;
+mod chain_freshness;
 pub mod clients;
+mod dapp_directory;
 pub mod error;
 mod import;
+pub mod metrics;
 mod proto;
 pub mod repository;
 pub mod search;
+pub mod search_backend;
+mod token_mappings;
 mod types;
 
-pub use import::batch_import;
+#[cfg(test)]
+mod test_utils;
+
+pub use chain_freshness::{chains_freshness, run_chain_freshness_metrics, stale_chain_ids};
+pub use dapp_directory::{import_dapp_directory, run_dapp_directory_import};
+pub use import::{batch_import, replay_dead_letter, validate_batch_import};
+pub use token_mappings::{import_token_lists, run_token_lists_import};
 pub use types::{
-    api_keys::ApiKey, batch_import_request::BatchImportRequest, chains::Chain, token_info::Token,
-    ChainId,
+    api_keys::ApiKey, batch_import_request::BatchImportRequest, chain_freshness::ChainFreshness,
+    chains::Chain, dapp_directory::DirectoryDapp, import_dead_letters::ImportDeadLetter,
+    nft_holdings::NftHoldingsPageToken, token_info::Token, ChainId,
 };
@@ -0,0 +1,23 @@
+use lazy_static::lazy_static;
+use prometheus::{register_int_gauge_vec, IntGaugeVec};
+
+lazy_static! {
+    pub static ref CHAIN_LAST_BLOCK_NUMBER: IntGaugeVec = register_int_gauge_vec!(
+        "multichain_aggregator_chain_last_block_number",
+        "latest block number imported from the chain",
+        &["chain_id"],
+    )
+    .unwrap();
+    pub static ref CHAIN_LAST_IMPORT_TIMESTAMP: IntGaugeVec = register_int_gauge_vec!(
+        "multichain_aggregator_chain_last_import_timestamp",
+        "unix timestamp of the latest import from the chain",
+        &["chain_id"],
+    )
+    .unwrap();
+    pub static ref CHAIN_STALE: IntGaugeVec = register_int_gauge_vec!(
+        "multichain_aggregator_chain_stale",
+        "1 if no data has been imported from the chain within the staleness threshold",
+        &["chain_id"],
+    )
+    .unwrap();
+}
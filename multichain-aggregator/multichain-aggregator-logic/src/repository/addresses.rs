@@ -1,3 +1,4 @@
+use super::escape_like_pattern;
 use crate::{
     error::{ParseError, ServiceError},
     types::{addresses::Address, ChainId},
@@ -16,7 +17,7 @@ fn words_regex() -> &'static Regex {
     RE.get_or_init(|| Regex::new(r"[a-zA-Z0-9]+").unwrap())
 }
 
-fn hex_regex() -> &'static Regex {
+pub(crate) fn hex_regex() -> &'static Regex {
     static RE: OnceLock<Regex> = OnceLock::new();
     RE.get_or_init(|| Regex::new(r"^(0x)?[0-9a-fA-F]{3,40}$").unwrap())
 }
@@ -120,6 +121,119 @@ where
     }
 }
 
+/// Fuzzy search of the tokens by name or symbol, based on the trigram similarity.
+/// Results are ordered by the similarity only, final ranking is done by the caller.
+pub async fn search_tokens<C>(
+    db: &C,
+    q: &str,
+    chain_ids: &[ChainId],
+    limit: u64,
+) -> Result<Vec<Address>, ServiceError>
+where
+    C: ConnectionTrait,
+{
+    let q = q.trim();
+    if q.is_empty() {
+        return Ok(vec![]);
+    }
+    let pattern = format!("%{}%", escape_like_pattern(q));
+
+    let mut query = Entity::find()
+        .filter(Column::IsToken.eq(true))
+        .filter(Expr::cust_with_values(
+            "(token_name % $1 OR token_symbol % $1 OR token_name ILIKE $2 OR token_symbol ILIKE $2)",
+            [q.to_string(), pattern],
+        ))
+        .order_by_desc(Expr::cust_with_values(
+            "GREATEST(similarity(token_name, $1), similarity(token_symbol, $1))",
+            [q.to_string()],
+        ))
+        .limit(limit);
+
+    if !chain_ids.is_empty() {
+        query = query.filter(Column::ChainId.is_in(chain_ids.iter().copied()));
+    }
+
+    let addresses = query
+        .all(db)
+        .await?
+        .into_iter()
+        .map(Address::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(addresses)
+}
+
+/// Lists all the addresses ordered by the primary key
+pub async fn list<C>(
+    db: &C,
+    page_token: Option<(AddressAlloy, ChainId)>,
+    limit: u64,
+) -> Result<(Vec<Address>, Option<(AddressAlloy, ChainId)>), ServiceError>
+where
+    C: ConnectionTrait,
+{
+    let page_token = page_token.unwrap_or((AddressAlloy::ZERO, ChainId::MIN));
+    let addresses = Entity::find()
+        .filter(
+            Expr::tuple([
+                Column::Hash.into_simple_expr(),
+                Column::ChainId.into_simple_expr(),
+            ])
+            .gte(Expr::tuple([
+                page_token.0.as_slice().into(),
+                page_token.1.into(),
+            ])),
+        )
+        .order_by_asc(Column::Hash)
+        .order_by_asc(Column::ChainId)
+        .limit(limit + 1)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(Address::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match addresses.get(limit as usize) {
+        Some(a) => Ok((
+            addresses[0..limit as usize].to_vec(),
+            Some((a.hash, a.chain_id)),
+        )),
+        None => Ok((addresses, None)),
+    }
+}
+
+pub async fn find_many<C>(
+    db: &C,
+    keys: &[(AddressAlloy, ChainId)],
+) -> Result<Vec<Address>, ServiceError>
+where
+    C: ConnectionTrait,
+{
+    if keys.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let addresses = Entity::find()
+        .filter(
+            Expr::tuple([
+                Column::Hash.into_simple_expr(),
+                Column::ChainId.into_simple_expr(),
+            ])
+            .in_tuples(
+                keys.iter()
+                    .map(|(hash, chain_id)| (hash.to_vec(), *chain_id)),
+            ),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(Address::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(addresses)
+}
+
 fn non_primary_columns() -> impl Iterator<Item = Column> {
     Column::iter().filter(|col| {
         !matches!(
@@ -140,3 +254,44 @@ fn prepare_ts_query(query: &str) -> String {
         .collect::<Vec<String>>()
         .join(" & ")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{init_db, insert_chains, token};
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    #[ignore = "Needs database to run"]
+    async fn search_tokens_filters_by_chain_ids() {
+        let db = init_db("search_tokens_filters_by_chain_ids").await;
+        let db = db.client();
+        let db = db.as_ref();
+        insert_chains(db, &[1, 10]).await;
+        let tokens = vec![
+            token(1, 0x01, "Tether USD", "USDT"),
+            token(10, 0x02, "Tether USD", "USDT"),
+            token(10, 0x03, "Wrapped Ether", "WETH"),
+        ];
+        upsert_many(db, tokens).await.unwrap();
+
+        let found = search_tokens(db, "usdt", &[1], 10).await.unwrap();
+        // the chain filter must apply to all the name and symbol matches
+        assert_eq!(
+            vec![(1, AddressAlloy::repeat_byte(0x01))],
+            found
+                .iter()
+                .map(|a| (a.chain_id, a.hash))
+                .collect::<Vec<_>>()
+        );
+
+        let mut found = search_tokens(db, "tether", &[1, 10], 10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|a| a.chain_id)
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(vec![1, 10], found);
+    }
+}
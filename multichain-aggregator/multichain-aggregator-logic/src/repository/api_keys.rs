@@ -1,18 +1,85 @@
 use crate::types::{api_keys::ApiKey, ChainId};
-use entity::api_keys::{Column, Entity};
-use sea_orm::{prelude::Uuid, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use entity::{
+    api_keys::{Column, Entity, Relation},
+    chains,
+};
+use sea_orm::{
+    prelude::Uuid, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, JoinType,
+    QueryFilter, QuerySelect, RelationTrait, Statement,
+};
 
+/// Looks up the api key of the chain. Keys of the chains removed from the cluster are not returned.
 pub async fn find_by_key_and_chain_id(
     db: &DatabaseConnection,
     key: Uuid,
     chain_id: ChainId,
 ) -> Result<Option<ApiKey>, DbErr> {
     let api_key = Entity::find()
+        .join(JoinType::InnerJoin, Relation::Chains.def())
         .filter(Column::Key.eq(key))
         .filter(Column::ChainId.eq(chain_id))
+        .filter(chains::Column::IsActive.eq(true))
         .one(db)
         .await?
         .map(ApiKey::from);
 
     Ok(api_key)
 }
+
+/// Returns an api key of the chain, a new key is generated if the chain has none
+pub async fn find_or_create_by_chain_id(
+    db: &DatabaseConnection,
+    chain_id: ChainId,
+) -> Result<ApiKey, DbErr> {
+    let api_key = Entity::find()
+        .filter(Column::ChainId.eq(chain_id))
+        .one(db)
+        .await?;
+    if let Some(api_key) = api_key {
+        return Ok(api_key.into());
+    }
+
+    let api_key = Entity::find()
+        .from_raw_sql(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            "INSERT INTO api_keys (key, chain_id) VALUES (gen_random_uuid(), $1) RETURNING *",
+            [chain_id.into()],
+        ))
+        .one(db)
+        .await?
+        .ok_or(DbErr::RecordNotInserted)?;
+
+    Ok(api_key.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        repository::chains,
+        test_utils::{init_db, insert_chains},
+    };
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    #[ignore = "Needs database to run"]
+    async fn keys_of_removed_chains_are_not_found() {
+        let db = init_db("api_keys_keys_of_removed_chains_are_not_found").await;
+        let db = db.client();
+        let db = db.as_ref();
+        insert_chains(db, &[1]).await;
+
+        let key = find_or_create_by_chain_id(db, 1).await.unwrap().key;
+        let find = |chain_id| async move {
+            find_by_key_and_chain_id(db, key, chain_id)
+                .await
+                .unwrap()
+                .map(|api_key| api_key.key)
+        };
+        assert_eq!(find(1).await, Some(key));
+        assert_eq!(find(10).await, None);
+
+        chains::deactivate(db, 1).await.unwrap();
+        assert_eq!(find(1).await, None);
+    }
+}
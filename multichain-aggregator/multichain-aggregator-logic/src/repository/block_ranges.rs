@@ -1,6 +1,10 @@
 use crate::{
     error::ServiceError,
-    types::block_ranges::{BlockRange, ChainBlockNumber},
+    types::{
+        block_ranges::{BlockRange, ChainBlockNumber},
+        chain_freshness::ChainFreshness,
+        ChainId,
+    },
 };
 use entity::block_ranges::{ActiveModel, Column, Entity, Model};
 use sea_orm::{
@@ -94,3 +98,21 @@ where
         })
         .collect())
 }
+
+pub async fn list_freshness<C>(db: &C, chain_ids: &[ChainId]) -> Result<Vec<ChainFreshness>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    if chain_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let res = Entity::find()
+        .filter(Column::ChainId.is_in(chain_ids.iter().copied()))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(ChainFreshness::from)
+        .collect();
+    Ok(res)
+}
@@ -1,7 +1,8 @@
-use crate::types::chains::Chain;
+use crate::types::{chains::Chain, ChainId};
 use entity::chains::{ActiveModel, Column, Entity, Model};
 use sea_orm::{
-    prelude::Expr, sea_query::OnConflict, ActiveValue::NotSet, ConnectionTrait, DbErr, EntityTrait,
+    prelude::Expr, sea_query::OnConflict, ActiveValue::NotSet, ColumnTrait, ConnectionTrait, DbErr,
+    EntityTrait, QueryFilter, QueryOrder,
 };
 
 pub async fn upsert_many<C>(db: &C, chains: Vec<Chain>) -> Result<(), DbErr>
@@ -31,3 +32,64 @@ where
         .await?;
     Ok(())
 }
+
+/// Adds the chain to the cluster, or re-activates it if it was removed before.
+/// Explorer and icon urls are only updated if provided.
+pub async fn activate<C>(db: &C, chain: Chain) -> Result<Chain, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let model: Model = chain.into();
+    let mut active: ActiveModel = model.into();
+    active.created_at = NotSet;
+    active.updated_at = NotSet;
+
+    Entity::insert(active)
+        .on_conflict(
+            OnConflict::columns([Column::Id])
+                .value(Column::IsActive, true)
+                .value(
+                    Column::ExplorerUrl,
+                    Expr::cust("COALESCE(EXCLUDED.explorer_url, chains.explorer_url)"),
+                )
+                .value(
+                    Column::IconUrl,
+                    Expr::cust("COALESCE(EXCLUDED.icon_url, chains.icon_url)"),
+                )
+                .value(Column::UpdatedAt, Expr::current_timestamp())
+                .to_owned(),
+        )
+        .exec_with_returning(db)
+        .await
+        .map(Chain::from)
+}
+
+/// Removes the chain from the cluster. The chain data is kept, so the chain may be added back later.
+/// Returns `false` if the chain is unknown.
+pub async fn deactivate<C>(db: &C, chain_id: ChainId) -> Result<bool, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let res = Entity::update_many()
+        .col_expr(Column::IsActive, Expr::value(false))
+        .col_expr(Column::UpdatedAt, Expr::current_timestamp())
+        .filter(Column::Id.eq(chain_id))
+        .exec(db)
+        .await?;
+    Ok(res.rows_affected > 0)
+}
+
+pub async fn list_active<C>(db: &C) -> Result<Vec<Chain>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let chains = Entity::find()
+        .filter(Column::IsActive.eq(true))
+        .order_by_asc(Column::Id)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(Chain::from)
+        .collect();
+    Ok(chains)
+}
@@ -0,0 +1,291 @@
+use super::escape_like_pattern;
+use crate::{
+    error::ServiceError,
+    types::{dapp_directory::DirectoryDapp, ChainId},
+};
+use entity::{dapp_chains, dapps};
+use sea_orm::{
+    prelude::Expr,
+    sea_query::{OnConflict, Query},
+    ActiveValue::NotSet,
+    ColumnTrait, ConnectionTrait, DbErr, EntityTrait, FromQueryResult, QueryFilter, QueryOrder,
+    QuerySelect, Statement,
+};
+use std::collections::HashMap;
+
+const INSERT_BATCH_SIZE: usize = 1000;
+
+/// Replaces the stored directory with the `dapps`.
+/// Dapps which are missing in the new directory are removed together with their chains.
+/// Should be called inside a transaction.
+pub async fn replace_all<C>(db: &C, dapps: Vec<DirectoryDapp>) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let ids = dapps.iter().map(|d| d.id.clone()).collect::<Vec<_>>();
+
+    dapps::Entity::delete_many()
+        .filter(dapps::Column::Id.is_not_in(ids.clone()))
+        .exec(db)
+        .await?;
+    dapp_chains::Entity::delete_many()
+        .filter(dapp_chains::Column::DappId.is_in(ids))
+        .exec(db)
+        .await?;
+
+    let (dapps, chains): (Vec<_>, Vec<_>) = dapps.into_iter().map(|d| d.into_models()).unzip();
+
+    for batch in dapps.chunks(INSERT_BATCH_SIZE) {
+        let batch = batch.iter().cloned().map(|model| {
+            let mut active: dapps::ActiveModel = model.into();
+            active.created_at = NotSet;
+            active.updated_at = NotSet;
+            active
+        });
+        dapps::Entity::insert_many(batch)
+            .on_conflict(
+                OnConflict::column(dapps::Column::Id)
+                    .update_columns([
+                        dapps::Column::Name,
+                        dapps::Column::Description,
+                        dapps::Column::Link,
+                        dapps::Column::Logo,
+                        dapps::Column::Categories,
+                    ])
+                    .value(dapps::Column::UpdatedAt, Expr::current_timestamp())
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+    }
+
+    let chains = chains.into_iter().flatten().collect::<Vec<_>>();
+    for batch in chains.chunks(INSERT_BATCH_SIZE) {
+        let batch = batch.iter().cloned().map(|model| {
+            let mut active: dapp_chains::ActiveModel = model.into();
+            active.created_at = NotSet;
+            active.updated_at = NotSet;
+            active
+        });
+        dapp_chains::Entity::insert_many(batch)
+            .exec_without_returning(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+pub async fn list<C>(
+    db: &C,
+    q: Option<&str>,
+    chain_id: Option<ChainId>,
+    category: Option<&str>,
+    page_token: Option<String>,
+    limit: u64,
+) -> Result<(Vec<DirectoryDapp>, Option<String>), ServiceError>
+where
+    C: ConnectionTrait,
+{
+    let mut query = dapps::Entity::find()
+        .order_by_asc(dapps::Column::Id)
+        .limit(limit + 1);
+
+    if let Some(page_token) = page_token {
+        query = query.filter(dapps::Column::Id.gte(page_token));
+    }
+
+    if let Some(q) = q.map(str::trim).filter(|q| !q.is_empty()) {
+        let pattern = format!("%{}%", escape_like_pattern(q));
+        query = query.filter(Expr::cust_with_values(
+            "(dapps.name % $1 OR dapps.name ILIKE $2 OR dapps.description ILIKE $2)",
+            [q.to_string(), pattern],
+        ));
+    }
+
+    if let Some(chain_id) = chain_id {
+        query = query.filter(
+            dapps::Column::Id.in_subquery(
+                Query::select()
+                    .column(dapp_chains::Column::DappId)
+                    .from(dapp_chains::Entity)
+                    .and_where(dapp_chains::Column::ChainId.eq(chain_id))
+                    .to_owned(),
+            ),
+        );
+    }
+
+    if let Some(category) = category.map(str::trim).filter(|c| !c.is_empty()) {
+        query = query.filter(Expr::cust_with_values(
+            "dapps.categories @> jsonb_build_array($1::text)",
+            [category.to_lowercase()],
+        ));
+    }
+
+    let mut dapps = query.all(db).await?;
+    let next_page_token = match dapps.len() > limit as usize {
+        true => dapps.pop().map(|d| d.id),
+        false => None,
+    };
+
+    let dapps = with_chains(db, dapps).await?;
+
+    Ok((dapps, next_page_token))
+}
+
+pub async fn get<C>(db: &C, id: &str) -> Result<Option<DirectoryDapp>, ServiceError>
+where
+    C: ConnectionTrait,
+{
+    let dapp = match dapps::Entity::find_by_id(id).one(db).await? {
+        Some(dapp) => dapp,
+        None => return Ok(None),
+    };
+
+    Ok(with_chains(db, vec![dapp]).await?.pop())
+}
+
+/// Returns the dapps with the given ids, the missing ones are skipped
+pub async fn find_many<C>(db: &C, ids: &[String]) -> Result<Vec<DirectoryDapp>, ServiceError>
+where
+    C: ConnectionTrait,
+{
+    if ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let dapps = dapps::Entity::find()
+        .filter(dapps::Column::Id.is_in(ids.iter().cloned()))
+        .all(db)
+        .await?;
+
+    with_chains(db, dapps).await
+}
+
+pub async fn list_categories<C>(db: &C) -> Result<Vec<String>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    #[derive(FromQueryResult)]
+    struct Category {
+        category: String,
+    }
+
+    let categories = Category::find_by_statement(Statement::from_string(
+        db.get_database_backend(),
+        "SELECT DISTINCT category \
+            FROM dapps, jsonb_array_elements_text(dapps.categories) AS category \
+            ORDER BY category",
+    ))
+    .all(db)
+    .await?
+    .into_iter()
+    .map(|c| c.category)
+    .collect();
+
+    Ok(categories)
+}
+
+async fn with_chains<C>(
+    db: &C,
+    dapps: Vec<dapps::Model>,
+) -> Result<Vec<DirectoryDapp>, ServiceError>
+where
+    C: ConnectionTrait,
+{
+    let mut chains: HashMap<String, Vec<dapp_chains::Model>> = HashMap::new();
+    dapp_chains::Entity::find()
+        .filter(dapp_chains::Column::DappId.is_in(dapps.iter().map(|d| d.id.clone())))
+        .order_by_asc(dapp_chains::Column::ChainId)
+        .all(db)
+        .await?
+        .into_iter()
+        .for_each(|c| chains.entry(c.dapp_id.clone()).or_default().push(c));
+
+    let dapps = dapps
+        .into_iter()
+        .map(|d| {
+            let chains = chains.remove(&d.id).unwrap_or_default();
+            DirectoryDapp::try_from((d, chains))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(dapps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{init_db, insert_chains},
+        types::dapp_directory::DirectoryDappChain,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn dapp(id: &str, name: &str, category: &str, chain_ids: &[ChainId]) -> DirectoryDapp {
+        DirectoryDapp {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: format!("{name} description"),
+            link: format!("https://{id}.example.com"),
+            logo: None,
+            categories: vec![category.to_string()],
+            chains: chain_ids
+                .iter()
+                .map(|chain_id| DirectoryDappChain {
+                    chain_id: *chain_id,
+                    contract_addresses: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    fn ids(dapps: &[DirectoryDapp]) -> Vec<&str> {
+        dapps.iter().map(|d| d.id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    #[ignore = "Needs database to run"]
+    async fn list_with_filters_and_pagination() {
+        let db = init_db("dapps_list_with_filters_and_pagination").await;
+        let db = db.client();
+        let db = db.as_ref();
+        insert_chains(db, &[1, 10]).await;
+        replace_all(
+            db,
+            vec![
+                dapp("aave", "Aave", "defi", &[1, 10]),
+                dapp("opensea", "OpenSea", "nft", &[1]),
+                dapp("uniswap", "Uniswap", "defi", &[1, 10]),
+                dapp("velodrome", "Velodrome", "defi", &[10]),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let (dapps, next_page_token) = list(db, None, None, None, None, 2).await.unwrap();
+        assert_eq!(vec!["aave", "opensea"], ids(&dapps));
+        assert_eq!(Some("uniswap".to_string()), next_page_token);
+        let (dapps, next_page_token) = list(db, None, None, None, next_page_token, 2)
+            .await
+            .unwrap();
+        assert_eq!(vec!["uniswap", "velodrome"], ids(&dapps));
+        assert_eq!(None, next_page_token);
+
+        let (dapps, _) = list(db, None, Some(10), Some("defi"), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(vec!["aave", "uniswap", "velodrome"], ids(&dapps));
+
+        // the chain filter must apply to the description matches as well
+        let (dapps, _) = list(db, Some("description"), Some(10), None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(vec!["aave", "uniswap", "velodrome"], ids(&dapps));
+
+        let (dapps, next_page_token) = list(db, Some("uni"), Some(1), Some("defi"), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(vec!["uniswap"], ids(&dapps));
+        assert_eq!(None, next_page_token);
+    }
+}
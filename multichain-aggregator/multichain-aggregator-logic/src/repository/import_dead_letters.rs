@@ -0,0 +1,114 @@
+use crate::{
+    error::{ParseError, ServiceError},
+    proto,
+    types::{import_dead_letters::ImportDeadLetter, ChainId},
+};
+use entity::import_dead_letters::{ActiveModel, Column, Entity};
+use sea_orm::{
+    prelude::Expr, ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr, EntityTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+
+pub async fn insert<C>(
+    db: &C,
+    chain_id: ChainId,
+    payload: &proto::BatchImportRequest,
+    error: String,
+) -> Result<i64, ServiceError>
+where
+    C: ConnectionTrait,
+{
+    let payload = serde_json::to_value(payload)
+        .map_err(|e| ParseError::Custom(format!("invalid payload: {e}")))?;
+    let model = ActiveModel {
+        chain_id: Set(chain_id),
+        payload: Set(payload),
+        error: Set(error),
+        ..Default::default()
+    };
+
+    let res = Entity::insert(model).exec(db).await?;
+    Ok(res.last_insert_id)
+}
+
+pub async fn get<C>(db: &C, id: i64) -> Result<Option<ImportDeadLetter>, ServiceError>
+where
+    C: ConnectionTrait,
+{
+    let dead_letter = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .map(ImportDeadLetter::try_from)
+        .transpose()?;
+    Ok(dead_letter)
+}
+
+/// Lists the dead letters starting from the most recent ones
+pub async fn list<C>(
+    db: &C,
+    chain_id: Option<ChainId>,
+    include_replayed: bool,
+    page_token: Option<i64>,
+    limit: u64,
+) -> Result<(Vec<ImportDeadLetter>, Option<i64>), ServiceError>
+where
+    C: ConnectionTrait,
+{
+    let mut query = Entity::find().order_by_desc(Column::Id).limit(limit + 1);
+
+    if let Some(page_token) = page_token {
+        query = query.filter(Column::Id.lte(page_token));
+    }
+    if let Some(chain_id) = chain_id {
+        query = query.filter(Column::ChainId.eq(chain_id));
+    }
+    if !include_replayed {
+        query = query.filter(Column::ReplayedAt.is_null());
+    }
+
+    let mut dead_letters = query.all(db).await?;
+    let next_page_token = match dead_letters.len() > limit as usize {
+        true => dead_letters.pop().map(|d| d.id),
+        false => None,
+    };
+    let dead_letters = dead_letters
+        .into_iter()
+        .map(ImportDeadLetter::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok((dead_letters, next_page_token))
+}
+
+pub async fn mark_replayed<C>(db: &C, id: i64) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    Entity::update_many()
+        .col_expr(Column::ReplayedAt, Expr::current_timestamp())
+        .col_expr(
+            Column::ReplayAttempts,
+            Expr::col(Column::ReplayAttempts).add(1),
+        )
+        .col_expr(Column::UpdatedAt, Expr::current_timestamp())
+        .filter(Column::Id.eq(id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn mark_failed<C>(db: &C, id: i64, error: String) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    Entity::update_many()
+        .col_expr(Column::Error, Expr::value(error))
+        .col_expr(
+            Column::ReplayAttempts,
+            Expr::col(Column::ReplayAttempts).add(1),
+        )
+        .col_expr(Column::UpdatedAt, Expr::current_timestamp())
+        .filter(Column::Id.eq(id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
@@ -2,4 +2,15 @@ pub mod addresses;
 pub mod api_keys;
 pub mod block_ranges;
 pub mod chains;
+pub mod dapps;
 pub mod hashes;
+pub mod import_dead_letters;
+pub mod nft_holdings;
+pub mod token_mappings;
+
+pub(crate) fn escape_like_pattern(query: &str) -> String {
+    query
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
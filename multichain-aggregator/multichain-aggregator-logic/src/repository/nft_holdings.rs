@@ -0,0 +1,207 @@
+use crate::{
+    error::{ParseError, ServiceError},
+    types::{
+        nft_holdings::{NftCollectionHolding, NftHolding, NftHoldingsPageToken},
+        ChainId,
+    },
+};
+use alloy_primitives::{Address, U256};
+use entity::{
+    nft_holdings::{ActiveModel, Column, Entity, Model},
+    sea_orm_active_enums as db_enum,
+};
+use sea_orm::{
+    prelude::Expr, sea_query::OnConflict, ActiveValue::NotSet, ConnectionTrait, DbErr, EntityTrait,
+    FromQueryResult, IntoSimpleExpr, QueryFilter, Statement, Value,
+};
+use std::collections::HashMap;
+
+/// Max number of token ids returned for a single collection
+const MAX_COLLECTION_TOKENS: i64 = 100;
+
+/// Upserts the holdings, the holdings with zero amount are removed
+pub async fn upsert_many<C>(db: &C, holdings: Vec<NftHolding>) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    // The same token may be transferred several times within a batch, the latest amount wins
+    let holdings = holdings
+        .into_iter()
+        .map(|h| ((h.owner, h.chain_id, h.token_address, h.token_id), h))
+        .collect::<HashMap<_, _>>();
+    let (removed, holdings): (Vec<_>, Vec<_>) =
+        holdings.into_values().partition(|h| h.amount.is_zero());
+
+    if !removed.is_empty() {
+        Entity::delete_many()
+            .filter(
+                Expr::tuple([
+                    Column::Owner.into_simple_expr(),
+                    Column::ChainId.into_simple_expr(),
+                    Column::TokenAddress.into_simple_expr(),
+                    Column::TokenId.into_simple_expr(),
+                ])
+                .in_tuples(removed.into_iter().map(|h| {
+                    (
+                        h.owner.to_vec(),
+                        h.chain_id,
+                        h.token_address.to_vec(),
+                        h.token_id.to_be_bytes_vec(),
+                    )
+                })),
+            )
+            .exec(db)
+            .await?;
+    }
+
+    if holdings.is_empty() {
+        return Ok(());
+    }
+
+    let holdings = holdings.into_iter().map(|holding| {
+        let model: Model = holding.into();
+        let mut active: ActiveModel = model.into();
+        active.created_at = NotSet;
+        active.updated_at = NotSet;
+        active
+    });
+
+    Entity::insert_many(holdings)
+        .on_conflict(
+            OnConflict::columns([
+                Column::Owner,
+                Column::ChainId,
+                Column::TokenAddress,
+                Column::TokenId,
+            ])
+            .update_column(Column::Amount)
+            .value(Column::UpdatedAt, Expr::current_timestamp())
+            .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Lists the collections owned by the address, grouped by the chain.
+/// Collections of different chains are interleaved, so that each chain is represented on the first pages.
+pub async fn list_collections<C>(
+    db: &C,
+    owner: Address,
+    chain_ids: &[ChainId],
+    page_token: Option<NftHoldingsPageToken>,
+    limit: u64,
+) -> Result<(Vec<NftCollectionHolding>, Option<NftHoldingsPageToken>), ServiceError>
+where
+    C: ConnectionTrait,
+{
+    #[derive(FromQueryResult)]
+    struct CollectionRow {
+        chain_id: i64,
+        token_address: Vec<u8>,
+        token_name: Option<String>,
+        token_symbol: Option<String>,
+        token_type: Option<db_enum::TokenType>,
+        tokens_count: i64,
+        // [[token_id, amount], ...] as hex strings
+        tokens: serde_json::Value,
+    }
+
+    let page_token = page_token.unwrap_or_default();
+
+    let mut values: Vec<Value> = vec![owner.to_vec().into()];
+    let mut filters = String::new();
+    if !chain_ids.is_empty() {
+        let placeholders = chain_ids
+            .iter()
+            .map(|chain_id| {
+                values.push((*chain_id).into());
+                format!("${}", values.len())
+            })
+            .collect::<Vec<_>>();
+        filters.push_str(&format!(" AND chain_id IN ({})", placeholders.join(", ")));
+    }
+    for (chain_id, address) in &page_token.0 {
+        values.push((*chain_id).into());
+        values.push(address.to_vec().into());
+        filters.push_str(&format!(
+            " AND NOT (chain_id = ${} AND token_address <= ${})",
+            values.len() - 1,
+            values.len()
+        ));
+    }
+    values.push(MAX_COLLECTION_TOKENS.into());
+    let max_tokens = values.len();
+    values.push(((limit + 1) as i64).into());
+    let limit_placeholder = values.len();
+
+    let sql = format!(
+        r#"
+        SELECT h.chain_id, h.token_address, a.token_name, a.token_symbol, a.token_type,
+            h.tokens_count, h.tokens
+        FROM (
+            SELECT chain_id, token_address,
+                count(*) AS tokens_count,
+                jsonb_agg(jsonb_build_array(encode(token_id, 'hex'), encode(amount, 'hex')) ORDER BY token_id)
+                    FILTER (WHERE token_rn <= ${max_tokens}) AS tokens,
+                row_number() OVER (PARTITION BY chain_id ORDER BY token_address) AS collection_rn
+            FROM (
+                SELECT *, row_number() OVER (PARTITION BY chain_id, token_address ORDER BY token_id) AS token_rn
+                FROM nft_holdings
+                WHERE owner = $1{filters}
+            ) t
+            GROUP BY chain_id, token_address
+        ) h
+        LEFT JOIN addresses a ON a.hash = h.token_address AND a.chain_id = h.chain_id
+        ORDER BY h.collection_rn, h.chain_id
+        LIMIT ${limit_placeholder}
+        "#
+    );
+
+    let rows = CollectionRow::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        sql,
+        values,
+    ))
+    .all(db)
+    .await?;
+
+    let has_next_page = rows.len() as u64 > limit;
+    let collections = rows
+        .into_iter()
+        .take(limit as usize)
+        .map(|row| {
+            let tokens = serde_json::from_value::<Vec<(String, String)>>(row.tokens)
+                .map_err(|e| ParseError::Custom(format!("invalid collection tokens: {e}")))?
+                .into_iter()
+                .map(|(token_id, amount)| {
+                    Ok((
+                        U256::from_be_slice(&alloy_primitives::hex::decode(token_id)?),
+                        U256::from_be_slice(&alloy_primitives::hex::decode(amount)?),
+                    ))
+                })
+                .collect::<Result<Vec<_>, ParseError>>()?;
+
+            Ok(NftCollectionHolding {
+                chain_id: row.chain_id,
+                token_address: Address::try_from(row.token_address.as_slice())?,
+                token_name: row.token_name,
+                token_symbol: row.token_symbol,
+                token_type: row.token_type,
+                tokens_count: row.tokens_count as u64,
+                tokens,
+            })
+        })
+        .collect::<Result<Vec<_>, ParseError>>()?;
+
+    let next_page_token = has_next_page.then(|| {
+        let mut page_token = page_token;
+        for c in &collections {
+            page_token.0.insert(c.chain_id, c.token_address);
+        }
+        page_token
+    });
+
+    Ok((collections, next_page_token))
+}
@@ -0,0 +1,86 @@
+use crate::{
+    error::ServiceError,
+    types::{token_mappings::TokenMapping, ChainId},
+};
+use alloy_primitives::Address as AddressAlloy;
+use entity::token_mappings::{ActiveModel, Column, Entity, Model};
+use sea_orm::{
+    prelude::Expr, sea_query::OnConflict, ActiveValue::NotSet, ConnectionTrait, DbErr, EntityTrait,
+    IntoSimpleExpr, QueryFilter,
+};
+
+const INSERT_BATCH_SIZE: usize = 5000;
+
+/// Replaces the stored mappings with the given ones. Should be run inside a transaction:
+/// every mapping written by the upsert gets the transaction timestamp as `updated_at`,
+/// so the mappings with an older timestamp are the ones missing from the new set.
+pub async fn replace_all<C>(db: &C, mappings: Vec<TokenMapping>) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    upsert_many(db, mappings).await?;
+    Entity::delete_many()
+        .filter(Expr::col(Column::UpdatedAt).lt(Expr::current_timestamp()))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn upsert_many<C>(db: &C, mappings: Vec<TokenMapping>) -> Result<(), DbErr>
+where
+    C: ConnectionTrait,
+{
+    for batch in mappings.chunks(INSERT_BATCH_SIZE) {
+        let batch = batch.iter().cloned().map(|mapping| {
+            let model: Model = mapping.into();
+            let mut active: ActiveModel = model.into();
+            active.created_at = NotSet;
+            active.updated_at = NotSet;
+            active
+        });
+        Entity::insert_many(batch)
+            .on_conflict(
+                OnConflict::columns([Column::ChainId, Column::Address])
+                    .update_columns([Column::CanonicalChainId, Column::CanonicalAddress])
+                    .value(Column::UpdatedAt, Expr::current_timestamp())
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Returns the mappings of the tokens which are known to be bridged representations
+pub async fn find_many<C>(
+    db: &C,
+    keys: &[(AddressAlloy, ChainId)],
+) -> Result<Vec<TokenMapping>, ServiceError>
+where
+    C: ConnectionTrait,
+{
+    if keys.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mappings = Entity::find()
+        .filter(
+            Expr::tuple([
+                Column::Address.into_simple_expr(),
+                Column::ChainId.into_simple_expr(),
+            ])
+            .in_tuples(
+                keys.iter()
+                    .map(|(address, chain_id)| (address.to_vec(), *chain_id)),
+            ),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(TokenMapping::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(mappings)
+}
@@ -1,24 +1,39 @@
 use crate::{
     clients::{
+        bens::{BensClient, GetDomain, GetDomainParams},
         dapp::{SearchDapps, SearchDappsParams},
-        token_info::{SearchTokenInfos, SearchTokenInfosParams},
+        token_info::{SearchTokenInfos, SearchTokenInfosParams, TokenInfo},
     },
     error::ServiceError,
-    repository::{addresses, block_ranges, hashes},
+    repository::{addresses, block_ranges, hashes, token_mappings},
+    search_backend::SearchBackend,
     types::{
+        addresses::Address,
         chains::Chain,
         dapp::MarketplaceDapp,
+        domains::ResolvedDomain,
         search_results::{ChainSearchResult, SearchResults},
         token_info::Token,
         ChainId,
     },
 };
+use alloy_primitives::Address as AddressAlloy;
 use api_client_framework::HttpApiClient;
+use regex::Regex;
 use sea_orm::DatabaseConnection;
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::OnceLock};
 use tokio::join;
 use tracing::instrument;
 
+fn domain_name_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    // the top level label should not start with a digit, so that decimals are not treated as names;
+    // url delimiters are not allowed in the labels, as the name is a part of the BENS request path
+    RE.get_or_init(|| {
+        Regex::new(r"^[^\s./?#%\\]+(\.[^\s./?#%\\]+)*\.[^\s./?#%\\\d][^\s./?#%\\]*$").unwrap()
+    })
+}
+
 macro_rules! populate_search_results {
     ($target:expr, $explorers:expr, $from:expr, $field:ident) => {
         for e in $from {
@@ -37,8 +52,10 @@ macro_rules! populate_search_results {
 #[instrument(skip_all, level = "info", fields(query = query))]
 pub async fn quick_search(
     db: &DatabaseConnection,
+    search_backend: &SearchBackend,
     dapp_client: &HttpApiClient,
     token_info_client: &HttpApiClient,
+    bens_client: Option<&BensClient>,
     query: String,
     chains: &[Chain],
 ) -> Result<SearchResults, ServiceError> {
@@ -50,21 +67,28 @@ pub async fn quick_search(
         },
     };
 
+    let chain_ids: Vec<ChainId> = chains.iter().map(|c| c.id).collect();
+
     let token_info_search_endpoint = SearchTokenInfos {
         params: SearchTokenInfosParams {
             query: raw_query.to_string(),
-            chain_id: None,
+            chain_id: match chain_ids.as_slice() {
+                [chain_id] => Some(*chain_id),
+                _ => None,
+            },
             page_size: Some(100),
             page_token: None,
         },
     };
 
-    let (hashes, block_numbers, addresses, dapps, token_infos) = join!(
+    let (hashes, block_numbers, addresses, dapps, token_infos, db_tokens, domain) = join!(
         hashes::search_by_query(db, raw_query),
         block_ranges::search_by_query(db, raw_query),
-        addresses::search_by_query(db, raw_query),
+        search_backend.search_addresses(db, raw_query, None, None, 100),
         dapp_client.request(&dapp_search_endpoint),
         token_info_client.request(&token_info_search_endpoint),
+        search_backend.search_tokens(db, raw_query, &chain_ids, 100),
+        resolve_domain(db, bens_client, raw_query, &chain_ids),
     );
 
     let explorers: BTreeMap<ChainId, String> = chains
@@ -94,7 +118,7 @@ pub async fn quick_search(
     }
 
     match addresses {
-        Ok(addresses) => {
+        Ok((addresses, _)) => {
             populate_search_results!(results, explorers, addresses, addresses);
         }
         Err(err) => {
@@ -115,19 +139,192 @@ pub async fn quick_search(
         }
     }
 
-    match token_infos {
-        Ok(token_infos) => {
-            let tokens: Vec<Token> = token_infos
-                .token_infos
-                .into_iter()
-                .filter_map(|t| t.try_into().ok())
-                .collect();
-            populate_search_results!(results, explorers, tokens, tokens);
-        }
+    let token_infos = token_infos
+        .inspect_err(|err| tracing::error!(error = ?err, "failed to search token infos"))
+        .map(|res| res.token_infos)
+        .unwrap_or_default();
+    let db_tokens = db_tokens
+        .inspect_err(|err| tracing::error!(error = ?err, "failed to search tokens"))
+        .unwrap_or_default();
+    let tokens = merge_and_rank_tokens(db, raw_query, token_infos, db_tokens).await;
+    populate_search_results!(results, explorers, tokens, tokens);
+
+    if let Some((domain, domain_addresses)) = domain {
+        // the address may be already found by its name, avoid duplicates in that case
+        let domain_addresses: Vec<Address> = domain_addresses
+            .into_iter()
+            .filter(|a| {
+                !results
+                    .items
+                    .get(&a.chain_id)
+                    .is_some_and(|r| r.addresses.iter().any(|found| found.hash == a.hash))
+            })
+            .collect();
+        populate_search_results!(results, explorers, domain_addresses, addresses);
+        results.domain = Some(domain);
+    }
+
+    Ok(results)
+}
+
+/// Resolves the query through BENS if it looks like a domain name.
+/// Returns the resolved domain together with the records of the resolved address
+/// on the chains where it is known.
+///
+/// Name resolution is optional, so any failure is logged and results in `None`.
+async fn resolve_domain(
+    db: &DatabaseConnection,
+    bens_client: Option<&BensClient>,
+    query: &str,
+    chain_ids: &[ChainId],
+) -> Option<(ResolvedDomain, Vec<Address>)> {
+    let bens_client = bens_client?;
+    if !domain_name_regex().is_match(query) {
+        return None;
+    }
+
+    let endpoint = GetDomain {
+        chain_id: bens_client.chain_id,
+        name: query.to_lowercase(),
+        params: GetDomainParams { only_active: true },
+    };
+    let domain = match bens_client.client.request(&endpoint).await {
+        Ok(domain) => domain,
+        Err(api_client_framework::Error::NotFound) => return None,
         Err(err) => {
-            tracing::error!(error = ?err, "failed to search token infos");
+            tracing::warn!(error = ?err, "failed to resolve domain name");
+            return None;
         }
+    };
+    let mut domain = ResolvedDomain::try_from(domain).ok()?;
+
+    let keys: Vec<(AddressAlloy, ChainId)> = chain_ids
+        .iter()
+        .map(|chain_id| (domain.address, *chain_id))
+        .collect();
+    let mut domain_addresses = addresses::find_many(db, &keys)
+        .await
+        .inspect_err(|err| tracing::error!(error = ?err, "failed to find domain addresses"))
+        .unwrap_or_default();
+    for address in domain_addresses.iter_mut() {
+        address.ens_name.get_or_insert_with(|| domain.name.clone());
     }
+    domain.chain_ids = domain_addresses.iter().map(|a| a.chain_id).collect();
 
-    Ok(results)
+    Some((domain, domain_addresses))
+}
+
+/// Merges the tokens found by the token info service with the fuzzy matches from the database,
+/// fills them with the data known to the aggregator and orders them by the relevance.
+async fn merge_and_rank_tokens(
+    db: &DatabaseConnection,
+    query: &str,
+    token_infos: Vec<TokenInfo>,
+    db_tokens: Vec<Address>,
+) -> Vec<Token> {
+    let mut tokens: BTreeMap<(ChainId, AddressAlloy), Token> = token_infos
+        .into_iter()
+        .filter_map(|t| Token::try_from(t).ok())
+        .map(|t| ((t.chain_id, t.address), t))
+        .collect();
+
+    let missing: Vec<(AddressAlloy, ChainId)> = tokens
+        .keys()
+        .filter(|key| !db_tokens.iter().any(|a| (a.chain_id, a.hash) == **key))
+        .map(|(chain_id, address)| (*address, *chain_id))
+        .collect();
+    let known = addresses::find_many(db, &missing)
+        .await
+        .inspect_err(|err| tracing::error!(error = ?err, "failed to find token addresses"))
+        .unwrap_or_default();
+
+    for address in known.into_iter().chain(db_tokens) {
+        match tokens.get_mut(&(address.chain_id, address.hash)) {
+            Some(token) => token.enrich(&address),
+            None => {
+                if let Ok(token) = Token::try_from(address) {
+                    tokens.insert((token.chain_id, token.address), token);
+                }
+            }
+        }
+    }
+
+    let mut ranked: Vec<(f64, Token)> = tokens
+        .into_values()
+        .map(|t| (t.relevance(query), t))
+        .collect();
+    ranked.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+    let ranked = ranked.into_iter().map(|(_, t)| t).collect();
+
+    merge_bridged_tokens(db, ranked).await
+}
+
+/// Merges the bridged representations of the same token into a single result.
+///
+/// The canonical token represents the group if it is among the `tokens`,
+/// otherwise the first token of the group does. The order of the `tokens` is preserved.
+pub async fn merge_bridged_tokens(db: &DatabaseConnection, tokens: Vec<Token>) -> Vec<Token> {
+    let keys: Vec<(AddressAlloy, ChainId)> =
+        tokens.iter().map(|t| (t.address, t.chain_id)).collect();
+    let canonical: BTreeMap<(ChainId, AddressAlloy), (ChainId, AddressAlloy)> =
+        token_mappings::find_many(db, &keys)
+            .await
+            .inspect_err(|err| tracing::error!(error = ?err, "failed to find token mappings"))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|m| {
+                (
+                    (m.chain_id, m.address),
+                    (m.canonical_chain_id, m.canonical_address),
+                )
+            })
+            .collect();
+
+    let mut groups: Vec<((ChainId, AddressAlloy), Vec<Token>)> = vec![];
+    for token in tokens {
+        let key = (token.chain_id, token.address);
+        let group_key = canonical.get(&key).copied().unwrap_or(key);
+        match groups.iter_mut().find(|(k, _)| *k == group_key) {
+            Some((_, group)) => group.push(token),
+            None => groups.push((group_key, vec![token])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(group_key, mut group)| {
+            let representative = group
+                .iter()
+                .position(|t| (t.chain_id, t.address) == group_key)
+                .unwrap_or_default();
+            let mut token = group.remove(representative);
+            token.bridged_tokens = group.into_iter().map(|t| (t.chain_id, t.address)).collect();
+            token
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn domain_name_regex_ok() {
+        for name in ["vitalik.eth", "sub.vitalik.eth", "💎.eth", "name.b0b"] {
+            assert!(domain_name_regex().is_match(name), "{name}");
+        }
+        for name in [
+            "1.5",
+            "vitalik",
+            "vitalik eth",
+            "../admin.eth",
+            "a/b.eth",
+            "a?b.eth",
+            "a#b.eth",
+            "a%2F.eth",
+            "a\\b.eth",
+        ] {
+            assert!(!domain_name_regex().is_match(name), "{name}");
+        }
+    }
 }
@@ -0,0 +1,94 @@
+mod opensearch;
+
+pub use opensearch::OpenSearchBackend;
+
+use crate::{
+    error::ServiceError,
+    repository::{addresses, dapps},
+    types::{addresses::Address, dapp_directory::DirectoryDapp, ChainId},
+};
+use alloy_primitives::Address as AddressAlloy;
+use sea_orm::DatabaseConnection;
+
+/// Backend of the text search over addresses, tokens and dapps.
+///
+/// The database stays the source of truth: external backends only find the keys
+/// of the matching entities, which are then loaded from the database.
+/// Postgres is searched until the external backend is filled with the existing data.
+#[derive(Clone, Default)]
+pub enum SearchBackend {
+    #[default]
+    Postgres,
+    OpenSearch(OpenSearchBackend),
+}
+
+impl SearchBackend {
+    pub async fn search_addresses(
+        &self,
+        db: &DatabaseConnection,
+        q: &str,
+        chain_id: Option<ChainId>,
+        page_token: Option<(AddressAlloy, ChainId)>,
+        limit: u64,
+    ) -> Result<(Vec<Address>, Option<(AddressAlloy, ChainId)>), ServiceError> {
+        match self {
+            Self::OpenSearch(backend) if backend.is_ready() => {
+                backend
+                    .search_addresses(db, q, chain_id, page_token, limit)
+                    .await
+            }
+            _ => addresses::search_by_query_paginated(db, q, chain_id, page_token, limit).await,
+        }
+    }
+
+    /// Fuzzy search of the tokens by name or symbol, ordered by the relevance
+    pub async fn search_tokens(
+        &self,
+        db: &DatabaseConnection,
+        q: &str,
+        chain_ids: &[ChainId],
+        limit: u64,
+    ) -> Result<Vec<Address>, ServiceError> {
+        match self {
+            Self::OpenSearch(backend) if backend.is_ready() => {
+                backend.search_tokens(db, q, chain_ids, limit).await
+            }
+            _ => addresses::search_tokens(db, q, chain_ids, limit).await,
+        }
+    }
+
+    pub async fn search_dapps(
+        &self,
+        db: &DatabaseConnection,
+        q: Option<&str>,
+        chain_id: Option<ChainId>,
+        category: Option<&str>,
+        page_token: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<DirectoryDapp>, Option<String>), ServiceError> {
+        match self {
+            Self::OpenSearch(backend) if backend.is_ready() => {
+                backend
+                    .search_dapps(db, q, chain_id, category, page_token, limit)
+                    .await
+            }
+            _ => dapps::list(db, q, chain_id, category, page_token, limit).await,
+        }
+    }
+
+    /// Indexes the imported addresses. Postgres search reads the tables directly, so it is a no-op there.
+    pub async fn index_addresses(&self, addresses: &[Address]) -> Result<(), ServiceError> {
+        match self {
+            Self::Postgres => Ok(()),
+            Self::OpenSearch(backend) => backend.index_addresses(addresses).await,
+        }
+    }
+
+    /// Replaces the indexed dapps with the imported directory
+    pub async fn index_dapps(&self, dapps: &[DirectoryDapp]) -> Result<(), ServiceError> {
+        match self {
+            Self::Postgres => Ok(()),
+            Self::OpenSearch(backend) => backend.replace_dapps(dapps).await,
+        }
+    }
+}
@@ -0,0 +1,879 @@
+use crate::{
+    clients::opensearch::{
+        Bulk, CreateIndex, DeleteByQuery, GetDocument, GetIndex, IndexDocument, Search,
+    },
+    error::{ParseError, ServiceError},
+    repository::{addresses, dapps},
+    types::{addresses::Address, dapp_directory::DirectoryDapp, ChainId},
+};
+use alloy_primitives::{hex, Address as AddressAlloy};
+use anyhow::{anyhow, Context};
+use api_client_framework::HttpApiClient;
+use sea_orm::DatabaseConnection;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+const BULK_BATCH_SIZE: usize = 1000;
+const REINDEX_BATCH_SIZE: u64 = 1000;
+/// Failed address writes kept for the retry, the oldest ones are dropped above the limit
+const MAX_PENDING_ADDRESSES: usize = 100_000;
+const REINDEX_STATE_ID: &str = "reindex";
+
+/// Search backend on top of the OpenSearch (or Elasticsearch) indices.
+/// Addresses (including tokens) and dapps are kept in separate indices named after the `index_prefix`.
+///
+/// The backend is not used for the search until the indices are filled with the data
+/// imported before it was enabled, see [`Self::run_sync`].
+#[derive(Clone)]
+pub struct OpenSearchBackend {
+    client: HttpApiClient,
+    addresses_index: String,
+    dapps_index: String,
+    state_index: String,
+    ready: Arc<AtomicBool>,
+    pending: Arc<Mutex<PendingWrites>>,
+}
+
+/// Writes which failed to be indexed and are retried by [`OpenSearchBackend::run_sync`]
+#[derive(Default)]
+struct PendingWrites {
+    /// Documents keyed by the id, so that only the latest version is retried
+    addresses: HashMap<String, AddressDocument>,
+    /// Order of the ids in `addresses`, used to drop the oldest documents
+    address_ids: VecDeque<String>,
+    dapps: Option<Vec<DirectoryDapp>>,
+}
+
+impl PendingWrites {
+    fn push_addresses(&mut self, documents: Vec<AddressDocument>) {
+        for document in documents {
+            let id = document.id();
+            if self.addresses.insert(id.clone(), document).is_none() {
+                self.address_ids.push_back(id);
+            }
+        }
+        while self.address_ids.len() > MAX_PENDING_ADDRESSES {
+            if let Some(id) = self.address_ids.pop_front() {
+                self.addresses.remove(&id);
+                tracing::warn!(id, "dropped pending address write");
+            }
+        }
+    }
+
+    fn take_addresses(&mut self) -> Vec<AddressDocument> {
+        self.address_ids.clear();
+        self.addresses.drain().map(|(_, d)| d).collect()
+    }
+}
+
+/// Progress of filling the indices with the data stored in the database,
+/// so that an interrupted reindex is resumed instead of started over
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct ReindexState {
+    completed: bool,
+    /// Next page of the addresses to index, as `(hash, chain_id)`
+    addresses_page_token: Option<(String, ChainId)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct AddressDocument {
+    /// Lowercase hex without the `0x` prefix, so that it can be searched by prefix
+    hash: String,
+    chain_id: ChainId,
+    /// Documents are paginated in the same order as the database rows, i.e. by `(hash, chain_id)`
+    key: String,
+    contract_name: Option<String>,
+    ens_name: Option<String>,
+    token_name: Option<String>,
+    token_symbol: Option<String>,
+    is_token: bool,
+}
+
+impl AddressDocument {
+    fn id(&self) -> String {
+        format!("{}:{}", self.chain_id, self.hash)
+    }
+
+    fn address_key(&self) -> Result<(AddressAlloy, ChainId), ParseError> {
+        Ok((self.hash.parse()?, self.chain_id))
+    }
+}
+
+impl From<&Address> for AddressDocument {
+    fn from(v: &Address) -> Self {
+        Self {
+            hash: hex::encode(v.hash),
+            chain_id: v.chain_id,
+            key: address_key(&v.hash, v.chain_id),
+            contract_name: v.contract_name.clone(),
+            ens_name: v.ens_name.clone(),
+            token_name: v.token_name.clone(),
+            token_symbol: v.token_symbol.clone(),
+            is_token: v.is_token,
+        }
+    }
+}
+
+fn address_key(hash: &AddressAlloy, chain_id: ChainId) -> String {
+    // chain id is padded, so that the lexicographical order matches the numeric one
+    format!("{}:{:020}", hex::encode(hash), chain_id)
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct DappDocument {
+    id: String,
+    name: String,
+    description: String,
+    categories: Vec<String>,
+    chain_ids: Vec<ChainId>,
+}
+
+impl From<&DirectoryDapp> for DappDocument {
+    fn from(v: &DirectoryDapp) -> Self {
+        Self {
+            id: v.id.clone(),
+            name: v.name.clone(),
+            description: v.description.clone(),
+            categories: v.categories.clone(),
+            chain_ids: v.chains.iter().map(|c| c.chain_id).collect(),
+        }
+    }
+}
+
+fn addresses_mappings() -> serde_json::Value {
+    json!({
+        "properties": {
+            "hash": { "type": "keyword" },
+            "chain_id": { "type": "long" },
+            "key": { "type": "keyword" },
+            "contract_name": { "type": "text" },
+            "ens_name": { "type": "text" },
+            "token_name": { "type": "text" },
+            "token_symbol": { "type": "text" },
+            "is_token": { "type": "boolean" }
+        }
+    })
+}
+
+fn dapps_mappings() -> serde_json::Value {
+    json!({
+        "properties": {
+            "id": { "type": "keyword" },
+            "name": { "type": "text" },
+            "description": { "type": "text" },
+            "categories": { "type": "keyword" },
+            "chain_ids": { "type": "long" }
+        }
+    })
+}
+
+fn addresses_query(
+    q: &str,
+    chain_id: Option<ChainId>,
+    page_token: Option<(AddressAlloy, ChainId)>,
+    limit: u64,
+) -> serde_json::Value {
+    let query = if addresses::hex_regex().is_match(q) {
+        let q = q.to_lowercase();
+        json!({ "prefix": { "hash": q.strip_prefix("0x").unwrap_or(&q) } })
+    } else {
+        json!({
+            "multi_match": {
+                "query": q,
+                "type": "bool_prefix",
+                "operator": "and",
+                "fields": ["contract_name", "ens_name", "token_name"]
+            }
+        })
+    };
+    let mut filter = vec![];
+    if let Some((hash, chain_id)) = page_token {
+        filter.push(json!({ "range": { "key": { "gte": address_key(&hash, chain_id) } } }));
+    }
+    if let Some(chain_id) = chain_id {
+        filter.push(json!({ "term": { "chain_id": chain_id } }));
+    }
+
+    json!({
+        "size": limit + 1,
+        "query": { "bool": { "must": query, "filter": filter } },
+        "sort": [{ "key": "asc" }]
+    })
+}
+
+fn tokens_query(q: &str, chain_ids: &[ChainId], limit: u64) -> serde_json::Value {
+    let mut filter = vec![json!({ "term": { "is_token": true } })];
+    if !chain_ids.is_empty() {
+        filter.push(json!({ "terms": { "chain_id": chain_ids } }));
+    }
+
+    json!({
+        "size": limit,
+        "query": {
+            "bool": {
+                "must": {
+                    "multi_match": {
+                        "query": q,
+                        "fuzziness": "AUTO",
+                        "fields": ["token_name", "token_symbol"]
+                    }
+                },
+                "filter": filter
+            }
+        }
+    })
+}
+
+fn dapps_query(
+    q: Option<&str>,
+    chain_id: Option<ChainId>,
+    category: Option<&str>,
+    page_token: Option<String>,
+    limit: u64,
+) -> serde_json::Value {
+    let query = match q.map(str::trim).filter(|q| !q.is_empty()) {
+        Some(q) => json!({
+            "multi_match": {
+                "query": q,
+                "fuzziness": "AUTO",
+                "fields": ["name", "description"]
+            }
+        }),
+        None => json!({ "match_all": {} }),
+    };
+    let mut filter = vec![];
+    if let Some(page_token) = page_token {
+        filter.push(json!({ "range": { "id": { "gte": page_token } } }));
+    }
+    if let Some(chain_id) = chain_id {
+        filter.push(json!({ "term": { "chain_ids": chain_id } }));
+    }
+    if let Some(category) = category.map(str::trim).filter(|c| !c.is_empty()) {
+        filter.push(json!({ "term": { "categories": category.to_lowercase() } }));
+    }
+
+    json!({
+        "size": limit + 1,
+        "query": { "bool": { "must": query, "filter": filter } },
+        "sort": [{ "id": "asc" }]
+    })
+}
+
+impl OpenSearchBackend {
+    pub fn new(client: HttpApiClient, index_prefix: &str) -> Self {
+        Self {
+            client,
+            addresses_index: format!("{index_prefix}_addresses"),
+            dapps_index: format!("{index_prefix}_dapps"),
+            state_index: format!("{index_prefix}_state"),
+            ready: Default::default(),
+            pending: Default::default(),
+        }
+    }
+
+    /// Whether the indices contain all the data imported before the backend was enabled
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Creates the missing indices. Returns `true` if any of the search indices has been created,
+    /// in which case the existing data is indexed from scratch by [`Self::run_sync`].
+    pub async fn ensure_indices(&self) -> Result<bool, ServiceError> {
+        let addresses_created = self
+            .ensure_index(&self.addresses_index, addresses_mappings())
+            .await?;
+        let dapps_created = self
+            .ensure_index(&self.dapps_index, dapps_mappings())
+            .await?;
+        self.ensure_index(&self.state_index, json!({ "enabled": false }))
+            .await?;
+        Ok(addresses_created || dapps_created)
+    }
+
+    /// Fills the indices with the data stored in the database, resuming the interrupted reindex
+    /// (unless the indices have been just created), then retries the failed writes every `retry_interval`.
+    /// The backend is used for the search once the reindex is completed.
+    pub async fn run_sync(
+        self,
+        db: DatabaseConnection,
+        indices_created: bool,
+        retry_interval: Duration,
+    ) {
+        let mut state = if indices_created {
+            ReindexState::default()
+        } else {
+            match self.load_reindex_state().await {
+                Ok(state) => state.unwrap_or_default(),
+                Err(err) => {
+                    tracing::error!(error = ?err, "failed to load reindex state, starting over");
+                    ReindexState::default()
+                }
+            }
+        };
+
+        while !state.completed {
+            match self.reindex(&db, &mut state).await {
+                Ok(()) => tracing::info!("search indices filled"),
+                Err(err) => {
+                    tracing::error!(error = ?err, "failed to fill search indices, retrying");
+                    tokio::time::sleep(retry_interval).await;
+                }
+            }
+        }
+        self.ready.store(true, Ordering::Release);
+
+        let mut interval = tokio::time::interval(retry_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.retry_pending_writes().await {
+                tracing::error!(error = ?err, "failed to retry search index writes");
+            }
+        }
+    }
+
+    /// Indexes all the addresses and dapps stored in the database,
+    /// saving the progress after each batch of the addresses
+    async fn reindex(
+        &self,
+        db: &DatabaseConnection,
+        state: &mut ReindexState,
+    ) -> Result<(), ServiceError> {
+        while !state.completed {
+            let page_token = state
+                .addresses_page_token
+                .as_ref()
+                .map(|(hash, chain_id)| Ok::<_, ParseError>((hash.parse()?, *chain_id)))
+                .transpose()?;
+            let (addresses, next_page_token) =
+                addresses::list(db, page_token, REINDEX_BATCH_SIZE).await?;
+            self.bulk_index_addresses(addresses.iter().map(AddressDocument::from).collect())
+                .await?;
+            match next_page_token {
+                Some((hash, chain_id)) => {
+                    state.addresses_page_token = Some((hex::encode(hash), chain_id))
+                }
+                None => {
+                    self.bulk_index_dapps(&load_all_dapps(db).await?).await?;
+                    state.completed = true;
+                }
+            }
+            self.save_reindex_state(state).await?;
+        }
+        Ok(())
+    }
+
+    async fn load_reindex_state(&self) -> Result<Option<ReindexState>, ServiceError> {
+        let get_state = GetDocument::<ReindexState>::new(
+            self.state_index.clone(),
+            REINDEX_STATE_ID.to_string(),
+        );
+        match self.client.request(&get_state).await {
+            Ok(res) => Ok(Some(res.source)),
+            Err(api_client_framework::Error::NotFound) => Ok(None),
+            Err(err) => Err(anyhow::Error::new(err)
+                .context("failed to get reindex state")
+                .into()),
+        }
+    }
+
+    async fn save_reindex_state(&self, state: &ReindexState) -> Result<(), ServiceError> {
+        self.client
+            .request(&IndexDocument {
+                index: self.state_index.clone(),
+                id: REINDEX_STATE_ID.to_string(),
+                body: json!(state),
+            })
+            .await
+            .context("failed to save reindex state")?;
+        Ok(())
+    }
+
+    /// Indexes the writes which failed previously, keeping the ones failed again
+    async fn retry_pending_writes(&self) -> Result<(), ServiceError> {
+        let (documents, dapps) = {
+            let mut pending = self.pending.lock().unwrap();
+            (pending.take_addresses(), pending.dapps.take())
+        };
+
+        if !documents.is_empty() {
+            if let Err(err) = self.bulk_index_addresses(documents.clone()).await {
+                let mut pending = self.pending.lock().unwrap();
+                // newer writes made during the retry take precedence
+                let documents = documents
+                    .into_iter()
+                    .filter(|d| !pending.addresses.contains_key(&d.id()))
+                    .collect();
+                pending.push_addresses(documents);
+                return Err(err);
+            }
+        }
+        if let Some(dapps) = dapps {
+            if let Err(err) = self.bulk_index_dapps(&dapps).await {
+                self.pending.lock().unwrap().dapps.get_or_insert(dapps);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn search_addresses(
+        &self,
+        db: &DatabaseConnection,
+        q: &str,
+        chain_id: Option<ChainId>,
+        page_token: Option<(AddressAlloy, ChainId)>,
+        limit: u64,
+    ) -> Result<(Vec<Address>, Option<(AddressAlloy, ChainId)>), ServiceError> {
+        let documents: Vec<AddressDocument> = self
+            .search(
+                &self.addresses_index,
+                addresses_query(q, chain_id, page_token, limit),
+            )
+            .await?;
+        let mut keys = documents
+            .iter()
+            .map(AddressDocument::address_key)
+            .collect::<Result<Vec<_>, _>>()?;
+        let next_page_token = match keys.len() > limit as usize {
+            true => keys.pop(),
+            false => None,
+        };
+
+        Ok((load_addresses(db, &keys).await?, next_page_token))
+    }
+
+    pub async fn search_tokens(
+        &self,
+        db: &DatabaseConnection,
+        q: &str,
+        chain_ids: &[ChainId],
+        limit: u64,
+    ) -> Result<Vec<Address>, ServiceError> {
+        let q = q.trim();
+        if q.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let documents: Vec<AddressDocument> = self
+            .search(&self.addresses_index, tokens_query(q, chain_ids, limit))
+            .await?;
+        let keys = documents
+            .iter()
+            .map(AddressDocument::address_key)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        load_addresses(db, &keys).await
+    }
+
+    pub async fn search_dapps(
+        &self,
+        db: &DatabaseConnection,
+        q: Option<&str>,
+        chain_id: Option<ChainId>,
+        category: Option<&str>,
+        page_token: Option<String>,
+        limit: u64,
+    ) -> Result<(Vec<DirectoryDapp>, Option<String>), ServiceError> {
+        let documents: Vec<DappDocument> = self
+            .search(
+                &self.dapps_index,
+                dapps_query(q, chain_id, category, page_token, limit),
+            )
+            .await?;
+        let mut ids = documents.into_iter().map(|d| d.id).collect::<Vec<_>>();
+        let next_page_token = match ids.len() > limit as usize {
+            true => ids.pop(),
+            false => None,
+        };
+
+        let mut dapps = dapps::find_many(db, &ids)
+            .await?
+            .into_iter()
+            .map(|d| (d.id.clone(), d))
+            .collect::<HashMap<_, _>>();
+        let dapps = ids.iter().filter_map(|id| dapps.remove(id)).collect();
+
+        Ok((dapps, next_page_token))
+    }
+
+    /// Indexes the addresses. Failed writes are retried later.
+    pub async fn index_addresses(&self, addresses: &[Address]) -> Result<(), ServiceError> {
+        let documents = addresses
+            .iter()
+            .map(AddressDocument::from)
+            .collect::<Vec<_>>();
+        if let Err(err) = self.bulk_index_addresses(documents.clone()).await {
+            self.pending.lock().unwrap().push_addresses(documents);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Indexes the dapps and removes the ones missing in `dapps`. Failed writes are retried later.
+    pub async fn replace_dapps(&self, dapps: &[DirectoryDapp]) -> Result<(), ServiceError> {
+        if let Err(err) = self.bulk_index_dapps(dapps).await {
+            self.pending.lock().unwrap().dapps = Some(dapps.to_vec());
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    async fn bulk_index_addresses(
+        &self,
+        documents: Vec<AddressDocument>,
+    ) -> Result<(), ServiceError> {
+        for batch in documents.chunks(BULK_BATCH_SIZE) {
+            let actions = batch.iter().map(|d| (d.id(), json!(d))).collect();
+            self.bulk_index(&self.addresses_index, actions).await?;
+        }
+        Ok(())
+    }
+
+    async fn bulk_index_dapps(&self, dapps: &[DirectoryDapp]) -> Result<(), ServiceError> {
+        for batch in dapps.chunks(BULK_BATCH_SIZE) {
+            let actions = batch
+                .iter()
+                .map(DappDocument::from)
+                .map(|d| (d.id.clone(), json!(d)))
+                .collect::<Vec<_>>();
+            self.bulk_index(&self.dapps_index, actions).await?;
+        }
+
+        let ids = dapps.iter().map(|d| d.id.as_str()).collect::<Vec<_>>();
+        self.client
+            .request(&DeleteByQuery {
+                index: self.dapps_index.clone(),
+                body: json!({
+                    "query": { "bool": { "must_not": { "ids": { "values": ids } } } }
+                }),
+            })
+            .await
+            .context("failed to delete removed dapps")?;
+        Ok(())
+    }
+
+    async fn ensure_index(
+        &self,
+        index: &str,
+        mappings: serde_json::Value,
+    ) -> Result<bool, ServiceError> {
+        let get_index = GetIndex {
+            index: index.to_string(),
+        };
+        match self.client.request(&get_index).await {
+            Ok(_) => Ok(false),
+            Err(api_client_framework::Error::NotFound) => {
+                let create_index = CreateIndex {
+                    index: index.to_string(),
+                    body: json!({ "mappings": mappings }),
+                };
+                self.client
+                    .request(&create_index)
+                    .await
+                    .with_context(|| format!("failed to create index {index}"))?;
+                tracing::info!(index, "search index created");
+                Ok(true)
+            }
+            Err(err) => Err(anyhow::Error::new(err)
+                .context(format!("failed to get index {index}"))
+                .into()),
+        }
+    }
+
+    async fn search<T: DeserializeOwned + Debug>(
+        &self,
+        index: &str,
+        body: serde_json::Value,
+    ) -> Result<Vec<T>, ServiceError> {
+        let res = self
+            .client
+            .request(&Search::<T>::new(index.to_string(), body))
+            .await
+            .with_context(|| format!("failed to search index {index}"))?;
+        Ok(res.hits.hits.into_iter().map(|h| h.source).collect())
+    }
+
+    async fn bulk_index(
+        &self,
+        index: &str,
+        documents: Vec<(String, serde_json::Value)>,
+    ) -> Result<(), ServiceError> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+
+        let mut body = String::new();
+        for (id, document) in documents {
+            body.push_str(&json!({ "index": { "_index": index, "_id": id } }).to_string());
+            body.push('\n');
+            body.push_str(&document.to_string());
+            body.push('\n');
+        }
+
+        let res = self
+            .client
+            .request(&Bulk { body })
+            .await
+            .with_context(|| format!("failed to index documents into {index}"))?;
+        if res.errors {
+            // each item is an object keyed by the action name
+            let error = res.items.iter().find_map(|item| {
+                item.as_object()?
+                    .values()
+                    .next()?
+                    .get("error")
+                    .map(|e| e.to_string())
+            });
+            return Err(anyhow!("failed to index documents into {index}: {error:?}").into());
+        }
+        Ok(())
+    }
+}
+
+async fn load_all_dapps(db: &DatabaseConnection) -> Result<Vec<DirectoryDapp>, ServiceError> {
+    let mut directory = vec![];
+    let mut page_token = None;
+    loop {
+        let (dapps, next_page_token) =
+            dapps::list(db, None, None, None, page_token, REINDEX_BATCH_SIZE).await?;
+        directory.extend(dapps);
+        if next_page_token.is_none() {
+            break;
+        }
+        page_token = next_page_token;
+    }
+    Ok(directory)
+}
+
+/// Loads the addresses from the database preserving the order of the `keys`
+async fn load_addresses(
+    db: &DatabaseConnection,
+    keys: &[(AddressAlloy, ChainId)],
+) -> Result<Vec<Address>, ServiceError> {
+    let mut addresses = addresses::find_many(db, keys)
+        .await?
+        .into_iter()
+        .map(|a| ((a.hash, a.chain_id), a))
+        .collect::<HashMap<_, _>>();
+    Ok(keys
+        .iter()
+        .filter_map(|key| addresses.remove(key))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::token, types::dapp_directory::DirectoryDappChain};
+    use pretty_assertions::assert_eq;
+
+    fn mapped_fields(mappings: serde_json::Value) -> Vec<String> {
+        let mut fields = mappings["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        fields.sort();
+        fields
+    }
+
+    fn document_fields(document: serde_json::Value) -> Vec<String> {
+        let mut fields = document
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        fields.sort();
+        fields
+    }
+
+    #[test]
+    fn address_document_matches_mappings() {
+        let address = token(10, 0xAB, "Token", "TKN");
+        let document = AddressDocument::from(&address);
+        assert_eq!(document.hash, "ab".repeat(20));
+        assert_eq!(document.id(), format!("10:{}", "ab".repeat(20)));
+        assert_eq!(
+            document.key,
+            format!("{}:00000000000000000010", "ab".repeat(20))
+        );
+        assert_eq!(document.address_key().unwrap(), (address.hash, 10));
+        assert_eq!(
+            document_fields(json!(document)),
+            mapped_fields(addresses_mappings())
+        );
+    }
+
+    #[test]
+    fn address_keys_are_ordered_by_chain_id() {
+        let hash = AddressAlloy::repeat_byte(1);
+        assert!(address_key(&hash, 9) < address_key(&hash, 10));
+        assert!(address_key(&hash, i64::MAX) < address_key(&AddressAlloy::repeat_byte(2), 1));
+    }
+
+    #[test]
+    fn dapp_document_matches_mappings() {
+        let dapp = DirectoryDapp {
+            id: "dapp".to_string(),
+            name: "Dapp".to_string(),
+            description: "Description".to_string(),
+            link: "https://dapp.example".to_string(),
+            logo: None,
+            categories: vec!["defi".to_string()],
+            chains: vec![
+                DirectoryDappChain {
+                    chain_id: 1,
+                    contract_addresses: vec![],
+                },
+                DirectoryDappChain {
+                    chain_id: 10,
+                    contract_addresses: vec![],
+                },
+            ],
+        };
+        let document = DappDocument::from(&dapp);
+        assert_eq!(document.chain_ids, vec![1, 10]);
+        assert_eq!(
+            document_fields(json!(document)),
+            mapped_fields(dapps_mappings())
+        );
+    }
+
+    #[test]
+    fn addresses_query_by_hash_prefix() {
+        let hash = AddressAlloy::repeat_byte(1);
+        let query = addresses_query("0xABcd", Some(10), Some((hash, 5)), 20);
+        assert_eq!(
+            query,
+            json!({
+                "size": 21,
+                "query": {
+                    "bool": {
+                        "must": { "prefix": { "hash": "abcd" } },
+                        "filter": [
+                            { "range": { "key": { "gte": address_key(&hash, 5) } } },
+                            { "term": { "chain_id": 10 } }
+                        ]
+                    }
+                },
+                "sort": [{ "key": "asc" }]
+            })
+        );
+    }
+
+    #[test]
+    fn addresses_query_by_name() {
+        let query = addresses_query("vitalik eth", None, None, 50);
+        assert_eq!(
+            query["query"]["bool"]["must"],
+            json!({
+                "multi_match": {
+                    "query": "vitalik eth",
+                    "type": "bool_prefix",
+                    "operator": "and",
+                    "fields": ["contract_name", "ens_name", "token_name"]
+                }
+            })
+        );
+        assert_eq!(query["query"]["bool"]["filter"], json!([]));
+    }
+
+    #[test]
+    fn tokens_query_filters_by_chain_ids() {
+        let query = tokens_query("usdc", &[1, 10], 100);
+        assert_eq!(query["size"], json!(100));
+        assert_eq!(
+            query["query"]["bool"]["filter"],
+            json!([
+                { "term": { "is_token": true } },
+                { "terms": { "chain_id": [1, 10] } }
+            ])
+        );
+
+        let query = tokens_query("usdc", &[], 100);
+        assert_eq!(
+            query["query"]["bool"]["filter"],
+            json!([{ "term": { "is_token": true } }])
+        );
+    }
+
+    #[test]
+    fn dapps_query_filters() {
+        let query = dapps_query(
+            Some(" "),
+            Some(1),
+            Some(" DeFi "),
+            Some("b".to_string()),
+            10,
+        );
+        assert_eq!(query["size"], json!(11));
+        assert_eq!(query["query"]["bool"]["must"], json!({ "match_all": {} }));
+        assert_eq!(
+            query["query"]["bool"]["filter"],
+            json!([
+                { "range": { "id": { "gte": "b" } } },
+                { "term": { "chain_ids": 1 } },
+                { "term": { "categories": "defi" } }
+            ])
+        );
+
+        let query = dapps_query(Some("swap"), None, None, None, 10);
+        assert_eq!(
+            query["query"]["bool"]["must"]["multi_match"]["query"],
+            json!("swap")
+        );
+        assert_eq!(query["query"]["bool"]["filter"], json!([]));
+    }
+
+    #[test]
+    fn pending_writes_keep_latest_documents() {
+        let mut pending = PendingWrites::default();
+        let mut address = token(1, 1, "Old", "OLD");
+        pending.push_addresses(vec![AddressDocument::from(&address)]);
+        address.token_name = Some("New".to_string());
+        pending.push_addresses(vec![
+            AddressDocument::from(&address),
+            AddressDocument::from(&token(1, 2, "Other", "OTH")),
+        ]);
+
+        let mut documents = pending.take_addresses();
+        documents.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(
+            documents
+                .iter()
+                .map(|d| d.token_name.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("New"), Some("Other")]
+        );
+        assert!(pending.take_addresses().is_empty());
+    }
+
+    #[test]
+    fn reindex_state_roundtrip() {
+        let state = ReindexState {
+            completed: false,
+            addresses_page_token: Some(("ab".repeat(20), 10)),
+        };
+        let value = json!(state);
+        assert_eq!(
+            value,
+            json!({ "completed": false, "addresses_page_token": ["ab".repeat(20), 10] })
+        );
+        assert_eq!(
+            serde_json::from_value::<ReindexState>(value).unwrap(),
+            state
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{
+    repository,
+    types::{addresses::Address, chains::Chain},
+    ChainId,
+};
+use blockscout_service_launcher::test_database::TestDbGuard;
+use sea_orm::DatabaseConnection;
+
+pub async fn init_db(test_name: &str) -> TestDbGuard {
+    TestDbGuard::new::<migration::Migrator>(&format!("multichain_aggregator_{test_name}")).await
+}
+
+pub async fn insert_chains(db: &DatabaseConnection, chain_ids: &[ChainId]) {
+    let chains = chain_ids
+        .iter()
+        .map(|id| Chain {
+            id: *id,
+            explorer_url: None,
+            icon_url: None,
+        })
+        .collect();
+    repository::chains::upsert_many(db, chains).await.unwrap();
+}
+
+pub fn token(chain_id: ChainId, hash_byte: u8, name: &str, symbol: &str) -> Address {
+    Address {
+        chain_id,
+        hash: alloy_primitives::Address::repeat_byte(hash_byte),
+        ens_name: None,
+        contract_name: None,
+        token_name: Some(name.to_string()),
+        token_type: None,
+        is_contract: true,
+        is_verified_contract: false,
+        is_token: true,
+        token_symbol: Some(symbol.to_string()),
+        holders_count: None,
+    }
+}
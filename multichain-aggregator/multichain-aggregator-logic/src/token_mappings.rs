@@ -0,0 +1,249 @@
+use crate::{
+    clients::token_list::{GetTokenList, TokenList},
+    error::ServiceError,
+    repository,
+    types::{token_mappings::TokenMapping, ChainId},
+};
+use alloy_primitives::Address as AddressAlloy;
+use api_client_framework::HttpApiClient;
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use std::{collections::BTreeMap, time::Duration};
+
+/// Bridged tokens are usually deployed from the Ethereum ones,
+/// so the Ethereum token is preferred as the canonical one.
+const ETHEREUM_CHAIN_ID: ChainId = 1;
+
+type TokenKey = (ChainId, AddressAlloy);
+
+/// Periodically imports the token mappings from the token lists
+pub async fn run_token_lists_import(
+    db: DatabaseConnection,
+    clients: Vec<HttpApiClient>,
+    interval: Duration,
+) {
+    let mut interval = tokio::time::interval(interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        match import_token_lists(&db, &clients).await {
+            Ok(count) => tracing::info!(count, "token mappings imported"),
+            Err(err) => tracing::error!(error = ?err, "failed to import token mappings"),
+        }
+    }
+}
+
+/// Fetches the token lists and stores the mappings of the bridged tokens to the canonical ones.
+///
+/// Token lists link the representations of the same token via `bridgeInfo` extension in both directions,
+/// so the tokens linked directly or through other tokens are grouped together,
+/// and a single canonical token is chosen for every group.
+/// The stored mappings are replaced with the imported ones, unless some of the lists failed to load,
+/// in which case the mappings are only added or updated. Returns the number of imported mappings.
+pub async fn import_token_lists(
+    db: &DatabaseConnection,
+    clients: &[HttpApiClient],
+) -> Result<usize, ServiceError> {
+    let mut links = vec![];
+    let mut all_loaded = true;
+    for client in clients {
+        match client.request(&GetTokenList).await {
+            Ok(list) => links.extend(bridge_links(list)),
+            Err(err) => {
+                tracing::warn!(error = ?err, "failed to fetch token list");
+                all_loaded = false;
+            }
+        }
+    }
+
+    let mappings = canonical_mappings(links);
+    let count = mappings.len();
+    // the mappings of the lists which failed to load would be lost otherwise
+    if all_loaded {
+        let tx = db.begin().await?;
+        repository::token_mappings::replace_all(&tx, mappings).await?;
+        tx.commit().await?;
+    } else {
+        repository::token_mappings::upsert_many(db, mappings).await?;
+    }
+
+    Ok(count)
+}
+
+fn bridge_links(list: TokenList) -> Vec<(TokenKey, TokenKey)> {
+    list.tokens
+        .into_iter()
+        .filter_map(|token| {
+            let bridge_info = token.extensions?.bridge_info;
+            let address = token
+                .address
+                .parse::<AddressAlloy>()
+                .inspect_err(|err| {
+                    tracing::warn!(address = token.address, error = ?err, "skipping invalid token");
+                })
+                .ok()?;
+            Some((token.chain_id, address, bridge_info))
+        })
+        .flat_map(|(chain_id, address, bridge_info)| {
+            bridge_info
+                .into_iter()
+                .filter_map(move |(bridged_chain_id, info)| {
+                    let bridged = bridged_chain_id
+                        .parse::<ChainId>()
+                        .ok()
+                        .zip(info.token_address.parse::<AddressAlloy>().ok());
+                    if bridged.is_none() {
+                        tracing::warn!(
+                            chain_id,
+                            address = address.to_string(),
+                            bridged_chain_id,
+                            bridged_address = info.token_address,
+                            "skipping invalid bridged token"
+                        );
+                    }
+                    Some(((chain_id, address), bridged?))
+                })
+        })
+        .collect()
+}
+
+fn canonical_mappings(links: Vec<(TokenKey, TokenKey)>) -> Vec<TokenMapping> {
+    // disjoint set of the linked tokens, every token points to another token of its group
+    let mut parents: BTreeMap<TokenKey, TokenKey> = BTreeMap::new();
+    fn root(parents: &mut BTreeMap<TokenKey, TokenKey>, key: TokenKey) -> TokenKey {
+        let mut root = key;
+        while let Some(parent) = parents.get(&root).filter(|p| **p != root) {
+            root = *parent;
+        }
+        // compress the path, so that the next lookups are faster
+        let mut current = key;
+        while current != root {
+            let next = parents.insert(current, root).unwrap_or(root);
+            current = next;
+        }
+        root
+    }
+
+    for (a, b) in links {
+        parents.entry(a).or_insert(a);
+        parents.entry(b).or_insert(b);
+        let (root_a, root_b) = (root(&mut parents, a), root(&mut parents, b));
+        if root_a != root_b {
+            parents.insert(root_a, root_b);
+        }
+    }
+
+    let mut groups: BTreeMap<TokenKey, Vec<TokenKey>> = BTreeMap::new();
+    for key in parents.keys().copied().collect::<Vec<_>>() {
+        let root = root(&mut parents, key);
+        groups.entry(root).or_default().push(key);
+    }
+
+    groups
+        .into_values()
+        .flat_map(|members| {
+            let canonical = members
+                .iter()
+                .copied()
+                .min_by_key(|(chain_id, address)| {
+                    (*chain_id != ETHEREUM_CHAIN_ID, *chain_id, *address)
+                })
+                .expect("groups are never empty");
+            members
+                .into_iter()
+                .filter(move |key| *key != canonical)
+                .map(move |(chain_id, address)| TokenMapping {
+                    chain_id,
+                    address,
+                    canonical_chain_id: canonical.0,
+                    canonical_address: canonical.1,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn token(chain_id: ChainId, address: u8) -> TokenKey {
+        (chain_id, AddressAlloy::repeat_byte(address))
+    }
+
+    fn mapping(from: TokenKey, to: TokenKey) -> TokenMapping {
+        TokenMapping {
+            chain_id: from.0,
+            address: from.1,
+            canonical_chain_id: to.0,
+            canonical_address: to.1,
+        }
+    }
+
+    #[test]
+    fn ethereum_token_is_canonical() {
+        let links = vec![
+            (token(1, 1), token(10, 2)),
+            // The same link listed in the opposite direction
+            (token(10, 2), token(1, 1)),
+            (token(100, 3), token(1, 1)),
+        ];
+        assert_eq!(
+            canonical_mappings(links),
+            vec![
+                mapping(token(10, 2), token(1, 1)),
+                mapping(token(100, 3), token(1, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn transitively_linked_tokens_are_grouped() {
+        // 100:3 -> 10:2 -> 56:4 -> 1:1, plus a separate group with no ethereum token
+        let links = vec![
+            (token(100, 3), token(10, 2)),
+            (token(56, 4), token(1, 1)),
+            (token(137, 5), token(10, 6)),
+            (token(10, 2), token(56, 4)),
+        ];
+        assert_eq!(
+            canonical_mappings(links),
+            vec![
+                mapping(token(10, 2), token(1, 1)),
+                mapping(token(56, 4), token(1, 1)),
+                mapping(token(100, 3), token(1, 1)),
+                // The lowest chain id is canonical if the group has no ethereum token
+                mapping(token(137, 5), token(10, 6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_bridged_tokens_are_skipped() {
+        let list: TokenList = serde_json::from_value(serde_json::json!({
+            "tokens": [
+                {
+                    "chainId": 1,
+                    "address": AddressAlloy::repeat_byte(1).to_string(),
+                    "extensions": {
+                        "bridgeInfo": {
+                            "10": { "tokenAddress": AddressAlloy::repeat_byte(2).to_string() },
+                            "op": { "tokenAddress": AddressAlloy::repeat_byte(3).to_string() },
+                            "56": { "tokenAddress": "0x01" }
+                        }
+                    }
+                },
+                {
+                    "chainId": 1,
+                    "address": "invalid",
+                    "extensions": {
+                        "bridgeInfo": {
+                            "10": { "tokenAddress": AddressAlloy::repeat_byte(4).to_string() }
+                        }
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(bridge_links(list), vec![(token(1, 1), token(10, 2))]);
+    }
+}
@@ -13,6 +13,8 @@ pub struct Address {
     pub is_contract: bool,
     pub is_verified_contract: bool,
     pub is_token: bool,
+    pub token_symbol: Option<String>,
+    pub holders_count: Option<u64>,
 }
 
 impl From<Address> for Model {
@@ -29,6 +31,8 @@ impl From<Address> for Model {
             is_token: v.is_token,
             created_at: Default::default(),
             updated_at: Default::default(),
+            token_symbol: v.token_symbol,
+            holders_count: v.holders_count.map(|c| c as i64),
         }
     }
 }
@@ -47,6 +51,8 @@ impl TryFrom<Model> for Address {
             is_contract: v.is_contract,
             is_verified_contract: v.is_verified_contract,
             is_token: v.is_token,
+            token_symbol: v.token_symbol,
+            holders_count: v.holders_count.map(|c| c as u64),
         })
     }
 }
@@ -67,6 +73,8 @@ impl From<Address> for proto::Address {
             is_verified_contract: Some(v.is_verified_contract),
             is_token: Some(v.is_token),
             chain_id: v.chain_id.to_string(),
+            token_symbol: v.token_symbol,
+            holders_count: v.holders_count,
         }
     }
 }
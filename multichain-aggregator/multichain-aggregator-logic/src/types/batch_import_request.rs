@@ -2,6 +2,7 @@ use super::{
     addresses::{proto_token_type_to_db_token_type, Address},
     block_ranges::BlockRange,
     hashes::{proto_hash_type_to_db_hash_type, Hash},
+    nft_holdings::NftHolding,
 };
 use crate::{
     error::{ParseError, ServiceError},
@@ -13,6 +14,7 @@ pub struct BatchImportRequest {
     pub block_ranges: Vec<BlockRange>,
     pub hashes: Vec<Hash>,
     pub addresses: Vec<Address>,
+    pub nft_holdings: Vec<NftHolding>,
 }
 
 impl TryFrom<proto::BatchImportRequest> for BatchImportRequest {
@@ -60,6 +62,25 @@ impl TryFrom<proto::BatchImportRequest> for BatchImportRequest {
                         is_contract: a.is_contract.unwrap_or(false),
                         is_verified_contract: a.is_verified_contract.unwrap_or(false),
                         is_token: a.is_token.unwrap_or(false),
+                        token_symbol: a.token_symbol,
+                        holders_count: a.holders_count,
+                    })
+                })
+                .collect::<Result<Vec<_>, Self::Error>>()?,
+            nft_holdings: value
+                .nft_holdings
+                .into_iter()
+                .map(|h| {
+                    let parse_u256 = |v: &str| {
+                        v.parse::<alloy_primitives::U256>()
+                            .map_err(|e| ParseError::Custom(format!("invalid uint256 {v}: {e}")))
+                    };
+                    Ok(NftHolding {
+                        chain_id,
+                        owner: h.owner.parse().map_err(ParseError::from)?,
+                        token_address: h.token_address.parse().map_err(ParseError::from)?,
+                        token_id: parse_u256(&h.token_id)?,
+                        amount: parse_u256(&h.amount)?,
                     })
                 })
                 .collect::<Result<Vec<_>, Self::Error>>()?,
@@ -0,0 +1,45 @@
+use super::ChainId;
+use crate::proto;
+use entity::block_ranges::Model;
+use sea_orm::prelude::DateTime;
+use std::time::Duration;
+
+/// Latest data imported from the chain
+#[derive(Debug, Clone)]
+pub struct ChainFreshness {
+    pub chain_id: ChainId,
+    pub last_block_number: Option<u64>,
+    pub last_imported_at: Option<DateTime>,
+}
+
+impl ChainFreshness {
+    /// Chains which have never been imported are considered stale as well
+    pub fn is_stale(&self, threshold: Duration) -> bool {
+        match self.last_imported_at {
+            Some(last_imported_at) => (chrono::Utc::now().naive_utc() - last_imported_at)
+                .to_std()
+                .is_ok_and(|elapsed| elapsed > threshold),
+            None => true,
+        }
+    }
+
+    pub fn into_proto(self, threshold: Duration) -> proto::ChainFreshness {
+        let is_stale = self.is_stale(threshold);
+        proto::ChainFreshness {
+            chain_id: self.chain_id.to_string(),
+            last_block_number: self.last_block_number,
+            last_imported_at: self.last_imported_at.map(|t| t.and_utc().to_rfc3339()),
+            is_stale,
+        }
+    }
+}
+
+impl From<Model> for ChainFreshness {
+    fn from(v: Model) -> Self {
+        Self {
+            chain_id: v.chain_id,
+            last_block_number: Some(v.max_block_number as u64),
+            last_imported_at: Some(v.updated_at),
+        }
+    }
+}
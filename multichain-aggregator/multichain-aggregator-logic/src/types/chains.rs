@@ -1,4 +1,5 @@
 use super::ChainId;
+use crate::proto;
 use blockscout_chains::BlockscoutChainData;
 use entity::chains::Model;
 
@@ -17,6 +18,17 @@ impl From<Chain> for Model {
             icon_url: v.icon_url,
             created_at: Default::default(),
             updated_at: Default::default(),
+            is_active: true,
+        }
+    }
+}
+
+impl From<Model> for Chain {
+    fn from(v: Model) -> Self {
+        Self {
+            id: v.id,
+            explorer_url: v.explorer_url,
+            icon_url: v.icon_url,
         }
     }
 }
@@ -30,3 +42,13 @@ impl From<(ChainId, BlockscoutChainData)> for Chain {
         }
     }
 }
+
+impl From<Chain> for proto::Chain {
+    fn from(v: Chain) -> Self {
+        Self {
+            id: v.id.to_string(),
+            explorer_url: v.explorer_url,
+            icon_url: v.icon_url,
+        }
+    }
+}
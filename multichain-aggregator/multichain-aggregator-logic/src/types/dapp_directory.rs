@@ -0,0 +1,162 @@
+use super::ChainId;
+use crate::{clients::dapp_directory::CatalogDapp, error::ParseError, proto};
+use entity::{dapp_chains, dapps};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Dapp imported from the dapp catalog
+#[derive(Debug, Clone)]
+pub struct DirectoryDapp {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub link: String,
+    pub logo: Option<String>,
+    pub categories: Vec<String>,
+    pub chains: Vec<DirectoryDappChain>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DirectoryDappChain {
+    pub chain_id: ChainId,
+    pub contract_addresses: Vec<alloy_primitives::Address>,
+}
+
+impl DirectoryDapp {
+    pub fn into_models(self) -> (dapps::Model, Vec<dapp_chains::Model>) {
+        let chains = self
+            .chains
+            .into_iter()
+            .map(|c| dapp_chains::Model {
+                dapp_id: self.id.clone(),
+                chain_id: c.chain_id,
+                contract_addresses: serde_json::json!(c
+                    .contract_addresses
+                    .iter()
+                    .map(|a| a.to_checksum(None))
+                    .collect::<Vec<_>>()),
+                created_at: Default::default(),
+                updated_at: Default::default(),
+            })
+            .collect();
+        let dapp = dapps::Model {
+            id: self.id,
+            name: self.name,
+            description: self.description,
+            link: self.link,
+            logo: self.logo,
+            categories: serde_json::json!(self.categories),
+            created_at: Default::default(),
+            updated_at: Default::default(),
+        };
+        (dapp, chains)
+    }
+}
+
+impl TryFrom<CatalogDapp> for DirectoryDapp {
+    type Error = ParseError;
+
+    fn try_from(v: CatalogDapp) -> Result<Self, Self::Error> {
+        // the same chain may be listed several times, merge its contract addresses
+        let mut chains: BTreeMap<ChainId, BTreeSet<alloy_primitives::Address>> = BTreeMap::new();
+        for c in v.chains {
+            let contract_addresses = c
+                .contract_addresses
+                .iter()
+                .map(|a| a.parse::<alloy_primitives::Address>())
+                .collect::<Result<Vec<_>, _>>()?;
+            chains
+                .entry(c.chain_id)
+                .or_default()
+                .extend(contract_addresses);
+        }
+        let chains = chains
+            .into_iter()
+            .map(|(chain_id, contract_addresses)| DirectoryDappChain {
+                chain_id,
+                contract_addresses: contract_addresses.into_iter().collect(),
+            })
+            .collect();
+        // categories are matched exactly on filtering, so keep them normalized
+        let categories = v
+            .categories
+            .iter()
+            .map(|c| c.trim().to_lowercase())
+            .filter(|c| !c.is_empty())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        Ok(Self {
+            id: v.id,
+            name: v.name,
+            description: v.description,
+            link: v.link,
+            logo: v.logo,
+            categories,
+            chains,
+        })
+    }
+}
+
+impl TryFrom<(dapps::Model, Vec<dapp_chains::Model>)> for DirectoryDapp {
+    type Error = ParseError;
+
+    fn try_from(
+        (dapp, chains): (dapps::Model, Vec<dapp_chains::Model>),
+    ) -> Result<Self, Self::Error> {
+        let chains = chains
+            .into_iter()
+            .map(|c| {
+                let contract_addresses =
+                    serde_json::from_value::<Vec<String>>(c.contract_addresses)
+                        .map_err(|e| {
+                            ParseError::Custom(format!("invalid contract addresses: {e}"))
+                        })?
+                        .iter()
+                        .map(|a| a.parse::<alloy_primitives::Address>())
+                        .collect::<Result<Vec<_>, _>>()?;
+                Ok(DirectoryDappChain {
+                    chain_id: c.chain_id,
+                    contract_addresses,
+                })
+            })
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        let categories = serde_json::from_value::<Vec<String>>(dapp.categories)
+            .map_err(|e| ParseError::Custom(format!("invalid categories: {e}")))?;
+
+        Ok(Self {
+            id: dapp.id,
+            name: dapp.name,
+            description: dapp.description,
+            link: dapp.link,
+            logo: dapp.logo,
+            categories,
+            chains,
+        })
+    }
+}
+
+impl From<DirectoryDapp> for proto::Dapp {
+    fn from(v: DirectoryDapp) -> Self {
+        Self {
+            id: v.id,
+            name: v.name,
+            description: v.description,
+            link: v.link,
+            logo: v.logo,
+            categories: v.categories,
+            chains: v
+                .chains
+                .into_iter()
+                .map(|c| proto::dapp::DappChain {
+                    chain_id: c.chain_id.to_string(),
+                    contract_addresses: c
+                        .contract_addresses
+                        .into_iter()
+                        .map(|a| a.to_checksum(None))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use super::ChainId;
+use crate::{clients::bens::Domain, error::ParseError, proto};
+
+/// Domain name resolved through BENS
+#[derive(Debug, Clone)]
+pub struct ResolvedDomain {
+    pub name: String,
+    pub address: alloy_primitives::Address,
+    pub protocol_id: Option<String>,
+    /// Chains of the cluster where the resolved address is known
+    pub chain_ids: Vec<ChainId>,
+}
+
+impl TryFrom<Domain> for ResolvedDomain {
+    type Error = ParseError;
+
+    fn try_from(v: Domain) -> Result<Self, Self::Error> {
+        let address = v
+            .resolved_address
+            .ok_or_else(|| ParseError::Custom("domain is not resolved".to_string()))?
+            .hash
+            .parse()?;
+        Ok(Self {
+            name: v.name,
+            address,
+            protocol_id: v.protocol.map(|p| p.id),
+            chain_ids: vec![],
+        })
+    }
+}
+
+impl From<ResolvedDomain> for proto::quick_search_response::ResolvedDomain {
+    fn from(v: ResolvedDomain) -> Self {
+        Self {
+            name: v.name,
+            address: v.address.to_checksum(None),
+            protocol_id: v.protocol_id,
+            chain_ids: v.chain_ids.into_iter().map(|c| c.to_string()).collect(),
+        }
+    }
+}
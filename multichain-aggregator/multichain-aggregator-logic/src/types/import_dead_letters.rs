@@ -0,0 +1,47 @@
+use super::ChainId;
+use crate::{error::ParseError, proto};
+use entity::import_dead_letters::Model;
+use sea_orm::prelude::DateTime;
+
+/// Batch import request rejected by the validation
+#[derive(Debug, Clone)]
+pub struct ImportDeadLetter {
+    pub id: i64,
+    pub chain_id: ChainId,
+    pub payload: proto::BatchImportRequest,
+    pub error: String,
+    pub replay_attempts: i32,
+    pub replayed_at: Option<DateTime>,
+    pub created_at: DateTime,
+}
+
+impl TryFrom<Model> for ImportDeadLetter {
+    type Error = ParseError;
+
+    fn try_from(v: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id,
+            chain_id: v.chain_id,
+            payload: serde_json::from_value(v.payload)
+                .map_err(|e| ParseError::Custom(format!("invalid payload: {e}")))?,
+            error: v.error,
+            replay_attempts: v.replay_attempts,
+            replayed_at: v.replayed_at,
+            created_at: v.created_at,
+        })
+    }
+}
+
+impl From<ImportDeadLetter> for proto::ImportDeadLetter {
+    fn from(v: ImportDeadLetter) -> Self {
+        Self {
+            id: v.id.to_string(),
+            chain_id: v.chain_id.to_string(),
+            payload: Some(v.payload),
+            error: v.error,
+            replay_attempts: v.replay_attempts as u32,
+            created_at: v.created_at.and_utc().to_rfc3339(),
+            replayed_at: v.replayed_at.map(|t| t.and_utc().to_rfc3339()),
+        }
+    }
+}
@@ -2,9 +2,15 @@ pub mod addresses;
 pub mod api_keys;
 pub mod batch_import_request;
 pub mod block_ranges;
+pub mod chain_freshness;
 pub mod chains;
 pub mod dapp;
+pub mod dapp_directory;
+pub mod domains;
 pub mod hashes;
+pub mod import_dead_letters;
+pub mod nft_holdings;
 pub mod search_results;
 pub mod token_info;
+pub mod token_mappings;
 pub type ChainId = i64;
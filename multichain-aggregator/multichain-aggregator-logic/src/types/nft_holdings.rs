@@ -0,0 +1,101 @@
+use super::{addresses::db_token_type_to_proto_token_type, ChainId};
+use crate::{error::ParseError, proto};
+use alloy_primitives::{Address, U256};
+use entity::{nft_holdings::Model, sea_orm_active_enums as db_enum};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// Amount of the token owned by the address. Zero amount means the token is no longer owned.
+#[derive(Debug, Clone)]
+pub struct NftHolding {
+    pub chain_id: ChainId,
+    pub owner: Address,
+    pub token_address: Address,
+    pub token_id: U256,
+    pub amount: U256,
+}
+
+impl From<NftHolding> for Model {
+    fn from(v: NftHolding) -> Self {
+        Self {
+            owner: v.owner.to_vec(),
+            chain_id: v.chain_id,
+            token_address: v.token_address.to_vec(),
+            token_id: v.token_id.to_be_bytes_vec(),
+            amount: v.amount.to_be_bytes_vec(),
+            created_at: Default::default(),
+            updated_at: Default::default(),
+        }
+    }
+}
+
+/// Tokens of the collection owned by the address
+#[derive(Debug, Clone)]
+pub struct NftCollectionHolding {
+    pub chain_id: ChainId,
+    pub token_address: Address,
+    pub token_name: Option<String>,
+    pub token_symbol: Option<String>,
+    pub token_type: Option<db_enum::TokenType>,
+    /// Total number of the owned token ids, `tokens` may be truncated
+    pub tokens_count: u64,
+    /// Owned token ids with amounts, ordered by token id
+    pub tokens: Vec<(U256, U256)>,
+}
+
+impl From<NftCollectionHolding> for proto::NftCollectionHolding {
+    fn from(v: NftCollectionHolding) -> Self {
+        Self {
+            chain_id: v.chain_id.to_string(),
+            token_address: v.token_address.to_string(),
+            token_name: v.token_name,
+            token_symbol: v.token_symbol,
+            token_type: v
+                .token_type
+                .map(db_token_type_to_proto_token_type)
+                .unwrap_or_default()
+                .into(),
+            tokens_count: v.tokens_count,
+            tokens: v
+                .tokens
+                .into_iter()
+                .map(|(token_id, amount)| proto::nft_collection_holding::Token {
+                    token_id: token_id.to_string(),
+                    amount: amount.to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Last returned collection of each chain.
+/// Chains are paginated independently, so that the results of all chains are interleaved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NftHoldingsPageToken(pub BTreeMap<ChainId, Address>);
+
+impl fmt::Display for NftHoldingsPageToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cursors = self
+            .0
+            .iter()
+            .map(|(chain_id, address)| format!("{chain_id}:{address}"))
+            .collect::<Vec<_>>();
+        write!(f, "{}", cursors.join(","))
+    }
+}
+
+impl FromStr for NftHoldingsPageToken {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|c| !c.is_empty())
+            .map(|cursor| {
+                let (chain_id, address) = cursor
+                    .split_once(':')
+                    .ok_or_else(|| ParseError::Custom(format!("invalid cursor: {cursor}")))?;
+                Ok((chain_id.parse()?, address.parse()?))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
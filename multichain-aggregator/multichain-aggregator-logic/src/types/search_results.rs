@@ -1,8 +1,8 @@
 use crate::{
     proto,
     types::{
-        addresses::Address, block_ranges::ChainBlockNumber, dapp::MarketplaceDapp, hashes::Hash,
-        token_info::Token, ChainId,
+        addresses::Address, block_ranges::ChainBlockNumber, dapp::MarketplaceDapp,
+        domains::ResolvedDomain, hashes::Hash, token_info::Token, ChainId,
     },
 };
 use std::collections::BTreeMap;
@@ -16,6 +16,7 @@ pub struct ChainSearchResult {
     pub block_numbers: Vec<ChainBlockNumber>,
     pub dapps: Vec<MarketplaceDapp>,
     pub tokens: Vec<Token>,
+    pub is_stale: bool,
 }
 
 impl From<ChainSearchResult> for proto::quick_search_response::ChainSearchResult {
@@ -28,6 +29,7 @@ impl From<ChainSearchResult> for proto::quick_search_response::ChainSearchResult
             block_numbers: v.block_numbers.into_iter().map(|b| b.into()).collect(),
             dapps: v.dapps.into_iter().map(|d| d.into()).collect(),
             tokens: v.tokens.into_iter().map(|t| t.into()).collect(),
+            is_stale: v.is_stale,
         }
     }
 }
@@ -35,6 +37,7 @@ impl From<ChainSearchResult> for proto::quick_search_response::ChainSearchResult
 #[derive(Default, Debug)]
 pub struct SearchResults {
     pub items: BTreeMap<ChainId, ChainSearchResult>,
+    pub domain: Option<ResolvedDomain>,
 }
 
 impl From<SearchResults> for proto::QuickSearchResponse {
@@ -45,6 +48,7 @@ impl From<SearchResults> for proto::QuickSearchResponse {
                 .into_iter()
                 .map(|(k, v)| (k.to_string(), v.into()))
                 .collect(),
+            domain: v.domain.map(|d| d.into()),
         }
     }
 }
@@ -1,5 +1,6 @@
-use super::ChainId;
+use super::{addresses::Address, ChainId};
 use crate::{clients, error::ParseError, proto};
+use std::collections::BTreeSet;
 
 #[derive(Debug)]
 pub struct Token {
@@ -8,6 +9,76 @@ pub struct Token {
     pub name: String,
     pub symbol: String,
     pub chain_id: ChainId,
+    pub is_verified_contract: Option<bool>,
+    pub holders_count: Option<u64>,
+    /// Bridged representations of the token merged into it
+    pub bridged_tokens: Vec<(ChainId, alloy_primitives::Address)>,
+}
+
+impl Token {
+    /// Fills the token with the data known to the aggregator
+    pub fn enrich(&mut self, address: &Address) {
+        self.is_verified_contract = Some(address.is_verified_contract);
+        self.holders_count = address.holders_count.or(self.holders_count);
+    }
+
+    /// Relevance of the token for the search `query`.
+    ///
+    /// Text match is scored first (exact symbol, exact name, prefix, trigram similarity),
+    /// verified contracts and tokens with more holders are boosted on top of it.
+    pub fn relevance(&self, query: &str) -> f64 {
+        let query = query.trim().to_lowercase();
+        let name = self.name.to_lowercase();
+        let symbol = self.symbol.to_lowercase();
+
+        let text = if symbol == query {
+            1.0
+        } else if name == query {
+            0.9
+        } else if symbol.starts_with(&query) || name.starts_with(&query) {
+            0.7
+        } else {
+            0.6 * trigram_similarity(&query, &name).max(trigram_similarity(&query, &symbol))
+        };
+        let verified = match self.is_verified_contract {
+            Some(true) => 0.2,
+            _ => 0.0,
+        };
+        let holders = self
+            .holders_count
+            .map(|count| ((count as f64 + 1.0).log10() / 6.0).min(1.0) * 0.3)
+            .unwrap_or_default();
+
+        text + verified + holders
+    }
+}
+
+/// Similarity of the strings in the same way as the `pg_trgm` extension computes it:
+/// the number of shared trigrams divided by the number of unique trigrams of both strings
+fn trigram_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (trigrams(a), trigrams(b));
+    let total = a.union(&b).count();
+    if total == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / total as f64
+}
+
+fn trigrams(s: &str) -> BTreeSet<[char; 3]> {
+    s.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .flat_map(|word| {
+            let padded: Vec<char> = "  "
+                .chars()
+                .chain(word.to_lowercase().chars())
+                .chain(" ".chars())
+                .collect();
+            padded
+                .windows(3)
+                .map(|w| [w[0], w[1], w[2]])
+                .collect::<Vec<_>>()
+        })
+        .collect()
 }
 
 impl TryFrom<clients::token_info::TokenInfo> for Token {
@@ -24,6 +95,30 @@ impl TryFrom<clients::token_info::TokenInfo> for Token {
                 .token_symbol
                 .ok_or_else(|| ParseError::Custom("token symbol is required".to_string()))?,
             chain_id: v.chain_id.parse().map_err(ParseError::from)?,
+            is_verified_contract: None,
+            holders_count: None,
+            bridged_tokens: vec![],
+        })
+    }
+}
+
+impl TryFrom<Address> for Token {
+    type Error = ParseError;
+
+    fn try_from(v: Address) -> Result<Self, Self::Error> {
+        Ok(Self {
+            address: v.hash,
+            icon_url: String::new(),
+            name: v
+                .token_name
+                .ok_or_else(|| ParseError::Custom("token name is required".to_string()))?,
+            symbol: v
+                .token_symbol
+                .ok_or_else(|| ParseError::Custom("token symbol is required".to_string()))?,
+            chain_id: v.chain_id,
+            is_verified_contract: Some(v.is_verified_contract),
+            holders_count: v.holders_count,
+            bridged_tokens: vec![],
         })
     }
 }
@@ -36,6 +131,16 @@ impl From<Token> for proto::Token {
             symbol: v.symbol,
             icon_url: v.icon_url,
             chain_id: v.chain_id.to_string(),
+            is_verified_contract: v.is_verified_contract,
+            holders_count: v.holders_count,
+            bridged_tokens: v
+                .bridged_tokens
+                .into_iter()
+                .map(|(chain_id, address)| proto::token::BridgedToken {
+                    chain_id: chain_id.to_string(),
+                    address: address.to_string(),
+                })
+                .collect(),
         }
     }
 }
@@ -0,0 +1,38 @@
+use super::ChainId;
+use crate::error::ParseError;
+use entity::token_mappings::Model;
+
+/// Links the bridged representation of the token to its canonical token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenMapping {
+    pub chain_id: ChainId,
+    pub address: alloy_primitives::Address,
+    pub canonical_chain_id: ChainId,
+    pub canonical_address: alloy_primitives::Address,
+}
+
+impl From<TokenMapping> for Model {
+    fn from(v: TokenMapping) -> Self {
+        Self {
+            chain_id: v.chain_id,
+            address: v.address.to_vec(),
+            canonical_chain_id: v.canonical_chain_id,
+            canonical_address: v.canonical_address.to_vec(),
+            created_at: Default::default(),
+            updated_at: Default::default(),
+        }
+    }
+}
+
+impl TryFrom<Model> for TokenMapping {
+    type Error = ParseError;
+
+    fn try_from(v: Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            chain_id: v.chain_id,
+            address: alloy_primitives::Address::try_from(v.address.as_slice())?,
+            canonical_chain_id: v.canonical_chain_id,
+            canonical_address: alloy_primitives::Address::try_from(v.canonical_address.as_slice())?,
+        })
+    }
+}
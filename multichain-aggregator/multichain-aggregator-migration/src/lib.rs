@@ -2,13 +2,27 @@ pub use sea_orm_migration::prelude::*;
 use sea_orm_migration::sea_orm::{Statement, TransactionTrait};
 
 mod m20220101_000001_initial_tables;
+mod m20241203_000000_add_token_search;
+mod m20241210_000000_add_dapp_directory;
+mod m20241211_000000_add_chains_is_active;
+mod m20241212_000000_add_token_mappings;
+mod m20241213_000000_add_import_dead_letters;
+mod m20241216_000000_add_nft_holdings;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_initial_tables::Migration)]
+        vec![
+            Box::new(m20220101_000001_initial_tables::Migration),
+            Box::new(m20241203_000000_add_token_search::Migration),
+            Box::new(m20241210_000000_add_dapp_directory::Migration),
+            Box::new(m20241211_000000_add_chains_is_active::Migration),
+            Box::new(m20241212_000000_add_token_mappings::Migration),
+            Box::new(m20241213_000000_add_import_dead_letters::Migration),
+            Box::new(m20241216_000000_add_nft_holdings::Migration),
+        ]
     }
 }
 
@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE addresses ADD COLUMN token_symbol text;
+            ALTER TABLE addresses ADD COLUMN holders_count bigint;
+
+            CREATE INDEX addresses_token_name_gin_trgm_idx ON addresses USING GIN (token_name gin_trgm_ops) WHERE is_token;
+            CREATE INDEX addresses_token_symbol_gin_trgm_idx ON addresses USING GIN (token_symbol gin_trgm_ops) WHERE is_token;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX addresses_token_symbol_gin_trgm_idx;
+            DROP INDEX addresses_token_name_gin_trgm_idx;
+
+            ALTER TABLE addresses DROP COLUMN holders_count;
+            ALTER TABLE addresses DROP COLUMN token_symbol;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,57 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE dapps;
+
+            CREATE TABLE dapps (
+              id varchar PRIMARY KEY,
+              name varchar NOT NULL,
+              description varchar NOT NULL,
+              link varchar NOT NULL,
+              logo varchar,
+              categories jsonb NOT NULL DEFAULT '[]',
+              created_at timestamp NOT NULL DEFAULT (now()),
+              updated_at timestamp NOT NULL DEFAULT (now())
+            );
+
+            CREATE INDEX dapps_name_gin_trgm_idx ON dapps USING GIN (name gin_trgm_ops);
+            CREATE INDEX dapps_categories_idx ON dapps USING GIN (categories);
+
+            CREATE TABLE dapp_chains (
+              dapp_id varchar NOT NULL REFERENCES dapps (id) ON DELETE CASCADE,
+              chain_id bigint NOT NULL REFERENCES chains (id),
+              contract_addresses jsonb NOT NULL DEFAULT '[]',
+              created_at timestamp NOT NULL DEFAULT (now()),
+              updated_at timestamp NOT NULL DEFAULT (now()),
+              PRIMARY KEY (dapp_id, chain_id)
+            );
+
+            CREATE INDEX dapp_chains_chain_id_idx ON dapp_chains (chain_id);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE dapp_chains;
+            DROP TABLE dapps;
+
+            CREATE TABLE dapps (
+              chain_id bigint NOT NULL REFERENCES chains (id),
+              name varchar NOT NULL,
+              description varchar NOT NULL,
+              link varchar NOT NULL,
+              created_at timestamp NOT NULL DEFAULT (now()),
+              updated_at timestamp NOT NULL DEFAULT (now()),
+              PRIMARY KEY (chain_id, name)
+            );
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,31 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE token_mappings (
+              chain_id bigint NOT NULL,
+              address bytea NOT NULL,
+              canonical_chain_id bigint NOT NULL,
+              canonical_address bytea NOT NULL,
+              created_at timestamp NOT NULL DEFAULT (now()),
+              updated_at timestamp NOT NULL DEFAULT (now()),
+              PRIMARY KEY (chain_id, address)
+            );
+
+            CREATE INDEX token_mappings_canonical_idx ON token_mappings (canonical_chain_id, canonical_address);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE token_mappings;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE import_dead_letters (
+              id bigint GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+              chain_id bigint NOT NULL REFERENCES chains (id),
+              payload jsonb NOT NULL,
+              error text NOT NULL,
+              replay_attempts integer NOT NULL DEFAULT 0,
+              replayed_at timestamp,
+              created_at timestamp NOT NULL DEFAULT (now()),
+              updated_at timestamp NOT NULL DEFAULT (now())
+            );
+
+            CREATE INDEX import_dead_letters_chain_id_id_idx ON import_dead_letters (chain_id, id);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE import_dead_letters;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,32 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // token ids and amounts are uint256 values stored as 32-byte big-endian,
+        // so that the byte order matches the numeric one
+        let sql = r#"
+            CREATE TABLE nft_holdings (
+              owner bytea NOT NULL,
+              chain_id bigint NOT NULL REFERENCES chains (id),
+              token_address bytea NOT NULL,
+              token_id bytea NOT NULL,
+              amount bytea NOT NULL,
+              created_at timestamp NOT NULL DEFAULT (now()),
+              updated_at timestamp NOT NULL DEFAULT (now()),
+              PRIMARY KEY (owner, chain_id, token_address, token_id)
+            );
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE nft_holdings;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -2,7 +2,7 @@ use env_collector::{run_env_collector_cli, PrefixFilter};
 use multichain_aggregator_server::Settings;
 
 fn main() {
-    run_env_collector_cli::<Settings>(
+    let code = run_env_collector_cli::<Settings>(
         "MULTICHAIN_AGGREGATOR",
         "README.md",
         "multichain-aggregator-server/config/example.toml",
@@ -14,4 +14,5 @@ fn main() {
         ]),
         None,
     );
+    std::process::exit(code);
 }
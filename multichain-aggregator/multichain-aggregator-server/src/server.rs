@@ -4,15 +4,19 @@ use crate::{
         multichain_aggregator_service_actix::route_multichain_aggregator_service,
         multichain_aggregator_service_server::MultichainAggregatorServiceServer,
     },
-    services::{HealthService, MultichainAggregator},
+    services::{HealthService, MultichainAggregator, MultichainAggregatorClients},
     settings::Settings,
 };
 use blockscout_chains::BlockscoutChainsClient;
-use blockscout_service_launcher::{database, launcher, launcher::LaunchSettings};
+use blockscout_service_launcher::{
+    database, launcher,
+    launcher::{GracefulShutdownHandler, LaunchSettings, Probes},
+};
 use migration::Migrator;
 use multichain_aggregator_logic::{
-    clients::{dapp, token_info},
+    clients::{bens, dapp, dapp_directory, opensearch, token_info, token_list},
     repository,
+    search_backend::{OpenSearchBackend, SearchBackend},
 };
 use std::sync::Arc;
 
@@ -62,23 +66,104 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
         .await?
         .into_iter()
         .filter_map(|(id, chain)| {
-            let id = id.parse::<i64>().ok()?;
+            let id = i64::try_from(u64::from(id)).ok()?;
             Some((id, chain).into())
         })
         .collect::<Vec<_>>();
-    repository::chains::upsert_many(&db, blockscout_chains.clone()).await?;
+    repository::chains::upsert_many(&db, blockscout_chains).await?;
+    // Chains may be removed from the cluster, so only the active ones are served
+    let chains = repository::chains::list_active(&db).await?;
+
+    let search_backend = match settings.service.opensearch.clone() {
+        Some(opensearch) => {
+            let client = opensearch::new_client(opensearch.url, opensearch.request_timeout)?;
+            let backend = OpenSearchBackend::new(client, &opensearch.index_prefix);
+            // the indices are filled with the data imported before the backend was enabled,
+            // Postgres is searched until then
+            let indices_created = backend.ensure_indices().await?;
+            tokio::spawn(backend.clone().run_sync(
+                db.clone(),
+                indices_created,
+                opensearch.retry_interval,
+            ));
+            SearchBackend::OpenSearch(backend)
+        }
+        None => SearchBackend::Postgres,
+    };
 
-    let dapp_client = dapp::new_client(settings.service.dapp_client.url)?;
-    let token_info_client = token_info::new_client(settings.service.token_info_client.url)?;
+    let dapp_directory_client = match settings.service.dapp_directory.clone() {
+        Some(dapp_directory) => {
+            let client = dapp_directory::new_client(dapp_directory.url)?;
+            tokio::spawn(multichain_aggregator_logic::run_dapp_directory_import(
+                db.clone(),
+                client.clone(),
+                search_backend.clone(),
+                dapp_directory.refresh_interval,
+            ));
+            Some(client)
+        }
+        None => None,
+    };
+
+    if let Some(token_lists) = settings.service.token_lists.clone() {
+        let clients = token_lists
+            .urls
+            .into_iter()
+            .map(token_list::new_client)
+            .collect::<Result<Vec<_>, _>>()?;
+        tokio::spawn(multichain_aggregator_logic::run_token_lists_import(
+            db.clone(),
+            clients,
+            token_lists.refresh_interval,
+        ));
+    }
+
+    tokio::spawn(multichain_aggregator_logic::run_chain_freshness_metrics(
+        db.clone(),
+        settings.service.stale_chain_threshold,
+        settings.service.chains_reload_interval,
+    ));
+
+    let clients = MultichainAggregatorClients {
+        dapp: dapp::new_client(settings.service.dapp_client.url.clone())?,
+        token_info: token_info::new_client(settings.service.token_info_client.url.clone())?,
+        bens: settings
+            .service
+            .bens_client
+            .as_ref()
+            .map(|bens| {
+                bens::new_client(bens.url.clone(), bens.request_timeout).map(|client| {
+                    bens::BensClient {
+                        client,
+                        chain_id: bens.chain_id,
+                    }
+                })
+            })
+            .transpose()?,
+        dapp_directory: dapp_directory_client,
+    };
 
     let multichain_aggregator = Arc::new(MultichainAggregator::new(
         db,
-        blockscout_chains,
-        dapp_client,
-        token_info_client,
-        settings.service.api,
+        search_backend,
+        chains,
+        clients,
+        &settings.service,
     ));
 
+    tokio::spawn({
+        let multichain_aggregator = multichain_aggregator.clone();
+        let mut interval = tokio::time::interval(settings.service.chains_reload_interval);
+        async move {
+            loop {
+                interval.tick().await;
+                if let Err(err) = multichain_aggregator.reload_chains().await {
+                    tracing::error!(error = ?err, "failed to reload chains");
+                }
+            }
+        }
+    });
+
     let router = Router {
         health,
         multichain_aggregator,
@@ -91,6 +176,9 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
         service_name: SERVICE_NAME.to_string(),
         server: settings.server,
         metrics: settings.metrics,
+        graceful_shutdown: GracefulShutdownHandler::new(),
+        probes: Probes::new(),
+        api_key_store: None,
     };
 
     launcher::launch(&launch_settings, http_router, grpc_router).await
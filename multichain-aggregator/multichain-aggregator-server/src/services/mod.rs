@@ -2,4 +2,4 @@ mod health;
 mod multichain_aggregator;
 
 pub use health::HealthService;
-pub use multichain_aggregator::MultichainAggregator;
+pub use multichain_aggregator::{MultichainAggregator, MultichainAggregatorClients};
@@ -1,51 +1,143 @@
 use crate::{
     proto::{
-        multichain_aggregator_service_server::MultichainAggregatorService, BatchImportRequest,
-        BatchImportResponse, ListAddressesRequest, ListAddressesResponse, Pagination,
-        QuickSearchRequest, QuickSearchResponse,
+        multichain_aggregator_service_server::MultichainAggregatorService, AddChainRequest,
+        AddChainResponse, BatchImportRequest, BatchImportResponse, ListAddressesRequest,
+        ListAddressesResponse, ListChainsRequest, ListChainsResponse, Pagination,
+        QuickSearchRequest, QuickSearchResponse, RemoveChainRequest, RemoveChainResponse,
     },
-    settings::ApiSettings,
+    settings::{ApiSettings, ServiceSettings},
 };
 use api_client_framework::HttpApiClient;
+use blockscout_chains::BlockscoutChainsClient;
 use multichain_aggregator_logic::{
     self as logic,
     api_key_manager::ApiKeyManager,
-    clients::token_info::{SearchTokenInfos, SearchTokenInfosParams},
+    clients::{
+        bens::BensClient,
+        token_info::{SearchTokenInfos, SearchTokenInfosParams},
+    },
     error::ServiceError,
+    search_backend::SearchBackend,
     Chain, Token,
 };
 use multichain_aggregator_proto::blockscout::multichain_aggregator::v1::{
-    ListTokensRequest, ListTokensResponse,
+    Dapp, GetDappRequest, ImportDeadLetter, ListChainsFreshnessRequest,
+    ListChainsFreshnessResponse, ListDappCategoriesRequest, ListDappCategoriesResponse,
+    ListDappsRequest, ListDappsResponse, ListImportDeadLettersRequest,
+    ListImportDeadLettersResponse, ListNftHoldingsRequest, ListNftHoldingsResponse,
+    ListTokensRequest, ListTokensResponse, ReplayImportDeadLetterRequest,
 };
 use sea_orm::DatabaseConnection;
-use std::str::FromStr;
-use tonic::{Request, Response, Status};
+use std::{str::FromStr, sync::RwLock, time::Duration};
+use subtle::ConstantTimeEq;
+use tonic::{metadata::MetadataMap, Request, Response, Status};
+
+const API_KEY_NAME: &str = "x-api-key";
+
+/// Clients of the external services the aggregator proxies the requests to
+pub struct MultichainAggregatorClients {
+    pub dapp: HttpApiClient,
+    pub token_info: HttpApiClient,
+    pub bens: Option<BensClient>,
+    pub dapp_directory: Option<HttpApiClient>,
+}
 
 pub struct MultichainAggregator {
     db: DatabaseConnection,
+    search_backend: SearchBackend,
     api_key_manager: ApiKeyManager,
-    // Cached chains
-    chains: Vec<Chain>,
+    // Cached active chains of the cluster
+    chains: RwLock<Vec<Chain>>,
     dapp_client: HttpApiClient,
     token_info_client: HttpApiClient,
+    bens_client: Option<BensClient>,
+    dapp_directory_client: Option<HttpApiClient>,
     api_settings: ApiSettings,
+    admin_api_key: Option<String>,
+    stale_chain_threshold: Duration,
 }
 
 impl MultichainAggregator {
     pub fn new(
         db: DatabaseConnection,
+        search_backend: SearchBackend,
         chains: Vec<Chain>,
-        dapp_client: HttpApiClient,
-        token_info_client: HttpApiClient,
-        api_settings: ApiSettings,
+        clients: MultichainAggregatorClients,
+        settings: &ServiceSettings,
     ) -> Self {
         Self {
             db: db.clone(),
+            search_backend,
             api_key_manager: ApiKeyManager::new(db),
-            chains,
-            dapp_client,
-            token_info_client,
-            api_settings,
+            chains: RwLock::new(chains),
+            dapp_client: clients.dapp,
+            token_info_client: clients.token_info,
+            bens_client: clients.bens,
+            dapp_directory_client: clients.dapp_directory,
+            api_settings: settings.api.clone(),
+            admin_api_key: settings.admin_api_key.clone(),
+            stale_chain_threshold: settings.stale_chain_threshold,
+        }
+    }
+
+    /// Reloads the cached chains from the database
+    pub async fn reload_chains(&self) -> Result<(), ServiceError> {
+        let chains = logic::repository::chains::list_active(&self.db).await?;
+        *self.chains.write().unwrap() = chains;
+        Ok(())
+    }
+
+    fn chains(&self) -> Vec<Chain> {
+        self.chains.read().unwrap().clone()
+    }
+
+    fn check_admin_api_key(&self, metadata: &MetadataMap) -> Result<(), Status> {
+        let admin_api_key = self
+            .admin_api_key
+            .as_deref()
+            .ok_or_else(|| Status::unimplemented("admin endpoints are disabled"))?;
+        let api_key = metadata
+            .get(API_KEY_NAME)
+            .map(|api_key| api_key.to_str())
+            .transpose()
+            .map_err(|err| {
+                Status::invalid_argument(format!("invalid api key value ({API_KEY_NAME}): {err}"))
+            })?;
+
+        // constant-time comparison, so that the key cannot be guessed from the response time
+        match api_key {
+            Some(api_key) if bool::from(api_key.as_bytes().ct_eq(admin_api_key.as_bytes())) => {
+                Ok(())
+            }
+            _ => Err(Status::permission_denied("invalid api key")),
+        }
+    }
+
+    /// Re-imports the dapp directory in the background, so that it reflects the cluster changes
+    fn spawn_dapp_directory_import(&self) {
+        let Some(client) = self.dapp_directory_client.clone() else {
+            return;
+        };
+        let db = self.db.clone();
+        let search_backend = self.search_backend.clone();
+        tokio::spawn(async move {
+            if let Err(err) = logic::import_dapp_directory(&db, &client, &search_backend).await {
+                tracing::error!(error = ?err, "failed to import dapp directory");
+            }
+        });
+    }
+
+    /// Re-imports the dapp directory, so that the dapps of the newly added chain are served
+    /// right away instead of after the next periodic import. The directory is replaced as a whole,
+    /// so it is imported for all chains. Failures are not fatal, the directory is imported again
+    /// by the periodic import.
+    async fn reimport_dapp_directory(&self) {
+        let Some(client) = &self.dapp_directory_client else {
+            return;
+        };
+        match logic::import_dapp_directory(&self.db, client, &self.search_backend).await {
+            Ok(count) => tracing::info!(count, "dapp directory imported"),
+            Err(err) => tracing::error!(error = ?err, "failed to import dapp directory"),
         }
     }
 
@@ -63,17 +155,18 @@ impl MultichainAggregatorService for MultichainAggregator {
     ) -> Result<Response<BatchImportResponse>, Status> {
         let inner = request.into_inner();
 
-        let api_key = (inner.api_key.as_str(), inner.chain_id.as_str())
+        let api_key: logic::ApiKey = (inner.api_key.as_str(), inner.chain_id.as_str())
             .try_into()
             .map_err(ServiceError::from)?;
+        let chain_id = api_key.chain_id;
         self.api_key_manager
             .validate_api_key(api_key)
             .await
             .map_err(ServiceError::from)?;
 
-        let import_request: logic::BatchImportRequest = inner.try_into()?;
+        let import_request = logic::validate_batch_import(&self.db, chain_id, inner).await?;
 
-        logic::batch_import(&self.db, import_request)
+        logic::batch_import(&self.db, &self.search_backend, import_request)
             .await
             .inspect_err(|err| {
                 tracing::error!(error = ?err, "failed to batch import");
@@ -94,20 +187,25 @@ impl MultichainAggregatorService for MultichainAggregator {
             inner.page_token.map(parse_query_2).transpose()?;
         let page_size = self.normalize_page_size(inner.page_size);
         let chain_id = inner.chain_id.map(parse_query).transpose()?;
-        let (addresses, next_page_token) = logic::repository::addresses::search_by_query_paginated(
-            &self.db,
-            &inner.q,
-            chain_id,
-            page_token,
-            page_size as u64,
-        )
-        .await
-        .inspect_err(|err| {
-            tracing::error!(error = ?err, "failed to list addresses");
-        })?;
+        // Data of the chains removed from the cluster is kept, but not served
+        let chain_ids = self.chains().into_iter().map(|c| c.id).collect::<Vec<_>>();
+        if chain_id.is_some_and(|id| !chain_ids.contains(&id)) {
+            return Ok(Response::new(ListAddressesResponse::default()));
+        }
+        let (addresses, next_page_token) = self
+            .search_backend
+            .search_addresses(&self.db, &inner.q, chain_id, page_token, page_size as u64)
+            .await
+            .inspect_err(|err| {
+                tracing::error!(error = ?err, "failed to list addresses");
+            })?;
 
         Ok(Response::new(ListAddressesResponse {
-            addresses: addresses.into_iter().map(|a| a.into()).collect(),
+            addresses: addresses
+                .into_iter()
+                .filter(|a| chain_ids.contains(&a.chain_id))
+                .map(|a| a.into())
+                .collect(),
             pagination: next_page_token.map(|(a, c)| Pagination {
                 page_token: format!("{},{}", a.to_checksum(None), c),
                 page_size,
@@ -144,9 +242,14 @@ impl MultichainAggregatorService for MultichainAggregator {
         let tokens = res
             .token_infos
             .into_iter()
-            .map(|t| Token::try_from(t).map(|t| t.into()))
+            .map(Token::try_from)
             .collect::<Result<Vec<_>, _>>()
             .map_err(ServiceError::from)?;
+        let tokens = logic::search::merge_bridged_tokens(&self.db, tokens)
+            .await
+            .into_iter()
+            .map(|t| t.into())
+            .collect();
 
         Ok(Response::new(ListTokensResponse {
             tokens,
@@ -157,26 +260,319 @@ impl MultichainAggregatorService for MultichainAggregator {
         }))
     }
 
+    async fn list_nft_holdings(
+        &self,
+        request: Request<ListNftHoldingsRequest>,
+    ) -> Result<Response<ListNftHoldingsResponse>, Status> {
+        let inner = request.into_inner();
+
+        let address = parse_query(inner.address)?;
+        let page_token = inner
+            .page_token
+            .map(parse_query::<logic::NftHoldingsPageToken>)
+            .transpose()?;
+        let page_size = self.normalize_page_size(inner.page_size);
+        // Only the chains of the cluster are listed
+        let mut chain_ids = self.chains().into_iter().map(|c| c.id).collect::<Vec<_>>();
+        if let Some(filter) = inner.chain_ids {
+            let filter = filter
+                .split(',')
+                .map(|id| parse_query::<logic::ChainId>(id.trim().to_string()))
+                .collect::<Result<Vec<_>, _>>()?;
+            chain_ids.retain(|id| filter.contains(id));
+        }
+        if chain_ids.is_empty() {
+            return Ok(Response::new(ListNftHoldingsResponse::default()));
+        }
+
+        let (collections, next_page_token) = logic::repository::nft_holdings::list_collections(
+            &self.db,
+            address,
+            &chain_ids,
+            page_token,
+            page_size as u64,
+        )
+        .await
+        .inspect_err(|err| {
+            tracing::error!(error = ?err, "failed to list nft holdings");
+        })?;
+
+        Ok(Response::new(ListNftHoldingsResponse {
+            collections: collections.into_iter().map(|c| c.into()).collect(),
+            pagination: next_page_token.map(|page_token| Pagination {
+                page_token: page_token.to_string(),
+                page_size,
+            }),
+        }))
+    }
+
     async fn quick_search(
         &self,
         request: Request<QuickSearchRequest>,
     ) -> Result<Response<QuickSearchResponse>, Status> {
         let inner = request.into_inner();
 
-        let results = logic::search::quick_search(
+        let chains = match inner.chain_ids {
+            Some(chain_ids) => {
+                let chain_ids = chain_ids
+                    .split(',')
+                    .map(|id| parse_query::<logic::ChainId>(id.trim().to_string()))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.chains()
+                    .into_iter()
+                    .filter(|c| chain_ids.contains(&c.id))
+                    .collect()
+            }
+            None => self.chains(),
+        };
+
+        let mut results = logic::search::quick_search(
             &self.db,
+            &self.search_backend,
             &self.dapp_client,
             &self.token_info_client,
+            self.bens_client.as_ref(),
             inner.q,
-            &self.chains,
+            &chains,
         )
         .await
         .inspect_err(|err| {
             tracing::error!(error = ?err, "failed to quick search");
         })?;
 
+        let chain_ids = results.items.keys().copied().collect::<Vec<_>>();
+        // Staleness is only a hint, so the results are returned without it on failures
+        match logic::stale_chain_ids(&self.db, &chain_ids, self.stale_chain_threshold).await {
+            Ok(stale_chain_ids) => {
+                for (chain_id, result) in results.items.iter_mut() {
+                    result.is_stale = stale_chain_ids.contains(chain_id);
+                }
+            }
+            Err(err) => {
+                tracing::error!(error = ?err, "failed to get stale chains for quick search");
+            }
+        }
+
         Ok(Response::new(results.into()))
     }
+
+    async fn list_dapps(
+        &self,
+        request: Request<ListDappsRequest>,
+    ) -> Result<Response<ListDappsResponse>, Status> {
+        let inner = request.into_inner();
+
+        let page_size = self.normalize_page_size(inner.page_size);
+        let chain_id = inner.chain_id.map(parse_query).transpose()?;
+        let (dapps, next_page_token) = self
+            .search_backend
+            .search_dapps(
+                &self.db,
+                inner.q.as_deref(),
+                chain_id,
+                inner.category.as_deref(),
+                inner.page_token,
+                page_size as u64,
+            )
+            .await
+            .inspect_err(|err| {
+                tracing::error!(error = ?err, "failed to list dapps");
+            })?;
+
+        Ok(Response::new(ListDappsResponse {
+            dapps: dapps.into_iter().map(|d| d.into()).collect(),
+            pagination: next_page_token.map(|page_token| Pagination {
+                page_token,
+                page_size,
+            }),
+        }))
+    }
+
+    async fn get_dapp(&self, request: Request<GetDappRequest>) -> Result<Response<Dapp>, Status> {
+        let inner = request.into_inner();
+
+        let dapp = logic::repository::dapps::get(&self.db, &inner.id)
+            .await
+            .inspect_err(|err| {
+                tracing::error!(error = ?err, "failed to get dapp");
+            })?
+            .ok_or_else(|| ServiceError::NotFound(format!("dapp {}", inner.id)))?;
+
+        Ok(Response::new(dapp.into()))
+    }
+
+    async fn list_dapp_categories(
+        &self,
+        _request: Request<ListDappCategoriesRequest>,
+    ) -> Result<Response<ListDappCategoriesResponse>, Status> {
+        let categories = logic::repository::dapps::list_categories(&self.db)
+            .await
+            .map_err(ServiceError::from)
+            .inspect_err(|err| {
+                tracing::error!(error = ?err, "failed to list dapp categories");
+            })?;
+
+        Ok(Response::new(ListDappCategoriesResponse { categories }))
+    }
+
+    async fn list_chains(
+        &self,
+        _request: Request<ListChainsRequest>,
+    ) -> Result<Response<ListChainsResponse>, Status> {
+        Ok(Response::new(ListChainsResponse {
+            chains: self.chains().into_iter().map(|c| c.into()).collect(),
+        }))
+    }
+
+    async fn list_chains_freshness(
+        &self,
+        _request: Request<ListChainsFreshnessRequest>,
+    ) -> Result<Response<ListChainsFreshnessResponse>, Status> {
+        let chain_ids = self.chains().into_iter().map(|c| c.id).collect::<Vec<_>>();
+        let freshness = logic::chains_freshness(&self.db, &chain_ids)
+            .await
+            .inspect_err(|err| {
+                tracing::error!(error = ?err, "failed to get chains freshness");
+            })?;
+
+        Ok(Response::new(ListChainsFreshnessResponse {
+            chains: freshness
+                .into_iter()
+                .map(|f| f.into_proto(self.stale_chain_threshold))
+                .collect(),
+        }))
+    }
+
+    async fn add_chain(
+        &self,
+        request: Request<AddChainRequest>,
+    ) -> Result<Response<AddChainResponse>, Status> {
+        self.check_admin_api_key(request.metadata())?;
+        let inner = request.into_inner();
+
+        let chain_id = parse_query(inner.chain_id)?;
+        let mut chain = Chain {
+            id: chain_id,
+            explorer_url: inner.explorer_url,
+            icon_url: inner.icon_url,
+        };
+        if chain.explorer_url.is_none() || chain.icon_url.is_none() {
+            if let Some(known) = fetch_blockscout_chain(chain_id).await {
+                chain.explorer_url = chain.explorer_url.or(known.explorer_url);
+                chain.icon_url = chain.icon_url.or(known.icon_url);
+            }
+        }
+
+        let chain = logic::repository::chains::activate(&self.db, chain)
+            .await
+            .map_err(ServiceError::from)
+            .inspect_err(|err| {
+                tracing::error!(error = ?err, "failed to add chain");
+            })?;
+        let api_key = logic::repository::api_keys::find_or_create_by_chain_id(&self.db, chain.id)
+            .await
+            .map_err(ServiceError::from)
+            .inspect_err(|err| {
+                tracing::error!(error = ?err, "failed to create api key");
+            })?;
+
+        self.reload_chains().await?;
+        self.reimport_dapp_directory().await;
+        tracing::info!(chain_id = chain.id, "chain added to the cluster");
+
+        Ok(Response::new(AddChainResponse {
+            chain: Some(chain.into()),
+            api_key: api_key.key.to_string(),
+        }))
+    }
+
+    async fn remove_chain(
+        &self,
+        request: Request<RemoveChainRequest>,
+    ) -> Result<Response<RemoveChainResponse>, Status> {
+        self.check_admin_api_key(request.metadata())?;
+        let inner = request.into_inner();
+
+        let chain_id: logic::ChainId = parse_query(inner.chain_id)?;
+        let found = logic::repository::chains::deactivate(&self.db, chain_id)
+            .await
+            .map_err(ServiceError::from)
+            .inspect_err(|err| {
+                tracing::error!(error = ?err, "failed to remove chain");
+            })?;
+        if !found {
+            return Err(ServiceError::NotFound(format!("chain {chain_id}")).into());
+        }
+
+        self.reload_chains().await?;
+        self.spawn_dapp_directory_import();
+        tracing::info!(chain_id, "chain removed from the cluster");
+
+        Ok(Response::new(RemoveChainResponse {
+            status: "ok".to_string(),
+        }))
+    }
+
+    async fn list_import_dead_letters(
+        &self,
+        request: Request<ListImportDeadLettersRequest>,
+    ) -> Result<Response<ListImportDeadLettersResponse>, Status> {
+        self.check_admin_api_key(request.metadata())?;
+        let inner = request.into_inner();
+
+        let chain_id = inner.chain_id.map(parse_query).transpose()?;
+        let page_token = inner.page_token.map(parse_query).transpose()?;
+        let page_size = self.normalize_page_size(inner.page_size);
+        let (dead_letters, next_page_token) = logic::repository::import_dead_letters::list(
+            &self.db,
+            chain_id,
+            inner.include_replayed.unwrap_or(false),
+            page_token,
+            page_size as u64,
+        )
+        .await?;
+
+        Ok(Response::new(ListImportDeadLettersResponse {
+            dead_letters: dead_letters.into_iter().map(|d| d.into()).collect(),
+            pagination: next_page_token.map(|page_token| Pagination {
+                page_token: page_token.to_string(),
+                page_size,
+            }),
+        }))
+    }
+
+    async fn replay_import_dead_letter(
+        &self,
+        request: Request<ReplayImportDeadLetterRequest>,
+    ) -> Result<Response<ImportDeadLetter>, Status> {
+        self.check_admin_api_key(request.metadata())?;
+        let inner = request.into_inner();
+
+        let id = parse_query(inner.id)?;
+        let dead_letter = logic::replay_dead_letter(&self.db, &self.search_backend, id)
+            .await
+            .inspect_err(|err| {
+                tracing::error!(error = ?err, "failed to replay dead letter");
+            })?;
+
+        Ok(Response::new(dead_letter.into()))
+    }
+}
+
+/// Looks up the chain in the Blockscout chains list, `None` if it is missing or the list is unavailable
+async fn fetch_blockscout_chain(chain_id: logic::ChainId) -> Option<Chain> {
+    let chains = BlockscoutChainsClient::builder()
+        .with_max_retries(0)
+        .build()
+        .fetch_all()
+        .await
+        .inspect_err(|err| {
+            tracing::warn!(error = ?err, "failed to fetch blockscout chains");
+        })
+        .ok()?;
+    chains
+        .by_chain_id(u64::try_from(chain_id).ok()?)
+        .map(|chain| (chain_id, chain.clone()).into())
 }
 
 #[inline]
@@ -4,6 +4,10 @@ use blockscout_service_launcher::{
     tracing::{JaegerSettings, TracingSettings},
 };
 use serde::{Deserialize, Serialize};
+use serde_with::{
+    formats::CommaSeparator, serde_as, DurationMilliseconds, DurationSeconds, StringWithSeparator,
+};
+use std::time;
 use url::Url;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,6 +26,7 @@ pub struct Settings {
     pub service: ServiceSettings,
 }
 
+#[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
 pub struct ServiceSettings {
@@ -29,6 +34,27 @@ pub struct ServiceSettings {
     pub token_info_client: TokenInfoClientSettings,
     #[serde(default)]
     pub api: ApiSettings,
+    #[serde(default)]
+    pub dapp_directory: Option<DappDirectorySettings>,
+    #[serde(default)]
+    pub bens_client: Option<BensClientSettings>,
+    #[serde(default)]
+    pub token_lists: Option<TokenListsSettings>,
+    /// Search backend for addresses, tokens and dapps, Postgres is used if not set
+    #[serde(default)]
+    pub opensearch: Option<OpenSearchSettings>,
+    /// Key required by the admin endpoints, the endpoints are disabled if not set
+    #[serde(default)]
+    pub admin_api_key: Option<String>,
+    /// Interval to reload the cluster chains from the database,
+    /// so that the changes made on other instances are picked up
+    #[serde(default = "default_chains_reload_interval")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub chains_reload_interval: time::Duration,
+    /// Chains with no data imported within the threshold are marked as stale
+    #[serde(default = "default_stale_chain_threshold")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub stale_chain_threshold: time::Duration,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -61,8 +87,82 @@ pub struct TokenInfoClientSettings {
     pub url: Url,
 }
 
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct DappDirectorySettings {
+    /// Url of the JSON document with the dapp catalog
+    pub url: Url,
+    #[serde(default = "default_dapp_directory_refresh_interval")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub refresh_interval: time::Duration,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BensClientSettings {
+    pub url: Url,
+    /// Chain where the domain names are resolved
+    #[serde(default = "default_bens_chain_id")]
+    pub chain_id: i64,
+    #[serde(default = "default_bens_request_timeout")]
+    #[serde_as(as = "DurationMilliseconds<u64>")]
+    pub request_timeout: time::Duration,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TokenListsSettings {
+    /// Comma-separated urls of the token lists to seed the token mappings from
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, Url>")]
+    pub urls: Vec<Url>,
+    #[serde(default = "default_token_lists_refresh_interval")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub refresh_interval: time::Duration,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct OpenSearchSettings {
+    pub url: Url,
+    #[serde(default = "default_opensearch_index_prefix")]
+    pub index_prefix: String,
+    #[serde(default = "default_opensearch_request_timeout")]
+    #[serde_as(as = "DurationMilliseconds<u64>")]
+    pub request_timeout: time::Duration,
+    /// Interval to retry the failed index writes and the interrupted reindex
+    #[serde(default = "default_opensearch_retry_interval")]
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub retry_interval: time::Duration,
+}
+
 impl ConfigSettings for Settings {
     const SERVICE_NAME: &'static str = "MULTICHAIN_AGGREGATOR";
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Some(dapp_directory) = &self.service.dapp_directory {
+            if dapp_directory.refresh_interval.is_zero() {
+                anyhow::bail!("dapp directory refresh interval must be non-zero");
+            }
+        }
+        if let Some(token_lists) = &self.service.token_lists {
+            if token_lists.refresh_interval.is_zero() {
+                anyhow::bail!("token lists refresh interval must be non-zero");
+            }
+        }
+        if self.service.chains_reload_interval.is_zero() {
+            anyhow::bail!("chains reload interval must be non-zero");
+        }
+        if let Some(opensearch) = &self.service.opensearch {
+            if opensearch.retry_interval.is_zero() {
+                anyhow::bail!("opensearch retry interval must be non-zero");
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Settings {
@@ -89,6 +189,13 @@ impl Settings {
                     default_page_size: default_default_page_size(),
                     max_page_size: default_max_page_size(),
                 },
+                dapp_directory: None,
+                bens_client: None,
+                token_lists: None,
+                opensearch: None,
+                admin_api_key: None,
+                chains_reload_interval: default_chains_reload_interval(),
+                stale_chain_threshold: default_stale_chain_threshold(),
             },
         }
     }
@@ -101,3 +208,39 @@ fn default_max_page_size() -> u32 {
 fn default_default_page_size() -> u32 {
     50
 }
+
+fn default_dapp_directory_refresh_interval() -> time::Duration {
+    time::Duration::from_secs(3600)
+}
+
+fn default_chains_reload_interval() -> time::Duration {
+    time::Duration::from_secs(60)
+}
+
+fn default_stale_chain_threshold() -> time::Duration {
+    time::Duration::from_secs(600)
+}
+
+fn default_bens_chain_id() -> i64 {
+    1
+}
+
+fn default_bens_request_timeout() -> time::Duration {
+    time::Duration::from_millis(1000)
+}
+
+fn default_token_lists_refresh_interval() -> time::Duration {
+    time::Duration::from_secs(86400)
+}
+
+fn default_opensearch_index_prefix() -> String {
+    "multichain_aggregator".to_string()
+}
+
+fn default_opensearch_request_timeout() -> time::Duration {
+    time::Duration::from_millis(5000)
+}
+
+fn default_opensearch_retry_interval() -> time::Duration {
+    time::Duration::from_secs(60)
+}
@@ -1,26 +1,27 @@
-use blockscout_service_launcher::{test_database::TestDbGuard, test_server};
+use blockscout_service_launcher::{
+    database::DatabaseConnectSettings,
+    test_server::{TestServer, TestServerBuilder},
+};
 use multichain_aggregator_server::Settings;
-use reqwest::Url;
 
-pub async fn init_db(db_prefix: &str, test_name: &str) -> TestDbGuard {
-    let db_name = format!("{db_prefix}_{test_name}");
-    TestDbGuard::new::<migration::Migrator>(db_name.as_str()).await
-}
-pub async fn init_multichain_aggregator_server<F>(db_url: String, settings_setup: F) -> Url
+pub async fn init_multichain_aggregator_server<F>(db_name: &str, settings_setup: F) -> TestServer
 where
-    F: Fn(Settings) -> Settings,
+    F: FnOnce(&mut Settings) + Send + 'static,
 {
-    let (settings, base) = {
-        let mut settings = Settings::default(db_url);
-        let (server_settings, base) = test_server::get_test_server_settings();
-        settings.server = server_settings;
+    TestServerBuilder::new(Settings::default(String::new()), |settings| {
+        &mut settings.server
+    })
+    .with_grpc()
+    .with_database::<migration::Migrator>(db_name, |settings, db_url| {
+        settings.database.connect = DatabaseConnectSettings::Url(db_url)
+    })
+    .await
+    .with_overrides(|settings| {
         settings.metrics.enabled = false;
         settings.tracing.enabled = false;
         settings.jaeger.enabled = false;
-
-        (settings_setup(settings), base)
-    };
-
-    test_server::init_server(|| multichain_aggregator_server::run(settings), &base).await;
-    base
+    })
+    .with_overrides(settings_setup)
+    .start(multichain_aggregator_server::run)
+    .await
 }
@@ -1,14 +1,22 @@
 mod helpers;
 
-use blockscout_service_launcher::test_server;
+use multichain_aggregator_proto::blockscout::multichain_aggregator::v1::{
+    health_check_response::ServingStatus, health_client::HealthClient, HealthCheckRequest,
+};
 use pretty_assertions::assert_eq;
 
 #[tokio::test]
 #[ignore = "Needs database to run"]
 async fn test_startup_works() {
-    let db = helpers::init_db("test", "startup_works").await;
-    let db_url = db.db_url();
-    let base = helpers::init_multichain_aggregator_server(db_url, |x| x).await;
-    let response: serde_json::Value = test_server::send_get_request(&base, "/health").await;
+    let server = helpers::init_multichain_aggregator_server("test_startup_works", |_| {}).await;
+    let response: serde_json::Value = server.get("/health").await;
     assert_eq!(response, serde_json::json!({"status": "SERVING"}));
+
+    let mut client = server.grpc_client(HealthClient::new).await;
+    let response = client
+        .check(HealthCheckRequest { service: None })
+        .await
+        .expect("grpc health check failed")
+        .into_inner();
+    assert_eq!(response.status, ServingStatus::Serving as i32);
 }
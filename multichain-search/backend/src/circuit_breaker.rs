@@ -0,0 +1,285 @@
+use crate::settings::CircuitBreakerSettings;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time,
+};
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Requests are sent to the instance
+    Closed,
+    /// The instance is skipped until the circuit is half-opened
+    Open,
+    /// A single probe request is sent, the result decides whether to close the circuit
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Outcome {
+    success: bool,
+    elapsed: time::Duration,
+}
+
+#[derive(Debug)]
+struct InstanceHealth {
+    state: CircuitState,
+    opened_at: Option<time::Instant>,
+    probe_in_flight: bool,
+    outcomes: VecDeque<Outcome>,
+}
+
+impl Default for InstanceHealth {
+    fn default() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            opened_at: None,
+            probe_in_flight: false,
+            outcomes: VecDeque::new(),
+        }
+    }
+}
+
+impl InstanceHealth {
+    fn error_rate(&self) -> f64 {
+        if self.outcomes.is_empty() {
+            return 0.0;
+        }
+        let failures = self.outcomes.iter().filter(|o| !o.success).count();
+        failures as f64 / self.outcomes.len() as f64
+    }
+
+    fn avg_latency(&self) -> Option<time::Duration> {
+        if self.outcomes.is_empty() {
+            return None;
+        }
+        let total: time::Duration = self.outcomes.iter().map(|o| o.elapsed).sum();
+        Some(total / self.outcomes.len() as u32)
+    }
+
+    fn open(&mut self) {
+        self.state = CircuitState::Open;
+        self.opened_at = Some(time::Instant::now());
+        self.probe_in_flight = false;
+    }
+
+    fn close(&mut self) {
+        self.state = CircuitState::Closed;
+        self.opened_at = None;
+        self.probe_in_flight = false;
+        self.outcomes.clear();
+    }
+}
+
+/// Health statistics of the instance over the recent requests
+#[derive(Serialize, Clone, Debug)]
+pub struct InstanceHealthStatus {
+    pub state: CircuitState,
+    pub requests: usize,
+    pub error_rate: f64,
+    pub avg_latency_secs: Option<f64>,
+}
+
+/// Tracks error rates and latencies of the instances and excludes the failing ones
+/// from the aggregation, so that requests don't wait for their timeouts every time.
+///
+/// The state is shared between the clones, as every server worker gets its own copy of the proxy.
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    settings: CircuitBreakerSettings,
+    health: Arc<Mutex<HashMap<String, InstanceHealth>>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(settings: CircuitBreakerSettings) -> Self {
+        Self {
+            settings,
+            health: Default::default(),
+        }
+    }
+
+    /// Returns the permit to make the request to the instance, if the request should be made.
+    /// After `open_duration` an open circuit lets through a single probe request.
+    ///
+    /// The result must be recorded via [`CircuitPermit::record`]. If the permit is dropped
+    /// without the result (e.g., the client has disconnected), the probe is released,
+    /// so that the next request could probe the instance.
+    pub fn try_acquire(&self, instance_id: &str) -> Option<CircuitPermit> {
+        let permit = |probe| CircuitPermit {
+            circuit_breaker: self.clone(),
+            instance_id: instance_id.to_string(),
+            probe,
+            recorded: false,
+        };
+        if !self.settings.enabled {
+            return Some(permit(false));
+        }
+        let mut health = self.health.lock().expect("circuit breaker lock poisoned");
+        let health = health.entry(instance_id.to_string()).or_default();
+        match health.state {
+            CircuitState::Closed => Some(permit(false)),
+            CircuitState::Open => {
+                let open_elapsed = health
+                    .opened_at
+                    .map(|opened_at| opened_at.elapsed() >= self.settings.open_duration)
+                    .unwrap_or(true);
+                if open_elapsed {
+                    tracing::info!(instance_id, "circuit half-opened, sending probe request");
+                    health.state = CircuitState::HalfOpen;
+                    health.probe_in_flight = true;
+                    Some(permit(true))
+                } else {
+                    None
+                }
+            }
+            CircuitState::HalfOpen => {
+                if health.probe_in_flight {
+                    None
+                } else {
+                    health.probe_in_flight = true;
+                    Some(permit(true))
+                }
+            }
+        }
+    }
+
+    fn record(&self, instance_id: &str, success: bool, elapsed: time::Duration) {
+        if !self.settings.enabled {
+            return;
+        }
+        // Slow instances delay the whole aggregation, so they are excluded as the failing ones
+        let success = success
+            && self
+                .settings
+                .slow_request_threshold
+                .map_or(true, |threshold| elapsed < threshold);
+        let mut health = self.health.lock().expect("circuit breaker lock poisoned");
+        let health = health.entry(instance_id.to_string()).or_default();
+        match health.state {
+            CircuitState::HalfOpen if success => {
+                tracing::info!(instance_id, "probe request succeeded, circuit closed");
+                health.close();
+            }
+            CircuitState::HalfOpen => {
+                tracing::warn!(instance_id, "probe request failed, circuit opened again");
+                health.open();
+            }
+            // Requests started before the circuit was opened
+            CircuitState::Open => {}
+            CircuitState::Closed => {
+                health.outcomes.push_back(Outcome { success, elapsed });
+                while health.outcomes.len() > self.settings.window_size {
+                    health.outcomes.pop_front();
+                }
+                let error_rate = health.error_rate();
+                if health.outcomes.len() >= self.settings.min_requests
+                    && error_rate >= self.settings.error_rate_threshold
+                {
+                    tracing::warn!(instance_id, error_rate, "circuit opened");
+                    health.open();
+                }
+            }
+        }
+    }
+
+    fn release_probe(&self, instance_id: &str) {
+        let mut health = self.health.lock().expect("circuit breaker lock poisoned");
+        if let Some(health) = health.get_mut(instance_id) {
+            if health.state == CircuitState::HalfOpen {
+                health.probe_in_flight = false;
+            }
+        }
+    }
+
+    pub fn status(&self, instance_id: &str) -> InstanceHealthStatus {
+        let health = self.health.lock().expect("circuit breaker lock poisoned");
+        match health.get(instance_id) {
+            Some(health) => InstanceHealthStatus {
+                state: health.state,
+                requests: health.outcomes.len(),
+                error_rate: health.error_rate(),
+                avg_latency_secs: health.avg_latency().map(|l| l.as_secs_f64()),
+            },
+            None => InstanceHealthStatus {
+                state: CircuitState::Closed,
+                requests: 0,
+                error_rate: 0.0,
+                avg_latency_secs: None,
+            },
+        }
+    }
+}
+
+/// Permission to make a request to the instance, see [`CircuitBreaker::try_acquire`]
+#[derive(Debug)]
+pub struct CircuitPermit {
+    circuit_breaker: CircuitBreaker,
+    instance_id: String,
+    probe: bool,
+    recorded: bool,
+}
+
+impl CircuitPermit {
+    pub fn record(mut self, success: bool, elapsed: time::Duration) {
+        self.recorded = true;
+        self.circuit_breaker
+            .record(&self.instance_id, success, elapsed);
+    }
+}
+
+impl Drop for CircuitPermit {
+    fn drop(&mut self) {
+        if self.probe && !self.recorded {
+            tracing::debug!(instance_id = %self.instance_id, "probe request cancelled");
+            self.circuit_breaker.release_probe(&self.instance_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn circuit_breaker() -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerSettings {
+            min_requests: 1,
+            open_duration: time::Duration::ZERO,
+            slow_request_threshold: Some(time::Duration::from_secs(1)),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn cancelled_probe_is_released() {
+        let circuit_breaker = circuit_breaker();
+        circuit_breaker
+            .try_acquire("instance")
+            .unwrap()
+            .record(false, time::Duration::ZERO);
+        assert_eq!(circuit_breaker.status("instance").state, CircuitState::Open);
+
+        let probe = circuit_breaker.try_acquire("instance").unwrap();
+        assert!(circuit_breaker.try_acquire("instance").is_none());
+        drop(probe);
+
+        let probe = circuit_breaker.try_acquire("instance").unwrap();
+        probe.record(true, time::Duration::ZERO);
+        assert_eq!(
+            circuit_breaker.status("instance").state,
+            CircuitState::Closed
+        );
+    }
+
+    #[test]
+    fn slow_requests_open_circuit() {
+        let circuit_breaker = circuit_breaker();
+        circuit_breaker
+            .try_acquire("instance")
+            .unwrap()
+            .record(true, time::Duration::from_secs(2));
+        assert_eq!(circuit_breaker.status("instance").state, CircuitState::Open);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::{
+    proxy::{BlockscoutProxy, Instance},
+    settings::DiscoverySettings,
+};
+use blockscout_chains::{BlockscoutChainData, BlockscoutChainsClient, ChainId, Ecosystem};
+use std::collections::BTreeMap;
+
+/// Periodically replaces the instances of the proxy with the chains of the blockscout-chains catalog.
+/// Configured instances are always kept and take precedence over the discovered ones with the same id.
+pub async fn run_instances_discovery(
+    proxy: BlockscoutProxy,
+    configured_instances: Vec<Instance>,
+    settings: DiscoverySettings,
+) {
+    let mut builder = BlockscoutChainsClient::builder();
+    if let Some(url) = settings.url.clone() {
+        builder = builder.with_url(url);
+    }
+    let client = builder.build();
+
+    let mut interval = tokio::time::interval(settings.refresh_interval);
+    loop {
+        interval.tick().await;
+        let chains = match client.fetch_all().await {
+            Ok(chains) => chains,
+            Err(err) => {
+                tracing::error!(err = ?err, "failed to fetch blockscout chains");
+                continue;
+            }
+        };
+
+        let mut instances = chains
+            .into_iter()
+            .filter_map(|(chain_id, chain)| chain_instance(&settings, chain_id, chain))
+            .map(|instance| (instance.id.clone(), instance))
+            .collect::<BTreeMap<_, _>>();
+        instances.extend(
+            configured_instances
+                .iter()
+                .map(|instance| (instance.id.clone(), instance.clone())),
+        );
+        let instances = instances.into_values().collect::<Vec<_>>();
+
+        tracing::info!(count = instances.len(), "discovered instances");
+        proxy.set_instances(instances);
+    }
+}
+
+fn chain_instance(
+    settings: &DiscoverySettings,
+    chain_id: ChainId,
+    chain: BlockscoutChainData,
+) -> Option<Instance> {
+    if !settings.include_testnets && chain.is_testnet.unwrap_or(false) {
+        return None;
+    }
+    if !settings.ecosystems.is_empty() {
+        let ecosystems = match &chain.ecosystem {
+            Ecosystem::Single(ecosystem) => vec![ecosystem],
+            Ecosystem::Multiple(ecosystems) => ecosystems.iter().collect(),
+        };
+        let matches = ecosystems.into_iter().any(|ecosystem| {
+            settings
+                .ecosystems
+                .iter()
+                .any(|e| e.eq_ignore_ascii_case(ecosystem))
+        });
+        if !matches {
+            return None;
+        }
+    }
+
+    let explorer = chain.explorers.into_iter().find(|explorer| {
+        settings.hosted_by.is_empty()
+            || settings
+                .hosted_by
+                .iter()
+                .any(|h| h.eq_ignore_ascii_case(&explorer.hosted_by))
+    })?;
+    let url = match url::Url::parse(&explorer.url) {
+        Ok(url) => url,
+        Err(err) => {
+            tracing::warn!(%chain_id, url = %explorer.url, err = ?err, "invalid explorer url");
+            return None;
+        }
+    };
+
+    Some(Instance {
+        id: chain_id.to_string(),
+        title: chain.name,
+        url,
+        headers: Default::default(),
+        api_key: None,
+    })
+}
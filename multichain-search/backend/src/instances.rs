@@ -4,7 +4,10 @@ use actix_web::{
 };
 use serde::Serialize;
 
-use crate::proxy::{self, Instance};
+use crate::{
+    circuit_breaker::InstanceHealthStatus,
+    proxy::{self, Instance},
+};
 
 #[derive(Serialize)]
 pub struct InstancesResponse {
@@ -18,3 +21,27 @@ pub async fn get_instances(
     let items = proxy.instances();
     Json(InstancesResponse { items })
 }
+
+#[derive(Serialize)]
+pub struct InstanceHealth {
+    pub instance: Instance,
+    #[serde(flatten)]
+    pub health: InstanceHealthStatus,
+}
+
+#[derive(Serialize)]
+pub struct InstancesHealthResponse {
+    pub items: Vec<InstanceHealth>,
+}
+
+pub async fn get_instances_health(
+    _request: HttpRequest,
+    proxy: Data<proxy::BlockscoutProxy>,
+) -> Json<InstancesHealthResponse> {
+    let items = proxy
+        .instances_health()
+        .into_iter()
+        .map(|(instance, health)| InstanceHealth { instance, health })
+        .collect();
+    Json(InstancesHealthResponse { items })
+}
@@ -1,5 +1,8 @@
+pub mod circuit_breaker;
+mod discovery;
 mod instances;
 pub mod proxy;
+pub mod routing;
 pub mod server;
 mod settings;
 mod tracer;
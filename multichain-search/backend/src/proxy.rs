@@ -1,51 +1,138 @@
+use crate::{
+    circuit_breaker::{CircuitBreaker, InstanceHealthStatus},
+    routing::Router,
+    settings::{BlockscoutSettings, CircuitBreakerSettings},
+};
 use actix_web::{
     dev::RequestHead,
-    http::{uri::PathAndQuery, StatusCode, Uri},
+    http::{header, uri::PathAndQuery, Method, StatusCode, Uri},
     web::Bytes,
 };
 use awc::{Client, ClientRequest};
-use futures::{stream, StreamExt};
+use futures::{stream, Stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, str, time};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::{Arc, RwLock},
+    time,
+};
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct Instance {
     pub id: String,
     pub title: String,
     pub url: url::Url,
+    /// Headers added to every request to the instance, e.g. the authorization of a private deployment
+    #[serde(default, skip_serializing)]
+    pub headers: BTreeMap<String, String>,
+    /// Sent as the `apikey` query parameter to lift the rate limits of the instance
+    #[serde(default, skip_serializing)]
+    pub api_key: Option<String>,
+}
+
+// Credentials of the instance must not get into the logs
+impl fmt::Debug for Instance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Instance")
+            .field("id", &self.id)
+            .field("title", &self.title)
+            .field("url", &self.url)
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .field("api_key", &self.api_key.as_ref().map(|_| "<hidden>"))
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct BlockscoutProxy {
-    instances: Vec<Instance>,
+    // Shared between the clones, as the instances may be updated by the discovery
+    instances: Arc<RwLock<Vec<Instance>>>,
     concurrent_requests: usize,
     request_timeout: time::Duration,
+    forwarded_headers: Vec<String>,
+    router: Router,
+    circuit_breaker: CircuitBreaker,
 }
 
 impl BlockscoutProxy {
-    pub fn new(
-        instances: Vec<Instance>,
-        concurrent_requests: usize,
-        request_timeout: time::Duration,
-    ) -> Self {
+    pub fn new(settings: BlockscoutSettings, circuit_breaker: CircuitBreakerSettings) -> Self {
         Self {
-            instances,
-            concurrent_requests,
-            request_timeout,
+            instances: Arc::new(RwLock::new(settings.instances)),
+            concurrent_requests: settings.concurrent_requests,
+            request_timeout: settings.request_timeout,
+            forwarded_headers: settings.forwarded_headers,
+            router: Router::new(settings.routes),
+            circuit_breaker: CircuitBreaker::new(circuit_breaker),
         }
     }
 
     pub fn instances(&self) -> Vec<Instance> {
-        self.instances.clone()
+        self.instances
+            .read()
+            .expect("instances lock poisoned")
+            .clone()
+    }
+
+    pub fn set_instances(&self, instances: Vec<Instance>) {
+        *self.instances.write().expect("instances lock poisoned") = instances;
+    }
+
+    pub fn has_instances(&self) -> bool {
+        !self
+            .instances
+            .read()
+            .expect("instances lock poisoned")
+            .is_empty()
+    }
+
+    /// Whether the request is allowed by the routing rules for at least one instance.
+    /// Always false if there are no instances, which should be checked with `has_instances` first.
+    pub fn is_routable(&self, method: &Method, path: &str) -> bool {
+        self.instances()
+            .iter()
+            .any(|instance| self.router.route(&instance.id, method, path).is_some())
+    }
+
+    pub fn instances_health(&self) -> Vec<(Instance, InstanceHealthStatus)> {
+        self.instances()
+            .into_iter()
+            .map(|instance| {
+                let health = self.circuit_breaker.status(&instance.id);
+                (instance, health)
+            })
+            .collect()
     }
 }
 
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InstanceErrorKind {
+    /// The instance was skipped, as its circuit is open
+    Unavailable,
+    /// The request was not completed, e.g. connection error or timeout
+    RequestFailed,
+    /// The instance responded with an error status
+    Upstream,
+    /// The response body is not a valid json
+    InvalidResponse,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct InstanceError {
+    pub kind: InstanceErrorKind,
+    pub message: String,
+}
+
+/// Result of the request to the instance.
+/// `data` contains the json returned by the instance, including the error payloads of the `upstream` errors.
 #[derive(Deserialize, Serialize, Debug, PartialEq, Eq)]
 pub struct InstanceResponse {
     pub instance: Instance,
-    pub content: String,
     #[serde(with = "http_serde::status_code")]
     pub status: StatusCode,
+    pub error: Option<InstanceError>,
+    pub data: Option<serde_json::Value>,
     #[serde(with = "http_serde::uri")]
     pub uri: Uri,
     pub elapsed_secs: String,
@@ -62,58 +149,208 @@ impl BlockscoutProxy {
         body: Bytes,
         request_head: &RequestHead,
     ) -> Response {
+        let responses = self
+            .responses_stream(path_and_query, body, request_head)
+            .collect::<HashMap<_, _>>()
+            .await;
+        Response(responses)
+    }
+
+    /// Makes the requests to all instances and yields the responses as soon as they arrive,
+    /// so that a slow instance doesn't delay the results of the others.
+    pub fn responses_stream(
+        &self,
+        path_and_query: Option<&PathAndQuery>,
+        body: Bytes,
+        request_head: &RequestHead,
+    ) -> impl Stream<Item = (String, InstanceResponse)> + 'static {
         let client = Client::builder().timeout(self.request_timeout).finish();
 
-        let responses = stream::iter(self.instances.iter())
-            .map(|instance| async {
-                let mut url = instance.url.clone().to_string();
-                if let Some(path_and_query) = path_and_query {
-                    url = url.trim_end_matches('/').to_string();
-                    url = format!("{url}{path_and_query}")
+        let path = path_and_query.map(|p| p.path()).unwrap_or("/");
+        let query = path_and_query.and_then(|p| p.query());
+
+        let requests = self
+            .instances()
+            .into_iter()
+            .filter_map(|instance| {
+                let path = self
+                    .router
+                    .route(&instance.id, &request_head.method, path)?;
+                let mut url = instance.url.to_string().trim_end_matches('/').to_string();
+                url = format!("{url}{path}");
+                if let Some(query) = query {
+                    url = format!("{url}?{query}");
                 };
-                let request = client.request_from(url, request_head);
-                let response = Self::send_request(instance, request, body.clone()).await;
-                (instance.id.clone(), response)
+                let request = self.build_request(&client, &instance, &url, request_head);
+                Some((instance, url, request))
+            })
+            .collect::<Vec<_>>();
+
+        let circuit_breaker = self.circuit_breaker.clone();
+        stream::iter(requests)
+            .map(move |(instance, url, request)| {
+                let circuit_breaker = circuit_breaker.clone();
+                let body = body.clone();
+                async move {
+                    // Acquired only when the request is about to be sent, so that a probe
+                    // is not held while waiting for the concurrency limit
+                    let permit = match circuit_breaker.try_acquire(&instance.id) {
+                        Some(permit) => permit,
+                        None => {
+                            tracing::debug!(
+                                instance_id = %instance.id,
+                                "circuit is open, skipping instance"
+                            );
+                            let response = Self::unavailable_response(&instance, url);
+                            return (instance.id, response);
+                        }
+                    };
+                    let now = time::Instant::now();
+                    let uri = url.parse().unwrap_or_default();
+                    let response = Self::send_request(&instance, request, uri, body).await;
+                    permit.record(!response.status.is_server_error(), now.elapsed());
+                    (instance.id, response)
+                }
             })
             .buffer_unordered(self.concurrent_requests)
-            .collect::<HashMap<_, _>>()
-            .await;
-        Response(responses)
+    }
+
+    fn build_request(
+        &self,
+        client: &Client,
+        instance: &Instance,
+        url: &str,
+        request_head: &RequestHead,
+    ) -> ClientRequest {
+        let url = match &instance.api_key {
+            Some(api_key) => match url::Url::parse(url) {
+                Ok(mut url) => {
+                    url.query_pairs_mut().append_pair("apikey", api_key);
+                    url.to_string()
+                }
+                Err(err) => {
+                    tracing::warn!(instance_id = %instance.id, err = ?err, "invalid instance url");
+                    url.to_string()
+                }
+            },
+            None => url.to_string(),
+        };
+
+        // All client headers are forwarded unless the allowed ones are specified
+        let mut request = if self.forwarded_headers.is_empty() {
+            client.request_from(url, request_head)
+        } else {
+            let mut request = client.request(request_head.method.clone(), url);
+            for name in &self.forwarded_headers {
+                for value in request_head.headers.get_all(name.as_str()) {
+                    request = request.append_header((name.as_str(), value.clone()));
+                }
+            }
+            request
+        };
+        // Instances always respond with json, even if the events are requested from us
+        if accepts_event_stream(request_head) {
+            request = request.insert_header((header::ACCEPT, "application/json"));
+        }
+        for (name, value) in &instance.headers {
+            request = request.insert_header((name.as_str(), value.as_str()));
+        }
+        request
     }
 
     #[tracing::instrument(skip(request, body), level = "debug")]
     async fn send_request(
         instance: &Instance,
         request: ClientRequest,
+        uri: Uri,
         body: Bytes,
     ) -> InstanceResponse {
-        let uri = request.get_uri().to_owned();
         let now = time::Instant::now();
-        let (content, status) = match Self::perform_request(request, body).await {
-            Ok((body, status)) => (body, status),
-            Err(err) => (err.to_string(), StatusCode::INTERNAL_SERVER_ERROR),
+        let (status, error, data) = match Self::perform_request(request, body).await {
+            Ok((status, content)) => {
+                let (error, data) = Self::parse_content(status, &content);
+                (status, error, data)
+            }
+            Err(err) => {
+                let error = InstanceError {
+                    kind: InstanceErrorKind::RequestFailed,
+                    message: err.to_string(),
+                };
+                (StatusCode::INTERNAL_SERVER_ERROR, Some(error), None)
+            }
         };
         let elapsed_secs = now.elapsed().as_secs_f64().to_string();
         tracing::debug!(elapsed = ?elapsed_secs, "request finished");
         InstanceResponse {
             instance: instance.clone(),
-            content,
             status,
+            error,
+            data,
             uri,
             elapsed_secs,
         }
     }
 
+    fn parse_content(
+        status: StatusCode,
+        content: &Bytes,
+    ) -> (Option<InstanceError>, Option<serde_json::Value>) {
+        let data = if content.is_empty() {
+            None
+        } else {
+            match serde_json::from_slice(content) {
+                Ok(data) => Some(data),
+                Err(err) if status.is_success() => {
+                    let error = InstanceError {
+                        kind: InstanceErrorKind::InvalidResponse,
+                        message: err.to_string(),
+                    };
+                    return (Some(error), None);
+                }
+                // Error pages of the instances are not necessarily json
+                Err(_) => None,
+            }
+        };
+        let error = (!status.is_success()).then(|| InstanceError {
+            kind: InstanceErrorKind::Upstream,
+            message: status.to_string(),
+        });
+        (error, data)
+    }
+
+    fn unavailable_response(instance: &Instance, url: String) -> InstanceResponse {
+        InstanceResponse {
+            instance: instance.clone(),
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            error: Some(InstanceError {
+                kind: InstanceErrorKind::Unavailable,
+                message: "instance is unavailable".to_string(),
+            }),
+            data: None,
+            uri: url.parse().unwrap_or_default(),
+            elapsed_secs: 0f64.to_string(),
+        }
+    }
+
     async fn perform_request(
         request: ClientRequest,
         body: Bytes,
-    ) -> Result<(String, StatusCode), anyhow::Error> {
+    ) -> Result<(StatusCode, Bytes), anyhow::Error> {
         let mut response = request
             .send_body(body.clone())
             .await
             .map_err(|e| anyhow::Error::msg(e.to_string()))?;
-        let bytes = response.body().await?;
-        let content = str::from_utf8(bytes.as_ref())?.to_string();
-        Ok((content, response.status()))
+        let content = response.body().await?;
+        Ok((response.status(), content))
     }
 }
+
+/// Whether the client asked for the server-sent events instead of the merged json
+pub fn accepts_event_stream(request_head: &RequestHead) -> bool {
+    request_head
+        .headers
+        .get(header::ACCEPT)
+        .and_then(|accept| accept.to_str().ok())
+        .map(|accept| accept.contains("text/event-stream"))
+        .unwrap_or(false)
+}
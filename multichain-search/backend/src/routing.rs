@@ -0,0 +1,77 @@
+use actix_web::http::Method;
+use serde::Deserialize;
+
+/// Routing rule of the requests matching the path prefix.
+/// Rules are applied in order, the first matching one is used for the instance.
+#[derive(Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RouteRule {
+    pub path_prefix: String,
+    /// Ids of the instances the rule is applied to. Applied to all instances if empty.
+    #[serde(default)]
+    pub instances: Vec<String>,
+    /// Allowed methods, requests with other methods are not sent to the instances. All methods are allowed if empty.
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// Replaces the matched prefix of the path
+    #[serde(default)]
+    pub rewrite: Option<String>,
+}
+
+impl RouteRule {
+    fn matches(&self, instance_id: &str, path: &str) -> bool {
+        let applies_to_instance =
+            self.instances.is_empty() || self.instances.iter().any(|id| id == instance_id);
+        applies_to_instance && strip_path_prefix(path, &self.path_prefix).is_some()
+    }
+
+    fn allows(&self, method: &Method) -> bool {
+        self.methods.is_empty()
+            || self
+                .methods
+                .iter()
+                .any(|m| m.eq_ignore_ascii_case(method.as_str()))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Router {
+    rules: Vec<RouteRule>,
+}
+
+impl Router {
+    pub fn new(rules: Vec<RouteRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Returns the path the request should be sent to the instance with,
+    /// or `None` if the request is not allowed for the instance.
+    pub fn route(&self, instance_id: &str, method: &Method, path: &str) -> Option<String> {
+        let rule = match self.rules.iter().find(|r| r.matches(instance_id, path)) {
+            Some(rule) => rule,
+            None => return Some(path.to_string()),
+        };
+        if !rule.allows(method) {
+            return None;
+        }
+        match &rule.rewrite {
+            Some(rewrite) => {
+                let rest = strip_path_prefix(path, &rule.path_prefix)?;
+                let path = format!("{}{rest}", rewrite.trim_end_matches('/'));
+                Some(if path.is_empty() {
+                    "/".to_string()
+                } else {
+                    path
+                })
+            }
+            None => Some(path.to_string()),
+        }
+    }
+}
+
+/// Strips the prefix only at the segment boundary, so that `/api/v2` doesn't match `/api/v20`
+fn strip_path_prefix<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    let prefix = prefix.trim_end_matches('/');
+    let rest = path.strip_prefix(prefix)?;
+    (rest.is_empty() || rest.starts_with('/')).then_some(rest)
+}
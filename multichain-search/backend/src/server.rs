@@ -1,38 +1,71 @@
-pub use crate::settings::{BlockscoutSettings, Settings};
+pub use crate::settings::{BlockscoutSettings, CircuitBreakerSettings, Settings};
 use crate::{
-    instances::get_instances,
+    discovery::run_instances_discovery,
+    instances::{get_instances, get_instances_health},
     proxy::{self, BlockscoutProxy},
 };
 use actix_cors::Cors;
 use actix_web::{
     dev::Server,
+    http::header,
     web,
-    web::{Bytes, Data, Json},
-    App, HttpRequest, HttpServer,
+    web::{Bytes, Data},
+    App, HttpRequest, HttpResponse, HttpServer,
 };
-use std::net::TcpListener;
+use futures::{stream, StreamExt};
+use std::{collections::HashMap, convert::Infallible, net::TcpListener};
 use tracing_actix_web::TracingLogger;
 
 pub async fn handle_request(
     request: HttpRequest,
     proxy: Data<BlockscoutProxy>,
     body: Bytes,
-) -> Json<proxy::Response> {
+) -> HttpResponse {
     let uri = request.uri();
     tracing::info!(uri = ?uri, "Got request");
+    // Could be the case when the discovery has not found any instance yet
+    if !proxy.has_instances() {
+        return HttpResponse::ServiceUnavailable().body("no blockscout instances available");
+    }
+    if !proxy.is_routable(request.method(), uri.path()) {
+        return HttpResponse::MethodNotAllowed().finish();
+    }
+    if proxy::accepts_event_stream(request.head()) {
+        let events = proxy
+            .responses_stream(uri.path_and_query(), body, request.head())
+            .map(|(instance_id, response)| {
+                let data = serde_json::to_string(&proxy::Response(HashMap::from([(
+                    instance_id,
+                    response,
+                )])))
+                .expect("response should be serializable");
+                Ok::<_, Infallible>(Bytes::from(format!("event: response\ndata: {data}\n\n")))
+            })
+            .chain(stream::once(async {
+                Ok(Bytes::from_static(b"event: done\ndata: {}\n\n"))
+            }));
+        return HttpResponse::Ok()
+            .content_type("text/event-stream")
+            .insert_header((header::CACHE_CONTROL, "no-cache"))
+            .streaming(events);
+    }
     let responses = proxy
         .make_requests(uri.path_and_query(), body, request.head())
         .await;
-    Json(responses)
+    HttpResponse::Ok().json(responses)
 }
 
 pub fn run(settings: Settings) -> Result<Server, std::io::Error> {
     let listener = TcpListener::bind(settings.server.addr)?;
-    let proxy = BlockscoutProxy::new(
-        settings.blockscout.instances,
-        settings.blockscout.concurrent_requests,
-        settings.blockscout.request_timeout,
-    );
+    let configured_instances = settings.blockscout.instances.clone();
+    let proxy = BlockscoutProxy::new(settings.blockscout, settings.circuit_breaker);
+    if settings.discovery.enabled {
+        tokio::spawn(run_instances_discovery(
+            proxy.clone(),
+            configured_instances,
+            settings.discovery,
+        ));
+    }
 
     let server = HttpServer::new(move || {
         let cors = Cors::default().allow_any_origin();
@@ -40,7 +73,11 @@ pub fn run(settings: Settings) -> Result<Server, std::io::Error> {
             .wrap(TracingLogger::default())
             .wrap(cors)
             .app_data(Data::new(proxy.clone()))
-            .service(web::scope("/api/v1").route("/instances", web::get().to(get_instances)))
+            .service(
+                web::scope("/api/v1")
+                    .route("/instances", web::get().to(get_instances))
+                    .route("/instances/health", web::get().to(get_instances_health)),
+            )
             .default_service(web::route().to(handle_request))
     })
     .listen(listener)?
@@ -5,7 +5,7 @@ use serde::{de::IgnoredAny, Deserialize};
 use serde_with::{As, DisplayFromStr};
 use std::time;
 
-use crate::proxy::Instance;
+use crate::{proxy::Instance, routing::RouteRule};
 
 impl FromStr for Instance {
     type Err = serde_json::Error;
@@ -14,6 +14,13 @@ impl FromStr for Instance {
     }
 }
 
+impl FromStr for RouteRule {
+    type Err = serde_json::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
 /// Settings for the Blockscout API
 #[serde_with::serde_as]
 #[derive(Deserialize, Clone, Debug)]
@@ -28,6 +35,13 @@ pub struct BlockscoutSettings {
     /// The timeout of waiting for response from the Blockscout API.
     #[serde_as(as = "serde_with::DurationSeconds<u64>")]
     pub request_timeout: time::Duration,
+
+    /// The client headers forwarded to the instances. All headers are forwarded if empty.
+    pub forwarded_headers: Vec<String>,
+
+    /// Routing rules of the requests, e.g. path rewrites for the instances with a different api prefix.
+    #[serde(with = "As::<Vec<DisplayFromStr>>")]
+    pub routes: Vec<RouteRule>,
 }
 
 impl Default for BlockscoutSettings {
@@ -39,6 +53,82 @@ impl Default for BlockscoutSettings {
             ])).expect("invalid default instances"),
             concurrent_requests: 10,
             request_timeout: time::Duration::from_secs(60),
+            forwarded_headers: vec![],
+            routes: vec![],
+        }
+    }
+}
+
+/// Settings of the instances discovery from the blockscout-chains catalog
+#[serde_with::serde_as]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiscoverySettings {
+    pub enabled: bool,
+
+    /// Url of the catalog, the public blockscout-chains catalog is used if not set.
+    pub url: Option<String>,
+
+    /// Only the chains of the ecosystems are discovered. All chains are discovered if empty.
+    pub ecosystems: Vec<String>,
+
+    /// Only the explorers hosted by the providers are discovered. All explorers are discovered if empty.
+    pub hosted_by: Vec<String>,
+
+    pub include_testnets: bool,
+
+    /// The interval between the catalog refreshes.
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub refresh_interval: time::Duration,
+}
+
+impl Default for DiscoverySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: None,
+            ecosystems: vec![],
+            hosted_by: vec!["blockscout".to_string()],
+            include_testnets: true,
+            refresh_interval: time::Duration::from_secs(3600),
+        }
+    }
+}
+
+/// Settings of the circuit breaker excluding failing instances from the aggregation
+#[serde_with::serde_as]
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default, deny_unknown_fields)]
+pub struct CircuitBreakerSettings {
+    pub enabled: bool,
+
+    /// The number of the latest requests to the instance the error rate is calculated over.
+    pub window_size: usize,
+
+    /// The minimal number of requests in the window required to open the circuit.
+    pub min_requests: usize,
+
+    /// The error rate (from 0 to 1) at which the circuit is opened.
+    pub error_rate_threshold: f64,
+
+    /// The time the instance is skipped for before a probe request is sent.
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub open_duration: time::Duration,
+
+    /// Successful requests slower than the threshold are counted as failures. Not applied if not set.
+    #[serde_as(as = "Option<serde_with::DurationSeconds<u64>>")]
+    pub slow_request_threshold: Option<time::Duration>,
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_size: 20,
+            min_requests: 5,
+            error_rate_threshold: 0.5,
+            open_duration: time::Duration::from_secs(30),
+            slow_request_threshold: None,
         }
     }
 }
@@ -78,6 +168,8 @@ impl Default for JaegerSettings {
 pub struct Settings {
     pub server: ServerSettings,
     pub blockscout: BlockscoutSettings,
+    pub discovery: DiscoverySettings,
+    pub circuit_breaker: CircuitBreakerSettings,
     pub jaeger: JaegerSettings,
 
     // Is required as we deny unknown fields, but allow users provide
@@ -99,11 +191,48 @@ impl Settings {
             .try_parsing(true)
             .separator("__")
             .list_separator(";")
-            .with_list_parse_key("blockscout.instances");
+            .with_list_parse_key("blockscout.instances")
+            .with_list_parse_key("blockscout.forwarded_headers")
+            .with_list_parse_key("blockscout.routes")
+            .with_list_parse_key("discovery.ecosystems")
+            .with_list_parse_key("discovery.hosted_by");
         builder = builder.add_source(environment);
 
         let settings: Settings = builder.build()?.try_deserialize()?;
+        settings.validate()?;
 
         Ok(settings)
     }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.discovery.enabled && self.discovery.refresh_interval.is_zero() {
+            anyhow::bail!("discovery refresh interval must be non-zero");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_discovery_refresh_interval_is_rejected() {
+        let mut settings = Settings::default();
+        settings.discovery.refresh_interval = time::Duration::ZERO;
+        // The interval is not used while the discovery is disabled
+        settings
+            .validate()
+            .expect("disabled discovery should be valid");
+
+        settings.discovery.enabled = true;
+        settings
+            .validate()
+            .expect_err("zero refresh interval should be rejected");
+
+        settings.discovery.refresh_interval = time::Duration::from_secs(1);
+        settings
+            .validate()
+            .expect("non-zero interval should be valid");
+    }
 }
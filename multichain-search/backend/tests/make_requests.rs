@@ -1,44 +1,77 @@
-use actix_web::{http::StatusCode, test, web, web::Data, App};
+use actix_web::{
+    body::MessageBody,
+    dev::{Service, ServiceResponse},
+    http::StatusCode,
+    test, web,
+    web::Data,
+    App,
+};
 use multichain_search::{proxy, server, Settings};
 use pretty_assertions::assert_eq;
 use serde_json::json;
+use std::collections::BTreeMap;
 use wiremock::{
-    matchers::{method, path},
+    matchers::{header, method, path, query_param},
     Mock, MockServer, ResponseTemplate,
 };
 
+fn instance(id: &str, url: &str) -> proxy::Instance {
+    proxy::Instance {
+        id: id.to_string(),
+        title: format!("Mocked {id}"),
+        url: url.parse().unwrap(),
+        headers: Default::default(),
+        api_key: None,
+    }
+}
+
+/// Instances are served by the mock server under `/poa/{id}`
+fn settings_with_instances(server_host: &str, ids: &[&str]) -> Settings {
+    let mut settings = Settings::default();
+    settings.blockscout.instances = ids
+        .iter()
+        .map(|id| instance(id, &format!("{server_host}/poa/{id}")))
+        .collect();
+    settings
+}
+
+/// Mocks the `/api/v1/my_name` endpoint of the instance returning its name
+async fn mock_name(mock_server: &MockServer, name: &str) {
+    Mock::given(method("GET"))
+        .and(path(format!("poa/{name}/api/v1/my_name")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "name": name })))
+        .mount(mock_server)
+        .await;
+}
+
+async fn init_app(
+    settings: Settings,
+) -> impl Service<
+    actix_http::Request,
+    Response = ServiceResponse<impl MessageBody>,
+    Error = actix_web::Error,
+> {
+    let proxy = proxy::BlockscoutProxy::new(settings.blockscout, settings.circuit_breaker);
+    test::init_service(
+        App::new()
+            .app_data(Data::new(proxy))
+            .default_service(web::route().to(server::handle_request)),
+    )
+    .await
+}
+
 #[actix_web::test]
 async fn check_make_requests() {
     let mock_server = MockServer::start().await;
     let names = vec!["blockscout-1", "blockscout-2", "blockscout-3"];
 
     for name in names.iter() {
-        Mock::given(method("GET"))
-            .and(path(format!("poa/{name}/api/v1/my_name")))
-            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "name": name })))
-            .mount(&mock_server)
-            .await;
+        mock_name(&mock_server, name).await;
     }
     let server_host = mock_server.uri();
-    let mut settings = Settings::default();
-    settings.blockscout.instances = serde_json::from_value(serde_json::json!([
-        {"title": "Mocked blockscout 1", "url": format!("{server_host}/poa/blockscout-1"), "id": "blockscout-1"},
-        {"title": "Mocked blockscout 2", "url": format!("{server_host}/poa/blockscout-2"), "id": "blockscout-2"},
-        {"title": "Mocked blockscout 3", "url": format!("{server_host}/poa/blockscout-3"), "id": "blockscout-3"},
-    ])).unwrap();
-
-    let proxy = proxy::BlockscoutProxy::new(
-        settings.blockscout.instances,
-        settings.blockscout.concurrent_requests,
-        settings.blockscout.request_timeout,
-    );
+    let settings = settings_with_instances(&server_host, &names);
 
-    let app = test::init_service(
-        App::new()
-            .app_data(Data::new(proxy.clone()))
-            .default_service(web::route().to(server::handle_request)),
-    )
-    .await;
+    let app = init_app(settings).await;
 
     let path = "/api/v1/my_name";
 
@@ -55,9 +88,242 @@ async fn check_make_requests() {
             format!("{server_host}/poa/{name}/api/v1/my_name")
         );
         assert_eq!(instance_response.instance.id, name);
-        assert_eq!(
-            instance_response.content,
-            json!({ "name": name }).to_string()
-        );
+        assert_eq!(instance_response.error, None);
+        assert_eq!(instance_response.data, Some(json!({ "name": name })));
+    }
+}
+
+#[actix_web::test]
+async fn check_circuit_breaker_skips_failing_instance() {
+    let mock_server = MockServer::start().await;
+    mock_name(&mock_server, "healthy").await;
+    Mock::given(method("GET"))
+        .and(path("poa/failing/api/v1/my_name"))
+        .respond_with(ResponseTemplate::new(500))
+        // the instance is skipped as soon as the circuit is opened
+        .expect(3)
+        .mount(&mock_server)
+        .await;
+
+    let server_host = mock_server.uri();
+    let mut settings = settings_with_instances(&server_host, &["healthy", "failing"]);
+    settings.circuit_breaker.min_requests = 3;
+
+    let app = init_app(settings).await;
+
+    for i in 0..5 {
+        let get_request = test::TestRequest::get().uri("/api/v1/my_name").to_request();
+        let actual_response: proxy::Response =
+            test::call_and_read_body_json(&app, get_request).await;
+
+        let healthy = actual_response.0.get("healthy").unwrap();
+        assert_eq!(healthy.status, StatusCode::OK);
+        assert_eq!(healthy.error, None);
+
+        let failing = actual_response.0.get("failing").unwrap();
+        if i < 3 {
+            assert_eq!(failing.status, StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(
+                failing.error.as_ref().map(|e| e.kind),
+                Some(proxy::InstanceErrorKind::Upstream)
+            );
+        } else {
+            assert_eq!(failing.status, StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(
+                failing.error.as_ref().map(|e| e.kind),
+                Some(proxy::InstanceErrorKind::Unavailable)
+            );
+        }
     }
 }
+
+#[actix_web::test]
+async fn check_make_requests_event_stream() {
+    let mock_server = MockServer::start().await;
+    let names = vec!["blockscout-1", "blockscout-2"];
+
+    for name in names.iter() {
+        Mock::given(method("GET"))
+            .and(path(format!("poa/{name}/api/v1/my_name")))
+            .and(header("accept", "application/json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "name": name })))
+            .mount(&mock_server)
+            .await;
+    }
+    let server_host = mock_server.uri();
+    let settings = settings_with_instances(&server_host, &names);
+
+    let app = init_app(settings).await;
+
+    let get_request = test::TestRequest::get()
+        .uri("/api/v1/my_name")
+        .insert_header(("accept", "text/event-stream"))
+        .to_request();
+    let body = test::call_and_read_body(&app, get_request).await;
+    let body = std::str::from_utf8(&body).unwrap();
+
+    let events = body
+        .split("\n\n")
+        .filter(|event| !event.is_empty())
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), names.len() + 1);
+    assert_eq!(events.last().unwrap(), &"event: done\ndata: {}");
+
+    let mut responses = proxy::Response(Default::default());
+    for event in &events[..names.len()] {
+        let data = event
+            .strip_prefix("event: response\ndata: ")
+            .unwrap_or_else(|| panic!("invalid event: {event}"));
+        let response: proxy::Response = serde_json::from_str(data).unwrap();
+        assert_eq!(response.0.len(), 1);
+        responses.0.extend(response.0);
+    }
+    for name in names {
+        let instance_response = responses
+            .0
+            .get(name)
+            .unwrap_or_else(|| panic!("response for {name} not found"));
+        assert_eq!(instance_response.status, StatusCode::OK);
+        assert_eq!(instance_response.error, None);
+        assert_eq!(instance_response.data, Some(json!({ "name": name })));
+    }
+}
+
+#[actix_web::test]
+async fn check_instance_credentials() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("poa/private/api/v1/my_name"))
+        .and(header("authorization", "Bearer secret"))
+        .and(query_param("apikey", "secret-key"))
+        .and(header("x-client", "client"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "name": "private" })))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let server_host = mock_server.uri();
+    let mut settings = settings_with_instances(&server_host, &["private"]);
+    settings.blockscout.instances[0].headers =
+        BTreeMap::from([("Authorization".to_string(), "Bearer secret".to_string())]);
+    settings.blockscout.instances[0].api_key = Some("secret-key".to_string());
+    settings.blockscout.forwarded_headers = vec!["x-client".to_string()];
+
+    let app = init_app(settings).await;
+
+    let get_request = test::TestRequest::get()
+        .uri("/api/v1/my_name")
+        .insert_header(("x-client", "client"))
+        .to_request();
+    let body = test::call_and_read_body(&app, get_request).await;
+    let body = std::str::from_utf8(&body).unwrap();
+    assert!(!body.contains("secret"), "credentials leaked: {body}");
+
+    let actual_response: proxy::Response = serde_json::from_str(body).unwrap();
+    let instance_response = actual_response.0.get("private").unwrap();
+    assert_eq!(instance_response.status, StatusCode::OK);
+    assert_eq!(
+        instance_response.uri.to_string(),
+        format!("{server_host}/poa/private/api/v1/my_name")
+    );
+}
+
+#[actix_web::test]
+async fn check_error_envelope() {
+    let mock_server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("poa/not-found/api/v1/my_name"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({ "message": "Not found" })))
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("poa/invalid/api/v1/my_name"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+        .mount(&mock_server)
+        .await;
+
+    let server_host = mock_server.uri();
+    let settings = settings_with_instances(&server_host, &["not-found", "invalid"]);
+
+    let app = init_app(settings).await;
+
+    let get_request = test::TestRequest::get().uri("/api/v1/my_name").to_request();
+    let actual_response: proxy::Response = test::call_and_read_body_json(&app, get_request).await;
+
+    let not_found = actual_response.0.get("not-found").unwrap();
+    assert_eq!(not_found.status, StatusCode::NOT_FOUND);
+    assert_eq!(
+        not_found.error.as_ref().map(|e| e.kind),
+        Some(proxy::InstanceErrorKind::Upstream)
+    );
+    assert_eq!(not_found.data, Some(json!({ "message": "Not found" })));
+
+    let invalid = actual_response.0.get("invalid").unwrap();
+    assert_eq!(invalid.status, StatusCode::OK);
+    assert_eq!(
+        invalid.error.as_ref().map(|e| e.kind),
+        Some(proxy::InstanceErrorKind::InvalidResponse)
+    );
+    assert_eq!(invalid.data, None);
+}
+
+#[actix_web::test]
+async fn check_routing_rules() {
+    let mock_server = MockServer::start().await;
+    mock_name(&mock_server, "default").await;
+    Mock::given(method("GET"))
+        .and(path("custom/api-prefix/v1/my_name"))
+        .and(query_param("q", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "name": "custom" })))
+        .mount(&mock_server)
+        .await;
+
+    let server_host = mock_server.uri();
+    let mut settings = settings_with_instances(&server_host, &["default"]);
+    settings
+        .blockscout
+        .instances
+        .push(instance("custom", &format!("{server_host}/custom")));
+    settings.blockscout.routes = serde_json::from_value(serde_json::json!([
+        {"path_prefix": "/api", "instances": ["custom"], "methods": ["GET"], "rewrite": "/api-prefix"},
+        {"path_prefix": "/", "methods": ["GET"]},
+    ]))
+    .unwrap();
+
+    let app = init_app(settings).await;
+
+    let get_request = test::TestRequest::get()
+        .uri("/api/v1/my_name?q=1")
+        .to_request();
+    let actual_response: proxy::Response = test::call_and_read_body_json(&app, get_request).await;
+    for name in ["default", "custom"] {
+        let instance_response = actual_response
+            .0
+            .get(name)
+            .unwrap_or_else(|| panic!("response for {name} not found"));
+        assert_eq!(instance_response.status, StatusCode::OK);
+        assert_eq!(instance_response.data, Some(json!({ "name": name })));
+    }
+    assert_eq!(
+        actual_response.0.get("custom").unwrap().uri.to_string(),
+        format!("{server_host}/custom/api-prefix/v1/my_name?q=1")
+    );
+
+    let post_request = test::TestRequest::post()
+        .uri("/api/v1/my_name")
+        .to_request();
+    let response = test::call_service(&app, post_request).await;
+    assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+}
+
+#[actix_web::test]
+async fn check_no_instances() {
+    let mut settings = Settings::default();
+    settings.blockscout.instances = vec![];
+
+    let app = init_app(settings).await;
+
+    let request = test::TestRequest::get().uri("/api/v1/my_name").to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
@@ -10,9 +10,9 @@ interface Props {
 
 export const ResultTable = ({responses}: Props) => {
     let corrent_responses = responses
-        .filter((r) => r.status == 200)
+        .filter((r) => r.status == 200 && r.error === null && r.data !== null)
         .map((r) => {
-            let response = JSON.parse(r.content) as InstanceSearchResponse
+            let response = r.data as InstanceSearchResponse
 
             return {
                 instance: r.instance, 
@@ -10,10 +10,18 @@ title: string,
 url: string
 }
 
+export type InstanceErrorKind = 'unavailable' | 'request_failed' | 'upstream' | 'invalid_response';
+
+export interface InstanceError {
+    kind: InstanceErrorKind,
+    message: string,
+}
+
 export interface InstanceResponse {
     instance: Instance,
-    content: string,
     status: number
+    error: InstanceError | null,
+    data: unknown | null,
     uri: string,
     elapsed_secs: number
 }
@@ -0,0 +1,254 @@
+use entity::api_keys::{ActiveModel, Column, Entity, Model};
+use sea_orm::{
+    prelude::Uuid, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DbErr,
+    EntityTrait, QueryFilter, QueryOrder, Statement,
+};
+use sha2::{Digest, Sha256};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuotaCheck {
+    /// Usage has been recorded; `remaining` is `None` for the keys without a daily limit
+    Allowed { remaining: Option<i64> },
+    /// The key does not exist or has been deactivated
+    InvalidKey,
+    /// Recording the usage would exceed the daily limit of the key
+    Exceeded { daily_limit: i64 },
+}
+
+/// Only the hash of the key is stored, so the key itself is returned just once, on creation
+pub async fn create<C>(
+    db: &C,
+    name: String,
+    daily_limit: Option<i64>,
+) -> Result<(Model, String), DbErr>
+where
+    C: ConnectionTrait,
+{
+    let key = Uuid::new_v4().simple().to_string();
+    let model = ActiveModel {
+        key_hash: Set(hash_key(&key)),
+        name: Set(name),
+        daily_limit: Set(daily_limit),
+        is_active: Set(true),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+    Ok((model, key))
+}
+
+pub async fn list<C>(db: &C) -> Result<Vec<Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Entity::find().order_by_asc(Column::Id).all(db).await
+}
+
+pub async fn get<C>(db: &C, id: i64) -> Result<Option<Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Entity::find_by_id(id).one(db).await
+}
+
+/// Updates the provided fields of the key. Returns `None` if the key does not exist.
+/// `daily_limit` set to `Some(None)` removes the limit.
+pub async fn update<C>(
+    db: &C,
+    id: i64,
+    daily_limit: Option<Option<i64>>,
+    is_active: Option<bool>,
+) -> Result<Option<Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let model = match get(db, id).await? {
+        Some(model) => model,
+        None => return Ok(None),
+    };
+    let mut active_model: ActiveModel = model.into();
+    if let Some(daily_limit) = daily_limit {
+        active_model.daily_limit = Set(daily_limit);
+    }
+    if let Some(is_active) = is_active {
+        active_model.is_active = Set(is_active);
+    }
+    active_model.updated_at = Set(chrono::Utc::now().naive_utc());
+    active_model.update(db).await.map(Some)
+}
+
+/// Returns `false` if the key does not exist
+pub async fn delete<C>(db: &C, id: i64) -> Result<bool, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let result = Entity::delete_by_id(id).exec(db).await?;
+    Ok(result.rows_affected > 0)
+}
+
+/// Number of verifications made with the key today (UTC)
+pub async fn today_usage<C>(db: &C, id: i64) -> Result<i64, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let usage = entity::api_key_usages::Entity::find_by_id((id, today()))
+        .one(db)
+        .await?;
+    Ok(usage.map(|usage| usage.verifications).unwrap_or_default())
+}
+
+/// Checks that the key is active and records `verifications` usages against its daily limit.
+/// Usage is not recorded if the limit would be exceeded.
+pub async fn consume<C>(db: &C, key: &str, verifications: i64) -> Result<QuotaCheck, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let api_key = Entity::find()
+        .filter(Column::KeyHash.eq(hash_key(key)))
+        .filter(Column::IsActive.eq(true))
+        .one(db)
+        .await?;
+    let api_key = match api_key {
+        Some(api_key) => api_key,
+        None => return Ok(QuotaCheck::InvalidKey),
+    };
+
+    // The limit is checked inside the upsert so that concurrent requests cannot exceed it
+    let statement = Statement::from_sql_and_values(
+        db.get_database_backend(),
+        r#"
+            INSERT INTO "api_key_usages" ("api_key_id", "day", "verifications")
+            SELECT $1::bigint, $2::date, $3::bigint
+            WHERE $4::bigint IS NULL OR $3 <= $4::bigint
+            ON CONFLICT ("api_key_id", "day") DO UPDATE
+            SET "verifications" = "api_key_usages"."verifications" + EXCLUDED."verifications"
+            WHERE $4::bigint IS NULL OR "api_key_usages"."verifications" + EXCLUDED."verifications" <= $4::bigint
+            RETURNING "verifications"
+        "#,
+        [
+            api_key.id.into(),
+            today().into(),
+            verifications.into(),
+            api_key.daily_limit.into(),
+        ],
+    );
+    let row = db.query_one(statement).await?;
+
+    match (row, api_key.daily_limit) {
+        (Some(row), daily_limit) => {
+            let used: i64 = row.try_get("", "verifications")?;
+            Ok(QuotaCheck::Allowed {
+                remaining: daily_limit.map(|limit| limit - used),
+            })
+        }
+        (None, Some(daily_limit)) => Ok(QuotaCheck::Exceeded { daily_limit }),
+        (None, None) => Err(DbErr::RecordNotInserted),
+    }
+}
+
+fn hash_key(key: &str) -> Vec<u8> {
+    Sha256::digest(key.as_bytes()).to_vec()
+}
+
+fn today() -> chrono::NaiveDate {
+    chrono::Utc::now().date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockscout_service_launcher::test_database::TestDbGuard;
+    use pretty_assertions::assert_eq;
+
+    async fn init_db(test_name: &str) -> TestDbGuard {
+        TestDbGuard::new::<migration::Migrator>(&format!("api_keys_{test_name}")).await
+    }
+
+    #[tokio::test]
+    #[ignore = "Needs database to run"]
+    async fn consume_respects_daily_limit() {
+        let db = init_db("consume_respects_daily_limit").await;
+        let db = db.client();
+
+        let (api_key, key) = create(db.as_ref(), "limited".to_string(), Some(5))
+            .await
+            .unwrap();
+        assert_ne!(api_key.key_hash, key.as_bytes());
+
+        assert_eq!(
+            QuotaCheck::Allowed { remaining: Some(2) },
+            consume(db.as_ref(), &key, 3).await.unwrap()
+        );
+        // Exceeding requests are not recorded
+        assert_eq!(
+            QuotaCheck::Exceeded { daily_limit: 5 },
+            consume(db.as_ref(), &key, 3).await.unwrap()
+        );
+        assert_eq!(3, today_usage(db.as_ref(), api_key.id).await.unwrap());
+        assert_eq!(
+            QuotaCheck::Allowed { remaining: Some(0) },
+            consume(db.as_ref(), &key, 2).await.unwrap()
+        );
+        assert_eq!(
+            QuotaCheck::Exceeded { daily_limit: 5 },
+            consume(db.as_ref(), &key, 1).await.unwrap()
+        );
+        assert_eq!(5, today_usage(db.as_ref(), api_key.id).await.unwrap());
+
+        // A single request over the limit is rejected even if nothing has been used yet
+        let (_, other_key) = create(db.as_ref(), "other".to_string(), Some(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            QuotaCheck::Exceeded { daily_limit: 1 },
+            consume(db.as_ref(), &other_key, 2).await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "Needs database to run"]
+    async fn consume_without_limit() {
+        let db = init_db("consume_without_limit").await;
+        let db = db.client();
+
+        let (api_key, key) = create(db.as_ref(), "unlimited".to_string(), None)
+            .await
+            .unwrap();
+        for _ in 0..3 {
+            assert_eq!(
+                QuotaCheck::Allowed { remaining: None },
+                consume(db.as_ref(), &key, 1000).await.unwrap()
+            );
+        }
+        assert_eq!(3000, today_usage(db.as_ref(), api_key.id).await.unwrap());
+    }
+
+    #[tokio::test]
+    #[ignore = "Needs database to run"]
+    async fn consume_rejects_invalid_keys() {
+        let db = init_db("consume_rejects_invalid_keys").await;
+        let db = db.client();
+
+        let (api_key, key) = create(db.as_ref(), "key".to_string(), None).await.unwrap();
+        assert_eq!(
+            QuotaCheck::InvalidKey,
+            consume(db.as_ref(), "unknown", 1).await.unwrap()
+        );
+        // The stored hash is not accepted as the key
+        assert_eq!(
+            QuotaCheck::InvalidKey,
+            consume(db.as_ref(), &hex::encode(api_key.key_hash.as_slice()), 1)
+                .await
+                .unwrap()
+        );
+
+        update(db.as_ref(), api_key.id, None, Some(false))
+            .await
+            .unwrap();
+        assert_eq!(
+            QuotaCheck::InvalidKey,
+            consume(db.as_ref(), &key, 1).await.unwrap()
+        );
+        assert_eq!(0, today_usage(db.as_ref(), api_key.id).await.unwrap());
+    }
+}
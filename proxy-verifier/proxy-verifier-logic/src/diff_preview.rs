@@ -0,0 +1,269 @@
+use crate::{address_details::AddressDetails, Error};
+use ethers_core::types;
+use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2 as smart_contract_verifier_v2;
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SegmentKind {
+    /// Part of the on-chain code equal to the compiled one
+    Match,
+    /// Immutable variable values, which are set during the contract deployment
+    Immutable,
+    /// Auxdata appended by the compiler (e.g., metadata hash)
+    Metadata,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodeSegment {
+    pub kind: SegmentKind,
+    pub offset: usize,
+    /// On-chain value of the segment
+    pub value: types::Bytes,
+    /// Value of the segment in the locally compiled code. Set only for the metadata segments
+    pub compiled_value: Option<types::Bytes>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractDiff {
+    pub match_type: smart_contract_verifier_v2::source::MatchType,
+    /// Full match means the metadata hash of the compiled contract is the same as on-chain one
+    pub metadata_matches: bool,
+    pub runtime_code_segments: Vec<CodeSegment>,
+    /// Exists only if the contract was matched against its creation code
+    pub constructor_arguments: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PreviewResponse {
+    CompilationFailed(Error),
+    InvalidContracts(Vec<Option<Error>>),
+    Results(Vec<Result<ContractDiff, Error>>),
+}
+
+pub(crate) fn contract_diff(
+    contract_details: &AddressDetails,
+    source: &smart_contract_verifier_v2::Source,
+    extra_data: &smart_contract_verifier_v2::verify_response::ExtraData,
+) -> ContractDiff {
+    let immutable_references = source
+        .deployed_bytecode_artifacts
+        .as_deref()
+        .map(parse_immutable_references)
+        .unwrap_or_default();
+    let compiled_metadata = compiled_metadata(&extra_data.local_deployed_bytecode_parts);
+
+    let match_type = source.match_type();
+    ContractDiff {
+        match_type,
+        metadata_matches: match_type == smart_contract_verifier_v2::source::MatchType::Full,
+        runtime_code_segments: runtime_code_segments(
+            &contract_details.runtime_code,
+            immutable_references,
+            compiled_metadata,
+        ),
+        constructor_arguments: source.constructor_arguments.clone(),
+    }
+}
+
+/// Restores the locally compiled runtime code from its parts and returns its auxdata
+fn compiled_metadata(
+    parts: &[smart_contract_verifier_v2::verify_response::extra_data::BytecodePart],
+) -> Option<types::Bytes> {
+    let mut code = vec![];
+    for part in parts {
+        match hex::decode(part.data.trim_start_matches("0x")) {
+            Ok(data) => code.extend(data),
+            Err(err) => {
+                tracing::warn!("invalid compiled bytecode part: {err}");
+                return None;
+            }
+        }
+    }
+    metadata_offset(&code).map(|offset| types::Bytes::from(code[offset..].to_vec()))
+}
+
+/// Returns (offset, length) pairs of the immutable values inside the runtime code
+fn parse_immutable_references(deployed_bytecode_artifacts: &str) -> Vec<(usize, usize)> {
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Artifacts {
+        #[serde(default)]
+        immutable_references: BTreeMap<String, Vec<ImmutableReference>>,
+    }
+    #[derive(serde::Deserialize)]
+    struct ImmutableReference {
+        start: usize,
+        length: usize,
+    }
+
+    match serde_json::from_str::<Artifacts>(deployed_bytecode_artifacts) {
+        Ok(artifacts) => artifacts
+            .immutable_references
+            .into_values()
+            .flatten()
+            .map(|reference| (reference.start, reference.length))
+            .collect(),
+        Err(err) => {
+            tracing::warn!("invalid deployed bytecode artifacts: {err}");
+            vec![]
+        }
+    }
+}
+
+/// Splits the runtime code into matching, immutable and metadata segments
+fn runtime_code_segments(
+    runtime_code: &types::Bytes,
+    mut immutable_references: Vec<(usize, usize)>,
+    compiled_metadata: Option<types::Bytes>,
+) -> Vec<CodeSegment> {
+    let code = runtime_code.as_ref();
+    let metadata_offset = metadata_offset(code).unwrap_or(code.len());
+
+    immutable_references.sort();
+    let mut regions = immutable_references
+        .into_iter()
+        .filter(|(offset, length)| offset + length <= metadata_offset)
+        .map(|(offset, length)| (SegmentKind::Immutable, offset, length))
+        .collect::<Vec<_>>();
+    if metadata_offset < code.len() {
+        regions.push((
+            SegmentKind::Metadata,
+            metadata_offset,
+            code.len() - metadata_offset,
+        ));
+    }
+
+    let segment = |kind, offset: usize, length: usize| CodeSegment {
+        kind,
+        offset,
+        value: types::Bytes::from(code[offset..offset + length].to_vec()),
+        compiled_value: match kind {
+            SegmentKind::Metadata => compiled_metadata.clone(),
+            _ => None,
+        },
+    };
+
+    let mut segments = vec![];
+    let mut position = 0;
+    for (kind, offset, length) in regions {
+        // Skip overlapping references
+        if offset < position {
+            continue;
+        }
+        if offset > position {
+            segments.push(segment(SegmentKind::Match, position, offset - position));
+        }
+        segments.push(segment(kind, offset, length));
+        position = offset + length;
+    }
+    if position < code.len() {
+        segments.push(segment(SegmentKind::Match, position, code.len() - position));
+    }
+    segments
+}
+
+/// Compilers append CBOR encoded auxdata followed by its 2-bytes length to the end of the code
+fn metadata_offset(code: &[u8]) -> Option<usize> {
+    let length_bytes = code.len().checked_sub(2).map(|start| &code[start..])?;
+    let length = u16::from_be_bytes([length_bytes[0], length_bytes[1]]) as usize;
+    let offset = code.len().checked_sub(2 + length)?;
+    // Auxdata is expected to be a non-empty CBOR map
+    matches!(code.get(offset), Some(0xa1..=0xb7 | 0xbf)).then_some(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn to_bytes(value: &str) -> types::Bytes {
+        types::Bytes::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn runtime_code_segments_split() {
+        // code: 6080 | immutable: aabb | code: 6000 | metadata: a1 6474657374 00 | length: 0007
+        let runtime_code = to_bytes("0x6080aabb6000a16474657374000007");
+
+        let segments = runtime_code_segments(
+            &runtime_code,
+            vec![(2, 2)],
+            Some(to_bytes("0xa16474657374010007")),
+        );
+        assert_eq!(
+            vec![
+                CodeSegment {
+                    kind: SegmentKind::Match,
+                    offset: 0,
+                    value: to_bytes("0x6080"),
+                    compiled_value: None,
+                },
+                CodeSegment {
+                    kind: SegmentKind::Immutable,
+                    offset: 2,
+                    value: to_bytes("0xaabb"),
+                    compiled_value: None,
+                },
+                CodeSegment {
+                    kind: SegmentKind::Match,
+                    offset: 4,
+                    value: to_bytes("0x6000"),
+                    compiled_value: None,
+                },
+                CodeSegment {
+                    kind: SegmentKind::Metadata,
+                    offset: 6,
+                    value: to_bytes("0xa16474657374000007"),
+                    compiled_value: Some(to_bytes("0xa16474657374010007")),
+                },
+            ],
+            segments
+        );
+    }
+
+    #[test]
+    fn runtime_code_segments_without_metadata() {
+        let runtime_code = to_bytes("0x60806040");
+
+        let segments = runtime_code_segments(&runtime_code, vec![], None);
+        assert_eq!(
+            vec![CodeSegment {
+                kind: SegmentKind::Match,
+                offset: 0,
+                value: runtime_code,
+                compiled_value: None,
+            }],
+            segments
+        );
+    }
+
+    #[test]
+    fn compiled_metadata_from_parts() {
+        let part = |r#type: &str, data: &str| {
+            smart_contract_verifier_v2::verify_response::extra_data::BytecodePart {
+                r#type: r#type.to_string(),
+                data: data.to_string(),
+            }
+        };
+        let parts = vec![
+            part("main", "0x6080aabb6000"),
+            part("meta", "0xa16474657374010007"),
+        ];
+
+        assert_eq!(
+            Some(to_bytes("0xa16474657374010007")),
+            compiled_metadata(&parts)
+        );
+        assert_eq!(None, compiled_metadata(&[part("main", "0x60806040")]));
+    }
+
+    #[test]
+    fn immutable_references_parsing() {
+        let artifacts = r#"{"immutableReferences":{"7":[{"start":10,"length":32},{"start":100,"length":32}],"9":[{"start":50,"length":32}]},"sourceMap":""}"#;
+
+        let mut references = parse_immutable_references(artifacts);
+        references.sort();
+        assert_eq!(vec![(10, 32), (50, 32), (100, 32)], references);
+    }
+}
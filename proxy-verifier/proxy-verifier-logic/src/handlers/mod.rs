@@ -7,10 +7,14 @@ pub mod vyper_verifier_standard_json;
 /************************************************/
 
 use crate::{
-    address_details, address_details::AddressDetails, to_hex::ToHex, Error, VerificationResponse,
-    VerificationSuccess,
+    address_details,
+    address_details::AddressDetails,
+    diff_preview::{self, PreviewResponse},
+    to_hex::ToHex,
+    Error, VerificationResponse, VerificationSuccess,
 };
 use eth_bytecode_db_proto::blockscout::eth_bytecode_db::v2 as eth_bytecode_db_v2;
+use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2 as smart_contract_verifier_v2;
 use std::future::Future;
 
 async fn process_verification_request<'a, Request, RequestBuilder, Verify, VerifyOutput>(
@@ -23,7 +27,7 @@ where
     RequestBuilder: Fn(
             ethers_core::types::Bytes,
             eth_bytecode_db_v2::BytecodeType,
-            eth_bytecode_db_v2::VerificationMetadata,
+            Option<eth_bytecode_db_v2::VerificationMetadata>,
         ) -> Request
         + Clone,
     Verify: Fn(&'a eth_bytecode_db_proto::http_client::Client, Request) -> VerifyOutput + Clone,
@@ -48,9 +52,13 @@ where
         )
         .await;
         match result {
-            Ok(match_type) => {
+            Ok(source) => {
                 let search_result = search_contract(blockscout_client, contract_address).await;
-                let result = search_result.map(|url| VerificationSuccess { url, match_type });
+                let result = search_result.map(|url| VerificationSuccess {
+                    url,
+                    match_type: source.match_type(),
+                    source,
+                });
                 results.push(result)
             }
             Err(err) if err.is_compilation_failed_error() => {
@@ -63,9 +71,80 @@ where
     VerificationResponse::Results(results)
 }
 
+/// Compiles the sources against the on-chain code of the contracts without saving the results.
+/// The request is sent directly to the smart-contract-verifier, so that nothing is stored
+/// in eth-bytecode-db or the verifier alliance database, and no contracts are imported into blockscout.
+async fn process_preview_request<'a, Request, RequestBuilder, Verify, VerifyOutput>(
+    verifier_client: &'a smart_contract_verifier_proto::http_client::Client,
+    contracts: Vec<(&blockscout_client::Client, ethers_core::types::Address)>,
+    request_builder: RequestBuilder,
+    verify: Verify,
+) -> PreviewResponse
+where
+    RequestBuilder:
+        Fn(ethers_core::types::Bytes, smart_contract_verifier_v2::BytecodeType) -> Request,
+    Verify: Fn(&'a smart_contract_verifier_proto::http_client::Client, Request) -> VerifyOutput,
+    VerifyOutput: Future<
+        Output = smart_contract_verifier_proto::http_client::Result<
+            smart_contract_verifier_v2::VerifyResponse,
+        >,
+    >,
+{
+    let contract_details = address_details::batch_retrieve_address_details(&contracts).await;
+    if let Some(statuses) = invalid_contracts_statuses(&contract_details) {
+        return PreviewResponse::InvalidContracts(statuses);
+    }
+
+    let mut results = vec![];
+    for contract_details in contract_details {
+        let contract_details = match contract_details {
+            Ok(details) => details,
+            Err(err) => {
+                results.push(Err(err));
+                continue;
+            }
+        };
+
+        let (bytecode, bytecode_type) = bytecode_to_verify(
+            contract_details.creation_code.clone(),
+            contract_details.runtime_code.clone(),
+        );
+        let bytecode_type = match bytecode_type {
+            eth_bytecode_db_v2::BytecodeType::CreationInput => {
+                smart_contract_verifier_v2::BytecodeType::CreationInput
+            }
+            _ => smart_contract_verifier_v2::BytecodeType::DeployedBytecode,
+        };
+        let verifier_response =
+            verify(verifier_client, request_builder(bytecode, bytecode_type)).await;
+        let result = process_preview_response(
+            &contract_details.chain_id,
+            contract_details.address,
+            verifier_response,
+        )
+        .map(|(source, extra_data)| {
+            diff_preview::contract_diff(&contract_details, &source, &extra_data)
+        });
+        match result {
+            Err(err) if err.is_compilation_failed_error() => {
+                return PreviewResponse::CompilationFailed(err)
+            }
+            result => results.push(result),
+        }
+    }
+
+    PreviewResponse::Results(results)
+}
+
 fn check_invalid_contracts(
     contract_details: &[Result<AddressDetails, Error>],
 ) -> Option<VerificationResponse> {
+    invalid_contracts_statuses(contract_details).map(VerificationResponse::InvalidContracts)
+}
+
+fn invalid_contracts_statuses(
+    contract_details: &[Result<AddressDetails, Error>],
+) -> Option<Vec<Option<Error>>> {
     let has_invalid_contracts = contract_details.iter().any(|details| {
         details
             .as_ref()
@@ -81,7 +160,7 @@ fn check_invalid_contracts(
             })
             .collect();
 
-        Some(VerificationResponse::InvalidContracts(validation_statuses))
+        Some(validation_statuses)
     } else {
         None
     }
@@ -92,12 +171,12 @@ async fn verify_contract<'a, Request, RequestBuilder, Verify, VerifyOutput>(
     contract_details: Result<AddressDetails, Error>,
     request_builder: RequestBuilder,
     verify: Verify,
-) -> Result<eth_bytecode_db_v2::source::MatchType, Error>
+) -> Result<eth_bytecode_db_v2::Source, Error>
 where
     RequestBuilder: Fn(
         ethers_core::types::Bytes,
         eth_bytecode_db_v2::BytecodeType,
-        eth_bytecode_db_v2::VerificationMetadata,
+        Option<eth_bytecode_db_v2::VerificationMetadata>,
     ) -> Request,
     Verify: Fn(&'a eth_bytecode_db_proto::http_client::Client, Request) -> VerifyOutput,
     VerifyOutput: Future<
@@ -126,15 +205,8 @@ where
                 runtime_code: Some(runtime_code.to_hex()),
             };
 
-            let (bytecode, bytecode_type) = if let Some(code) = creation_code {
-                (code, eth_bytecode_db_v2::BytecodeType::CreationInput)
-            } else {
-                (
-                    runtime_code,
-                    eth_bytecode_db_v2::BytecodeType::DeployedBytecode,
-                )
-            };
-            let eth_bytecode_db_request = request_builder(bytecode, bytecode_type, metadata);
+            let (bytecode, bytecode_type) = bytecode_to_verify(creation_code, runtime_code);
+            let eth_bytecode_db_request = request_builder(bytecode, bytecode_type, Some(metadata));
 
             let eth_bytecode_db_response =
                 verify(eth_bytecode_db_client, eth_bytecode_db_request).await;
@@ -145,41 +217,94 @@ where
     }
 }
 
+fn bytecode_to_verify(
+    creation_code: Option<ethers_core::types::Bytes>,
+    runtime_code: ethers_core::types::Bytes,
+) -> (ethers_core::types::Bytes, eth_bytecode_db_v2::BytecodeType) {
+    if let Some(code) = creation_code {
+        (code, eth_bytecode_db_v2::BytecodeType::CreationInput)
+    } else {
+        (
+            runtime_code,
+            eth_bytecode_db_v2::BytecodeType::DeployedBytecode,
+        )
+    }
+}
+
 fn process_verify_response(
     chain_id: &str,
     contract_address: ethers_core::types::Address,
     response: Result<eth_bytecode_db_v2::VerifyResponse, eth_bytecode_db_proto::http_client::Error>,
-) -> Result<eth_bytecode_db_v2::source::MatchType, Error> {
+) -> Result<eth_bytecode_db_v2::Source, Error> {
     match response {
         Ok(response)
             if response.status == eth_bytecode_db_v2::verify_response::Status::Success as i32 =>
         {
             response
                 .source
-                .map(|value| value.match_type())
                 .ok_or_else(|| Error::internal("Eth-bytecode-db returned invalid response"))
         }
+        Ok(response) => Err(verification_failure(response.message)),
+        Err(err) => {
+            tracing::error!(
+                chain_id = chain_id,
+                contract_address = contract_address.to_hex(),
+                "eth_bytecode_db verification request failed: {err}"
+            );
+            Err(Error::internal(
+                "Error while sending verification request to eth-bytecode-db",
+            ))
+        }
+    }
+}
+
+fn process_preview_response(
+    chain_id: &str,
+    contract_address: ethers_core::types::Address,
+    response: smart_contract_verifier_proto::http_client::Result<
+        smart_contract_verifier_v2::VerifyResponse,
+    >,
+) -> Result<
+    (
+        smart_contract_verifier_v2::Source,
+        smart_contract_verifier_v2::verify_response::ExtraData,
+    ),
+    Error,
+> {
+    match response {
         Ok(response)
-            if response
-                .message
-                .contains("No contract could be verified with provided data") =>
+            if response.status
+                == smart_contract_verifier_v2::verify_response::Status::Success as i32 =>
         {
-            Err(Error::verification_failed(response.message))
+            response
+                .source
+                .zip(response.extra_data)
+                .ok_or_else(|| Error::internal("Verifier returned invalid response"))
         }
-        Ok(response) => Err(Error::compilation_failed(response.message)),
+        Ok(response) => Err(verification_failure(response.message)),
         Err(err) => {
             tracing::error!(
                 chain_id = chain_id,
                 contract_address = contract_address.to_hex(),
-                "eth_bytecode_db verification request failed: {err}"
+                "smart_contract_verifier verification request failed: {err}"
             );
             Err(Error::internal(
-                "Error while sending verification request to eth-bytecode-db",
+                "Error while sending verification request to the verifier",
             ))
         }
     }
 }
 
+/// Eth-bytecode-db passes the verifier failure messages through as is,
+/// so the same message distinguishes a mismatch from a compilation error.
+fn verification_failure(message: String) -> Error {
+    if message.contains("No contract could be verified with provided data") {
+        Error::verification_failed(message)
+    } else {
+        Error::compilation_failed(message)
+    }
+}
+
 async fn search_contract(
     blockscout_client: &blockscout_client::Client,
     contract_address: ethers_core::types::Address,
@@ -1,7 +1,15 @@
-use crate::{handlers::process_verification_request, ToHex, VerificationResponse};
+use crate::{
+    diff_preview::PreviewResponse,
+    handlers::{process_preview_request, process_verification_request},
+    ToHex, VerificationResponse,
+};
 use eth_bytecode_db_proto::{
     blockscout::eth_bytecode_db::v2 as eth_bytecode_db_v2, http_client::solidity_verifier_client,
 };
+use smart_contract_verifier_proto::{
+    blockscout::smart_contract_verifier::v2 as smart_contract_verifier_v2,
+    http_client as smart_contract_verifier_http,
+};
 use std::collections::BTreeMap;
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -18,26 +26,66 @@ pub async fn verify(
     contracts: Vec<(&blockscout_client::Client, ethers_core::types::Address)>,
     request: VerificationRequest,
 ) -> VerificationResponse {
-    let request_builder = |bytecode: ethers_core::types::Bytes,
-                           bytecode_type: eth_bytecode_db_v2::BytecodeType,
-                           metadata| {
-        eth_bytecode_db_v2::VerifySolidityMultiPartRequest {
-            bytecode: bytecode.to_hex(),
-            bytecode_type: bytecode_type.into(),
-            compiler_version: request.compiler.clone(),
-            evm_version: request.evm_version.clone(),
-            optimization_runs: request.optimization_runs,
-            source_files: request.source_files.clone(),
-            libraries: request.libraries.clone(),
-            metadata: Some(metadata),
-        }
-    };
-
     process_verification_request(
         eth_bytecode_db_client,
         contracts,
-        request_builder,
+        request_builder(&request),
         solidity_verifier_client::verify_multi_part,
     )
     .await
 }
+
+pub async fn preview(
+    verifier_client: &smart_contract_verifier_proto::http_client::Client,
+    contracts: Vec<(&blockscout_client::Client, ethers_core::types::Address)>,
+    request: VerificationRequest,
+) -> PreviewResponse {
+    process_preview_request(
+        verifier_client,
+        contracts,
+        preview_request_builder(&request),
+        smart_contract_verifier_http::solidity_verifier_client::verify_multi_part,
+    )
+    .await
+}
+
+fn request_builder(
+    request: &VerificationRequest,
+) -> impl Fn(
+    ethers_core::types::Bytes,
+    eth_bytecode_db_v2::BytecodeType,
+    Option<eth_bytecode_db_v2::VerificationMetadata>,
+) -> eth_bytecode_db_v2::VerifySolidityMultiPartRequest
+       + Clone
+       + '_ {
+    move |bytecode, bytecode_type, metadata| eth_bytecode_db_v2::VerifySolidityMultiPartRequest {
+        bytecode: bytecode.to_hex(),
+        bytecode_type: bytecode_type.into(),
+        compiler_version: request.compiler.clone(),
+        evm_version: request.evm_version.clone(),
+        optimization_runs: request.optimization_runs,
+        source_files: request.source_files.clone(),
+        libraries: request.libraries.clone(),
+        metadata,
+    }
+}
+
+fn preview_request_builder(
+    request: &VerificationRequest,
+) -> impl Fn(
+    ethers_core::types::Bytes,
+    smart_contract_verifier_v2::BytecodeType,
+) -> smart_contract_verifier_v2::VerifySolidityMultiPartRequest
+       + '_ {
+    move |bytecode, bytecode_type| smart_contract_verifier_v2::VerifySolidityMultiPartRequest {
+        bytecode: bytecode.to_hex(),
+        bytecode_type: bytecode_type.into(),
+        compiler_version: request.compiler.clone(),
+        evm_version: request.evm_version.clone(),
+        optimization_runs: request.optimization_runs,
+        source_files: request.source_files.clone(),
+        libraries: request.libraries.clone(),
+        metadata: None,
+        post_actions: vec![],
+    }
+}
@@ -1,7 +1,15 @@
-use crate::{handlers::process_verification_request, ToHex, VerificationResponse};
+use crate::{
+    diff_preview::PreviewResponse,
+    handlers::{process_preview_request, process_verification_request},
+    ToHex, VerificationResponse,
+};
 use eth_bytecode_db_proto::{
     blockscout::eth_bytecode_db::v2 as eth_bytecode_db_v2, http_client::vyper_verifier_client,
 };
+use smart_contract_verifier_proto::{
+    blockscout::smart_contract_verifier::v2 as smart_contract_verifier_v2,
+    http_client as smart_contract_verifier_http,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct VerificationRequest {
@@ -14,23 +22,59 @@ pub async fn verify(
     contracts: Vec<(&blockscout_client::Client, ethers_core::types::Address)>,
     request: VerificationRequest,
 ) -> VerificationResponse {
-    let request_builder = |bytecode: ethers_core::types::Bytes,
-                           bytecode_type: eth_bytecode_db_v2::BytecodeType,
-                           metadata| {
-        eth_bytecode_db_v2::VerifyVyperStandardJsonRequest {
-            bytecode: bytecode.to_hex(),
-            bytecode_type: bytecode_type.into(),
-            compiler_version: request.compiler.clone(),
-            input: request.input.clone(),
-            metadata: Some(metadata),
-        }
-    };
-
     process_verification_request(
         eth_bytecode_db_client,
         contracts,
-        request_builder,
+        request_builder(&request),
         vyper_verifier_client::verify_standard_json,
     )
     .await
 }
+
+pub async fn preview(
+    verifier_client: &smart_contract_verifier_proto::http_client::Client,
+    contracts: Vec<(&blockscout_client::Client, ethers_core::types::Address)>,
+    request: VerificationRequest,
+) -> PreviewResponse {
+    process_preview_request(
+        verifier_client,
+        contracts,
+        preview_request_builder(&request),
+        smart_contract_verifier_http::vyper_verifier_client::verify_standard_json,
+    )
+    .await
+}
+
+fn request_builder(
+    request: &VerificationRequest,
+) -> impl Fn(
+    ethers_core::types::Bytes,
+    eth_bytecode_db_v2::BytecodeType,
+    Option<eth_bytecode_db_v2::VerificationMetadata>,
+) -> eth_bytecode_db_v2::VerifyVyperStandardJsonRequest
+       + Clone
+       + '_ {
+    move |bytecode, bytecode_type, metadata| eth_bytecode_db_v2::VerifyVyperStandardJsonRequest {
+        bytecode: bytecode.to_hex(),
+        bytecode_type: bytecode_type.into(),
+        compiler_version: request.compiler.clone(),
+        input: request.input.clone(),
+        metadata,
+    }
+}
+
+fn preview_request_builder(
+    request: &VerificationRequest,
+) -> impl Fn(
+    ethers_core::types::Bytes,
+    smart_contract_verifier_v2::BytecodeType,
+) -> smart_contract_verifier_v2::VerifyVyperStandardJsonRequest
+       + '_ {
+    move |bytecode, bytecode_type| smart_contract_verifier_v2::VerifyVyperStandardJsonRequest {
+        bytecode: bytecode.to_hex(),
+        bytecode_type: bytecode_type.into(),
+        compiler_version: request.compiler.clone(),
+        input: request.input.clone(),
+        metadata: None,
+    }
+}
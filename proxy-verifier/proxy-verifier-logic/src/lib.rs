@@ -1,6 +1,11 @@
 mod address_details;
+pub mod api_keys;
+pub mod diff_preview;
 mod handlers;
+pub mod same_bytecode;
+pub mod sourcify_submission;
 mod to_hex;
+pub mod verification_jobs;
 
 use to_hex::ToHex;
 
@@ -55,13 +60,15 @@ impl Error {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct VerificationSuccess {
     pub url: String,
     pub match_type: eth_bytecode_db_proto::blockscout::eth_bytecode_db::v2::source::MatchType,
+    /// Verified source as returned by eth-bytecode-db
+    pub source: eth_bytecode_db_proto::blockscout::eth_bytecode_db::v2::Source,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum VerificationResponse {
     CompilationFailed(Error),
     InvalidContracts(Vec<Option<Error>>),
@@ -0,0 +1,216 @@
+use crate::{address_details, address_details::AddressDetails, Error, ToHex};
+use eth_bytecode_db_proto::{
+    blockscout::eth_bytecode_db::v2 as eth_bytecode_db_v2, http_client::database_client,
+};
+use ethers_core::types;
+use futures::StreamExt;
+use std::{collections::BTreeSet, str::FromStr};
+
+/// Max number of the contracts which code is retrieved from the explorers concurrently
+const MAX_CONCURRENT_REQUESTS: usize = 10;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SameBytecodeStatus {
+    SameBytecode,
+    DifferentBytecode,
+    NotContract(Error),
+    InternalError(Error),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainContract {
+    pub chain_id: String,
+    pub address: types::Address,
+    pub status: SameBytecodeStatus,
+}
+
+/// Looks for the contracts with the same code on the other chains.
+/// The candidates are the contracts deployed at the same address, and the deployments
+/// of the identical code known to the verifier alliance database of eth-bytecode-db.
+/// Contracts with the same code may be submitted for verification together with the source one.
+pub async fn find_same_bytecode_contracts(
+    eth_bytecode_db_client: &eth_bytecode_db_proto::http_client::Client,
+    source_client: &blockscout_client::Client,
+    target_clients: &[&blockscout_client::Client],
+    contract_address: types::Address,
+) -> Result<Vec<ChainContract>, Error> {
+    let source_details =
+        address_details::retrieve_address_details(source_client, contract_address).await?;
+
+    let deployments = search_deployments(eth_bytecode_db_client, &source_details).await;
+    let target_chain_ids: Vec<_> = target_clients
+        .iter()
+        .map(|client| client.chain_id().to_string())
+        .collect();
+    let candidates = candidates(&target_chain_ids, contract_address, deployments);
+
+    let source_details = &source_details;
+    let contracts = futures::stream::iter(candidates)
+        .map(|(chain_id, address)| async move {
+            let client = target_clients
+                .iter()
+                .find(|client| client.chain_id().to_string() == chain_id)
+                .expect("candidates are built from the target clients");
+            let status = match address_details::retrieve_address_details(client, address).await {
+                Ok(details) if is_same_bytecode(source_details, &details) => {
+                    SameBytecodeStatus::SameBytecode
+                }
+                Ok(_) => SameBytecodeStatus::DifferentBytecode,
+                Err(err) if err.is_invalid_contract_error() => SameBytecodeStatus::NotContract(err),
+                Err(err) => SameBytecodeStatus::InternalError(err),
+            };
+            ChainContract {
+                chain_id,
+                address,
+                status,
+            }
+        })
+        .buffered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await;
+
+    Ok(contracts)
+}
+
+/// Returns (chain_id, address) pairs of the deployments with the same code stored in eth-bytecode-db.
+/// The lookup is best-effort: on failures only the contracts at the same address are compared.
+async fn search_deployments(
+    eth_bytecode_db_client: &eth_bytecode_db_proto::http_client::Client,
+    source_details: &AddressDetails,
+) -> Vec<(String, types::Address)> {
+    let mut codes = vec![(
+        source_details.runtime_code.clone(),
+        eth_bytecode_db_v2::BytecodeType::DeployedBytecode,
+    )];
+    if let Some(creation_code) = source_details.creation_code.clone() {
+        codes.push((
+            creation_code,
+            eth_bytecode_db_v2::BytecodeType::CreationInput,
+        ));
+    }
+
+    let mut deployments = vec![];
+    for (code, bytecode_type) in codes {
+        let request = eth_bytecode_db_v2::SearchAllianceDeploymentsRequest {
+            bytecode: code.to_hex(),
+            bytecode_type: bytecode_type.into(),
+        };
+        match database_client::search_alliance_deployments(eth_bytecode_db_client, request).await {
+            Ok(response) => {
+                deployments.extend(response.deployments.into_iter().filter_map(|deployment| {
+                    types::Address::from_str(&deployment.address)
+                        .ok()
+                        .map(|address| (deployment.chain_id, address))
+                }))
+            }
+            Err(err) => {
+                tracing::warn!(
+                    chain_id = source_details.chain_id,
+                    contract_address = source_details.address.to_hex(),
+                    "eth_bytecode_db deployments search failed: {err}"
+                );
+            }
+        }
+    }
+    deployments
+}
+
+/// Combines the same address contracts on each target chain with the known deployments
+/// on the target chains. Keeps the order of the target chains and removes duplicates.
+fn candidates(
+    target_chain_ids: &[String],
+    contract_address: types::Address,
+    deployments: Vec<(String, types::Address)>,
+) -> Vec<(String, types::Address)> {
+    let mut seen = BTreeSet::new();
+    let mut candidates = vec![];
+    for chain_id in target_chain_ids {
+        let chain_deployments = deployments
+            .iter()
+            .filter(|(deployment_chain_id, _)| deployment_chain_id == chain_id)
+            .map(|(_, address)| *address);
+        for address in std::iter::once(contract_address).chain(chain_deployments) {
+            if seen.insert((chain_id.clone(), address)) {
+                candidates.push((chain_id.clone(), address));
+            }
+        }
+    }
+    candidates
+}
+
+/// Runtime codes may differ because of the chain-specific immutable values,
+/// so matching creation codes are considered the same bytecode as well.
+fn is_same_bytecode(source: &AddressDetails, target: &AddressDetails) -> bool {
+    if source.runtime_code == target.runtime_code {
+        return true;
+    }
+    match (&source.creation_code, &target.creation_code) {
+        (Some(source), Some(target)) => source == target,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn address(value: u8) -> types::Address {
+        types::Address::repeat_byte(value)
+    }
+
+    fn details(creation_code: Option<&str>, runtime_code: &str) -> AddressDetails {
+        AddressDetails {
+            chain_id: "1".to_string(),
+            address: address(1),
+            transaction_hash: None,
+            block_number: None,
+            transaction_index: None,
+            deployer: None,
+            creation_code: creation_code.map(|code| types::Bytes::from_str(code).unwrap()),
+            runtime_code: types::Bytes::from_str(runtime_code).unwrap(),
+        }
+    }
+
+    #[test]
+    fn candidates_include_known_deployments() {
+        let deployments = vec![
+            ("10".to_string(), address(2)),
+            // Not a target chain
+            ("56".to_string(), address(3)),
+            // Duplicates the same address contract
+            ("100".to_string(), address(1)),
+            ("100".to_string(), address(4)),
+            ("10".to_string(), address(2)),
+        ];
+
+        let target_chain_ids = ["10".to_string(), "100".to_string()];
+        let candidates = candidates(&target_chain_ids, address(1), deployments);
+        assert_eq!(
+            vec![
+                ("10".to_string(), address(1)),
+                ("10".to_string(), address(2)),
+                ("100".to_string(), address(1)),
+                ("100".to_string(), address(4)),
+            ],
+            candidates
+        );
+    }
+
+    #[test]
+    fn same_bytecode_comparison() {
+        let source = details(Some("0x6080aa"), "0x6000aa");
+
+        assert!(is_same_bytecode(&source, &details(None, "0x6000aa")));
+        // Runtime codes differ in the immutable values
+        assert!(is_same_bytecode(
+            &source,
+            &details(Some("0x6080aa"), "0x6000bb")
+        ));
+        assert!(!is_same_bytecode(
+            &source,
+            &details(Some("0x6080bb"), "0x6000bb")
+        ));
+        assert!(!is_same_bytecode(&source, &details(None, "0x6000bb")));
+    }
+}
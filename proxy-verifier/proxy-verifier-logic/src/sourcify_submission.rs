@@ -0,0 +1,134 @@
+use crate::{Error, VerificationSuccess};
+use eth_bytecode_db_proto::blockscout::eth_bytecode_db::v2 as eth_bytecode_db_v2;
+use ethers_core::types;
+
+pub const SOURCIFY_REGISTRY: &str = "sourcify";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmissionStatus {
+    Success,
+    Failure,
+    /// The contract cannot be submitted into the registry (e.g., the chain is not supported)
+    Skipped,
+}
+
+/// Outcome of submitting the verified contract into the external registry
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistrySubmission {
+    pub chain_id: String,
+    pub address: types::Address,
+    pub registry: String,
+    pub status: SubmissionStatus,
+    pub message: String,
+}
+
+/// Submits successfully verified contracts to Sourcify. Contracts are submitted in parallel,
+/// and failures do not affect the verification results.
+pub async fn submit_to_sourcify(
+    client: &sourcify::Client,
+    contracts: &[(&blockscout_client::Client, types::Address)],
+    results: &[Result<VerificationSuccess, Error>],
+) -> Vec<RegistrySubmission> {
+    let submissions =
+        contracts
+            .iter()
+            .zip(results)
+            .map(|((blockscout_client, address), result)| {
+                let chain_id = blockscout_client.chain_id().to_string();
+                async move {
+                    let (status, message) = match result {
+                        Ok(success) => {
+                            submit_contract(client, &chain_id, *address, &success.source).await
+                        }
+                        Err(_) => (
+                            SubmissionStatus::Skipped,
+                            "Contract has not been verified".to_string(),
+                        ),
+                    };
+                    RegistrySubmission {
+                        chain_id,
+                        address: *address,
+                        registry: SOURCIFY_REGISTRY.to_string(),
+                        status,
+                        message,
+                    }
+                }
+            });
+
+    futures::future::join_all(submissions).await
+}
+
+async fn submit_contract(
+    client: &sourcify::Client,
+    chain_id: &str,
+    address: types::Address,
+    source: &eth_bytecode_db_v2::Source,
+) -> (SubmissionStatus, String) {
+    let language = match source.source_type() {
+        eth_bytecode_db_v2::source::SourceType::Solidity => "Solidity",
+        eth_bytecode_db_v2::source::SourceType::Yul => "Yul",
+        _ => {
+            return (
+                SubmissionStatus::Skipped,
+                "Only Solidity and Yul contracts may be submitted".to_string(),
+            )
+        }
+    };
+    let input = match solc_json_input(language, source) {
+        Ok(input) => input,
+        Err(err) => {
+            tracing::error!(chain_id, address = ?address, "invalid compiler settings: {err}");
+            return (
+                SubmissionStatus::Failure,
+                "Invalid compiler settings".to_string(),
+            );
+        }
+    };
+    // eth-bytecode-db returns versions prefixed with 'v' (e.g., "v0.8.7+commit.e28d00a7")
+    let compiler_version = source.compiler_version.trim_start_matches('v');
+
+    let response = client
+        .verify_from_solc_json(
+            chain_id,
+            types::Bytes::from(address.as_bytes().to_vec()).0,
+            compiler_version,
+            &source.contract_name,
+            &input,
+        )
+        .await;
+    match response {
+        Ok(response) => {
+            let message = match response.status {
+                sourcify::MatchType::Full => "Full match",
+                sourcify::MatchType::Partial => "Partial match",
+            };
+            (SubmissionStatus::Success, message.to_string())
+        }
+        Err(sourcify::Error::Sourcify(sourcify::SourcifyError::ChainNotSupported(message))) => {
+            (SubmissionStatus::Skipped, message)
+        }
+        Err(err) => {
+            tracing::warn!(chain_id, address = ?address, "sourcify submission failed: {err}");
+            (SubmissionStatus::Failure, err.to_string())
+        }
+    }
+}
+
+fn solc_json_input(
+    language: &str,
+    source: &eth_bytecode_db_v2::Source,
+) -> Result<String, serde_json::Error> {
+    let settings: serde_json::Value = serde_json::from_str(&source.compiler_settings)?;
+    let sources: serde_json::Map<_, _> = source
+        .source_files
+        .iter()
+        .map(|(path, content)| (path.clone(), serde_json::json!({ "content": content })))
+        .collect();
+
+    let input = serde_json::json!({
+        "language": language,
+        "sources": sources,
+        "settings": settings,
+    });
+    Ok(input.to_string())
+}
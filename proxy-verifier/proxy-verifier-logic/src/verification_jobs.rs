@@ -0,0 +1,185 @@
+use entity::{
+    sea_orm_active_enums::VerificationJobStatus,
+    verification_jobs::{ActiveModel, Column, Entity, Model},
+};
+use sea_orm::{
+    prelude::Uuid, sea_query::Expr, ActiveEnum, ActiveModelTrait, ActiveValue::Set, ColumnTrait,
+    ConnectionTrait, DbErr, EntityTrait, FromQueryResult, QueryFilter, Statement,
+};
+
+/// Persists a new job. `request` should contain everything required to run the job again after restart.
+pub async fn create<C>(db: &C, request: serde_json::Value) -> Result<Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    ActiveModel {
+        id: Set(Uuid::new_v4()),
+        status: Set(VerificationJobStatus::Pending),
+        request: Set(request),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+}
+
+pub async fn get<C>(db: &C, id: Uuid) -> Result<Option<Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Entity::find_by_id(id).one(db).await
+}
+
+/// Atomically marks the oldest pending job as running and returns it.
+/// Jobs locked by concurrent claims are skipped, so each job is claimed exactly once.
+pub async fn claim_next<C>(db: &C) -> Result<Option<Model>, DbErr>
+where
+    C: ConnectionTrait,
+{
+    Model::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        r#"
+            UPDATE "verification_jobs"
+            SET "status" = 'running', "updated_at" = $1
+            WHERE "id" = (
+                SELECT "id" FROM "verification_jobs"
+                WHERE "status" = 'pending'
+                ORDER BY "created_at"
+                LIMIT 1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING *;"#,
+        [chrono::Utc::now().naive_utc().into()],
+    ))
+    .one(db)
+    .await
+}
+
+/// Returns the jobs running for longer than `timeout` back to the queue,
+/// e.g. the ones interrupted by the service restart
+pub async fn requeue_stale<C>(db: &C, timeout: std::time::Duration) -> Result<u64, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let now = chrono::Utc::now().naive_utc();
+    let updated_before = now - chrono::Duration::from_std(timeout).unwrap_or(chrono::Duration::MAX);
+    let result = Entity::update_many()
+        .col_expr(Column::Status, VerificationJobStatus::Pending.as_enum())
+        .col_expr(Column::UpdatedAt, Expr::value(now))
+        .filter(Column::Status.eq(VerificationJobStatus::Running))
+        .filter(Column::UpdatedAt.lt(updated_before))
+        .exec(db)
+        .await?;
+    Ok(result.rows_affected)
+}
+
+/// `registry_submissions` contains outcomes of submitting the results into the external registries
+pub async fn mark_finished<C>(
+    db: &C,
+    id: Uuid,
+    result: serde_json::Value,
+    registry_submissions: Option<serde_json::Value>,
+) -> Result<Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    ActiveModel {
+        id: Set(id),
+        status: Set(VerificationJobStatus::Finished),
+        result: Set(Some(result)),
+        error: Set(None),
+        registry_submissions: Set(registry_submissions),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+pub async fn mark_failed<C>(db: &C, id: Uuid, error: String) -> Result<Model, DbErr>
+where
+    C: ConnectionTrait,
+{
+    ActiveModel {
+        id: Set(id),
+        status: Set(VerificationJobStatus::Failed),
+        result: Set(None),
+        error: Set(Some(error)),
+        updated_at: Set(chrono::Utc::now().naive_utc()),
+        ..Default::default()
+    }
+    .update(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blockscout_service_launcher::test_database::TestDbGuard;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    async fn init_db(test_name: &str) -> TestDbGuard {
+        TestDbGuard::new::<migration::Migrator>(&format!("verification_jobs_{test_name}")).await
+    }
+
+    #[tokio::test]
+    #[ignore = "Needs database to run"]
+    async fn claim_next_returns_each_job_once() {
+        let db = init_db("claim_next_returns_each_job_once").await;
+        let db = db.client();
+
+        let mut created = HashSet::new();
+        for i in 0..10 {
+            let job = create(db.as_ref(), serde_json::json!({ "job": i }))
+                .await
+                .unwrap();
+            created.insert(job.id);
+        }
+
+        let claims = (0..15).map(|_| {
+            let db = db.clone();
+            tokio::spawn(async move { claim_next(db.as_ref()).await.unwrap() })
+        });
+        let claimed = futures::future::join_all(claims)
+            .await
+            .into_iter()
+            .filter_map(|res| res.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(claimed.len(), 10);
+        assert!(claimed
+            .iter()
+            .all(|job| job.status == VerificationJobStatus::Running));
+        assert_eq!(
+            claimed.iter().map(|job| job.id).collect::<HashSet<_>>(),
+            created
+        );
+        assert_eq!(claim_next(db.as_ref()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    #[ignore = "Needs database to run"]
+    async fn requeue_stale_returns_jobs_to_queue() {
+        let db = init_db("requeue_stale_returns_jobs_to_queue").await;
+        let db = db.client();
+
+        let job = create(db.as_ref(), serde_json::json!({})).await.unwrap();
+        let claimed = claim_next(db.as_ref()).await.unwrap().unwrap();
+        assert_eq!(claimed.id, job.id);
+
+        let hour = std::time::Duration::from_secs(3600);
+        assert_eq!(requeue_stale(db.as_ref(), hour).await.unwrap(), 0);
+        assert_eq!(claim_next(db.as_ref()).await.unwrap(), None);
+
+        let zero = std::time::Duration::ZERO;
+        assert_eq!(requeue_stale(db.as_ref(), zero).await.unwrap(), 1);
+        let reclaimed = claim_next(db.as_ref()).await.unwrap().unwrap();
+        assert_eq!(reclaimed.id, job.id);
+
+        let failed = mark_failed(db.as_ref(), job.id, "error".to_string())
+            .await
+            .unwrap();
+        assert_eq!(failed.status, VerificationJobStatus::Failed);
+        assert_eq!(requeue_stale(db.as_ref(), zero).await.unwrap(), 0);
+    }
+}
@@ -0,0 +1,37 @@
+pub use sea_orm_migration::prelude::*;
+use sea_orm_migration::sea_orm::{Statement, TransactionTrait};
+
+mod m20241220_000000_create_verification_jobs;
+mod m20241227_000000_add_registry_submissions;
+mod m20250103_000000_create_api_keys;
+
+pub struct Migrator;
+
+#[async_trait::async_trait]
+impl MigratorTrait for Migrator {
+    fn migrations() -> Vec<Box<dyn MigrationTrait>> {
+        vec![
+            Box::new(m20241220_000000_create_verification_jobs::Migration),
+            Box::new(m20241227_000000_add_registry_submissions::Migration),
+            Box::new(m20250103_000000_create_api_keys::Migration),
+        ]
+    }
+}
+
+pub async fn from_sql(manager: &SchemaManager<'_>, content: &str) -> Result<(), DbErr> {
+    let statements: Vec<&str> = content
+        .split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let txn = manager.get_connection().begin().await?;
+    for st in statements {
+        txn.execute(Statement::from_string(
+            manager.get_database_backend(),
+            st.to_string(),
+        ))
+        .await
+        .map_err(|e| DbErr::Migration(format!("{e}\nQuery: {st}")))?;
+    }
+    txn.commit().await
+}
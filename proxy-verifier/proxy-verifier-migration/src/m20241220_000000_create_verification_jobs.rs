@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TYPE "verification_job_status" AS ENUM (
+              'pending',
+              'running',
+              'finished',
+              'failed'
+            );
+
+            CREATE TABLE "verification_jobs" (
+              "id" uuid PRIMARY KEY,
+              "status" verification_job_status NOT NULL DEFAULT 'pending',
+              "request" jsonb NOT NULL,
+              "result" jsonb,
+              "error" varchar,
+              "created_at" timestamp NOT NULL DEFAULT (now()),
+              "updated_at" timestamp NOT NULL DEFAULT (now())
+            );
+
+            CREATE INDEX "verification_jobs_status_index" ON "verification_jobs" ("status");
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "verification_jobs";
+            DROP TYPE "verification_job_status";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,38 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Only sha256 hashes of the keys are stored
+        let sql = r#"
+            CREATE TABLE "api_keys" (
+              "id" bigserial PRIMARY KEY,
+              "key_hash" bytea UNIQUE NOT NULL,
+              "name" varchar NOT NULL,
+              "daily_limit" bigint,
+              "is_active" bool NOT NULL DEFAULT true,
+              "created_at" timestamp NOT NULL DEFAULT (now()),
+              "updated_at" timestamp NOT NULL DEFAULT (now())
+            );
+
+            CREATE TABLE "api_key_usages" (
+              "api_key_id" bigint NOT NULL REFERENCES "api_keys" ("id") ON DELETE CASCADE,
+              "day" date NOT NULL,
+              "verifications" bigint NOT NULL DEFAULT 0,
+              PRIMARY KEY ("api_key_id", "day")
+            );
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "api_key_usages";
+            DROP TABLE "api_keys";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
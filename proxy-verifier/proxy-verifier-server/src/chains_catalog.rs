@@ -0,0 +1,77 @@
+use crate::settings::ChainsCatalogSettings;
+use blockscout_chains::{BlockscoutChainData, BlockscoutChainsClient, Ecosystem};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+/// Chain information retrieved from the blockscout-chains catalog
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatalogChain {
+    pub name: String,
+    pub icon_url: Option<url::Url>,
+    pub ecosystems: Vec<String>,
+    pub is_testnet: bool,
+}
+
+impl From<BlockscoutChainData> for CatalogChain {
+    fn from(chain: BlockscoutChainData) -> Self {
+        let ecosystems = match chain.ecosystem {
+            Ecosystem::Single(ecosystem) => vec![ecosystem],
+            Ecosystem::Multiple(ecosystems) => ecosystems,
+        };
+        Self {
+            name: chain.name,
+            icon_url: url::Url::parse(&chain.logo).ok(),
+            ecosystems,
+            is_testnet: chain.is_testnet.unwrap_or_default(),
+        }
+    }
+}
+
+/// Latest known state of the blockscout-chains catalog.
+/// Empty if the catalog is disabled or has not been fetched yet.
+#[derive(Clone, Debug, Default)]
+pub struct ChainsCatalog {
+    chains: Arc<RwLock<BTreeMap<String, CatalogChain>>>,
+}
+
+impl ChainsCatalog {
+    pub fn chains(&self) -> BTreeMap<String, CatalogChain> {
+        self.chains
+            .read()
+            .expect("chains catalog lock poisoned")
+            .clone()
+    }
+
+    fn set_chains(&self, chains: BTreeMap<String, CatalogChain>) {
+        *self.chains.write().expect("chains catalog lock poisoned") = chains;
+    }
+}
+
+/// Periodically refreshes the catalog with the chains from blockscout-chains
+pub async fn run_catalog_refresh(catalog: ChainsCatalog, settings: ChainsCatalogSettings) {
+    let mut builder = BlockscoutChainsClient::builder();
+    if let Some(url) = settings.url {
+        builder = builder.with_url(url.to_string());
+    }
+    let client = builder.build();
+
+    let mut interval = tokio::time::interval(settings.refresh_interval);
+    loop {
+        interval.tick().await;
+        match client.fetch_all().await {
+            Ok(chains) => {
+                let chains = chains
+                    .into_iter()
+                    .map(|(id, chain)| (id.to_string(), CatalogChain::from(chain)))
+                    .collect::<BTreeMap<_, _>>();
+                tracing::info!(count = chains.len(), "chains catalog refreshed");
+                catalog.set_chains(chains);
+            }
+            Err(err) => {
+                tracing::error!(err = ?err, "failed to fetch blockscout chains");
+            }
+        }
+    }
+}
@@ -65,4 +65,8 @@ pub struct ChainSettings {
     pub api_url: url::Url,
     pub icon_url: Option<url::Url>,
     pub sensitive_api_key: Option<String>,
+    /// Overrides the value from the chains catalog
+    pub ecosystems: Option<Vec<String>>,
+    /// Overrides the value from the chains catalog
+    pub is_testnet: Option<bool>,
 }
@@ -1,3 +1,4 @@
+mod chains_catalog;
 mod config;
 mod proto;
 mod server;
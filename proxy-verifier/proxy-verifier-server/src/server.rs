@@ -3,16 +3,21 @@ use crate::{
     services::HealthService,
     settings::Settings,
 };
-use blockscout_service_launcher::{launcher, launcher::LaunchSettings, tracing};
+use blockscout_service_launcher::{database, launcher, launcher::LaunchSettings, tracing};
+use migration::Migrator;
 use std::collections::BTreeMap;
 
 use crate::{
+    chains_catalog::{self, ChainsCatalog},
     config::ChainsSettings,
-    services::{ProxyService, SolidityVerifierService, VyperVerifierService},
+    services::{
+        ApiKeyGuard, ApiKeysService, JobRunner, ProxyService, SolidityVerifierService,
+        VyperVerifierService,
+    },
 };
 use proxy_verifier_proto::blockscout::proxy_verifier::v1::{
-    proxy_actix::route_proxy, proxy_server::ProxyServer,
-    solidity_verifier_actix::route_solidity_verifier,
+    api_keys_actix::route_api_keys, api_keys_server::ApiKeysServer, proxy_actix::route_proxy,
+    proxy_server::ProxyServer, solidity_verifier_actix::route_solidity_verifier,
     solidity_verifier_server::SolidityVerifierServer, vyper_verifier_actix::route_vyper_verifier,
     vyper_verifier_server::VyperVerifierServer,
 };
@@ -23,6 +28,7 @@ const SERVICE_NAME: &str = "proxy_verifier";
 #[derive(Clone)]
 struct Router {
     health: Arc<HealthService>,
+    api_keys: Arc<ApiKeysService>,
     proxy: Arc<ProxyService>,
     solidity_verifier: Arc<SolidityVerifierService>,
     vyper_verifier: Arc<VyperVerifierService>,
@@ -32,6 +38,7 @@ impl Router {
     pub fn grpc_router(&self) -> tonic::transport::server::Router {
         tonic::transport::Server::builder()
             .add_service(HealthServer::from_arc(self.health.clone()))
+            .add_service(ApiKeysServer::from_arc(self.api_keys.clone()))
             .add_service(ProxyServer::from_arc(self.proxy.clone()))
             .add_service(SolidityVerifierServer::from_arc(
                 self.solidity_verifier.clone(),
@@ -44,6 +51,7 @@ impl launcher::HttpRouter for Router {
     fn register_routes(&self, service_config: &mut actix_web::web::ServiceConfig) {
         service_config
             .configure(|config| route_health(config, self.health.clone()))
+            .configure(|config| route_api_keys(config, self.api_keys.clone()))
             .configure(|config| route_proxy(config, self.proxy.clone()))
             .configure(|config| route_solidity_verifier(config, self.solidity_verifier.clone()))
             .configure(|config| route_vyper_verifier(config, self.vyper_verifier.clone()));
@@ -66,15 +74,21 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
         Arc::new(eth_bytecode_db_proto::http_client::Client::new(config).await)
     };
 
+    let verifier_client = {
+        let config = smart_contract_verifier_proto::http_client::Config::new(
+            settings.smart_contract_verifier.http_url.into(),
+        )
+        .with_retry_middleware(settings.smart_contract_verifier.max_retries)
+        .probe_url(settings.smart_contract_verifier.probe_url);
+
+        Arc::new(smart_contract_verifier_proto::http_client::Client::new(config).await)
+    };
+
     let health = Arc::new(HealthService::default());
-    let proxy = Arc::new(ProxyService::new(
-        chains.clone(),
-        eth_bytecode_db_client.clone(),
-    ));
 
     let blockscout_clients = {
         let mut clients = BTreeMap::new();
-        for (id, settings) in chains.into_inner() {
+        for (id, settings) in chains.clone().into_inner() {
             let config = blockscout_client::Config::new(id.clone(), settings.api_url.to_string())
                 .with_api_sensitive_endpoints_key(
                     settings
@@ -88,17 +102,90 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
         Arc::new(clients)
     };
 
+    if settings.api_keys.enabled && settings.database.is_none() {
+        anyhow::bail!("api keys require the database to be configured");
+    }
+
+    let db = match settings.database {
+        Some(database_settings) => {
+            let db = database::initialize_postgres::<Migrator>(
+                database_settings.connect.url(),
+                database_settings.create_database,
+                database_settings.run_migrations,
+            )
+            .await?;
+            Some(Arc::new(db))
+        }
+        None => None,
+    };
+
+    let jobs = match db.clone() {
+        Some(db) => {
+            let sourcify_client = settings.sourcify.enabled.then(|| {
+                let client = sourcify::ClientBuilder::default()
+                    .try_base_url(settings.sourcify.url.as_str())
+                    .expect("sourcify url is a valid url")
+                    .max_retries(settings.sourcify.max_retries)
+                    .build();
+                Arc::new(client)
+            });
+
+            let jobs = Arc::new(JobRunner::new(
+                db,
+                settings.jobs.clone(),
+                blockscout_clients.clone(),
+                eth_bytecode_db_client.clone(),
+                sourcify_client,
+            ));
+            jobs.start();
+            Some(jobs)
+        }
+        None => None,
+    };
+
+    let api_key_guard = match db.clone() {
+        Some(db) if settings.api_keys.enabled => Some(Arc::new(ApiKeyGuard::new(
+            db,
+            settings.api_keys.admin_key.clone(),
+        ))),
+        _ => None,
+    };
+    let api_keys = Arc::new(ApiKeysService::new(db, settings.api_keys.admin_key));
+
+    let chains_catalog = ChainsCatalog::default();
+    if settings.chains_catalog.enabled {
+        tokio::spawn(chains_catalog::run_catalog_refresh(
+            chains_catalog.clone(),
+            settings.chains_catalog,
+        ));
+    }
+
+    let proxy = Arc::new(ProxyService::new(
+        chains,
+        chains_catalog,
+        blockscout_clients.clone(),
+        eth_bytecode_db_client.clone(),
+        jobs.clone(),
+    ));
+
     let solidity_verifier = Arc::new(SolidityVerifierService::new(
         blockscout_clients.clone(),
         eth_bytecode_db_client.clone(),
+        verifier_client.clone(),
+        jobs.clone(),
+        api_key_guard.clone(),
     ));
     let vyper_verifier = Arc::new(VyperVerifierService::new(
         blockscout_clients,
         eth_bytecode_db_client,
+        verifier_client,
+        jobs,
+        api_key_guard,
     ));
 
     let router = Router {
         health,
+        api_keys,
         proxy,
         solidity_verifier,
         vyper_verifier,
@@ -0,0 +1,234 @@
+use crate::proto::{
+    api_keys_server::ApiKeys, ApiKey, CreateApiKeyRequest, DeleteApiKeyRequest,
+    DeleteApiKeyResponse, ListApiKeysRequest, ListApiKeysResponse, UpdateApiKeyRequest,
+};
+use async_trait::async_trait;
+use entity::api_keys;
+use proxy_verifier_logic::api_keys::QuotaCheck;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tonic::{metadata::MetadataMap, Request, Response, Status};
+
+const API_KEY_NAME: &str = "x-api-key";
+
+fn api_key_from_metadata(metadata: &MetadataMap) -> Result<Option<&str>, Status> {
+    metadata
+        .get(API_KEY_NAME)
+        .map(|api_key| api_key.to_str())
+        .transpose()
+        .map_err(|err| {
+            Status::invalid_argument(format!("invalid api key value ({API_KEY_NAME}): {err}"))
+        })
+}
+
+fn is_admin_key(admin_key: &str, api_key: &str) -> bool {
+    admin_key.as_bytes().ct_eq(api_key.as_bytes()).into()
+}
+
+/// Checks that verification requests contain a valid api key
+/// and that the daily limit of the key has not been reached yet.
+// TODO: move the key lookup to `blockscout_service_launcher::launcher::ApiKeyAuth`
+// (with the database keys as its `ApiKeyStore`) once it supports the grpc server.
+pub struct ApiKeyGuard {
+    db: Arc<DatabaseConnection>,
+    admin_key: Option<String>,
+}
+
+impl ApiKeyGuard {
+    pub fn new(db: Arc<DatabaseConnection>, admin_key: Option<String>) -> Self {
+        Self { db, admin_key }
+    }
+
+    /// Records `contracts` verifications against the quota of the request api key.
+    /// Requests made with the admin key are not limited.
+    ///
+    /// The quota limits the load on the verifiers rather than the number of verified contracts,
+    /// so it is consumed before the request is processed, and previews and failed verifications
+    /// are charged as well.
+    pub async fn consume(&self, metadata: &MetadataMap, contracts: usize) -> Result<(), Status> {
+        let api_key = api_key_from_metadata(metadata)?
+            .ok_or_else(|| Status::unauthenticated(format!("{API_KEY_NAME} header is missing")))?;
+        if let Some(admin_key) = self.admin_key.as_deref() {
+            if is_admin_key(admin_key, api_key) {
+                return Ok(());
+            }
+        }
+
+        let check =
+            proxy_verifier_logic::api_keys::consume(self.db.as_ref(), api_key, contracts as i64)
+                .await
+                .map_err(|err| {
+                    tracing::error!("failed to check api key quota: {err}");
+                    Status::internal("failed to check api key quota")
+                })?;
+        match check {
+            QuotaCheck::Allowed { .. } => Ok(()),
+            QuotaCheck::InvalidKey => Err(Status::permission_denied("invalid api key")),
+            QuotaCheck::Exceeded { daily_limit } => Err(Status::resource_exhausted(format!(
+                "daily verification limit ({daily_limit} contracts) has been reached"
+            ))),
+        }
+    }
+}
+
+/// Consumes the quota if api keys are enabled
+pub(crate) async fn check_quota<T>(
+    guard: Option<&ApiKeyGuard>,
+    request: &Request<T>,
+    contracts: usize,
+) -> Result<(), Status> {
+    match guard {
+        Some(guard) => guard.consume(request.metadata(), contracts).await,
+        None => Ok(()),
+    }
+}
+
+pub struct ApiKeysService {
+    db: Option<Arc<DatabaseConnection>>,
+    admin_key: Option<String>,
+}
+
+impl ApiKeysService {
+    pub fn new(db: Option<Arc<DatabaseConnection>>, admin_key: Option<String>) -> Self {
+        Self { db, admin_key }
+    }
+
+    fn check_admin_api_key(&self, metadata: &MetadataMap) -> Result<&DatabaseConnection, Status> {
+        let admin_key = self
+            .admin_key
+            .as_deref()
+            .ok_or_else(|| Status::unimplemented("admin endpoints are disabled"))?;
+        let db = self.db.as_deref().ok_or_else(|| {
+            Status::unimplemented("api keys require the database to be configured")
+        })?;
+
+        match api_key_from_metadata(metadata)? {
+            Some(api_key) if is_admin_key(admin_key, api_key) => Ok(db),
+            _ => Err(Status::permission_denied("invalid api key")),
+        }
+    }
+}
+
+#[async_trait]
+impl ApiKeys for ApiKeysService {
+    async fn create_api_key(
+        &self,
+        request: Request<CreateApiKeyRequest>,
+    ) -> Result<Response<ApiKey>, Status> {
+        let db = self.check_admin_api_key(request.metadata())?;
+        let request = request.into_inner();
+        if request.name.trim().is_empty() {
+            return Err(Status::invalid_argument("name must not be empty"));
+        }
+
+        let (api_key, key) = proxy_verifier_logic::api_keys::create(
+            db,
+            request.name,
+            request.daily_limit.map(i64::from),
+        )
+        .await
+        .map_err(db_error)?;
+
+        Ok(Response::new(ApiKey {
+            key,
+            ..api_key_model_to_proto(api_key, 0)?
+        }))
+    }
+
+    async fn list_api_keys(
+        &self,
+        request: Request<ListApiKeysRequest>,
+    ) -> Result<Response<ListApiKeysResponse>, Status> {
+        let db = self.check_admin_api_key(request.metadata())?;
+
+        let mut api_keys = vec![];
+        for api_key in proxy_verifier_logic::api_keys::list(db)
+            .await
+            .map_err(db_error)?
+        {
+            let usage = proxy_verifier_logic::api_keys::today_usage(db, api_key.id)
+                .await
+                .map_err(db_error)?;
+            api_keys.push(api_key_model_to_proto(api_key, usage)?);
+        }
+
+        Ok(Response::new(ListApiKeysResponse { api_keys }))
+    }
+
+    async fn update_api_key(
+        &self,
+        request: Request<UpdateApiKeyRequest>,
+    ) -> Result<Response<ApiKey>, Status> {
+        let db = self.check_admin_api_key(request.metadata())?;
+        let request = request.into_inner();
+        let id = parse_id(&request.id)?;
+
+        let daily_limit = match (request.unlimited, request.daily_limit) {
+            (Some(true), _) => Some(None),
+            (_, Some(daily_limit)) => Some(Some(daily_limit.into())),
+            _ => None,
+        };
+        let api_key =
+            proxy_verifier_logic::api_keys::update(db, id, daily_limit, request.is_active)
+                .await
+                .map_err(db_error)?
+                .ok_or_else(|| Status::not_found("api key not found"))?;
+        let usage = proxy_verifier_logic::api_keys::today_usage(db, api_key.id)
+            .await
+            .map_err(db_error)?;
+
+        Ok(Response::new(api_key_model_to_proto(api_key, usage)?))
+    }
+
+    async fn delete_api_key(
+        &self,
+        request: Request<DeleteApiKeyRequest>,
+    ) -> Result<Response<DeleteApiKeyResponse>, Status> {
+        let db = self.check_admin_api_key(request.metadata())?;
+        let id = parse_id(&request.into_inner().id)?;
+
+        let deleted = proxy_verifier_logic::api_keys::delete(db, id)
+            .await
+            .map_err(db_error)?;
+        if !deleted {
+            return Err(Status::not_found("api key not found"));
+        }
+
+        Ok(Response::new(DeleteApiKeyResponse {}))
+    }
+}
+
+fn parse_id(id: &str) -> Result<i64, Status> {
+    id.parse()
+        .map_err(|err| Status::invalid_argument(format!("invalid api key id: {err}")))
+}
+
+fn db_error(err: sea_orm::DbErr) -> Status {
+    tracing::error!("api keys database error: {err}");
+    Status::internal("database error")
+}
+
+/// The key itself is not stored, so it is left empty
+fn api_key_model_to_proto(api_key: api_keys::Model, today_usage: i64) -> Result<ApiKey, Status> {
+    let daily_limit = api_key
+        .daily_limit
+        .map(u32::try_from)
+        .transpose()
+        .map_err(|err| {
+            tracing::error!(id = api_key.id, "invalid api key daily limit: {err}");
+            Status::internal("invalid api key daily limit")
+        })?;
+    // Keys without a limit may exceed the proto type
+    let today_usage = u32::try_from(today_usage).unwrap_or(u32::MAX);
+
+    Ok(ApiKey {
+        id: api_key.id.to_string(),
+        key: String::new(),
+        name: api_key.name,
+        daily_limit,
+        is_active: api_key.is_active,
+        today_usage,
+        created_at: api_key.created_at.and_utc().to_rfc3339(),
+    })
+}
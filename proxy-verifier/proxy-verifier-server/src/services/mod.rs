@@ -1,11 +1,15 @@
+mod api_keys;
 mod health;
 mod proxy;
 mod solidity_verifier;
+mod verification_jobs;
 mod vyper_verifier;
 
+pub use api_keys::{ApiKeyGuard, ApiKeysService};
 pub use health::HealthService;
 pub use proxy::ProxyService;
 pub use solidity_verifier::SolidityVerifierService;
+pub use verification_jobs::JobRunner;
 pub use vyper_verifier::VyperVerifierService;
 
 /****************************************************/
@@ -14,10 +18,11 @@ use eth_bytecode_db_proto::{
     blockscout::eth_bytecode_db::v2 as eth_bytecode_db_proto_v2,
     http_client as eth_bytecode_db_http,
 };
-use proxy_verifier_logic::VerificationSuccess;
+use proxy_verifier_logic::{diff_preview, VerificationSuccess};
 use proxy_verifier_proto::blockscout::proxy_verifier::v1::{
     self as proxy_verifier_proto_v1, verification_response,
 };
+use smart_contract_verifier_proto::blockscout::smart_contract_verifier::v2 as smart_contract_verifier_v2;
 use std::collections::BTreeMap;
 use tonic::{Response, Status};
 
@@ -72,6 +77,28 @@ where
     )))
 }
 
+pub(crate) async fn preview<'a, Request, Preview, PreviewOutput>(
+    blockscout_clients: &'a BTreeMap<String, blockscout_client::Client>,
+    verifier_client: &'a smart_contract_verifier_proto::http_client::Client,
+    contracts: Vec<proxy_verifier_proto_v1::Contract>,
+    verification_request: Request,
+    preview_function: Preview,
+) -> Result<Response<proxy_verifier_proto_v1::VerificationPreviewResponse>, Status>
+where
+    Preview: Fn(
+        &'a smart_contract_verifier_proto::http_client::Client,
+        Vec<(&'a blockscout_client::Client, ethers_core::types::Address)>,
+        Request,
+    ) -> PreviewOutput,
+    PreviewOutput: std::future::Future<Output = diff_preview::PreviewResponse>,
+{
+    let contracts = contracts_proto_to_inner(blockscout_clients, &contracts)?;
+
+    let response = preview_function(verifier_client, contracts, verification_request).await;
+
+    Ok(Response::new(preview_response_inner_to_proto(response)))
+}
+
 pub(crate) async fn list_compilers<'a, List, ListOutput, EvmVersion: Into<String>>(
     eth_bytecode_db_client: &'a eth_bytecode_db_proto::http_client::Client,
     list_compiler_versions: List,
@@ -143,7 +170,9 @@ pub fn verification_response_inner_to_proto(
 
     let verification_status = match response {
         proxy_verifier_logic::VerificationResponse::InvalidContracts(invalid_contracts) => {
-            process_invalid_contracts_response(invalid_contracts)
+            VerificationStatus::ContractValidationResults(process_invalid_contracts_response(
+                invalid_contracts,
+            ))
         }
         proxy_verifier_logic::VerificationResponse::CompilationFailed(error) => {
             VerificationStatus::CompilationFailure(CompilationFailure {
@@ -162,10 +191,10 @@ pub fn verification_response_inner_to_proto(
 
 fn process_invalid_contracts_response(
     invalid_contracts: Vec<Option<proxy_verifier_logic::Error>>,
-) -> verification_response::VerificationStatus {
+) -> verification_response::ContractValidationResults {
     use verification_response::{
         contract_validation_results::{contract_validation_result, ContractValidationResult},
-        ContractValidationResults, VerificationStatus,
+        ContractValidationResults,
     };
 
     let items = invalid_contracts
@@ -185,7 +214,7 @@ fn process_invalid_contracts_response(
             },
         })
         .collect();
-    VerificationStatus::ContractValidationResults(ContractValidationResults { items })
+    ContractValidationResults { items }
 }
 
 fn process_results_response(
@@ -224,3 +253,90 @@ fn process_results_response(
         .collect();
     VerificationStatus::ContractVerificationResults(ContractVerificationResults { items })
 }
+
+pub fn preview_response_inner_to_proto(
+    response: diff_preview::PreviewResponse,
+) -> proxy_verifier_proto_v1::VerificationPreviewResponse {
+    use proxy_verifier_proto_v1::{
+        verification_preview_response::PreviewStatus, verification_response::CompilationFailure,
+    };
+
+    let preview_status = match response {
+        diff_preview::PreviewResponse::InvalidContracts(invalid_contracts) => {
+            PreviewStatus::ContractValidationResults(process_invalid_contracts_response(
+                invalid_contracts,
+            ))
+        }
+        diff_preview::PreviewResponse::CompilationFailed(error) => {
+            PreviewStatus::CompilationFailure(CompilationFailure {
+                message: error.to_string(),
+            })
+        }
+        diff_preview::PreviewResponse::Results(results) => process_preview_results(results),
+    };
+
+    proxy_verifier_proto_v1::VerificationPreviewResponse {
+        preview_status: Some(preview_status),
+    }
+}
+
+fn process_preview_results(
+    results: Vec<Result<diff_preview::ContractDiff, proxy_verifier_logic::Error>>,
+) -> proxy_verifier_proto_v1::verification_preview_response::PreviewStatus {
+    use proxy_verifier_proto_v1::verification_preview_response::{
+        code_segment,
+        contract_diffs::{contract_diff, ContractDiff},
+        CodeSegment, ContractDiffs, PreviewStatus,
+    };
+
+    let items = results
+        .into_iter()
+        .map(|result| match result {
+            Ok(diff) => {
+                let status = match diff.match_type {
+                    smart_contract_verifier_v2::source::MatchType::Full => {
+                        contract_diff::Status::FullMatch
+                    }
+                    _ => contract_diff::Status::PartialMatch,
+                };
+                let runtime_code_segments = diff
+                    .runtime_code_segments
+                    .into_iter()
+                    .map(|segment| {
+                        let kind = match segment.kind {
+                            diff_preview::SegmentKind::Match => code_segment::Kind::Match,
+                            diff_preview::SegmentKind::Immutable => code_segment::Kind::Immutable,
+                            diff_preview::SegmentKind::Metadata => code_segment::Kind::Metadata,
+                        };
+                        CodeSegment {
+                            kind: kind.into(),
+                            offset: segment.offset as u32,
+                            value: format!("{:#x}", segment.value),
+                            compiled_value: segment
+                                .compiled_value
+                                .map(|value| format!("{:#x}", value)),
+                        }
+                    })
+                    .collect();
+                ContractDiff {
+                    message: "Ok".to_string(),
+                    status: status.into(),
+                    runtime_code_segments,
+                    metadata_matches: diff.metadata_matches,
+                    constructor_arguments: diff.constructor_arguments,
+                }
+            }
+            Err(err) if err.is_internal_error() => ContractDiff {
+                message: err.to_string(),
+                status: contract_diff::Status::InternalError.into(),
+                ..Default::default()
+            },
+            Err(err) => ContractDiff {
+                message: err.to_string(),
+                status: contract_diff::Status::NoMatch.into(),
+                ..Default::default()
+            },
+        })
+        .collect();
+    PreviewStatus::ContractDiffs(ContractDiffs { items })
+}
@@ -1,25 +1,37 @@
 use crate::{
+    chains_catalog::ChainsCatalog,
     config::ChainsSettings,
     proto::{
-        proxy_server::Proxy, Chain, GetVerificationConfigRequest, ListChainsRequest,
-        ListChainsResponse, VerificationConfig,
+        chain::VerificationSupport,
+        list_same_bytecode_contracts_response::{chain_contract, ChainContract},
+        proxy_server::Proxy,
+        Chain, Contract, GetVerificationConfigRequest, GetVerificationJobRequest,
+        ListChainsRequest, ListChainsResponse, ListSameBytecodeContractsRequest,
+        ListSameBytecodeContractsResponse, VerificationConfig, VerificationJob,
     },
-    services::{SOLIDITY_EVM_VERSIONS, VYPER_EVM_VERSIONS},
+    services::{verification_jobs, JobRunner, SOLIDITY_EVM_VERSIONS, VYPER_EVM_VERSIONS},
 };
 use async_trait::async_trait;
-use std::sync::Arc;
+use proxy_verifier_logic::same_bytecode;
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
 use tonic::{Request, Response, Status};
 
 pub struct ProxyService {
     /// Mapping from supported chain ids to chain names
     chains: ChainsSettings,
+    chains_catalog: ChainsCatalog,
+    blockscout_clients: Arc<BTreeMap<String, blockscout_client::Client>>,
     eth_bytecode_db_client: Arc<eth_bytecode_db_proto::http_client::Client>,
+    jobs: Option<Arc<JobRunner>>,
 }
 
 impl ProxyService {
     pub fn new(
         chains_settings: ChainsSettings,
+        chains_catalog: ChainsCatalog,
+        blockscout_clients: Arc<BTreeMap<String, blockscout_client::Client>>,
         eth_bytecode_db_client: Arc<eth_bytecode_db_proto::http_client::Client>,
+        jobs: Option<Arc<JobRunner>>,
     ) -> Self {
         chains_settings
             .clone()
@@ -28,7 +40,10 @@ impl ProxyService {
             .for_each(|settings| settings.sensitive_api_key = None);
         Self {
             chains: chains_settings,
+            chains_catalog,
+            blockscout_clients,
             eth_bytecode_db_client,
+            jobs,
         }
     }
 }
@@ -37,11 +52,46 @@ impl ProxyService {
 impl Proxy for ProxyService {
     async fn list_chains(
         &self,
-        _request: Request<ListChainsRequest>,
+        request: Request<ListChainsRequest>,
     ) -> Result<Response<ListChainsResponse>, Status> {
-        let response = ListChainsResponse {
-            chains: list_chains(self).await,
-        };
+        let request = request.into_inner();
+        let verification_support = request
+            .verification_support
+            .map(|value| {
+                VerificationSupport::from_i32(value).ok_or_else(|| {
+                    Status::invalid_argument(format!("invalid verification_support={value}"))
+                })
+            })
+            .transpose()?;
+
+        let q = request.q.map(|q| q.to_lowercase());
+        let chains = list_chains(self)
+            .await
+            .into_iter()
+            .filter(|chain| {
+                q.as_ref().map_or(true, |q| {
+                    chain.name.to_lowercase().contains(q) || chain.id.to_lowercase().contains(q)
+                })
+            })
+            .filter(|chain| {
+                request.ecosystem.as_ref().map_or(true, |ecosystem| {
+                    chain
+                        .ecosystems
+                        .iter()
+                        .any(|e| e.eq_ignore_ascii_case(ecosystem))
+                })
+            })
+            .filter(|chain| {
+                request
+                    .is_testnet
+                    .map_or(true, |is_testnet| chain.is_testnet == is_testnet)
+            })
+            .filter(|chain| {
+                verification_support.map_or(true, |support| chain.verification_support() == support)
+            })
+            .collect();
+
+        let response = ListChainsResponse { chains };
 
         Ok(Response::new(response))
     }
@@ -64,19 +114,116 @@ impl Proxy for ProxyService {
         .await?;
 
         Ok(Response::new(VerificationConfig {
-            chains: list_chains(self).await,
+            chains: list_chains(self)
+                .await
+                .into_iter()
+                .filter(|chain| chain.verification_support() == VerificationSupport::Supported)
+                .collect(),
             solidity_compilers,
             vyper_compilers,
         }))
     }
+
+    async fn list_same_bytecode_contracts(
+        &self,
+        request: Request<ListSameBytecodeContractsRequest>,
+    ) -> Result<Response<ListSameBytecodeContractsResponse>, Status> {
+        let request = request.into_inner();
+
+        let source_client = self
+            .blockscout_clients
+            .get(&request.chain_id)
+            .ok_or_else(|| {
+                Status::invalid_argument(format!("chain_id={}; is not supported", request.chain_id))
+            })?;
+        let contract_address =
+            ethers_core::types::Address::from_str(&request.address).map_err(|err| {
+                Status::invalid_argument(format!(
+                    "chain_id={}, address={}; invalid address={err}",
+                    request.chain_id, request.address
+                ))
+            })?;
+        // Keep the order of the chains configuration
+        let target_clients: Vec<_> = self
+            .chains
+            .insertion_iter()
+            .filter(|(id, _)| **id != request.chain_id)
+            .filter_map(|(id, _)| self.blockscout_clients.get(id))
+            .collect();
+
+        let contracts = same_bytecode::find_same_bytecode_contracts(
+            self.eth_bytecode_db_client.as_ref(),
+            source_client,
+            &target_clients,
+            contract_address,
+        )
+        .await
+        .map_err(|err| {
+            if err.is_invalid_contract_error() {
+                Status::invalid_argument(err.to_string())
+            } else {
+                Status::internal(err.to_string())
+            }
+        })?;
+
+        let items = contracts
+            .into_iter()
+            .map(|contract| {
+                let (status, message) = match contract.status {
+                    same_bytecode::SameBytecodeStatus::SameBytecode => {
+                        (chain_contract::Status::SameBytecode, "Ok".to_string())
+                    }
+                    same_bytecode::SameBytecodeStatus::DifferentBytecode => (
+                        chain_contract::Status::DifferentBytecode,
+                        "Contract bytecode differs".to_string(),
+                    ),
+                    same_bytecode::SameBytecodeStatus::NotContract(err) => {
+                        (chain_contract::Status::NotContract, err.to_string())
+                    }
+                    same_bytecode::SameBytecodeStatus::InternalError(err) => {
+                        (chain_contract::Status::InternalError, err.to_string())
+                    }
+                };
+                ChainContract {
+                    contract: Some(Contract {
+                        chain_id: contract.chain_id,
+                        address: format!("{:#x}", contract.address),
+                    }),
+                    status: status.into(),
+                    message,
+                }
+            })
+            .collect();
+
+        Ok(Response::new(ListSameBytecodeContractsResponse { items }))
+    }
+
+    async fn get_verification_job(
+        &self,
+        request: Request<GetVerificationJobRequest>,
+    ) -> Result<Response<VerificationJob>, Status> {
+        let jobs = self
+            .jobs
+            .as_ref()
+            .ok_or_else(verification_jobs::jobs_not_enabled)?;
+        let job = jobs.get(&request.into_inner().id).await?;
+
+        Ok(Response::new(job))
+    }
 }
 
+/// Configured chains go first in the order of the configuration,
+/// followed by the chains known from the catalog only, ordered by name.
+/// Configured values take precedence over the catalog ones.
 async fn list_chains(proxy: &ProxyService) -> Vec<Chain> {
-    proxy
+    let mut catalog = proxy.chains_catalog.chains();
+
+    let mut chains: Vec<_> = proxy
         .chains
         .insertion_iter()
         .map(|(id, settings)| {
             let settings = settings.clone();
+            let catalog_chain = catalog.remove(id);
 
             let icon_url = if let Some(icon_url) = settings.icon_url {
                 icon_url.to_string()
@@ -90,7 +237,35 @@ async fn list_chains(proxy: &ProxyService) -> Vec<Chain> {
                 id: id.clone(),
                 name: settings.name,
                 icon_url,
+                ecosystems: settings
+                    .ecosystems
+                    .or_else(|| catalog_chain.as_ref().map(|c| c.ecosystems.clone()))
+                    .unwrap_or_default(),
+                is_testnet: settings
+                    .is_testnet
+                    .or_else(|| catalog_chain.as_ref().map(|c| c.is_testnet))
+                    .unwrap_or_default(),
+                verification_support: VerificationSupport::Supported.into(),
             }
         })
-        .collect()
+        .collect();
+
+    let mut catalog_chains: Vec<_> = catalog
+        .into_iter()
+        .map(|(id, chain)| Chain {
+            id,
+            name: chain.name,
+            icon_url: chain
+                .icon_url
+                .map(|url| url.to_string())
+                .unwrap_or_default(),
+            ecosystems: chain.ecosystems,
+            is_testnet: chain.is_testnet,
+            verification_support: VerificationSupport::Unsupported.into(),
+        })
+        .collect();
+    catalog_chains.sort_by(|a, b| a.name.cmp(&b.name));
+    chains.extend(catalog_chains);
+
+    chains
 }
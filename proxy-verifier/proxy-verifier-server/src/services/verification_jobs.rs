@@ -0,0 +1,323 @@
+use crate::{
+    proto::{
+        verification_job::{self, registry_submission, RegistrySubmission},
+        Contract, SolidityVerifyMultiPartRequest, SolidityVerifyStandardJsonRequest,
+        VerificationJob, VerificationResponse, VyperVerifyMultiPartRequest,
+        VyperVerifyStandardJsonRequest,
+    },
+    settings::JobsSettings,
+};
+use entity::{sea_orm_active_enums::VerificationJobStatus, verification_jobs};
+use proxy_verifier_logic::{
+    solidity_verifier_multi_part, solidity_verifier_standard_json, sourcify_submission,
+    vyper_verifier_multi_part, vyper_verifier_standard_json,
+};
+use sea_orm::{prelude::Uuid, DatabaseConnection};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+use tokio::sync::Notify;
+use tonic::Status;
+
+/// Original verification request of the job.
+/// Stored in the database, so that unfinished jobs could be restarted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", content = "request", rename_all = "snake_case")]
+pub enum JobRequest {
+    SolidityMultiPart(SolidityVerifyMultiPartRequest),
+    SolidityStandardJson(SolidityVerifyStandardJsonRequest),
+    VyperMultiPart(VyperVerifyMultiPartRequest),
+    VyperStandardJson(VyperVerifyStandardJsonRequest),
+}
+
+impl JobRequest {
+    fn contracts(&self) -> &[Contract] {
+        match self {
+            JobRequest::SolidityMultiPart(request) => &request.contracts,
+            JobRequest::SolidityStandardJson(request) => &request.contracts,
+            JobRequest::VyperMultiPart(request) => &request.contracts,
+            JobRequest::VyperStandardJson(request) => &request.contracts,
+        }
+    }
+}
+
+/// Persists the verification jobs and processes them in the background.
+///
+/// Jobs are claimed from the database by [`JobsSettings::concurrency`] workers,
+/// so the jobs submitted to any instance sharing the database are processed exactly once.
+pub struct JobRunner {
+    db: Arc<DatabaseConnection>,
+    settings: JobsSettings,
+    /// Wakes up a worker when a job is submitted to this instance
+    new_job: Notify,
+    blockscout_clients: Arc<BTreeMap<String, blockscout_client::Client>>,
+    eth_bytecode_db_client: Arc<eth_bytecode_db_proto::http_client::Client>,
+    /// If set, verified contracts are submitted to Sourcify as well
+    sourcify_client: Option<Arc<sourcify::Client>>,
+}
+
+impl JobRunner {
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        settings: JobsSettings,
+        blockscout_clients: Arc<BTreeMap<String, blockscout_client::Client>>,
+        eth_bytecode_db_client: Arc<eth_bytecode_db_proto::http_client::Client>,
+        sourcify_client: Option<Arc<sourcify::Client>>,
+    ) -> Self {
+        Self {
+            db,
+            settings,
+            new_job: Notify::new(),
+            blockscout_clients,
+            eth_bytecode_db_client,
+            sourcify_client,
+        }
+    }
+
+    /// Persists the job to be processed by the workers.
+    /// The request is validated beforehand, so that invalid requests are rejected immediately.
+    pub async fn submit(self: &Arc<Self>, request: JobRequest) -> Result<VerificationJob, Status> {
+        super::contracts_proto_to_inner(self.blockscout_clients.as_ref(), request.contracts())?;
+
+        let request_value = serde_json::to_value(&request)
+            .map_err(|err| Status::internal(format!("failed to serialize the request: {err}")))?;
+        let job = proxy_verifier_logic::verification_jobs::create(self.db.as_ref(), request_value)
+            .await
+            .map_err(|err| Status::internal(format!("failed to create the job: {err}")))?;
+
+        self.new_job.notify_one();
+
+        job_model_to_proto(job)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<VerificationJob, Status> {
+        let id = Uuid::from_str(id)
+            .map_err(|err| Status::invalid_argument(format!("invalid job id: {err}")))?;
+        let job = proxy_verifier_logic::verification_jobs::get(self.db.as_ref(), id)
+            .await
+            .map_err(|err| Status::internal(format!("failed to retrieve the job: {err}")))?
+            .ok_or_else(|| Status::not_found("job not found"))?;
+
+        job_model_to_proto(job)
+    }
+
+    /// Starts the workers processing the jobs, including the ones
+    /// interrupted by the previous shutdown of the service
+    pub fn start(self: &Arc<Self>) {
+        for _ in 0..self.settings.concurrency.get() {
+            tokio::spawn(self.clone().work());
+        }
+        tokio::spawn(self.clone().requeue_stale());
+    }
+
+    async fn work(self: Arc<Self>) {
+        loop {
+            match proxy_verifier_logic::verification_jobs::claim_next(self.db.as_ref()).await {
+                Ok(Some(job)) => self.process(job).await,
+                Ok(None) => {
+                    tokio::select! {
+                        _ = self.new_job.notified() => {}
+                        _ = tokio::time::sleep(self.settings.poll_interval) => {}
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(err = %err, "failed to claim a verification job");
+                    tokio::time::sleep(self.settings.poll_interval).await;
+                }
+            }
+        }
+    }
+
+    /// Returns the jobs interrupted by the restarts back to the queue
+    async fn requeue_stale(self: Arc<Self>) {
+        let mut interval = tokio::time::interval(self.settings.poll_interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            match proxy_verifier_logic::verification_jobs::requeue_stale(
+                self.db.as_ref(),
+                self.settings.stale_timeout,
+            )
+            .await
+            {
+                Ok(0) => {}
+                Ok(count) => {
+                    tracing::info!(count, "restarting interrupted verification jobs");
+                    self.new_job.notify_waiters();
+                }
+                Err(err) => {
+                    tracing::error!(err = %err, "failed to requeue interrupted verification jobs")
+                }
+            }
+        }
+    }
+
+    async fn process(&self, job: verification_jobs::Model) {
+        let id = job.id;
+        let res = match serde_json::from_value::<JobRequest>(job.request) {
+            Ok(request) => self.run(id, request).await,
+            Err(err) => Err(anyhow::anyhow!("invalid job request: {err}")),
+        };
+        if let Err(err) = res {
+            tracing::error!(job_id = %id, err = %err, "verification job failed");
+            let _ = proxy_verifier_logic::verification_jobs::mark_failed(
+                self.db.as_ref(),
+                id,
+                err.to_string(),
+            )
+            .await;
+        }
+    }
+
+    async fn run(&self, id: Uuid, request: JobRequest) -> Result<(), anyhow::Error> {
+        let (response, registry_submissions) = self.verify(request).await?;
+        let result = serde_json::to_value(response)?;
+        let registry_submissions = (!registry_submissions.is_empty())
+            .then(|| serde_json::to_value(registry_submissions))
+            .transpose()?;
+
+        proxy_verifier_logic::verification_jobs::mark_finished(
+            self.db.as_ref(),
+            id,
+            result,
+            registry_submissions,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the verification response and the outcomes of the submissions into the registries
+    async fn verify(
+        &self,
+        request: JobRequest,
+    ) -> Result<(VerificationResponse, Vec<RegistrySubmission>), Status> {
+        let contracts =
+            super::contracts_proto_to_inner(self.blockscout_clients.as_ref(), request.contracts())?;
+        let eth_bytecode_db_client = self.eth_bytecode_db_client.as_ref();
+        let response = match request {
+            JobRequest::SolidityMultiPart(request) => {
+                let request = solidity_verifier_multi_part::VerificationRequest {
+                    compiler: request.compiler,
+                    evm_version: request.evm_version,
+                    optimization_runs: request.optimization_runs,
+                    source_files: request.source_files,
+                    libraries: request.libraries,
+                };
+                solidity_verifier_multi_part::verify(
+                    eth_bytecode_db_client,
+                    contracts.clone(),
+                    request,
+                )
+                .await
+            }
+            JobRequest::SolidityStandardJson(request) => {
+                let request = solidity_verifier_standard_json::VerificationRequest {
+                    compiler: request.compiler,
+                    input: request.input,
+                };
+                solidity_verifier_standard_json::verify(
+                    eth_bytecode_db_client,
+                    contracts.clone(),
+                    request,
+                )
+                .await
+            }
+            JobRequest::VyperMultiPart(request) => {
+                let request = vyper_verifier_multi_part::VerificationRequest {
+                    compiler: request.compiler,
+                    evm_version: request.evm_version,
+                    source_files: request.source_files,
+                    interfaces: request.interfaces,
+                };
+                vyper_verifier_multi_part::verify(
+                    eth_bytecode_db_client,
+                    contracts.clone(),
+                    request,
+                )
+                .await
+            }
+            JobRequest::VyperStandardJson(request) => {
+                let request = vyper_verifier_standard_json::VerificationRequest {
+                    compiler: request.compiler,
+                    input: request.input,
+                };
+                vyper_verifier_standard_json::verify(
+                    eth_bytecode_db_client,
+                    contracts.clone(),
+                    request,
+                )
+                .await
+            }
+        };
+
+        let registry_submissions = match (&self.sourcify_client, &response) {
+            (
+                Some(sourcify_client),
+                proxy_verifier_logic::VerificationResponse::Results(results),
+            ) => sourcify_submission::submit_to_sourcify(sourcify_client, &contracts, results)
+                .await
+                .into_iter()
+                .map(registry_submission_inner_to_proto)
+                .collect(),
+            _ => vec![],
+        };
+
+        Ok((
+            super::verification_response_inner_to_proto(response),
+            registry_submissions,
+        ))
+    }
+}
+
+/// Returns an error for the submit endpoints when the database is not configured
+pub fn jobs_not_enabled() -> Status {
+    Status::unimplemented("verification jobs require the database to be configured")
+}
+
+fn job_model_to_proto(job: verification_jobs::Model) -> Result<VerificationJob, Status> {
+    let status = match job.status {
+        VerificationJobStatus::Pending => verification_job::Status::Pending,
+        VerificationJobStatus::Running => verification_job::Status::Running,
+        VerificationJobStatus::Finished => verification_job::Status::Finished,
+        VerificationJobStatus::Failed => verification_job::Status::Failed,
+    };
+    let result = job
+        .result
+        .map(serde_json::from_value::<VerificationResponse>)
+        .transpose()
+        .map_err(|err| Status::internal(format!("invalid job result: {err}")))?;
+    let registry_submissions = job
+        .registry_submissions
+        .map(serde_json::from_value::<Vec<RegistrySubmission>>)
+        .transpose()
+        .map_err(|err| Status::internal(format!("invalid job registry submissions: {err}")))?
+        .unwrap_or_default();
+
+    Ok(VerificationJob {
+        id: job.id.to_string(),
+        status: status.into(),
+        result,
+        error: job.error,
+        created_at: job.created_at.and_utc().to_rfc3339(),
+        updated_at: job.updated_at.and_utc().to_rfc3339(),
+        registry_submissions,
+    })
+}
+
+fn registry_submission_inner_to_proto(
+    submission: sourcify_submission::RegistrySubmission,
+) -> RegistrySubmission {
+    let status = match submission.status {
+        sourcify_submission::SubmissionStatus::Success => registry_submission::Status::Success,
+        sourcify_submission::SubmissionStatus::Failure => registry_submission::Status::Failure,
+        sourcify_submission::SubmissionStatus::Skipped => registry_submission::Status::Skipped,
+    };
+    RegistrySubmission {
+        contract: Some(Contract {
+            chain_id: submission.chain_id,
+            address: format!("{:#x}", submission.address),
+        }),
+        registry: submission.registry,
+        status: status.into(),
+        message: submission.message,
+    }
+}
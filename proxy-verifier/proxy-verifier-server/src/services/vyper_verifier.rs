@@ -1,6 +1,11 @@
+use super::{
+    api_keys::{self, ApiKeyGuard},
+    verification_jobs::{self, JobRequest, JobRunner},
+};
 use crate::proto::{
     vyper_verifier_server::VyperVerifier, ListCompilersRequest, ListCompilersResponse,
-    VerificationResponse, VyperVerifyMultiPartRequest, VyperVerifyStandardJsonRequest,
+    VerificationJob, VerificationPreviewResponse, VerificationResponse,
+    VyperVerifyMultiPartRequest, VyperVerifyStandardJsonRequest,
 };
 use async_trait::async_trait;
 use proxy_verifier_logic::{vyper_verifier_multi_part, vyper_verifier_standard_json};
@@ -10,16 +15,26 @@ use tonic::{Request, Response, Status};
 pub struct VyperVerifierService {
     blockscout_clients: Arc<BTreeMap<String, blockscout_client::Client>>,
     eth_bytecode_db_client: Arc<eth_bytecode_db_proto::http_client::Client>,
+    verifier_client: Arc<smart_contract_verifier_proto::http_client::Client>,
+    jobs: Option<Arc<JobRunner>>,
+    /// If set, verification requests are limited by the api key quotas
+    api_keys: Option<Arc<ApiKeyGuard>>,
 }
 
 impl VyperVerifierService {
     pub fn new(
         blockscout_clients: Arc<BTreeMap<String, blockscout_client::Client>>,
         eth_bytecode_db_client: Arc<eth_bytecode_db_proto::http_client::Client>,
+        verifier_client: Arc<smart_contract_verifier_proto::http_client::Client>,
+        jobs: Option<Arc<JobRunner>>,
+        api_keys: Option<Arc<ApiKeyGuard>>,
     ) -> Self {
         Self {
             blockscout_clients,
             eth_bytecode_db_client,
+            verifier_client,
+            jobs,
+            api_keys,
         }
     }
 }
@@ -30,6 +45,12 @@ impl VyperVerifier for VyperVerifierService {
         &self,
         request: Request<VyperVerifyMultiPartRequest>,
     ) -> Result<Response<VerificationResponse>, Status> {
+        api_keys::check_quota(
+            self.api_keys.as_deref(),
+            &request,
+            request.get_ref().contracts.len(),
+        )
+        .await?;
         let request = request.into_inner();
         let verification_request = vyper_verifier_multi_part::VerificationRequest {
             compiler: request.compiler,
@@ -52,6 +73,12 @@ impl VyperVerifier for VyperVerifierService {
         &self,
         request: Request<VyperVerifyStandardJsonRequest>,
     ) -> Result<Response<VerificationResponse>, Status> {
+        api_keys::check_quota(
+            self.api_keys.as_deref(),
+            &request,
+            request.get_ref().contracts.len(),
+        )
+        .await?;
         let request = request.into_inner();
         let verification_request = vyper_verifier_standard_json::VerificationRequest {
             compiler: request.compiler,
@@ -68,6 +95,100 @@ impl VyperVerifier for VyperVerifierService {
         .await
     }
 
+    async fn submit_multi_part(
+        &self,
+        request: Request<VyperVerifyMultiPartRequest>,
+    ) -> Result<Response<VerificationJob>, Status> {
+        let jobs = self
+            .jobs
+            .as_ref()
+            .ok_or_else(verification_jobs::jobs_not_enabled)?;
+        api_keys::check_quota(
+            self.api_keys.as_deref(),
+            &request,
+            request.get_ref().contracts.len(),
+        )
+        .await?;
+        let request = request.into_inner();
+        let job = jobs.submit(JobRequest::VyperMultiPart(request)).await?;
+
+        Ok(Response::new(job))
+    }
+
+    async fn submit_standard_json(
+        &self,
+        request: Request<VyperVerifyStandardJsonRequest>,
+    ) -> Result<Response<VerificationJob>, Status> {
+        let jobs = self
+            .jobs
+            .as_ref()
+            .ok_or_else(verification_jobs::jobs_not_enabled)?;
+        api_keys::check_quota(
+            self.api_keys.as_deref(),
+            &request,
+            request.get_ref().contracts.len(),
+        )
+        .await?;
+        let request = request.into_inner();
+        let job = jobs.submit(JobRequest::VyperStandardJson(request)).await?;
+
+        Ok(Response::new(job))
+    }
+
+    async fn preview_multi_part(
+        &self,
+        request: Request<VyperVerifyMultiPartRequest>,
+    ) -> Result<Response<VerificationPreviewResponse>, Status> {
+        api_keys::check_quota(
+            self.api_keys.as_deref(),
+            &request,
+            request.get_ref().contracts.len(),
+        )
+        .await?;
+        let request = request.into_inner();
+        let verification_request = vyper_verifier_multi_part::VerificationRequest {
+            compiler: request.compiler,
+            evm_version: request.evm_version,
+            source_files: request.source_files,
+            interfaces: request.interfaces,
+        };
+
+        super::preview(
+            self.blockscout_clients.as_ref(),
+            self.verifier_client.as_ref(),
+            request.contracts,
+            verification_request,
+            vyper_verifier_multi_part::preview,
+        )
+        .await
+    }
+
+    async fn preview_standard_json(
+        &self,
+        request: Request<VyperVerifyStandardJsonRequest>,
+    ) -> Result<Response<VerificationPreviewResponse>, Status> {
+        api_keys::check_quota(
+            self.api_keys.as_deref(),
+            &request,
+            request.get_ref().contracts.len(),
+        )
+        .await?;
+        let request = request.into_inner();
+        let verification_request = vyper_verifier_standard_json::VerificationRequest {
+            compiler: request.compiler,
+            input: request.input,
+        };
+
+        super::preview(
+            self.blockscout_clients.as_ref(),
+            self.verifier_client.as_ref(),
+            request.contracts,
+            verification_request,
+            vyper_verifier_standard_json::preview,
+        )
+        .await
+    }
+
     async fn list_compilers(
         &self,
         _request: Request<ListCompilersRequest>,
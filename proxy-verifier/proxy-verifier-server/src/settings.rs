@@ -1,9 +1,11 @@
 use blockscout_service_launcher::{
+    database::DatabaseSettings,
     launcher::{ConfigSettings, MetricsSettings, ServerSettings},
     tracing::{JaegerSettings, TracingSettings},
 };
 use serde::Deserialize;
-use std::{path::PathBuf, str::FromStr};
+use serde_with::serde_as;
+use std::{num::NonZeroUsize, path::PathBuf, str::FromStr, time};
 
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
@@ -19,12 +21,42 @@ pub struct Settings {
 
     pub chains_config: Option<PathBuf>,
 
+    #[serde(default)]
+    pub chains_catalog: ChainsCatalogSettings,
+
     #[serde(default)]
     pub eth_bytecode_db: EthBytecodeDbSettings,
+
+    /// Used by the preview endpoints, which compile the sources without saving the results
+    #[serde(default)]
+    pub smart_contract_verifier: SmartContractVerifierSettings,
+
+    /// Required for the asynchronous verification jobs
+    #[serde(default)]
+    pub database: Option<DatabaseSettings>,
+
+    #[serde(default)]
+    pub jobs: JobsSettings,
+
+    #[serde(default)]
+    pub sourcify: SourcifySettings,
+
+    #[serde(default)]
+    pub api_keys: ApiKeysSettings,
 }
 
 impl ConfigSettings for Settings {
     const SERVICE_NAME: &'static str = "PROXY_VERIFIER";
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.jobs.poll_interval.is_zero() {
+            anyhow::bail!("jobs poll interval must be non-zero");
+        }
+        if self.chains_catalog.enabled && self.chains_catalog.refresh_interval.is_zero() {
+            anyhow::bail!("chains catalog refresh interval must be non-zero");
+        }
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
@@ -50,6 +82,107 @@ impl Default for EthBytecodeDbSettings {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SmartContractVerifierSettings {
+    #[serde(default = "default_smart_contract_verifier_http_url")]
+    pub http_url: url::Url,
+    #[serde(default = "default_smart_contract_verifier_max_retries")]
+    pub max_retries: u32,
+    #[serde(default)]
+    pub probe_url: bool,
+}
+
+impl Default for SmartContractVerifierSettings {
+    fn default() -> Self {
+        Self {
+            http_url: default_smart_contract_verifier_http_url(),
+            max_retries: default_smart_contract_verifier_max_retries(),
+            probe_url: Default::default(),
+        }
+    }
+}
+
+/// If enabled, contracts successfully verified by the verification jobs
+/// are submitted to Sourcify as well.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SourcifySettings {
+    pub enabled: bool,
+    pub url: url::Url,
+    pub max_retries: u32,
+}
+
+impl Default for SourcifySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: url::Url::from_str("https://sourcify.dev/server/").unwrap(),
+            max_retries: 3,
+        }
+    }
+}
+
+/// Verification jobs are processed by a fixed number of workers,
+/// which take the pending jobs from the database.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct JobsSettings {
+    /// Max number of jobs processed concurrently by the instance
+    pub concurrency: NonZeroUsize,
+    /// Interval between the checks for the pending jobs submitted to other instances
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub poll_interval: time::Duration,
+    /// Running jobs not finished within the timeout are considered interrupted and restarted
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub stale_timeout: time::Duration,
+}
+
+impl Default for JobsSettings {
+    fn default() -> Self {
+        Self {
+            concurrency: NonZeroUsize::new(4).unwrap(),
+            poll_interval: time::Duration::from_secs(5),
+            stale_timeout: time::Duration::from_secs(3600),
+        }
+    }
+}
+
+/// Api keys are stored in the database, so it must be configured if the keys are enabled.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiKeysSettings {
+    /// If enabled, verification requests must contain a valid api key
+    /// and are limited by the daily quota of the key
+    pub enabled: bool,
+    /// Key authorized to manage the api keys. Admin endpoints are disabled if not set
+    pub admin_key: Option<String>,
+}
+
+/// Chains from blockscout-chains catalog are listed alongside the configured ones,
+/// so that users could find the chains verification is not supported for yet.
+#[serde_as]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChainsCatalogSettings {
+    pub enabled: bool,
+    /// If not set, the default blockscout-chains url is used
+    pub url: Option<url::Url>,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub refresh_interval: time::Duration,
+}
+
+impl Default for ChainsCatalogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: None,
+            refresh_interval: time::Duration::from_secs(3600),
+        }
+    }
+}
+
 fn default_eth_bytecode_db_max_retries() -> u32 {
     3
 }
@@ -57,3 +190,11 @@ fn default_eth_bytecode_db_max_retries() -> u32 {
 fn default_eth_bytecode_db_http_url() -> url::Url {
     url::Url::from_str("https://eth-bytecode-db.services.blockscout.com").unwrap()
 }
+
+fn default_smart_contract_verifier_max_retries() -> u32 {
+    3
+}
+
+fn default_smart_contract_verifier_http_url() -> url::Url {
+    url::Url::from_str("https://http.sc-verifier.services.blockscout.com").unwrap()
+}
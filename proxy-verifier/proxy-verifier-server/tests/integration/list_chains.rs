@@ -50,3 +50,63 @@ async fn test_chains_listed_in_correct_order() {
         "Invalid order for `api/v1/verification/config` endpoint"
     );
 }
+
+#[tokio::test]
+async fn test_chains_filtered() {
+    let config_file = helpers::create_temp_config(serde_json::json!({
+        "1": {
+            "name": "Ethereum",
+            "api_url": "https://eth.blockscout.com/api/",
+            "sensitive_api_key": "null",
+            "ecosystems": ["Ethereum"],
+            "is_testnet": false
+        },
+        "10": {
+            "name": "Optimism",
+            "api_url": "https://optimism.blockscout.com/api/",
+            "sensitive_api_key": "null",
+            "ecosystems": ["Optimism", "Superchain"],
+            "is_testnet": false
+        },
+        "11155111": {
+            "name": "Sepolia",
+            "api_url": "https://eth-sepolia.blockscout.com/api/",
+            "sensitive_api_key": "null",
+            "ecosystems": ["Ethereum"],
+            "is_testnet": true
+        },
+    }));
+
+    let base = helpers::init_proxy_verifier_server(|mut settings| {
+        settings.chains_config = Some(config_file.as_ref().to_path_buf());
+        settings
+    })
+    .await;
+
+    let chain_ids = |response: proxy_verifier_v1::ListChainsResponse| -> Vec<String> {
+        response.chains.into_iter().map(|chain| chain.id).collect()
+    };
+
+    let response = test_server::send_get_request(&base, "/api/v1/chains?q=sepo").await;
+    assert_eq!(
+        vec!["11155111"],
+        chain_ids(response),
+        "Invalid search result"
+    );
+
+    let response = test_server::send_get_request(&base, "/api/v1/chains?ecosystem=ethereum").await;
+    assert_eq!(
+        vec!["1", "11155111"],
+        chain_ids(response),
+        "Invalid ecosystem filter result"
+    );
+
+    let response =
+        test_server::send_get_request(&base, "/api/v1/chains?ecosystem=ethereum&isTestnet=false")
+            .await;
+    assert_eq!(
+        vec!["1"],
+        chain_ids(response),
+        "Invalid testnet filter result"
+    );
+}
@@ -335,6 +335,8 @@ fn parse_bytecode_parts(
         // `i` is the first different byte. The metadata hash itself started somewhere earlier
         // (at least for "a1"/"a2" indicating number of elements in cbor mapping).
         // Next steps are trying to find that beginning.
+        // TODO: replace with `solidity_metadata::find_metadata_hashes`
+        //  once the dependency is bumped to solidity-metadata 2.0.
 
         let (metadata, metadata_length) = loop {
             let mut result = MetadataHash::from_cbor(&raw[i..]);
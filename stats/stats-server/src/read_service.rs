@@ -13,6 +13,7 @@ use async_trait::async_trait;
 use chrono::{DateTime, NaiveDate, Utc};
 use futures::{stream::FuturesOrdered, StreamExt};
 use proto_v1::stats_service_server::StatsService;
+use rust_decimal::Decimal;
 use sea_orm::{DatabaseConnection, DbErr};
 use stats::{
     counters::{
@@ -112,6 +113,53 @@ fn convert_resolution(input: proto_v1::Resolution) -> ResolutionKind {
     }
 }
 
+/// Aggregate values of the points with `function`.
+///
+/// Values are aggregated as decimals, so that large integer counters
+/// do not lose precision.
+///
+/// Returns `None` if there is nothing to aggregate (except for sum,
+/// which is zero for empty input).
+fn aggregate_points(
+    points: &[proto_v1::Point],
+    function: proto_v1::AggregationFunction,
+) -> Result<Option<Decimal>, Status> {
+    let values = points
+        .iter()
+        .map(|p| {
+            Decimal::from_str(&p.value)
+                .or_else(|_| Decimal::from_scientific(&p.value))
+                .map_err(|_| {
+                    Status::invalid_argument(format!(
+                        "value '{}' at {} cannot be aggregated",
+                        p.value, p.date
+                    ))
+                })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let sum = || {
+        values
+            .iter()
+            .try_fold(Decimal::ZERO, |sum, v| sum.checked_add(*v))
+            .ok_or_else(|| Status::out_of_range("aggregated value is too large"))
+    };
+    let result = match function {
+        proto_v1::AggregationFunction::Unspecified => {
+            return Err(Status::invalid_argument(
+                "aggregation function must be specified",
+            ))
+        }
+        proto_v1::AggregationFunction::Sum => Some(sum()?),
+        proto_v1::AggregationFunction::Average => match values.len() {
+            0 => None,
+            len => Some(sum()? / Decimal::from(len)),
+        },
+        proto_v1::AggregationFunction::Max => values.iter().max().copied(),
+        proto_v1::AggregationFunction::Min => values.iter().min().copied(),
+    };
+    Ok(result.map(|v| v.normalize()))
+}
+
 fn get_line_chart_query_handle(
     line_chart: &EnabledChartEntry,
     resolution: ResolutionKind,
@@ -210,6 +258,7 @@ impl ReadService {
             blockscout_applied_migrations: migrations,
             update_time_override: Some(query_time),
             force_full: false,
+            market_data: None,
         });
         query_handle
             .query_data(&context, range, points_limit, true)
@@ -407,6 +456,49 @@ impl StatsService for ReadService {
         Ok(Response::new(chart_data))
     }
 
+    async fn get_line_chart_aggregate(
+        &self,
+        request: Request<proto_v1::GetLineChartAggregateRequest>,
+    ) -> Result<Response<proto_v1::LineChartAggregate>, Status> {
+        let request = request.into_inner();
+        let resolution = convert_resolution(request.resolution());
+        let function = request.function();
+        if function == proto_v1::AggregationFunction::Unspecified {
+            return Err(Status::invalid_argument(
+                "aggregation function must be specified",
+            ));
+        }
+        let chart_name = request.name;
+
+        let request_range = inclusive_date_range_to_query_range(
+            request
+                .from
+                .and_then(|date| NaiveDate::from_str(&date).ok()),
+            request.to.and_then(|date| NaiveDate::from_str(&date).ok()),
+        );
+        let points_limit = Some(self.limits.requested_points_limit);
+
+        let chart_data = self
+            .query_line_chart(
+                chart_name,
+                resolution,
+                request_range,
+                points_limit,
+                Utc::now(),
+            )
+            .await?;
+        let value = aggregate_points(&chart_data.chart, function)?;
+
+        Ok(Response::new(proto_v1::LineChartAggregate {
+            value: value.map(|v| v.to_string()),
+            from: chart_data.chart.first().map(|p| p.date.clone()),
+            to: chart_data.chart.last().map(|p| p.date_to.clone()),
+            points_count: chart_data.chart.len() as u64,
+            is_approximate: chart_data.chart.iter().any(|p| p.is_approximate),
+            info: chart_data.info,
+        }))
+    }
+
     async fn get_line_charts(
         &self,
         _request: Request<proto_v1::GetLineChartsRequest>,
@@ -497,3 +589,95 @@ impl StatsService for ReadService {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use proto_v1::AggregationFunction;
+
+    fn points(values: &[&str]) -> Vec<proto_v1::Point> {
+        values
+            .iter()
+            .map(|v| proto_v1::Point {
+                date: "2022-11-10".into(),
+                date_to: "2022-11-10".into(),
+                value: v.to_string(),
+                is_approximate: false,
+            })
+            .collect()
+    }
+
+    fn aggregate(values: &[&str], function: AggregationFunction) -> Option<String> {
+        aggregate_points(&points(values), function)
+            .unwrap()
+            .map(|v| v.to_string())
+    }
+
+    #[test]
+    fn aggregate_points_works() {
+        let data = ["1", "2.5", "-0.5", "4"];
+        assert_eq!(
+            aggregate(&data, AggregationFunction::Sum),
+            Some("7".to_string())
+        );
+        assert_eq!(
+            aggregate(&data, AggregationFunction::Average),
+            Some("1.75".to_string())
+        );
+        assert_eq!(
+            aggregate(&data, AggregationFunction::Max),
+            Some("4".to_string())
+        );
+        assert_eq!(
+            aggregate(&data, AggregationFunction::Min),
+            Some("-0.5".to_string())
+        );
+    }
+
+    #[test]
+    fn aggregate_points_keeps_precision() {
+        // not representable as f64 exactly
+        let data = ["9007199254740993", "9007199254740993"];
+        assert_eq!(
+            aggregate(&data, AggregationFunction::Sum),
+            Some("18014398509481986".to_string())
+        );
+        assert_eq!(
+            aggregate(&data, AggregationFunction::Average),
+            Some("9007199254740993".to_string())
+        );
+    }
+
+    #[test]
+    fn aggregate_points_empty() {
+        assert_eq!(
+            aggregate(&[], AggregationFunction::Sum),
+            Some("0".to_string())
+        );
+        for function in [
+            AggregationFunction::Average,
+            AggregationFunction::Max,
+            AggregationFunction::Min,
+        ] {
+            assert_eq!(aggregate(&[], function), None);
+        }
+    }
+
+    #[test]
+    fn aggregate_points_fails_on_bad_input() {
+        let data = points(&["1", "abc"]);
+        assert_eq!(
+            aggregate_points(&data, AggregationFunction::Sum)
+                .unwrap_err()
+                .code(),
+            tonic::Code::InvalidArgument
+        );
+        assert_eq!(
+            aggregate_points(&points(&["1"]), AggregationFunction::Unspecified)
+                .unwrap_err()
+                .code(),
+            tonic::Code::InvalidArgument
+        );
+    }
+}
@@ -331,6 +331,7 @@ impl RuntimeSetup {
             Arc::new(AverageTxnFeeGroup),
             Arc::new(GasUsedGrowthGroup),
             Arc::new(NativeCoinSupplyGroup),
+            Arc::new(NativeCoinMarketGroup),
             Arc::new(NewBlocksGroup),
             Arc::new(TxnsFeeGroup),
             Arc::new(TxnsSuccessRateGroup),
@@ -6,7 +6,10 @@ use crate::{
     health::HealthService,
     read_service::ReadService,
     runtime_setup::RuntimeSetup,
-    settings::{handle_disable_internal_transactions, handle_enable_all_arbitrum, Settings},
+    settings::{
+        handle_disable_internal_transactions, handle_enable_all_arbitrum, handle_market_data,
+        Settings,
+    },
     update_service::UpdateService,
 };
 
@@ -14,7 +17,7 @@ use anyhow::Context;
 use blockscout_endpoint_swagger::route_swagger;
 use blockscout_service_launcher::launcher::{self, LaunchSettings};
 use sea_orm::{ConnectOptions, Database};
-use stats::metrics;
+use stats::{market_data::MarketDataProvider, metrics, ChartKey};
 use stats_proto::blockscout::stats::v1::{
     health_actix::route_health,
     health_server::HealthServer,
@@ -72,6 +75,7 @@ pub async fn stats(mut settings: Settings) -> Result<(), anyhow::Error> {
         &mut settings.conditional_start,
         &mut charts_config,
     );
+    handle_market_data(&settings.market_data, &mut charts_config);
     let mut opt = ConnectOptions::new(settings.db_url.clone());
     opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
     blockscout_service_launcher::database::initialize_postgres::<stats::migration::Migrator>(
@@ -108,8 +112,17 @@ pub async fn stats(mut settings: Settings) -> Result<(), anyhow::Error> {
 
     let blockscout_api_config = init_blockscout_api_client(&settings).await?;
 
-    let update_service =
-        Arc::new(UpdateService::new(db.clone(), blockscout.clone(), charts.clone()).await?);
+    let market_data = if settings.market_data.enabled {
+        let provider = MarketDataProvider::new(settings.market_data.provider_config())
+            .context("market data provider")?;
+        Some(Arc::new(provider))
+    } else {
+        None
+    };
+
+    let update_service = Arc::new(
+        UpdateService::new(db.clone(), blockscout.clone(), charts.clone(), market_data).await?,
+    );
 
     let update_service_handle = tokio::spawn(async move {
         // Wait for blockscout to index, if necessary.
@@ -133,7 +146,17 @@ pub async fn stats(mut settings: Settings) -> Result<(), anyhow::Error> {
     });
 
     if settings.metrics.enabled {
-        metrics::initialize_metrics(charts.charts_info.keys().map(|f| f.as_str()));
+        let enabled_keys: Vec<_> = charts
+            .charts_info
+            .iter()
+            .flat_map(|(name, entry)| {
+                entry
+                    .resolutions
+                    .keys()
+                    .map(|resolution| ChartKey::new(name.clone(), *resolution))
+            })
+            .collect();
+        metrics::initialize_metrics(enabled_keys.iter());
     }
 
     let read_service =
@@ -9,10 +9,14 @@ use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
 use stats::{
     counters::{LastNewContracts, TotalOperationalTxns},
-    lines::{ContractsGrowth, NewContracts, NewOperationalTxns, OperationalTxnsGrowth},
+    lines::{
+        ContractsGrowth, MarketCap, NativeCoinPrice, NewContracts, NewOperationalTxns,
+        OperationalTxnsGrowth,
+    },
+    market_data::MarketDataProviderConfig,
     ChartProperties,
 };
-use std::{net::SocketAddr, path::PathBuf, str::FromStr};
+use std::{net::SocketAddr, path::PathBuf, str::FromStr, time::Duration};
 use tracing::warn;
 
 use crate::config::{self, types::AllChartSettings};
@@ -45,6 +49,7 @@ pub struct Settings {
     pub concurrent_start_updates: usize,
     pub limits: LimitsSettings,
     pub conditional_start: StartConditionSettings,
+    pub market_data: MarketDataSettings,
     pub charts_config: PathBuf,
     pub layout_config: PathBuf,
     pub update_groups_config: PathBuf,
@@ -78,6 +83,7 @@ impl Default for Settings {
             concurrent_start_updates: 3,
             limits: Default::default(),
             conditional_start: Default::default(),
+            market_data: Default::default(),
             charts_config: PathBuf::from_str("config/charts.json").unwrap(),
             layout_config: PathBuf::from_str("config/layout.json").unwrap(),
             update_groups_config: PathBuf::from_str("config/update_groups.json").unwrap(),
@@ -158,6 +164,31 @@ pub fn handle_enable_all_arbitrum(
     }
 }
 
+/// Disables the market data charts if the provider is disabled.
+/// Otherwise, the units not set in the config are taken from the provider currency.
+pub fn handle_market_data(
+    market_data: &MarketDataSettings,
+    charts: &mut config::charts::Config<AllChartSettings>,
+) {
+    for key in [NativeCoinPrice::key().name(), MarketCap::key().name()] {
+        let Some(settings) = charts.lines.get_mut(key) else {
+            continue;
+        };
+        if !market_data.enabled {
+            if settings.enabled {
+                warn!(
+                    "Chart {} requires market data provider, but it's disabled. \
+                    Disabling the chart.",
+                    key
+                );
+                settings.enabled = false;
+            }
+        } else if settings.units.is_none() {
+            settings.units = Some(market_data.vs_currency.to_uppercase());
+        }
+    }
+}
+
 /// Various limits like rate limiting and restrictions on input.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(default, deny_unknown_fields)]
@@ -179,6 +210,51 @@ impl Default for LimitsSettings {
     }
 }
 
+/// Provider of native coin price and market cap.
+/// Any CoinGecko-compatible API can be used.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketDataSettings {
+    pub enabled: bool,
+    pub url: url::Url,
+    /// Id of the native coin in the provider
+    pub coin_id: String,
+    pub vs_currency: String,
+    pub api_key: Option<String>,
+    /// Header to pass [`MarketDataSettings::api_key`] in
+    pub api_key_header: String,
+    pub request_timeout_secs: u64,
+}
+
+impl Default for MarketDataSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: url::Url::parse("https://api.coingecko.com/api/v3/").unwrap(),
+            coin_id: "ethereum".to_string(),
+            vs_currency: "usd".to_string(),
+            api_key: None,
+            api_key_header: "x-cg-pro-api-key".to_string(),
+            request_timeout_secs: 30,
+        }
+    }
+}
+
+impl MarketDataSettings {
+    pub fn provider_config(&self) -> MarketDataProviderConfig {
+        MarketDataProviderConfig {
+            url: self.url.clone(),
+            coin_id: self.coin_id.clone(),
+            vs_currency: self.vs_currency.clone(),
+            api_key: self
+                .api_key
+                .clone()
+                .map(|key| (self.api_key_header.clone(), key)),
+            timeout: Duration::from_secs(self.request_timeout_secs),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct StartConditionSettings {
@@ -350,4 +426,45 @@ mod tests {
             true
         );
     }
+
+    #[test]
+    fn market_data_charts_are_disabled_without_provider() {
+        let settings = Settings::default();
+        let charts_settings_default_enabled = config::types::AllChartSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        let mut charts = config::charts::Config {
+            counters: Default::default(),
+            lines: [
+                (
+                    NativeCoinPrice::key().name().to_owned(),
+                    charts_settings_default_enabled.clone(),
+                ),
+                (
+                    MarketCap::key().name().to_owned(),
+                    charts_settings_default_enabled.clone(),
+                ),
+            ]
+            .iter()
+            .cloned()
+            .collect(),
+        };
+
+        let market_data_enabled = MarketDataSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        handle_market_data(&market_data_enabled, &mut charts);
+        for key in [NativeCoinPrice::key(), MarketCap::key()] {
+            let chart = charts.lines.get(key.name()).unwrap();
+            assert_eq!(chart.enabled, true);
+            assert_eq!(chart.units.as_deref(), Some("USD"));
+        }
+
+        handle_market_data(&settings.market_data, &mut charts);
+        for key in [NativeCoinPrice::key(), MarketCap::key()] {
+            assert_eq!(charts.lines.get(key.name()).unwrap().enabled, false);
+        }
+    }
 }
@@ -2,7 +2,10 @@ use crate::runtime_setup::{RuntimeSetup, UpdateGroupEntry};
 use chrono::Utc;
 use cron::Schedule;
 use sea_orm::{DatabaseConnection, DbErr};
-use stats::data_source::types::{BlockscoutMigrations, UpdateParameters};
+use stats::{
+    data_source::types::{BlockscoutMigrations, UpdateParameters},
+    market_data::MarketDataProvider,
+};
 use std::sync::Arc;
 use tokio::task::JoinHandle;
 
@@ -12,6 +15,7 @@ pub struct UpdateService {
     db: Arc<DatabaseConnection>,
     blockscout: Arc<DatabaseConnection>,
     charts: Arc<RuntimeSetup>,
+    market_data: Option<Arc<MarketDataProvider>>,
 }
 
 fn time_till_next_call(schedule: &Schedule) -> std::time::Duration {
@@ -29,11 +33,13 @@ impl UpdateService {
         db: Arc<DatabaseConnection>,
         blockscout: Arc<DatabaseConnection>,
         charts: Arc<RuntimeSetup>,
+        market_data: Option<Arc<MarketDataProvider>>,
     ) -> Result<Self, DbErr> {
         Ok(Self {
             db,
             blockscout,
             charts,
+            market_data,
         })
     }
 
@@ -118,6 +124,7 @@ impl UpdateService {
             blockscout_applied_migrations: active_migrations,
             update_time_override: None,
             force_full,
+            market_data: self.market_data.as_deref(),
         };
         let result = group_entry
             .group
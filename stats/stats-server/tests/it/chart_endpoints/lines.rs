@@ -45,6 +45,8 @@ pub async fn test_lines_ok(base: Url) {
         "gasUsedGrowth",
         // "nativeCoinHoldersGrowth",
         // "nativeCoinSupply",
+        // "nativeCoinPrice",
+        // "marketCap",
         // "newNativeCoinHolders",
         "newBlocks",
         "newNativeCoinTransfers",
@@ -6,7 +6,7 @@
 
 use std::fmt::Display;
 
-use crate::{types::Timespan, ReadError};
+use crate::{market_data::MarketDataError, types::Timespan, ReadError};
 use chrono::{DateTime, Utc};
 use entity::sea_orm_active_enums::{ChartResolution, ChartType};
 use sea_orm::prelude::*;
@@ -23,6 +23,8 @@ pub enum ChartError {
     BlockscoutDB(DbErr),
     #[error("stats database error: {0}")]
     StatsDB(DbErr),
+    #[error("market data error: {0}")]
+    MarketData(MarketDataError),
     #[error("chart {0} not found")]
     ChartNotFound(ChartKey),
     #[error("exceeded limit on requested data points (~{limit}); choose smaller time interval.")]
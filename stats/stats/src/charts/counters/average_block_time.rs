@@ -202,6 +202,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: true,
+            market_data: None,
         };
         let cx = UpdateContext::from_params_now_or_override(parameters.clone());
         AverageBlockTime::update_recursively(&cx).await.unwrap();
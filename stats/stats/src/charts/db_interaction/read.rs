@@ -839,6 +839,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: false,
+            market_data: None,
         });
         assert_eq!(
             value(&date.to_string(), "1350"),
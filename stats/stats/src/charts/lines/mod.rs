@@ -9,6 +9,7 @@ mod average_txn_fee;
 mod contracts_growth;
 mod gas_used_growth;
 mod native_coin_holders_growth;
+mod native_coin_market;
 mod native_coin_supply;
 mod new_accounts;
 mod new_block_rewards;
@@ -76,6 +77,12 @@ pub use native_coin_holders_growth::{
     NativeCoinHoldersGrowth, NativeCoinHoldersGrowthMonthly, NativeCoinHoldersGrowthWeekly,
     NativeCoinHoldersGrowthYearly,
 };
+pub use native_coin_market::{
+    market_cap::{MarketCap, MarketCapMonthly, MarketCapWeekly, MarketCapYearly},
+    native_coin_price::{
+        NativeCoinPrice, NativeCoinPriceMonthly, NativeCoinPriceWeekly, NativeCoinPriceYearly,
+    },
+};
 pub use native_coin_supply::{
     NativeCoinSupply, NativeCoinSupplyMonthly, NativeCoinSupplyWeekly, NativeCoinSupplyYearly,
 };
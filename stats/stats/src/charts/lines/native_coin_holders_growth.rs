@@ -96,7 +96,7 @@ impl UpdateBehaviour<(), (), NaiveDate> for Update {
         last_accurate_point: Option<DateValue<String>>,
         min_blockscout_block: i64,
         dependency_data_fetch_timer: &mut AggregateTimer,
-    ) -> Result<(), ChartError> {
+    ) -> Result<usize, ChartError> {
         update_sequentially_with_support_table(
             cx,
             chart_id,
@@ -115,7 +115,7 @@ pub async fn update_sequentially_with_support_table(
     last_accurate_point: Option<DateValue<String>>,
     min_blockscout_block: i64,
     remote_fetch_timer: &mut AggregateTimer,
-) -> Result<(), ChartError> {
+) -> Result<usize, ChartError> {
     tracing::info!(chart =% Properties::key(), "start sequential update");
     let all_days = match last_accurate_point {
         Some(last_row) => {
@@ -133,6 +133,7 @@ pub async fn update_sequentially_with_support_table(
         }
     };
 
+    let mut written = 0;
     for days in all_days.chunks(Properties::step_duration_days()) {
         let first = days.first();
         let last = days.last();
@@ -152,12 +153,13 @@ pub async fn update_sequentially_with_support_table(
                 .into_iter()
                 .map(|result| result.active_model(chart_id, Some(min_blockscout_block)))
                 .collect();
+        written += data.len();
         insert_data_many(&db_tx, data)
             .await
             .map_err(ChartError::StatsDB)?;
         db_tx.commit().await.map_err(ChartError::StatsDB)?;
     }
-    Ok(())
+    Ok(written)
 }
 
 async fn calculate_days_using_support_table<C1, C2>(
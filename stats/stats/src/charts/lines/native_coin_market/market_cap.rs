@@ -0,0 +1,97 @@
+//! Native coin market capitalization (at the end of each period)
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{Map, MapFunction, MapToString, StripExt},
+            resolutions::last_value::LastValueLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Weeks, Batch30Years, Batch36Months, BatchMaxDays,
+            },
+            DirectVecLocalDbChartSource,
+        },
+    },
+    define_and_impl_resolution_properties,
+    market_data::MarketDataValue,
+    types::{
+        timespans::{Month, Week, Year},
+        TimespanValue,
+    },
+    ChartError, ChartProperties, MissingDatePolicy, Named,
+};
+
+use super::MarketDataRemote;
+
+pub struct ExtractMarketCap;
+
+impl MapFunction<Vec<TimespanValue<NaiveDate, MarketDataValue>>> for ExtractMarketCap {
+    type Output = Vec<TimespanValue<NaiveDate, f64>>;
+
+    fn function(
+        inner_data: Vec<TimespanValue<NaiveDate, MarketDataValue>>,
+    ) -> Result<Self::Output, ChartError> {
+        Ok(inner_data
+            .into_iter()
+            .filter_map(|p| {
+                p.value.market_cap.map(|value| TimespanValue {
+                    timespan: p.timespan,
+                    value,
+                })
+            })
+            .collect())
+    }
+}
+
+pub type MarketCapRemote = MapToString<Map<MarketDataRemote, ExtractMarketCap>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "marketCap".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+    fn missing_date_policy() -> MissingDatePolicy {
+        MissingDatePolicy::FillPrevious
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+pub type MarketCap = DirectVecLocalDbChartSource<MarketCapRemote, BatchMaxDays, Properties>;
+type MarketCapS = StripExt<MarketCap>;
+pub type MarketCapWeekly = DirectVecLocalDbChartSource<
+    LastValueLowerResolution<MarketCapS, Week>,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type MarketCapMonthly = DirectVecLocalDbChartSource<
+    LastValueLowerResolution<MarketCapS, Month>,
+    Batch36Months,
+    MonthlyProperties,
+>;
+type MarketCapMonthlyS = StripExt<MarketCapMonthly>;
+pub type MarketCapYearly = DirectVecLocalDbChartSource<
+    LastValueLowerResolution<MarketCapMonthlyS, Year>,
+    Batch30Years,
+    YearlyProperties,
+>;
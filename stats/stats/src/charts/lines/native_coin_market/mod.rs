@@ -0,0 +1,43 @@
+//! Charts based on native coin market data retrieved from
+//! external provider (see [`crate::market_data`]).
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::{
+    charts::db_interaction::read::QueryAllBlockTimestampRange,
+    data_source::{
+        kinds::remote_db::{RemoteDatabaseSource, RemoteQueryBehaviour},
+        types::UpdateContext,
+    },
+    market_data::MarketDataValue,
+    range::{data_source_query_range_to_db_statement_range, UniversalRange},
+    types::TimespanValue,
+    ChartError,
+};
+
+pub mod market_cap;
+pub mod native_coin_price;
+
+pub struct MarketDataQuery;
+
+impl RemoteQueryBehaviour for MarketDataQuery {
+    type Output = Vec<TimespanValue<NaiveDate, MarketDataValue>>;
+
+    async fn query_data(
+        cx: &UpdateContext<'_>,
+        range: UniversalRange<DateTime<Utc>>,
+    ) -> Result<Self::Output, ChartError> {
+        let provider = cx
+            .market_data
+            .ok_or_else(|| ChartError::Internal("market data provider is not configured".into()))?;
+        let query_range =
+            data_source_query_range_to_db_statement_range::<QueryAllBlockTimestampRange>(cx, range)
+                .await?;
+        provider
+            .fetch_daily(query_range)
+            .await
+            .map_err(ChartError::MarketData)
+    }
+}
+
+pub type MarketDataRemote = RemoteDatabaseSource<MarketDataQuery>;
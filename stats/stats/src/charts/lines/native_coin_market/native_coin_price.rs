@@ -0,0 +1,134 @@
+//! Native coin price (at the end of each period)
+
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+
+use crate::{
+    data_source::kinds::{
+        data_manipulation::{
+            map::{Map, MapFunction, MapToString, StripExt},
+            resolutions::last_value::LastValueLowerResolution,
+        },
+        local_db::{
+            parameters::update::batching::parameters::{
+                Batch30Weeks, Batch30Years, Batch36Months, BatchMaxDays,
+            },
+            DirectVecLocalDbChartSource,
+        },
+    },
+    define_and_impl_resolution_properties,
+    market_data::MarketDataValue,
+    types::{
+        timespans::{Month, Week, Year},
+        TimespanValue,
+    },
+    ChartError, ChartProperties, MissingDatePolicy, Named,
+};
+
+use super::MarketDataRemote;
+
+pub struct ExtractPrice;
+
+impl MapFunction<Vec<TimespanValue<NaiveDate, MarketDataValue>>> for ExtractPrice {
+    type Output = Vec<TimespanValue<NaiveDate, f64>>;
+
+    fn function(
+        inner_data: Vec<TimespanValue<NaiveDate, MarketDataValue>>,
+    ) -> Result<Self::Output, ChartError> {
+        Ok(inner_data
+            .into_iter()
+            .filter_map(|p| {
+                p.value.price.map(|value| TimespanValue {
+                    timespan: p.timespan,
+                    value,
+                })
+            })
+            .collect())
+    }
+}
+
+pub type NativeCoinPriceRemote = MapToString<Map<MarketDataRemote, ExtractPrice>>;
+
+pub struct Properties;
+
+impl Named for Properties {
+    fn name() -> String {
+        "nativeCoinPrice".into()
+    }
+}
+
+impl ChartProperties for Properties {
+    type Resolution = NaiveDate;
+
+    fn chart_type() -> ChartType {
+        ChartType::Line
+    }
+    fn missing_date_policy() -> MissingDatePolicy {
+        MissingDatePolicy::FillPrevious
+    }
+}
+
+define_and_impl_resolution_properties!(
+    define_and_impl: {
+        WeeklyProperties: Week,
+        MonthlyProperties: Month,
+        YearlyProperties: Year,
+    },
+    base_impl: Properties
+);
+
+// provider returns the whole requested range in a single response,
+// so there's no point in splitting the update into many requests
+pub type NativeCoinPrice =
+    DirectVecLocalDbChartSource<NativeCoinPriceRemote, BatchMaxDays, Properties>;
+type NativeCoinPriceS = StripExt<NativeCoinPrice>;
+pub type NativeCoinPriceWeekly = DirectVecLocalDbChartSource<
+    LastValueLowerResolution<NativeCoinPriceS, Week>,
+    Batch30Weeks,
+    WeeklyProperties,
+>;
+pub type NativeCoinPriceMonthly = DirectVecLocalDbChartSource<
+    LastValueLowerResolution<NativeCoinPriceS, Month>,
+    Batch36Months,
+    MonthlyProperties,
+>;
+type NativeCoinPriceMonthlyS = StripExt<NativeCoinPriceMonthly>;
+pub type NativeCoinPriceYearly = DirectVecLocalDbChartSource<
+    LastValueLowerResolution<NativeCoinPriceMonthlyS, Year>,
+    Batch30Years,
+    YearlyProperties,
+>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::point_construction::d;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn extract_price_skips_missing_values() {
+        let data = vec![
+            TimespanValue {
+                timespan: d("2022-11-09"),
+                value: MarketDataValue {
+                    price: Some(1.5),
+                    market_cap: Some(100.0),
+                },
+            },
+            TimespanValue {
+                timespan: d("2022-11-10"),
+                value: MarketDataValue {
+                    price: None,
+                    market_cap: Some(200.0),
+                },
+            },
+        ];
+        assert_eq!(
+            ExtractPrice::function(data).unwrap(),
+            vec![TimespanValue {
+                timespan: d("2022-11-09"),
+                value: 1.5
+            }]
+        );
+    }
+}
@@ -172,6 +172,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: false,
+            market_data: None,
         });
         NewBlocks::update_recursively(&cx).await.unwrap();
         let data = NewBlocks::query_data_static(&cx, UniversalRange::full(), None, false)
@@ -248,6 +249,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: true,
+            market_data: None,
         });
         NewBlocks::update_recursively(&cx).await.unwrap();
         let data = NewBlocks::query_data_static(&cx, UniversalRange::full(), None, false)
@@ -343,6 +345,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: false,
+            market_data: None,
         });
         NewBlocks::update_recursively(&cx).await.unwrap();
         let data = NewBlocks::query_data_static(&cx, UniversalRange::full(), None, false)
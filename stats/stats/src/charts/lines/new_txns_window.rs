@@ -137,6 +137,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(current_time),
             force_full: false,
+            market_data: None,
         };
         let cx = UpdateContext::from_params_now_or_override(parameters.clone());
         NewTxnsWindow::update_recursively(&cx).await.unwrap();
@@ -210,6 +210,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 update_time_override: Some(dt("2024-07-15T09:00:00").and_utc()),
                 force_full: false,
+                market_data: None,
             }),
             (dt("2024-07-08T09:00:00").and_utc()..dt("2024-07-15T00:00:01").and_utc()).into(),
             &mut AggregateTimer::new(),
@@ -258,6 +259,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(dt("2024-07-30T09:00:00").and_utc()),
             force_full: false,
+            market_data: None,
         });
         let week_1_average = (5.0 * 100.0 + 34.2 * 2.0 + 10.3 * 12.0) / (100.0 + 2.0 + 12.0);
         assert_eq!(
@@ -308,6 +310,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(dt("2023-03-30T09:00:00").and_utc()),
             force_full: false,
+            market_data: None,
         });
         assert_eq!(
             TestedAverageSource::query_data(
@@ -354,6 +357,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(dt("2023-03-30T09:00:00").and_utc()),
             force_full: false,
+            market_data: None,
         });
         assert_eq!(
             TestedAverageSource::query_data(
@@ -137,6 +137,7 @@ mod tests {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(dt("2024-07-30T09:00:00").and_utc()),
             force_full: false,
+            market_data: None,
         });
         assert_eq!(
             MockSource::query_data(&context, UniversalRange::full(), &mut AggregateTimer::new())
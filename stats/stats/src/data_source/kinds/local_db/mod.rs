@@ -138,10 +138,12 @@ where
 {
     /// Performs common checks and prepares values useful for further
     /// update. Then proceeds to update according to parameters.
+    ///
+    /// Returns number of written data points.
     async fn update_itself_inner(
         cx: &UpdateContext<'_>,
         dependency_data_fetch_timer: &mut AggregateTimer,
-    ) -> Result<(), ChartError> {
+    ) -> Result<usize, ChartError> {
         let metadata = get_chart_metadata(cx.db, &ChartProps::key()).await?;
         if let Some(last_updated_at) = metadata.last_updated_at {
             if postgres_timestamps_eq(cx.time, last_updated_at) {
@@ -153,7 +155,7 @@ where
                     update_timestamp =? cx.time,
                     "Not updating the chart because it was already handled within ongoing update"
                 );
-                return Ok(());
+                return Ok(0);
             } else {
                 tracing::debug!(
                     last_updated_at =? last_updated_at,
@@ -176,7 +178,7 @@ where
         )
         .await?;
         tracing::info!(last_accurate_point =? last_accurate_point, chart =% ChartProps::key(), "updating chart values");
-        Update::update_values(
+        let written = Update::update_values(
             cx,
             chart_id,
             last_accurate_point,
@@ -186,16 +188,28 @@ where
         .await?;
         tracing::info!(chart =% ChartProps::key(), "updating chart metadata");
         Update::update_metadata(cx.db, chart_id, cx.time).await?;
-        Ok(())
+        Ok(written)
     }
 
     fn observe_query_time(time: Duration) {
         if time > Duration::ZERO {
+            let labels = metrics::chart_labels(&ChartProps::key());
             metrics::CHART_FETCH_NEW_DATA_TIME
-                .with_label_values(&[&ChartProps::key().to_string()])
+                .with_label_values(&[labels[0].as_str(), labels[1].as_str()])
                 .observe(time.as_secs_f64());
         }
     }
+
+    fn observe_successful_update(written: usize) {
+        let labels = metrics::chart_labels(&ChartProps::key());
+        let labels = [labels[0].as_str(), labels[1].as_str()];
+        metrics::CHART_ROWS_WRITTEN
+            .with_label_values(&labels)
+            .inc_by(written as u64);
+        metrics::CHART_LAST_SUCCESS_TIMESTAMP
+            .with_label_values(&labels)
+            .set(Utc::now().timestamp());
+    }
 }
 
 /// Compare timestamps as they're seen in Postgres (compare up to microseconds)
@@ -233,17 +247,17 @@ where
         // set up metrics + write some logs
 
         let mut dependency_data_fetch_timer = AggregateTimer::new();
+        let labels = metrics::chart_labels(&ChartProps::key());
+        let labels = [labels[0].as_str(), labels[1].as_str()];
         let _update_timer = metrics::CHART_UPDATE_TIME
-            .with_label_values(&[&ChartProps::key().to_string()])
+            .with_label_values(&labels)
             .start_timer();
         tracing::info!(chart =% ChartProps::key(), "started chart update");
 
-        Self::update_itself_inner(cx, &mut dependency_data_fetch_timer)
+        let written = Self::update_itself_inner(cx, &mut dependency_data_fetch_timer)
             .await
             .inspect_err(|err| {
-                metrics::UPDATE_ERRORS
-                    .with_label_values(&[&ChartProps::key().to_string()])
-                    .inc();
+                metrics::UPDATE_ERRORS.with_label_values(&labels).inc();
                 tracing::error!(
                     chart =% ChartProps::key(),
                     "error during updating chart: {}",
@@ -252,7 +266,12 @@ where
             })?;
 
         Self::observe_query_time(dependency_data_fetch_timer.total_time());
-        tracing::info!(chart =% ChartProps::key(), "successfully updated chart");
+        Self::observe_successful_update(written);
+        tracing::info!(
+            chart =% ChartProps::key(),
+            written = written,
+            "successfully updated chart"
+        );
         Ok(())
     }
 
@@ -368,7 +387,7 @@ mod tests {
                 _last_accurate_point: Option<TimespanValue<Resolution, String>>,
                 min_blockscout_block: i64,
                 _dependency_data_fetch_timer: &mut AggregateTimer,
-            ) -> Result<(), ChartError> {
+            ) -> Result<usize, ChartError> {
                 Self::record_trigger().await;
                 // insert smth for dependency to work well
                 let data = DateValue::<String> {
@@ -379,7 +398,7 @@ mod tests {
                 insert_data_many(cx.db, vec![value])
                     .await
                     .map_err(ChartError::StatsDB)?;
-                Ok(())
+                Ok(1)
             }
         }
 
@@ -460,6 +479,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 update_time_override: Some(next_time),
                 force_full: true,
+                market_data: None,
             };
             group
                 .update_charts_with_mutexes(parameters, &enabled)
@@ -480,6 +500,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 update_time_override: Some(time),
                 force_full: true,
+                market_data: None,
             };
             group
                 .update_charts_with_mutexes(parameters, &enabled)
@@ -496,6 +517,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 update_time_override: Some(time),
                 force_full: true,
+                market_data: None,
             };
             group
                 .update_charts_with_mutexes(parameters, &enabled)
@@ -510,6 +532,7 @@ mod tests {
                 blockscout_applied_migrations: BlockscoutMigrations::latest(),
                 update_time_override: Some(time),
                 force_full: true,
+                market_data: None,
             };
             group
                 .update_charts_with_mutexes(parameters, &enabled)
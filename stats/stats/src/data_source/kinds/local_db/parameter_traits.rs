@@ -32,13 +32,15 @@ where
     ///
     /// `dependency_data_fetch_timer` - timer to track data fetch from (remote) dependencies.
     /// `min_blockscout_block` - indicator of blockscout reindexation
+    ///
+    /// Returns number of written data points.
     fn update_values(
         cx: &UpdateContext<'_>,
         chart_id: i32,
         last_accurate_point: Option<TimespanValue<Resolution, String>>,
         min_blockscout_block: i64,
         dependency_data_fetch_timer: &mut AggregateTimer,
-    ) -> impl Future<Output = Result<(), ChartError>> + Send;
+    ) -> impl Future<Output = Result<usize, ChartError>> + Send;
 
     /// Update only chart metadata.
     fn update_metadata(
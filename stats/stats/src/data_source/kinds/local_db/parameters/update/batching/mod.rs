@@ -61,7 +61,7 @@ where
         last_accurate_point: Option<TimespanValue<ChartProps::Resolution, String>>,
         min_blockscout_block: i64,
         dependency_data_fetch_timer: &mut AggregateTimer,
-    ) -> Result<(), ChartError> {
+    ) -> Result<usize, ChartError> {
         let now = cx.time;
         let update_from = last_accurate_point
             .clone()
@@ -78,6 +78,7 @@ where
 
         let steps = generate_batch_ranges(update_range_start, now, BatchSizeUpperBound::get())?;
         let n = steps.len();
+        let mut written = 0;
 
         for (i, range) in steps.into_iter().enumerate() {
             let previous_step_last_point = get_previous_step_last_point::<
@@ -106,6 +107,7 @@ where
                 dependency_data_fetch_timer,
             )
             .await?;
+            written += found;
             // for query in `get_previous_step_last_point` to work correctly
             Self::update_metadata(cx.db, chart_id, range.into_date_time_range().end).await?;
             let elapsed: std::time::Duration = now.elapsed();
@@ -116,7 +118,7 @@ where
                 "{}/{} step of batch done", i + 1, n
             );
         }
-        Ok(())
+        Ok(written)
     }
 }
 
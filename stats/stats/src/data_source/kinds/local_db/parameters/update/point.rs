@@ -24,13 +24,13 @@ where
         _last_accurate_point: Option<TimespanValue<Resolution, String>>,
         min_blockscout_block: i64,
         remote_fetch_timer: &mut AggregateTimer,
-    ) -> Result<(), ChartError> {
+    ) -> Result<usize, ChartError> {
         // range doesn't make sense there; thus is not used
         let data = MainDep::query_data(cx, UniversalRange::full(), remote_fetch_timer).await?;
         let value = data.active_model(chart_id, Some(min_blockscout_block));
         insert_data_many(cx.db, vec![value])
             .await
             .map_err(ChartError::StatsDB)?;
-        Ok(())
+        Ok(1)
     }
 }
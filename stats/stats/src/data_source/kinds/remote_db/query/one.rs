@@ -196,6 +196,7 @@ mod test {
             blockscout_applied_migrations: BlockscoutMigrations::latest(),
             update_time_override: Some(time),
             force_full: false,
+            market_data: None,
         });
         assert_eq!(
             TimespanValue {
@@ -303,6 +303,7 @@ async fn update_examples() {
         blockscout_applied_migrations: BlockscoutMigrations::latest(),
         update_time_override: None,
         force_full: true,
+        market_data: None,
     };
     group
         .update_charts_with_mutexes(parameters, &enabled)
@@ -8,7 +8,7 @@ use sea_orm::{
 use tokio::sync::Mutex;
 use tracing::warn;
 
-use crate::counters::TxnsStatsValue;
+use crate::{counters::TxnsStatsValue, market_data::MarketDataProvider};
 
 #[derive(Clone)]
 pub struct UpdateParameters<'a> {
@@ -20,6 +20,9 @@ pub struct UpdateParameters<'a> {
     pub update_time_override: Option<chrono::DateTime<Utc>>,
     /// Force full re-update
     pub force_full: bool,
+    /// Source of native coin market data. Charts relying on it
+    /// fail to update if it's not provided.
+    pub market_data: Option<&'a MarketDataProvider>,
 }
 
 #[derive(Clone)]
@@ -31,6 +34,7 @@ pub struct UpdateContext<'a> {
     /// Update time
     pub time: chrono::DateTime<Utc>,
     pub force_full: bool,
+    pub market_data: Option<&'a MarketDataProvider>,
 }
 
 impl<'a> UpdateContext<'a> {
@@ -42,6 +46,7 @@ impl<'a> UpdateContext<'a> {
             cache: UpdateCache::new(),
             time: value.update_time_override.unwrap_or_else(Utc::now),
             force_full: value.force_full,
+            market_data: value.market_data,
         }
     }
 }
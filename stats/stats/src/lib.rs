@@ -3,6 +3,7 @@
 mod charts;
 pub mod data_processing;
 pub mod data_source;
+pub mod market_data;
 pub mod metrics;
 mod missing_date;
 pub mod range;
@@ -0,0 +1,274 @@
+//! Client for retrieving native coin market data (price, market cap)
+//! from an external provider.
+//!
+//! Any provider compatible with CoinGecko `market_chart` API
+//! (`/coins/{id}/market_chart` and `/coins/{id}/market_chart/range`)
+//! is supported.
+
+use std::{collections::BTreeMap, ops::Range, time::Duration};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Deserialize;
+use thiserror::Error;
+use url::Url;
+
+use crate::types::TimespanValue;
+
+#[derive(Error, Debug)]
+pub enum MarketDataError {
+    #[error("request to market data provider failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("invalid market data provider url: {0}")]
+    Url(#[from] url::ParseError),
+    #[error("market data provider returned invalid data: {0}")]
+    InvalidResponse(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct MarketDataProviderConfig {
+    /// Base url of the API, e.g. `https://api.coingecko.com/api/v3/`
+    pub url: Url,
+    /// Id of the native coin in the provider (e.g. `ethereum`)
+    pub coin_id: String,
+    /// Currency for the values (e.g. `usd`)
+    pub vs_currency: String,
+    /// `(header name, value)` that is attached to each request
+    pub api_key: Option<(String, String)>,
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MarketDataValue {
+    /// Last known price within the day
+    pub price: Option<f64>,
+    /// Last known market cap within the day
+    pub market_cap: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketChartResponse {
+    #[serde(default)]
+    prices: Vec<(f64, Option<f64>)>,
+    #[serde(default)]
+    market_caps: Vec<(f64, Option<f64>)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MarketDataProvider {
+    client: reqwest::Client,
+    config: MarketDataProviderConfig,
+}
+
+impl MarketDataProvider {
+    pub fn new(mut config: MarketDataProviderConfig) -> Result<Self, MarketDataError> {
+        // otherwise `Url::join` drops the last path segment
+        if !config.url.path().ends_with('/') {
+            let path = format!("{}/", config.url.path());
+            config.url.set_path(&path);
+        }
+        let client = reqwest::Client::builder().timeout(config.timeout).build()?;
+        Ok(Self { client, config })
+    }
+
+    /// Retrieve daily market data within the `range`.
+    ///
+    /// `None` range means all available history. Returned points are
+    /// sorted by date; each date contains the latest values reported
+    /// within the day.
+    pub async fn fetch_daily(
+        &self,
+        range: Option<Range<DateTime<Utc>>>,
+    ) -> Result<Vec<TimespanValue<NaiveDate, MarketDataValue>>, MarketDataError> {
+        let mut url = match &range {
+            Some(_) => self
+                .config
+                .url
+                .join(&format!("coins/{}/market_chart/range", self.config.coin_id))?,
+            None => self
+                .config
+                .url
+                .join(&format!("coins/{}/market_chart", self.config.coin_id))?,
+        };
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("vs_currency", &self.config.vs_currency);
+            match &range {
+                Some(r) => {
+                    query.append_pair("from", &r.start.timestamp().to_string());
+                    query.append_pair("to", &r.end.timestamp().to_string());
+                }
+                None => {
+                    query.append_pair("days", "max");
+                    query.append_pair("interval", "daily");
+                }
+            }
+        }
+        let mut request = self.client.get(url);
+        if let Some((header, value)) = &self.config.api_key {
+            request = request.header(header, value);
+        }
+        let response: MarketChartResponse =
+            request.send().await?.error_for_status()?.json().await?;
+        aggregate_daily(response, range)
+    }
+}
+
+fn aggregate_daily(
+    response: MarketChartResponse,
+    range: Option<Range<DateTime<Utc>>>,
+) -> Result<Vec<TimespanValue<NaiveDate, MarketDataValue>>, MarketDataError> {
+    // (date -> (timestamp of the latest value, value))
+    let mut prices: BTreeMap<NaiveDate, (i64, f64)> = BTreeMap::new();
+    let mut market_caps: BTreeMap<NaiveDate, (i64, f64)> = BTreeMap::new();
+    for (points, target) in [
+        (response.prices, &mut prices),
+        (response.market_caps, &mut market_caps),
+    ] {
+        for (timestamp_ms, value) in points {
+            let Some(value) = value else {
+                continue;
+            };
+            let timestamp_ms = timestamp_ms as i64;
+            let time = DateTime::<Utc>::from_timestamp_millis(timestamp_ms).ok_or_else(|| {
+                MarketDataError::InvalidResponse(format!("invalid timestamp {timestamp_ms}"))
+            })?;
+            if let Some(r) = &range {
+                if !r.contains(&time) {
+                    continue;
+                }
+            }
+            let entry = target
+                .entry(time.date_naive())
+                .or_insert((timestamp_ms, value));
+            if entry.0 <= timestamp_ms {
+                *entry = (timestamp_ms, value);
+            }
+        }
+    }
+    let mut result: BTreeMap<NaiveDate, MarketDataValue> = BTreeMap::new();
+    for (date, (_, price)) in prices {
+        result.entry(date).or_default().price = Some(price);
+    }
+    for (date, (_, market_cap)) in market_caps {
+        result.entry(date).or_default().market_cap = Some(market_cap);
+    }
+    Ok(result
+        .into_iter()
+        .map(|(timespan, value)| TimespanValue { timespan, value })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::point_construction::{d, dt};
+    use pretty_assertions::assert_eq;
+    use wiremock::{
+        matchers::{method, path, query_param},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn value(price: Option<f64>, market_cap: Option<f64>) -> MarketDataValue {
+        MarketDataValue { price, market_cap }
+    }
+
+    #[test]
+    fn aggregate_daily_takes_last_value_within_day() {
+        let response: MarketChartResponse = serde_json::from_str(
+            r#"{
+                "prices": [
+                    [1667952000000, 1.0],
+                    [1667995200000, 2.0],
+                    [1668038400000, 3.0],
+                    [1668081600000, null]
+                ],
+                "market_caps": [
+                    [1667952000000, 100.0],
+                    [1668081600000, 300.5]
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            aggregate_daily(response, None).unwrap(),
+            vec![
+                TimespanValue {
+                    timespan: d("2022-11-09"),
+                    value: value(Some(2.0), Some(100.0))
+                },
+                TimespanValue {
+                    timespan: d("2022-11-10"),
+                    value: value(Some(3.0), Some(300.5))
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn aggregate_daily_respects_range() {
+        let response: MarketChartResponse = serde_json::from_str(
+            r#"{
+                "prices": [[1667952000000, 1.0], [1668038400000, 3.0]],
+                "market_caps": []
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            aggregate_daily(
+                response,
+                Some(dt("2022-11-10T00:00:00").and_utc()..dt("2022-11-11T00:00:00").and_utc())
+            )
+            .unwrap(),
+            vec![TimespanValue {
+                timespan: d("2022-11-10"),
+                value: value(Some(3.0), None)
+            },]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_daily_works() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/coins/ethereum/market_chart/range"))
+            .and(query_param("vs_currency", "usd"))
+            .and(query_param("from", "1667952000"))
+            .and(query_param("to", "1668124800"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{
+                    "prices": [[1667952000000, 1.5], [1668038400000, 2.5]],
+                    "market_caps": [[1667952000000, 150.0], [1668038400000, 250.0]],
+                    "total_volumes": []
+                }"#,
+            ))
+            .mount(&server)
+            .await;
+        let provider = MarketDataProvider::new(MarketDataProviderConfig {
+            url: Url::parse(&format!("{}/api/v3", server.uri())).unwrap(),
+            coin_id: "ethereum".into(),
+            vs_currency: "usd".into(),
+            api_key: None,
+            timeout: Duration::from_secs(5),
+        })
+        .unwrap();
+        let data = provider
+            .fetch_daily(Some(
+                dt("2022-11-09T00:00:00").and_utc()..dt("2022-11-11T00:00:00").and_utc(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            data,
+            vec![
+                TimespanValue {
+                    timespan: d("2022-11-09"),
+                    value: value(Some(1.5), Some(150.0))
+                },
+                TimespanValue {
+                    timespan: d("2022-11-10"),
+                    value: value(Some(2.5), Some(250.0))
+                },
+            ]
+        );
+    }
+}
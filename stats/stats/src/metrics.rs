@@ -1,32 +1,60 @@
 use lazy_static::lazy_static;
-use prometheus::{register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec};
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramVec,
+    IntCounterVec, IntGaugeVec,
+};
+
+use crate::ChartKey;
 
 lazy_static! {
     pub static ref UPDATE_ERRORS: IntCounterVec = register_int_counter_vec!(
         "stats_update_errors_total",
         "total update errors",
-        &["chart_id"],
+        &["chart", "resolution"],
     )
     .unwrap();
     pub static ref CHART_UPDATE_TIME: HistogramVec = register_histogram_vec!(
         "stats_chart_update_time_seconds",
         "single chart update time",
-        &["chart_id"],
+        &["chart", "resolution"],
         vec![1.0, 2.0, 4.0, 8.0, 16.0, 30.0, 60.0, 120.0, 240.0, 480.0, 960.0, 1920.0, 3840.0],
     )
     .unwrap();
     pub static ref CHART_FETCH_NEW_DATA_TIME: HistogramVec = register_histogram_vec!(
         "stats_fetch_new_data_time_seconds",
         "single chart time for fetching data from blockscout",
-        &["chart_id"],
+        &["chart", "resolution"],
         vec![1.0, 2.0, 4.0, 8.0, 16.0, 30.0, 60.0, 120.0, 240.0, 480.0, 960.0, 1920.0, 3840.0],
     )
     .unwrap();
+    pub static ref CHART_ROWS_WRITTEN: IntCounterVec = register_int_counter_vec!(
+        "stats_chart_rows_written_total",
+        "total data points written to stats db during chart updates",
+        &["chart", "resolution"],
+    )
+    .unwrap();
+    pub static ref CHART_LAST_SUCCESS_TIMESTAMP: IntGaugeVec = register_int_gauge_vec!(
+        "stats_chart_last_success_timestamp_seconds",
+        "unix timestamp of the last successful chart update",
+        &["chart", "resolution"],
+    )
+    .unwrap();
+}
+
+/// Labels for metrics with `["chart", "resolution"]` label names.
+/// All chart metrics are labeled this way, so that they could be aggregated by chart.
+pub fn chart_labels(key: &ChartKey) -> [String; 2] {
+    [key.name().to_owned(), String::from(*key.resolution())]
 }
 
-pub fn initialize_metrics<'a>(enabled_chart_keys: impl IntoIterator<Item = &'a str>) {
-    for chart_id in enabled_chart_keys {
-        UPDATE_ERRORS.with_label_values(&[chart_id]).reset();
-        // making zero observation for histograms doesn't make sense
+pub fn initialize_metrics<'a>(enabled_chart_keys: impl IntoIterator<Item = &'a ChartKey>) {
+    for key in enabled_chart_keys {
+        let labels = chart_labels(key);
+        let labels = [labels[0].as_str(), labels[1].as_str()];
+        UPDATE_ERRORS.with_label_values(&labels).reset();
+        CHART_ROWS_WRITTEN.with_label_values(&labels).reset();
+        // making zero observation for histograms doesn't make sense.
+        // last success timestamp is not set until the first successful update
+        // in order not to mislead alerts
     }
 }
@@ -85,6 +85,7 @@ where
         blockscout_applied_migrations: migrations,
         update_time_override: Some(current_time),
         force_full: true,
+        market_data: None,
     };
     let cx = UpdateContext::from_params_now_or_override(parameters.clone());
     C::update_recursively(&cx).await.unwrap();
@@ -135,6 +136,7 @@ pub async fn dirty_force_update_and_check<C>(
         blockscout_applied_migrations: BlockscoutMigrations::latest(),
         update_time_override: Some(current_time),
         force_full: true,
+        market_data: None,
     };
     let cx = UpdateContext::from_params_now_or_override(parameters.clone());
     C::update_recursively(&cx).await.unwrap();
@@ -234,6 +236,7 @@ async fn ranged_test_chart_inner<C>(
         blockscout_applied_migrations: migrations,
         update_time_override: Some(current_time),
         force_full: true,
+        market_data: None,
     };
     let cx = UpdateContext::from_params_now_or_override(parameters.clone());
     C::update_recursively(&cx).await.unwrap();
@@ -315,6 +318,7 @@ async fn simple_test_counter_inner<C>(
         blockscout_applied_migrations: migrations,
         update_time_override: Some(current_time),
         force_full: true,
+        market_data: None,
     };
     let cx = UpdateContext::from_params_now_or_override(parameters.clone());
     C::update_recursively(&cx).await.unwrap();
@@ -354,6 +358,7 @@ where
         blockscout_applied_migrations: BlockscoutMigrations::latest(),
         update_time_override: Some(current_time),
         force_full: false,
+        market_data: None,
     };
     let cx: UpdateContext<'_> = UpdateContext::from_params_now_or_override(parameters.clone());
     let data = get_counter::<C>(&cx).await;
@@ -111,6 +111,21 @@ construct_update_group!(NativeCoinSupplyGroup {
     ]
 });
 
+// Both charts are built from the market data provider. Each of them requests
+// the provider separately, the group only keeps their updates in sync
+construct_update_group!(NativeCoinMarketGroup {
+    charts: [
+        NativeCoinPrice,
+        NativeCoinPriceWeekly,
+        NativeCoinPriceMonthly,
+        NativeCoinPriceYearly,
+        MarketCap,
+        MarketCapWeekly,
+        MarketCapMonthly,
+        MarketCapYearly,
+    ]
+});
+
 construct_update_group!(NewBlocksGroup {
     charts: [
         NewBlocks,
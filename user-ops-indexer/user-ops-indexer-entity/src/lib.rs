@@ -2,5 +2,7 @@
 
 pub mod prelude;
 
+pub mod account_delegations;
+pub mod bundler_transactions;
 pub mod sea_orm_active_enums;
 pub mod user_operations;
@@ -1,3 +1,5 @@
 //! `SeaORM` Entity. Generated by sea-orm-codegen 0.12.6
 
+pub use super::account_delegations::Entity as AccountDelegations;
+pub use super::bundler_transactions::Entity as BundlerTransactions;
 pub use super::user_operations::Entity as UserOperations;
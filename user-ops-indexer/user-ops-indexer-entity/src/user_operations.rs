@@ -68,6 +68,15 @@ pub struct Model {
     pub inserted_at: DateTime,
     pub updated_at: DateTime,
     pub entry_point_version: EntryPointVersion,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub violations: Option<Json>,
+    #[sea_orm(column_type = "Binary(BlobSize::Blob(None))", nullable)]
+    pub payer: Option<Vec<u8>>,
+    #[sea_orm(column_type = "Decimal(Some((100, 0)))", nullable)]
+    pub prefund: Option<BigDecimal>,
+    #[sea_orm(column_type = "Decimal(Some((100, 0)))", nullable)]
+    pub refund: Option<BigDecimal>,
+    pub pruned: bool,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
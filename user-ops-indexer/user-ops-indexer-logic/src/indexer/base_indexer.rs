@@ -1,26 +1,42 @@
 use crate::{
     indexer::{
+        common::{extract_inner_calls_logs, match_user_logs_boundaries},
         common_transport::CommonTransport,
+        delegations,
+        progress::BackfillProgressTracker,
         rpc_utils::{to_string, CallTracer, TraceType},
-        settings::IndexerSettings,
+        settings::{IndexerSettings, TraceClient},
+        validation_rules,
     },
     repository,
-    types::user_op::UserOp,
+    types::{
+        bundler_stats::BundlerTxStats,
+        user_op::{EntryPointVersion, UserOp},
+    },
 };
 use anyhow::{anyhow, bail};
 use ethers::prelude::{
     abi::{AbiEncode, Error},
     parse_log,
-    types::{Address, Bytes, Filter, Log, TransactionReceipt},
-    EthEvent, Middleware, NodeClient, Provider, ProviderError, WsClientError, H256,
+    types::{Address, Bytes, CallLogFrame, Filter, Log, TransactionReceipt},
+    EthEvent, Middleware, NodeClient, Provider, ProviderError, RpcError, WsClientError, H256,
 };
 use futures::{
     stream,
     stream::{repeat_with, BoxStream},
     Stream, StreamExt, TryStreamExt,
 };
-use sea_orm::DatabaseConnection;
-use std::{future, num::NonZeroUsize, sync::Arc, time, time::Duration};
+use sea_orm::{DatabaseConnection, TransactionTrait};
+use std::{
+    future,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time,
+    time::Duration,
+};
 use tokio::time::sleep;
 use tracing::instrument;
 
@@ -59,8 +75,18 @@ impl TryFrom<Log> for Job {
     }
 }
 
+/// Version-specific part of the indexer.
+///
+/// Each supported EntryPoint version is implemented in its own module
+/// (ABI, events and calldata decoding), so that several versions
+/// can be indexed simultaneously by separate [`Indexer`] instances.
 pub trait IndexerLogic {
+    fn new(entry_point: Address) -> Self;
+
     fn entry_point(&self) -> Address;
+
+    fn entry_point_version() -> EntryPointVersion;
+
     fn version() -> &'static str;
 
     fn user_operation_event_signature() -> H256;
@@ -108,6 +134,8 @@ pub struct Indexer<L: IndexerLogic + Sync> {
     settings: IndexerSettings,
 
     logic: L,
+
+    progress: BackfillProgressTracker,
 }
 
 impl<L: IndexerLogic + Sync> Indexer<L> {
@@ -122,16 +150,34 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
             db,
             settings,
             logic,
+            progress: Default::default(),
         }
     }
 
+    pub fn with_progress(mut self, progress: BackfillProgressTracker) -> Self {
+        self.progress = progress;
+        self
+    }
+
     #[instrument(name = "indexer", skip_all, level = "info", fields(version = L::version()))]
     pub async fn start(&self) -> anyhow::Result<()> {
-        tracing::debug!("fetching node client");
-        let variant = self.client.node_client().await.unwrap_or(NodeClient::Geth);
-        tracing::info!(variant = to_string(variant), "fetched node client");
+        let trace_client = match self.settings.trace_client {
+            Some(trace_client) => trace_client,
+            None => {
+                tracing::debug!("fetching node client");
+                let variant = self.client.node_client().await.unwrap_or(NodeClient::Geth);
+                tracing::info!(variant = to_string(variant), "fetched node client");
+                TraceClient::from(variant)
+            }
+        };
+        if self.settings.trace_attribution && trace_client != TraceClient::Debug {
+            tracing::warn!(
+                ?trace_client,
+                "trace attribution requires the debug trace client, using logs-based attribution"
+            );
+        }
 
-        let mut stream_jobs = stream::SelectAll::<BoxStream<Job>>::new();
+        let mut stream_jobs = stream::SelectAll::<BoxStream<anyhow::Result<Job>>>::new();
 
         if self.settings.realtime.enabled {
             if self.client.as_ref().supports_subscriptions() {
@@ -149,10 +195,10 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
                 // ethers-rs does not handle ws reconnects well, neither it can guarantee that no
                 // events would be lost even if reconnect is successful, so it's better to restart
                 // the whole indexer at once instead of trying to reconnect.
-                stream_jobs.push(Box::pin(realtime_stream_jobs));
+                stream_jobs.push(Box::pin(realtime_stream_jobs.map(Ok)));
             } else {
                 tracing::info!("starting polling of past BeforeExecution logs from rpc");
-                stream_jobs.push(Box::pin(self.poll_for_jobs()));
+                stream_jobs.push(Box::pin(self.poll_for_jobs().map(Ok)));
             }
         }
 
@@ -185,15 +231,24 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
             )
             .await?;
 
-            stream_jobs.push(Box::pin(missed_txs.map(Job::from)));
+            stream_jobs.push(Box::pin(missed_txs.map(|tx_hash| Ok(Job::from(tx_hash)))));
+        }
+
+        if self.settings.bundler_stats_backfill {
+            tracing::info!("fetching tx hashes without bundler stats in db");
+            let txs = repository::bundler_stats::stream_tx_hashes_without_stats(
+                &self.db,
+                self.logic.entry_point(),
+            )
+            .await?;
+
+            stream_jobs.push(Box::pin(txs.map(|tx_hash| Ok(Job::from(tx_hash)))));
         }
 
         if self.settings.past_rpc_logs_indexer.enabled {
-            let jobs = self
-                .fetch_jobs_for_block_range(rpc_refetch_block_number + 1, block_number)
-                .await?;
+            let jobs = self.stream_jobs_for_block_range(rpc_refetch_block_number + 1, block_number);
 
-            stream_jobs.push(Box::pin(stream::iter(jobs)));
+            stream_jobs.push(Box::pin(jobs));
         }
 
         let cache_size =
@@ -203,6 +258,11 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
         // e.g. [A, A, B, B, B, C, C] -> [A, B, C]
         let stream_txs = stream_jobs
             .scan(cache, |cache, job| {
+                // failures of the job sources are passed through to stop the indexer
+                let job = match job {
+                    Ok(job) => job,
+                    Err(err) => return future::ready(Some(Some(Err(err)))),
+                };
                 let now = time::Instant::now();
                 let tx_hash = job.tx_hash;
                 match cache.put(job, now) {
@@ -210,16 +270,15 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
                     Some(ts) if now < ts + self.settings.deduplication_interval => {
                         future::ready(Some(None))
                     }
-                    _ => future::ready(Some(Some(tx_hash))),
+                    _ => future::ready(Some(Some(Ok(tx_hash)))),
                 }
             })
             .filter_map(|tx_hash| async move { tx_hash });
 
         stream_txs
-            .map(Ok)
             .try_for_each_concurrent(Some(self.settings.concurrency as usize), |tx| async move {
                 let mut backoff = vec![5, 20, 120].into_iter().map(Duration::from_secs);
-                while let Err(err) = self.handle_tx(tx, variant).await {
+                while let Err(err) = self.handle_tx(tx, trace_client).await {
                     // terminate stream if WS connection is closed, indexer will be restarted
                     if self.client.as_ref().supports_subscriptions() && err.to_string() == WsClientError::UnexpectedClose.to_string() {
                         tracing::error!(error = ?err, tx_hash = ?tx, "tx handler failed, ws connection closed, exiting");
@@ -270,6 +329,95 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
         Ok(jobs)
     }
 
+    /// Fetches jobs for a large block range in chunks, using up to `workers` concurrent requests.
+    /// Chunk size is halved on the rpc range/size limit errors and grows back on success.
+    /// Other rpc errors are retried with backoff for the failed chunk only,
+    /// the error is returned terminating the stream once the retries are exhausted.
+    fn stream_jobs_for_block_range(
+        &self,
+        from_block: u32,
+        to_block: u32,
+    ) -> impl Stream<Item = anyhow::Result<Job>> + '_ {
+        let settings = &self.settings.past_rpc_logs_indexer;
+        let max_block_range = settings.max_block_range.max(1);
+        let chunk_size = Arc::new(AtomicU32::new(max_block_range));
+        self.progress
+            .start(L::version(), from_block as u64, to_block as u64);
+
+        let ranges = {
+            let chunk_size = chunk_size.clone();
+            stream::unfold(from_block, move |from| {
+                let chunk_size = chunk_size.load(Ordering::Relaxed);
+                future::ready((from <= to_block).then(|| {
+                    let to = from.saturating_add(chunk_size - 1).min(to_block);
+                    ((from, to), to.saturating_add(1))
+                }))
+            })
+        };
+
+        ranges
+            .map(move |(from, to)| {
+                let chunk_size = chunk_size.clone();
+                async move {
+                    self.fetch_jobs_adaptive(from, to, &chunk_size, max_block_range)
+                        .await
+                }
+            })
+            .buffer_unordered(settings.workers.max(1) as usize)
+            .flat_map(|res| {
+                let jobs = match res {
+                    Ok(jobs) => jobs.into_iter().map(Ok).collect(),
+                    Err(err) => vec![Err(err.into())],
+                };
+                stream::iter(jobs)
+            })
+    }
+
+    async fn fetch_jobs_adaptive(
+        &self,
+        from_block: u32,
+        to_block: u32,
+        chunk_size: &AtomicU32,
+        max_block_range: u32,
+    ) -> Result<Vec<Job>, ProviderError> {
+        let mut ranges = vec![(from_block, to_block)];
+        let mut jobs = Vec::new();
+        let new_backoff = || vec![5, 20, 120].into_iter().map(Duration::from_secs);
+        let mut backoff = new_backoff();
+        while let Some((from, to)) = ranges.pop() {
+            match self.fetch_jobs_for_block_range(from, to).await {
+                Ok(res) => {
+                    backoff = new_backoff();
+                    jobs.extend(res);
+                    self.progress
+                        .add_scanned_blocks(L::version(), (to - from + 1) as u64);
+                    let _ = chunk_size.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |s| {
+                        Some(s.saturating_mul(2).min(max_block_range))
+                    });
+                }
+                Err(err) if from < to && is_range_limit_error(&err) => {
+                    let mid = from + (to - from) / 2;
+                    chunk_size.fetch_min(mid - from + 1, Ordering::Relaxed);
+                    tracing::warn!(from, to, error = ?err, "rpc logs limit reached, splitting block range");
+                    ranges.push((mid + 1, to));
+                    ranges.push((from, mid));
+                }
+                Err(err) => match backoff.next() {
+                    None => {
+                        tracing::error!(from, to, error = ?err, "failed to fetch logs");
+                        return Err(err);
+                    }
+                    Some(delay) => {
+                        tracing::error!(from, to, error = ?err, ?delay, "failed to fetch logs, retrying");
+                        sleep(delay).await;
+                        ranges.push((from, to));
+                    }
+                },
+            }
+        }
+        Ok(jobs)
+    }
+
     fn poll_for_jobs(&self) -> impl Stream<Item = Job> + '_ {
         repeat_with(|| async {
             sleep(self.settings.realtime.polling_interval).await;
@@ -293,8 +441,8 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
         .flat_map(stream::iter)
     }
 
-    #[instrument(name = "indexer::handle_tx", skip(self, variant), level = "info")]
-    async fn handle_tx(&self, tx_hash: H256, variant: NodeClient) -> anyhow::Result<()> {
+    #[instrument(name = "indexer::handle_tx", skip(self, trace_client), level = "info")]
+    async fn handle_tx(&self, tx_hash: H256, trace_client: TraceClient) -> anyhow::Result<()> {
         let tx = self
             .client
             .get_transaction(tx_hash)
@@ -326,13 +474,13 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
 
         let calldatas: Vec<Bytes> =
             if log_bundles.len() == 1 && tx.to == Some(self.logic.entry_point()) {
-                vec![tx.input]
+                vec![tx.input.clone()]
             } else {
                 tracing::info!(
                     "tx contains more than one bundle or was sent indirectly, fetching tx trace"
                 );
                 self.client
-                    .common_trace_transaction(tx_hash, variant)
+                    .common_trace_transaction(tx_hash, trace_client)
                     .await?
                     .into_iter()
                     .filter_map(|t| {
@@ -357,7 +505,7 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
             )
         }
 
-        let user_ops: Vec<UserOp> = calldatas
+        let mut user_ops: Vec<UserOp> = calldatas
             .iter()
             .zip(log_bundles.iter())
             .enumerate()
@@ -380,12 +528,143 @@ impl<L: IndexerLogic + Sync> Indexer<L> {
             missed = total - parsed,
             "found and parsed user ops",
         );
+        // logs of the calls are available in the debug traces only
+        if parsed > 0 && self.settings.trace_attribution && trace_client == TraceClient::Debug {
+            self.attribute_user_logs(tx_hash, &log_bundles, &mut user_ops)
+                .await;
+        }
+        if parsed > 0 && parsed == total && self.settings.validation_rules_check {
+            self.tag_violations(tx_hash, &mut user_ops).await;
+        }
         if parsed > 0 {
-            repository::user_op::upsert_many(&self.db, user_ops).await?;
+            // bundle gas cost is shared between all user operations in the transaction,
+            // including the ones sent to other entrypoints
+            let total_ops = receipt
+                .logs
+                .iter()
+                .filter(|log| log.topics.first() == Some(&L::user_operation_event_signature()))
+                .count();
+            let gas_cost = receipt.gas_used.unwrap_or_default()
+                * receipt
+                    .effective_gas_price
+                    .or(tx.gas_price)
+                    .unwrap_or_default();
+            let stats = BundlerTxStats::from_user_ops(&user_ops, gas_cost, total_ops);
+
+            let txn = self.db.begin().await?;
+            repository::user_op::upsert_many(&txn, user_ops).await?;
+            repository::bundler_stats::upsert_many(&txn, stats).await?;
+            txn.commit().await?;
         }
 
+        delegations::index_transaction(&self.client, &self.db, &tx).await?;
+
         Ok(())
     }
+
+    /// Refines user logs boundaries of the parsed user operations based on the transaction trace.
+    /// Logs-based boundaries are kept if the trace is not available or doesn't match the receipt.
+    async fn attribute_user_logs(
+        &self,
+        tx_hash: H256,
+        log_bundles: &[Vec<&[Log]>],
+        user_ops: &mut [UserOp],
+    ) {
+        let root = match self.client.trace_transaction_with_logs(tx_hash).await {
+            Ok(root) => root,
+            Err(err) => {
+                tracing::warn!(error = ?err, "failed to trace transaction, falling back to logs-based attribution");
+                return;
+            }
+        };
+
+        let inner_calls = extract_inner_calls_logs(&root, self.logic.entry_point());
+        let total: usize = log_bundles.iter().map(Vec::len).sum();
+        if inner_calls.len() != total {
+            tracing::warn!(
+                traced = inner_calls.len(),
+                total,
+                "number of traced user ops doesn't match, falling back to logs-based attribution"
+            );
+            return;
+        }
+
+        let bundle_offsets: Vec<usize> = log_bundles
+            .iter()
+            .scan(0, |offset, bundle| {
+                let res = *offset;
+                *offset += bundle.len();
+                Some(res)
+            })
+            .collect();
+        for user_op in user_ops.iter_mut() {
+            let (bundle_index, index) = (user_op.bundle_index as usize, user_op.index as usize);
+            let user_logs: Vec<CallLogFrame> = inner_calls[bundle_offsets[bundle_index] + index]
+                .iter()
+                .filter(|call| call.to == Some(user_op.sender))
+                .flat_map(|call| call.logs.iter().cloned())
+                .collect();
+            match match_user_logs_boundaries(log_bundles[bundle_index][index], &user_logs) {
+                Some((start, count)) => {
+                    user_op.user_logs_start_index = start;
+                    user_op.user_logs_count = count;
+                }
+                None => {
+                    tracing::warn!(
+                        op_hash = ?user_op.hash,
+                        "traced user logs don't match receipt logs, falling back to logs-based attribution"
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks the validation phases of the parsed user operations against the ERC-7562 rules.
+    /// Violations are left unset if the trace is not available or doesn't match the user operations.
+    async fn tag_violations(&self, tx_hash: H256, user_ops: &mut [UserOp]) {
+        let phases = match self
+            .client
+            .trace_validation_phases(tx_hash, self.logic.entry_point())
+            .await
+        {
+            Ok(phases) => phases,
+            Err(err) => {
+                tracing::warn!(error = ?err, "failed to trace validation phases, skipping validation rules check");
+                return;
+            }
+        };
+
+        if !validation_rules::tag_violations(phases, user_ops, self.logic.entry_point()) {
+            tracing::warn!(
+                "traced validation phases don't match user ops, skipping validation rules check"
+            );
+        }
+    }
+}
+
+/// (code, message prefix) of the errors returned by the rpc providers
+/// when `eth_getLogs` range or response is too large
+const RANGE_LIMIT_ERRORS: &[(i64, &str)] = &[
+    // Infura, also returned by geth based nodes with the results limit
+    (-32005, "query returned more than"),
+    // Alchemy
+    (-32602, "log response size exceeded"),
+    // QuickNode
+    (-32614, "eth_getlogs is limited to"),
+    // Ankr, Chainstack
+    (-32000, "exceed maximum block range"),
+    // Erigon
+    (-32000, "query exceeds max block range"),
+];
+
+fn is_range_limit_error(err: &ProviderError) -> bool {
+    let Some(err) = err.as_error_response() else {
+        return false;
+    };
+    let message = err.message.to_lowercase();
+    RANGE_LIMIT_ERRORS
+        .iter()
+        .any(|(code, prefix)| err.code == *code && message.starts_with(prefix))
 }
 
 #[cfg(test)]
@@ -396,7 +675,7 @@ mod tests {
         repository::tests::get_shared_db,
     };
     use entity::sea_orm_active_enums::{EntryPointVersion, SponsorType};
-    use ethers::prelude::{MockProvider, Provider};
+    use ethers::prelude::{HttpClientError, JsonRpcError, MockProvider, Provider};
     use ethers_core::types::{Transaction, TransactionReceipt, U256};
     use std::str::FromStr;
 
@@ -422,7 +701,10 @@ mod tests {
             Default::default(),
             v06::IndexerV06 { entry_point },
         );
-        indexer.handle_tx(tx_hash, NodeClient::Geth).await.unwrap();
+        indexer
+            .handle_tx(tx_hash, TraceClient::Debug)
+            .await
+            .unwrap();
 
         let op_hash =
             H256::from_str("0x2d5f7a884e9a99cfe2445db2af140a8851fbd860852b668f2f199190f68adf87")
@@ -465,6 +747,12 @@ mod tests {
             user_logs_start_index: 268,
             user_logs_count: 1,
             fee: U256::from(6172156091732370u64),
+            payer: Address::from_str("0xeae4d85f7733ad522f601ce7ad4f595704a2d677").unwrap(),
+            prefund: U256::from(12360803600000000u64),
+            refund: U256::from(6188647508267630u64),
+            violations: None,
+            pruned: false,
+            sender_delegate: None,
             consensus: None,
             timestamp: None,
         })
@@ -492,7 +780,10 @@ mod tests {
             Default::default(),
             v07::IndexerV07 { entry_point },
         );
-        indexer.handle_tx(tx_hash, NodeClient::Geth).await.unwrap();
+        indexer
+            .handle_tx(tx_hash, TraceClient::Debug)
+            .await
+            .unwrap();
 
         let op_hash =
             H256::from_str("0x02bfece5db8c1bd400049c14e20ee988e62c057d296e9aefa34bd9b7f146033e")
@@ -535,8 +826,42 @@ mod tests {
             user_logs_start_index: 42,
             user_logs_count: 3,
             fee: U256::from(1534051),
+            payer: Address::from_str("0x1b637a3008dc1f86d92031a97FC4B5aC0803329e").unwrap(),
+            prefund: U256::from(4300000),
+            refund: U256::from(2765949),
+            violations: None,
+            pruned: false,
+            sender_delegate: None,
             consensus: None,
             timestamp: None,
         })
     }
+
+    #[test]
+    fn is_range_limit_error_ok() {
+        let rpc_error = |code, message: &str| {
+            ProviderError::JsonRpcClientError(Box::new(HttpClientError::JsonRpcError(
+                JsonRpcError {
+                    code,
+                    message: message.to_string(),
+                    data: None,
+                },
+            )))
+        };
+
+        for (code, message) in [
+            (-32005, "query returned more than 10000 results"),
+            (-32602, "Log response size exceeded. You can make eth_getLogs requests with up to a 2K block range"),
+            (-32614, "eth_getLogs is limited to a 10,000 range"),
+            (-32000, "exceed maximum block range: 5000"),
+        ] {
+            assert!(is_range_limit_error(&rpc_error(code, message)));
+        }
+        // rate limits use the same code as the results limit
+        assert!(!is_range_limit_error(&rpc_error(-32005, "limit exceeded")));
+        assert!(!is_range_limit_error(&rpc_error(-32000, "request timeout")));
+        assert!(!is_range_limit_error(&ProviderError::CustomError(
+            "query returned more than 10000 results".to_string()
+        )));
+    }
 }
@@ -1,7 +1,7 @@
-use entity::sea_orm_active_enums::SponsorType;
+use entity::sea_orm_active_enums::{EntryPointVersion, SponsorType};
 use ethers::prelude::{
     abi::{decode, parse_abi, ParamType, Token},
-    Address, Bytes, Log, U256,
+    Address, Bytes, CallFrame, CallLogFrame, Log, U256,
 };
 use lazy_static::lazy_static;
 
@@ -53,6 +53,46 @@ pub fn extract_sponsor_type(
     }
 }
 
+/// Splits the prefund locked from the payer deposit during the validation into the charged fee
+/// and the refund returned to the payer deposit after the execution.
+/// Returns `(payer, prefund, refund)`, where payer is either the paymaster or the sender itself.
+pub fn extract_payment(
+    sender: Address,
+    paymaster: Option<Address>,
+    gas: U256,
+    max_fee_per_gas: U256,
+    fee: U256,
+) -> (Address, U256, U256) {
+    let prefund = gas.saturating_mul(max_fee_per_gas);
+    (
+        paymaster.unwrap_or(sender),
+        prefund,
+        prefund.saturating_sub(fee),
+    )
+}
+
+/// Splits `paymasterAndData` into the paymaster gas limits (v0.7 only) and the paymaster-specific data.
+/// Returns `(verification_gas_limit, post_op_gas_limit, data)`.
+pub fn decode_paymaster_and_data(
+    version: &EntryPointVersion,
+    b: &Bytes,
+) -> (Option<U256>, Option<U256>, Option<Bytes>) {
+    match version {
+        EntryPointVersion::V06 if b.len() >= 20 => {
+            (None, None, Some(Bytes::from(b[20..].to_vec())))
+        }
+        EntryPointVersion::V07 if b.len() >= 52 => {
+            let (verification_gas_limit, post_op_gas_limit) = unpack_uints(&b[20..52]);
+            (
+                Some(verification_gas_limit),
+                Some(post_op_gas_limit),
+                Some(Bytes::from(b[52..].to_vec())),
+            )
+        }
+        _ => (None, None, None),
+    }
+}
+
 pub fn extract_user_logs_boundaries(
     logs: &[Log],
     entry_point: Address,
@@ -73,6 +113,106 @@ pub fn extract_user_logs_boundaries(
     )
 }
 
+/// Logs emitted within a call made by the entrypoint during a single user operation execution
+#[derive(Debug, Clone, PartialEq)]
+pub struct InnerCallLogs {
+    pub to: Option<Address>,
+    pub logs: Vec<CallLogFrame>,
+}
+
+/// Splits the traced transaction execution by user operations.
+///
+/// Each user operation is executed in a separate `innerHandleOp` self-call of the entrypoint,
+/// so the returned batches follow the order of `UserOperationEvent` logs in the receipt.
+pub fn extract_inner_calls_logs(root: &CallFrame, entry_point: Address) -> Vec<Vec<InnerCallLogs>> {
+    let mut inner_handle_ops = Vec::new();
+    find_inner_handle_ops(root, entry_point, &mut inner_handle_ops);
+    inner_handle_ops
+        .into_iter()
+        .map(|frame| {
+            frame
+                .calls
+                .iter()
+                .flatten()
+                .map(|call| {
+                    let mut logs = Vec::new();
+                    collect_logs(call, &mut logs);
+                    InnerCallLogs {
+                        to: call.to.as_ref().and_then(|to| to.as_address().cloned()),
+                        logs,
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn find_inner_handle_ops<'a>(
+    frame: &'a CallFrame,
+    entry_point: Address,
+    res: &mut Vec<&'a CallFrame>,
+) {
+    let to = frame.to.as_ref().and_then(|to| to.as_address().cloned());
+    if frame.from == entry_point && to == Some(entry_point) {
+        res.push(frame);
+        return;
+    }
+    for call in frame.calls.iter().flatten() {
+        find_inner_handle_ops(call, entry_point, res);
+    }
+}
+
+fn collect_logs(frame: &CallFrame, res: &mut Vec<CallLogFrame>) {
+    res.extend(frame.logs.iter().flatten().cloned());
+    for call in frame.calls.iter().flatten() {
+        collect_logs(call, res);
+    }
+}
+
+/// Locates traced `user_logs` within the logs batch of a single user operation.
+///
+/// Logs of a single call are always contiguous in the receipt, however the call tracer
+/// doesn't preserve their relative order against the nested calls, so the window is compared
+/// regardless of the order. Returns `None` if traced logs are not found in the batch.
+pub fn match_user_logs_boundaries(logs: &[Log], user_logs: &[CallLogFrame]) -> Option<(u32, u32)> {
+    let n = user_logs.len();
+    if n == 0 {
+        let start = logs
+            .first()
+            .and_then(|l| l.log_index)
+            .map_or(0, |v| v.as_u32());
+        return Some((start, 0));
+    }
+    if logs.len() < n {
+        return None;
+    }
+    (0..=logs.len() - n)
+        .find(|&k| same_logs(&logs[k..k + n], user_logs))
+        .map(|k| (logs[k].log_index.map_or(0, |v| v.as_u32()), n as u32))
+}
+
+fn same_logs(logs: &[Log], traced: &[CallLogFrame]) -> bool {
+    let mut used = vec![false; logs.len()];
+    traced.iter().all(
+        |t| match (0..logs.len()).find(|&i| !used[i] && log_matches(&logs[i], t)) {
+            Some(i) => {
+                used[i] = true;
+                true
+            }
+            None => false,
+        },
+    )
+}
+
+fn log_matches(log: &Log, traced: &CallLogFrame) -> bool {
+    traced.address == Some(log.address)
+        && traced.topics.as_deref().unwrap_or_default() == log.topics.as_slice()
+        && traced
+            .data
+            .as_ref()
+            .map_or(log.data.is_empty(), |data| data == &log.data)
+}
+
 pub fn unpack_uints(data: &[u8]) -> (U256, U256) {
     (
         U256::from_big_endian(&data[..16]),
@@ -122,8 +262,12 @@ pub fn decode_execute_call_data(call_data: &Bytes) -> (Option<Address>, Option<B
 
 #[cfg(test)]
 mod tests {
-    use crate::indexer::common::{decode_execute_call_data, extract_user_logs_boundaries};
-    use ethers::prelude::{types::Log, Address, U256};
+    use crate::indexer::common::{
+        decode_execute_call_data, decode_paymaster_and_data, extract_inner_calls_logs,
+        extract_payment, extract_user_logs_boundaries, match_user_logs_boundaries, InnerCallLogs,
+    };
+    use entity::sea_orm_active_enums::EntryPointVersion;
+    use ethers::prelude::{types::Log, Address, CallFrame, CallLogFrame, H256, U256};
     use ethers_core::types::Bytes;
     use std::str::FromStr;
 
@@ -197,4 +341,164 @@ mod tests {
         assert_eq!(execute_target, None);
         assert_eq!(execute_call_data, None);
     }
+
+    fn traced_log(address: Address, topic: u64) -> CallLogFrame {
+        serde_json::from_value(serde_json::json!({
+            "address": address,
+            "topics": [H256::from_low_u64_be(topic)],
+            "data": "0x",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_extract_inner_calls_logs() {
+        let entry_point = Address::from_low_u64_be(1);
+        let sender = Address::from_low_u64_be(3);
+        let token = Address::from_low_u64_be(4);
+        let paymaster = Address::from_low_u64_be(2);
+        let frame =
+            |from: Address, to: Address, logs: Vec<CallLogFrame>, calls: Vec<serde_json::Value>| {
+                serde_json::json!({
+                    "type": "CALL",
+                    "from": from,
+                    "to": to,
+                    "gas": "0x0",
+                    "gasUsed": "0x0",
+                    "input": "0x",
+                    "logs": logs,
+                    "calls": calls,
+                })
+            };
+        let root: CallFrame = serde_json::from_value(frame(
+            Address::from_low_u64_be(0xff),
+            entry_point,
+            vec![traced_log(entry_point, 1)],
+            vec![
+                frame(
+                    entry_point,
+                    entry_point,
+                    vec![traced_log(entry_point, 2)],
+                    vec![
+                        frame(
+                            entry_point,
+                            sender,
+                            vec![traced_log(sender, 3)],
+                            vec![frame(sender, token, vec![traced_log(token, 4)], vec![])],
+                        ),
+                        frame(
+                            entry_point,
+                            paymaster,
+                            vec![traced_log(paymaster, 5)],
+                            vec![],
+                        ),
+                    ],
+                ),
+                frame(entry_point, entry_point, vec![], vec![]),
+            ],
+        ))
+        .unwrap();
+
+        assert_eq!(
+            extract_inner_calls_logs(&root, entry_point),
+            vec![
+                vec![
+                    InnerCallLogs {
+                        to: Some(sender),
+                        logs: vec![traced_log(sender, 3), traced_log(token, 4)],
+                    },
+                    InnerCallLogs {
+                        to: Some(paymaster),
+                        logs: vec![traced_log(paymaster, 5)],
+                    },
+                ],
+                vec![],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_match_user_logs_boundaries() {
+        let entry_point = Address::from_low_u64_be(1);
+        let sender = Address::from_low_u64_be(3);
+        let token = Address::from_low_u64_be(4);
+        // user operation emits a log from the entrypoint address itself, which can't be
+        // attributed correctly based on the log addresses only
+        let logs = vec![
+            (token, 4),
+            (entry_point, 6),
+            (sender, 3),
+            (entry_point, 2),
+            (entry_point, 1),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (address, topic))| Log {
+            address,
+            topics: vec![H256::from_low_u64_be(topic)],
+            log_index: Some(U256::from(i + 10)),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+
+        let user_logs = vec![
+            traced_log(sender, 3),
+            traced_log(token, 4),
+            traced_log(entry_point, 6),
+        ];
+        assert_eq!(match_user_logs_boundaries(&logs, &user_logs), Some((10, 3)));
+        assert_eq!(match_user_logs_boundaries(&logs, &[]), Some((10, 0)));
+        assert_eq!(match_user_logs_boundaries(&logs[1..], &user_logs), None);
+        assert_eq!(
+            match_user_logs_boundaries(&logs, &user_logs[..1]),
+            Some((12, 1))
+        );
+    }
+
+    #[test]
+    fn test_extract_payment() {
+        let sender = Address::from_low_u64_be(1);
+        let paymaster = Address::from_low_u64_be(2);
+        assert_eq!(
+            extract_payment(
+                sender,
+                None,
+                U256::from(100),
+                U256::from(10),
+                U256::from(600)
+            ),
+            (sender, U256::from(1000), U256::from(400))
+        );
+        assert_eq!(
+            extract_payment(
+                sender,
+                Some(paymaster),
+                U256::from(100),
+                U256::from(10),
+                U256::from(1200)
+            ),
+            (paymaster, U256::from(1000), U256::zero())
+        );
+    }
+
+    #[test]
+    fn test_decode_paymaster_and_data() {
+        let b = Bytes::from_str("0x1b637a3008dc1f86d92031a97fc4b5ac0803329e00000000000000000000000000061a8000000000000000000000000000061a80aabb").unwrap();
+        assert_eq!(
+            decode_paymaster_and_data(&EntryPointVersion::V07, &b),
+            (
+                Some(U256::from(400000)),
+                Some(U256::from(400000)),
+                Some(Bytes::from_str("0xaabb").unwrap())
+            )
+        );
+        assert_eq!(
+            decode_paymaster_and_data(&EntryPointVersion::V06, &b),
+            (None, None, Some(Bytes::from(b[20..].to_vec())))
+        );
+        assert_eq!(
+            decode_paymaster_and_data(&EntryPointVersion::V07, &Bytes::default()),
+            (None, None, None)
+        );
+    }
 }
@@ -1,8 +1,10 @@
+use crate::indexer::{failover_transport::FailoverTransport, settings::IndexerSettings};
 use async_trait::async_trait;
 use ethabi::ethereum_types::U256;
 use ethers::prelude::{Http, JsonRpcClient, ProviderError, PubsubClient, Ws};
+use reqwest_rate_limiter::DefaultRateLimiterMiddleware;
 use serde::{de::DeserializeOwned, Serialize};
-use std::{fmt::Debug, str::FromStr};
+use std::{fmt::Debug, num::NonZeroU32, str::FromStr, sync::Arc};
 
 #[cfg(test)]
 use ethers::prelude::MockProvider;
@@ -11,10 +13,38 @@ use ethers::prelude::MockProvider;
 pub enum CommonTransport {
     Ws(Ws),
     Http(Http),
+    Failover(FailoverTransport),
+    RateLimited(RateLimitedTransport),
     #[cfg(test)]
     Mock(MockProvider),
 }
 
+/// Delays the requests to the inner transport to keep them within the configured rate
+#[derive(Clone)]
+pub struct RateLimitedTransport {
+    inner: Box<CommonTransport>,
+    rate_limiter: Arc<DefaultRateLimiterMiddleware>,
+}
+
+impl RateLimitedTransport {
+    pub fn new(inner: CommonTransport, max_requests_per_second: NonZeroU32) -> Self {
+        Self {
+            inner: Box::new(inner),
+            rate_limiter: Arc::new(DefaultRateLimiterMiddleware::per_second(
+                max_requests_per_second,
+            )),
+        }
+    }
+}
+
+impl Debug for RateLimitedTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimitedTransport")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
 impl CommonTransport {
     pub async fn new(rpc_url: String) -> Result<Self, ProviderError> {
         if rpc_url.trim().starts_with("ws") {
@@ -29,8 +59,40 @@ impl CommonTransport {
         }
     }
 
+    /// Creates the transport for the configured rpc endpoints.
+    /// Failover between several endpoints is supported for HTTP endpoints only,
+    /// while rate limiting is applied to any transport.
+    pub async fn from_settings(settings: &IndexerSettings) -> Result<Self, ProviderError> {
+        let transport = if settings.fallback_rpc_urls.is_empty() {
+            Self::new(settings.rpc_url.clone()).await?
+        } else {
+            let urls: Vec<String> = std::iter::once(&settings.rpc_url)
+                .chain(settings.fallback_rpc_urls.iter())
+                .map(|url| url.trim().to_string())
+                .collect();
+            if urls.iter().any(|url| url.starts_with("ws")) {
+                return Err(ProviderError::CustomError(
+                    "rpc failover is supported for HTTP rpc urls only".to_string(),
+                ));
+            }
+            Self::Failover(FailoverTransport::new(urls, &settings.rpc_failover)?)
+        };
+
+        Ok(match settings.rpc_max_requests_per_second {
+            Some(max_requests_per_second) => Self::RateLimited(RateLimitedTransport::new(
+                transport,
+                max_requests_per_second,
+            )),
+            None => transport,
+        })
+    }
+
     pub fn supports_subscriptions(&self) -> bool {
-        matches!(self, CommonTransport::Ws(_))
+        match self {
+            CommonTransport::Ws(_) => true,
+            CommonTransport::RateLimited(transport) => transport.inner.supports_subscriptions(),
+            _ => false,
+        }
     }
 }
 
@@ -52,6 +114,11 @@ impl JsonRpcClient for CommonTransport {
                 .request(method, params)
                 .await
                 .map_err(ProviderError::from),
+            CommonTransport::Failover(failover) => failover.request(method, params).await,
+            CommonTransport::RateLimited(transport) => {
+                transport.rate_limiter.until_ready().await;
+                transport.inner.request(method, params).await
+            }
             #[cfg(test)]
             CommonTransport::Mock(mock) => mock
                 .request(method, params)
@@ -67,6 +134,7 @@ impl PubsubClient for CommonTransport {
     fn subscribe<T: Into<U256>>(&self, id: T) -> Result<Self::NotificationStream, Self::Error> {
         match self {
             CommonTransport::Ws(ws) => ws.subscribe(id).map_err(ProviderError::from),
+            CommonTransport::RateLimited(transport) => transport.inner.subscribe(id),
             _ => Err(ProviderError::UnsupportedRPC),
         }
     }
@@ -74,7 +142,35 @@ impl PubsubClient for CommonTransport {
     fn unsubscribe<T: Into<U256>>(&self, id: T) -> Result<(), Self::Error> {
         match self {
             CommonTransport::Ws(ws) => ws.unsubscribe(id).map_err(ProviderError::from),
+            CommonTransport::RateLimited(transport) => transport.inner.unsubscribe(id),
             _ => Err(ProviderError::UnsupportedRPC),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::prelude::U64;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn rate_limited_transport_delays_requests() {
+        let mock = MockProvider::new();
+        for _ in 0..3 {
+            mock.push::<U64, _>(U64::from(1)).unwrap();
+        }
+        let transport = CommonTransport::RateLimited(RateLimitedTransport::new(
+            CommonTransport::Mock(mock),
+            NonZeroU32::new(10).unwrap(),
+        ));
+        assert!(!transport.supports_subscriptions());
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            let _: U64 = transport.request("eth_blockNumber", ()).await.unwrap();
+        }
+        // the first request is sent immediately, the next ones are delayed by 100ms each
+        assert!(start.elapsed() >= Duration::from_millis(190));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{
+    indexer::{common_transport::CommonTransport, settings::DelegationsIndexerSettings},
+    repository,
+    types::delegation::AccountDelegation,
+};
+use anyhow::anyhow;
+use ethers::prelude::{Middleware, Provider, Transaction};
+use futures::{stream, StreamExt, TryStreamExt};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tokio::time::sleep;
+
+/// Keeps only the delegations which took effect, comparing the authority code at the end of the block.
+/// Authorizations with a stale nonce are skipped during the execution, and the chain is the only
+/// source of the authority nonce, so the check is done against the resulting account code instead.
+pub async fn applied_delegations<M: Middleware>(
+    client: &M,
+    delegations: Vec<AccountDelegation>,
+) -> anyhow::Result<Vec<AccountDelegation>> {
+    let mut applied = Vec::with_capacity(delegations.len());
+    for delegation in delegations {
+        let code = client
+            .get_code(delegation.address, Some(delegation.block_number.into()))
+            .await
+            .map_err(|err| anyhow!("failed to fetch authority code: {err}"))?;
+        if delegation.is_applied(&code) {
+            applied.push(delegation);
+        } else {
+            tracing::warn!(
+                authority = ?delegation.address,
+                tx_hash = ?delegation.transaction_hash,
+                "EIP-7702 authorization was not applied, skipping"
+            );
+        }
+    }
+    Ok(applied)
+}
+
+/// Extracts the delegations of the set code transaction and stores the ones which took effect
+pub async fn index_transaction<M: Middleware>(
+    client: &M,
+    db: &DatabaseConnection,
+    tx: &Transaction,
+) -> anyhow::Result<()> {
+    let delegations = AccountDelegation::from_transaction(tx);
+    if delegations.is_empty() {
+        return Ok(());
+    }
+    let delegations = applied_delegations(client, delegations).await?;
+    if !delegations.is_empty() {
+        tracing::info!(
+            tx_hash = ?tx.hash,
+            count = delegations.len(),
+            "found EIP-7702 delegations"
+        );
+        repository::delegation::upsert_many(db, delegations).await?;
+    }
+    Ok(())
+}
+
+/// Scans the new blocks for the set code transactions, so that the delegations
+/// set outside of the user operations bundles are tracked as well.
+pub async fn run(
+    client: Provider<CommonTransport>,
+    db: Arc<DatabaseConnection>,
+    settings: DelegationsIndexerSettings,
+) {
+    tracing::info!(
+        start_block = settings.start_block,
+        "starting EIP-7702 delegations indexing"
+    );
+    let mut next_block = settings.start_block;
+    loop {
+        if let Err(err) = index_new_blocks(&client, &db, &settings, &mut next_block).await {
+            tracing::error!(error = ?err, next_block, "failed to index EIP-7702 delegations");
+        }
+        sleep(settings.polling_interval).await;
+    }
+}
+
+/// Indexes blocks from `next_block` (the latest one if not set) up to the latest block.
+/// `next_block` is moved forward after each indexed block, so that the progress is kept on failures.
+async fn index_new_blocks(
+    client: &Provider<CommonTransport>,
+    db: &DatabaseConnection,
+    settings: &DelegationsIndexerSettings,
+    next_block: &mut Option<u64>,
+) -> anyhow::Result<()> {
+    let latest_block = client.get_block_number().await?.as_u64();
+    let from_block = *next_block.get_or_insert(latest_block);
+    if from_block > latest_block {
+        return Ok(());
+    }
+
+    tracing::debug!(
+        from_block,
+        latest_block,
+        "scanning blocks for EIP-7702 delegations"
+    );
+    // blocks are fetched concurrently, but handled in order
+    let mut blocks = stream::iter(from_block..=latest_block)
+        .map(|number| async move {
+            let block = client
+                .get_block_with_txs(number)
+                .await?
+                .ok_or(anyhow!("empty block {number} returned from rpc"))?;
+            Ok::<_, anyhow::Error>((number, block))
+        })
+        .buffered(settings.concurrency.max(1) as usize);
+
+    while let Some((number, block)) = blocks.try_next().await? {
+        for tx in &block.transactions {
+            index_transaction(client, db, tx).await?;
+        }
+        *next_block = Some(number + 1);
+    }
+    Ok(())
+}
@@ -0,0 +1,236 @@
+use crate::indexer::settings::RpcFailoverSettings;
+use async_trait::async_trait;
+use ethers::prelude::{Http, HttpClientError, JsonRpcClient, ProviderError};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::Debug,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+    time::Duration,
+};
+use tokio::time::sleep;
+
+struct Endpoint {
+    url: String,
+    http: Http,
+    healthy: AtomicBool,
+}
+
+/// HTTP JSON-RPC transport over a list of endpoints.
+///
+/// All requests are sent to the active endpoint. Once it fails with a network or rate limit
+/// error, the request is retried on the other healthy endpoints, and the first one that succeeds
+/// becomes active. Unhealthy endpoints are periodically checked in the background, the primary
+/// endpoint is preferred as soon as it recovers.
+///
+/// WS endpoints are not supported: subscriptions can't be moved between the endpoints
+/// without losing events, so the WS indexer is restarted on disconnects instead.
+#[derive(Clone)]
+pub struct FailoverTransport {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    endpoints: Vec<Endpoint>,
+    active: AtomicUsize,
+}
+
+impl Debug for FailoverTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FailoverTransport")
+            .field(
+                "endpoints",
+                &self
+                    .inner
+                    .endpoints
+                    .iter()
+                    .map(|e| e.url.as_str())
+                    .collect::<Vec<_>>(),
+            )
+            .field("active", &self.inner.active.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl FailoverTransport {
+    pub fn new(urls: Vec<String>, settings: &RpcFailoverSettings) -> Result<Self, ProviderError> {
+        if urls.is_empty() {
+            return Err(ProviderError::CustomError(
+                "at least one rpc url is required".to_string(),
+            ));
+        }
+        let endpoints = urls
+            .into_iter()
+            .map(|url| {
+                let http =
+                    Http::from_str(&url).map_err(|e| ProviderError::CustomError(e.to_string()))?;
+                Ok(Endpoint {
+                    url,
+                    http,
+                    healthy: AtomicBool::new(true),
+                })
+            })
+            .collect::<Result<Vec<_>, ProviderError>>()?;
+
+        let transport = Self {
+            inner: Arc::new(Inner {
+                endpoints,
+                active: AtomicUsize::new(0),
+            }),
+        };
+        if transport.inner.endpoints.len() > 1 {
+            tokio::spawn(run_health_checks(
+                Arc::downgrade(&transport.inner),
+                settings.health_check_interval,
+            ));
+        }
+        Ok(transport)
+    }
+
+    pub fn active_url(&self) -> &str {
+        &self.inner.endpoints[self.inner.active.load(Ordering::Relaxed)].url
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for FailoverTransport {
+    type Error = ProviderError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        let params = serde_json::to_value(params)?;
+        let endpoints = &self.inner.endpoints;
+        let active = self.inner.active.load(Ordering::Relaxed);
+
+        let mut last_err = None;
+        for i in 0..endpoints.len() {
+            let idx = (active + i) % endpoints.len();
+            let endpoint = &endpoints[idx];
+            // the active endpoint is always tried first, other ones only if they are healthy
+            if i > 0 && !endpoint.healthy.load(Ordering::Relaxed) {
+                continue;
+            }
+            match endpoint.http.request(method, &params).await {
+                Ok(res) => {
+                    endpoint.healthy.store(true, Ordering::Relaxed);
+                    if idx != active {
+                        self.inner.active.store(idx, Ordering::Relaxed);
+                        tracing::warn!(url = endpoint.url, "switched to the fallback rpc endpoint");
+                    }
+                    return Ok(res);
+                }
+                Err(err) if is_failover_error(&err) => {
+                    tracing::warn!(url = endpoint.url, method, error = ?err, "rpc endpoint request failed");
+                    endpoint.healthy.store(false, Ordering::Relaxed);
+                    last_err = Some(err);
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+
+        Err(last_err.map(ProviderError::from).unwrap_or_else(|| {
+            ProviderError::CustomError("no healthy rpc endpoints available".to_string())
+        }))
+    }
+}
+
+async fn run_health_checks(inner: Weak<Inner>, interval: Duration) {
+    loop {
+        sleep(interval).await;
+        // transport was dropped, e.g. during the indexer restart
+        let Some(inner) = inner.upgrade() else {
+            return;
+        };
+        for endpoint in inner.endpoints.iter() {
+            let healthy = endpoint
+                .http
+                .request::<_, serde_json::Value>("eth_blockNumber", ())
+                .await
+                .is_ok();
+            if healthy != endpoint.healthy.swap(healthy, Ordering::Relaxed) {
+                tracing::info!(url = endpoint.url, healthy, "rpc endpoint health changed");
+            }
+        }
+        // prefer the endpoints in the configured order
+        if let Some(idx) = inner
+            .endpoints
+            .iter()
+            .position(|e| e.healthy.load(Ordering::Relaxed))
+        {
+            if idx != inner.active.swap(idx, Ordering::Relaxed) {
+                tracing::info!(
+                    url = inner.endpoints[idx].url,
+                    "switched active rpc endpoint"
+                );
+            }
+        }
+    }
+}
+
+/// Errors caused by the endpoint itself rather than by the request
+fn is_failover_error(err: &HttpClientError) -> bool {
+    match err {
+        HttpClientError::ReqwestError(_) => true,
+        // non-JSON responses, e.g. 429 or 5xx pages returned by the proxies
+        HttpClientError::SerdeJson { .. } => true,
+        HttpClientError::JsonRpcError(err) => {
+            // 429 and -32005 are commonly used by the providers for the exceeded limits
+            err.code == 429
+                || err.code == -32005
+                || err.message.to_lowercase().contains("rate limit")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::prelude::JsonRpcError;
+
+    fn rpc_error(code: i64, message: &str) -> HttpClientError {
+        HttpClientError::JsonRpcError(JsonRpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        })
+    }
+
+    #[test]
+    fn is_failover_error_ok() {
+        assert!(is_failover_error(&rpc_error(429, "Too Many Requests")));
+        assert!(is_failover_error(&rpc_error(-32005, "limit exceeded")));
+        assert!(is_failover_error(&rpc_error(-32000, "Rate limit reached")));
+        assert!(is_failover_error(&HttpClientError::SerdeJson {
+            err: serde_json::from_str::<serde_json::Value>("<html>").unwrap_err(),
+            text: "<html>".to_string(),
+        }));
+        assert!(!is_failover_error(&rpc_error(-32000, "execution reverted")));
+        assert!(!is_failover_error(&rpc_error(-32601, "method not found")));
+    }
+
+    #[tokio::test]
+    async fn failover_transport_fails_without_healthy_endpoints() {
+        let transport = FailoverTransport::new(
+            vec![
+                "http://127.0.0.1:1".to_string(),
+                "http://127.0.0.1:2".to_string(),
+            ],
+            &Default::default(),
+        )
+        .unwrap();
+        let res: Result<serde_json::Value, _> = transport.request("eth_blockNumber", ()).await;
+        assert!(res.is_err());
+        assert_eq!(transport.active_url(), "http://127.0.0.1:1");
+        assert!(transport
+            .inner
+            .endpoints
+            .iter()
+            .all(|e| !e.healthy.load(Ordering::Relaxed)));
+    }
+}
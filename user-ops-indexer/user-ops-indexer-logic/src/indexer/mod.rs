@@ -1,9 +1,21 @@
+//! Indexing of the user operations.
+//!
+//! Each supported EntryPoint version lives in its own module implementing
+//! [`IndexerLogic`]. Supporting a new version requires such a module, a new
+//! `EntryPointVersion` variant (along with the database enum migration),
+//! and the corresponding entries in [`settings::EntrypointsSettings`].
+
 mod base_indexer;
 pub mod common;
 pub mod common_transport;
+pub mod delegations;
+pub mod failover_transport;
+pub mod progress;
+pub mod pruning;
 pub mod rpc_utils;
 pub mod settings;
 pub mod v06;
 pub mod v07;
+pub mod validation_rules;
 
 pub use base_indexer::{Indexer, IndexerLogic};
@@ -0,0 +1,100 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+/// Progress of the past rpc logs indexer for a single entrypoint version.
+/// Blocks are counted as scanned once the logs of the block are fetched from the rpc.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BackfillProgress {
+    pub from_block: u64,
+    pub to_block: u64,
+    pub scanned_blocks: u64,
+}
+
+impl BackfillProgress {
+    pub fn total_blocks(&self) -> u64 {
+        (self.to_block + 1).saturating_sub(self.from_block)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.scanned_blocks >= self.total_blocks()
+    }
+
+    pub fn percent(&self) -> f64 {
+        let total = self.total_blocks();
+        if total == 0 {
+            return 100.0;
+        }
+        (self.scanned_blocks.min(total) as f64 * 100.0) / total as f64
+    }
+}
+
+/// Shared in-memory storage of the backfill progress, keyed by the entrypoint version
+#[derive(Clone, Debug, Default)]
+pub struct BackfillProgressTracker {
+    inner: Arc<RwLock<BTreeMap<String, BackfillProgress>>>,
+}
+
+impl BackfillProgressTracker {
+    pub fn start(&self, version: &str, from_block: u64, to_block: u64) {
+        self.inner.write().unwrap().insert(
+            version.to_string(),
+            BackfillProgress {
+                from_block,
+                to_block,
+                scanned_blocks: 0,
+            },
+        );
+    }
+
+    pub fn add_scanned_blocks(&self, version: &str, blocks: u64) {
+        if let Some(progress) = self.inner.write().unwrap().get_mut(version) {
+            progress.scanned_blocks += blocks;
+        }
+    }
+
+    pub fn get(&self) -> Vec<(String, BackfillProgress)> {
+        self.inner
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(version, progress)| (version.clone(), progress.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn backfill_progress_tracker_ok() {
+        let tracker = BackfillProgressTracker::default();
+        assert_eq!(tracker.get(), vec![]);
+
+        tracker.start("v0.6", 101, 300);
+        tracker.add_scanned_blocks("v0.6", 50);
+        tracker.add_scanned_blocks("v0.7", 50);
+        let progress = tracker.get();
+        assert_eq!(
+            progress,
+            vec![(
+                "v0.6".to_string(),
+                BackfillProgress {
+                    from_block: 101,
+                    to_block: 300,
+                    scanned_blocks: 50,
+                }
+            )]
+        );
+        assert_eq!(progress[0].1.percent(), 25.0);
+        assert!(!progress[0].1.is_finished());
+
+        tracker.add_scanned_blocks("v0.6", 150);
+        let progress = tracker.get();
+        assert_eq!(progress[0].1.percent(), 100.0);
+        assert!(progress[0].1.is_finished());
+    }
+}
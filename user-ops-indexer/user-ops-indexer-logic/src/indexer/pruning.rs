@@ -0,0 +1,35 @@
+use crate::{indexer::settings::PruningSettings, repository};
+use chrono::Utc;
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tokio::time::sleep;
+
+/// Periodically deletes raw data of the user operations which are older than the retention period.
+/// Aggregated tables and the user operations themselves are kept, so the statistics are not affected.
+pub async fn run(db: Arc<DatabaseConnection>, settings: PruningSettings) {
+    tracing::info!(
+        retention_days = settings.retention_days,
+        interval = ?settings.interval,
+        "starting user operations pruning"
+    );
+    loop {
+        match prune(&db, &settings).await {
+            Ok(pruned) => tracing::info!(pruned, "finished user operations pruning"),
+            Err(err) => tracing::error!(error = ?err, "failed to prune user operations"),
+        }
+        sleep(settings.interval).await;
+    }
+}
+
+async fn prune(db: &DatabaseConnection, settings: &PruningSettings) -> anyhow::Result<u64> {
+    let before = (Utc::now() - chrono::Duration::days(settings.retention_days as i64)).naive_utc();
+    let batch_size = settings.batch_size.max(1) as u64;
+    let mut total = 0;
+    loop {
+        let pruned = repository::user_op::prune_raw_data(db, before, batch_size).await?;
+        total += pruned;
+        if pruned < batch_size {
+            return Ok(total);
+        }
+    }
+}
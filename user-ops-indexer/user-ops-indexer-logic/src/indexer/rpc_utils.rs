@@ -1,6 +1,11 @@
+use crate::indexer::{
+    settings::TraceClient,
+    validation_rules::{validation_tracer, ValidationPhase, ValidationTrace},
+};
 use async_trait::async_trait;
 use ethers::prelude::{
-    Action, Address, Bytes, CallFrame, CallType, GethDebugBuiltInTracerType, GethDebugTracerType,
+    Action, Address, Bytes, CallConfig, CallFrame, CallType, GethDebugBuiltInTracerConfig,
+    GethDebugBuiltInTracerType, GethDebugTracerConfig, GethDebugTracerType,
     GethDebugTracingOptions, GethTrace, GethTraceFrame, JsonRpcClient, Middleware, NodeClient,
     Provider, ProviderError, TxHash,
 };
@@ -27,8 +32,23 @@ pub trait CallTracer {
     async fn common_trace_transaction(
         &self,
         tx_hash: TxHash,
-        variant: NodeClient,
+        trace_client: TraceClient,
     ) -> Result<Vec<CommonCallTrace>, ProviderError>;
+
+    /// Fetches the full call tree of the transaction together with the emitted logs.
+    /// Requires `debug_traceTransaction` with `callTracer` and `withLog` support.
+    async fn trace_transaction_with_logs(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<CallFrame, ProviderError>;
+
+    /// Traces the validation phases of the user operations sent to the given entrypoint.
+    /// Requires `debug_traceTransaction` with JS tracers support.
+    async fn trace_validation_phases(
+        &self,
+        tx_hash: TxHash,
+        entry_point: Address,
+    ) -> Result<Vec<ValidationPhase>, ProviderError>;
 }
 
 #[async_trait]
@@ -36,10 +56,10 @@ impl<T: JsonRpcClient> CallTracer for Provider<T> {
     async fn common_trace_transaction(
         &self,
         tx_hash: TxHash,
-        variant: NodeClient,
+        trace_client: TraceClient,
     ) -> Result<Vec<CommonCallTrace>, ProviderError> {
-        match variant {
-            NodeClient::Geth => {
+        match trace_client {
+            TraceClient::Debug => {
                 let geth_trace = self
                     .debug_trace_transaction(
                         tx_hash,
@@ -66,7 +86,7 @@ impl<T: JsonRpcClient> CallTracer for Provider<T> {
                     )),
                 }
             }
-            _ => {
+            TraceClient::Trace => {
                 let traces = self
                     .trace_transaction(tx_hash)
                     .await?
@@ -98,6 +118,68 @@ impl<T: JsonRpcClient> CallTracer for Provider<T> {
             }
         }
     }
+
+    async fn trace_transaction_with_logs(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<CallFrame, ProviderError> {
+        let geth_trace = self
+            .debug_trace_transaction(
+                tx_hash,
+                GethDebugTracingOptions {
+                    disable_storage: Some(true),
+                    disable_stack: Some(true),
+                    enable_memory: Some(false),
+                    enable_return_data: Some(false),
+                    tracer: Some(GethDebugTracerType::BuiltInTracer(
+                        GethDebugBuiltInTracerType::CallTracer,
+                    )),
+                    tracer_config: Some(GethDebugTracerConfig::BuiltInTracer(
+                        GethDebugBuiltInTracerConfig::CallTracer(CallConfig {
+                            only_top_call: Some(false),
+                            with_log: Some(true),
+                        }),
+                    )),
+                    timeout: Some("60s".to_string()),
+                },
+            )
+            .await?;
+
+        match geth_trace {
+            GethTrace::Known(GethTraceFrame::CallTracer(root)) => Ok(root),
+            _ => Err(ProviderError::CustomError(
+                "can't parse geth trace result".to_string(),
+            )),
+        }
+    }
+
+    async fn trace_validation_phases(
+        &self,
+        tx_hash: TxHash,
+        entry_point: Address,
+    ) -> Result<Vec<ValidationPhase>, ProviderError> {
+        let geth_trace = self
+            .debug_trace_transaction(
+                tx_hash,
+                GethDebugTracingOptions {
+                    tracer: Some(GethDebugTracerType::JsTracer(validation_tracer(
+                        entry_point,
+                    ))),
+                    timeout: Some("60s".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+
+        match geth_trace {
+            GethTrace::Unknown(value) => serde_json::from_value::<ValidationTrace>(value)
+                .map(|trace| trace.phases)
+                .map_err(ProviderError::SerdeJson),
+            _ => Err(ProviderError::CustomError(
+                "can't parse validation trace result".to_string(),
+            )),
+        }
+    }
 }
 
 fn flatten_geth_trace(root: CallFrame) -> Vec<CommonCallTrace> {
@@ -132,6 +214,15 @@ fn flatten_geth_trace(root: CallFrame) -> Vec<CommonCallTrace> {
     res
 }
 
+impl From<NodeClient> for TraceClient {
+    fn from(node_client: NodeClient) -> Self {
+        match node_client {
+            NodeClient::Geth => TraceClient::Debug,
+            _ => TraceClient::Trace,
+        }
+    }
+}
+
 pub fn to_string(node_client: NodeClient) -> String {
     match node_client {
         NodeClient::Geth => "geth",
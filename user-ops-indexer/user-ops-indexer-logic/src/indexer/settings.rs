@@ -1,7 +1,8 @@
+use crate::types::user_op::EntryPointVersion;
 use ethers::prelude::Address;
 use serde::Deserialize;
-use serde_with::serde_as;
-use std::time;
+use serde_with::{formats::CommaSeparator, serde_as, StringWithSeparator};
+use std::{num::NonZeroU32, time};
 
 #[serde_as]
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -9,6 +10,18 @@ use std::time;
 pub struct IndexerSettings {
     pub rpc_url: String,
 
+    /// Comma-separated list of HTTP RPC urls used when `rpc_url` is unavailable
+    #[serde(default)]
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, String>")]
+    pub fallback_rpc_urls: Vec<String>,
+
+    #[serde(default)]
+    pub rpc_failover: RpcFailoverSettings,
+
+    /// Max number of requests per second sent to the rpc, applied to both HTTP and WS transports
+    #[serde(default)]
+    pub rpc_max_requests_per_second: Option<NonZeroU32>,
+
     pub concurrency: u32,
 
     pub entrypoints: EntrypointsSettings,
@@ -19,6 +32,25 @@ pub struct IndexerSettings {
 
     pub past_db_logs_indexer: PastDbLogsIndexerSettings,
 
+    /// Use `debug_traceTransaction` call traces to attribute logs to user operations,
+    /// instead of relying on the log addresses only
+    #[serde(default)]
+    pub trace_attribution: bool,
+
+    /// Trace the validation phase of the user operations with a JS tracer
+    /// and tag the ERC-7562 validation rules violations
+    #[serde(default)]
+    pub validation_rules_check: bool,
+
+    /// Rpc namespace used to trace the transactions, detected from the node client if not set
+    #[serde(default)]
+    pub trace_client: Option<TraceClient>,
+
+    /// Reindex the transactions of the already indexed user operations which have no bundler stats,
+    /// e.g. the ones indexed before the stats were introduced
+    #[serde(default)]
+    pub bundler_stats_backfill: bool,
+
     #[serde(default = "default_deduplication_cache_size")]
     pub deduplication_cache_size: usize,
 
@@ -29,6 +61,64 @@ pub struct IndexerSettings {
     #[serde(default = "default_restart_delay")]
     #[serde_as(as = "serde_with::DurationSeconds<u64>")]
     pub restart_delay: time::Duration,
+
+    #[serde(default)]
+    pub pruning: PruningSettings,
+
+    #[serde(default)]
+    pub delegations: DelegationsIndexerSettings,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RpcFailoverSettings {
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub health_check_interval: time::Duration,
+}
+
+/// Periodic deletion of the raw user operations data, aggregated statistics are kept intact
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PruningSettings {
+    pub enabled: bool,
+
+    /// Raw data of the user operations older than the given number of days is deleted
+    pub retention_days: u32,
+
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub interval: time::Duration,
+
+    /// Max number of user operations pruned in a single database query
+    pub batch_size: u32,
+}
+
+/// Indexing of the EIP-7702 delegations from all set code transactions in the new blocks,
+/// not only from the ones containing user operations
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct DelegationsIndexerSettings {
+    pub enabled: bool,
+
+    /// Block to start scanning from, the latest block is used if not set
+    pub start_block: Option<u64>,
+
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub polling_interval: time::Duration,
+
+    /// Number of blocks fetched concurrently
+    pub concurrency: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceClient {
+    /// `debug_traceTransaction` with `callTracer`
+    Debug,
+    /// `trace_transaction`, doesn't provide the logs emitted by the calls
+    Trace,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -60,6 +150,15 @@ pub struct PastRpcLogsIndexerSettings {
     pub enabled: bool,
 
     pub block_range: u32,
+
+    /// Number of concurrent `eth_getLogs` requests
+    #[serde(default = "default_past_rpc_logs_workers")]
+    pub workers: u32,
+
+    /// Max number of blocks in a single `eth_getLogs` request.
+    /// Actual range is adjusted automatically if rpc responds with the range or size limit errors.
+    #[serde(default = "default_past_rpc_logs_max_block_range")]
+    pub max_block_range: u32,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -80,6 +179,14 @@ fn default_polling_block_range() -> u32 {
     6
 }
 
+fn default_past_rpc_logs_workers() -> u32 {
+    1
+}
+
+fn default_past_rpc_logs_max_block_range() -> u32 {
+    10000
+}
+
 fn default_deduplication_cache_size() -> usize {
     1000
 }
@@ -96,6 +203,9 @@ impl Default for IndexerSettings {
     fn default() -> Self {
         Self {
             rpc_url: "ws://127.0.0.1:8546".to_string(),
+            fallback_rpc_urls: vec![],
+            rpc_failover: Default::default(),
+            rpc_max_requests_per_second: None,
             concurrency: 10,
             entrypoints: Default::default(),
             realtime: RealtimeIndexerSettings {
@@ -106,15 +216,102 @@ impl Default for IndexerSettings {
             past_rpc_logs_indexer: PastRpcLogsIndexerSettings {
                 enabled: false,
                 block_range: 0,
+                workers: default_past_rpc_logs_workers(),
+                max_block_range: default_past_rpc_logs_max_block_range(),
             },
             past_db_logs_indexer: PastDbLogsIndexerSettings {
                 enabled: false,
                 start_block: 0,
                 end_block: 0,
             },
+            trace_attribution: false,
+            validation_rules_check: false,
+            trace_client: None,
+            bundler_stats_backfill: false,
             deduplication_cache_size: default_deduplication_cache_size(),
             deduplication_interval: default_deduplication_interval(),
             restart_delay: default_restart_delay(),
+            pruning: Default::default(),
+            delegations: Default::default(),
+        }
+    }
+}
+
+impl IndexerSettings {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        self.rpc_failover.validate()?;
+        self.pruning.validate()?;
+        self.delegations.validate()?;
+        Ok(())
+    }
+}
+
+impl RpcFailoverSettings {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.health_check_interval.is_zero() {
+            anyhow::bail!("rpc failover health check interval must be positive");
+        }
+        Ok(())
+    }
+}
+
+impl PruningSettings {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.interval.is_zero() {
+            anyhow::bail!("pruning interval must be positive");
+        }
+        if self.retention_days == 0 {
+            anyhow::bail!("pruning retention days must be positive");
+        }
+        Ok(())
+    }
+}
+
+impl DelegationsIndexerSettings {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.polling_interval.is_zero() {
+            anyhow::bail!("delegations polling interval must be positive");
+        }
+        Ok(())
+    }
+}
+
+impl EntrypointsSettings {
+    /// Address of the EntryPoint of the given `version`, if its indexing is enabled
+    pub fn entry_point(&self, version: &EntryPointVersion) -> Option<Address> {
+        match version {
+            EntryPointVersion::V06 => self.v06.then_some(self.v06_entry_point),
+            EntryPointVersion::V07 => self.v07.then_some(self.v07_entry_point),
+        }
+    }
+}
+
+impl Default for RpcFailoverSettings {
+    fn default() -> Self {
+        Self {
+            health_check_interval: time::Duration::from_secs(30),
+        }
+    }
+}
+
+impl Default for PruningSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 30,
+            interval: time::Duration::from_secs(3600),
+            batch_size: 1000,
+        }
+    }
+}
+
+impl Default for DelegationsIndexerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start_block: None,
+            polling_interval: default_polling_interval(),
+            concurrency: 5,
         }
     }
 }
@@ -2,7 +2,8 @@ use crate::{
     indexer::{
         base_indexer::IndexerLogic,
         common::{
-            extract_address, extract_sponsor_type, extract_user_logs_boundaries, none_if_empty,
+            extract_address, extract_payment, extract_sponsor_type, extract_user_logs_boundaries,
+            none_if_empty,
         },
     },
     types::user_op::UserOp,
@@ -32,10 +33,18 @@ struct ExtendedUserOperation {
 }
 
 impl IndexerLogic for IndexerV06 {
+    fn new(entry_point: Address) -> Self {
+        Self { entry_point }
+    }
+
     fn entry_point(&self) -> Address {
         self.entry_point
     }
 
+    fn entry_point_version() -> EntryPointVersion {
+        EntryPointVersion::V06
+    }
+
     fn version() -> &'static str {
         "v0.6"
     }
@@ -161,6 +170,16 @@ impl IndexerV06 {
         let sender = user_op.user_op.sender;
         let (user_logs_start_index, user_logs_count) =
             extract_user_logs_boundaries(logs, self.entry_point, paymaster);
+        let gas = user_op.user_op.call_gas_limit
+            + user_op.user_op.verification_gas_limit * if paymaster.is_none() { 1 } else { 3 }
+            + user_op.user_op.pre_verification_gas;
+        let (payer, prefund, refund) = extract_payment(
+            sender,
+            paymaster,
+            gas,
+            user_op.user_op.max_fee_per_gas,
+            user_op_event.actual_gas_cost,
+        );
         Ok(UserOp {
             hash: H256::from(user_op_event.user_op_hash),
             sender,
@@ -177,7 +196,7 @@ impl IndexerV06 {
             aggregator: user_op.aggregator,
             aggregator_signature: user_op.aggregator_signature,
             entry_point: self.entry_point,
-            entry_point_version: EntryPointVersion::V06,
+            entry_point_version: Self::entry_point_version(),
             transaction_hash: receipt.transaction_hash,
             block_number: receipt.block_number.map_or(0, |n| n.as_u64()),
             block_hash: receipt.block_hash.unwrap_or(H256::zero()),
@@ -188,9 +207,7 @@ impl IndexerV06 {
             paymaster,
             status: user_op_event.success,
             revert_reason: revert_event.map(|e| e.revert_reason),
-            gas: user_op.user_op.call_gas_limit
-                + user_op.user_op.verification_gas_limit * if paymaster.is_none() { 1 } else { 3 }
-                + user_op.user_op.pre_verification_gas,
+            gas,
             gas_price: user_op_event
                 .actual_gas_cost
                 .div(user_op_event.actual_gas_used),
@@ -199,7 +216,13 @@ impl IndexerV06 {
             user_logs_start_index,
             user_logs_count,
             fee: user_op_event.actual_gas_cost,
+            payer,
+            prefund,
+            refund,
+            violations: None,
+            pruned: false,
 
+            sender_delegate: None,
             consensus: None,
             timestamp: None,
         })
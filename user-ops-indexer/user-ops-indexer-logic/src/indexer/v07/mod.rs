@@ -2,8 +2,8 @@ use crate::{
     indexer::{
         base_indexer::IndexerLogic,
         common::{
-            extract_address, extract_sponsor_type, extract_user_logs_boundaries, none_if_empty,
-            unpack_uints,
+            extract_address, extract_payment, extract_sponsor_type, extract_user_logs_boundaries,
+            none_if_empty, unpack_uints,
         },
     },
     types::user_op::UserOp,
@@ -33,10 +33,18 @@ struct ExtendedUserOperation {
 }
 
 impl IndexerLogic for IndexerV07 {
+    fn new(entry_point: Address) -> Self {
+        Self { entry_point }
+    }
+
     fn entry_point(&self) -> Address {
         self.entry_point
     }
 
+    fn entry_point_version() -> EntryPointVersion {
+        EntryPointVersion::V07
+    }
+
     fn version() -> &'static str {
         "v0.7"
     }
@@ -180,6 +188,13 @@ impl IndexerV07 {
         let sender = user_op.user_op.sender;
         let (user_logs_start_index, user_logs_count) =
             extract_user_logs_boundaries(logs, self.entry_point, paymaster);
+        let (payer, prefund, refund) = extract_payment(
+            sender,
+            paymaster,
+            gas,
+            max_fee_per_gas,
+            user_op_event.actual_gas_cost,
+        );
         Ok(UserOp {
             hash: H256::from(user_op_event.user_op_hash),
             sender,
@@ -196,7 +211,7 @@ impl IndexerV07 {
             aggregator: user_op.aggregator,
             aggregator_signature: user_op.aggregator_signature,
             entry_point: self.entry_point,
-            entry_point_version: EntryPointVersion::V07,
+            entry_point_version: Self::entry_point_version(),
             transaction_hash: receipt.transaction_hash,
             block_number: receipt.block_number.map_or(0, |n| n.as_u64()),
             block_hash: receipt.block_hash.unwrap_or(H256::zero()),
@@ -216,7 +231,13 @@ impl IndexerV07 {
             user_logs_start_index,
             user_logs_count,
             fee: user_op_event.actual_gas_cost,
+            payer,
+            prefund,
+            refund,
+            violations: None,
+            pruned: false,
 
+            sender_delegate: None,
             consensus: None,
             timestamp: None,
         })
@@ -0,0 +1,482 @@
+//! Lightweight detection of the ERC-7562 validation rules violations.
+//!
+//! Bundled user operations were already accepted by the bundler, so there are no guarantees
+//! they were checked against the canonical mempool rules. The validation phase of each user
+//! operation is traced with a small JS tracer, which records banned opcodes, nested calls,
+//! storage writes and `KECCAK256` preimages (used to detect the associated storage slots).
+//!
+//! Only the violations that don't depend on the entity stake are reported, since the stake
+//! at the moment of the bundle inclusion is not known to the indexer.
+
+use crate::types::{
+    user_op::UserOp,
+    validation::{ValidationEntity, ValidationViolation},
+};
+use ethers::{
+    prelude::{Address, Bytes, H256, U256},
+    utils::{keccak256, to_checksum},
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// `SenderCreator.createSender(bytes)`, same for all entrypoint versions
+const CREATE_SENDER_SELECTOR: &str = "0x570e1a36";
+/// `validateUserOp` for v0.6 and v0.7
+const VALIDATE_USER_OP_SELECTORS: [&str; 2] = ["0x3a871cdd", "0x19822f7c"];
+/// `validatePaymasterUserOp` for v0.6 and v0.7
+const VALIDATE_PAYMASTER_USER_OP_SELECTORS: [&str; 2] = ["0xf465c77e", "0x52b7512c"];
+/// `EntryPoint.depositTo(address)`
+const DEPOSIT_TO_SELECTOR: &str = "0xb760faf9";
+
+/// Number of slots following the associated slot which are also considered associated
+const ASSOCIATED_SLOTS_RANGE: u64 = 128;
+
+const VALIDATION_TRACER: &str = r#"{
+    entryPoint: "__ENTRY_POINT__",
+    selectors: {"0x570e1a36": 1, "0x3a871cdd": 1, "0x19822f7c": 1, "0xf465c77e": 1, "0x52b7512c": 1},
+    banned: {
+        GASPRICE: 1, GASLIMIT: 1, DIFFICULTY: 1, PREVRANDAO: 1, RANDOM: 1, TIMESTAMP: 1, BASEFEE: 1,
+        BLOCKHASH: 1, NUMBER: 1, ORIGIN: 1, CREATE: 1, COINBASE: 1, SELFDESTRUCT: 1, BLOBHASH: 1,
+        BLOBBASEFEE: 1
+    },
+    callOps: {CALL: 1, CALLCODE: 1, DELEGATECALL: 1, STATICCALL: 1},
+    phases: [],
+    current: null,
+    depth: 0,
+    lastOp: "",
+    enter: function(frame) {
+        this.depth++;
+        var input = toHex(frame.getInput());
+        var value = frame.getValue();
+        var call = {
+            type: frame.getType(),
+            from: toHex(frame.getFrom()),
+            to: toHex(frame.getTo()),
+            selector: input.slice(0, 10),
+            value: value === undefined ? "0x0" : "0x" + value.toString(16)
+        };
+        if (this.current !== null) {
+            this.current.calls.push(call);
+        } else if (call.from === this.entryPoint && this.selectors[call.selector]) {
+            this.current = {
+                to: call.to, selector: call.selector, depth: this.depth,
+                opcodes: {}, calls: [], writes: [], keccak: []
+            };
+            this.phases.push(this.current);
+        }
+    },
+    exit: function(res) {
+        if (this.current !== null && this.current.depth === this.depth) {
+            this.current = null;
+        }
+        this.depth--;
+    },
+    step: function(log, db) {
+        if (this.current === null) {
+            return;
+        }
+        var op = log.op.toString();
+        if (this.lastOp === "GAS" && !this.callOps[op]) {
+            this.current.opcodes["GAS"] = 1;
+        }
+        this.lastOp = op;
+        if (this.banned[op]) {
+            this.current.opcodes[op] = 1;
+        } else if (op === "SSTORE") {
+            this.current.writes.push({
+                address: toHex(log.contract.getAddress()),
+                slot: "0x" + log.stack.peek(0).toString(16)
+            });
+        } else if (op === "KECCAK256" || op === "SHA3") {
+            var ofs = parseInt(log.stack.peek(0).toString());
+            var len = parseInt(log.stack.peek(1).toString());
+            if (len >= 32 && len <= 128) {
+                this.current.keccak.push(toHex(log.memory.slice(ofs, ofs + len)));
+            }
+        }
+    },
+    fault: function(log, db) {},
+    result: function(ctx, db) {
+        return {
+            phases: this.phases.map(function(p) {
+                return {
+                    to: p.to, selector: p.selector, opcodes: Object.keys(p.opcodes),
+                    calls: p.calls, writes: p.writes, keccak: p.keccak
+                };
+            })
+        };
+    }
+}"#;
+
+pub fn validation_tracer(entry_point: Address) -> String {
+    VALIDATION_TRACER.replace("__ENTRY_POINT__", &format!("{entry_point:#x}"))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidationTrace {
+    pub phases: Vec<ValidationPhase>,
+}
+
+/// Validation call made by the entrypoint, e.g. `validateUserOp`
+#[derive(Debug, Deserialize)]
+pub struct ValidationPhase {
+    pub to: Address,
+    pub selector: String,
+    pub opcodes: Vec<String>,
+    pub calls: Vec<ValidationCall>,
+    pub writes: Vec<StorageWrite>,
+    pub keccak: Vec<Bytes>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ValidationCall {
+    #[serde(rename = "type")]
+    pub typ: String,
+    pub to: Address,
+    pub selector: String,
+    pub value: U256,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StorageWrite {
+    pub address: Address,
+    pub slot: U256,
+}
+
+impl ValidationPhase {
+    fn entity(&self) -> Option<ValidationEntity> {
+        let selector = self.selector.as_str();
+        if selector == CREATE_SENDER_SELECTOR {
+            Some(ValidationEntity::Factory)
+        } else if VALIDATE_USER_OP_SELECTORS.contains(&selector) {
+            Some(ValidationEntity::Account)
+        } else if VALIDATE_PAYMASTER_USER_OP_SELECTORS.contains(&selector) {
+            Some(ValidationEntity::Paymaster)
+        } else {
+            None
+        }
+    }
+}
+
+/// Sets the violations of the user operations based on the traced validation phases.
+/// User operations are expected in the order of the execution, e.g. sorted by the bundle and index.
+/// Returns false if the traced phases can't be matched with the user operations.
+pub fn tag_violations(
+    phases: Vec<ValidationPhase>,
+    user_ops: &mut [UserOp],
+    entry_point: Address,
+) -> bool {
+    // entrypoint validates each user op in the following order:
+    // optional sender creation, account validation, optional paymaster validation
+    let mut groups: Vec<Vec<(ValidationEntity, ValidationPhase)>> = Vec::new();
+    for phase in phases {
+        let Some(entity) = phase.entity() else {
+            return false;
+        };
+        let starts_new_group = match groups.last() {
+            None => true,
+            Some(group) => {
+                entity == ValidationEntity::Factory
+                    || (entity == ValidationEntity::Account
+                        && group.iter().any(|(e, _)| *e == ValidationEntity::Account))
+            }
+        };
+        if starts_new_group {
+            groups.push(Vec::new());
+        }
+        groups.last_mut().unwrap().push((entity, phase));
+    }
+
+    if groups.len() != user_ops.len()
+        || groups.iter().zip(user_ops.iter()).any(|(group, user_op)| {
+            !group
+                .iter()
+                .any(|(e, p)| *e == ValidationEntity::Account && p.to == user_op.sender)
+        })
+    {
+        return false;
+    }
+
+    for (group, user_op) in groups.into_iter().zip(user_ops.iter_mut()) {
+        let violations = group
+            .iter()
+            .flat_map(|(entity, phase)| check_phase(*entity, phase, user_op, entry_point))
+            .collect();
+        user_op.violations = Some(violations);
+    }
+    true
+}
+
+fn check_phase(
+    entity: ValidationEntity,
+    phase: &ValidationPhase,
+    user_op: &UserOp,
+    entry_point: Address,
+) -> Vec<ValidationViolation> {
+    let violation = |rule: &str, description: String| ValidationViolation {
+        rule: rule.to_string(),
+        entity,
+        description,
+    };
+    let mut res = Vec::new();
+
+    for op in phase.opcodes.iter() {
+        if op == "GAS" {
+            res.push(violation(
+                "OP-012",
+                "GAS opcode is not followed by a call".to_string(),
+            ));
+        } else {
+            res.push(violation("OP-011", format!("banned opcode {op}")));
+        }
+    }
+
+    let creates = phase.calls.iter().filter(|c| c.typ == "CREATE2").count();
+    if creates > 0 && (entity != ValidationEntity::Factory || creates > 1) {
+        res.push(violation(
+            "OP-031",
+            format!("CREATE2 is used {creates} times"),
+        ));
+    }
+
+    for call in phase.calls.iter() {
+        if call.to == entry_point {
+            if call.selector != DEPOSIT_TO_SELECTOR && call.selector != "0x" {
+                res.push(violation(
+                    "OP-054",
+                    format!("entrypoint method {} is called", call.selector),
+                ));
+            }
+        } else if call.typ == "CALL" && !call.value.is_zero() {
+            res.push(violation(
+                "OP-061",
+                format!("call with value to {}", to_checksum(&call.to, None)),
+            ));
+        }
+    }
+
+    // storage of the entities themselves is allowed for staked entities only, so it's skipped
+    let entities: Vec<Address> = [Some(user_op.sender), user_op.factory, user_op.paymaster]
+        .into_iter()
+        .flatten()
+        .collect();
+    let associated: Vec<U256> = phase
+        .keccak
+        .iter()
+        .filter(|preimage| {
+            preimage.len() >= 32
+                && entities
+                    .iter()
+                    .any(|addr| preimage[..32] == H256::from(*addr).as_bytes()[..])
+        })
+        .map(|preimage| U256::from(keccak256(preimage)))
+        .collect();
+    let mut reported = HashSet::new();
+    for write in phase.writes.iter() {
+        if entities.contains(&write.address)
+            || associated.iter().any(|base| {
+                write.slot >= *base && write.slot - *base <= U256::from(ASSOCIATED_SLOTS_RANGE)
+            })
+            || !reported.insert((write.address, write.slot))
+        {
+            continue;
+        }
+        res.push(violation(
+            "STO-033",
+            format!(
+                "write to non-associated storage slot {:#x} of {}",
+                write.slot,
+                to_checksum(&write.address, None)
+            ),
+        ));
+    }
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn phase(to: Address, selector: &str) -> ValidationPhase {
+        ValidationPhase {
+            to,
+            selector: selector.to_string(),
+            opcodes: vec![],
+            calls: vec![],
+            writes: vec![],
+            keccak: vec![],
+        }
+    }
+
+    #[test]
+    fn check_phase_ok() {
+        let entry_point = Address::from_str("0x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789").unwrap();
+        let sender = Address::from_low_u64_be(1);
+        let token = Address::from_low_u64_be(2);
+        let user_op = UserOp {
+            sender,
+            ..test_user_op()
+        };
+
+        // balances[sender] slot of the mapping at slot 0
+        let preimage = Bytes::from([H256::from(sender).as_bytes(), &[0u8; 32]].concat());
+        let associated_slot = U256::from(keccak256(&preimage));
+
+        let mut account = phase(sender, "0x3a871cdd");
+        account.opcodes = vec!["TIMESTAMP".to_string(), "GAS".to_string()];
+        account.calls = vec![
+            ValidationCall {
+                typ: "CALL".to_string(),
+                to: entry_point,
+                selector: DEPOSIT_TO_SELECTOR.to_string(),
+                value: U256::from(1),
+            },
+            ValidationCall {
+                typ: "CALL".to_string(),
+                to: token,
+                selector: "0xa9059cbb".to_string(),
+                value: U256::from(1),
+            },
+            ValidationCall {
+                typ: "CALL".to_string(),
+                to: entry_point,
+                selector: "0x1fad948c".to_string(),
+                value: U256::zero(),
+            },
+        ];
+        account.writes = vec![
+            StorageWrite {
+                address: sender,
+                slot: U256::from(5),
+            },
+            StorageWrite {
+                address: token,
+                slot: associated_slot + 1,
+            },
+            StorageWrite {
+                address: token,
+                slot: U256::from(7),
+            },
+            StorageWrite {
+                address: token,
+                slot: U256::from(7),
+            },
+        ];
+        account.keccak = vec![preimage];
+
+        let violations = check_phase(ValidationEntity::Account, &account, &user_op, entry_point);
+        let rules: Vec<&str> = violations.iter().map(|v| v.rule.as_str()).collect();
+        assert_eq!(
+            rules,
+            vec!["OP-011", "OP-012", "OP-061", "OP-054", "STO-033"]
+        );
+        assert_eq!(
+            violations[4].description,
+            "write to non-associated storage slot 0x7 of 0x0000000000000000000000000000000000000002"
+        );
+    }
+
+    #[test]
+    fn tag_violations_ok() {
+        let entry_point = Address::from_str("0x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789").unwrap();
+        let sender_1 = Address::from_low_u64_be(1);
+        let sender_2 = Address::from_low_u64_be(2);
+        let factory = Address::from_low_u64_be(3);
+        let paymaster = Address::from_low_u64_be(4);
+        let sender_creator = Address::from_low_u64_be(5);
+
+        let mut user_ops = vec![
+            UserOp {
+                sender: sender_1,
+                factory: Some(factory),
+                ..test_user_op()
+            },
+            UserOp {
+                sender: sender_2,
+                paymaster: Some(paymaster),
+                ..test_user_op()
+            },
+        ];
+
+        let mut factory_phase = phase(sender_creator, CREATE_SENDER_SELECTOR);
+        factory_phase.calls = vec![ValidationCall {
+            typ: "CREATE2".to_string(),
+            to: sender_1,
+            selector: "0x60806040".to_string(),
+            value: U256::zero(),
+        }];
+        let mut paymaster_phase = phase(paymaster, "0xf465c77e");
+        paymaster_phase.opcodes = vec!["NUMBER".to_string()];
+        let phases = vec![
+            factory_phase,
+            phase(sender_1, "0x3a871cdd"),
+            phase(sender_2, "0x3a871cdd"),
+            paymaster_phase,
+        ];
+
+        assert!(tag_violations(phases, &mut user_ops, entry_point));
+        assert_eq!(user_ops[0].violations, Some(vec![]));
+        assert_eq!(
+            user_ops[1].violations,
+            Some(vec![ValidationViolation {
+                rule: "OP-011".to_string(),
+                entity: ValidationEntity::Paymaster,
+                description: "banned opcode NUMBER".to_string(),
+            }])
+        );
+
+        // phases don't match the user operations
+        let phases = vec![phase(sender_2, "0x3a871cdd"), phase(sender_1, "0x3a871cdd")];
+        assert!(!tag_violations(phases, &mut user_ops, entry_point));
+    }
+
+    fn test_user_op() -> UserOp {
+        UserOp {
+            hash: Default::default(),
+            sender: Default::default(),
+            nonce: Default::default(),
+            init_code: None,
+            call_data: Default::default(),
+            call_gas_limit: Default::default(),
+            verification_gas_limit: Default::default(),
+            pre_verification_gas: Default::default(),
+            max_fee_per_gas: Default::default(),
+            max_priority_fee_per_gas: Default::default(),
+            paymaster_and_data: None,
+            signature: Default::default(),
+            aggregator: None,
+            aggregator_signature: None,
+            entry_point: Default::default(),
+            entry_point_version: crate::types::user_op::EntryPointVersion::V06,
+            transaction_hash: Default::default(),
+            block_number: 0,
+            block_hash: Default::default(),
+            bundler: Default::default(),
+            bundle_index: 0,
+            index: 0,
+            factory: None,
+            paymaster: None,
+            status: true,
+            revert_reason: None,
+            gas: Default::default(),
+            gas_price: Default::default(),
+            gas_used: Default::default(),
+            sponsor_type: crate::types::user_op::SponsorType::WalletDeposit,
+            user_logs_start_index: 0,
+            user_logs_count: 0,
+            fee: Default::default(),
+            payer: Default::default(),
+            prefund: Default::default(),
+            refund: Default::default(),
+            violations: None,
+            pruned: false,
+
+            sender_delegate: None,
+            consensus: None,
+            timestamp: None,
+        }
+    }
+}
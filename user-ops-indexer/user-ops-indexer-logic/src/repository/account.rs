@@ -1,6 +1,9 @@
-use crate::types::account::Account;
+use crate::types::account::{Account, AccountProfile};
 use ethers::prelude::Address;
-use sea_orm::{prelude::DateTime, ConnectionTrait, DatabaseConnection, FromQueryResult, Statement};
+use sea_orm::{
+    prelude::{BigDecimal, DateTime},
+    ConnectionTrait, DatabaseConnection, FromQueryResult, Statement,
+};
 
 #[derive(FromQueryResult)]
 pub struct AccountDB {
@@ -10,6 +13,22 @@ pub struct AccountDB {
     pub creation_op_hash: Option<Vec<u8>>,
     pub creation_timestamp: Option<DateTime>,
     pub total_ops: i64,
+    pub delegate: Option<Vec<u8>>,
+}
+
+#[derive(FromQueryResult)]
+pub struct AccountProfileDB {
+    pub total_ops: i64,
+    pub first_op_hash: Vec<u8>,
+    pub first_op_timestamp: DateTime,
+    pub last_op_hash: Vec<u8>,
+    pub last_op_timestamp: DateTime,
+    pub total_fees: BigDecimal,
+}
+
+#[derive(FromQueryResult)]
+struct AddressDB {
+    address: Vec<u8>,
 }
 
 pub async fn find_account_by_address(
@@ -32,9 +51,11 @@ SELECT account_total_cte.sender                 as address,
        account_creation_op_cte.factory          as factory,
        account_creation_op_cte.transaction_hash as creation_transaction_hash,
        account_creation_op_cte.hash             as creation_op_hash,
-       account_creation_op_cte.timestamp        as creation_timestamp
+       account_creation_op_cte.timestamp        as creation_timestamp,
+       account_delegations.delegate             as delegate
 FROM account_total_cte
-         LEFT JOIN account_creation_op_cte ON account_total_cte.sender = account_creation_op_cte.sender"#,
+         LEFT JOIN account_creation_op_cte ON account_total_cte.sender = account_creation_op_cte.sender
+         LEFT JOIN account_delegations ON account_total_cte.sender = account_delegations.address"#,
         [addr.as_bytes().into()],
     ))
         .one(db)
@@ -44,6 +65,68 @@ FROM account_total_cte
     Ok(acc)
 }
 
+pub async fn find_account_profile(
+    db: &DatabaseConnection,
+    addr: Address,
+) -> Result<Option<AccountProfile>, anyhow::Error> {
+    let profile = AccountProfileDB::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        r#"
+SELECT count(*)                                                                       as total_ops,
+       (array_agg(user_operations.hash ORDER BY block_number, user_operations.hash))[1] as first_op_hash,
+       min(blocks.timestamp)                                                          as first_op_timestamp,
+       (array_agg(user_operations.hash ORDER BY block_number DESC,
+           user_operations.hash DESC))[1]                                             as last_op_hash,
+       max(blocks.timestamp)                                                          as last_op_timestamp,
+       coalesce(sum(user_operations.gas_price * user_operations.gas_used), 0)         as total_fees
+FROM user_operations
+         JOIN blocks ON blocks.hash = block_hash AND consensus
+WHERE sender = $1
+HAVING count(*) > 0"#,
+        [addr.as_bytes().into()],
+    ))
+    .one(db)
+    .await?;
+
+    let Some(profile) = profile else {
+        return Ok(None);
+    };
+
+    let factories = find_account_counterparties(db, addr, "factory").await?;
+    let paymasters = find_account_counterparties(db, addr, "paymaster").await?;
+
+    Ok(Some(AccountProfile::new(
+        addr, profile, factories, paymasters,
+    )))
+}
+
+async fn find_account_counterparties(
+    db: &DatabaseConnection,
+    addr: Address,
+    column: &'static str,
+) -> Result<Vec<Address>, anyhow::Error> {
+    let addresses = AddressDB::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        format!(
+            r#"
+SELECT DISTINCT {column} as address
+FROM user_operations
+         JOIN blocks ON blocks.hash = block_hash AND consensus
+WHERE sender = $1
+  AND {column} IS NOT NULL
+ORDER BY 1"#
+        ),
+        [addr.as_bytes().into()],
+    ))
+    .all(db)
+    .await?
+    .into_iter()
+    .map(|a| Address::from_slice(&a.address))
+    .collect();
+
+    Ok(addresses)
+}
+
 pub async fn list_accounts(
     db: &DatabaseConnection,
     factory_filter: Option<Address>,
@@ -75,9 +158,11 @@ SELECT accounts_cte.sender                    as address,
        accounts_cte.factory                   as factory,
        accounts_cte.creation_transaction_hash as creation_transaction_hash,
        accounts_cte.creation_op_hash          as creation_op_hash,
-       accounts_cte.creation_timestamp        as creation_timestamp
+       accounts_cte.creation_timestamp        as creation_timestamp,
+       account_delegations.delegate           as delegate
 FROM accounts_cte
-         JOIN accounts_total_cte ON accounts_cte.sender = accounts_total_cte.sender"#,
+         JOIN accounts_total_cte ON accounts_cte.sender = accounts_total_cte.sender
+         LEFT JOIN account_delegations ON accounts_cte.sender = account_delegations.address"#,
         [
             factory_filter.map(|f| f.as_bytes().to_vec()).into(),
             page_token.unwrap_or(Address::zero()).as_bytes().into(),
@@ -122,6 +207,7 @@ mod tests {
                 creation_op_hash: None,
                 creation_timestamp: None,
                 total_ops: 100,
+                delegate: Some(Address::from_low_u64_be(0x7702)),
             })
         );
 
@@ -136,10 +222,42 @@ mod tests {
                 creation_op_hash: Some(H256::from_low_u64_be(0x3201)),
                 creation_timestamp: Some("2024-01-01T00:01:00.000000Z".to_string()),
                 total_ops: 100,
+                delegate: None,
             })
         );
     }
 
+    #[tokio::test]
+    async fn find_account_profile_ok() {
+        let db = get_shared_db().await;
+
+        let addr = Address::from_low_u64_be(0xffff);
+        let item = find_account_profile(&db, addr).await.unwrap();
+        assert_eq!(item, None);
+
+        let addr = Address::from_low_u64_be(0x0202);
+        let item = find_account_profile(&db, addr).await.unwrap();
+        assert_eq!(
+            item,
+            Some(AccountProfile {
+                address: addr,
+                total_ops: 100,
+                first_op_hash: H256::from_low_u64_be(0x0201),
+                first_op_timestamp: "2024-01-01T00:00:00.000000Z".to_string(),
+                last_op_hash: H256::from_low_u64_be(0x26ae01),
+                last_op_timestamp: "2024-01-01T03:18:00.000000Z".to_string(),
+                factories: vec![],
+                paymasters: vec![Address::from_low_u64_be(0xe1)],
+                total_fees: BigDecimal::from(5607431285480400u64),
+            })
+        );
+
+        let addr = Address::from_low_u64_be(0x3402);
+        let item = find_account_profile(&db, addr).await.unwrap().unwrap();
+        assert_eq!(item.factories, vec![Address::from_low_u64_be(0xf1)]);
+        assert_eq!(item.paymasters, vec![]);
+    }
+
     #[tokio::test]
     async fn list_accounts_ok() {
         let db = get_shared_db().await;
@@ -156,6 +274,11 @@ mod tests {
         let (items, next_page_token) = list_accounts(&db, factory, None, 60).await.unwrap();
         assert_eq!(items.len(), 10);
         assert_eq!(next_page_token, None);
-        assert!(items.iter().all(|a| a.factory == factory))
+        assert!(items.iter().all(|a| a.factory == factory));
+
+        let (items, _) = list_accounts(&db, None, Some(Address::from_low_u64_be(0x0102)), 1)
+            .await
+            .unwrap();
+        assert_eq!(items[0].delegate, Some(Address::from_low_u64_be(0x7702)));
     }
 }
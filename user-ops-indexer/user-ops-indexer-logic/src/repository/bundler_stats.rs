@@ -0,0 +1,291 @@
+use crate::types::bundler_stats::{
+    BundlerDailyStats, BundlerPerformance, BundlerTxStats, LeaderboardSort,
+};
+use chrono::NaiveDate;
+use entity::bundler_transactions::{ActiveModel, Column, Entity, Model};
+use ethers::prelude::{Address, H256};
+use futures::{Stream, StreamExt};
+use sea_orm::{
+    prelude::{BigDecimal, DateTime},
+    sea_query::{Expr, OnConflict},
+    ActiveValue, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, FromQueryResult,
+    Iterable, Statement,
+};
+
+#[derive(FromQueryResult)]
+struct TxHash {
+    transaction_hash: Vec<u8>,
+}
+
+#[derive(FromQueryResult, Clone)]
+pub struct BundlerPerformanceDB {
+    pub bundler: Vec<u8>,
+    pub total_bundles: i64,
+    pub total_ops: i64,
+    pub failed_ops: i64,
+    pub fees: BigDecimal,
+    pub gas_cost: BigDecimal,
+}
+
+#[derive(FromQueryResult, Clone)]
+pub struct BundlerDailyStatsDB {
+    pub date: NaiveDate,
+    pub bundler: Vec<u8>,
+    pub total_bundles: i64,
+    pub total_ops: i64,
+    pub failed_ops: i64,
+    pub fees: BigDecimal,
+    pub gas_cost: BigDecimal,
+}
+
+pub async fn upsert_many<C: ConnectionTrait>(
+    db: &C,
+    stats: Vec<BundlerTxStats>,
+) -> Result<(), anyhow::Error> {
+    if stats.is_empty() {
+        return Ok(());
+    }
+
+    let stats = stats.into_iter().map(|s| {
+        let model: Model = s.into();
+        let mut active: ActiveModel = model.into();
+        active.inserted_at = ActiveValue::NotSet;
+        active.updated_at = ActiveValue::NotSet;
+        active
+    });
+
+    Entity::insert_many(stats)
+        .on_conflict(
+            OnConflict::columns([Column::TransactionHash, Column::EntryPoint, Column::Bundler])
+                .update_columns(Column::iter().filter(|col| {
+                    !matches!(
+                        col,
+                        Column::TransactionHash
+                            | Column::EntryPoint
+                            | Column::Bundler
+                            | Column::InsertedAt
+                            | Column::UpdatedAt
+                    )
+                }))
+                .value(Column::UpdatedAt, Expr::current_timestamp())
+                .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn leaderboard(
+    db: &DatabaseConnection,
+    sort: LeaderboardSort,
+    since: Option<DateTime>,
+    limit: u64,
+) -> Result<Vec<BundlerPerformance>, anyhow::Error> {
+    let order_by = match sort {
+        LeaderboardSort::Ops => "total_ops DESC",
+        LeaderboardSort::Revenue => "fees - gas_cost DESC",
+        LeaderboardSort::FailureRate => "failed_ops::float8 / greatest(total_ops, 1) DESC",
+    };
+
+    let bundlers = BundlerPerformanceDB::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        format!(
+            r#"
+SELECT *
+FROM (SELECT bundler,
+             sum(bundles_count)::int8    as total_bundles,
+             sum(ops_count)::int8        as total_ops,
+             sum(failed_ops_count)::int8 as failed_ops,
+             sum(fees)                   as fees,
+             sum(gas_cost)               as gas_cost
+      FROM bundler_transactions
+               JOIN blocks ON blocks.hash = bundler_transactions.block_hash AND consensus
+      WHERE ($1::timestamp IS NULL OR blocks.timestamp >= $1)
+      GROUP BY bundler) stats
+ORDER BY {order_by}, bundler DESC
+LIMIT $2"#
+        ),
+        [since.into(), limit.into()],
+    ))
+    .all(db)
+    .await?
+    .into_iter()
+    .map(BundlerPerformance::from)
+    .collect();
+
+    Ok(bundlers)
+}
+
+pub async fn daily_stats(
+    db: &DatabaseConnection,
+    addr: Address,
+    since: Option<DateTime>,
+) -> Result<Vec<BundlerDailyStats>, anyhow::Error> {
+    let stats = BundlerDailyStatsDB::find_by_statement(Statement::from_sql_and_values(
+        db.get_database_backend(),
+        r#"
+SELECT blocks.timestamp::date                          as date,
+       bundler,
+       sum(bundles_count)::int8                        as total_bundles,
+       sum(ops_count)::int8                            as total_ops,
+       sum(failed_ops_count)::int8                     as failed_ops,
+       sum(fees)                                       as fees,
+       sum(gas_cost)                                   as gas_cost
+FROM bundler_transactions
+         JOIN blocks ON blocks.hash = bundler_transactions.block_hash AND consensus
+WHERE bundler = $1
+  AND ($2::timestamp IS NULL OR blocks.timestamp >= $2)
+GROUP BY 1, 2
+ORDER BY 1"#,
+        [addr.as_bytes().into(), since.into()],
+    ))
+    .all(db)
+    .await?
+    .into_iter()
+    .map(BundlerDailyStats::from)
+    .collect();
+
+    Ok(stats)
+}
+
+/// Streams hashes of the transactions with the indexed user operations of the entrypoint,
+/// but without the bundler stats, e.g. the ones indexed before the stats were introduced.
+pub async fn stream_tx_hashes_without_stats(
+    db: &DatabaseConnection,
+    entry_point: Address,
+) -> Result<impl Stream<Item = H256> + '_, anyhow::Error> {
+    let tx_stream = TxHash::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+SELECT DISTINCT user_operations.transaction_hash as transaction_hash
+FROM user_operations
+         LEFT JOIN bundler_transactions
+                   ON user_operations.transaction_hash = bundler_transactions.transaction_hash
+                       AND user_operations.entry_point = bundler_transactions.entry_point
+WHERE user_operations.entry_point = $1
+  AND bundler_transactions.transaction_hash IS NULL"#,
+        [entry_point.as_bytes().into()],
+    ))
+    .stream(db)
+    .await?
+    .filter_map(|tx| async {
+        match tx {
+            Ok(tx) => Some(H256::from_slice(&tx.transaction_hash)),
+            Err(err) => {
+                tracing::error!(error = ?err, "error during tx hash retrieval");
+                None
+            }
+        }
+    });
+
+    Ok(tx_stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::tests::get_shared_db;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn bundler(i: u64) -> Address {
+        Address::from_low_u64_be(i * 256 + 10)
+    }
+
+    #[tokio::test]
+    async fn leaderboard_ok() {
+        let db = get_shared_db().await;
+
+        let items = leaderboard(&db, LeaderboardSort::Ops, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            items.iter().map(|b| b.bundler).collect::<Vec<_>>(),
+            vec![bundler(2), bundler(1), bundler(0)]
+        );
+        assert_eq!(
+            items[0],
+            BundlerPerformance {
+                bundler: bundler(2),
+                total_bundles: 33,
+                total_ops: 99,
+                failed_ops: 33,
+                fees: 99000.into(),
+                gas_cost: 16500.into(),
+            }
+        );
+        assert_eq!(items[0].revenue(), 82500.into());
+
+        let items = leaderboard(&db, LeaderboardSort::Revenue, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            items.iter().map(|b| b.bundler).collect::<Vec<_>>(),
+            vec![bundler(2), bundler(1), bundler(0)]
+        );
+
+        let items = leaderboard(&db, LeaderboardSort::FailureRate, None, 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            items.iter().map(|b| b.bundler).collect::<Vec<_>>(),
+            vec![bundler(0), bundler(2)]
+        );
+        assert_eq!(items[0].failure_rate(), 1.0);
+
+        // only bundle transactions from blocks 90..99 are counted
+        let since = "2024-01-01T00:18:00".parse().unwrap();
+        let items = leaderboard(&db, LeaderboardSort::Ops, Some(since), 10)
+            .await
+            .unwrap();
+        assert_eq!(items.iter().map(|b| b.total_ops).sum::<u64>(), 19);
+    }
+
+    #[tokio::test]
+    async fn stream_tx_hashes_without_stats_ok() {
+        let db = get_shared_db().await;
+
+        let entry_point = Address::from_str("0x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789").unwrap();
+        let tx_hashes = stream_tx_hashes_without_stats(&db, entry_point)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        // none of the fixture transactions have stats, one of them contains two user operations
+        assert_eq!(tx_hashes.len(), 9999);
+        assert!(tx_hashes.contains(&H256::from_low_u64_be(0x504)));
+
+        let tx_hashes = stream_tx_hashes_without_stats(&db, Address::zero())
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(tx_hashes, vec![]);
+    }
+
+    #[tokio::test]
+    async fn daily_stats_ok() {
+        let db = get_shared_db().await;
+
+        let items = daily_stats(&db, Address::from_low_u64_be(0xffff), None)
+            .await
+            .unwrap();
+        assert_eq!(items, vec![]);
+
+        let items = daily_stats(&db, bundler(1), None).await.unwrap();
+        assert_eq!(
+            items,
+            vec![BundlerDailyStats {
+                date: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                stats: BundlerPerformance {
+                    bundler: bundler(1),
+                    total_bundles: 33,
+                    total_ops: 66,
+                    failed_ops: 0,
+                    fees: 66000.into(),
+                    gas_cost: 33000.into(),
+                },
+            }]
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use crate::types::delegation::AccountDelegation;
+use entity::account_delegations::{ActiveModel, Column, Entity, Model};
+use ethers::prelude::Address;
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ActiveValue, DatabaseConnection, EntityTrait, Iterable,
+};
+
+pub async fn upsert_many(
+    db: &DatabaseConnection,
+    delegations: Vec<AccountDelegation>,
+) -> Result<(), anyhow::Error> {
+    if delegations.is_empty() {
+        return Ok(());
+    }
+
+    let delegations = delegations.into_iter().map(|d| {
+        let model: Model = d.into();
+        let mut active: ActiveModel = model.into();
+        active.inserted_at = ActiveValue::NotSet;
+        active.updated_at = ActiveValue::NotSet;
+        active
+    });
+
+    Entity::insert_many(delegations)
+        .on_conflict(
+            OnConflict::column(Column::Address)
+                .update_columns(Column::iter().filter(|col| {
+                    !matches!(
+                        col,
+                        Column::Address | Column::InsertedAt | Column::UpdatedAt
+                    )
+                }))
+                .value(Column::UpdatedAt, Expr::current_timestamp())
+                // past transactions may be indexed after the recent ones, keep the latest delegation
+                .action_and_where(Expr::cust(
+                    "account_delegations.block_number <= EXCLUDED.block_number",
+                ))
+                .to_owned(),
+        )
+        .exec_without_returning(db)
+        .await?;
+    Ok(())
+}
+
+/// Returns the current delegate of the EOA, `None` if the account was never delegated
+/// or the delegation was cleared
+pub async fn find_delegate(
+    db: &DatabaseConnection,
+    addr: Address,
+) -> Result<Option<Address>, anyhow::Error> {
+    let delegate = Entity::find_by_id(addr.as_bytes())
+        .one(db)
+        .await?
+        .map(|d| Address::from_slice(&d.delegate))
+        .filter(|d| !d.is_zero());
+
+    Ok(delegate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::tests::TestMigrator;
+    use blockscout_service_launcher::test_database::TestDbGuard;
+    use ethers::prelude::H256;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn find_delegate_ok() {
+        let db = TestDbGuard::new::<TestMigrator>("delegation_find_delegate_ok")
+            .await
+            .client();
+
+        let addr = Address::from_low_u64_be(0x0202);
+        assert_eq!(find_delegate(&db, addr).await.unwrap(), None);
+
+        let addr = Address::from_low_u64_be(0x0102);
+        assert_eq!(
+            find_delegate(&db, addr).await.unwrap(),
+            Some(Address::from_low_u64_be(0x7702))
+        );
+
+        let addr = Address::from_low_u64_be(0x3202);
+        assert_eq!(find_delegate(&db, addr).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn upsert_many_keeps_latest_ok() {
+        let db = TestDbGuard::new::<TestMigrator>("delegation_upsert_many_keeps_latest_ok")
+            .await
+            .client();
+
+        let addr = Address::from_low_u64_be(0x7777);
+        let delegation = |delegate: u64, block_number: u64| AccountDelegation {
+            address: addr,
+            delegate: Address::from_low_u64_be(delegate),
+            nonce: block_number.into(),
+            transaction_hash: H256::from_low_u64_be(block_number),
+            block_number,
+            block_hash: H256::from_low_u64_be(block_number),
+        };
+
+        upsert_many(&db, vec![delegation(0x7701, 20)])
+            .await
+            .unwrap();
+        upsert_many(&db, vec![delegation(0x7702, 10)])
+            .await
+            .unwrap();
+        assert_eq!(
+            find_delegate(&db, addr).await.unwrap(),
+            Some(Address::from_low_u64_be(0x7701))
+        );
+
+        upsert_many(&db, vec![delegation(0, 30)]).await.unwrap();
+        assert_eq!(find_delegate(&db, addr).await.unwrap(), None);
+    }
+}
@@ -1,6 +1,8 @@
 pub mod account;
 pub mod bundle;
 pub mod bundler;
+pub mod bundler_stats;
+pub mod delegation;
 pub mod factory;
 pub mod paymaster;
 pub mod user_op;
@@ -16,7 +16,8 @@ FROM generate_series(0, 999) n;
 INSERT INTO user_operations (hash, sender, nonce, call_data, call_gas_limit, verification_gas_limit,
                              pre_verification_gas, max_fee_per_gas, max_priority_fee_per_gas, signature, entry_point,
                              transaction_hash, block_number, block_hash, bundle_index, index, user_logs_start_index,
-                             user_logs_count, bundler, status, gas, gas_price, gas_used, sponsor_type)
+                             user_logs_count, bundler, status, gas, gas_price, gas_used, sponsor_type, payer,
+                             prefund, refund)
 SELECT decode(lpad(to_hex(n * 256 + 1), 64, '0'), 'hex'),
        decode(lpad(to_hex(mod(n, 100) * 256 + 2), 40, '0'), 'hex'),
        decode(lpad(to_hex(n * 256 + 3), 64, '0'), 'hex'),
@@ -40,7 +41,10 @@ SELECT decode(lpad(to_hex(n * 256 + 1), 64, '0'), 'hex'),
        6000000 + n,
        7000000 + n,
        8000000 + n,
-       'wallet_deposit'
+       'wallet_deposit',
+       decode(lpad(to_hex(mod(n, 100) * 256 + 2), 40, '0'), 'hex'),
+       (6000000 + n)::numeric * (4000000 + n),
+       0
 FROM generate_series(0, 9999) n;
 
 UPDATE blocks
@@ -68,17 +72,47 @@ WHERE transaction_hash = '\x0000000000000000000000000000000000000000000000000000
 
 UPDATE user_operations
 SET paymaster    = '\x00000000000000000000000000000000000000e1',
+    payer        = '\x00000000000000000000000000000000000000e1',
     sponsor_type = 'paymaster_sponsor'
 WHERE block_number = 20;
 
 UPDATE user_operations
 SET paymaster    = '\x00000000000000000000000000000000000000e2',
+    payer        = '\x00000000000000000000000000000000000000e2',
     sponsor_type = 'paymaster_sponsor'
 WHERE block_number = 21;
 
+UPDATE user_operations
+SET payer   = NULL,
+    prefund = NULL,
+    refund  = NULL
+WHERE block_number = 22;
+
 INSERT INTO logs (data, index, first_topic, second_topic, third_topic, fourth_topic, inserted_at, updated_at,
                   address_hash, transaction_hash, block_hash, block_number)
 VALUES ('\x', 0, '\x49628fd1471006c1482da88028e9ce4dbb080b815c9b0344d39e5a8e6ec1419f', NULL, NULL, NULL, now(),
         now(), '\x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789',
         '\x000000000000000000000000000000000000000000000000000000000000ffff',
         '\x000000000000000000000000000000000000000000000000000000000000ff00', 123);
+
+INSERT INTO bundler_transactions (transaction_hash, entry_point, bundler, block_number, block_hash, bundles_count,
+                                  ops_count, failed_ops_count, fees, gas_cost)
+SELECT decode(lpad(to_hex(n * 256 + 9), 64, '0'), 'hex'),
+       '\x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789',
+       decode(lpad(to_hex(mod(n, 3) * 256 + 10), 40, '0'), 'hex'),
+       n,
+       decode(lpad(to_hex(n * 256), 64, '0'), 'hex'),
+       1,
+       mod(n, 3) + 1,
+       CASE WHEN mod(n, 3) = 1 THEN 0 ELSE 1 END,
+       1000 * (mod(n, 3) + 1),
+       500 * (3 - mod(n, 3))
+FROM generate_series(0, 99) n;
+
+INSERT INTO account_delegations (address, delegate, nonce, transaction_hash, block_number, block_hash)
+VALUES ('\x0000000000000000000000000000000000000102', '\x0000000000000000000000000000000000007702', 0,
+        '\x0000000000000000000000000000000000000000000000000000000000000104', 0,
+        '\x0000000000000000000000000000000000000000000000000000000000000000'),
+       ('\x0000000000000000000000000000000000003202', '\x0000000000000000000000000000000000000000', 1,
+        '\x0000000000000000000000000000000000000000000000000000000000003204', 50,
+        '\x0000000000000000000000000000000000000000000000000000000000003200');
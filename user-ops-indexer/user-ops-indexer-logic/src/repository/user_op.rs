@@ -1,4 +1,7 @@
-use crate::types::user_op::{ListUserOp, UserOp};
+use crate::{
+    repository::delegation,
+    types::user_op::{ListUserOp, UserOp},
+};
 use blockscout_db::entity::blocks;
 use entity::{
     sea_orm_active_enums::EntryPointVersion,
@@ -75,6 +78,7 @@ pub async fn find_user_op_by_op_hash(
                 t.and_utc()
                     .to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
             });
+            user_op.sender_delegate = delegation::find_delegate(db, user_op.sender).await?;
             Some(user_op)
         }
     };
@@ -168,8 +172,91 @@ pub async fn list_user_ops(
     }
 }
 
-pub async fn upsert_many(
+/// Lists canonical user operations included within the `[from, to)` time range,
+/// in the ascending order, which keeps the cursors stable while new blocks are indexed.
+pub async fn export_user_ops(
+    db: &DatabaseConnection,
+    from: DateTime,
+    to: DateTime,
+    cursor: Option<(u64, H256)>,
+    limit: u64,
+) -> Result<(Vec<UserOp>, Option<(u64, H256)>), anyhow::Error> {
+    let cursor = cursor.unwrap_or((0, H256::zero()));
+    let rows = db
+        .query_all(
+            Entity::find()
+                .column(blocks::Column::Timestamp)
+                .join_rev(JoinType::Join, user_ops_blocks_rel())
+                .filter(blocks::Column::Timestamp.gte(from))
+                .filter(blocks::Column::Timestamp.lt(to))
+                .filter(
+                    Expr::tuple([
+                        Column::BlockNumber.into_simple_expr(),
+                        Column::Hash.into_simple_expr(),
+                    ])
+                    .gte(Expr::tuple([cursor.0.into(), cursor.1.as_bytes().into()])),
+                )
+                .order_by_asc(Column::BlockNumber)
+                .order_by_asc(Column::Hash)
+                .limit(limit + 1)
+                .build(db.get_database_backend()),
+        )
+        .await?;
+
+    let mut user_ops = rows
+        .iter()
+        .map(|row| {
+            let mut user_op = UserOp::from(Model::from_query_result(row, "")?);
+            user_op.consensus = Some(true);
+            user_op.timestamp = Some(
+                row.try_get::<DateTime>("", "timestamp")?
+                    .and_utc()
+                    .to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            );
+            Ok(user_op)
+        })
+        .collect::<Result<Vec<_>, sea_orm::DbErr>>()?;
+
+    let next_cursor = user_ops
+        .get(limit as usize)
+        .map(|user_op| (user_op.block_number, user_op.hash));
+    user_ops.truncate(limit as usize);
+    Ok((user_ops, next_cursor))
+}
+
+/// Deletes raw call data, init code and signatures of up to `limit` canonical user operations
+/// included before `before`. Returns the number of pruned user operations.
+pub async fn prune_raw_data(
     db: &DatabaseConnection,
+    before: DateTime,
+    limit: u64,
+) -> Result<u64, anyhow::Error> {
+    let res = db
+        .execute(Statement::from_sql_and_values(
+            db.get_database_backend(),
+            r#"
+UPDATE user_operations
+SET call_data            = '\x',
+    init_code            = NULL,
+    signature            = '\x',
+    aggregator_signature = NULL,
+    pruned               = true,
+    updated_at           = now()
+WHERE hash IN (SELECT user_operations.hash
+               FROM user_operations
+                        JOIN blocks ON blocks.hash = block_hash AND consensus
+               WHERE NOT pruned
+                 AND blocks.timestamp < $1
+               LIMIT $2)"#,
+            [before.into(), (limit as i64).into()],
+        ))
+        .await?;
+
+    Ok(res.rows_affected())
+}
+
+pub async fn upsert_many<C: ConnectionTrait>(
+    db: &C,
     user_ops: Vec<UserOp>,
 ) -> Result<(), anyhow::Error> {
     let user_ops = user_ops.into_iter().map(|user_op| {
@@ -184,8 +271,16 @@ pub async fn upsert_many(
         .on_conflict(
             OnConflict::column(Column::Hash)
                 .update_columns(Column::iter().filter(|col| {
-                    !matches!(col, Column::Hash | Column::InsertedAt | Column::UpdatedAt)
+                    !matches!(
+                        col,
+                        Column::Hash | Column::InsertedAt | Column::UpdatedAt | Column::Violations
+                    )
                 }))
+                // keep previously detected violations when re-indexing without the check
+                .value(
+                    Column::Violations,
+                    Expr::cust("COALESCE(EXCLUDED.violations, user_operations.violations)"),
+                )
                 .value(Column::UpdatedAt, Expr::current_timestamp())
                 .to_owned(),
         )
@@ -238,8 +333,9 @@ WHERE logs.address_hash    = $1
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::repository::tests::get_shared_db;
-    use ethers::prelude::U256;
+    use crate::repository::tests::{get_shared_db, TestMigrator};
+    use blockscout_service_launcher::test_database::TestDbGuard;
+    use ethers::prelude::{Bytes, U256};
     use pretty_assertions::assert_eq;
     use std::str::FromStr;
 
@@ -257,6 +353,7 @@ mod tests {
         let item = item.unwrap();
         assert_eq!(item.hash, hash);
         assert_eq!(item.consensus, Some(true));
+        assert_eq!(item.sender_delegate, Some(Address::from_low_u64_be(0x7702)));
 
         let hash = H256::from_low_u64_be(0x1a0401);
         let item = find_user_op_by_op_hash(&db, hash).await.unwrap();
@@ -271,6 +368,52 @@ mod tests {
         let item = item.unwrap();
         assert_eq!(item.hash, hash);
         assert_eq!(item.consensus, None);
+
+        let hash = H256::from_low_u64_be(0xc801);
+        let item = find_user_op_by_op_hash(&db, hash).await.unwrap().unwrap();
+        assert_eq!(item.payer, Address::from_low_u64_be(0xe1));
+        assert_eq!(item.prefund, U256::from(24002000040000u64));
+        assert_eq!(item.refund, U256::zero());
+
+        // payment is derived for the operations indexed before it was stored
+        let hash = H256::from_low_u64_be(0xdc01);
+        let item = find_user_op_by_op_hash(&db, hash).await.unwrap().unwrap();
+        assert_eq!(item.payer, Address::from_low_u64_be(0x1402));
+        assert_eq!(item.prefund, U256::from(24002200048400u64));
+        assert_eq!(item.refund, U256::zero());
+    }
+
+    #[tokio::test]
+    async fn export_user_ops_ok() {
+        let db = get_shared_db().await;
+        let from = DateTime::from_str("2024-01-01T00:00:00").unwrap();
+        let to = DateTime::from_str("2024-01-01T00:01:00").unwrap();
+
+        // blocks 0..4, including one user op moved to the block 0
+        let (items, next_cursor) = export_user_ops(&db, from, to, None, 30).await.unwrap();
+        assert_eq!(items.len(), 30);
+        assert_eq!(items[0].block_number, 0);
+        assert_eq!(
+            items[0].timestamp.as_deref(),
+            Some("2024-01-01T00:00:00.000000Z")
+        );
+        assert!(items
+            .windows(2)
+            .all(|w| (w[0].block_number, w[0].hash) < (w[1].block_number, w[1].hash)));
+        let next_cursor = next_cursor.unwrap();
+
+        let (rest, next_cursor) = export_user_ops(&db, from, to, Some(next_cursor), 30)
+            .await
+            .unwrap();
+        assert_eq!(rest.len(), 21);
+        assert_eq!(next_cursor, None);
+        assert_eq!(rest.last().unwrap().block_number, 4);
+        assert!(
+            (
+                items.last().unwrap().block_number,
+                items.last().unwrap().hash
+            ) < (rest[0].block_number, rest[0].hash)
+        );
     }
 
     #[tokio::test]
@@ -370,4 +513,32 @@ mod tests {
             .await;
         assert_eq!(items, [H256::from_low_u64_be(0xffff)]);
     }
+
+    #[tokio::test]
+    async fn prune_raw_data_ok() {
+        let db = TestDbGuard::new::<TestMigrator>("user_op_prune_raw_data_ok")
+            .await
+            .client();
+
+        // only the first block with 10 user operations is older than the threshold
+        let before = DateTime::from_str("2024-01-01T00:00:12").unwrap();
+        assert_eq!(prune_raw_data(&db, before, 6).await.unwrap(), 6);
+        assert_eq!(prune_raw_data(&db, before, 6).await.unwrap(), 4);
+        assert_eq!(prune_raw_data(&db, before, 6).await.unwrap(), 0);
+
+        let item = find_user_op_by_op_hash(&db, H256::from_low_u64_be(0x0101))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(item.pruned);
+        assert_eq!(item.call_data, Bytes::default());
+        assert_eq!(item.signature, Bytes::default());
+        assert_eq!(item.init_code, None);
+
+        let item = find_user_op_by_op_hash(&db, H256::from_low_u64_be(0x0b01))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!item.pruned);
+    }
 }
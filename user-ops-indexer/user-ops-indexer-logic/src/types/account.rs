@@ -1,8 +1,9 @@
-use crate::repository::account::AccountDB;
+use crate::repository::account::{AccountDB, AccountProfileDB};
 use ethers::{
     prelude::{abi::AbiEncode, Address, H256},
     utils::to_checksum,
 };
+use sea_orm::prelude::BigDecimal;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Account {
@@ -12,6 +13,8 @@ pub struct Account {
     pub creation_op_hash: Option<H256>,
     pub creation_timestamp: Option<String>,
     pub total_ops: u32,
+    /// EIP-7702 delegate of the sender, if the sender is a delegated EOA
+    pub delegate: Option<Address>,
 }
 
 impl From<AccountDB> for Account {
@@ -26,6 +29,10 @@ impl From<AccountDB> for Account {
                     .to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
             }),
             total_ops: v.total_ops as u32,
+            delegate: v
+                .delegate
+                .map(|a| Address::from_slice(&a))
+                .filter(|a| !a.is_zero()),
         }
     }
 }
@@ -39,6 +46,66 @@ impl From<Account> for user_ops_indexer_proto::blockscout::user_ops_indexer::v1:
             creation_op_hash: v.creation_op_hash.map(|a| a.encode_hex()),
             creation_timestamp: v.creation_timestamp,
             total_ops: v.total_ops,
+            delegate: v.delegate.map(|a| to_checksum(&a, None)),
+        }
+    }
+}
+
+/// Aggregated history of a single account (sender)
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountProfile {
+    pub address: Address,
+    pub total_ops: u32,
+    pub first_op_hash: H256,
+    pub first_op_timestamp: String,
+    pub last_op_hash: H256,
+    pub last_op_timestamp: String,
+    pub factories: Vec<Address>,
+    pub paymasters: Vec<Address>,
+    pub total_fees: BigDecimal,
+}
+
+impl AccountProfile {
+    pub fn new(
+        address: Address,
+        v: AccountProfileDB,
+        factories: Vec<Address>,
+        paymasters: Vec<Address>,
+    ) -> Self {
+        Self {
+            address,
+            total_ops: v.total_ops as u32,
+            first_op_hash: H256::from_slice(&v.first_op_hash),
+            first_op_timestamp: v
+                .first_op_timestamp
+                .and_utc()
+                .to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            last_op_hash: H256::from_slice(&v.last_op_hash),
+            last_op_timestamp: v
+                .last_op_timestamp
+                .and_utc()
+                .to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+            factories,
+            paymasters,
+            total_fees: v.total_fees,
+        }
+    }
+}
+
+impl From<AccountProfile>
+    for user_ops_indexer_proto::blockscout::user_ops_indexer::v1::AccountProfile
+{
+    fn from(v: AccountProfile) -> Self {
+        Self {
+            address: to_checksum(&v.address, None),
+            total_ops: v.total_ops,
+            first_op_hash: v.first_op_hash.encode_hex(),
+            first_op_timestamp: v.first_op_timestamp,
+            last_op_hash: v.last_op_hash.encode_hex(),
+            last_op_timestamp: v.last_op_timestamp,
+            factories: v.factories.iter().map(|a| to_checksum(a, None)).collect(),
+            paymasters: v.paymasters.iter().map(|a| to_checksum(a, None)).collect(),
+            total_fees: v.total_fees.to_string(),
         }
     }
 }
@@ -0,0 +1,210 @@
+use crate::{
+    repository::bundler_stats::{BundlerDailyStatsDB, BundlerPerformanceDB},
+    types::{common::u256_to_decimal, user_op::UserOp},
+};
+use chrono::NaiveDate;
+use entity::bundler_transactions::Model;
+use ethers::{
+    prelude::{Address, H256, U256},
+    utils::to_checksum,
+};
+use sea_orm::prelude::BigDecimal;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    str::FromStr,
+};
+
+/// Contribution of a single transaction to the statistics of its bundler
+#[derive(Clone, Debug, PartialEq)]
+pub struct BundlerTxStats {
+    pub transaction_hash: H256,
+    pub entry_point: Address,
+    pub bundler: Address,
+    pub block_number: u64,
+    pub block_hash: H256,
+    pub bundles_count: u32,
+    pub ops_count: u32,
+    pub failed_ops_count: u32,
+    pub fees: U256,
+    pub gas_cost: U256,
+}
+
+impl BundlerTxStats {
+    /// Groups user operations of a single transaction by the bundler.
+    ///
+    /// Transaction `gas_cost` is split between the bundlers proportionally to the
+    /// number of their operations among `total_ops` operations of the transaction
+    /// (including the ones sent to other EntryPoints).
+    pub fn from_user_ops(user_ops: &[UserOp], gas_cost: U256, total_ops: usize) -> Vec<Self> {
+        let total_ops = total_ops.max(user_ops.len()).max(1);
+        let mut stats: BTreeMap<Address, (BTreeSet<u32>, Self)> = BTreeMap::new();
+        for op in user_ops {
+            let (bundles, entry) = stats.entry(op.bundler).or_insert_with(|| {
+                (
+                    BTreeSet::new(),
+                    Self {
+                        transaction_hash: op.transaction_hash,
+                        entry_point: op.entry_point,
+                        bundler: op.bundler,
+                        block_number: op.block_number,
+                        block_hash: op.block_hash,
+                        bundles_count: 0,
+                        ops_count: 0,
+                        failed_ops_count: 0,
+                        fees: U256::zero(),
+                        gas_cost: U256::zero(),
+                    },
+                )
+            });
+            bundles.insert(op.bundle_index);
+            entry.ops_count += 1;
+            if !op.status {
+                entry.failed_ops_count += 1;
+            }
+            entry.fees += op.fee;
+        }
+        stats
+            .into_values()
+            .map(|(bundles, mut entry)| {
+                entry.bundles_count = bundles.len() as u32;
+                entry.gas_cost = gas_cost * entry.ops_count / total_ops;
+                entry
+            })
+            .collect()
+    }
+}
+
+impl From<BundlerTxStats> for Model {
+    fn from(v: BundlerTxStats) -> Self {
+        Self {
+            transaction_hash: v.transaction_hash.as_bytes().to_vec(),
+            entry_point: v.entry_point.as_bytes().to_vec(),
+            bundler: v.bundler.as_bytes().to_vec(),
+            block_number: v.block_number as i32,
+            block_hash: v.block_hash.as_bytes().to_vec(),
+            bundles_count: v.bundles_count as i32,
+            ops_count: v.ops_count as i32,
+            failed_ops_count: v.failed_ops_count as i32,
+            fees: u256_to_decimal(v.fees),
+            gas_cost: u256_to_decimal(v.gas_cost),
+            inserted_at: Default::default(),
+            updated_at: Default::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LeaderboardSort {
+    #[default]
+    Ops,
+    Revenue,
+    FailureRate,
+}
+
+impl FromStr for LeaderboardSort {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ops" => Ok(Self::Ops),
+            "revenue" => Ok(Self::Revenue),
+            "failure_rate" => Ok(Self::FailureRate),
+            _ => Err(anyhow::anyhow!(
+                "expected one of: ops, revenue, failure_rate"
+            )),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BundlerPerformance {
+    pub bundler: Address,
+    pub total_bundles: u64,
+    pub total_ops: u64,
+    pub failed_ops: u64,
+    pub fees: BigDecimal,
+    pub gas_cost: BigDecimal,
+}
+
+impl BundlerPerformance {
+    /// Fees collected from the user operations minus the gas spent on bundle transactions
+    pub fn revenue(&self) -> BigDecimal {
+        &self.fees - &self.gas_cost
+    }
+
+    pub fn failure_rate(&self) -> f64 {
+        if self.total_ops == 0 {
+            return 0.0;
+        }
+        self.failed_ops as f64 / self.total_ops as f64
+    }
+}
+
+impl From<BundlerPerformanceDB> for BundlerPerformance {
+    fn from(v: BundlerPerformanceDB) -> Self {
+        Self {
+            bundler: Address::from_slice(&v.bundler),
+            total_bundles: v.total_bundles as u64,
+            total_ops: v.total_ops as u64,
+            failed_ops: v.failed_ops as u64,
+            fees: v.fees,
+            gas_cost: v.gas_cost,
+        }
+    }
+}
+
+impl From<BundlerPerformance>
+    for user_ops_indexer_proto::blockscout::user_ops_indexer::v1::BundlerPerformance
+{
+    fn from(v: BundlerPerformance) -> Self {
+        Self {
+            address: to_checksum(&v.bundler, None),
+            total_bundles: v.total_bundles,
+            total_ops: v.total_ops,
+            failed_ops: v.failed_ops,
+            failure_rate: v.failure_rate(),
+            revenue: v.revenue().to_string(),
+            fees: v.fees.to_string(),
+            gas_cost: v.gas_cost.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BundlerDailyStats {
+    pub date: NaiveDate,
+    pub stats: BundlerPerformance,
+}
+
+impl From<BundlerDailyStatsDB> for BundlerDailyStats {
+    fn from(v: BundlerDailyStatsDB) -> Self {
+        Self {
+            date: v.date,
+            stats: BundlerPerformance {
+                bundler: Address::from_slice(&v.bundler),
+                total_bundles: v.total_bundles as u64,
+                total_ops: v.total_ops as u64,
+                failed_ops: v.failed_ops as u64,
+                fees: v.fees,
+                gas_cost: v.gas_cost,
+            },
+        }
+    }
+}
+
+impl From<BundlerDailyStats>
+    for user_ops_indexer_proto::blockscout::user_ops_indexer::v1::BundlerDailyStats
+{
+    fn from(v: BundlerDailyStats) -> Self {
+        Self {
+            date: v.date.to_string(),
+            total_bundles: v.stats.total_bundles,
+            total_ops: v.stats.total_ops,
+            failed_ops: v.stats.failed_ops,
+            failure_rate: v.stats.failure_rate(),
+            revenue: v.stats.revenue().to_string(),
+            fees: v.stats.fees.to_string(),
+            gas_cost: v.stats.gas_cost.to_string(),
+        }
+    }
+}
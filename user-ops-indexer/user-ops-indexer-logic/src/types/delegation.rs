@@ -0,0 +1,313 @@
+use crate::types::common::u256_to_decimal;
+use entity::account_delegations::Model;
+use ethers::{
+    prelude::{Address, Signature, Transaction, H256, U256, U64},
+    utils::{keccak256, rlp::RlpStream},
+};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// EIP-7702 transaction type
+const SET_CODE_TX_TYPE: u64 = 4;
+
+/// Prefix of the EIP-7702 authorization message
+const AUTHORIZATION_MAGIC: u8 = 0x05;
+
+/// Prefix of the code set to the delegated EOA, followed by the delegate address
+const DELEGATION_DESIGNATOR: [u8; 3] = [0xef, 0x01, 0x00];
+
+/// Half of the secp256k1 curve order, signatures with a greater `s` are rejected as malleable
+const SECP256K1N_HALF: U256 = U256([
+    0xdfe92f46681b20a0,
+    0x5d576e7357a4501d,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
+/// Signed EIP-7702 authorization, as returned in the `authorizationList` of the rpc transaction
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Authorization {
+    pub chain_id: U256,
+    pub address: Address,
+    pub nonce: U256,
+    pub y_parity: U64,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl Authorization {
+    pub fn signing_hash(&self) -> H256 {
+        let mut stream = RlpStream::new_list(3);
+        stream.append(&self.chain_id);
+        stream.append(&self.address);
+        stream.append(&self.nonce);
+        let mut message = vec![AUTHORIZATION_MAGIC];
+        message.extend_from_slice(&stream.out());
+        H256::from(keccak256(message))
+    }
+
+    /// Checks the authorization fields bounds, as required by the EIP-7702 processing rules
+    pub fn is_well_formed(&self) -> bool {
+        self.nonce < U256::from(u64::MAX)
+            && self.y_parity <= U64::one()
+            && !self.r.is_zero()
+            && !self.s.is_zero()
+            && self.s <= SECP256K1N_HALF
+    }
+
+    /// Recovers the EOA which signed the authorization, `None` if the authorization is malformed
+    pub fn authority(&self) -> Option<Address> {
+        if !self.is_well_formed() {
+            return None;
+        }
+        let signature = Signature {
+            r: self.r,
+            s: self.s,
+            v: self.y_parity.as_u64(),
+        };
+        signature.recover(self.signing_hash()).ok()
+    }
+}
+
+/// Latest known EIP-7702 delegation of the EOA.
+/// Zero `delegate` address means that the delegation was cleared.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountDelegation {
+    pub address: Address,
+    pub delegate: Address,
+    pub nonce: U256,
+    pub transaction_hash: H256,
+    pub block_number: u64,
+    pub block_hash: H256,
+}
+
+impl AccountDelegation {
+    /// Extracts delegations from the authorization list of the set code transaction.
+    ///
+    /// Authorizations for other chains and malformed or invalid signed authorizations are skipped.
+    /// If the same EOA signed several authorizations, the last one wins, as during the execution.
+    /// The authority nonce is not known here, so the result should be checked
+    /// against the account code with [`AccountDelegation::is_applied`].
+    pub fn from_transaction(tx: &Transaction) -> Vec<Self> {
+        if tx.transaction_type != Some(SET_CODE_TX_TYPE.into()) {
+            return vec![];
+        }
+        let authorizations = match tx
+            .other
+            .get_deserialized::<Vec<Authorization>>("authorizationList")
+        {
+            Some(Ok(authorizations)) => authorizations,
+            Some(Err(err)) => {
+                tracing::warn!(error = ?err, "failed to parse authorization list");
+                return vec![];
+            }
+            None => return vec![],
+        };
+
+        let chain_id = tx.chain_id.unwrap_or_default();
+        authorizations
+            .into_iter()
+            .filter(|auth| auth.chain_id.is_zero() || auth.chain_id == chain_id)
+            .filter_map(|auth| {
+                let address = auth.authority()?;
+                Some((
+                    address,
+                    Self {
+                        address,
+                        delegate: auth.address,
+                        nonce: auth.nonce,
+                        transaction_hash: tx.hash,
+                        block_number: tx.block_number.unwrap_or_default().as_u64(),
+                        block_hash: tx.block_hash.unwrap_or_default(),
+                    },
+                ))
+            })
+            .collect::<BTreeMap<_, _>>()
+            .into_values()
+            .collect()
+    }
+
+    /// Code of the authority account after the delegation is applied
+    pub fn expected_code(&self) -> Vec<u8> {
+        if self.delegate.is_zero() {
+            return vec![];
+        }
+        [DELEGATION_DESIGNATOR.as_slice(), self.delegate.as_bytes()].concat()
+    }
+
+    /// Checks whether the delegation took effect, given the authority code at the end of the block.
+    /// Authorizations with a stale nonce are skipped during the execution without failing the transaction.
+    pub fn is_applied(&self, code: &[u8]) -> bool {
+        code == self.expected_code()
+    }
+}
+
+impl From<AccountDelegation> for Model {
+    fn from(v: AccountDelegation) -> Self {
+        Self {
+            address: v.address.as_bytes().to_vec(),
+            delegate: v.delegate.as_bytes().to_vec(),
+            nonce: u256_to_decimal(v.nonce),
+            transaction_hash: v.transaction_hash.as_bytes().to_vec(),
+            block_number: v.block_number as i32,
+            block_hash: v.block_hash.as_bytes().to_vec(),
+            inserted_at: Default::default(),
+            updated_at: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::prelude::{LocalWallet, Signer};
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn sign(wallet: &LocalWallet, chain_id: u64, address: Address, nonce: u64) -> Authorization {
+        let mut auth = Authorization {
+            chain_id: chain_id.into(),
+            address,
+            nonce: nonce.into(),
+            y_parity: U64::zero(),
+            r: U256::zero(),
+            s: U256::zero(),
+        };
+        let signature = wallet.sign_hash(auth.signing_hash()).unwrap();
+        auth.y_parity = (signature.v - 27).into();
+        auth.r = signature.r;
+        auth.s = signature.s;
+        auth
+    }
+
+    #[test]
+    fn authority_ok() {
+        let wallet = LocalWallet::from_str(
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        )
+        .unwrap();
+        let auth = sign(&wallet, 1, Address::from_low_u64_be(0x7702), 5);
+        assert_eq!(auth.authority(), Some(wallet.address()));
+
+        let tampered = Authorization {
+            nonce: 6.into(),
+            ..auth.clone()
+        };
+        assert_ne!(tampered.authority(), Some(wallet.address()));
+    }
+
+    #[test]
+    fn malformed_authorization_is_skipped() {
+        let wallet = LocalWallet::from_str(
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        )
+        .unwrap();
+        let auth = sign(&wallet, 1, Address::from_low_u64_be(0x7702), 5);
+        assert!(auth.is_well_formed());
+
+        let malformed = [
+            Authorization {
+                nonce: U256::from(u64::MAX),
+                ..auth.clone()
+            },
+            Authorization {
+                y_parity: auth.y_parity + 27,
+                ..auth.clone()
+            },
+            Authorization {
+                r: U256::zero(),
+                ..auth.clone()
+            },
+            // malleable counterpart of the valid signature
+            Authorization {
+                y_parity: U64::one() - auth.y_parity,
+                s: SECP256K1N_HALF * 2 + 1 - auth.s,
+                ..auth.clone()
+            },
+        ];
+        for auth in malformed {
+            assert!(!auth.is_well_formed(), "{auth:?}");
+            assert_eq!(auth.authority(), None);
+        }
+    }
+
+    #[test]
+    fn is_applied_ok() {
+        let delegation = AccountDelegation {
+            address: Address::from_low_u64_be(0x0102),
+            delegate: Address::from_low_u64_be(0x7702),
+            nonce: 1.into(),
+            transaction_hash: H256::zero(),
+            block_number: 10,
+            block_hash: H256::zero(),
+        };
+        let code = [
+            vec![0xef, 0x01, 0x00],
+            Address::from_low_u64_be(0x7702).as_bytes().to_vec(),
+        ]
+        .concat();
+        assert!(delegation.is_applied(&code));
+        assert!(!delegation.is_applied(&[]));
+
+        let cleared = AccountDelegation {
+            delegate: Address::zero(),
+            ..delegation
+        };
+        assert!(cleared.is_applied(&[]));
+        assert!(!cleared.is_applied(&code));
+    }
+
+    #[test]
+    fn from_transaction_ok() {
+        let wallet1 = LocalWallet::from_str(
+            "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318",
+        )
+        .unwrap();
+        let wallet2 = LocalWallet::from_str(
+            "0x8da4ef21b864d2cc526dbdb2a120bd2874c36c9d0a1fb7f8c63d7f7a8b41de8f",
+        )
+        .unwrap();
+        let authorizations = vec![
+            sign(&wallet1, 1, Address::from_low_u64_be(0x7701), 0),
+            sign(&wallet1, 0, Address::from_low_u64_be(0x7702), 1),
+            sign(&wallet2, 5, Address::from_low_u64_be(0x7703), 0),
+        ];
+
+        let mut tx = Transaction {
+            hash: H256::from_low_u64_be(0x0104),
+            block_number: Some(10.into()),
+            block_hash: Some(H256::from_low_u64_be(0x0a00)),
+            chain_id: Some(1.into()),
+            ..Default::default()
+        };
+        tx.other.insert(
+            "authorizationList".to_string(),
+            serde_json::json!(authorizations
+                .iter()
+                .map(|auth| serde_json::json!({
+                    "chainId": auth.chain_id,
+                    "address": auth.address,
+                    "nonce": auth.nonce,
+                    "yParity": auth.y_parity,
+                    "r": auth.r,
+                    "s": auth.s,
+                }))
+                .collect::<Vec<_>>()),
+        );
+        assert_eq!(AccountDelegation::from_transaction(&tx), vec![]);
+
+        tx.transaction_type = Some(SET_CODE_TX_TYPE.into());
+        assert_eq!(
+            AccountDelegation::from_transaction(&tx),
+            vec![AccountDelegation {
+                address: wallet1.address(),
+                delegate: Address::from_low_u64_be(0x7702),
+                nonce: 1.into(),
+                transaction_hash: H256::from_low_u64_be(0x0104),
+                block_number: 10,
+                block_hash: H256::from_low_u64_be(0x0a00),
+            }]
+        );
+    }
+}
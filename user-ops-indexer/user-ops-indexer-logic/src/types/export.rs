@@ -0,0 +1,99 @@
+use crate::types::user_op::UserOp;
+use ethers::{
+    prelude::{abi::AbiEncode, Address},
+    utils::to_checksum,
+};
+use sea_orm::ActiveEnum;
+use serde::Serialize;
+
+/// Version of the [`ExportUserOp`] schema. Fields may only be appended within the same version,
+/// any other change of the existing fields requires a version bump.
+pub const EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Flat representation of the user operation for the research datasets.
+/// All integer values are encoded as decimal strings to avoid precision loss.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExportUserOp {
+    pub hash: String,
+    pub sender: String,
+    pub nonce: String,
+    pub entry_point: String,
+    pub entry_point_version: String,
+    pub transaction_hash: String,
+    pub block_number: u64,
+    pub block_hash: String,
+    pub timestamp: Option<String>,
+    pub bundler: String,
+    pub bundle_index: u32,
+    pub index: u32,
+    pub factory: Option<String>,
+    pub paymaster: Option<String>,
+    pub aggregator: Option<String>,
+    pub status: bool,
+    pub revert_reason: Option<String>,
+    pub call_gas_limit: String,
+    pub verification_gas_limit: String,
+    pub pre_verification_gas: String,
+    pub max_fee_per_gas: String,
+    pub max_priority_fee_per_gas: String,
+    pub gas: String,
+    pub gas_price: String,
+    pub gas_used: String,
+    pub fee: String,
+    pub sponsor_type: String,
+    pub payer: String,
+    pub prefund: String,
+    pub refund: String,
+    pub init_code: Option<String>,
+    pub call_data: String,
+    pub paymaster_and_data: Option<String>,
+    pub signature: String,
+    pub user_logs_start_index: u32,
+    pub user_logs_count: u32,
+    pub pruned: bool,
+}
+
+impl From<UserOp> for ExportUserOp {
+    fn from(v: UserOp) -> Self {
+        let checksum = |a: Address| to_checksum(&a, None);
+        Self {
+            hash: v.hash.encode_hex(),
+            sender: checksum(v.sender),
+            nonce: v.nonce.encode_hex(),
+            entry_point: checksum(v.entry_point),
+            entry_point_version: v.entry_point_version.to_value().to_string(),
+            transaction_hash: v.transaction_hash.encode_hex(),
+            block_number: v.block_number,
+            block_hash: v.block_hash.encode_hex(),
+            timestamp: v.timestamp,
+            bundler: checksum(v.bundler),
+            bundle_index: v.bundle_index,
+            index: v.index,
+            factory: v.factory.map(checksum),
+            paymaster: v.paymaster.map(checksum),
+            aggregator: v.aggregator.map(checksum),
+            status: v.status,
+            revert_reason: v.revert_reason.map(|b| b.to_string()),
+            call_gas_limit: v.call_gas_limit.to_string(),
+            verification_gas_limit: v.verification_gas_limit.to_string(),
+            pre_verification_gas: v.pre_verification_gas.to_string(),
+            max_fee_per_gas: v.max_fee_per_gas.to_string(),
+            max_priority_fee_per_gas: v.max_priority_fee_per_gas.to_string(),
+            gas: v.gas.to_string(),
+            gas_price: v.gas_price.to_string(),
+            gas_used: v.gas_used.to_string(),
+            fee: v.fee.to_string(),
+            sponsor_type: v.sponsor_type.to_value().to_string(),
+            payer: checksum(v.payer),
+            prefund: v.prefund.to_string(),
+            refund: v.refund.to_string(),
+            init_code: v.init_code.map(|b| b.to_string()),
+            call_data: v.call_data.to_string(),
+            paymaster_and_data: v.paymaster_and_data.map(|b| b.to_string()),
+            signature: v.signature.to_string(),
+            user_logs_start_index: v.user_logs_start_index,
+            user_logs_count: v.user_logs_count,
+            pruned: v.pruned,
+        }
+    }
+}
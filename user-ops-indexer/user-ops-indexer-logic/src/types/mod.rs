@@ -1,7 +1,11 @@
 pub mod account;
 pub mod bundle;
 pub mod bundler;
+pub mod bundler_stats;
 pub mod common;
+pub mod delegation;
+pub mod export;
 pub mod factory;
 pub mod paymaster;
 pub mod user_op;
+pub mod validation;
@@ -1,6 +1,7 @@
 use crate::{
-    indexer::common::decode_execute_call_data, repository::user_op::ListUserOpDB,
-    types::common::u256_to_decimal,
+    indexer::common::{decode_execute_call_data, decode_paymaster_and_data, extract_payment},
+    repository::user_op::ListUserOpDB,
+    types::{common::u256_to_decimal, validation::ValidationViolation},
 };
 pub use entity::sea_orm_active_enums::{EntryPointVersion, SponsorType};
 use entity::user_operations::Model;
@@ -47,7 +48,19 @@ pub struct UserOp {
     pub user_logs_start_index: u32,
     pub user_logs_count: u32,
     pub fee: U256,
+    /// Entity whose deposit was charged for the gas, paymaster or the sender itself
+    pub payer: Address,
+    /// Max gas cost locked from the payer deposit during the validation
+    pub prefund: U256,
+    /// Unused part of the prefund returned to the payer deposit
+    pub refund: U256,
+    /// ERC-7562 validation rules violations, `None` if the user operation wasn't checked
+    pub violations: Option<Vec<ValidationViolation>>,
+    /// Raw calldata, init code and signatures were deleted by the pruning
+    pub pruned: bool,
 
+    /// EIP-7702 delegate of the sender, set if the sender is a delegated EOA
+    pub sender_delegate: Option<Address>,
     pub consensus: Option<bool>,
     pub timestamp: Option<String>,
 }
@@ -102,22 +115,43 @@ impl From<UserOp> for Model {
             user_logs_count: v.user_logs_count as i32,
             inserted_at: Default::default(),
             updated_at: Default::default(),
+            violations: v
+                .violations
+                .and_then(|violations| serde_json::to_value(violations).ok()),
+            payer: Some(v.payer.as_bytes().to_vec()),
+            prefund: Some(u256_to_decimal(v.prefund)),
+            refund: Some(u256_to_decimal(v.refund)),
+            pruned: v.pruned,
         }
     }
 }
 
 impl From<Model> for UserOp {
     fn from(v: Model) -> Self {
+        let sender = Address::from_slice(&v.sender);
+        let paymaster = v.paymaster.clone().map(|a| Address::from_slice(&a));
+        let gas = U256::from(v.gas.to_u128().unwrap_or(0));
+        let max_fee_per_gas = U256::from(v.max_fee_per_gas.to_u128().unwrap_or(0));
+        let fee = U256::from((&v.gas_price).mul(&v.gas_used).to_u128().unwrap_or(0));
+        // payment is not stored for the operations indexed before it was introduced
+        let (payer, prefund, refund) = match (&v.payer, &v.prefund, &v.refund) {
+            (Some(payer), Some(prefund), Some(refund)) => (
+                Address::from_slice(payer),
+                U256::from(prefund.to_u128().unwrap_or(0)),
+                U256::from(refund.to_u128().unwrap_or(0)),
+            ),
+            _ => extract_payment(sender, paymaster, gas, max_fee_per_gas, fee),
+        };
         Self {
             hash: H256::from_slice(&v.hash),
-            sender: Address::from_slice(&v.sender),
+            sender,
             nonce: H256::from_slice(&v.nonce),
             init_code: v.init_code.clone().map(Bytes::from),
             call_data: Bytes::from(v.call_data.clone()),
             call_gas_limit: U256::from(v.call_gas_limit.to_u128().unwrap_or(0)),
             verification_gas_limit: U256::from(v.verification_gas_limit.to_u128().unwrap_or(0)),
             pre_verification_gas: U256::from(v.pre_verification_gas.to_u128().unwrap_or(0)),
-            max_fee_per_gas: U256::from(v.max_fee_per_gas.to_u128().unwrap_or(0)),
+            max_fee_per_gas,
             max_priority_fee_per_gas: U256::from(v.max_priority_fee_per_gas.to_u128().unwrap_or(0)),
             paymaster_and_data: v.paymaster_and_data.clone().map(Bytes::from),
             signature: Bytes::from(v.signature.clone()),
@@ -132,17 +166,25 @@ impl From<Model> for UserOp {
             bundle_index: v.bundle_index as u32,
             index: v.index as u32,
             factory: v.factory.clone().map(|a| Address::from_slice(&a)),
-            paymaster: v.paymaster.clone().map(|a| Address::from_slice(&a)),
+            paymaster,
             status: v.status,
             revert_reason: v.revert_reason.clone().map(Bytes::from),
-            gas: U256::from(v.gas.to_u128().unwrap_or(0)),
+            gas,
             gas_price: U256::from(v.gas_price.to_u128().unwrap_or(0)),
             gas_used: U256::from(v.gas_used.to_u128().unwrap_or(0)),
             sponsor_type: v.sponsor_type.clone(),
             user_logs_start_index: v.user_logs_start_index as u32,
             user_logs_count: v.user_logs_count as u32,
-            fee: U256::from(v.gas_price.mul(v.gas_used).to_u128().unwrap_or(0)),
+            fee,
+            payer,
+            prefund,
+            refund,
+            violations: v
+                .violations
+                .and_then(|violations| serde_json::from_value(violations).ok()),
+            pruned: v.pruned,
 
+            sender_delegate: None,
             consensus: None,
             timestamp: None,
         }
@@ -151,6 +193,22 @@ impl From<Model> for UserOp {
 
 impl From<UserOp> for user_ops_indexer_proto::blockscout::user_ops_indexer::v1::UserOp {
     fn from(v: UserOp) -> Self {
+        let (paymaster_verification_gas_limit, paymaster_post_op_gas_limit, paymaster_data) = v
+            .paymaster_and_data
+            .as_ref()
+            .map(|b| decode_paymaster_and_data(&v.entry_point_version, b))
+            .unwrap_or_default();
+        let sponsorship = user_ops_indexer_proto::blockscout::user_ops_indexer::v1::Sponsorship {
+            payer: to_checksum(&v.payer, None),
+            prefund: v.prefund.to_string(),
+            charged: v.fee.to_string(),
+            refund: v.refund.to_string(),
+            paymaster_verification_gas_limit: paymaster_verification_gas_limit
+                .map(|g| g.to_string()),
+            paymaster_post_op_gas_limit: paymaster_post_op_gas_limit.map(|g| g.to_string()),
+            paymaster_data: paymaster_data.map(|b| b.to_string()),
+        };
+
         let raw = match v.entry_point_version {
             EntryPointVersion::V06 => {
                 user_ops_indexer_proto::blockscout::user_ops_indexer::v1::user_op::Raw::RawV06(
@@ -241,12 +299,23 @@ impl From<UserOp> for user_ops_indexer_proto::blockscout::user_ops_indexer::v1::
             user_logs_start_index: v.user_logs_start_index,
             user_logs_count: v.user_logs_count,
             fee: v.fee.to_string(),
+            sponsorship: Some(sponsorship),
+            violations_checked: v.violations.is_some(),
+            violations: v
+                .violations
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| v.into())
+                .collect(),
 
             consensus: v.consensus,
             timestamp: v.timestamp,
 
             execute_target: execute_target.map(|a| to_checksum(&a, None)),
             execute_call_data: execute_call_data.map(|b| b.to_string()),
+
+            sender_delegate: v.sender_delegate.map(|a| to_checksum(&a, None)),
+            pruned: v.pruned,
         }
     }
 }
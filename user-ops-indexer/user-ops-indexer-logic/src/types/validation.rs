@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Entity whose validation code violated the rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationEntity {
+    Account,
+    Factory,
+    Paymaster,
+}
+
+impl Display for ValidationEntity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationEntity::Account => write!(f, "account"),
+            ValidationEntity::Factory => write!(f, "factory"),
+            ValidationEntity::Paymaster => write!(f, "paymaster"),
+        }
+    }
+}
+
+/// Violation of the ERC-7562 validation rules, detected during the indexing
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ValidationViolation {
+    /// Rule identifier from the ERC-7562, e.g. `OP-011`
+    pub rule: String,
+    pub entity: ValidationEntity,
+    pub description: String,
+}
+
+impl From<ValidationViolation>
+    for user_ops_indexer_proto::blockscout::user_ops_indexer::v1::ValidationViolation
+{
+    fn from(v: ValidationViolation) -> Self {
+        Self {
+            rule: v.rule,
+            entity: v.entity.to_string(),
+            description: v.description,
+        }
+    }
+}
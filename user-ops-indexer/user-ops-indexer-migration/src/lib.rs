@@ -4,6 +4,11 @@ mod m20220101_000001_initial_tables;
 mod m20231117_093738_add_indexes;
 mod m20240206_150422_add_entrypoint_version;
 mod m20240717_111524_add_transaction_hash_index;
+mod m20241018_000000_add_bundler_transactions;
+mod m20241021_000000_add_user_op_violations;
+mod m20241022_000000_add_user_op_payment;
+mod m20241023_000000_add_account_delegations;
+mod m20241024_000000_add_user_op_pruned;
 
 pub struct Migrator;
 
@@ -15,6 +20,11 @@ impl MigratorTrait for Migrator {
             Box::new(m20231117_093738_add_indexes::Migration),
             Box::new(m20240206_150422_add_entrypoint_version::Migration),
             Box::new(m20240717_111524_add_transaction_hash_index::Migration),
+            Box::new(m20241018_000000_add_bundler_transactions::Migration),
+            Box::new(m20241021_000000_add_user_op_violations::Migration),
+            Box::new(m20241022_000000_add_user_op_payment::Migration),
+            Box::new(m20241023_000000_add_account_delegations::Migration),
+            Box::new(m20241024_000000_add_user_op_pruned::Migration),
         ]
     }
     fn migration_table_name() -> DynIden {
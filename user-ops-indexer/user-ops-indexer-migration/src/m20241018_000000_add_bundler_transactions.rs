@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "bundler_transactions" (
+                "transaction_hash" bytea NOT NULL,
+                "entry_point" bytea NOT NULL,
+                "bundler" bytea NOT NULL,
+                "block_number" int NOT NULL,
+                "block_hash" bytea NOT NULL,
+                "bundles_count" int NOT NULL,
+                "ops_count" int NOT NULL,
+                "failed_ops_count" int NOT NULL,
+                "fees" NUMERIC(100) NOT NULL,
+                "gas_cost" NUMERIC(100) NOT NULL,
+                "inserted_at" timestamp NOT NULL DEFAULT (now()),
+                "updated_at" timestamp NOT NULL DEFAULT (now()),
+                PRIMARY KEY ("transaction_hash", "entry_point", "bundler")
+            );
+
+            CREATE INDEX bundler_transactions_bundler_index ON bundler_transactions (bundler);
+
+            CREATE INDEX bundler_transactions_block_hash_index ON bundler_transactions (block_hash);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "bundler_transactions";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
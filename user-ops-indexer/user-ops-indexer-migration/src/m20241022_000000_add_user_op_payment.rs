@@ -0,0 +1,29 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Columns are left nullable, so that adding them doesn't rewrite the table.
+        // Payment of the operations indexed before is derived from the gas fields on read.
+        let sql = r#"
+            ALTER TABLE "user_operations"
+                ADD COLUMN "payer"   bytea,
+                ADD COLUMN "prefund" numeric(100),
+                ADD COLUMN "refund"  numeric(100);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "user_operations"
+                DROP COLUMN "payer",
+                DROP COLUMN "prefund",
+                DROP COLUMN "refund";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
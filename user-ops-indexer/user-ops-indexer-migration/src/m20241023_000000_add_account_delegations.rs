@@ -0,0 +1,33 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            CREATE TABLE "account_delegations" (
+                "address" bytea NOT NULL,
+                "delegate" bytea NOT NULL,
+                "nonce" NUMERIC(100) NOT NULL,
+                "transaction_hash" bytea NOT NULL,
+                "block_number" int NOT NULL,
+                "block_hash" bytea NOT NULL,
+                "inserted_at" timestamp NOT NULL DEFAULT (now()),
+                "updated_at" timestamp NOT NULL DEFAULT (now()),
+                PRIMARY KEY ("address")
+            );
+
+            CREATE INDEX account_delegations_delegate_index ON account_delegations (delegate);
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP TABLE "account_delegations";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
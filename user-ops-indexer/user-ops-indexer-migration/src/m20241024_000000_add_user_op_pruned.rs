@@ -0,0 +1,26 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            ALTER TABLE "user_operations" ADD COLUMN "pruned" boolean NOT NULL DEFAULT false;
+
+            -- pruning looks up not pruned user operations joined with the old blocks by hash
+            CREATE INDEX user_operations_not_pruned_block_hash_index ON user_operations (block_hash) WHERE NOT pruned;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+            DROP INDEX user_operations_not_pruned_block_hash_index;
+
+            ALTER TABLE "user_operations" DROP COLUMN "pruned";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
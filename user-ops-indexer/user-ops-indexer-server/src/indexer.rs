@@ -1,42 +1,68 @@
 use crate::settings::Settings;
+use ethers::prelude::Address;
 use ethers::{prelude::Provider, utils::to_checksum};
-use sea_orm::DatabaseConnection;
+use sea_orm::{ActiveEnum, DatabaseConnection, Iterable};
 use std::sync::Arc;
 use tokio::time::sleep;
-use user_ops_indexer_logic::indexer::{
-    common_transport::CommonTransport, settings::IndexerSettings, v06, v07, Indexer, IndexerLogic,
+use user_ops_indexer_logic::{
+    indexer::{
+        common_transport::CommonTransport, delegations, progress::BackfillProgressTracker, pruning,
+        settings::IndexerSettings, v06, v07, Indexer, IndexerLogic,
+    },
+    types::user_op::EntryPointVersion,
 };
 
 pub async fn run(
     settings: Settings,
     db_connection: DatabaseConnection,
+    progress: BackfillProgressTracker,
 ) -> Result<(), anyhow::Error> {
     let db_connection = Arc::new(db_connection);
 
-    if settings.indexer.entrypoints.v06 {
-        start_indexer_with_retries(
+    for version in EntryPointVersion::iter() {
+        let Some(entry_point) = settings.indexer.entrypoints.entry_point(&version) else {
+            tracing::warn!("indexer for {} is disabled in settings", version.to_value());
+            continue;
+        };
+        let db_connection = db_connection.clone();
+        let settings = settings.indexer.clone();
+        let progress = progress.clone();
+        match version {
+            EntryPointVersion::V06 => {
+                start_indexer_with_retries::<v06::IndexerV06>(
+                    db_connection,
+                    settings,
+                    entry_point,
+                    progress,
+                )
+                .await?
+            }
+            EntryPointVersion::V07 => {
+                start_indexer_with_retries::<v07::IndexerV07>(
+                    db_connection,
+                    settings,
+                    entry_point,
+                    progress,
+                )
+                .await?
+            }
+        }
+    }
+
+    if settings.indexer.pruning.enabled {
+        tokio::spawn(pruning::run(
             db_connection.clone(),
-            settings.indexer.clone(),
-            v06::IndexerV06 {
-                entry_point: settings.indexer.entrypoints.v06_entry_point,
-            },
-        )
-        .await?;
-    } else {
-        tracing::warn!("indexer for v0.6 is disabled in settings");
+            settings.indexer.pruning.clone(),
+        ));
     }
 
-    if settings.indexer.entrypoints.v07 {
-        start_indexer_with_retries(
+    if settings.indexer.delegations.enabled {
+        let transport = CommonTransport::from_settings(&settings.indexer).await?;
+        tokio::spawn(delegations::run(
+            Provider::new(transport),
             db_connection.clone(),
-            settings.indexer.clone(),
-            v07::IndexerV07 {
-                entry_point: settings.indexer.entrypoints.v07_entry_point,
-            },
-        )
-        .await?;
-    } else {
-        tracing::warn!("indexer for v0.7 is disabled in settings");
+            settings.indexer.delegations.clone(),
+        ));
     }
 
     Ok(())
@@ -45,8 +71,10 @@ pub async fn run(
 async fn start_indexer_with_retries<L: IndexerLogic + Sync + Clone + Send + 'static>(
     db_connection: Arc<DatabaseConnection>,
     settings: IndexerSettings,
-    logic: L,
+    entry_point: Address,
+    progress: BackfillProgressTracker,
 ) -> anyhow::Result<()> {
+    let logic = L::new(entry_point);
     tracing::info!(
         version = L::version(),
         entry_point = to_checksum(&logic.entry_point(), None),
@@ -55,14 +83,15 @@ async fn start_indexer_with_retries<L: IndexerLogic + Sync + Clone + Send + 'sta
 
     // If the first connect fails, the function will return an error immediately.
     // All subsequent reconnects are done inside tokio task and will not propagate to above.
-    let transport = CommonTransport::new(settings.rpc_url.clone()).await?;
+    let transport = CommonTransport::from_settings(&settings).await?;
     let client = Provider::new(transport);
     let mut indexer = Indexer::new(
         client,
         db_connection.clone(),
         settings.clone(),
         logic.clone(),
-    );
+    )
+    .with_progress(progress.clone());
 
     let delay = settings.restart_delay;
 
@@ -98,7 +127,7 @@ async fn start_indexer_with_retries<L: IndexerLogic + Sync + Clone + Send + 'sta
 
                 tracing::info!(version = L::version(), "re-connecting to rpc");
 
-                let transport = match CommonTransport::new(settings.rpc_url.clone()).await {
+                let transport = match CommonTransport::from_settings(&settings).await {
                     Ok(transport) => transport,
                     Err(err) => {
                         tracing::error!(
@@ -116,7 +145,8 @@ async fn start_indexer_with_retries<L: IndexerLogic + Sync + Clone + Send + 'sta
                     db_connection.clone(),
                     settings.clone(),
                     logic.clone(),
-                );
+                )
+                .with_progress(progress.clone());
                 break;
             }
         }
@@ -1,5 +1,6 @@
 use blockscout_service_launcher::{database, launcher::ConfigSettings};
 use migration::Migrator;
+use user_ops_indexer_logic::indexer::progress::BackfillProgressTracker;
 use user_ops_indexer_server::{run_indexer, run_server, Settings};
 
 const SERVICE_NAME: &str = "user_ops_indexer";
@@ -24,10 +25,11 @@ async fn main() -> Result<(), anyhow::Error> {
     )
     .await?;
 
-    run_indexer(settings.clone(), db_connection).await?;
+    let progress = BackfillProgressTracker::default();
+    run_indexer(settings.clone(), db_connection, progress.clone()).await?;
 
     let db_connection =
         database::initialize_postgres::<Migrator>(&database_url, false, false).await?;
 
-    run_server(settings, db_connection).await
+    run_server(settings, db_connection, progress).await
 }
@@ -4,12 +4,13 @@ use crate::{
         user_ops_service_actix::route_user_ops_service,
         user_ops_service_server::UserOpsServiceServer,
     },
-    services::{HealthService, UserOpsService},
+    services::{route_export, ExportService, HealthService, UserOpsService},
     settings::Settings,
 };
 use blockscout_service_launcher::{launcher, launcher::LaunchSettings};
 use sea_orm::DatabaseConnection;
 use std::sync::Arc;
+use user_ops_indexer_logic::indexer::progress::BackfillProgressTracker;
 
 const SERVICE_NAME: &str = "user_ops_indexer_server";
 
@@ -17,6 +18,7 @@ const SERVICE_NAME: &str = "user_ops_indexer_server";
 struct Router {
     health: Arc<HealthService>,
     user_ops: Arc<UserOpsService>,
+    export: Arc<ExportService>,
 }
 
 impl Router {
@@ -31,17 +33,31 @@ impl launcher::HttpRouter for Router {
     fn register_routes(&self, service_config: &mut actix_web::web::ServiceConfig) {
         service_config.configure(|config| route_health(config, self.health.clone()));
         service_config.configure(|config| route_user_ops_service(config, self.user_ops.clone()));
+        service_config.configure(|config| route_export(config, self.export.clone()));
     }
 }
 
 pub async fn run(
     settings: Settings,
     database_connection: DatabaseConnection,
+    progress: BackfillProgressTracker,
 ) -> Result<(), anyhow::Error> {
     let health = Arc::new(HealthService::default());
-    let user_ops = Arc::new(UserOpsService::new(database_connection, settings.api));
+    let export = Arc::new(ExportService::new(
+        database_connection.clone(),
+        settings.api.export.clone(),
+    ));
+    let user_ops = Arc::new(UserOpsService::new(
+        database_connection,
+        settings.api,
+        progress,
+    ));
 
-    let router = Router { health, user_ops };
+    let router = Router {
+        health,
+        user_ops,
+        export,
+    };
 
     let grpc_router = router.grpc_router();
     let http_router = router;
@@ -0,0 +1,263 @@
+use crate::settings::ExportSettings;
+use actix_web::{http::header, web, HttpRequest, HttpResponse};
+use chrono::DateTime;
+use ethers::prelude::{abi::AbiEncode, H256};
+use flate2::{write::GzEncoder, Compression};
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+use sea_orm::{prelude::DateTime as NaiveDateTime, DatabaseConnection};
+use serde::{Deserialize, Serialize};
+use std::{io::Write, net::IpAddr, str::FromStr, sync::Arc};
+use user_ops_indexer_logic::{
+    repository,
+    types::export::{ExportUserOp, EXPORT_SCHEMA_VERSION},
+};
+
+const NEXT_CURSOR_HEADER: &str = "x-next-cursor";
+const SCHEMA_VERSION_HEADER: &str = "x-export-schema-version";
+/// Number of tracked clients after which the rate limiter state of the idle ones is dropped
+const MAX_RATE_LIMITED_CLIENTS: usize = 10_000;
+
+/// Export of the user operations as gzip-compressed NDJSON files.
+///
+/// Each response contains up to `limit` user operations included within the `[from, to)` range.
+/// If there are more user operations left, the `x-next-cursor` header is set, which should be
+/// passed as `cursor` to continue the export. Since the gzip members can be concatenated,
+/// all the chunks can be appended to a single file.
+///
+/// Requests are rate limited per client, identified by its IP address. The forwarding headers
+/// are only taken into account for the requests coming from the trusted proxies, so that
+/// the limit cannot be bypassed by setting them.
+pub struct ExportService {
+    db: DatabaseConnection,
+    settings: ExportSettings,
+    rate_limiter: DefaultKeyedRateLimiter<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    from: String,
+    to: String,
+    cursor: Option<String>,
+    limit: Option<u32>,
+}
+
+impl ExportService {
+    pub fn new(db: DatabaseConnection, settings: ExportSettings) -> Self {
+        let rate_limiter = RateLimiter::keyed(Quota::per_second(settings.max_requests_per_second));
+        Self {
+            db,
+            settings,
+            rate_limiter,
+        }
+    }
+
+    /// Identifies the client by the peer address, or by the forwarding headers
+    /// if the peer is a trusted proxy
+    fn client_key(&self, peer: Option<IpAddr>, forwarded_for: Option<&str>) -> String {
+        match (peer, forwarded_for) {
+            (Some(peer), Some(forwarded_for)) if self.settings.trusted_proxies.contains(&peer) => {
+                forwarded_for.to_string()
+            }
+            (Some(peer), _) => peer.to_string(),
+            (None, _) => String::new(),
+        }
+    }
+
+    async fn export_user_ops(&self, client: String, query: ExportQuery) -> HttpResponse {
+        if self.rate_limiter.len() > MAX_RATE_LIMITED_CLIENTS {
+            self.rate_limiter.retain_recent();
+        }
+        if self.rate_limiter.check_key(&client).is_err() {
+            return HttpResponse::TooManyRequests()
+                .insert_header((header::RETRY_AFTER, "1"))
+                .json(error_body("export rate limit exceeded"));
+        }
+
+        let (from, to) = match (parse_time(&query.from), parse_time(&query.to)) {
+            (Ok(from), Ok(to)) if from < to => (from, to),
+            (Ok(_), Ok(_)) => {
+                return HttpResponse::BadRequest().json(error_body("`from` must be before `to`"))
+            }
+            (Err(err), _) | (_, Err(err)) => {
+                return HttpResponse::BadRequest().json(error_body(&err))
+            }
+        };
+        let cursor = match query.cursor.as_deref().map(parse_cursor).transpose() {
+            Ok(cursor) => cursor,
+            Err(err) => return HttpResponse::BadRequest().json(error_body(&err)),
+        };
+        let max_items = self.settings.max_items.get();
+        let limit = query.limit.unwrap_or(max_items).clamp(1, max_items);
+
+        let (user_ops, next_cursor) =
+            match repository::user_op::export_user_ops(&self.db, from, to, cursor, limit as u64)
+                .await
+            {
+                Ok(res) => res,
+                Err(err) => {
+                    tracing::error!(error = ?err, "failed to query user ops for export");
+                    return HttpResponse::InternalServerError()
+                        .json(error_body("failed to query user ops"));
+                }
+            };
+
+        let body = match encode_ndjson_gz(user_ops.into_iter().map(ExportUserOp::from)) {
+            Ok(body) => body,
+            Err(err) => {
+                tracing::error!(error = ?err, "failed to encode exported user ops");
+                return HttpResponse::InternalServerError()
+                    .json(error_body("failed to encode user ops"));
+            }
+        };
+
+        let mut response = HttpResponse::Ok();
+        response
+            .content_type("application/gzip")
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"user_ops.ndjson.gz\"",
+            ))
+            .insert_header((SCHEMA_VERSION_HEADER, EXPORT_SCHEMA_VERSION.to_string()));
+        if let Some((block_number, hash)) = next_cursor {
+            response.insert_header((
+                NEXT_CURSOR_HEADER,
+                format!("{},{}", block_number, hash.encode_hex()),
+            ));
+        }
+        response.body(body)
+    }
+}
+
+pub fn route_export(config: &mut web::ServiceConfig, service: Arc<ExportService>) {
+    config
+        .app_data(web::Data::from(service))
+        .route("/api/v1/export/userOps", web::get().to(export_user_ops));
+}
+
+async fn export_user_ops(
+    service: web::Data<ExportService>,
+    request: HttpRequest,
+    query: web::Query<ExportQuery>,
+) -> HttpResponse {
+    let client = {
+        let connection_info = request.connection_info();
+        // falls back to the peer address if there are no forwarding headers
+        let forwarded_for = connection_info
+            .realip_remote_addr()
+            .filter(|addr| Some(*addr) != connection_info.peer_addr());
+        service.client_key(request.peer_addr().map(|addr| addr.ip()), forwarded_for)
+    };
+    service.export_user_ops(client, query.into_inner()).await
+}
+
+fn encode_ndjson_gz<T: Serialize>(
+    items: impl IntoIterator<Item = T>,
+) -> Result<Vec<u8>, std::io::Error> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for item in items {
+        serde_json::to_writer(&mut encoder, &item)?;
+        encoder.write_all(b"\n")?;
+    }
+    encoder.finish()
+}
+
+fn parse_time(input: &str) -> Result<NaiveDateTime, String> {
+    DateTime::parse_from_rfc3339(input)
+        .map(|t| t.naive_utc())
+        .map_err(|e| format!("Invalid value {input}: {e}"))
+}
+
+fn parse_cursor(input: &str) -> Result<(u64, H256), String> {
+    match input.split(',').collect::<Vec<&str>>().as_slice() {
+        [block_number, hash] => Ok((
+            u64::from_str(block_number).map_err(|e| format!("Invalid value {input}: {e}"))?,
+            H256::from_str(hash).map_err(|e| format!("Invalid value {input}: {e}"))?,
+        )),
+        _ => Err("invalid cursor format".to_string()),
+    }
+}
+
+fn error_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "message": message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use flate2::read::MultiGzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn parse_cursor_ok() {
+        assert_eq!(
+            parse_cursor("10,0x0000000000000000000000000000000000000000000000000000000000000101"),
+            Ok((10, H256::from_low_u64_be(0x0101)))
+        );
+        assert!(parse_cursor("10").is_err());
+        assert!(parse_cursor("abc,0x01").is_err());
+    }
+
+    #[test]
+    fn parse_time_ok() {
+        assert_eq!(
+            parse_time("2024-01-01T01:00:00+01:00"),
+            Ok(NaiveDateTime::from_str("2024-01-01T00:00:00").unwrap())
+        );
+        assert!(parse_time("2024-01-01").is_err());
+    }
+
+    #[tokio::test]
+    async fn rate_limit_is_per_client() {
+        let service = ExportService::new(DatabaseConnection::Disconnected, Default::default());
+        let query = || ExportQuery {
+            from: "invalid".to_string(),
+            to: "invalid".to_string(),
+            cursor: None,
+            limit: None,
+        };
+
+        for (client, expected) in [
+            ("10.0.0.1", StatusCode::BAD_REQUEST),
+            ("10.0.0.1", StatusCode::TOO_MANY_REQUESTS),
+            ("10.0.0.2", StatusCode::BAD_REQUEST),
+        ] {
+            let response = service.export_user_ops(client.to_string(), query()).await;
+            assert_eq!(response.status(), expected, "client {client}");
+        }
+    }
+
+    #[test]
+    fn client_key_trusts_only_configured_proxies() {
+        let proxy = IpAddr::from([10, 0, 0, 100]);
+        let settings = ExportSettings {
+            trusted_proxies: vec![proxy],
+            ..Default::default()
+        };
+        let service = ExportService::new(DatabaseConnection::Disconnected, settings);
+
+        assert_eq!(service.client_key(Some(proxy), Some("1.2.3.4")), "1.2.3.4");
+        // spoofed header of an untrusted peer is ignored
+        assert_eq!(
+            service.client_key(Some(IpAddr::from([10, 0, 0, 1])), Some("1.2.3.4")),
+            "10.0.0.1"
+        );
+        assert_eq!(service.client_key(Some(proxy), None), "10.0.0.100");
+    }
+
+    #[test]
+    fn encode_ndjson_gz_concatenates() {
+        let data = [
+            encode_ndjson_gz([serde_json::json!({"a": 1})]).unwrap(),
+            encode_ndjson_gz([serde_json::json!({"a": 2}), serde_json::json!({"a": 3})]).unwrap(),
+            encode_ndjson_gz(Vec::<serde_json::Value>::new()).unwrap(),
+        ]
+        .concat();
+
+        let mut decoded = String::new();
+        MultiGzDecoder::new(data.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "{\"a\":1}\n{\"a\":2}\n{\"a\":3}\n");
+    }
+}
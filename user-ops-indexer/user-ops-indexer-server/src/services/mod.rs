@@ -1,6 +1,8 @@
+mod export;
 mod health;
 mod user_ops;
 
+pub use export::{route_export, ExportService};
 pub use health::HealthService;
 pub use user_ops::UserOpsService;
 
@@ -4,16 +4,21 @@ use ethers::{
     prelude::H256,
     utils::to_checksum,
 };
-use sea_orm::DatabaseConnection;
+use sea_orm::{prelude::DateTime, DatabaseConnection};
 use std::str::FromStr;
 use tonic::{Request, Response, Status};
-use user_ops_indexer_logic::repository;
+use user_ops_indexer_logic::{
+    indexer::progress::BackfillProgressTracker, repository, types::bundler_stats::LeaderboardSort,
+};
 use user_ops_indexer_proto::blockscout::user_ops_indexer::v1::{
-    Account, Bundler, Factory, GetAccountRequest, GetBundlerRequest, GetFactoryRequest,
-    GetPaymasterRequest, GetUserOpRequest, ListAccountsRequest, ListAccountsResponse,
-    ListBundlersRequest, ListBundlersResponse, ListBundlesRequest, ListBundlesResponse,
-    ListFactoriesRequest, ListFactoriesResponse, ListPaymastersRequest, ListPaymastersResponse,
-    ListUserOpsRequest, ListUserOpsResponse, Pagination, Paymaster, UserOp,
+    Account, BackfillProgress, Bundler, Factory, GetAccountProfileRequest,
+    GetAccountProfileResponse, GetAccountRequest, GetBackfillProgressRequest,
+    GetBackfillProgressResponse, GetBundlerRequest, GetBundlerStatsRequest,
+    GetBundlerStatsResponse, GetFactoryRequest, GetPaymasterRequest, GetUserOpRequest,
+    ListAccountsRequest, ListAccountsResponse, ListBundlerLeaderboardRequest,
+    ListBundlerLeaderboardResponse, ListBundlersRequest, ListBundlersResponse, ListBundlesRequest,
+    ListBundlesResponse, ListFactoriesRequest, ListFactoriesResponse, ListPaymastersRequest,
+    ListPaymastersResponse, ListUserOpsRequest, ListUserOpsResponse, Pagination, Paymaster, UserOp,
 };
 
 const DEFAULT_PAGE_SIZE: u32 = 50;
@@ -22,11 +27,21 @@ pub struct UserOpsService {
     db: DatabaseConnection,
 
     settings: ApiSettings,
+
+    progress: BackfillProgressTracker,
 }
 
 impl UserOpsService {
-    pub fn new(db: DatabaseConnection, settings: ApiSettings) -> Self {
-        Self { db, settings }
+    pub fn new(
+        db: DatabaseConnection,
+        settings: ApiSettings,
+        progress: BackfillProgressTracker,
+    ) -> Self {
+        Self {
+            db,
+            settings,
+            progress,
+        }
     }
 
     fn normalize_page_size(&self, size: Option<u32>) -> u32 {
@@ -56,6 +71,55 @@ impl UserOps for UserOpsService {
         Ok(Response::new(acc.into()))
     }
 
+    async fn get_account_profile(
+        &self,
+        request: Request<GetAccountProfileRequest>,
+    ) -> Result<Response<GetAccountProfileResponse>, Status> {
+        let inner = request.into_inner();
+
+        let address = parse_filter(inner.address)?;
+        let page_token: Option<(u64, H256)> = inner.page_token.map(parse_filter_2).transpose()?;
+        let page_size = self.normalize_page_size(inner.page_size);
+
+        let profile = repository::account::find_account_profile(&self.db, address)
+            .await
+            .map_err(|err| {
+                tracing::error!(error = ?err, "failed to query account profile");
+                Status::internal("failed to query account profile")
+            })?
+            .ok_or(Status::not_found("account not found"))?;
+
+        let (ops, next_page_token) = repository::user_op::list_user_ops(
+            &self.db,
+            Some(address),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            page_token,
+            page_size as u64,
+        )
+        .await
+        .map_err(|err| {
+            tracing::error!(error = ?err, "failed to query user operations");
+            Status::internal("failed to query user operations")
+        })?;
+
+        let res = GetAccountProfileResponse {
+            profile: Some(profile.into()),
+            items: ops.into_iter().map(|op| op.into()).collect(),
+            next_page_params: next_page_token.map(|(b, o)| Pagination {
+                page_token: format!("{},{}", b, o.encode_hex()),
+                page_size,
+            }),
+        };
+
+        Ok(Response::new(res))
+    }
+
     async fn get_user_op(
         &self,
         request: Request<GetUserOpRequest>,
@@ -336,6 +400,87 @@ impl UserOps for UserOpsService {
 
         Ok(Response::new(res))
     }
+
+    async fn list_bundler_leaderboard(
+        &self,
+        request: Request<ListBundlerLeaderboardRequest>,
+    ) -> Result<Response<ListBundlerLeaderboardResponse>, Status> {
+        let inner = request.into_inner();
+
+        let sort: LeaderboardSort = inner
+            .sort
+            .map(parse_filter)
+            .transpose()?
+            .unwrap_or_default();
+        let limit = self.normalize_page_size(inner.limit);
+
+        let bundlers = repository::bundler_stats::leaderboard(
+            &self.db,
+            sort,
+            days_to_since(inner.days),
+            limit as u64,
+        )
+        .await
+        .map_err(|err| {
+            tracing::error!(error = ?err, "failed to query bundler leaderboard");
+            Status::internal("failed to query bundler leaderboard")
+        })?;
+
+        let res = ListBundlerLeaderboardResponse {
+            items: bundlers.into_iter().map(|b| b.into()).collect(),
+        };
+
+        Ok(Response::new(res))
+    }
+
+    async fn get_bundler_stats(
+        &self,
+        request: Request<GetBundlerStatsRequest>,
+    ) -> Result<Response<GetBundlerStatsResponse>, Status> {
+        let inner = request.into_inner();
+
+        let bundler = parse_filter(inner.address)?;
+
+        let stats =
+            repository::bundler_stats::daily_stats(&self.db, bundler, days_to_since(inner.days))
+                .await
+                .map_err(|err| {
+                    tracing::error!(error = ?err, "failed to query bundler stats");
+                    Status::internal("failed to query bundler stats")
+                })?;
+
+        let res = GetBundlerStatsResponse {
+            items: stats.into_iter().map(|s| s.into()).collect(),
+        };
+
+        Ok(Response::new(res))
+    }
+
+    async fn get_backfill_progress(
+        &self,
+        _request: Request<GetBackfillProgressRequest>,
+    ) -> Result<Response<GetBackfillProgressResponse>, Status> {
+        let items = self
+            .progress
+            .get()
+            .into_iter()
+            .map(|(version, p)| BackfillProgress {
+                entry_point_version: version,
+                from_block: p.from_block,
+                to_block: p.to_block,
+                scanned_blocks: p.scanned_blocks,
+                percent: p.percent(),
+                finished: p.is_finished(),
+            })
+            .collect();
+
+        Ok(Response::new(GetBackfillProgressResponse { items }))
+    }
+}
+
+#[inline]
+fn days_to_since(days: Option<u32>) -> Option<DateTime> {
+    days.map(|days| (chrono::Utc::now() - chrono::Duration::days(days as i64)).naive_utc())
 }
 
 #[inline]
@@ -4,6 +4,8 @@ use blockscout_service_launcher::{
     tracing::{JaegerSettings, TracingSettings},
 };
 use serde::Deserialize;
+use serde_with::{formats::CommaSeparator, serde_as, StringWithSeparator};
+use std::{net::IpAddr, num::NonZeroU32};
 use user_ops_indexer_logic::indexer::settings::IndexerSettings;
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -27,12 +29,51 @@ pub struct Settings {
 
 impl ConfigSettings for Settings {
     const SERVICE_NAME: &'static str = "USER_OPS_INDEXER";
+
+    fn validate(&self) -> anyhow::Result<()> {
+        self.indexer.validate()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
 pub struct ApiSettings {
     pub max_page_size: u32,
+    #[serde(default)]
+    pub export: ExportSettings,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ExportSettings {
+    #[serde(default = "default_export_max_items")]
+    pub max_items: NonZeroU32,
+    #[serde(default = "default_export_max_requests_per_second")]
+    pub max_requests_per_second: NonZeroU32,
+    /// Comma-separated addresses of the reverse proxies whose `Forwarded`/`X-Forwarded-For`
+    /// headers identify the client. Requests from other peers are limited by the peer address.
+    #[serde(default)]
+    #[serde_as(as = "StringWithSeparator::<CommaSeparator, IpAddr>")]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        Self {
+            max_items: default_export_max_items(),
+            max_requests_per_second: default_export_max_requests_per_second(),
+            trusted_proxies: vec![],
+        }
+    }
+}
+
+fn default_export_max_items() -> NonZeroU32 {
+    NonZeroU32::new(10000).unwrap()
+}
+
+fn default_export_max_requests_per_second() -> NonZeroU32 {
+    NonZeroU32::new(1).unwrap()
 }
 
 impl Settings {
@@ -47,7 +88,10 @@ impl Settings {
                 create_database: false,
                 run_migrations: false,
             },
-            api: ApiSettings { max_page_size: 100 },
+            api: ApiSettings {
+                max_page_size: 100,
+                export: Default::default(),
+            },
             indexer: Default::default(),
         }
     }
@@ -0,0 +1,415 @@
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+use tokio::sync::Notify;
+use visualizer::{
+    OutputMask, Response, VisualizeCallGraphRequest, VisualizeContractsRequest,
+    VisualizeStorageRequest,
+};
+
+/// The rendered diagrams may change with any release,
+/// so the results cached by the other versions of the service are not served.
+const KEY_VERSION: &str = env!("CARGO_PKG_VERSION");
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Hash of everything the visualization result depends on
+pub struct CacheKey(Sha256);
+
+impl CacheKey {
+    fn new(kind: &str) -> Self {
+        Self(Sha256::new()).field(KEY_VERSION).field(kind)
+    }
+
+    pub fn contracts(request: &VisualizeContractsRequest) -> Self {
+        let options = &request.options;
+        Self::new("contracts")
+            .sources(&request.sources)
+            .field(options.inheritance_only.to_string())
+            .field(options.hide_libraries.to_string())
+            .field(options.hide_interfaces.to_string())
+            .field(options.contract_names.len().to_string())
+            .fields(&options.contract_names)
+            .output_mask(&request.output_mask)
+    }
+
+    pub fn storage(request: &VisualizeStorageRequest) -> Self {
+        Self::new("storage")
+            .sources(&request.sources)
+            .field(request.file_path.to_string_lossy())
+            .field(&request.contract_name)
+            .output_mask(&request.output_mask)
+    }
+
+    pub fn call_graph(request: &VisualizeCallGraphRequest) -> Self {
+        Self::new("call_graph")
+            .sources(&request.sources)
+            .field(request.file_path.to_string_lossy())
+            .field(&request.contract_name)
+            .output_mask(&request.output_mask)
+    }
+
+    fn sources(mut self, sources: &BTreeMap<PathBuf, String>) -> Self {
+        self = self.field(sources.len().to_string());
+        for (path, content) in sources {
+            self = self.field(path.to_string_lossy()).field(content);
+        }
+        self
+    }
+
+    fn fields(self, values: &[String]) -> Self {
+        values.iter().fold(self, |key, value| key.field(value))
+    }
+
+    fn output_mask(self, output_mask: &OutputMask) -> Self {
+        let mut fields: Vec<_> = output_mask
+            .0
+            .iter()
+            .map(|field| field.to_string())
+            .collect();
+        fields.sort();
+        self.field(fields.join(","))
+    }
+
+    /// Values are prefixed with their lengths, so that different inputs could not be concatenated
+    /// into the same sequence of bytes
+    fn field(mut self, value: impl AsRef<str>) -> Self {
+        let value = value.as_ref().as_bytes();
+        self.0.update((value.len() as u64).to_be_bytes());
+        self.0.update(value);
+        self
+    }
+
+    fn finish(self) -> String {
+        hex::encode(self.0.finalize())
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    #[serde_as(as = "Option<serde_with::base64::Base64>")]
+    svg: Option<Vec<u8>>,
+    #[serde_as(as = "Option<serde_with::base64::Base64>")]
+    png: Option<Vec<u8>>,
+}
+
+/// Visualizations are deterministic, so their results are stored on disk
+/// and served without re-running the generation while not expired.
+/// Cache failures are logged and never fail the request.
+#[derive(Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    /// The least recently written entries are evicted once their total size exceeds the limit
+    max_size: u64,
+    /// Approximate total size of the entries, recalculated on each cleanup
+    size: AtomicU64,
+    size_exceeded: Notify,
+    temp_files_counter: AtomicU64,
+}
+
+impl ResponseCache {
+    pub async fn new(dir: PathBuf, ttl: Duration, max_size: u64) -> anyhow::Result<Self> {
+        tokio::fs::create_dir_all(&dir).await?;
+        Ok(Self {
+            dir,
+            ttl,
+            max_size,
+            size: AtomicU64::new(0),
+            size_exceeded: Notify::new(),
+            temp_files_counter: AtomicU64::new(0),
+        })
+    }
+
+    pub async fn get(&self, key: CacheKey) -> (String, Option<Response>) {
+        let key = key.finish();
+        let path = self.entry_path(&key);
+        let response = match self.read(&path).await {
+            Ok(response) => response,
+            Err(err) => {
+                tracing::warn!(path = %path.display(), "failed to read cached response: {err:#}");
+                None
+            }
+        };
+        (key, response)
+    }
+
+    pub async fn insert(&self, key: &str, response: &Response) {
+        match self.write(key, response).await {
+            Ok(written) => {
+                let size = self.size.fetch_add(written, Ordering::Relaxed) + written;
+                if size > self.max_size {
+                    self.size_exceeded.notify_one();
+                }
+            }
+            Err(err) => tracing::warn!(key, "failed to cache response: {err:#}"),
+        }
+    }
+
+    /// Removes expired entries every hour, and evicts the oldest entries
+    /// as soon as the size limit is exceeded
+    pub fn spawn_cleanup(self: Arc<Self>) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    _ = self.size_exceeded.notified() => {}
+                }
+                match self.cleanup().await {
+                    Ok((expired, evicted)) => {
+                        tracing::debug!(expired, evicted, "cache entries removed")
+                    }
+                    Err(err) => tracing::warn!("failed to clean up cache entries: {err:#}"),
+                }
+            }
+        });
+    }
+
+    async fn read(&self, path: &Path) -> anyhow::Result<Option<Response>> {
+        let metadata = match tokio::fs::metadata(path).await {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        if self.is_expired(metadata.modified()?) {
+            return Ok(None);
+        }
+        let content = tokio::fs::read(path).await?;
+        let cached: CachedResponse = serde_json::from_slice(&content)?;
+        Ok(Some(Response {
+            svg: cached.svg,
+            png: cached.png,
+        }))
+    }
+
+    /// Returns the number of bytes written
+    async fn write(&self, key: &str, response: &Response) -> anyhow::Result<u64> {
+        let content = serde_json::to_vec(&CachedResponse {
+            svg: response.svg.clone(),
+            png: response.png.clone(),
+        })?;
+        let written = content.len() as u64;
+        // Concurrent readers should never see partially written entries
+        let counter = self.temp_files_counter.fetch_add(1, Ordering::Relaxed);
+        let temp_path = self
+            .dir
+            .join(format!("{key}.{}.{counter}.tmp", std::process::id()));
+        tokio::fs::write(&temp_path, content).await?;
+        if let Err(err) = tokio::fs::rename(&temp_path, self.entry_path(key)).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(err.into());
+        }
+        Ok(written)
+    }
+
+    /// Removes the expired entries, and then the least recently written ones
+    /// while the total size exceeds the limit. Entries which fail to be processed
+    /// are logged and skipped. Returns the numbers of the expired and the evicted entries.
+    async fn cleanup(&self) -> anyhow::Result<(usize, usize)> {
+        let mut expired = 0;
+        let mut entries = vec![];
+        let mut dir = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            let metadata = entry.metadata().await.and_then(|metadata| {
+                Ok((metadata.is_file(), metadata.modified()?, metadata.len()))
+            });
+            let (modified, len) = match metadata {
+                Ok((true, modified, len)) => (modified, len),
+                Ok(_) => continue,
+                Err(err) => {
+                    tracing::warn!(
+                        path = %path.display(),
+                        "failed to read cache entry metadata: {err:#}"
+                    );
+                    continue;
+                }
+            };
+            if !self.is_expired(modified) {
+                entries.push((modified, len, path));
+                continue;
+            }
+            match tokio::fs::remove_file(&path).await {
+                Ok(()) => expired += 1,
+                Err(err) => {
+                    tracing::warn!(
+                        path = %path.display(),
+                        "failed to remove expired cache entry: {err:#}"
+                    );
+                }
+            }
+        }
+
+        let mut size: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        let mut evicted = 0;
+        if size > self.max_size {
+            entries.sort_by_key(|(modified, _, _)| *modified);
+            for (_, len, path) in entries {
+                if size <= self.max_size {
+                    break;
+                }
+                match tokio::fs::remove_file(&path).await {
+                    Ok(()) => {
+                        size -= len;
+                        evicted += 1;
+                    }
+                    Err(err) => {
+                        tracing::warn!(
+                            path = %path.display(),
+                            "failed to evict cache entry: {err:#}"
+                        )
+                    }
+                }
+            }
+        }
+        self.size.store(size, Ordering::Relaxed);
+
+        Ok((expired, evicted))
+    }
+
+    fn is_expired(&self, modified: SystemTime) -> bool {
+        let age = SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or_default();
+        age >= self.ttl
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use visualizer::ResponseFieldMask;
+
+    fn request(source: &str) -> VisualizeContractsRequest {
+        VisualizeContractsRequest {
+            sources: BTreeMap::from([(PathBuf::from("main.sol"), source.to_string())]),
+            options: Default::default(),
+            output_mask: OutputMask::full(),
+        }
+    }
+
+    #[test]
+    fn keys_depend_on_all_inputs() {
+        let key = |request: &VisualizeContractsRequest| CacheKey::contracts(request).finish();
+
+        assert_eq!(
+            key(&request("contract A {}")),
+            key(&request("contract A {}"))
+        );
+        assert_ne!(
+            key(&request("contract A {}")),
+            key(&request("contract B {}"))
+        );
+
+        let mut svg_only = request("contract A {}");
+        svg_only.output_mask = OutputMask([ResponseFieldMask::Svg].into_iter().collect());
+        assert_ne!(key(&request("contract A {}")), key(&svg_only));
+
+        let mut filtered = request("contract A {}");
+        filtered.options.contract_names = vec!["A".to_string()];
+        assert_ne!(key(&request("contract A {}")), key(&filtered));
+
+        // Sources are length prefixed, so moving the content between files changes the key
+        let mut split = request("contract A {}");
+        split.sources = BTreeMap::from([
+            (PathBuf::from("main.sol"), "contract A".to_string()),
+            (PathBuf::from("other.sol"), " {}".to_string()),
+        ]);
+        assert_ne!(key(&request("contract A {}")), key(&split));
+    }
+
+    #[tokio::test]
+    async fn stores_responses_until_expired() {
+        let dir = tempfile::tempdir().unwrap();
+        let response = Response {
+            svg: Some(b"<svg></svg>".to_vec()),
+            png: None,
+        };
+
+        let cache = ResponseCache::new(
+            dir.path().join("cache"),
+            Duration::from_secs(3600),
+            u64::MAX,
+        )
+        .await
+        .unwrap();
+        let (key, cached) = cache
+            .get(CacheKey::contracts(&request("contract A {}")))
+            .await;
+        assert_eq!(None, cached);
+        cache.insert(&key, &response).await;
+        let (_, cached) = cache
+            .get(CacheKey::contracts(&request("contract A {}")))
+            .await;
+        assert_eq!(Some(response.clone()), cached);
+
+        let expired_cache = ResponseCache::new(dir.path().join("cache"), Duration::ZERO, u64::MAX)
+            .await
+            .unwrap();
+        let (_, cached) = expired_cache
+            .get(CacheKey::contracts(&request("contract A {}")))
+            .await;
+        assert_eq!(None, cached);
+        assert_eq!((1, 0), expired_cache.cleanup().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn evicts_oldest_entries_above_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let response = Response {
+            svg: Some(b"<svg></svg>".to_vec()),
+            png: None,
+        };
+        let keys = ["contract A {}", "contract B {}", "contract C {}"]
+            .map(|source| CacheKey::contracts(&request(source)).finish());
+
+        let unlimited = ResponseCache::new(
+            dir.path().to_path_buf(),
+            Duration::from_secs(3600),
+            u64::MAX,
+        )
+        .await
+        .unwrap();
+        let entry_size = unlimited.write(&keys[0], &response).await.unwrap();
+        let now = SystemTime::now();
+        for (i, key) in keys.iter().enumerate() {
+            unlimited.write(key, &response).await.unwrap();
+            // The first entry is the oldest one
+            let modified = now - Duration::from_secs(60 * (keys.len() - i) as u64);
+            std::fs::File::options()
+                .write(true)
+                .open(unlimited.entry_path(key))
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        }
+
+        let cache = ResponseCache::new(
+            dir.path().to_path_buf(),
+            Duration::from_secs(3600),
+            2 * entry_size,
+        )
+        .await
+        .unwrap();
+        assert_eq!((0, 1), cache.cleanup().await.unwrap());
+        assert!(!cache.entry_path(&keys[0]).exists());
+        assert!(cache.entry_path(&keys[1]).exists());
+        assert!(cache.entry_path(&keys[2]).exists());
+        assert_eq!(2 * entry_size, cache.size.load(Ordering::Relaxed));
+    }
+}
@@ -1,9 +1,11 @@
+mod cache;
 mod proto;
 mod server;
 mod services;
 mod settings;
 mod types;
 
+pub use cache::ResponseCache;
 pub use server::run;
 pub use services::SolidityVisualizerService;
 pub use settings::Settings;
@@ -1,4 +1,5 @@
 use crate::{
+    cache::ResponseCache,
     proto::{
         health_actix::route_health, health_server::HealthServer,
         solidity_visualizer_actix::route_solidity_visualizer,
@@ -37,7 +38,24 @@ impl launcher::HttpRouter for Router {
 pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
     tracing::init_logs(SERVICE_NAME, &settings.tracing, &settings.jaeger)?;
 
-    let visualizer = Arc::new(SolidityVisualizerService::default());
+    let cache = if settings.cache.enabled {
+        let cache = Arc::new(
+            ResponseCache::new(
+                settings.cache.dir,
+                settings.cache.ttl,
+                settings.cache.max_size,
+            )
+            .await?,
+        );
+        cache.clone().spawn_cleanup();
+        Some(cache)
+    } else {
+        None
+    };
+    let visualizer = Arc::new(SolidityVisualizerService::new(
+        settings.limits.into(),
+        cache,
+    ));
     let health = Arc::new(HealthService::default());
 
     let router = Router { visualizer, health };
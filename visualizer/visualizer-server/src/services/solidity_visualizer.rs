@@ -1,16 +1,47 @@
 use crate::{
+    cache::{CacheKey, ResponseCache},
     proto::{
-        solidity_visualizer_server::SolidityVisualizer, VisualizeContractsRequest,
-        VisualizeResponse, VisualizeStorageRequest,
+        solidity_visualizer_server::SolidityVisualizer, VisualizeCallGraphRequest,
+        VisualizeContractsRequest, VisualizeResponse, VisualizeStorageRequest,
     },
     types::{
-        VisualizeContractsRequestWrapper, VisualizeResponseWrapper, VisualizeStorageRequestWrapper,
+        VisualizeCallGraphRequestWrapper, VisualizeContractsRequestWrapper,
+        VisualizeResponseWrapper, VisualizeStorageRequestWrapper,
     },
 };
 use async_trait::async_trait;
+use std::{future::Future, sync::Arc};
 
-#[derive(Default)]
-pub struct SolidityVisualizerService {}
+pub struct SolidityVisualizerService {
+    limits: visualizer::Limits,
+    cache: Option<Arc<ResponseCache>>,
+}
+
+impl SolidityVisualizerService {
+    pub fn new(limits: visualizer::Limits, cache: Option<Arc<ResponseCache>>) -> Self {
+        Self { limits, cache }
+    }
+
+    /// Returns the cached response if any. Otherwise, runs the visualization
+    /// and caches its successful result. The key is `None` if caching is disabled.
+    async fn cached<E>(
+        &self,
+        key: Option<CacheKey>,
+        visualize: impl Future<Output = Result<visualizer::Response, E>>,
+    ) -> Result<visualizer::Response, E> {
+        let (Some(cache), Some(key)) = (&self.cache, key) else {
+            return visualize.await;
+        };
+        let (key, cached) = cache.get(key).await;
+        if let Some(response) = cached {
+            tracing::debug!(key, "response found in cache");
+            return Ok(response);
+        }
+        let response = visualize.await?;
+        cache.insert(&key, &response).await;
+        Ok(response)
+    }
+}
 
 #[async_trait]
 impl SolidityVisualizer for SolidityVisualizerService {
@@ -19,8 +50,12 @@ impl SolidityVisualizer for SolidityVisualizerService {
         &self,
         request: tonic::Request<VisualizeContractsRequest>,
     ) -> Result<tonic::Response<VisualizeResponse>, tonic::Status> {
-        let request: VisualizeContractsRequestWrapper = request.into_inner().into();
-        let result = visualizer::visualize_contracts(request.try_into()?).await;
+        let request: visualizer::VisualizeContractsRequest =
+            VisualizeContractsRequestWrapper::from(request.into_inner()).try_into()?;
+        let key = self.cache.as_ref().map(|_| CacheKey::contracts(&request));
+        let result = self
+            .cached(key, visualizer::visualize_contracts(request, &self.limits))
+            .await;
         result
             .map(|response| tonic::Response::new(VisualizeResponseWrapper::from(response).into()))
             .map_err(|error| match error {
@@ -30,6 +65,9 @@ impl SolidityVisualizer for SolidityVisualizerService {
                 visualizer::VisualizeContractsError::Execution(e) => {
                     tonic::Status::invalid_argument(e)
                 }
+                visualizer::VisualizeContractsError::ResourceLimit(e) => {
+                    tonic::Status::resource_exhausted(e)
+                }
             })
     }
 
@@ -38,8 +76,12 @@ impl SolidityVisualizer for SolidityVisualizerService {
         &self,
         request: tonic::Request<VisualizeStorageRequest>,
     ) -> Result<tonic::Response<VisualizeResponse>, tonic::Status> {
-        let request: VisualizeStorageRequestWrapper = request.into_inner().into();
-        let result = visualizer::visualize_storage(request.try_into()?).await;
+        let request: visualizer::VisualizeStorageRequest =
+            VisualizeStorageRequestWrapper::from(request.into_inner()).try_into()?;
+        let key = self.cache.as_ref().map(|_| CacheKey::storage(&request));
+        let result = self
+            .cached(key, visualizer::visualize_storage(request, &self.limits))
+            .await;
         result
             .map(|response| tonic::Response::new(VisualizeResponseWrapper::from(response).into()))
             .map_err(|error| match error {
@@ -52,6 +94,38 @@ impl SolidityVisualizer for SolidityVisualizerService {
                 visualizer::VisualizeStorageError::Execution(e) => {
                     tonic::Status::invalid_argument(e)
                 }
+                visualizer::VisualizeStorageError::ResourceLimit(e) => {
+                    tonic::Status::resource_exhausted(e)
+                }
+            })
+    }
+
+    #[tracing::instrument(skip(self, request), level = "info")]
+    async fn visualize_call_graph(
+        &self,
+        request: tonic::Request<VisualizeCallGraphRequest>,
+    ) -> Result<tonic::Response<VisualizeResponse>, tonic::Status> {
+        let request: visualizer::VisualizeCallGraphRequest =
+            VisualizeCallGraphRequestWrapper::from(request.into_inner()).try_into()?;
+        let key = self.cache.as_ref().map(|_| CacheKey::call_graph(&request));
+        let result = self
+            .cached(key, visualizer::visualize_call_graph(request, &self.limits))
+            .await;
+        result
+            .map(|response| tonic::Response::new(VisualizeResponseWrapper::from(response).into()))
+            .map_err(|error| match error {
+                visualizer::VisualizeCallGraphError::Internal(e) => {
+                    tonic::Status::internal(e.to_string())
+                }
+                visualizer::VisualizeCallGraphError::InvalidFileName => {
+                    tonic::Status::invalid_argument("Invalid file name")
+                }
+                visualizer::VisualizeCallGraphError::Execution(e) => {
+                    tonic::Status::invalid_argument(e)
+                }
+                visualizer::VisualizeCallGraphError::ResourceLimit(e) => {
+                    tonic::Status::resource_exhausted(e)
+                }
             })
     }
 }
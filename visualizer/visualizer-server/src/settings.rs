@@ -4,6 +4,8 @@ use blockscout_service_launcher::{
 };
 use config::{Config, File};
 use serde::{de, Deserialize};
+use serde_with::serde_as;
+use std::{path::PathBuf, time};
 
 /// Wrapper under [`serde::de::IgnoredAny`] which implements
 /// [`PartialEq`] and [`Eq`] for fields to be ignored.
@@ -26,6 +28,8 @@ pub struct Settings {
     pub metrics: MetricsSettings,
     pub jaeger: JaegerSettings,
     pub tracing: TracingSettings,
+    pub limits: LimitsSettings,
+    pub cache: CacheSettings,
 
     // Is required as we deny unknown fields, but allow users provide
     // path to config through PREFIX__CONFIG env variable. If removed,
@@ -34,6 +38,64 @@ pub struct Settings {
     config_path: IgnoredAny,
 }
 
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct LimitsSettings {
+    /// Maximum total size of the sources in bytes
+    pub max_sources_size: usize,
+    /// Maximum number of contracts, structs and enums shown on the class diagram
+    pub max_classes: usize,
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub timeout: time::Duration,
+}
+
+impl Default for LimitsSettings {
+    fn default() -> Self {
+        let limits = visualizer::Limits::default();
+        Self {
+            max_sources_size: limits.max_sources_size,
+            max_classes: limits.max_classes,
+            timeout: limits.timeout,
+        }
+    }
+}
+
+impl From<LimitsSettings> for visualizer::Limits {
+    fn from(settings: LimitsSettings) -> Self {
+        Self {
+            max_sources_size: settings.max_sources_size,
+            max_classes: settings.max_classes,
+            timeout: settings.timeout,
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheSettings {
+    pub enabled: bool,
+    /// Directory the visualization results are stored in
+    pub dir: PathBuf,
+    /// Time the results are served from the cache for
+    #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+    pub ttl: time::Duration,
+    /// Total size of the results in bytes, above which the oldest ones are evicted
+    pub max_size: u64,
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: std::env::temp_dir().join("visualizer-cache"),
+            ttl: time::Duration::from_secs(7 * 24 * 60 * 60),
+            max_size: 1024 * 1024 * 1024,
+        }
+    }
+}
+
 impl Settings {
     pub fn new() -> anyhow::Result<Self> {
         let config_path = std::env::var("VISUALIZER__CONFIG");
@@ -54,6 +116,9 @@ impl Settings {
     }
 
     fn validate(&self) -> anyhow::Result<()> {
+        if self.cache.enabled && self.cache.max_size == 0 {
+            anyhow::bail!("cache max size must be non-zero");
+        }
         Ok(())
     }
 }
@@ -1,8 +1,10 @@
 mod util;
+mod visualize_call_graph;
 mod visualize_contracts;
 mod visualize_response;
 mod visualize_storage;
 
+pub use visualize_call_graph::VisualizeCallGraphRequestWrapper;
 pub use visualize_contracts::VisualizeContractsRequestWrapper;
 pub use visualize_response::VisualizeResponseWrapper;
 pub use visualize_storage::VisualizeStorageRequestWrapper;
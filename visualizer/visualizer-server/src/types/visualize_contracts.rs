@@ -12,6 +12,12 @@ impl TryFrom<VisualizeContractsRequestWrapper> for visualizer::VisualizeContract
         let request = request.0;
         Ok(Self {
             sources: fix_sources_paths(sources(request.sources)),
+            options: visualizer::ContractsDiagramOptions {
+                inheritance_only: request.inheritance_only,
+                hide_libraries: request.hide_libraries,
+                hide_interfaces: request.hide_interfaces,
+                contract_names: request.contract_names,
+            },
             output_mask: output_mask(request.output_mask)
                 .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?,
         })
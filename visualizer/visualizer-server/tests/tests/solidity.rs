@@ -1,4 +1,3 @@
-use assert_str::assert_str_eq;
 use bytes::Bytes;
 use serde::Deserialize;
 use serde_json::json;
@@ -7,7 +6,6 @@ use std::{collections::BTreeMap, fs, path::PathBuf, str::from_utf8};
 use walkdir::WalkDir;
 
 const CONTRACTS_DIR: &str = "tests/contracts";
-const SAMPLES_DIR: &str = "tests/samples";
 
 fn get_dir_files(project_path: &PathBuf) -> BTreeMap<PathBuf, String> {
     let mut sources = BTreeMap::new();
@@ -54,8 +52,23 @@ async fn test_setup(request: serde_json::Value, route: &str) -> reqwest::Respons
         .expect("failed to send request")
 }
 
-async fn visualize_contract_success(request: serde_json::Value, expected_svg: String) {
-    let response = test_setup(request, "/api/v1/solidity:visualize-contracts").await;
+/// Diagrams are checked by their content, as the exact layout is an implementation detail.
+/// Expected texts should be xml escaped (e.g., `&lt;&lt;Library&gt;&gt;`).
+fn assert_svg_contains(svg: &str, expected: &[&str]) {
+    assert!(
+        svg.starts_with("<?xml") && svg.trim_end().ends_with("</svg>"),
+        "invalid svg: {svg}"
+    );
+    for text in expected {
+        assert!(
+            svg.contains(&format!(">{text}<")),
+            "svg does not contain \"{text}\": {svg}"
+        );
+    }
+}
+
+async fn visualize_success(request: serde_json::Value, route: &str, expected: &[&str]) -> String {
+    let response = test_setup(request, route).await;
     assert!(
         response.status().is_success(),
         "response: {:?}",
@@ -68,43 +81,49 @@ async fn visualize_contract_success(request: serde_json::Value, expected_svg: St
 
     let result_svg = from_utf8(&result.svg).expect("failed to convert result svg to string");
 
-    assert_str_eq!(result_svg, expected_svg);
+    assert_svg_contains(result_svg, expected);
+    result_svg.to_string()
+}
+
+async fn visualize_contract_success(request: serde_json::Value, expected: &[&str]) {
+    visualize_success(request, "/api/v1/solidity:visualize-contracts", expected).await;
 }
 
-async fn visualize_contracts_success_from_dir(project_name: &str, sample_name: &str) {
+async fn visualize_contracts_success_from_dir(project_name: &str, expected: &[&str]) {
     let project_path = PathBuf::from(format!("{CONTRACTS_DIR}/{project_name}",));
 
     let request = json!({
         "sources": get_dir_files(&project_path),
     });
-    let svg_path = format!("{SAMPLES_DIR}/uml/{sample_name}.svg");
-    let expected_svg = fs::read_to_string(&svg_path)
-        .unwrap_or_else(|_| panic!("Error while reading {sample_name}.svg",));
-    visualize_contract_success(request, expected_svg).await;
+    visualize_contract_success(request, expected).await;
 }
 
-async fn visualize_storage_success(request: serde_json::Value, expected_svg: String) {
-    let response = test_setup(request, "/api/v1/solidity:visualize-storage").await;
+async fn visualize_storage_success(request: serde_json::Value, expected: &[&str]) {
+    visualize_success(request, "/api/v1/solidity:visualize-storage", expected).await;
+}
 
-    assert!(
-        response.status().is_success(),
-        "response: {:?}",
-        response.text().await
-    );
-    let result: Response = response
-        .json()
-        .await
-        .expect("could not deserialize response");
-    let result_svg = from_utf8(&result.svg).expect("failed to convert result svg to string");
+async fn visualize_call_graph_success_from_dir(
+    project_name: &str,
+    main_contract: &str,
+    main_contract_filename: &str,
+    expected: &[&str],
+) {
+    let project_path = PathBuf::from(format!("{CONTRACTS_DIR}/{project_name}"));
 
-    assert_str_eq!(result_svg, expected_svg);
+    let request = json!({
+        "sources": get_dir_files(&project_path),
+        "contract_name": main_contract,
+        "file_name": main_contract_filename,
+    });
+
+    visualize_success(request, "/api/v1/solidity:visualize-call-graph", expected).await;
 }
 
 async fn visualize_storage_success_from_dir(
     project_name: &str,
     main_contract: &str,
     main_contract_filename: &str,
-    sample_name: &str,
+    expected: &[&str],
 ) {
     let project_path = PathBuf::from(format!("{CONTRACTS_DIR}/{project_name}"));
 
@@ -114,19 +133,32 @@ async fn visualize_storage_success_from_dir(
         "file_name": main_contract_filename,
     });
 
-    let svg_path = format!("{SAMPLES_DIR}/storage/{sample_name}.svg");
-    let expected_svg = fs::read_to_string(&svg_path)
-        .unwrap_or_else(|_| panic!("Error while reading {sample_name}.svg",));
-
-    visualize_storage_success(request, expected_svg).await;
+    visualize_storage_success(request, expected).await;
 }
 
+const SIMPLE_CONTRACT_UML: &[&str] = &[
+    "&lt;&lt;Library&gt;&gt;",
+    "BadSafeMath",
+    "add(a: uint256, b: uint256): uint256",
+    "SimpleStorage",
+    "  storedData: uint256",
+    "  increment(x: uint256)",
+    "  get(): uint256",
+];
+
+const SIMPLE_CONTRACT_STORAGE: &[&str] = &[
+    "SimpleStorage &lt;&lt;Contract&gt;&gt;",
+    "slot",
+    "0",
+    "uint256: storedData (32)",
+];
+
 mod success_simple_tests {
     use super::*;
 
     #[actix_web::test]
     async fn uml_simple_contract() {
-        visualize_contracts_success_from_dir("SimpleContract.sol", "simple_contract").await;
+        visualize_contracts_success_from_dir("SimpleContract.sol", SIMPLE_CONTRACT_UML).await;
     }
 
     #[actix_web::test]
@@ -135,7 +167,23 @@ mod success_simple_tests {
             "SimpleContract.sol",
             "SimpleStorage",
             "SimpleContract.sol",
-            "simple_contract",
+            SIMPLE_CONTRACT_STORAGE,
+        )
+        .await;
+    }
+
+    #[actix_web::test]
+    async fn call_graph_simple_contract() {
+        visualize_call_graph_success_from_dir(
+            "SimpleContract.sol",
+            "SimpleStorage",
+            "SimpleContract.sol",
+            &[
+                "&lt;&lt;public&gt;&gt;",
+                "increment(x: uint256)",
+                "set(x: uint256)",
+                "get(): uint256",
+            ],
         )
         .await;
     }
@@ -143,11 +191,8 @@ mod success_simple_tests {
     #[actix_web::test]
     async fn storage_simple_contract_alt_path() {
         let contract_path = format!("{CONTRACTS_DIR}/SimpleContract.sol",);
-        let storage_path = format!("{SAMPLES_DIR}/storage/simple_contract.svg",);
         let contract =
             fs::read_to_string(&contract_path).expect("Error while reading SimpleContract.sol");
-        let storage =
-            fs::read_to_string(&storage_path).expect("Error while reading simple_contract.svg");
 
         let request = json!({
             "sources": {"c/d/SimpleContract.sol": contract},
@@ -155,7 +200,7 @@ mod success_simple_tests {
             "file_name": "c/d/SimpleContract.sol",
         });
 
-        visualize_storage_success(request, storage).await;
+        visualize_storage_success(request, SIMPLE_CONTRACT_STORAGE).await;
     }
 }
 
@@ -166,7 +211,17 @@ mod success_advanced_tests {
     async fn uml_large_project() {
         visualize_contracts_success_from_dir(
             "large_project_many_methods",
-            "large_project_many_methods",
+            &[
+                "&lt;&lt;Interface&gt;&gt;",
+                "IERC20",
+                "ERC20",
+                "MyToken",
+                "TokenSwap",
+                "  totalSupply: uint",
+                "  token1: IERC20",
+                "  constructor(name: string, symbol: string)",
+                "  uselessMethod1(recipient: address, amount: uint): bool",
+            ],
         )
         .await;
     }
@@ -177,19 +232,83 @@ mod success_advanced_tests {
             "large_project_many_methods",
             "MyToken",
             "Token.sol",
+            &[
+                "MyToken &lt;&lt;Contract&gt;&gt;",
+                "uint: ERC20.totalSupply (32)",
+                "uint8: ERC20.decimals (1)",
+                "uint: useless_variable1 (32)",
+                "uint32: useless_variable3 (4)",
+                "bool: useless_variable6 (1)",
+            ],
+        )
+        .await;
+    }
+
+    #[actix_web::test]
+    async fn call_graph_large_project() {
+        visualize_call_graph_success_from_dir(
             "large_project_many_methods",
+            "MyToken",
+            "Token.sol",
+            &[
+                "&lt;&lt;constructor&gt;&gt;",
+                "constructor(name: string, symbol: string)",
+                "&lt;&lt;internal&gt;&gt;",
+                "ERC20._mint(amount: uint)",
+                "&lt;&lt;external&gt;&gt; &lt;&lt;payable&gt;&gt;",
+                "uselessMethod3(recipient: address, amount: uint)",
+                "ERC20.transfer(recipient: address, amount: uint): bool",
+            ],
         )
         .await;
     }
 
+    #[actix_web::test]
+    async fn uml_large_project_filtered() {
+        let project_path = PathBuf::from(format!("{CONTRACTS_DIR}/large_project_many_methods"));
+        let request = json!({
+            "sources": get_dir_files(&project_path),
+            "inheritance_only": true,
+            "hide_interfaces": true,
+            "contract_names": ["MyToken"],
+        });
+        let svg = visualize_success(
+            request,
+            "/api/v1/solidity:visualize-contracts",
+            &["ERC20", "MyToken"],
+        )
+        .await;
+        for hidden in ["IERC20", "TokenSwap"] {
+            assert!(
+                !svg.contains(&format!(">{hidden}<")),
+                "svg contains filtered out \"{hidden}\": {svg}"
+            );
+        }
+    }
+
     #[actix_web::test]
     async fn uml_many_libraries() {
-        visualize_contracts_success_from_dir("many_libraries", "many_libraries").await;
+        visualize_contracts_success_from_dir(
+            "many_libraries",
+            &[
+                "MyToken",
+                "SafeMath1",
+                "SafeMath2",
+                "SafeMath3",
+                "SafeMath4",
+                "SafeMath5",
+            ],
+        )
+        .await;
     }
 
     #[actix_web::test]
     async fn uml_same_contract_names() {
-        visualize_contracts_success_from_dir("same_contract_names", "same_contract_names").await;
+        visualize_contracts_success_from_dir(
+            "same_contract_names",
+            &["A", "  C1: uint32", "  C2: uint32", "  C3: uint32"],
+        )
+        .await;
     }
 
     #[actix_web::test]
@@ -198,7 +317,23 @@ mod success_advanced_tests {
             "same_contract_names",
             "A",
             "Main.sol",
-            "same_contract_names",
+            &[
+                "unallocated (20)",
+                "uint32: A.C2 (4)",
+                "uint32: A.C3 (4)",
+                "uint32: C1 (4)",
+            ],
+        )
+        .await;
+    }
+
+    #[actix_web::test]
+    async fn storage_same_filenames() {
+        visualize_storage_success_from_dir(
+            "same_filenames",
+            "A",
+            "main_dir/SameName.sol",
+            &["uint32: A.C (4)", "uint32: C1 (4)"],
         )
         .await;
     }
@@ -209,7 +344,7 @@ mod success_advanced_tests {
             "same_filenames_different_contracts",
             "A",
             "SameName.sol",
-            "same_filenames_different_contracts",
+            &["uint32: A1.C (4)", "uint32: C1 (4)"],
         )
         .await;
     }
@@ -219,15 +354,12 @@ mod success_advanced_tests {
         let contract_path = format!("{CONTRACTS_DIR}/SimpleContract.sol",);
         let contract =
             fs::read_to_string(&contract_path).expect("Error while reading SimpleContract.sol");
-        let svg_path = format!("{SAMPLES_DIR}/uml/simple_contract.svg",);
-        let expected_svg = fs::read_to_string(&svg_path)
-            .unwrap_or_else(|_| panic!("Error while reading simple_contract.svg",));
         let request = json!({
             "sources": {
                 "/usr/SimpleContract.sol": contract,
             }
         });
-        visualize_contract_success(request, expected_svg).await;
+        visualize_contract_success(request, SIMPLE_CONTRACT_UML).await;
     }
 
     #[actix_web::test]
@@ -235,21 +367,19 @@ mod success_advanced_tests {
         let contract_path = format!("{CONTRACTS_DIR}/SimpleContract.sol",);
         let contract =
             fs::read_to_string(&contract_path).expect("Error while reading SimpleContract.sol");
-        let svg_path = format!("{SAMPLES_DIR}/uml/simple_contract.svg",);
-        let expected_svg = fs::read_to_string(&svg_path)
-            .unwrap_or_else(|_| panic!("Error while reading simple_contract.svg",));
         let request = json!({
             "sources": {
                 ".sol": contract,
             }
         });
-        visualize_contract_success(request, expected_svg).await;
+        visualize_contract_success(request, SIMPLE_CONTRACT_UML).await;
     }
 
     // filename that starts with @
     #[actix_web::test]
     async fn uml_starting_at_sign() {
-        visualize_contracts_success_from_dir("openzeppelin_lib", "openzeppelin_lib").await;
+        visualize_contracts_success_from_dir("openzeppelin_lib", &["A", "B", "  number(): uint16"])
+            .await;
     }
 }
 
@@ -258,83 +388,83 @@ mod success_known_issues {
 
     #[actix_web::test]
     async fn uml_contract_with_compile_error() {
-        // sol2uml ignores not syntax compile errors
-        visualize_contracts_success_from_dir("ContractCompileError.sol", "contract_compile_error")
-            .await;
+        // only syntax errors are reported, other compile errors are ignored
+        visualize_contracts_success_from_dir(
+            "ContractCompileError.sol",
+            &["Main", "  error: uint", "  add(x: uint, y: address): uint"],
+        )
+        .await;
     }
 
     #[actix_web::test]
     async fn storage_contract_with_compile_error() {
-        // sol2uml ignores not syntax compile errors also in storage mod
+        // compile errors are ignored also in storage mode
         visualize_storage_success_from_dir(
             "ContractCompileError.sol",
             "Main",
             "ContractCompileError.sol",
-            "contract_compile_error",
+            &["uint: C (32)", "address: a (20)", "uint: error (32)"],
         )
         .await;
     }
 
     #[actix_web::test]
     async fn uml_import_missing_contract() {
-        // sol2uml just doesn`t show missing contract on uml diagram
-        visualize_contracts_success_from_dir(
-            "ImportMissingContract.sol",
-            "import_missing_contract",
-        )
-        .await;
+        // missing contract is just not shown on uml diagram
+        visualize_contracts_success_from_dir("ImportMissingContract.sol", &["Main"]).await;
     }
 
     #[actix_web::test]
     async fn storage_import_missing_contract() {
-        // sol2uml ignores missing contract if it doesn`t affect storage
+        // missing contract is ignored if it doesn`t affect storage
         visualize_storage_success_from_dir(
             "ImportMissingContract.sol",
             "Main",
             "ImportMissingContract.sol",
-            "import_missing_contract",
+            &["uint: C (32)"],
         )
         .await;
     }
 
     #[actix_web::test]
     async fn uml_import_missing_inherited_contract() {
-        // sol2uml just doesn`t show missing contract on uml, even if some of
+        // missing contract is not shown on uml, even if some of
         // existing contracts is inherited from it
-        visualize_contracts_success_from_dir(
-            "ImportMissingInheritedContract.sol",
-            "import_missing_inherited_contract",
-        )
-        .await;
+        visualize_contracts_success_from_dir("ImportMissingInheritedContract.sol", &["Main"]).await;
     }
 
     #[actix_web::test]
     async fn uml_import_missing_library() {
-        // sol2uml just doesn`t show missing library on uml
-        visualize_contracts_success_from_dir("ImportMissingLibrary.sol", "import_missing_library")
-            .await;
+        // missing library is not shown on uml
+        visualize_contracts_success_from_dir(
+            "ImportMissingLibrary.sol",
+            &["Main", "  add(x: uint, y: address): uint"],
+        )
+        .await;
     }
 
     #[actix_web::test]
     async fn uml_long_names() {
-        visualize_contracts_success_from_dir("LongNames.sol", "long_names").await;
+        visualize_contracts_success_from_dir(
+            "LongNames.sol",
+            &[
+                "LibraryWithAVeryLongNameWhichCanVerySadlyRuinSomeSvgGenerationOrBeCutOutOrMayBeEvenCrashButItDoesnt",
+                "ContractWithAVeryLongNameWhichCanVerySadlyRuinSomeSvgGenerationOrBeCutOutOrMayBeEvenCrashButItDoesnt",
+                "Main",
+            ],
+        )
+        .await;
     }
 
     #[actix_web::test]
     async fn storage_long_names() {
-        visualize_storage_success_from_dir("LongNames.sol", "Main", "LongNames.sol", "long_names")
-            .await;
-    }
-
-    #[actix_web::test]
-    async fn storage_same_filenames() {
-        // when contracts with the same name have the same filename, then
-        // storage will be returned for the contract with the smallest filename in sort order
         visualize_storage_success_from_dir(
-            "same_filenames",
-            "A",
-            "main_dir/SameName.sol",
-            "same_filenames",
+            "LongNames.sol",
+            "Main",
+            "LongNames.sol",
+            &[
+                "uint: ContractWithAVeryLongNameWhichCanVerySadlyRuinSomeSvgGenerationOrBeCutOutOrMayBeEvenCrashButItDoesnt.v1 (32)",
+            ],
         )
         .await;
     }
@@ -370,6 +500,33 @@ mod failure_tests {
         );
     }
 
+    #[actix_web::test]
+    async fn call_graph_wrong_main_contract() {
+        let contract_path = PathBuf::from(format!("{CONTRACTS_DIR}/SimpleContract.sol",));
+
+        let request = json!({
+            "sources": get_dir_files(&contract_path),
+            "contract_name": "dsd",
+            "file_name": "SimpleContract.sol",
+        });
+        let response = test_setup(request, "/api/v1/solidity:visualize-call-graph").await;
+
+        assert!(
+            response.status().is_client_error(),
+            "Invalid status code (failed expected): {}",
+            response.status()
+        );
+
+        let message = response
+            .text()
+            .await
+            .expect("could not deserialize response text");
+        assert!(
+            message.contains("Failed to find contract with name"),
+            "Invalid response message: {message}",
+        );
+    }
+
     #[actix_web::test]
     async fn uml_library_with_syntax_error() {
         let project_path = PathBuf::from(format!("{CONTRACTS_DIR}/library_syntax_error",));
@@ -394,7 +551,7 @@ mod failure_tests {
 
     #[actix_web::test]
     async fn storage_import_missing_inherited_contract() {
-        // sol2uml returns error if main contract is inherited from missing contract
+        // returns error if main contract is inherited from missing contract
         // cause it affects main contract storage
         let project_path = PathBuf::from(format!(
             "{CONTRACTS_DIR}/ImportMissingInheritedContract.sol",
@@ -1,11 +1,17 @@
+mod limits;
 mod metrics;
 mod response;
 mod solidity;
 
+pub use limits::Limits;
 pub use response::{OutputMask, Response, ResponseFieldMask};
 pub use solidity::{
+    visualize_call_graph::{
+        visualize_call_graph, VisualizeCallGraphError, VisualizeCallGraphRequest,
+    },
     visualize_contracts::{
-        visualize_contracts, VisualizeContractsError, VisualizeContractsRequest,
+        visualize_contracts, ContractsDiagramOptions, VisualizeContractsError,
+        VisualizeContractsRequest,
     },
     visualize_storage::{visualize_storage, VisualizeStorageError, VisualizeStorageRequest},
 };
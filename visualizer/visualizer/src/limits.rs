@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+/// Restrictions applied to every visualization request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum total size of the sources in bytes
+    pub max_sources_size: usize,
+    /// Maximum number of classes shown on the diagram
+    pub max_classes: usize,
+    /// Maximum time spent on parsing and rendering
+    pub timeout: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_sources_size: 10 * 1024 * 1024,
+            max_classes: 500,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
@@ -2,9 +2,9 @@ use lazy_static::lazy_static;
 use prometheus::{register_histogram, Histogram};
 
 lazy_static! {
-    pub static ref SOL2UML_EXECUTION_TIME: Histogram = register_histogram!(
-        "visualizer_sol2uml_execution_time",
-        "time of running sol2uml binary in seconds",
+    pub static ref VISUALIZATION_TIME: Histogram = register_histogram!(
+        "visualizer_visualization_time",
+        "time of parsing the sources and rendering the diagram in seconds",
     )
     .unwrap();
 }
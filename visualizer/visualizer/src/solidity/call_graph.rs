@@ -0,0 +1,341 @@
+use super::{
+    calls::Call,
+    internal::Error,
+    layout::{self, ArrowHead, Line, Node},
+    project::{ClassKind, OperatorKind, Project, Visibility},
+    storage,
+    svg::{FontStyle, LineStyle, Svg},
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Function identified by the class and operator indices
+type FunctionId = (usize, usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Edge {
+    /// Modifier or base constructor invocation
+    Modifier,
+    /// External call of the contract itself (`this.f()`)
+    External,
+    Internal,
+}
+
+/// Renders the graph of calls starting from the entry points of the contract
+/// (public and external functions, constructor, fallback and receive functions).
+/// Calls are resolved statically, so that the calls through the external contracts are not shown.
+pub fn render(project: &Project, contract: usize) -> Result<String, Error> {
+    let graph = CallGraph::build(project, contract)?;
+
+    let mut nodes: Vec<Node> = graph
+        .functions
+        .iter()
+        .map(|&function| Node::new(sections(project, contract, function)))
+        .collect();
+    for (node, &depth) in nodes.iter_mut().zip(&graph.depths) {
+        node.rank = depth;
+    }
+    let edge_list: Vec<_> = graph.edges.keys().copied().collect();
+    project.deadline.check()?;
+    let (width, height) = layout::assign_positions(&mut nodes, &edge_list);
+
+    let mut svg = Svg::new();
+    for (index, (&(from, to), &edge)) in graph.edges.iter().enumerate() {
+        svg.group_start(
+            &format!("edge{}", index + 1),
+            "edge",
+            &format!("{from}->{to}"),
+        );
+        let (style, head) = match edge {
+            Edge::Internal => (LineStyle::Solid, ArrowHead::Filled),
+            Edge::External => (LineStyle::Dashed, ArrowHead::Filled),
+            Edge::Modifier => (LineStyle::Dashed, ArrowHead::Open),
+        };
+        layout::draw_edge(&mut svg, &nodes[from], &nodes[to], style, head);
+        svg.group_end();
+    }
+    for (index, (node, &(class, operator))) in nodes.iter().zip(&graph.functions).enumerate() {
+        let title = format!(
+            "{}.{}",
+            project.classes[class].name, project.classes[class].operators[operator].name
+        );
+        svg.group_start(&format!("node{}", index + 1), "node", &title);
+        layout::draw_node(&mut svg, node);
+        svg.group_end();
+    }
+
+    Ok(svg.finish("CallGraph", width, height))
+}
+
+#[derive(Debug, Default)]
+struct CallGraph {
+    functions: Vec<FunctionId>,
+    /// Minimal number of calls required to reach the function from any entry point
+    depths: Vec<usize>,
+    /// The strongest edge for each (caller, callee) pair of nodes
+    edges: BTreeMap<(usize, usize), Edge>,
+}
+
+impl CallGraph {
+    fn build(project: &Project, contract: usize) -> Result<Self, Error> {
+        let resolver = Resolver {
+            project,
+            linearization: storage::linearize(project, contract)?,
+        };
+
+        let mut graph = CallGraph::default();
+        let mut indices: HashMap<FunctionId, usize> = HashMap::new();
+        let mut add_function = |graph: &mut CallGraph, function: FunctionId, depth: usize| {
+            *indices.entry(function).or_insert_with(|| {
+                graph.functions.push(function);
+                graph.depths.push(depth);
+                graph.functions.len() - 1
+            })
+        };
+
+        for function in resolver.entry_points() {
+            add_function(&mut graph, function, 0);
+        }
+        // Functions are visited in the breadth-first order, so that the depths are minimal
+        let mut next = 0;
+        while next < graph.functions.len() {
+            project.deadline.check()?;
+            let (class, operator) = graph.functions[next];
+            let operator = &project.classes[class].operators[operator];
+            let depth = graph.depths[next] + 1;
+
+            let modifiers = operator
+                .modifiers
+                .iter()
+                .filter_map(|call| resolver.modifier(class, call))
+                .map(|callee| (callee, Edge::Modifier));
+            let calls = operator
+                .calls
+                .iter()
+                .filter_map(|call| resolver.call(class, call));
+            for (callee, edge) in modifiers.chain(calls) {
+                let callee = add_function(&mut graph, callee, depth);
+                if callee != next {
+                    let current = graph.edges.entry((next, callee)).or_insert(edge);
+                    *current = (*current).max(edge);
+                }
+            }
+            next += 1;
+        }
+
+        Ok(graph)
+    }
+}
+
+struct Resolver<'a> {
+    project: &'a Project,
+    /// The contract itself goes first, the most base contract goes last
+    linearization: Vec<usize>,
+}
+
+impl Resolver<'_> {
+    fn entry_points(&self) -> Vec<FunctionId> {
+        let contract = self.linearization[0];
+        let mut seen = HashSet::new();
+        let mut result = vec![];
+        for &class in &self.linearization {
+            for (index, operator) in self.project.classes[class].operators.iter().enumerate() {
+                let is_entry_point = match operator.kind {
+                    OperatorKind::Constructor => class == contract,
+                    OperatorKind::Fallback | OperatorKind::Receive => true,
+                    OperatorKind::Function => matches!(
+                        operator.visibility,
+                        Visibility::Public | Visibility::External
+                    ),
+                    OperatorKind::Modifier | OperatorKind::Event => false,
+                };
+                // Overridden functions are reachable only via `super`
+                let key = (operator.kind, operator.name.clone(), operator.params.len());
+                if is_entry_point && self.is_implemented(class, index) && seen.insert(key) {
+                    result.push((class, index));
+                }
+            }
+        }
+        result
+    }
+
+    fn modifier(&self, caller: usize, call: &Call) -> Option<FunctionId> {
+        if let [name] = call.path.as_slice() {
+            let modifier = self.find(&self.linearization, name, None, OperatorKind::Modifier);
+            if modifier.is_some() {
+                return modifier;
+            }
+        }
+        // Base constructor invocation (`constructor() ERC20("Token", "TKN")`)
+        let class = self.resolve_class(caller, &call.path)?;
+        self.find(&[class], "constructor", None, OperatorKind::Constructor)
+    }
+
+    fn call(&self, caller: usize, call: &Call) -> Option<(FunctionId, Edge)> {
+        let arguments = Some(call.arguments);
+        let find = |classes: &[usize], name: &str| {
+            self.find(classes, name, arguments, OperatorKind::Function)
+        };
+        let is_library = self.project.classes[caller].kind == ClassKind::Library;
+        match call.path.as_slice() {
+            [name] if is_library => find(&[caller], name).map(|callee| (callee, Edge::Internal)),
+            [name] => find(&self.linearization, name).map(|callee| (callee, Edge::Internal)),
+            [this, name] if this == "this" && !is_library => {
+                find(&self.linearization, name).map(|callee| (callee, Edge::External))
+            }
+            [super_, name] if super_ == "super" => {
+                let position = self.position(caller)?;
+                find(&self.linearization[position + 1..], name)
+                    .map(|callee| (callee, Edge::Internal))
+            }
+            [qualifier @ .., name] => {
+                let class = self.resolve_class(caller, qualifier)?;
+                let callee = match self.position(class) {
+                    // Explicit base function call (`ERC20.transfer(...)`) is not virtual
+                    Some(position) => find(&self.linearization[position..], name),
+                    None if self.project.classes[class].kind == ClassKind::Library => {
+                        find(&[class], name)
+                    }
+                    None => None,
+                }?;
+                Some((callee, Edge::Internal))
+            }
+            [] => None,
+        }
+    }
+
+    /// Finds the implemented function in the first class which defines it.
+    /// The number of arguments is used to choose between the overloads when possible.
+    fn find(
+        &self,
+        classes: &[usize],
+        name: &str,
+        arguments: Option<usize>,
+        kind: OperatorKind,
+    ) -> Option<FunctionId> {
+        let find = |arguments: Option<usize>| {
+            classes.iter().find_map(|&class| {
+                self.project.classes[class]
+                    .operators
+                    .iter()
+                    .enumerate()
+                    .find(|(index, operator)| {
+                        operator.kind == kind
+                            && operator.name == name
+                            && (arguments.is_none() || arguments == Some(operator.params.len()))
+                            && self.is_implemented(class, *index)
+                    })
+                    .map(|(index, _)| (class, index))
+            })
+        };
+        find(arguments).or_else(|| arguments.and_then(|_| find(None)))
+    }
+
+    fn resolve_class(&self, caller: usize, path: &[String]) -> Option<usize> {
+        let file = self.project.classes[caller].file;
+        self.project
+            .resolve(file, Some(caller), path)
+            .filter(|&class| self.project.classes[class].is_contract_like())
+    }
+
+    fn position(&self, class: usize) -> Option<usize> {
+        self.linearization.iter().position(|&item| item == class)
+    }
+
+    fn is_implemented(&self, class: usize, operator: usize) -> bool {
+        let class = &self.project.classes[class];
+        class.kind != ClassKind::Interface && !class.operators[operator].is_abstract
+    }
+}
+
+fn sections(project: &Project, contract: usize, (class, operator): FunctionId) -> Vec<Vec<Line>> {
+    let operator = &project.classes[class].operators[operator];
+    let mut stereotypes = vec![match operator.kind {
+        OperatorKind::Constructor => "<<constructor>>",
+        OperatorKind::Fallback => "<<fallback>>",
+        OperatorKind::Receive => "<<receive>>",
+        OperatorKind::Modifier => "<<modifier>>",
+        OperatorKind::Function | OperatorKind::Event => match operator.visibility {
+            Visibility::Private => "<<private>>",
+            Visibility::Internal => "<<internal>>",
+            Visibility::External => "<<external>>",
+            Visibility::Public => "<<public>>",
+        },
+    }];
+    if operator.payable {
+        stereotypes.push("<<payable>>");
+    }
+
+    // Functions of the base contracts and libraries are prefixed with the class name
+    let name = if class == contract {
+        operator.signature()
+    } else {
+        format!("{}.{}", project.classes[class].name, operator.signature())
+    };
+    vec![vec![
+        Line::centered(stereotypes.join(" "), FontStyle::Normal),
+        Line::centered(name, FontStyle::Bold),
+    ]]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    /// Returns sorted "Caller -> Callee (edge)" strings
+    fn edges(source: &str, contract: &str) -> Vec<String> {
+        let sources = BTreeMap::from([(PathBuf::from("main.sol"), source.to_string())]);
+        let project = Project::parse(&sources, Default::default()).unwrap();
+        let contract = project
+            .classes
+            .iter()
+            .position(|class| class.name == contract)
+            .unwrap();
+        let graph = CallGraph::build(&project, contract).unwrap();
+        let name = |node: usize| {
+            let (class, operator) = graph.functions[node];
+            let class = &project.classes[class];
+            format!("{}.{}", class.name, class.operators[operator].name)
+        };
+        let mut edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|(&(from, to), edge)| format!("{} -> {} ({edge:?})", name(from), name(to)))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn resolves_virtual_and_super_calls() {
+        let source = r#"
+            library Math {
+                function add(uint a, uint b) internal pure returns (uint) { return a + b; }
+            }
+            contract Base {
+                modifier onlyOwner() { _; }
+                function _transfer(uint a) internal virtual { _log(Math.add(a, 1)); }
+                function _log(uint a) private {}
+                function transfer(uint a) public virtual onlyOwner { _transfer(a); }
+            }
+            contract Main is Base {
+                function _transfer(uint a) internal override { super._transfer(a); helper(); }
+                function helper() internal view {}
+                function callSelf() external { this.transfer(1); }
+            }
+        "#;
+        assert_eq!(
+            vec![
+                "Base._transfer -> Base._log (Internal)",
+                "Base._transfer -> Math.add (Internal)",
+                "Base.transfer -> Base.onlyOwner (Modifier)",
+                "Base.transfer -> Main._transfer (Internal)",
+                "Main._transfer -> Base._transfer (Internal)",
+                "Main._transfer -> Main.helper (Internal)",
+                "Main.callSelf -> Base.transfer (External)",
+            ],
+            edges(source, "Main")
+        );
+    }
+}
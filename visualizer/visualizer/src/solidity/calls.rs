@@ -0,0 +1,233 @@
+use super::types::identifier_path;
+use solang_parser::pt;
+use std::borrow::Borrow;
+
+/// Invocation of a function or a modifier
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    /// Called name with the qualifiers (e.g., `["super", "transfer"]` or `["SafeMath", "add"]`)
+    pub path: Vec<String>,
+    pub arguments: usize,
+}
+
+/// Collects all calls with a statically known callee in the order they appear in the body.
+/// Inline assembly is not analyzed.
+pub fn collect_calls(body: &pt::Statement) -> Vec<Call> {
+    let mut collector = Collector::default();
+    collector.statement(body);
+    collector.calls
+}
+
+/// Collects calls from the arguments of the modifier invocations
+pub fn collect_expression_calls(expression: &pt::Expression) -> Vec<Call> {
+    let mut collector = Collector::default();
+    collector.expression(expression);
+    collector.calls
+}
+
+#[derive(Debug, Default)]
+struct Collector {
+    calls: Vec<Call>,
+}
+
+impl Collector {
+    fn statement(&mut self, statement: &pt::Statement) {
+        match statement {
+            pt::Statement::Block { statements, .. } => {
+                statements
+                    .iter()
+                    .for_each(|statement| self.statement(statement));
+            }
+            pt::Statement::Args(_, arguments) => {
+                arguments
+                    .iter()
+                    .for_each(|argument| self.expression(&argument.expr));
+            }
+            pt::Statement::If(_, condition, then, otherwise) => {
+                self.expression(condition);
+                self.statement(then);
+                otherwise
+                    .iter()
+                    .for_each(|statement| self.statement(statement));
+            }
+            pt::Statement::While(_, condition, body) => {
+                self.expression(condition);
+                self.statement(body);
+            }
+            pt::Statement::DoWhile(_, body, condition) => {
+                self.statement(body);
+                self.expression(condition);
+            }
+            pt::Statement::For(_, init, condition, next, body) => {
+                init.iter().for_each(|statement| self.statement(statement));
+                condition
+                    .iter()
+                    .for_each(|expression| self.expression(expression.borrow()));
+                next.iter()
+                    .for_each(|expression| self.expression(expression.borrow()));
+                body.iter().for_each(|statement| self.statement(statement));
+            }
+            pt::Statement::Expression(_, expression) | pt::Statement::Emit(_, expression) => {
+                self.expression(expression)
+            }
+            pt::Statement::VariableDefinition(_, _, initializer) => {
+                initializer
+                    .iter()
+                    .for_each(|expression| self.expression(expression));
+            }
+            pt::Statement::Return(_, value) => {
+                value
+                    .iter()
+                    .for_each(|expression| self.expression(expression));
+            }
+            pt::Statement::Revert(_, _, arguments) => {
+                arguments
+                    .iter()
+                    .for_each(|expression| self.expression(expression));
+            }
+            pt::Statement::RevertNamedArgs(_, _, arguments) => {
+                arguments
+                    .iter()
+                    .for_each(|argument| self.expression(&argument.expr));
+            }
+            pt::Statement::Try(_, expression, returns, catches) => {
+                self.expression(expression);
+                if let Some((_, body)) = returns {
+                    self.statement(body);
+                }
+                for catch in catches {
+                    match catch {
+                        pt::CatchClause::Simple(_, _, body)
+                        | pt::CatchClause::Named(_, _, _, body) => self.statement(body),
+                    }
+                }
+            }
+            pt::Statement::Assembly { .. }
+            | pt::Statement::Continue(_)
+            | pt::Statement::Break(_)
+            | pt::Statement::Error(_) => {}
+        }
+    }
+
+    fn expression(&mut self, expression: &pt::Expression) {
+        use pt::Expression::*;
+
+        match expression {
+            FunctionCall(_, callee, arguments) => {
+                self.call(callee, arguments.len());
+                arguments
+                    .iter()
+                    .for_each(|expression| self.expression(expression));
+            }
+            NamedFunctionCall(_, callee, arguments) => {
+                self.call(callee, arguments.len());
+                arguments
+                    .iter()
+                    .for_each(|argument| self.expression(&argument.expr));
+            }
+            // Call options (`{value: ...}`) without the call itself
+            FunctionCallBlock(_, callee, block) => {
+                self.expression(callee);
+                self.statement(block);
+            }
+            PostIncrement(_, expression)
+            | PostDecrement(_, expression)
+            | New(_, expression)
+            | Parenthesis(_, expression)
+            | MemberAccess(_, expression, _)
+            | Not(_, expression)
+            | BitwiseNot(_, expression)
+            | Delete(_, expression)
+            | PreIncrement(_, expression)
+            | PreDecrement(_, expression)
+            | UnaryPlus(_, expression)
+            | Negate(_, expression) => self.expression(expression),
+            ArraySubscript(_, base, index) => {
+                self.expression(base);
+                index
+                    .iter()
+                    .for_each(|expression| self.expression(expression));
+            }
+            ArraySlice(_, base, from, to) => {
+                self.expression(base);
+                from.iter()
+                    .chain(to)
+                    .for_each(|expression| self.expression(expression));
+            }
+            Power(_, left, right)
+            | Multiply(_, left, right)
+            | Divide(_, left, right)
+            | Modulo(_, left, right)
+            | Add(_, left, right)
+            | Subtract(_, left, right)
+            | ShiftLeft(_, left, right)
+            | ShiftRight(_, left, right)
+            | BitwiseAnd(_, left, right)
+            | BitwiseXor(_, left, right)
+            | BitwiseOr(_, left, right)
+            | Less(_, left, right)
+            | More(_, left, right)
+            | LessEqual(_, left, right)
+            | MoreEqual(_, left, right)
+            | Equal(_, left, right)
+            | NotEqual(_, left, right)
+            | And(_, left, right)
+            | Or(_, left, right)
+            | Assign(_, left, right)
+            | AssignOr(_, left, right)
+            | AssignAnd(_, left, right)
+            | AssignXor(_, left, right)
+            | AssignShiftLeft(_, left, right)
+            | AssignShiftRight(_, left, right)
+            | AssignAdd(_, left, right)
+            | AssignSubtract(_, left, right)
+            | AssignMultiply(_, left, right)
+            | AssignDivide(_, left, right)
+            | AssignModulo(_, left, right) => {
+                self.expression(left);
+                self.expression(right);
+            }
+            ConditionalOperator(_, condition, then, otherwise) => {
+                self.expression(condition);
+                self.expression(then);
+                self.expression(otherwise);
+            }
+            ArrayLiteral(_, expressions) => {
+                expressions
+                    .iter()
+                    .for_each(|expression| self.expression(expression));
+            }
+            List(_, parameters) => {
+                parameters
+                    .iter()
+                    .filter_map(|(_, parameter)| parameter.as_ref())
+                    .for_each(|parameter| self.expression(&parameter.ty));
+            }
+            BoolLiteral(..)
+            | NumberLiteral(..)
+            | RationalNumberLiteral(..)
+            | HexNumberLiteral(..)
+            | StringLiteral(..)
+            | Type(..)
+            | HexLiteral(..)
+            | AddressLiteral(..)
+            | Variable(..) => {}
+        }
+    }
+
+    fn call(&mut self, callee: &pt::Expression, arguments: usize) {
+        // Unwrap the call options: `f{value: 1}(...)`
+        let callee = match callee {
+            pt::Expression::FunctionCallBlock(_, callee, block) => {
+                self.statement(block);
+                callee.as_ref()
+            }
+            callee => callee,
+        };
+        match identifier_path(callee) {
+            Some(path) => self.calls.push(Call { path, arguments }),
+            // The callee itself may contain calls (e.g., `getToken().transfer(...)`)
+            None => self.expression(callee),
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use super::{
+    internal::Error,
+    layout::{self, ArrowHead, Line, Node},
+    project::{Class, ClassKind, Operator, OperatorKind, Project, Visibility},
+    svg::{FontStyle, LineStyle, Svg},
+    visualize_contracts::ContractsDiagramOptions,
+};
+use crate::limits::Limits;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+const INDENT: &str = "  ";
+
+/// Relations are ordered by their strength, so that only the strongest one is drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Relation {
+    /// Usage of the type in the function signature or via `using ... for`
+    Dependency,
+    /// Usage of the type in the state variable or the struct field
+    Association,
+    /// Implementation of the interface
+    Realization,
+    Inheritance,
+}
+
+/// Renders UML class diagram of the contracts, structs and enums of the project
+/// that are not filtered out by the options
+pub fn render(
+    project: &Project,
+    options: &ContractsDiagramOptions,
+    limits: &Limits,
+) -> Result<String, Error> {
+    let classes = visible_classes(project, options)?;
+    if classes.len() > limits.max_classes {
+        return Err(Error::ResourceLimit(format!(
+            "number of classes ({}) exceeds {}",
+            classes.len(),
+            limits.max_classes
+        )));
+    }
+
+    let node_indices: HashMap<usize, usize> = classes
+        .iter()
+        .enumerate()
+        .map(|(node, &class)| (class, node))
+        .collect();
+    let mut edges = relations(project, &classes, &node_indices)?;
+    if options.inheritance_only {
+        edges.retain(|_, relation| *relation >= Relation::Realization);
+    }
+
+    let mut nodes: Vec<Node> = classes
+        .iter()
+        .map(|&class| Node::new(sections(&project.classes[class])))
+        .collect();
+    assign_ranks(&mut nodes, &edges);
+    let edge_list: Vec<_> = edges.keys().copied().collect();
+    project.deadline.check()?;
+    let (width, height) = layout::assign_positions(&mut nodes, &edge_list);
+
+    let mut svg = Svg::new();
+    for (index, (&(from, to), &relation)) in edges.iter().enumerate() {
+        svg.group_start(
+            &format!("edge{}", index + 1),
+            "edge",
+            &format!("{from}->{to}"),
+        );
+        let (style, head) = match relation {
+            Relation::Inheritance => (LineStyle::Solid, ArrowHead::Hollow),
+            Relation::Realization => (LineStyle::Dashed, ArrowHead::Hollow),
+            Relation::Association => (LineStyle::Solid, ArrowHead::Filled),
+            Relation::Dependency => (LineStyle::Dashed, ArrowHead::Open),
+        };
+        layout::draw_edge(&mut svg, &nodes[from], &nodes[to], style, head);
+        svg.group_end();
+    }
+    for (index, (node, &class)) in nodes.iter().zip(&classes).enumerate() {
+        let class = &project.classes[class];
+        svg.group_start(&format!("node{}", index + 1), "node", &class.name);
+        layout::draw_node(&mut svg, node);
+        svg.group_end();
+    }
+
+    Ok(svg.finish("UmlClassDiagram", width, height))
+}
+
+fn visible_classes(
+    project: &Project,
+    options: &ContractsDiagramOptions,
+) -> Result<Vec<usize>, Error> {
+    let selected = if options.contract_names.is_empty() {
+        None
+    } else {
+        Some(selected_classes(project, &options.contract_names)?)
+    };
+    let classes = project
+        .classes
+        .iter()
+        .enumerate()
+        .filter(|(index, class)| {
+            let is_hidden = match class.kind {
+                ClassKind::ValueType => true,
+                ClassKind::Library => options.hide_libraries,
+                ClassKind::Interface => options.hide_interfaces,
+                ClassKind::Struct | ClassKind::Enum => options.inheritance_only,
+                ClassKind::Contract | ClassKind::Abstract => false,
+            };
+            let is_selected = match &selected {
+                Some(selected) => selected.contains(index),
+                None => true,
+            };
+            !is_hidden && is_selected
+        })
+        .map(|(index, _)| index)
+        .collect();
+    Ok(classes)
+}
+
+/// Contracts with the given names, all their base contracts
+/// and the structs and enums defined in any of them
+fn selected_classes(project: &Project, names: &[String]) -> Result<HashSet<usize>, Error> {
+    let mut selected = HashSet::new();
+    let mut queue = vec![];
+    for name in names {
+        let len = queue.len();
+        queue.extend(
+            project
+                .classes
+                .iter()
+                .enumerate()
+                .filter(|(_, class)| class.is_contract_like() && class.name == *name)
+                .map(|(index, _)| index),
+        );
+        if queue.len() == len {
+            return Err(Error::ContractNotFound(name.clone()));
+        }
+    }
+    while let Some(index) = queue.pop() {
+        if !selected.insert(index) {
+            continue;
+        }
+        let class = &project.classes[index];
+        // Missing base contracts are skipped, as everywhere on the class diagram
+        queue.extend(
+            class
+                .bases
+                .iter()
+                .filter_map(|base| project.resolve(class.file, None, base)),
+        );
+    }
+    let nested: Vec<_> = project
+        .classes
+        .iter()
+        .enumerate()
+        .filter(|(_, class)| {
+            class
+                .parent
+                .is_some_and(|parent| selected.contains(&parent))
+        })
+        .map(|(index, _)| index)
+        .collect();
+    selected.extend(nested);
+    Ok(selected)
+}
+
+/// Returns the strongest relation for each (from, to) pair of nodes
+fn relations(
+    project: &Project,
+    classes: &[usize],
+    node_indices: &HashMap<usize, usize>,
+) -> Result<BTreeMap<(usize, usize), Relation>, Error> {
+    let mut edges = BTreeMap::new();
+    for (from, &class_index) in classes.iter().enumerate() {
+        project.deadline.check()?;
+        let class = &project.classes[class_index];
+        let scope = if class.is_contract_like() {
+            Some(class_index)
+        } else {
+            class.parent
+        };
+        let mut add = |path: &[String], relation: Relation| {
+            let to = project
+                .resolve(class.file, scope, path)
+                .and_then(|target| node_indices.get(&target).copied());
+            if let Some(to) = to.filter(|&to| to != from) {
+                let current = edges.entry((from, to)).or_insert(relation);
+                *current = (*current).max(relation);
+            }
+        };
+
+        for base in &class.bases {
+            let is_interface = project
+                .resolve(class.file, None, base)
+                .is_some_and(|base| project.classes[base].kind == ClassKind::Interface);
+            let relation = if is_interface && class.kind != ClassKind::Interface {
+                Relation::Realization
+            } else {
+                Relation::Inheritance
+            };
+            add(base, relation);
+        }
+        for attribute in &class.attributes {
+            for reference in attribute.ty.references() {
+                add(reference, Relation::Association);
+            }
+        }
+        for operator in &class.operators {
+            for parameter in operator.params.iter().chain(&operator.returns) {
+                for reference in parameter.ty.references() {
+                    add(reference, Relation::Dependency);
+                }
+            }
+        }
+        for library in &class.libraries {
+            add(library, Relation::Dependency);
+        }
+    }
+    Ok(edges)
+}
+
+fn sections(class: &Class) -> Vec<Vec<Line>> {
+    let centered = Line::centered;
+    let left = Line::left;
+
+    let mut header = vec![];
+    let stereotype = match class.kind {
+        ClassKind::Contract | ClassKind::ValueType => None,
+        ClassKind::Abstract => Some("<<Abstract>>"),
+        ClassKind::Interface => Some("<<Interface>>"),
+        ClassKind::Library => Some("<<Library>>"),
+        ClassKind::Struct => Some("<<Struct>>"),
+        ClassKind::Enum => Some("<<Enum>>"),
+    };
+    if let Some(stereotype) = stereotype {
+        header.push(centered(stereotype.to_string(), FontStyle::Normal));
+    }
+    let name_style = if class.kind == ClassKind::Abstract {
+        FontStyle::Italic
+    } else {
+        FontStyle::Bold
+    };
+    header.push(centered(class.name.clone(), name_style));
+
+    let mut sections = vec![header];
+    match class.kind {
+        ClassKind::Struct => sections.push(
+            class
+                .attributes
+                .iter()
+                .map(|field| left(format!("{}: {}", field.name, field.ty.display)))
+                .collect(),
+        ),
+        ClassKind::Enum => sections.push(class.enum_values.iter().cloned().map(left).collect()),
+        _ => {
+            let mut attributes = vec![];
+            for visibility in [
+                Visibility::Private,
+                Visibility::Internal,
+                Visibility::Public,
+            ] {
+                let group: Vec<_> = class
+                    .attributes
+                    .iter()
+                    .filter(|attribute| attribute.visibility == visibility)
+                    .collect();
+                if group.is_empty() {
+                    continue;
+                }
+                attributes.push(left(visibility_title(visibility)));
+                for attribute in group {
+                    let prefix = if attribute.constant {
+                        "<<constant>> "
+                    } else if attribute.immutable {
+                        "<<immutable>> "
+                    } else {
+                        ""
+                    };
+                    attributes.push(left(format!(
+                        "{INDENT}{prefix}{}: {}",
+                        attribute.name, attribute.ty.display
+                    )));
+                }
+            }
+
+            let mut operators = vec![];
+            for visibility in [
+                Visibility::Private,
+                Visibility::Internal,
+                Visibility::External,
+                Visibility::Public,
+            ] {
+                let group: Vec<_> = class
+                    .operators
+                    .iter()
+                    .filter(|operator| operator.visibility == visibility)
+                    .collect();
+                if group.is_empty() {
+                    continue;
+                }
+                operators.push(left(visibility_title(visibility)));
+                for operator in group {
+                    operators.push(left(format!("{INDENT}{}", operator_signature(operator))));
+                }
+            }
+
+            sections.extend(
+                [attributes, operators]
+                    .into_iter()
+                    .filter(|s| !s.is_empty()),
+            );
+        }
+    }
+    sections
+}
+
+fn visibility_title(visibility: Visibility) -> String {
+    match visibility {
+        Visibility::Private => "Private:",
+        Visibility::Internal => "Internal:",
+        Visibility::External => "External:",
+        Visibility::Public => "Public:",
+    }
+    .to_string()
+}
+
+fn operator_signature(operator: &Operator) -> String {
+    let mut stereotypes = vec![];
+    match operator.kind {
+        OperatorKind::Event => stereotypes.push("<<event>>"),
+        OperatorKind::Modifier => stereotypes.push("<<modifier>>"),
+        OperatorKind::Fallback => stereotypes.push("<<fallback>>"),
+        OperatorKind::Receive => stereotypes.push("<<receive>>"),
+        OperatorKind::Function | OperatorKind::Constructor => {}
+    }
+    if operator.is_abstract && operator.kind != OperatorKind::Event {
+        stereotypes.push("<<abstract>>");
+    }
+    if operator.payable {
+        stereotypes.push("<<payable>>");
+    }
+
+    let signature = operator.signature();
+    stereotypes.push(&signature);
+    stereotypes.join(" ")
+}
+
+/// Base contracts are placed above the derived ones, the other classes are placed at the top rank
+fn assign_ranks(nodes: &mut [Node], edges: &BTreeMap<(usize, usize), Relation>) {
+    fn rank(
+        node: usize,
+        bases: &HashMap<usize, Vec<usize>>,
+        ranks: &mut HashMap<usize, usize>,
+        visiting: &mut Vec<usize>,
+    ) -> usize {
+        if let Some(&rank) = ranks.get(&node) {
+            return rank;
+        }
+        // Cyclic inheritance is invalid, but should not break rendering
+        if visiting.contains(&node) {
+            return 0;
+        }
+        visiting.push(node);
+        let result = bases
+            .get(&node)
+            .into_iter()
+            .flatten()
+            .map(|&base| rank(base, bases, ranks, visiting) + 1)
+            .max()
+            .unwrap_or(0);
+        visiting.pop();
+        ranks.insert(node, result);
+        result
+    }
+
+    let mut bases: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (&(from, to), &relation) in edges {
+        if relation >= Relation::Realization {
+            bases.entry(from).or_default().push(to);
+        }
+    }
+    let mut ranks = HashMap::new();
+    for index in 0..nodes.len() {
+        nodes[index].rank = rank(index, &bases, &mut ranks, &mut vec![]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    const SOURCE: &str = r#"
+        interface IToken {}
+        library Math {}
+        struct Point { uint x; }
+        contract Base is IToken {
+            enum State { Active }
+        }
+        contract Token is Base {
+            struct Balance { uint amount; }
+        }
+        contract Other {}
+    "#;
+
+    fn visible(options: ContractsDiagramOptions) -> Result<Vec<String>, Error> {
+        let sources = BTreeMap::from([(PathBuf::from("main.sol"), SOURCE.to_string())]);
+        let project = Project::parse(&sources, Default::default()).unwrap();
+        let mut names: Vec<_> = visible_classes(&project, &options)?
+            .into_iter()
+            .map(|class| project.classes[class].name.clone())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    #[test]
+    fn filters_classes() {
+        assert_eq!(
+            vec!["Balance", "Base", "IToken", "Math", "Other", "Point", "State", "Token"],
+            visible(ContractsDiagramOptions::default()).unwrap()
+        );
+        assert_eq!(
+            vec!["Base", "Other", "Token"],
+            visible(ContractsDiagramOptions {
+                inheritance_only: true,
+                hide_libraries: true,
+                hide_interfaces: true,
+                ..Default::default()
+            })
+            .unwrap()
+        );
+        assert_eq!(
+            vec!["Balance", "Base", "IToken", "State", "Token"],
+            visible(ContractsDiagramOptions {
+                contract_names: vec!["Token".to_string()],
+                ..Default::default()
+            })
+            .unwrap()
+        );
+        assert!(matches!(
+            visible(ContractsDiagramOptions {
+                contract_names: vec!["Missing".to_string()],
+                ..Default::default()
+            }),
+            Err(Error::ContractNotFound(_))
+        ));
+    }
+}
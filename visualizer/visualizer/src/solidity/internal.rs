@@ -1,108 +1,110 @@
-use crate::metrics;
+use super::project::Project;
+use crate::{limits::Limits, metrics};
 use std::{
     collections::BTreeMap,
-    ffi::OsStr,
-    io::Write,
     path::{Path, PathBuf},
+    time::{Duration, Instant},
 };
 use thiserror::Error;
-use tokio::process::Command;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("internal error: {0}")]
     Internal(#[from] anyhow::Error),
-    #[error("sol2uml call failed: {0}")]
-    Sol2Uml(String),
-    #[error("failed to save files")]
-    SaveFiles(#[from] std::io::Error),
+    #[error("Failed to parse solidity code: {0}")]
+    Parse(String),
+    #[error("invalid file path: {0}. All paths should be relative")]
+    InvalidPath(String),
+    #[error("Failed to find contract with name \"{0}\"")]
+    ContractNotFound(String),
+    #[error("Failed to find inherited contract \"{0}\"")]
+    InheritedContractNotFound(String),
+    #[error("Failed to calculate storage layout: {0}")]
+    Storage(String),
+    #[error("resource limit exceeded: {0}")]
+    ResourceLimit(String),
 }
 
-pub async fn save_files(root: &Path, files: BTreeMap<PathBuf, String>) -> Result<(), Error> {
-    let join = files.into_iter().map(|(name, content)| {
-        let root = root.to_owned();
-        tokio::task::spawn_blocking(move || -> Result<(), std::io::Error> {
-            if name.has_root() {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Error. All paths should be relative.",
-                ));
-            }
-
-            // Set a default file prefix if none is provided
-            let name = if name.ends_with(".sol") {
-                name.with_file_name("main.sol")
-            } else {
-                name
-            };
-
-            let file_path = root.join(name);
-            let prefix = file_path.parent();
-            if let Some(prefix) = prefix {
-                std::fs::create_dir_all(prefix)?;
-            }
-            let mut f = std::fs::File::create(file_path)?;
-            f.write_all(content.as_bytes())?;
-            Ok(())
-        })
-    });
-    let results: Vec<_> = futures::future::join_all(join).await;
-
-    for result in results {
-        result.map_err(anyhow::Error::msg)??;
-    }
-
-    Ok(())
-}
-
-pub struct Sol2Uml {
-    command: Command,
+/// Moment after which the visualization is aborted. Blocking tasks cannot be cancelled
+/// from the outside, so parsing and rendering check the deadline between the steps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Deadline {
+    /// `None` if the visualization is not limited in time
+    at: Option<(Instant, Duration)>,
 }
 
-#[allow(unused)]
-impl Sol2Uml {
-    pub fn new() -> Self {
+impl Deadline {
+    pub fn after(timeout: Duration) -> Self {
         Self {
-            command: Command::new("sol2uml"),
+            at: Some((Instant::now() + timeout, timeout)),
         }
     }
 
-    pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Self {
-        self.command.arg(arg);
-        self
+    pub fn check(&self) -> Result<(), Error> {
+        match self.at {
+            Some((at, timeout)) if Instant::now() >= at => Err(Error::ResourceLimit(format!(
+                "visualization took more than {} seconds",
+                timeout.as_secs()
+            ))),
+            _ => Ok(()),
+        }
     }
+}
 
-    pub fn args<I, S>(&mut self, args: I) -> &mut Self
-    where
-        I: IntoIterator<Item = S>,
-        S: AsRef<OsStr>,
-    {
-        self.command.args(args);
-        self
+/// Checks that the sources satisfy the limits
+pub fn validate_sources(sources: &BTreeMap<PathBuf, String>, limits: &Limits) -> Result<(), Error> {
+    if let Some(path) = sources.keys().find(|path| path.has_root()) {
+        return Err(Error::InvalidPath(path.display().to_string()));
     }
 
-    pub fn current_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
-        self.command.current_dir(dir);
-        self
+    let total_size: usize = sources.values().map(|content| content.len()).sum();
+    if total_size > limits.max_sources_size {
+        return Err(Error::ResourceLimit(format!(
+            "total size of the sources ({total_size} bytes) exceeds {} bytes",
+            limits.max_sources_size
+        )));
     }
+    Ok(())
+}
 
-    #[tracing::instrument(skip(self), level = "debug")]
-    pub async fn call(&mut self) -> Result<(), Error> {
-        let output = {
-            let _timer = metrics::SOL2UML_EXECUTION_TIME.start_timer();
-            self.command.output().await.map_err(anyhow::Error::msg)?
-        };
+/// Parsing and rendering are CPU-bound, so they are run on the blocking thread pool.
+/// The request fails if the visualization does not fit into the timeout: `visualize`
+/// is expected to check the deadline, so that the blocking thread is released right after it.
+pub async fn run_blocking<T, F>(limits: &Limits, visualize: F) -> Result<T, Error>
+where
+    F: FnOnce(Deadline) -> Result<T, Error> + Send + 'static,
+    T: Send + 'static,
+{
+    let _timer = metrics::VISUALIZATION_TIME.start_timer();
+    let deadline = Deadline::after(limits.timeout);
+    tokio::task::spawn_blocking(move || visualize(deadline))
+        .await
+        .map_err(anyhow::Error::msg)?
+}
 
-        tracing::info!(output = ?output, "process finished");
+/// Looks for the contract in the file with exactly the same path first.
+/// Otherwise, the contract in the file with the same name is used
+/// (paths of the sources may be sanitized by the caller).
+pub fn find_contract(project: &Project, file_path: &Path, name: &str) -> Result<usize, Error> {
+    let candidates: Vec<usize> = project
+        .classes
+        .iter()
+        .enumerate()
+        .filter(|(_, class)| class.name == name && class.is_contract_like())
+        .map(|(index, _)| index)
+        .collect();
+    let path = |index: usize| &project.files[project.classes[index].file].path;
 
-        if output.status.success() && output.stderr.is_empty() {
-            Ok(())
-        } else {
-            Err(Error::Sol2Uml(
-                std::str::from_utf8(&output.stderr)
-                    .map_err(anyhow::Error::msg)?
-                    .to_owned(),
-            ))
-        }
-    }
+    candidates
+        .iter()
+        .copied()
+        .find(|&index| path(index) == file_path)
+        .or_else(|| {
+            candidates
+                .iter()
+                .copied()
+                .filter(|&index| path(index).file_name() == file_path.file_name())
+                .min_by_key(|&index| path(index))
+        })
+        .ok_or_else(|| Error::ContractNotFound(name.to_string()))
 }
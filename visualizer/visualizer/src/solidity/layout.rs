@@ -0,0 +1,233 @@
+//! Layered layout and drawing of the box-and-arrow diagrams
+
+use super::svg::{
+    self, Anchor, FontStyle, LineStyle, Point, Svg, LINE_HEIGHT, NODE_FILL, PADDING, STROKE,
+};
+use std::collections::HashMap;
+
+const NODE_GAP: f64 = 40.0;
+const RANK_GAP: f64 = 70.0;
+const MARGIN: f64 = 10.0;
+const ARROW_LENGTH: f64 = 12.0;
+const ARROW_WIDTH: f64 = 10.0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub text: String,
+    pub style: FontStyle,
+    pub anchor: Anchor,
+}
+
+impl Line {
+    pub fn centered(text: String, style: FontStyle) -> Self {
+        Self {
+            text,
+            style,
+            anchor: Anchor::Middle,
+        }
+    }
+
+    pub fn left(text: String) -> Self {
+        Self {
+            text,
+            style: FontStyle::Normal,
+            anchor: Anchor::Start,
+        }
+    }
+}
+
+/// Box consisting of the sections separated by horizontal lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub sections: Vec<Vec<Line>>,
+    pub width: f64,
+    pub height: f64,
+    /// Nodes with the lower rank are placed above
+    pub rank: usize,
+    pub position: Point,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrowHead {
+    /// Hollow triangle
+    Hollow,
+    /// Filled triangle
+    Filled,
+    /// Two lines forming a `V`
+    Open,
+}
+
+impl Node {
+    pub fn new(sections: Vec<Vec<Line>>) -> Self {
+        let width = sections
+            .iter()
+            .flatten()
+            .map(|line| svg::text_width(&line.text))
+            .fold(0.0, f64::max)
+            + 2.0 * PADDING;
+        let height = sections
+            .iter()
+            .map(|section| section.len() as f64 * LINE_HEIGHT + PADDING)
+            .sum();
+        Self {
+            sections,
+            width,
+            height,
+            rank: 0,
+            position: Point::new(0.0, 0.0),
+        }
+    }
+
+    fn top(&self) -> Point {
+        Point::new(self.position.x + self.width / 2.0, self.position.y)
+    }
+
+    fn bottom(&self) -> Point {
+        Point::new(
+            self.position.x + self.width / 2.0,
+            self.position.y + self.height,
+        )
+    }
+
+    fn left(&self) -> Point {
+        Point::new(self.position.x, self.position.y + self.height / 2.0)
+    }
+
+    fn right(&self) -> Point {
+        Point::new(
+            self.position.x + self.width,
+            self.position.y + self.height / 2.0,
+        )
+    }
+}
+
+/// Orders nodes inside the ranks by the average position of the connected nodes
+/// in the previous rank (barycenter heuristic), and assigns the coordinates.
+/// Returns the size of the diagram.
+pub fn assign_positions(nodes: &mut [Node], edges: &[(usize, usize)]) -> (f64, f64) {
+    let rank_count = nodes.iter().map(|node| node.rank + 1).max().unwrap_or(0);
+    let mut ranks: Vec<Vec<usize>> = vec![vec![]; rank_count];
+    for (index, node) in nodes.iter().enumerate() {
+        ranks[node.rank].push(index);
+    }
+
+    let mut order: HashMap<usize, f64> = HashMap::new();
+    for rank in ranks.iter_mut() {
+        let barycenter = |node: usize| {
+            let neighbours: Vec<f64> = edges
+                .iter()
+                .filter_map(|&(from, to)| match (from == node, to == node) {
+                    (true, _) => order.get(&to).copied(),
+                    (_, true) => order.get(&from).copied(),
+                    _ => None,
+                })
+                .collect();
+            if neighbours.is_empty() {
+                None
+            } else {
+                Some(neighbours.iter().sum::<f64>() / neighbours.len() as f64)
+            }
+        };
+        let mut keyed: Vec<(f64, usize)> = rank
+            .iter()
+            .enumerate()
+            .map(|(position, &node)| (barycenter(node).unwrap_or(position as f64), node))
+            .collect();
+        keyed.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+        *rank = keyed.into_iter().map(|(_, node)| node).collect();
+        for (position, &node) in rank.iter().enumerate() {
+            order.insert(node, position as f64);
+        }
+    }
+
+    let rank_width = |rank: &[usize]| {
+        rank.iter().map(|&node| nodes[node].width).sum::<f64>()
+            + NODE_GAP * rank.len().saturating_sub(1) as f64
+    };
+    let diagram_width = ranks
+        .iter()
+        .map(|rank| rank_width(rank))
+        .fold(0.0, f64::max)
+        + 2.0 * MARGIN;
+
+    let mut y = MARGIN;
+    for rank in &ranks {
+        let mut x = (diagram_width - rank_width(rank)) / 2.0;
+        let mut rank_height: f64 = 0.0;
+        for &node in rank {
+            nodes[node].position = Point::new(x, y);
+            x += nodes[node].width + NODE_GAP;
+            rank_height = rank_height.max(nodes[node].height);
+        }
+        y += rank_height + RANK_GAP;
+    }
+    let diagram_height = if ranks.is_empty() {
+        2.0 * MARGIN
+    } else {
+        y - RANK_GAP + MARGIN
+    };
+
+    (diagram_width, diagram_height)
+}
+
+pub fn draw_node(svg: &mut Svg, node: &Node) {
+    let Point { x, y } = node.position;
+    svg.rect(x, y, node.width, node.height, NODE_FILL);
+
+    let mut current_y = y;
+    for (index, section) in node.sections.iter().enumerate() {
+        if index > 0 {
+            svg.polyline(&[
+                Point::new(x, current_y),
+                Point::new(x + node.width, current_y),
+            ]);
+        }
+        current_y += PADDING / 2.0;
+        for line in section {
+            current_y += LINE_HEIGHT;
+            let text_x = match line.anchor {
+                Anchor::Middle => x + node.width / 2.0,
+                Anchor::Start => x + PADDING,
+            };
+            // Baseline is placed a bit above the bottom of the line
+            svg.text(text_x, current_y - 4.0, &line.text, line.anchor, line.style);
+        }
+        current_y += PADDING / 2.0;
+    }
+}
+
+pub fn draw_edge(svg: &mut Svg, from: &Node, to: &Node, style: LineStyle, head: ArrowHead) {
+    let (start, end) = if to.rank < from.rank {
+        (from.top(), to.bottom())
+    } else if to.rank > from.rank {
+        (from.bottom(), to.top())
+    } else if to.position.x < from.position.x {
+        (from.left(), to.right())
+    } else {
+        (from.right(), to.left())
+    };
+
+    let (dx, dy) = (end.x - start.x, end.y - start.y);
+    let length = (dx * dx + dy * dy).sqrt().max(f64::EPSILON);
+    let (ux, uy) = (dx / length, dy / length);
+    let base = Point::new(end.x - ux * ARROW_LENGTH, end.y - uy * ARROW_LENGTH);
+    let (nx, ny) = (-uy * ARROW_WIDTH / 2.0, ux * ARROW_WIDTH / 2.0);
+    let left = Point::new(base.x + nx, base.y + ny);
+    let right = Point::new(base.x - nx, base.y - ny);
+
+    match head {
+        ArrowHead::Hollow => {
+            // The line ends at the base of the triangle
+            svg.line(start, base, style);
+            svg.polygon(&[end, left, right, end], "none");
+        }
+        ArrowHead::Filled => {
+            svg.line(start, base, style);
+            svg.polygon(&[end, left, right, end], STROKE);
+        }
+        ArrowHead::Open => {
+            svg.line(start, end, style);
+            svg.polyline(&[left, end, right]);
+        }
+    }
+}
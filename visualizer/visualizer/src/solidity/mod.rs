@@ -1,4 +1,14 @@
+mod call_graph;
+mod calls;
+mod class_diagram;
 mod internal;
+mod layout;
+mod project;
+mod storage;
+mod storage_diagram;
+mod svg;
+mod types;
 
+pub mod visualize_call_graph;
 pub mod visualize_contracts;
 pub mod visualize_storage;
@@ -0,0 +1,722 @@
+use super::{
+    calls::{collect_calls, collect_expression_calls, Call},
+    internal::{Deadline, Error},
+    types::{integer_literal, TypeName},
+};
+use solang_parser::pt;
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassKind {
+    Contract,
+    Abstract,
+    Interface,
+    Library,
+    Struct,
+    Enum,
+    /// User defined value type (`type Price is uint128`)
+    ValueType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Visibility {
+    Private,
+    Internal,
+    External,
+    Public,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OperatorKind {
+    Function,
+    Constructor,
+    Fallback,
+    Receive,
+    Modifier,
+    Event,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    pub name: String,
+    pub ty: TypeName,
+    pub visibility: Visibility,
+    pub constant: bool,
+    pub immutable: bool,
+    /// Value of the constant if it is an integer literal. Used to evaluate array lengths
+    pub value: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parameter {
+    pub name: Option<String>,
+    pub ty: TypeName,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operator {
+    pub name: String,
+    pub kind: OperatorKind,
+    pub visibility: Visibility,
+    pub payable: bool,
+    /// Function without implementation
+    pub is_abstract: bool,
+    pub params: Vec<Parameter>,
+    pub returns: Vec<Parameter>,
+    /// Modifiers and base constructors invoked in the function header
+    pub modifiers: Vec<Call>,
+    /// Calls made from the function body and from the modifier arguments
+    pub calls: Vec<Call>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Class {
+    pub name: String,
+    pub kind: ClassKind,
+    pub file: usize,
+    /// Contract the struct, enum or value type is defined in
+    pub parent: Option<usize>,
+    pub bases: Vec<Vec<String>>,
+    /// Contract state variables or struct fields
+    pub attributes: Vec<Attribute>,
+    pub operators: Vec<Operator>,
+    /// Libraries attached via `using ... for ...` directives
+    pub libraries: Vec<Vec<String>>,
+    pub enum_values: Vec<String>,
+    pub underlying_type: Option<TypeName>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ImportSymbols {
+    /// `import "path";`
+    All,
+    /// `import "path" as Name;` or `import * as Name from "path";`
+    Namespace(String),
+    /// `import {A as B, C} from "path";` as (original name, alias) pairs
+    Aliases(Vec<(String, String)>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Import {
+    /// `None` if the imported file is missing
+    file: Option<usize>,
+    symbols: ImportSymbols,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    imports: Vec<Import>,
+    /// File level constants with integer literal values
+    constants: BTreeMap<String, u64>,
+}
+
+/// All classes defined in the sources along with the information required to resolve names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Project {
+    pub files: Vec<SourceFile>,
+    pub classes: Vec<Class>,
+    /// Checked by the parser and by the renderers of the project
+    pub deadline: Deadline,
+}
+
+impl Project {
+    pub fn parse(sources: &BTreeMap<PathBuf, String>, deadline: Deadline) -> Result<Self, Error> {
+        let mut project = Project {
+            files: vec![],
+            classes: vec![],
+            deadline,
+        };
+        let mut imports = vec![];
+        for (file_no, (path, source)) in sources.iter().enumerate() {
+            deadline.check()?;
+            let (source_unit, _comments) =
+                solang_parser::parse(source, file_no).map_err(|diagnostics| {
+                    let message = diagnostics
+                        .first()
+                        .map(|diagnostic| {
+                            let offset = match diagnostic.loc {
+                                pt::Loc::File(_, start, _) => start.min(source.len()),
+                                _ => 0,
+                            };
+                            let line = source[..offset].matches('\n').count() + 1;
+                            format!("{}:{line}: {}", path.display(), diagnostic.message)
+                        })
+                        .unwrap_or_else(|| path.display().to_string());
+                    Error::Parse(message)
+                })?;
+
+            let mut file = SourceFile {
+                path: normalize(path),
+                imports: vec![],
+                constants: BTreeMap::new(),
+            };
+            let mut file_imports = vec![];
+            for part in &source_unit.0 {
+                match part {
+                    pt::SourceUnitPart::ImportDirective(import) => file_imports.push(import),
+                    pt::SourceUnitPart::VariableDefinition(variable) => {
+                        if let (Some(name), Some(value)) = (
+                            &variable.name,
+                            variable.initializer.as_ref().and_then(integer_literal),
+                        ) {
+                            file.constants.insert(name.name.clone(), value);
+                        }
+                    }
+                    part => project.add_part(file_no, None, part_as_contract_part(part), source),
+                }
+            }
+            imports.push(file_imports);
+            project.files.push(file);
+        }
+
+        for (file_no, file_imports) in imports.into_iter().enumerate() {
+            let importing_path = project.files[file_no].path.clone();
+            project.files[file_no].imports = file_imports
+                .into_iter()
+                .map(|import| project.import(&importing_path, import))
+                .collect();
+        }
+
+        Ok(project)
+    }
+
+    /// Finds the class referenced by `path` from the `scope` contract of the `file`.
+    /// If the name cannot be resolved via imports, falls back to the first class with the same name.
+    pub fn resolve(&self, file: usize, scope: Option<usize>, path: &[String]) -> Option<usize> {
+        self.resolve_scoped(file, scope, path)
+            .or_else(|| self.resolve_by_name(path))
+    }
+
+    /// Value of the integer constant referenced by `path`
+    pub fn resolve_constant(
+        &self,
+        file: usize,
+        scope: Option<usize>,
+        path: &[String],
+    ) -> Option<u64> {
+        let (name, class_path) = path.split_last()?;
+        let class = if class_path.is_empty() {
+            scope
+        } else {
+            Some(self.resolve(file, scope, class_path)?)
+        };
+        if let Some(class) = class {
+            let mut visited = HashSet::new();
+            if let Some(value) = self.find_constant_in_class(class, name, &mut visited) {
+                return Some(value);
+            }
+        }
+        if class_path.is_empty() {
+            return self.files[file].constants.get(name).copied();
+        }
+        None
+    }
+
+    fn resolve_scoped(&self, file: usize, scope: Option<usize>, path: &[String]) -> Option<usize> {
+        let (head, tail) = path.split_first()?;
+        let head = match scope.and_then(|scope| self.find_nested(scope, head)) {
+            Some(class) => Resolved::Class(class),
+            None => self.resolve_global(file, head, &mut HashSet::new())?,
+        };
+        let mut current = head;
+        for name in tail {
+            current = match current {
+                Resolved::Class(class) => Resolved::Class(self.find_nested(class, name)?),
+                Resolved::File(file) => self.resolve_global(file, name, &mut HashSet::new())?,
+            };
+        }
+        match current {
+            Resolved::Class(class) => Some(class),
+            Resolved::File(_) => None,
+        }
+    }
+
+    fn resolve_by_name(&self, path: &[String]) -> Option<usize> {
+        let name = path.last()?;
+        self.classes.iter().position(|class| &class.name == name)
+    }
+
+    /// Finds the class defined inside the contract or its base contracts
+    fn find_nested(&self, class: usize, name: &str) -> Option<usize> {
+        let mut visited = HashSet::new();
+        self.find_nested_inner(class, name, &mut visited)
+    }
+
+    fn find_nested_inner(
+        &self,
+        class: usize,
+        name: &str,
+        visited: &mut HashSet<usize>,
+    ) -> Option<usize> {
+        if !visited.insert(class) {
+            return None;
+        }
+        if let Some(nested) = self
+            .classes
+            .iter()
+            .position(|nested| nested.parent == Some(class) && nested.name == name)
+        {
+            return Some(nested);
+        }
+        let file = self.classes[class].file;
+        self.classes[class]
+            .bases
+            .iter()
+            .filter_map(|base| self.resolve_scoped(file, None, base))
+            .find_map(|base| self.find_nested_inner(base, name, visited))
+    }
+
+    fn find_constant_in_class(
+        &self,
+        class: usize,
+        name: &str,
+        visited: &mut HashSet<usize>,
+    ) -> Option<u64> {
+        if !visited.insert(class) {
+            return None;
+        }
+        let value = self.classes[class]
+            .attributes
+            .iter()
+            .find(|attribute| attribute.constant && attribute.name == name)
+            .and_then(|attribute| attribute.value);
+        if value.is_some() {
+            return value;
+        }
+        let file = self.classes[class].file;
+        self.classes[class]
+            .bases
+            .iter()
+            .filter_map(|base| self.resolve_scoped(file, None, base))
+            .find_map(|base| self.find_constant_in_class(base, name, visited))
+    }
+
+    /// Resolves the name among the top level definitions of the file and its imports
+    fn resolve_global(
+        &self,
+        file: usize,
+        name: &str,
+        visited: &mut HashSet<(usize, String)>,
+    ) -> Option<Resolved> {
+        if !visited.insert((file, name.to_string())) {
+            return None;
+        }
+        if let Some(class) = self
+            .classes
+            .iter()
+            .position(|class| class.file == file && class.parent.is_none() && class.name == name)
+        {
+            return Some(Resolved::Class(class));
+        }
+        self.files[file]
+            .imports
+            .iter()
+            .find_map(|import| match (&import.symbols, import.file) {
+                (ImportSymbols::All, Some(imported)) => {
+                    self.resolve_global(imported, name, visited)
+                }
+                (ImportSymbols::Namespace(namespace), Some(imported)) if namespace == name => {
+                    Some(Resolved::File(imported))
+                }
+                (ImportSymbols::Aliases(aliases), Some(imported)) => aliases
+                    .iter()
+                    .filter(|(_, alias)| alias == name)
+                    .find_map(|(original, _)| self.resolve_global(imported, original, visited)),
+                _ => None,
+            })
+    }
+
+    fn import(&self, importing_path: &Path, import: &pt::Import) -> Import {
+        let (path, symbols) = match import {
+            pt::Import::Plain(path, _) => (path, ImportSymbols::All),
+            pt::Import::GlobalSymbol(path, namespace, _) => {
+                (path, ImportSymbols::Namespace(namespace.name.clone()))
+            }
+            pt::Import::Rename(path, renames, _) => {
+                let aliases = renames
+                    .iter()
+                    .map(|(original, alias)| {
+                        let alias = alias.as_ref().unwrap_or(original);
+                        (original.name.clone(), alias.name.clone())
+                    })
+                    .collect();
+                (path, ImportSymbols::Aliases(aliases))
+            }
+        };
+        let file = match path {
+            pt::ImportPath::Filename(filename) => self.find_file(importing_path, &filename.string),
+            pt::ImportPath::Path(_) => None,
+        };
+        Import { file, symbols }
+    }
+
+    fn find_file(&self, importing_path: &Path, import_path: &str) -> Option<usize> {
+        let path = if import_path.starts_with("./") || import_path.starts_with("../") {
+            importing_path
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(import_path)
+        } else {
+            PathBuf::from(import_path)
+        };
+        let path = normalize(&path);
+        let sanitized = normalize(Path::new(&sanitize(&path.to_string_lossy())));
+        self.files
+            .iter()
+            .position(|file| file.path == path)
+            .or_else(|| self.files.iter().position(|file| file.path == sanitized))
+    }
+
+    fn add_part(
+        &mut self,
+        file: usize,
+        parent: Option<usize>,
+        part: Option<PartRef<'_>>,
+        source: &str,
+    ) {
+        match part {
+            Some(PartRef::Contract(contract)) => self.add_contract(file, contract, source),
+            Some(PartRef::Struct(definition)) => {
+                if let Some(name) = &definition.name {
+                    let attributes = definition
+                        .fields
+                        .iter()
+                        .filter_map(|field| {
+                            Some(Attribute {
+                                name: field.name.as_ref()?.name.clone(),
+                                ty: TypeName::from_expression(&field.ty, source),
+                                visibility: Visibility::Public,
+                                constant: false,
+                                immutable: false,
+                                value: None,
+                            })
+                        })
+                        .collect();
+                    self.classes.push(Class {
+                        attributes,
+                        ..Class::new(name.name.clone(), ClassKind::Struct, file, parent)
+                    });
+                }
+            }
+            Some(PartRef::Enum(definition)) => {
+                if let Some(name) = &definition.name {
+                    let enum_values = definition
+                        .values
+                        .iter()
+                        .flatten()
+                        .map(|value| value.name.clone())
+                        .collect();
+                    self.classes.push(Class {
+                        enum_values,
+                        ..Class::new(name.name.clone(), ClassKind::Enum, file, parent)
+                    });
+                }
+            }
+            Some(PartRef::Type(definition)) => {
+                self.classes.push(Class {
+                    underlying_type: Some(TypeName::from_expression(&definition.ty, source)),
+                    ..Class::new(
+                        definition.name.name.clone(),
+                        ClassKind::ValueType,
+                        file,
+                        parent,
+                    )
+                });
+            }
+            None => {}
+        }
+    }
+
+    fn add_contract(&mut self, file: usize, contract: &pt::ContractDefinition, source: &str) {
+        let Some(name) = &contract.name else {
+            return;
+        };
+        let kind = match contract.ty {
+            pt::ContractTy::Abstract(_) => ClassKind::Abstract,
+            pt::ContractTy::Contract(_) => ClassKind::Contract,
+            pt::ContractTy::Interface(_) => ClassKind::Interface,
+            pt::ContractTy::Library(_) => ClassKind::Library,
+        };
+        let index = self.classes.len();
+        let mut class = Class::new(name.name.clone(), kind, file, None);
+        class.bases = contract
+            .base
+            .iter()
+            .map(|base| {
+                base.name
+                    .identifiers
+                    .iter()
+                    .map(|identifier| identifier.name.clone())
+                    .collect()
+            })
+            .collect();
+        self.classes.push(class);
+
+        for part in &contract.parts {
+            match part {
+                pt::ContractPart::VariableDefinition(variable) => {
+                    if let Some(attribute) = attribute(variable, source) {
+                        self.classes[index].attributes.push(attribute);
+                    }
+                }
+                pt::ContractPart::FunctionDefinition(function) => {
+                    if let Some(operator) = function_operator(function, kind, source) {
+                        self.classes[index].operators.push(operator);
+                    }
+                }
+                pt::ContractPart::EventDefinition(event) => {
+                    if let Some(name) = &event.name {
+                        let params = event
+                            .fields
+                            .iter()
+                            .map(|field| Parameter {
+                                name: field.name.as_ref().map(|name| name.name.clone()),
+                                ty: TypeName::from_expression(&field.ty, source),
+                            })
+                            .collect();
+                        self.classes[index].operators.push(Operator {
+                            name: name.name.clone(),
+                            kind: OperatorKind::Event,
+                            visibility: Visibility::Public,
+                            payable: false,
+                            is_abstract: false,
+                            params,
+                            returns: vec![],
+                            modifiers: vec![],
+                            calls: vec![],
+                        });
+                    }
+                }
+                pt::ContractPart::Using(using) => {
+                    if let pt::UsingList::Library(library) = &using.list {
+                        let library = library
+                            .identifiers
+                            .iter()
+                            .map(|identifier| identifier.name.clone())
+                            .collect();
+                        self.classes[index].libraries.push(library);
+                    }
+                }
+                part => self.add_part(file, Some(index), contract_part(part), source),
+            }
+        }
+    }
+}
+
+impl Operator {
+    /// Signature in UML notation (e.g., `transfer(to: address, amount: uint): bool`)
+    pub fn signature(&self) -> String {
+        let parameters = |parameters: &[Parameter]| {
+            parameters
+                .iter()
+                .map(|parameter| match &parameter.name {
+                    Some(name) => format!("{name}: {}", parameter.ty.display),
+                    None => parameter.ty.display.clone(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut signature = format!("{}({})", self.name, parameters(&self.params));
+        match self.returns.len() {
+            0 => {}
+            1 => signature.push_str(&format!(": {}", parameters(&self.returns))),
+            _ => signature.push_str(&format!(": ({})", parameters(&self.returns))),
+        }
+        signature
+    }
+}
+
+impl Class {
+    fn new(name: String, kind: ClassKind, file: usize, parent: Option<usize>) -> Self {
+        Self {
+            name,
+            kind,
+            file,
+            parent,
+            bases: vec![],
+            attributes: vec![],
+            operators: vec![],
+            libraries: vec![],
+            enum_values: vec![],
+            underlying_type: None,
+        }
+    }
+
+    pub fn is_contract_like(&self) -> bool {
+        matches!(
+            self.kind,
+            ClassKind::Contract | ClassKind::Abstract | ClassKind::Interface | ClassKind::Library
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Resolved {
+    Class(usize),
+    /// Namespace created by `import "path" as Name`
+    File(usize),
+}
+
+/// Definitions which may be placed both at the file level and inside contracts
+enum PartRef<'a> {
+    Contract(&'a pt::ContractDefinition),
+    Struct(&'a pt::StructDefinition),
+    Enum(&'a pt::EnumDefinition),
+    Type(&'a pt::TypeDefinition),
+}
+
+fn part_as_contract_part(part: &pt::SourceUnitPart) -> Option<PartRef<'_>> {
+    match part {
+        pt::SourceUnitPart::ContractDefinition(contract) => Some(PartRef::Contract(contract)),
+        pt::SourceUnitPart::StructDefinition(definition) => Some(PartRef::Struct(definition)),
+        pt::SourceUnitPart::EnumDefinition(definition) => Some(PartRef::Enum(definition)),
+        pt::SourceUnitPart::TypeDefinition(definition) => Some(PartRef::Type(definition)),
+        _ => None,
+    }
+}
+
+fn contract_part(part: &pt::ContractPart) -> Option<PartRef<'_>> {
+    match part {
+        pt::ContractPart::StructDefinition(definition) => Some(PartRef::Struct(definition)),
+        pt::ContractPart::EnumDefinition(definition) => Some(PartRef::Enum(definition)),
+        pt::ContractPart::TypeDefinition(definition) => Some(PartRef::Type(definition)),
+        _ => None,
+    }
+}
+
+fn attribute(variable: &pt::VariableDefinition, source: &str) -> Option<Attribute> {
+    let name = variable.name.as_ref()?.name.clone();
+    let mut visibility = Visibility::Internal;
+    let mut constant = false;
+    let mut immutable = false;
+    for attribute in &variable.attrs {
+        match attribute {
+            pt::VariableAttribute::Visibility(value) => visibility = self::visibility(value),
+            pt::VariableAttribute::Constant(_) => constant = true,
+            pt::VariableAttribute::Immutable(_) => immutable = true,
+            _ => {}
+        }
+    }
+    let value = constant
+        .then(|| variable.initializer.as_ref().and_then(integer_literal))
+        .flatten();
+
+    Some(Attribute {
+        name,
+        ty: TypeName::from_expression(&variable.ty, source),
+        visibility,
+        constant,
+        immutable,
+        value,
+    })
+}
+
+fn function_operator(
+    function: &pt::FunctionDefinition,
+    contract_kind: ClassKind,
+    source: &str,
+) -> Option<Operator> {
+    let (kind, name) = match function.ty {
+        pt::FunctionTy::Constructor => (OperatorKind::Constructor, "constructor".to_string()),
+        pt::FunctionTy::Fallback => (OperatorKind::Fallback, "fallback".to_string()),
+        pt::FunctionTy::Receive => (OperatorKind::Receive, "receive".to_string()),
+        pt::FunctionTy::Modifier => (OperatorKind::Modifier, function.name.as_ref()?.name.clone()),
+        pt::FunctionTy::Function => (OperatorKind::Function, function.name.as_ref()?.name.clone()),
+    };
+
+    let mut visibility = match (kind, contract_kind) {
+        (OperatorKind::Modifier, _) => Visibility::Internal,
+        (OperatorKind::Fallback | OperatorKind::Receive, _) => Visibility::External,
+        (_, ClassKind::Interface) => Visibility::External,
+        _ => Visibility::Public,
+    };
+    let mut payable = matches!(kind, OperatorKind::Receive);
+    let mut modifiers = vec![];
+    let mut calls = vec![];
+    for attribute in &function.attributes {
+        match attribute {
+            pt::FunctionAttribute::Visibility(value) => visibility = self::visibility(value),
+            pt::FunctionAttribute::Mutability(pt::Mutability::Payable(_)) => payable = true,
+            pt::FunctionAttribute::BaseOrModifier(_, base) => {
+                let arguments = base.args.as_deref().unwrap_or_default();
+                modifiers.push(Call {
+                    path: base
+                        .name
+                        .identifiers
+                        .iter()
+                        .map(|identifier| identifier.name.clone())
+                        .collect(),
+                    arguments: arguments.len(),
+                });
+                calls.extend(arguments.iter().flat_map(collect_expression_calls));
+            }
+            _ => {}
+        }
+    }
+    if let Some(body) = &function.body {
+        calls.extend(collect_calls(body));
+    }
+
+    let parameters = |list: &pt::ParameterList| -> Vec<Parameter> {
+        list.iter()
+            .filter_map(|(_, parameter)| parameter.as_ref())
+            .map(|parameter| Parameter {
+                name: parameter.name.as_ref().map(|name| name.name.clone()),
+                ty: TypeName::from_expression(&parameter.ty, source),
+            })
+            .collect()
+    };
+
+    Some(Operator {
+        name,
+        kind,
+        visibility,
+        payable,
+        is_abstract: function.body.is_none() && contract_kind != ClassKind::Interface,
+        params: parameters(&function.params),
+        returns: parameters(&function.returns),
+        modifiers,
+        calls,
+    })
+}
+
+fn visibility(visibility: &pt::Visibility) -> Visibility {
+    match visibility {
+        pt::Visibility::External(_) => Visibility::External,
+        pt::Visibility::Public(_) => Visibility::Public,
+        pt::Visibility::Internal(_) => Visibility::Internal,
+        pt::Visibility::Private(_) => Visibility::Private,
+    }
+}
+
+/// Removes `.` and resolves `..` path components, as well as the root
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => result.push(part),
+            Component::ParentDir => {
+                result.pop();
+            }
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => {}
+        }
+    }
+    result
+}
+
+/// Replaces the characters the service replaces in the source file paths
+fn sanitize(path: &str) -> String {
+    path.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '/' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,462 @@
+use super::{
+    internal::Error,
+    project::{ClassKind, Project},
+    types::{ArrayLength, TypeKind},
+};
+use std::collections::HashMap;
+
+const SLOT_SIZE: usize = 32;
+/// Protects from the infinite recursion on invalid (self-containing) structs
+const MAX_TYPE_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageVariable {
+    /// Prefixed with the contract name for the inherited variables (e.g., `ERC20.totalSupply`)
+    pub name: String,
+    pub ty: String,
+    /// Offset inside the first slot in bytes
+    pub offset: usize,
+    /// Size in bytes
+    pub size: u64,
+}
+
+/// Variables starting at the same slot
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageRow {
+    pub first_slot: u64,
+    pub last_slot: u64,
+    pub variables: Vec<StorageVariable>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageLayout {
+    pub contract: String,
+    pub rows: Vec<StorageRow>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// Value type of the given size in bytes, which may be packed with the neighbours
+    Value(usize),
+    /// Occupies the given number of whole slots. Both the item and the next one start new slots
+    Slots(u64),
+}
+
+#[derive(Debug, Default)]
+struct Packer {
+    slot: u64,
+    offset: usize,
+}
+
+impl Packer {
+    /// Returns the first slot and the offset of the placed item
+    fn place(&mut self, layout: Layout) -> Result<(u64, usize), Error> {
+        match layout {
+            Layout::Value(size) => {
+                if self.offset + size > SLOT_SIZE {
+                    self.next_slot()?;
+                }
+                let position = (self.slot, self.offset);
+                self.offset += size;
+                Ok(position)
+            }
+            Layout::Slots(count) => {
+                if self.offset > 0 {
+                    self.next_slot()?;
+                }
+                let position = (self.slot, 0);
+                self.slot = self.slot.checked_add(count).ok_or_else(too_large)?;
+                Ok(position)
+            }
+        }
+    }
+
+    fn next_slot(&mut self) -> Result<(), Error> {
+        self.slot = self.slot.checked_add(1).ok_or_else(too_large)?;
+        self.offset = 0;
+        Ok(())
+    }
+
+    fn used_slots(&self) -> u64 {
+        if self.offset > 0 {
+            self.slot + 1
+        } else {
+            self.slot
+        }
+    }
+}
+
+pub fn storage_layout(project: &Project, contract: usize) -> Result<StorageLayout, Error> {
+    let linearization = linearize(project, contract)?;
+
+    let mut packer = Packer::default();
+    let mut rows: Vec<StorageRow> = vec![];
+    // Variables of the most base contract go first
+    for &class_index in linearization.iter().rev() {
+        project.deadline.check()?;
+        let class = &project.classes[class_index];
+        for attribute in &class.attributes {
+            if attribute.constant || attribute.immutable {
+                continue;
+            }
+            let layout = type_layout(
+                project,
+                class.file,
+                Some(class_index),
+                &attribute.ty.kind,
+                0,
+            )
+            .map_err(|err| match err {
+                Error::Storage(message) => {
+                    Error::Storage(format!("{}.{}: {message}", class.name, attribute.name))
+                }
+                err => err,
+            })?;
+            let (slot, offset) = packer.place(layout)?;
+            let (size, last_slot) = match layout {
+                Layout::Value(size) => (size as u64, slot),
+                Layout::Slots(count) => (
+                    count.saturating_mul(SLOT_SIZE as u64),
+                    slot + count.saturating_sub(1),
+                ),
+            };
+
+            let name = if class_index == contract {
+                attribute.name.clone()
+            } else {
+                format!("{}.{}", class.name, attribute.name)
+            };
+            let variable = StorageVariable {
+                name,
+                ty: attribute.ty.display.clone(),
+                offset,
+                size,
+            };
+            match rows.last_mut() {
+                Some(row) if row.first_slot == slot => {
+                    row.last_slot = row.last_slot.max(last_slot);
+                    row.variables.push(variable);
+                }
+                _ => rows.push(StorageRow {
+                    first_slot: slot,
+                    last_slot,
+                    variables: vec![variable],
+                }),
+            }
+        }
+    }
+
+    Ok(StorageLayout {
+        contract: project.classes[contract].name.clone(),
+        rows,
+    })
+}
+
+/// C3 linearization of the inheritance graph (the contract itself goes first,
+/// the most base contract goes last) as done by the Solidity compiler
+pub fn linearize(project: &Project, contract: usize) -> Result<Vec<usize>, Error> {
+    Linearizer {
+        project,
+        stack: vec![],
+        linearizations: HashMap::new(),
+    }
+    .linearize(contract)
+}
+
+struct Linearizer<'a> {
+    project: &'a Project,
+    /// Contracts being linearized, used to detect the cycles
+    stack: Vec<usize>,
+    /// Bases are shared in the diamond-shaped graphs, so that each of them is linearized once
+    linearizations: HashMap<usize, Vec<usize>>,
+}
+
+impl Linearizer<'_> {
+    fn linearize(&mut self, contract: usize) -> Result<Vec<usize>, Error> {
+        if let Some(linearization) = self.linearizations.get(&contract) {
+            return Ok(linearization.clone());
+        }
+        self.project.deadline.check()?;
+
+        let project = self.project;
+        let class = &project.classes[contract];
+        if self.stack.contains(&contract) {
+            return Err(Error::Storage(format!(
+                "cyclic inheritance of \"{}\"",
+                class.name
+            )));
+        }
+        self.stack.push(contract);
+
+        let mut bases = vec![];
+        for base in &class.bases {
+            let base_index = project
+                .resolve(class.file, None, base)
+                .filter(|&index| project.classes[index].is_contract_like())
+                .ok_or_else(|| Error::InheritedContractNotFound(base.join(".")))?;
+            bases.push(base_index);
+        }
+        // Solidity lists the bases from the "most base-like" to the "most derived"
+        bases.reverse();
+
+        let mut sequences = bases
+            .iter()
+            .map(|&base| self.linearize(base))
+            .collect::<Result<Vec<_>, _>>()?;
+        sequences.push(bases);
+        self.stack.pop();
+
+        let mut result = vec![contract];
+        loop {
+            sequences.retain(|sequence| !sequence.is_empty());
+            if sequences.is_empty() {
+                break;
+            }
+            let candidate = sequences
+                .iter()
+                .map(|sequence| sequence[0])
+                .find(|candidate| {
+                    sequences
+                        .iter()
+                        .all(|sequence| !sequence[1..].contains(candidate))
+                })
+                .ok_or_else(|| {
+                    Error::Storage(format!(
+                        "linearization of inheritance graph of \"{}\" is impossible",
+                        class.name
+                    ))
+                })?;
+            result.push(candidate);
+            for sequence in sequences.iter_mut() {
+                if sequence[0] == candidate {
+                    sequence.remove(0);
+                }
+            }
+        }
+        self.linearizations.insert(contract, result.clone());
+        Ok(result)
+    }
+}
+
+fn type_layout(
+    project: &Project,
+    file: usize,
+    scope: Option<usize>,
+    kind: &TypeKind,
+    depth: usize,
+) -> Result<Layout, Error> {
+    if depth > MAX_TYPE_DEPTH {
+        return Err(Error::Storage("type nesting is too deep".to_string()));
+    }
+    let layout = match kind {
+        TypeKind::Bool => Layout::Value(1),
+        TypeKind::Address => Layout::Value(20),
+        TypeKind::Int(bits) | TypeKind::Uint(bits) => Layout::Value(*bits as usize / 8),
+        TypeKind::FixedBytes(size) => Layout::Value(*size as usize),
+        TypeKind::Function { external: true } => Layout::Value(24),
+        TypeKind::Function { external: false } => Layout::Value(8),
+        TypeKind::String
+        | TypeKind::Bytes
+        | TypeKind::Mapping { .. }
+        | TypeKind::Array { length: None, .. } => Layout::Slots(1),
+        TypeKind::Array {
+            base,
+            length: Some(length),
+        } => {
+            let length = match length {
+                ArrayLength::Literal(length) => Some(*length),
+                ArrayLength::Constant(path) => project.resolve_constant(file, scope, path),
+                ArrayLength::Unsupported => None,
+            }
+            .ok_or_else(|| Error::Storage("unsupported array length".to_string()))?;
+            let slots = match type_layout(project, file, scope, base, depth + 1)? {
+                Layout::Value(size) if size <= SLOT_SIZE / 2 => {
+                    let per_slot = (SLOT_SIZE / size) as u64;
+                    length.div_ceil(per_slot)
+                }
+                Layout::Value(_) => length,
+                Layout::Slots(count) => length.checked_mul(count).ok_or_else(too_large)?,
+            };
+            Layout::Slots(slots)
+        }
+        TypeKind::UserDefined(path) => {
+            let index = project.resolve(file, scope, path).ok_or_else(|| {
+                Error::Storage(format!("failed to find type \"{}\"", path.join(".")))
+            })?;
+            let class = &project.classes[index];
+            match class.kind {
+                ClassKind::Contract
+                | ClassKind::Abstract
+                | ClassKind::Interface
+                | ClassKind::Library => Layout::Value(20),
+                ClassKind::Enum => Layout::Value(1),
+                ClassKind::ValueType => {
+                    let underlying = class.underlying_type.as_ref().ok_or_else(|| {
+                        Error::Storage(format!("invalid value type \"{}\"", class.name))
+                    })?;
+                    type_layout(
+                        project,
+                        class.file,
+                        class.parent,
+                        &underlying.kind,
+                        depth + 1,
+                    )?
+                }
+                ClassKind::Struct => {
+                    let mut packer = Packer::default();
+                    for field in &class.attributes {
+                        let layout = type_layout(
+                            project,
+                            class.file,
+                            class.parent,
+                            &field.ty.kind,
+                            depth + 1,
+                        )?;
+                        packer.place(layout)?;
+                    }
+                    Layout::Slots(packer.used_slots().max(1))
+                }
+            }
+        }
+        TypeKind::Unsupported => return Err(Error::Storage("unsupported type".to_string())),
+    };
+    Ok(layout)
+}
+
+fn too_large() -> Error {
+    Error::Storage("storage layout is too large".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solidity::internal::Deadline;
+    use pretty_assertions::assert_eq;
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    /// Returns (first slot, last slot, ["name@offset"]) for each row
+    fn layout(source: &str, contract: &str) -> Vec<(u64, u64, Vec<String>)> {
+        let sources = BTreeMap::from([(PathBuf::from("main.sol"), source.to_string())]);
+        let project = Project::parse(&sources, Default::default()).unwrap();
+        let contract = project
+            .classes
+            .iter()
+            .position(|class| class.name == contract)
+            .unwrap();
+        storage_layout(&project, contract)
+            .unwrap()
+            .rows
+            .into_iter()
+            .map(|row| {
+                let variables = row
+                    .variables
+                    .into_iter()
+                    .map(|variable| format!("{}@{}", variable.name, variable.offset))
+                    .collect();
+                (row.first_slot, row.last_slot, variables)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn packs_value_types() {
+        let source = r#"
+            contract Main {
+                uint public a;
+                uint32 b;
+                uint112 c;
+                address d;
+                bool e;
+                uint constant F = 1;
+                uint immutable g;
+                bytes12 h;
+            }
+        "#;
+        assert_eq!(
+            vec![
+                (0, 0, vec!["a@0".to_string()]),
+                (1, 1, vec!["b@0".to_string(), "c@4".to_string()]),
+                (2, 2, vec!["d@0".to_string(), "e@20".to_string()]),
+                (3, 3, vec!["h@0".to_string()]),
+            ],
+            layout(source, "Main")
+        );
+    }
+
+    #[test]
+    fn structs_and_arrays_start_new_slots() {
+        let source = r#"
+            uint constant LENGTH = 3;
+            contract Main {
+                struct S { uint128 x; uint128 y; bool z; }
+                enum E { A, B }
+                bool a;
+                S s;
+                E e;
+                uint64[5] small;
+                address[LENGTH] addresses;
+                mapping(address => S) m;
+                uint8 last;
+            }
+        "#;
+        assert_eq!(
+            vec![
+                (0, 0, vec!["a@0".to_string()]),
+                (1, 2, vec!["s@0".to_string()]),
+                (3, 3, vec!["e@0".to_string()]),
+                (4, 5, vec!["small@0".to_string()]),
+                (6, 8, vec!["addresses@0".to_string()]),
+                (9, 9, vec!["m@0".to_string()]),
+                (10, 10, vec!["last@0".to_string()]),
+            ],
+            layout(source, "Main")
+        );
+    }
+
+    #[test]
+    fn inherited_variables_use_c3_linearization() {
+        let source = r#"
+            contract A { uint a; }
+            contract B is A { uint b; }
+            contract C is A { uint c; }
+            contract D is B, C { uint d; }
+        "#;
+        assert_eq!(
+            vec![
+                (0, 0, vec!["A.a@0".to_string()]),
+                (1, 1, vec!["B.b@0".to_string()]),
+                (2, 2, vec!["C.c@0".to_string()]),
+                (3, 3, vec!["d@0".to_string()]),
+            ],
+            layout(source, "D")
+        );
+    }
+
+    #[test]
+    fn deep_diamond_inheritance_is_linearized() {
+        // every contract inherits from the two previous ones,
+        // so the number of inheritance paths grows exponentially
+        let mut source = "contract C0 { uint v0; }\ncontract C1 is C0 { uint v1; }\n".to_string();
+        for i in 2..64 {
+            source += &format!("contract C{i} is C{}, C{} {{ uint v{i}; }}\n", i - 2, i - 1);
+        }
+        let rows = layout(&source, "C63");
+        assert_eq!(64, rows.len());
+        assert_eq!((0, 0, vec!["C0.v0@0".to_string()]), rows[0]);
+        assert_eq!((63, 63, vec!["v63@0".to_string()]), rows[63]);
+    }
+
+    #[test]
+    fn expired_deadline_aborts_layout() {
+        let sources = BTreeMap::from([(
+            PathBuf::from("main.sol"),
+            "contract A { uint a; }".to_string(),
+        )]);
+        let mut project = Project::parse(&sources, Default::default()).unwrap();
+        project.deadline = Deadline::after(std::time::Duration::ZERO);
+        assert!(matches!(
+            storage_layout(&project, 0),
+            Err(Error::ResourceLimit(_))
+        ));
+    }
+}
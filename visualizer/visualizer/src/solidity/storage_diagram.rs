@@ -0,0 +1,96 @@
+use super::{
+    storage::StorageLayout,
+    svg::{self, Anchor, FontStyle, Svg, LINE_HEIGHT, NODE_FILL, PADDING},
+};
+
+const MARGIN: f64 = 10.0;
+const SLOT_SIZE: usize = 32;
+
+/// Renders storage layout as a table with a slot number column and a column of packed variables
+pub fn render(layout: &StorageLayout) -> String {
+    let title = format!("{} <<Contract>>", layout.contract);
+    let header = (
+        "slot".to_string(),
+        vec!["type: <inherited contract>.variable (bytes)".to_string()],
+    );
+    let rows: Vec<(String, Vec<String>)> =
+        std::iter::once(header)
+            .chain(layout.rows.iter().map(|row| {
+                let slot = if row.first_slot == row.last_slot {
+                    row.first_slot.to_string()
+                } else {
+                    format!("{}-{}", row.first_slot, row.last_slot)
+                };
+                let mut cells = vec![];
+                let used = row
+                    .variables
+                    .iter()
+                    .map(|variable| variable.offset + variable.size as usize)
+                    .max()
+                    .unwrap_or_default();
+                if used < SLOT_SIZE {
+                    cells.push(format!("unallocated ({})", SLOT_SIZE - used));
+                }
+                // Variables are packed from the lower-order bytes, so the last one goes first
+                cells.extend(row.variables.iter().rev().map(|variable| {
+                    format!("{}: {} ({})", variable.ty, variable.name, variable.size)
+                }));
+                (slot, cells)
+            }))
+            .collect();
+
+    let max_width = |texts: &mut dyn Iterator<Item = &String>| {
+        texts.map(|text| svg::text_width(text)).fold(0.0, f64::max) + 2.0 * PADDING
+    };
+    let slot_width = max_width(&mut rows.iter().map(|(slot, _)| slot));
+    let cells_width = max_width(&mut rows.iter().flat_map(|(_, cells)| cells));
+    let table_width = (slot_width + cells_width).max(svg::text_width(&title) + 2.0 * PADDING);
+    let cells_width = table_width - slot_width;
+    let row_height = |cells: &[String]| cells.len().max(1) as f64 * LINE_HEIGHT + PADDING;
+
+    let mut svg = Svg::new();
+    svg.group_start("node1", "node", &layout.contract);
+
+    let (x, mut y) = (MARGIN, MARGIN);
+    let title_height = LINE_HEIGHT + PADDING;
+    svg.rect(x, y, table_width, title_height, NODE_FILL);
+    svg.text(
+        x + table_width / 2.0,
+        y + title_height - PADDING / 2.0 - 4.0,
+        &title,
+        Anchor::Middle,
+        FontStyle::Bold,
+    );
+    y += title_height;
+
+    for (index, (slot, cells)) in rows.iter().enumerate() {
+        let height = row_height(cells);
+        let style = if index == 0 {
+            FontStyle::Bold
+        } else {
+            FontStyle::Normal
+        };
+        svg.rect(x, y, slot_width, height, NODE_FILL);
+        svg.rect(x + slot_width, y, cells_width, height, NODE_FILL);
+        svg.text(
+            x + slot_width / 2.0,
+            y + height / 2.0 + LINE_HEIGHT / 2.0 - 4.0,
+            slot,
+            Anchor::Middle,
+            style,
+        );
+        for (line, cell) in cells.iter().enumerate() {
+            svg.text(
+                x + slot_width + cells_width / 2.0,
+                y + PADDING / 2.0 + (line + 1) as f64 * LINE_HEIGHT - 4.0,
+                cell,
+                Anchor::Middle,
+                style,
+            );
+        }
+        y += height;
+    }
+    svg.group_end();
+
+    svg.finish("StorageDiagram", table_width + 2.0 * MARGIN, y + MARGIN)
+}
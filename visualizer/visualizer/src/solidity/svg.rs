@@ -0,0 +1,195 @@
+use std::fmt::Write;
+
+pub const FONT_FAMILY: &str = "Courier New";
+pub const FONT_SIZE: f64 = 14.0;
+/// Courier New is monospaced, so the text width is proportional to the number of characters
+pub const CHAR_WIDTH: f64 = FONT_SIZE * 0.6;
+pub const LINE_HEIGHT: f64 = 18.0;
+pub const PADDING: f64 = 8.0;
+
+pub const BACKGROUND: &str = "white";
+pub const NODE_FILL: &str = "#f2f2f2";
+pub const STROKE: &str = "black";
+
+pub fn text_width(text: &str) -> f64 {
+    text.chars().count() as f64 * CHAR_WIDTH
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    Start,
+    Middle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontStyle {
+    Normal,
+    Bold,
+    Italic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineStyle {
+    Solid,
+    Dashed,
+}
+
+/// Minimal SVG document builder
+#[derive(Debug, Default)]
+pub struct Svg {
+    body: String,
+}
+
+impl Svg {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn group_start(&mut self, id: &str, class: &str, title: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<g id="{}" class="{}">"#,
+            escape(id),
+            escape(class)
+        );
+        let _ = writeln!(self.body, "<title>{}</title>", escape(title));
+    }
+
+    pub fn group_end(&mut self) {
+        self.body.push_str("</g>\n");
+    }
+
+    pub fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, fill: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{fill}" stroke="{STROKE}"/>"#,
+            num(x),
+            num(y),
+            num(width),
+            num(height),
+        );
+    }
+
+    pub fn line(&mut self, from: Point, to: Point, style: LineStyle) {
+        let _ = writeln!(
+            self.body,
+            r#"<path fill="none" stroke="{STROKE}"{} d="M{},{} L{},{}"/>"#,
+            dash(style),
+            num(from.x),
+            num(from.y),
+            num(to.x),
+            num(to.y),
+        );
+    }
+
+    pub fn polyline(&mut self, points: &[Point]) {
+        let _ = writeln!(
+            self.body,
+            r#"<polyline fill="none" stroke="{STROKE}" points="{}"/>"#,
+            points_attribute(points)
+        );
+    }
+
+    pub fn polygon(&mut self, points: &[Point], fill: &str) {
+        let _ = writeln!(
+            self.body,
+            r#"<polygon fill="{fill}" stroke="{STROKE}" points="{}"/>"#,
+            points_attribute(points)
+        );
+    }
+
+    pub fn text(&mut self, x: f64, y: f64, text: &str, anchor: Anchor, style: FontStyle) {
+        let anchor = match anchor {
+            Anchor::Start => "start",
+            Anchor::Middle => "middle",
+        };
+        let style = match style {
+            FontStyle::Normal => "",
+            FontStyle::Bold => r#" font-weight="bold""#,
+            FontStyle::Italic => r#" font-style="italic""#,
+        };
+        let _ = writeln!(
+            self.body,
+            r#"<text text-anchor="{anchor}" x="{}" y="{}" font-family="{FONT_FAMILY}" font-size="{}"{style}>{}</text>"#,
+            num(x),
+            num(y),
+            num(FONT_SIZE),
+            escape(text),
+        );
+    }
+
+    pub fn finish(self, title: &str, width: f64, height: f64) -> String {
+        let mut result = String::with_capacity(self.body.len() + 512);
+        result.push_str(r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#);
+        result.push('\n');
+        let _ = writeln!(
+            result,
+            r#"<svg width="{w}pt" height="{h}pt" viewBox="0.00 0.00 {w} {h}" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink">"#,
+            w = num(width),
+            h = num(height),
+        );
+        let _ = writeln!(result, "<title>{}</title>", escape(title));
+        let _ = writeln!(
+            result,
+            r#"<rect x="0" y="0" width="{}" height="{}" fill="{BACKGROUND}"/>"#,
+            num(width),
+            num(height),
+        );
+        result.push_str(&self.body);
+        result.push_str("</svg>\n");
+        result
+    }
+}
+
+fn dash(style: LineStyle) -> &'static str {
+    match style {
+        LineStyle::Solid => "",
+        LineStyle::Dashed => r#" stroke-dasharray="5,2""#,
+    }
+}
+
+fn points_attribute(points: &[Point]) -> String {
+    points
+        .iter()
+        .map(|point| format!("{},{}", num(point.x), num(point.y)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Formats the number with at most two decimal places
+fn num(value: f64) -> String {
+    let value = format!("{value:.2}");
+    let value = value.trim_end_matches('0').trim_end_matches('.');
+    if value == "-0" {
+        "0".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&#39;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
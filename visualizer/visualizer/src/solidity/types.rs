@@ -0,0 +1,194 @@
+use solang_parser::pt::{self, CodeLocation};
+
+/// Type of the variable or parameter as written in the sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeName {
+    pub kind: TypeKind,
+    /// Source representation without whitespaces (e.g., `mapping(address=>uint)`)
+    pub display: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeKind {
+    Bool,
+    Address,
+    Int(u16),
+    Uint(u16),
+    FixedBytes(u8),
+    String,
+    Bytes,
+    Mapping {
+        key: Box<TypeKind>,
+        value: Box<TypeKind>,
+    },
+    Array {
+        base: Box<TypeKind>,
+        length: Option<ArrayLength>,
+    },
+    Function {
+        external: bool,
+    },
+    /// Contract, struct, enum or user defined value type referenced by (possibly qualified) name
+    UserDefined(Vec<String>),
+    Unsupported,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArrayLength {
+    Literal(u64),
+    /// Length defined via constant variable
+    Constant(Vec<String>),
+    Unsupported,
+}
+
+impl TypeName {
+    pub fn from_expression(expression: &pt::Expression, source: &str) -> Self {
+        Self {
+            kind: TypeKind::from_expression(expression),
+            display: display(expression.loc(), source),
+        }
+    }
+
+    /// Names of all user defined types the type refers to
+    pub fn references(&self) -> Vec<&[String]> {
+        let mut references = vec![];
+        self.kind.collect_references(&mut references);
+        references
+    }
+}
+
+impl TypeKind {
+    fn from_expression(expression: &pt::Expression) -> Self {
+        match expression {
+            pt::Expression::Type(_, ty) => match ty {
+                pt::Type::Address | pt::Type::AddressPayable | pt::Type::Payable => Self::Address,
+                pt::Type::Bool => Self::Bool,
+                pt::Type::String => Self::String,
+                pt::Type::DynamicBytes => Self::Bytes,
+                pt::Type::Int(bits) => Self::Int(*bits),
+                pt::Type::Uint(bits) => Self::Uint(*bits),
+                pt::Type::Bytes(size) => Self::FixedBytes(*size),
+                pt::Type::Mapping { key, value, .. } => Self::Mapping {
+                    key: Box::new(Self::from_expression(key)),
+                    value: Box::new(Self::from_expression(value)),
+                },
+                pt::Type::Function { attributes, .. } => Self::Function {
+                    external: attributes.iter().any(|attribute| {
+                        matches!(
+                            attribute,
+                            pt::FunctionAttribute::Visibility(pt::Visibility::External(_))
+                        )
+                    }),
+                },
+                pt::Type::Rational => Self::Unsupported,
+            },
+            pt::Expression::ArraySubscript(_, base, length) => Self::Array {
+                base: Box::new(Self::from_expression(base)),
+                length: length.as_deref().map(ArrayLength::from_expression),
+            },
+            expression => identifier_path(expression)
+                .map(Self::UserDefined)
+                .unwrap_or(Self::Unsupported),
+        }
+    }
+
+    fn collect_references<'a>(&'a self, references: &mut Vec<&'a [String]>) {
+        match self {
+            Self::UserDefined(path) => references.push(path),
+            Self::Mapping { key, value } => {
+                key.collect_references(references);
+                value.collect_references(references);
+            }
+            Self::Array { base, .. } => base.collect_references(references),
+            _ => {}
+        }
+    }
+}
+
+impl ArrayLength {
+    fn from_expression(expression: &pt::Expression) -> Self {
+        if let Some(value) = integer_literal(expression) {
+            return Self::Literal(value);
+        }
+        identifier_path(expression)
+            .map(Self::Constant)
+            .unwrap_or(Self::Unsupported)
+    }
+}
+
+/// Value of the decimal (optionally with exponent) or hex number literal
+pub fn integer_literal(expression: &pt::Expression) -> Option<u64> {
+    match expression {
+        pt::Expression::NumberLiteral(_, integer, exponent, None) => {
+            let integer = integer.replace('_', "").parse::<u64>().ok()?;
+            let exponent = if exponent.is_empty() {
+                0
+            } else {
+                exponent.parse::<u32>().ok()?
+            };
+            10u64
+                .checked_pow(exponent)
+                .and_then(|multiplier| integer.checked_mul(multiplier))
+        }
+        pt::Expression::HexNumberLiteral(_, value, None) => {
+            u64::from_str_radix(&value.trim_start_matches("0x").replace('_', ""), 16).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Converts `A.B.C` like expressions into the list of identifiers
+pub fn identifier_path(expression: &pt::Expression) -> Option<Vec<String>> {
+    match expression {
+        pt::Expression::Variable(identifier) => Some(vec![identifier.name.clone()]),
+        pt::Expression::MemberAccess(_, base, identifier) => {
+            let mut path = identifier_path(base)?;
+            path.push(identifier.name.clone());
+            Some(path)
+        }
+        _ => None,
+    }
+}
+
+/// Source code of the type with all whitespaces removed,
+/// except the ones separating words (e.g., `address payable`)
+fn display(loc: pt::Loc, source: &str) -> String {
+    let text = match loc {
+        pt::Loc::File(_, start, end) => source.get(start..end).unwrap_or_default(),
+        _ => "",
+    };
+
+    let mut result = String::with_capacity(text.len());
+    let mut pending_space = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+        if pending_space && result.chars().last().is_some_and(is_word) && is_word(c) {
+            result.push(' ');
+        }
+        pending_space = false;
+        result.push(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn display_removes_whitespaces() {
+        let source = "mapping (address  =>\n mapping(address => uint) ) x; address  payable y;";
+        let loc = |start, end| pt::Loc::File(0, start, end);
+
+        assert_eq!(
+            "mapping(address=>mapping(address=>uint))",
+            display(loc(0, 48), source)
+        );
+        assert_eq!("address payable", display(loc(52, 68), source));
+    }
+}
@@ -0,0 +1,70 @@
+use super::{
+    call_graph,
+    internal::{self, Error},
+    project::Project,
+};
+use crate::{
+    limits::Limits,
+    response::{OutputMask, Response, ResponseFieldMask},
+};
+use std::{collections::BTreeMap, path::PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisualizeCallGraphRequest {
+    pub sources: BTreeMap<PathBuf, String>,
+    pub file_path: PathBuf,
+    pub contract_name: String,
+    pub output_mask: OutputMask,
+}
+
+#[derive(Debug, Error)]
+pub enum VisualizeCallGraphError {
+    #[error("internal error: {0}")]
+    Internal(#[from] anyhow::Error),
+    #[error("file path should contain file name")]
+    InvalidFileName,
+    #[error("execution error: {0}")]
+    Execution(String),
+    #[error("{0}")]
+    ResourceLimit(String),
+}
+
+impl From<internal::Error> for VisualizeCallGraphError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Internal(err) => VisualizeCallGraphError::Internal(err),
+            err @ Error::ResourceLimit(_) => {
+                VisualizeCallGraphError::ResourceLimit(err.to_string())
+            }
+            err => VisualizeCallGraphError::Execution(err.to_string()),
+        }
+    }
+}
+
+#[tracing::instrument(level = "debug", name = "visualize_call_graph_internal", skip(limits))]
+pub async fn visualize_call_graph(
+    request: VisualizeCallGraphRequest,
+    limits: &Limits,
+) -> Result<Response, VisualizeCallGraphError> {
+    if request.file_path.file_name().is_none() {
+        return Err(VisualizeCallGraphError::InvalidFileName);
+    }
+    internal::validate_sources(&request.sources, limits)?;
+
+    let svg = if request.output_mask.contains(&ResponseFieldMask::Svg) {
+        let output = internal::run_blocking(limits, move |deadline| {
+            let project = Project::parse(&request.sources, deadline)?;
+            let contract =
+                internal::find_contract(&project, &request.file_path, &request.contract_name)?;
+            call_graph::render(&project, contract)
+        })
+        .await?;
+        Some(output.into_bytes())
+    } else {
+        None
+    };
+    let png = None;
+
+    Ok(Response { svg, png })
+}
@@ -1,59 +1,71 @@
-use super::internal::{self, Error, Sol2Uml};
-use crate::response::{OutputMask, Response, ResponseFieldMask};
+use super::{
+    class_diagram,
+    internal::{self, Error},
+    project::Project,
+};
+use crate::{
+    limits::Limits,
+    response::{OutputMask, Response, ResponseFieldMask},
+};
 use std::{collections::BTreeMap, path::PathBuf};
-use tempfile::TempDir;
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct VisualizeContractsRequest {
     pub sources: BTreeMap<PathBuf, String>,
+    pub options: ContractsDiagramOptions,
     pub output_mask: OutputMask,
 }
 
+/// Filters to produce smaller diagrams of large projects. Everything is shown by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContractsDiagramOptions {
+    /// Show only inheritance and interface realization relations (structs and enums are omitted)
+    pub inheritance_only: bool,
+    pub hide_libraries: bool,
+    pub hide_interfaces: bool,
+    /// Show only these contracts together with their base contracts
+    /// and the structs and enums defined in them. If empty, all contracts are shown.
+    pub contract_names: Vec<String>,
+}
+
 #[derive(Debug, Error)]
 pub enum VisualizeContractsError {
     #[error("internal error: {0}")]
     Internal(#[from] anyhow::Error),
     #[error("execution error: {0}")]
     Execution(String),
+    #[error("{0}")]
+    ResourceLimit(String),
 }
 
 impl From<internal::Error> for VisualizeContractsError {
     fn from(error: Error) -> Self {
         match error {
             Error::Internal(err) => VisualizeContractsError::Internal(err),
-            Error::Sol2Uml(err) => VisualizeContractsError::Execution(err),
-            Error::SaveFiles(err) => VisualizeContractsError::Execution(err.to_string()),
+            err @ Error::ResourceLimit(_) => {
+                VisualizeContractsError::ResourceLimit(err.to_string())
+            }
+            err => VisualizeContractsError::Execution(err.to_string()),
         }
     }
 }
 
-#[tracing::instrument(level = "debug", name = "visualize_contracts_internal")]
+#[tracing::instrument(level = "debug", name = "visualize_contracts_internal", skip(limits))]
 pub async fn visualize_contracts(
     request: VisualizeContractsRequest,
+    limits: &Limits,
 ) -> Result<Response, VisualizeContractsError> {
-    let base_dir = TempDir::new().map_err(anyhow::Error::msg)?;
-    let base_dir_path = base_dir.path();
-    internal::save_files(base_dir_path, request.sources).await?;
+    internal::validate_sources(&request.sources, limits)?;
 
     let svg = if request.output_mask.contains(&ResponseFieldMask::Svg) {
-        let output_file = "result.svg";
-        Sol2Uml::new()
-            .current_dir(&base_dir)
-            .arg("class")
-            .arg(".")
-            .arg("--hideFilename")
-            .args(["-f", "svg"])
-            .arg("-o")
-            .arg(output_file)
-            .call()
-            .await?;
-
-        let output_file_path = base_dir_path.join(output_file);
-        let output = tokio::fs::read(output_file_path)
-            .await
-            .map_err(anyhow::Error::msg)?;
-        Some(output)
+        let task_limits = limits.clone();
+        let output = internal::run_blocking(limits, move |deadline| {
+            let project = Project::parse(&request.sources, deadline)?;
+            class_diagram::render(&project, &request.options, &task_limits)
+        })
+        .await?;
+        Some(output.into_bytes())
     } else {
         None
     };
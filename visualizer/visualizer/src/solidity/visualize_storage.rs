@@ -1,7 +1,13 @@
-use super::internal::{self, Error, Sol2Uml};
-use crate::response::{OutputMask, Response, ResponseFieldMask};
+use super::{
+    internal::{self, Error},
+    project::Project,
+    storage, storage_diagram,
+};
+use crate::{
+    limits::Limits,
+    response::{OutputMask, Response, ResponseFieldMask},
+};
 use std::{collections::BTreeMap, path::PathBuf};
-use tempfile::TempDir;
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -20,52 +26,40 @@ pub enum VisualizeStorageError {
     InvalidFileName,
     #[error("execution error: {0}")]
     Execution(String),
+    #[error("{0}")]
+    ResourceLimit(String),
 }
 
 impl From<internal::Error> for VisualizeStorageError {
     fn from(error: Error) -> Self {
         match error {
             Error::Internal(err) => VisualizeStorageError::Internal(err),
-            Error::Sol2Uml(err) => VisualizeStorageError::Execution(err),
-            Error::SaveFiles(err) => VisualizeStorageError::Execution(err.to_string()),
+            err @ Error::ResourceLimit(_) => VisualizeStorageError::ResourceLimit(err.to_string()),
+            err => VisualizeStorageError::Execution(err.to_string()),
         }
     }
 }
 
-#[tracing::instrument(level = "debug", name = "visualize_storage_internal")]
+#[tracing::instrument(level = "debug", name = "visualize_storage_internal", skip(limits))]
 pub async fn visualize_storage(
     request: VisualizeStorageRequest,
+    limits: &Limits,
 ) -> Result<Response, VisualizeStorageError> {
-    let base_dir = TempDir::new().map_err(anyhow::Error::msg)?;
-    let base_dir_path = base_dir.path();
-
-    let file_name = request
-        .file_path
-        .file_name()
-        .ok_or(VisualizeStorageError::InvalidFileName)?;
-    internal::save_files(base_dir_path, request.sources).await?;
+    if request.file_path.file_name().is_none() {
+        return Err(VisualizeStorageError::InvalidFileName);
+    }
+    internal::validate_sources(&request.sources, limits)?;
 
     let svg = if request.output_mask.contains(&ResponseFieldMask::Svg) {
-        let output_file = "result.svg";
-        Sol2Uml::new()
-            .current_dir(&base_dir)
-            .arg("storage")
-            .arg(".")
-            .arg("-c")
-            .arg(&request.contract_name)
-            .arg("-cf")
-            .arg(file_name)
-            .args(["-f", "svg"])
-            .arg("-o")
-            .arg(output_file)
-            .call()
-            .await?;
-
-        let output_file_path = base_dir_path.join(output_file);
-        let output = tokio::fs::read(output_file_path)
-            .await
-            .map_err(anyhow::Error::msg)?;
-        Some(output)
+        let output = internal::run_blocking(limits, move |deadline| {
+            let project = Project::parse(&request.sources, deadline)?;
+            let contract =
+                internal::find_contract(&project, &request.file_path, &request.contract_name)?;
+            let layout = storage::storage_layout(&project, contract)?;
+            Ok(storage_diagram::render(&layout))
+        })
+        .await?;
+        Some(output.into_bytes())
     } else {
         None
     };